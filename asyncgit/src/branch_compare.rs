@@ -0,0 +1,105 @@
+//!
+
+use crate::{
+	asyncjob::{AsyncJob, RunParams},
+	error::Result,
+	sync::{branch_compare, default_branch, BranchCompare, RepoPath},
+	AsyncGitNotification,
+};
+use std::sync::{Arc, Mutex};
+
+///
+pub struct BranchCompareResult {
+	/// base the branch was compared to
+	pub base: String,
+	///
+	pub compare: BranchCompare,
+}
+
+enum JobState {
+	Request {
+		repo: RepoPath,
+		branch: String,
+		base: Option<String>,
+	},
+	Response(Result<Option<BranchCompareResult>>),
+}
+
+/// compares a local branch to a comparison base (e.g. `origin/main`)
+/// in the background, falls back to `sync::default_branch` if no base
+/// is given explicitly.
+#[derive(Clone, Default)]
+pub struct AsyncBranchCompareJob {
+	state: Arc<Mutex<Option<JobState>>>,
+}
+
+///
+impl AsyncBranchCompareJob {
+	///
+	pub fn new(
+		repo: RepoPath,
+		branch: String,
+		base: Option<String>,
+	) -> Self {
+		Self {
+			state: Arc::new(Mutex::new(Some(JobState::Request {
+				repo,
+				branch,
+				base,
+			}))),
+		}
+	}
+
+	/// `Ok(None)` if no comparison base could be determined
+	pub fn result(
+		&self,
+	) -> Option<Result<Option<BranchCompareResult>>> {
+		if let Ok(mut state) = self.state.lock() {
+			if let Some(state) = state.take() {
+				return match state {
+					JobState::Request { .. } => None,
+					JobState::Response(result) => Some(result),
+				};
+			}
+		}
+
+		None
+	}
+}
+
+impl AsyncJob for AsyncBranchCompareJob {
+	type Notification = AsyncGitNotification;
+	type Progress = ();
+
+	fn run(
+		&mut self,
+		_params: RunParams<Self::Notification, Self::Progress>,
+	) -> Result<Self::Notification> {
+		if let Ok(mut state) = self.state.lock() {
+			*state = state.take().map(|state| match state {
+				JobState::Request { repo, branch, base } => {
+					JobState::Response(compare(&repo, &branch, base))
+				}
+				JobState::Response(result) => {
+					JobState::Response(result)
+				}
+			});
+		}
+
+		Ok(AsyncGitNotification::BranchCompare)
+	}
+}
+
+fn compare(
+	repo_path: &RepoPath,
+	branch: &str,
+	base: Option<String>,
+) -> Result<Option<BranchCompareResult>> {
+	let base = base.or_else(|| default_branch(repo_path));
+
+	base.map(|base| {
+		let compare = branch_compare(repo_path, branch, &base)?;
+		Ok(BranchCompareResult { base, compare })
+	})
+	.transpose()
+}
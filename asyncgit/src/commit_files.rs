@@ -1,19 +1,36 @@
 use crate::{
 	error::Result,
+	lru::Lru,
 	sync::{self, commit_files::OldNew, CommitId, RepoPath},
 	AsyncGitNotification, StatusItem,
 };
 use crossbeam_channel::Sender;
-use std::sync::{
-	atomic::{AtomicUsize, Ordering},
-	Arc, Mutex,
+use std::{
+	sync::{
+		atomic::{AtomicUsize, Ordering},
+		Arc, Mutex,
+	},
+	thread,
 };
 
 type ResultType = Vec<StatusItem>;
+type Cache = Arc<Mutex<Lru<CommitFilesParams, ResultType>>>;
 struct Request<R, A>(R, A);
 
+/// amount of file lists kept (fetched and prefetched)
+const CACHE_CAPACITY: usize = 64;
+
+/// how often a file list was already cached when it was asked for
+#[derive(Debug, Default, Copy, Clone, PartialEq, Eq)]
+pub struct CommitFilesCacheStats {
+	///
+	pub hits: usize,
+	/// file lists that had to be fetched
+	pub misses: usize,
+}
+
 ///
-#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
 pub struct CommitFilesParams {
 	///
 	pub id: CommitId,
@@ -51,6 +68,12 @@ pub struct AsyncCommitFiles {
 		Arc<Mutex<Option<Request<CommitFilesParams, ResultType>>>>,
 	sender: Sender<AsyncGitNotification>,
 	pending: Arc<AtomicUsize>,
+	cache: Cache,
+	hits: AtomicUsize,
+	misses: AtomicUsize,
+	/// bumped to cancel a running prefetch
+	prefetch_generation: Arc<AtomicUsize>,
+	prefetching: Arc<AtomicUsize>,
 	repo: RepoPath,
 }
 
@@ -65,6 +88,11 @@ impl AsyncCommitFiles {
 			current: Arc::new(Mutex::new(None)),
 			sender: sender.clone(),
 			pending: Arc::new(AtomicUsize::new(0)),
+			cache: Arc::new(Mutex::new(Lru::new(CACHE_CAPACITY))),
+			hits: AtomicUsize::new(0),
+			misses: AtomicUsize::new(0),
+			prefetch_generation: Arc::new(AtomicUsize::new(0)),
+			prefetching: Arc::new(AtomicUsize::new(0)),
 		}
 	}
 
@@ -78,11 +106,118 @@ impl AsyncCommitFiles {
 			.map_or(Ok(None), |c| Ok(Some((c.0, c.1.clone()))))
 	}
 
+	/// file list of `params` if it was fetched or prefetched before,
+	/// it becomes the [`Self::current`] one
+	pub fn cached(
+		&self,
+		params: CommitFilesParams,
+	) -> Result<Option<ResultType>> {
+		let Some(res) = self.cache.lock()?.get_mut(&params).cloned()
+		else {
+			return Ok(None);
+		};
+
+		self.hits.fetch_add(1, Ordering::Relaxed);
+		*self.current.lock()? = Some(Request(params, res.clone()));
+
+		Ok(Some(res))
+	}
+
+	///
+	pub fn cache_stats(&self) -> CommitFilesCacheStats {
+		CommitFilesCacheStats {
+			hits: self.hits.load(Ordering::Relaxed),
+			misses: self.misses.load(Ordering::Relaxed),
+		}
+	}
+
 	///
 	pub fn is_pending(&self) -> bool {
 		self.pending.load(Ordering::Relaxed) > 0
 	}
 
+	/// whether a [`Self::prefetch`] is still running
+	pub fn is_prefetching(&self) -> bool {
+		self.prefetching.load(Ordering::Relaxed) > 0
+	}
+
+	/// fetches the file lists of `ids` (in order) into the cache on a
+	/// background thread, one at a time.
+	///
+	/// a previous prefetch still running is cancelled, as is this one as
+	/// soon as a file list that was not prefetched gets fetched.
+	pub fn prefetch(&self, ids: &[CommitId]) -> Result<()> {
+		let generation =
+			self.prefetch_generation.fetch_add(1, Ordering::Relaxed)
+				+ 1;
+
+		let ids: Vec<CommitId> = {
+			let cache = self.cache.lock()?;
+			ids.iter()
+				.copied()
+				.filter(|id| !cache.contains_key(&(*id).into()))
+				.collect()
+		};
+
+		if ids.is_empty() {
+			return Ok(());
+		}
+
+		let cache = Arc::clone(&self.cache);
+		let current_generation =
+			Arc::clone(&self.prefetch_generation);
+		let prefetching = Arc::clone(&self.prefetching);
+		let repo = self.repo.clone();
+
+		self.prefetching.fetch_add(1, Ordering::Relaxed);
+
+		let spawned = thread::Builder::new()
+			.name(String::from("commit-files-prefetch"))
+			.spawn(move || {
+				for id in ids {
+					if current_generation.load(Ordering::Relaxed)
+						!= generation
+					{
+						log::trace!("prefetch cancelled");
+						break;
+					}
+
+					if let Err(e) =
+						Self::prefetch_helper(&repo, id, &cache)
+					{
+						log::error!("prefetch of {id} failed: {e}");
+					}
+				}
+
+				prefetching.fetch_sub(1, Ordering::Relaxed);
+			});
+
+		if let Err(e) = spawned {
+			self.prefetching.fetch_sub(1, Ordering::Relaxed);
+			return Err(e.into());
+		}
+
+		Ok(())
+	}
+
+	fn prefetch_helper(
+		repo_path: &RepoPath,
+		id: CommitId,
+		cache: &Cache,
+	) -> Result<()> {
+		let params = CommitFilesParams::from(id);
+
+		if cache.lock()?.contains_key(&params) {
+			return Ok(());
+		}
+
+		let res = sync::get_commit_files(repo_path, id, None)?;
+
+		cache.lock()?.insert(params, res);
+
+		Ok(())
+	}
+
 	///
 	pub fn fetch(&self, params: CommitFilesParams) -> Result<()> {
 		if self.is_pending() {
@@ -100,7 +235,12 @@ impl AsyncCommitFiles {
 			}
 		}
 
+		// the selection moved away from what is being prefetched
+		self.prefetch_generation.fetch_add(1, Ordering::Relaxed);
+		self.misses.fetch_add(1, Ordering::Relaxed);
+
 		let arc_current = Arc::clone(&self.current);
+		let cache = Arc::clone(&self.cache);
 		let sender = self.sender.clone();
 		let arc_pending = Arc::clone(&self.pending);
 		let repo = self.repo.clone();
@@ -108,7 +248,7 @@ impl AsyncCommitFiles {
 		self.pending.fetch_add(1, Ordering::Relaxed);
 
 		rayon_core::spawn(move || {
-			Self::fetch_helper(&repo, params, &arc_current)
+			Self::fetch_helper(&repo, params, &arc_current, &cache)
 				.expect("failed to fetch");
 
 			arc_pending.fetch_sub(1, Ordering::Relaxed);
@@ -127,6 +267,7 @@ impl AsyncCommitFiles {
 		arc_current: &Arc<
 			Mutex<Option<Request<CommitFilesParams, ResultType>>>,
 		>,
+		cache: &Cache,
 	) -> Result<()> {
 		let res = sync::get_commit_files(
 			repo_path,
@@ -136,6 +277,8 @@ impl AsyncCommitFiles {
 
 		log::trace!("get_commit_files: {:?} ({})", params, res.len());
 
+		cache.lock()?.insert(params, res.clone());
+
 		{
 			let mut current = arc_current.lock()?;
 			*current = Some(Request(params, res));
@@ -144,3 +287,77 @@ impl AsyncCommitFiles {
 		Ok(())
 	}
 }
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::sync::tests::{repo_init, write_commit_file};
+	use std::time::{Duration, Instant};
+
+	fn wait_until(done: impl Fn() -> bool) {
+		let start = Instant::now();
+		while !done() {
+			assert!(start.elapsed() < Duration::from_secs(10));
+			thread::sleep(Duration::from_millis(5));
+		}
+	}
+
+	#[test]
+	fn test_prefetch_while_scrolling() {
+		const COMMITS: usize = 30;
+		const DISTANCE: usize = 3;
+
+		let (_td, repo) = repo_init().unwrap();
+		let repo_path: RepoPath =
+			repo.workdir().unwrap().to_str().unwrap().into();
+
+		// newest first, like the log
+		let log: Vec<CommitId> = (0..COMMITS)
+			.map(|i| {
+				write_commit_file(
+					&repo,
+					&format!("file{i}.txt"),
+					"content",
+					&format!("c{i}"),
+				)
+			})
+			.rev()
+			.collect();
+
+		let (sender, _receiver) = crossbeam_channel::unbounded();
+		let files = AsyncCommitFiles::new(repo_path, &sender);
+
+		// scroll down the log one commit at a time, pausing long enough
+		// on every commit for the prefetch to finish
+		for (selection, id) in log.iter().enumerate() {
+			let res = if let Some(res) =
+				files.cached((*id).into()).unwrap()
+			{
+				res
+			} else {
+				files.fetch((*id).into()).unwrap();
+				wait_until(|| !files.is_pending());
+				files.current().unwrap().unwrap().1
+			};
+
+			let file = format!("file{}.txt", COMMITS - 1 - selection);
+			assert_eq!(res.len(), 1);
+			assert_eq!(res[0].path, file);
+
+			let around: Vec<CommitId> = (1..=DISTANCE)
+				.flat_map(|d| {
+					[selection + d, selection.wrapping_sub(d)]
+				})
+				.filter_map(|idx| log.get(idx).copied())
+				.collect();
+			files.prefetch(&around).unwrap();
+			wait_until(|| !files.is_prefetching());
+		}
+
+		let stats = files.cache_stats();
+
+		// only the very first selection had to wait for its files
+		assert_eq!(stats.misses, 1);
+		assert_eq!(stats.hits, COMMITS - 1);
+	}
+}
@@ -0,0 +1,102 @@
+//!
+
+use crate::{
+	asyncjob::{AsyncJob, RunParams},
+	error::Result,
+	hash,
+	sync::{hooks_commit_msg_preview, HookResult, RepoPath},
+	AsyncGitNotification,
+};
+use std::{
+	sync::{Arc, Mutex},
+	time::Duration,
+};
+
+///
+pub struct CommitMsgPreviewResult {
+	/// hash of the message the hook was run against
+	pub msg_hash: u64,
+	///
+	pub result: Result<HookResult>,
+}
+
+enum JobState {
+	Request {
+		repo: RepoPath,
+		msg: String,
+		timeout: Option<Duration>,
+	},
+	Response(CommitMsgPreviewResult),
+}
+
+/// runs the `commit-msg` hook against a commit message that is
+/// still being edited (see `sync::hooks_commit_msg_preview`).
+///
+/// debouncing the edits is up to the caller, spawning this through an
+/// `AsyncSingleJob` at most runs the hook for the latest message next
+#[derive(Clone, Default)]
+pub struct AsyncCommitMsgPreviewJob {
+	state: Arc<Mutex<Option<JobState>>>,
+}
+
+///
+impl AsyncCommitMsgPreviewJob {
+	///
+	pub fn new(
+		repo: RepoPath,
+		msg: String,
+		timeout: Option<Duration>,
+	) -> Self {
+		Self {
+			state: Arc::new(Mutex::new(Some(JobState::Request {
+				repo,
+				msg,
+				timeout,
+			}))),
+		}
+	}
+
+	///
+	pub fn result(&self) -> Option<CommitMsgPreviewResult> {
+		if let Ok(mut state) = self.state.lock() {
+			if let Some(state) = state.take() {
+				return match state {
+					JobState::Request { .. } => None,
+					JobState::Response(result) => Some(result),
+				};
+			}
+		}
+
+		None
+	}
+}
+
+impl AsyncJob for AsyncCommitMsgPreviewJob {
+	type Notification = AsyncGitNotification;
+	type Progress = ();
+
+	fn run(
+		&mut self,
+		_params: RunParams<Self::Notification, Self::Progress>,
+	) -> Result<Self::Notification> {
+		if let Ok(mut state) = self.state.lock() {
+			*state = state.take().map(|state| match state {
+				JobState::Request { repo, msg, timeout } => {
+					let result = hooks_commit_msg_preview(
+						&repo, &msg, timeout,
+					);
+
+					JobState::Response(CommitMsgPreviewResult {
+						msg_hash: hash(&msg),
+						result,
+					})
+				}
+				JobState::Response(result) => {
+					JobState::Response(result)
+				}
+			});
+		}
+
+		Ok(AsyncGitNotification::CommitMsgPreview)
+	}
+}
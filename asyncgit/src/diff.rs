@@ -19,9 +19,9 @@ use std::{
 ///
 #[derive(Debug, Hash, Clone, PartialEq, Eq)]
 pub enum DiffType {
-	/// diff two commits
+	/// diff two commits, see `sync::diff::diff_request_commits`
 	Commits(OldNew<CommitId>),
-	/// diff in a given commit
+	/// diff in a given commit, see `sync::diff::diff_request_commit`
 	Commit(CommitId),
 	/// diff against staged file
 	Stage,
@@ -174,17 +174,23 @@ impl AsyncDiff {
 				false,
 				Some(params.options),
 			)?,
-			DiffType::Commit(id) => sync::diff::get_diff_commit(
+			DiffType::Commit(id) => sync::diff::get_diff_blobs(
 				repo_path,
-				id,
-				params.path.clone(),
-				Some(params.options),
+				&sync::diff::diff_request_commit(
+					repo_path,
+					id,
+					&params.path,
+					params.options,
+				)?,
 			)?,
-			DiffType::Commits(ids) => sync::diff::get_diff_commits(
+			DiffType::Commits(ids) => sync::diff::get_diff_blobs(
 				repo_path,
-				ids,
-				params.path.clone(),
-				Some(params.options),
+				&sync::diff::diff_request_commits(
+					repo_path,
+					ids,
+					&params.path,
+					params.options,
+				)?,
 			)?,
 		};
 
@@ -35,6 +35,10 @@ pub enum Error {
 	#[error("git: uncommitted changes")]
 	UncommittedChanges,
 
+	///
+	#[error("git: {0} in progress, finish or abort it first")]
+	PendingOperation(String),
+
 	///
 	#[error("git: can\u{2019}t run blame on a binary file")]
 	NoBlameOnBinaryFile,
@@ -43,6 +47,10 @@ pub enum Error {
 	#[error("binary file")]
 	BinaryFile,
 
+	///
+	#[error("file too large ({0} bytes)")]
+	FileTooLarge(usize),
+
 	///
 	#[error("io error:{0}")]
 	Io(#[from] std::io::Error),
@@ -79,6 +87,10 @@ pub enum Error {
 	#[error("not on a branch")]
 	NoBranch,
 
+	/// the ref points at an object missing locally
+	#[error("git: '{0}' points at a missing object, repair it in the remote branch list")]
+	BrokenRef(String),
+
 	///
 	#[error("rayon error: {0}")]
 	ThreadPool(#[from] rayon_core::ThreadPoolBuildError),
@@ -87,6 +99,10 @@ pub enum Error {
 	#[error("git hook error: {0}")]
 	Hooks(#[from] git2_hooks::HooksError),
 
+	///
+	#[error("{0}")]
+	HookRejected(#[from] crate::sync::HookRejection),
+
 	///
 	#[error("sign builder error: {0}")]
 	SignBuilder(#[from] crate::sync::sign::SignBuilderError),
@@ -124,6 +140,10 @@ pub enum Error {
 	///
 	#[error("reword error: config commit.gpgsign=true detected.\ngpg signing is not supported for rewording commits with staged changes\ntry unstaging or stashing your changes")]
 	SignRewordLastCommitStaged,
+
+	///
+	#[error("squash error: config commit.gpgsign=true detected.\ngpg signing is not supported for squashing commits")]
+	SignSquash,
 }
 
 ///
@@ -3,16 +3,33 @@
 use crate::{
 	asyncjob::{AsyncJob, RunParams},
 	error::Result,
-	sync::remotes::fetch_all,
+	sync::remotes::{
+		fetch_all, fetch_tags, fetch_target, FetchTags, FetchTarget,
+	},
 	sync::{cred::BasicAuthCredential, RepoPath},
 	AsyncGitNotification, ProgressPercent,
 };
 
-use std::sync::{Arc, Mutex};
+use std::{
+	collections::BTreeMap,
+	sync::{Arc, Mutex},
+};
 
 enum JobState {
 	Request(Option<BasicAuthCredential>),
-	Response(Result<()>),
+	/// the amount of tags created
+	Response(Result<usize>),
+}
+
+/// what [`AsyncFetchJob`] fetches
+#[derive(Clone)]
+enum FetchWhat {
+	/// all remotes with the tags of each remote overridden by name
+	All(BTreeMap<String, FetchTags>),
+	/// see [`AsyncFetchJob::with_target`]
+	Target(FetchTarget),
+	/// see [`AsyncFetchJob::only_tags`]
+	Tags,
 }
 
 ///
@@ -20,6 +37,7 @@ enum JobState {
 pub struct AsyncFetchJob {
 	state: Arc<Mutex<Option<JobState>>>,
 	repo: RepoPath,
+	what: FetchWhat,
 }
 
 ///
@@ -34,7 +52,48 @@ impl AsyncFetchJob {
 			state: Arc::new(Mutex::new(Some(JobState::Request(
 				basic_credential,
 			)))),
+			what: FetchWhat::All(BTreeMap::new()),
+		}
+	}
+
+	/// fetches only `target` instead of all remotes
+	#[must_use]
+	pub fn with_target(mut self, target: FetchTarget) -> Self {
+		self.what = FetchWhat::Target(target);
+		self
+	}
+
+	/// overrides `remote.<name>.tagOpt` of the remotes in `tags`
+	/// when fetching all of them
+	#[must_use]
+	pub fn with_tags(
+		mut self,
+		tags: BTreeMap<String, FetchTags>,
+	) -> Self {
+		self.what = FetchWhat::All(tags);
+		self
+	}
+
+	/// fetches every tag of all remotes but no branches, see
+	/// `sync::fetch_tags`
+	#[must_use]
+	pub fn only_tags(mut self) -> Self {
+		self.what = FetchWhat::Tags;
+		self
+	}
+
+	/// the amount of tags created, `None` until the job finished
+	pub fn result(&self) -> Option<Result<usize>> {
+		if let Ok(mut state) = self.state.lock() {
+			if let Some(state) = state.take() {
+				return match state {
+					JobState::Request(_) => None,
+					JobState::Response(result) => Some(result),
+				};
+			}
 		}
+
+		None
 	}
 }
 
@@ -50,11 +109,26 @@ impl AsyncJob for AsyncFetchJob {
 			*state = state.take().map(|state| match state {
 				JobState::Request(basic_credentials) => {
 					//TODO: support progress
-					let result = fetch_all(
-						&self.repo,
-						&basic_credentials,
-						&None,
-					);
+					let result = match &self.what {
+						FetchWhat::All(tags) => fetch_all(
+							&self.repo,
+							tags,
+							&basic_credentials,
+							&None,
+						),
+						FetchWhat::Target(target) => fetch_target(
+							&self.repo,
+							target,
+							basic_credentials,
+							None,
+						)
+						.map(|()| 0),
+						FetchWhat::Tags => fetch_tags(
+							&self.repo,
+							&basic_credentials,
+							&None,
+						),
+					};
 
 					JobState::Response(result)
 				}
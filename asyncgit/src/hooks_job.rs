@@ -0,0 +1,415 @@
+//!
+
+use crate::{
+	asyncjob::{AsyncJob, RunParams},
+	error::Result,
+	sync::{
+		hooks_commit_msg_cancellable, hooks_pre_commit_cancellable,
+		run_hook_cancellable, CancellationToken, HookBehavior,
+		HookResult, HookType, RepoPath,
+	},
+	AsyncGitNotification,
+};
+use std::{
+	sync::{Arc, Mutex},
+	time::Duration,
+};
+
+///
+pub struct HooksJobResult {
+	/// the hook `result` is of. for [`AsyncHooksJob::commit`] the one
+	/// that stopped the commit, `commit-msg` if none did
+	pub hook: HookType,
+	///
+	pub result: Result<HookResult>,
+	/// the message as left by `commit-msg`,
+	/// only set for [`AsyncHooksJob::commit`]
+	pub msg: Option<String>,
+}
+
+enum JobState {
+	Request {
+		hook: HookType,
+		args: Vec<String>,
+	},
+	Commit {
+		msg: String,
+		behavior: HookBehavior,
+		timeout: Option<Duration>,
+	},
+	Response(HooksJobResult),
+}
+
+/// runs a hook (see `sync::run_hook_cancellable`) without blocking the
+/// caller, it can be cancelled at any time through [`Self::cancel`]
+#[derive(Clone)]
+pub struct AsyncHooksJob {
+	state: Arc<Mutex<Option<JobState>>>,
+	repo: RepoPath,
+	cancel: CancellationToken,
+}
+
+///
+impl AsyncHooksJob {
+	///
+	pub fn new(
+		repo: RepoPath,
+		hook: HookType,
+		args: Vec<String>,
+	) -> Self {
+		Self {
+			state: Arc::new(Mutex::new(Some(JobState::Request {
+				hook,
+				args,
+			}))),
+			repo,
+			cancel: CancellationToken::new(),
+		}
+	}
+
+	/// runs the hooks checking a commit: `pre-commit` and, if it
+	/// passed, `commit-msg` on `msg`
+	pub fn commit(
+		repo: RepoPath,
+		msg: String,
+		behavior: HookBehavior,
+		timeout: Option<Duration>,
+	) -> Self {
+		Self {
+			state: Arc::new(Mutex::new(Some(JobState::Commit {
+				msg,
+				behavior,
+				timeout,
+			}))),
+			repo,
+			cancel: CancellationToken::new(),
+		}
+	}
+
+	/// kills the hook if it is running already, otherwise it is not
+	/// run at all. either way the result is `HookResult::Cancelled`
+	/// (unless the hook finished before).
+	///
+	/// can be called from any thread, clones of the job share this
+	pub fn cancel(&self) {
+		self.cancel.cancel();
+	}
+
+	///
+	pub fn is_cancelled(&self) -> bool {
+		self.cancel.is_cancelled()
+	}
+
+	/// `None` until the job finished
+	pub fn result(&self) -> Option<HooksJobResult> {
+		if let Ok(mut state) = self.state.lock() {
+			if let Some(state) = state.take() {
+				return match state {
+					JobState::Request { .. }
+					| JobState::Commit { .. } => None,
+					JobState::Response(result) => Some(result),
+				};
+			}
+		}
+
+		None
+	}
+}
+
+impl AsyncJob for AsyncHooksJob {
+	type Notification = AsyncGitNotification;
+	type Progress = ();
+
+	fn run(
+		&mut self,
+		_params: RunParams<Self::Notification, Self::Progress>,
+	) -> Result<Self::Notification> {
+		if let Ok(mut state) = self.state.lock() {
+			*state = state.take().map(|state| match state {
+				JobState::Request { hook, args } => {
+					let args = args
+						.iter()
+						.map(String::as_str)
+						.collect::<Vec<_>>();
+
+					JobState::Response(HooksJobResult {
+						hook,
+						result: run_hook_cancellable(
+							&self.repo,
+							hook,
+							&args,
+							&[],
+							&self.cancel,
+						),
+						msg: None,
+					})
+				}
+				JobState::Commit {
+					mut msg,
+					behavior,
+					timeout,
+				} => {
+					let (hook, result) =
+						match hooks_pre_commit_cancellable(
+							&self.repo,
+							behavior,
+							timeout,
+							&self.cancel,
+						) {
+							Ok(HookResult::Ok) => (
+								HookType::CommitMsg,
+								hooks_commit_msg_cancellable(
+									&self.repo,
+									&mut msg,
+									behavior,
+									timeout,
+									&self.cancel,
+								),
+							),
+							result => (HookType::PreCommit, result),
+						};
+
+					JobState::Response(HooksJobResult {
+						hook,
+						result,
+						msg: Some(msg),
+					})
+				}
+				JobState::Response(result) => {
+					JobState::Response(result)
+				}
+			});
+		}
+
+		Ok(AsyncGitNotification::Hooks)
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::{asyncjob::AsyncSingleJob, sync::tests::repo_init};
+	use crossbeam_channel::unbounded;
+	use std::{
+		path::Path,
+		thread,
+		time::{Duration, Instant},
+	};
+
+	/// writes `started` into the work tree and sleeps for a while
+	const SLOW_HOOK: &[u8] = b"#!/bin/sh
+touch started
+sleep 5
+";
+
+	fn run_job(
+		job: AsyncHooksJob,
+		running: impl FnOnce(&AsyncHooksJob),
+	) -> HooksJobResult {
+		let (sender, receiver) = unbounded();
+		let single: AsyncSingleJob<AsyncHooksJob> =
+			AsyncSingleJob::new(sender);
+
+		assert!(single.spawn(job.clone()));
+		running(&job);
+
+		assert_eq!(
+			receiver.recv_timeout(Duration::from_secs(10)).unwrap(),
+			AsyncGitNotification::Hooks
+		);
+		while single.is_pending() {
+			thread::sleep(Duration::from_millis(10));
+		}
+
+		single.take_last().unwrap().result().unwrap()
+	}
+
+	fn job(repo: &git2::Repository, args: &[&str]) -> AsyncHooksJob {
+		AsyncHooksJob::new(
+			repo.workdir().unwrap().to_str().unwrap().into(),
+			HookType::PreRebase,
+			args.iter().map(ToString::to_string).collect(),
+		)
+	}
+
+	fn started(repo: &git2::Repository) -> bool {
+		repo.workdir().unwrap().join(Path::new("started")).exists()
+	}
+
+	#[test]
+	fn test_completes() {
+		let (_td, repo) = repo_init().unwrap();
+
+		git2_hooks::create_hook(
+			&repo,
+			git2_hooks::HOOK_PRE_REBASE,
+			b"#!/bin/sh\necho \"rejected $1\"\nexit 1\n",
+		);
+
+		let res =
+			run_job(job(&repo, &["main"]), |_| {}).result.unwrap();
+
+		assert_eq!(res.code(), Some(1));
+		assert_eq!(res.msg(), Some(String::from("rejected main\n")));
+	}
+
+	#[test]
+	fn test_cancel_before_start() {
+		let (_td, repo) = repo_init().unwrap();
+
+		git2_hooks::create_hook(
+			&repo,
+			git2_hooks::HOOK_PRE_REBASE,
+			SLOW_HOOK,
+		);
+
+		let job = job(&repo, &[]);
+		job.cancel();
+
+		let start = Instant::now();
+		let res = run_job(job, |_| {}).result.unwrap();
+
+		assert!(matches!(res, HookResult::Cancelled { .. }));
+		assert!(start.elapsed() < Duration::from_secs(4));
+		assert!(!started(&repo));
+	}
+
+	#[test]
+	fn test_cancel_while_running() {
+		let (_td, repo) = repo_init().unwrap();
+
+		git2_hooks::create_hook(
+			&repo,
+			git2_hooks::HOOK_PRE_REBASE,
+			SLOW_HOOK,
+		);
+
+		let start = Instant::now();
+		let res = run_job(job(&repo, &[]), |job| {
+			while !started(&repo) {
+				assert!(start.elapsed() < Duration::from_secs(4));
+				thread::sleep(Duration::from_millis(10));
+			}
+			assert!(!job.is_cancelled());
+
+			job.cancel();
+		})
+		.result
+		.unwrap();
+
+		assert!(matches!(res, HookResult::Cancelled { .. }));
+		// killed instead of waiting for the `sleep`
+		assert!(start.elapsed() < Duration::from_secs(4));
+	}
+
+	fn commit_job(
+		repo: &git2::Repository,
+		msg: &str,
+		behavior: HookBehavior,
+	) -> AsyncHooksJob {
+		AsyncHooksJob::commit(
+			repo.workdir().unwrap().to_str().unwrap().into(),
+			msg.to_string(),
+			behavior,
+			None,
+		)
+	}
+
+	#[test]
+	fn test_commit_runs_commit_msg() {
+		let (_td, repo) = repo_init().unwrap();
+
+		git2_hooks::create_hook(
+			&repo,
+			git2_hooks::HOOK_PRE_COMMIT,
+			b"#!/bin/sh\nexit 0\n",
+		);
+		git2_hooks::create_hook(
+			&repo,
+			git2_hooks::HOOK_COMMIT_MSG,
+			b"#!/bin/sh\necho 'rewritten' > \"$1\"\nexit 0\n",
+		);
+
+		let res = run_job(
+			commit_job(&repo, "msg", HookBehavior::Verify),
+			|_| {},
+		);
+
+		assert_eq!(res.hook, HookType::CommitMsg);
+		assert!(res.result.unwrap().is_ok());
+		assert_eq!(res.msg.as_deref(), Some("rewritten\n"));
+
+		// nothing runs without verification
+		let res = run_job(
+			commit_job(&repo, "msg", HookBehavior::NoVerify),
+			|_| {},
+		);
+
+		assert!(res.result.unwrap().is_ok());
+		assert_eq!(res.msg.as_deref(), Some("msg"));
+	}
+
+	#[test]
+	fn test_commit_with_chatty_pre_commit() {
+		let (_td, repo) = repo_init().unwrap();
+
+		// more than fits into the pipe buffer, which must not block
+		// the hook until it is cancelled
+		git2_hooks::create_hook(
+			&repo,
+			git2_hooks::HOOK_PRE_COMMIT,
+			b"#!/bin/sh\nhead -c 200000 /dev/zero | tr '\\0' a\nexit 0\n",
+		);
+
+		let start = Instant::now();
+		let res = run_job(
+			AsyncHooksJob::commit(
+				repo.workdir().unwrap().to_str().unwrap().into(),
+				String::from("msg"),
+				HookBehavior::Verify,
+				Some(Duration::from_secs(5)),
+			),
+			|_| {},
+		);
+
+		assert!(res.result.unwrap().is_ok());
+		assert!(start.elapsed() < Duration::from_secs(4));
+	}
+
+	#[test]
+	fn test_commit_stops_at_pre_commit() {
+		let (_td, repo) = repo_init().unwrap();
+
+		git2_hooks::create_hook(
+			&repo,
+			git2_hooks::HOOK_PRE_COMMIT,
+			SLOW_HOOK,
+		);
+		git2_hooks::create_hook(
+			&repo,
+			git2_hooks::HOOK_COMMIT_MSG,
+			b"#!/bin/sh\necho 'rewritten' > \"$1\"\nexit 0\n",
+		);
+
+		let start = Instant::now();
+		let res = run_job(
+			commit_job(&repo, "msg", HookBehavior::Verify),
+			|job| {
+				while !started(&repo) {
+					assert!(start.elapsed() < Duration::from_secs(4));
+					thread::sleep(Duration::from_millis(10));
+				}
+
+				job.cancel();
+			},
+		);
+
+		assert_eq!(res.hook, HookType::PreCommit);
+		assert!(matches!(
+			res.result.unwrap(),
+			HookResult::Cancelled { .. }
+		));
+		// commit-msg did not run
+		assert_eq!(res.msg.as_deref(), Some("msg"));
+	}
+}
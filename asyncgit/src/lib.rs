@@ -34,13 +34,17 @@
 
 pub mod asyncjob;
 mod blame;
+mod branch_compare;
 mod branches;
 pub mod cached;
 mod commit_files;
+mod commit_msg_preview;
 mod diff;
 mod error;
 mod fetch_job;
 mod filter_commits;
+mod hooks_job;
+mod lru;
 mod progress;
 mod pull;
 mod push;
@@ -55,12 +59,19 @@ mod treefiles;
 
 pub use crate::{
 	blame::{AsyncBlame, BlameParams},
+	branch_compare::{AsyncBranchCompareJob, BranchCompareResult},
 	branches::AsyncBranchesJob,
-	commit_files::{AsyncCommitFiles, CommitFilesParams},
+	commit_files::{
+		AsyncCommitFiles, CommitFilesCacheStats, CommitFilesParams,
+	},
+	commit_msg_preview::{
+		AsyncCommitMsgPreviewJob, CommitMsgPreviewResult,
+	},
 	diff::{AsyncDiff, DiffParams, DiffType},
 	error::{Error, Result},
 	fetch_job::AsyncFetchJob,
 	filter_commits::{AsyncCommitFilterJob, CommitFilterResult},
+	hooks_job::{AsyncHooksJob, HooksJobResult},
 	progress::ProgressPercent,
 	pull::{AsyncPull, FetchRequest},
 	push::{AsyncPush, PushRequest},
@@ -117,6 +128,12 @@ pub enum AsyncGitNotification {
 	TreeFiles,
 	///
 	CommitFilter,
+	///
+	CommitMsgPreview,
+	///
+	BranchCompare,
+	///
+	Hooks,
 }
 
 /// helper function to calculate the hash of an arbitrary type that implements the `Hash` trait
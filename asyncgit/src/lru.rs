@@ -0,0 +1,109 @@
+//! small least recently used map used by the caches of this crate
+
+use std::{
+	collections::{BTreeMap, HashMap},
+	hash::Hash,
+};
+
+/// minimal lru map: entries remember when they were used last, the
+/// least recently used one is dropped once `capacity` is exceeded
+pub struct Lru<K, V> {
+	capacity: usize,
+	tick: u64,
+	entries: HashMap<K, (V, u64)>,
+	by_use: BTreeMap<u64, K>,
+}
+
+impl<K: Hash + Eq + Clone, V> Lru<K, V> {
+	pub fn new(capacity: usize) -> Self {
+		Self {
+			capacity,
+			tick: 0,
+			entries: HashMap::new(),
+			by_use: BTreeMap::new(),
+		}
+	}
+
+	fn next_tick(&mut self) -> u64 {
+		self.tick += 1;
+		self.tick
+	}
+
+	pub fn get_mut(&mut self, key: &K) -> Option<&mut V> {
+		let tick = self.next_tick();
+		let (value, used) = self.entries.get_mut(key)?;
+
+		self.by_use.remove(used);
+		self.by_use.insert(tick, key.clone());
+		*used = tick;
+
+		Some(value)
+	}
+
+	pub fn insert(&mut self, key: K, value: V) {
+		let tick = self.next_tick();
+
+		if let Some((_, used)) =
+			self.entries.insert(key.clone(), (value, tick))
+		{
+			self.by_use.remove(&used);
+		}
+		self.by_use.insert(tick, key);
+
+		self.evict();
+	}
+
+	pub fn set_capacity(&mut self, capacity: usize) {
+		self.capacity = capacity;
+		self.evict();
+	}
+
+	/// does not mark the entry as used
+	pub fn contains_key(&self, key: &K) -> bool {
+		self.entries.contains_key(key)
+	}
+
+	/// all values, without marking them as used
+	pub fn values_mut(&mut self) -> impl Iterator<Item = &mut V> {
+		self.entries.values_mut().map(|(value, _)| value)
+	}
+
+	fn evict(&mut self) {
+		while self.entries.len() > self.capacity {
+			let Some((_, key)) = self.by_use.pop_first() else {
+				break;
+			};
+			self.entries.remove(&key);
+		}
+	}
+
+	#[cfg(test)]
+	pub fn len(&self) -> usize {
+		self.entries.len()
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn test_lru_evicts_least_recently_used() {
+		let mut lru = Lru::new(2);
+
+		lru.insert(1, "a");
+		lru.insert(2, "b");
+		assert!(lru.get_mut(&1).is_some());
+
+		lru.insert(3, "c");
+
+		assert_eq!(lru.len(), 2);
+		assert!(lru.get_mut(&2).is_none());
+		assert!(lru.get_mut(&1).is_some());
+		assert!(lru.get_mut(&3).is_some());
+
+		lru.set_capacity(1);
+		assert_eq!(lru.len(), 1);
+		assert!(lru.get_mut(&3).is_some());
+	}
+}
@@ -0,0 +1,239 @@
+//! process wide observer of ref changing operations.
+//!
+//! the mutating functions of [`crate::sync`] (commit, reset, branches,
+//! tags, stashes, push) report what they changed once they succeeded,
+//! see [`set_audit_observer`]. only metadata is passed on: refs and
+//! commit ids, never messages, urls or credentials.
+
+use super::{utils::get_head_refname, CommitId, RepoPath};
+use git2::Repository;
+use std::sync::{PoisonError, RwLock};
+
+///
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AuditOperation {
+	///
+	Commit,
+	///
+	Amend,
+	///
+	Reset,
+	///
+	BranchCreate,
+	///
+	BranchDelete,
+	///
+	TagCreate,
+	///
+	TagDelete,
+	///
+	StashSave,
+	///
+	StashPop,
+	///
+	StashDrop,
+	///
+	Push,
+}
+
+impl AuditOperation {
+	///
+	pub const fn as_str(self) -> &'static str {
+		match self {
+			Self::Commit => "commit",
+			Self::Amend => "amend",
+			Self::Reset => "reset",
+			Self::BranchCreate => "branch-create",
+			Self::BranchDelete => "branch-delete",
+			Self::TagCreate => "tag-create",
+			Self::TagDelete => "tag-delete",
+			Self::StashSave => "stash-save",
+			Self::StashPop => "stash-pop",
+			Self::StashDrop => "stash-drop",
+			Self::Push => "push",
+		}
+	}
+}
+
+/// a ref changing operation that succeeded
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AuditEvent {
+	///
+	pub operation: AuditOperation,
+	/// refs changed by the operation, a push names the remote first
+	pub refs: Vec<String>,
+	/// target before the operation, `None` if it created the ref
+	pub old: Option<CommitId>,
+	/// target after the operation, `None` if it deleted the ref
+	pub new: Option<CommitId>,
+}
+
+/// called with every [`AuditEvent`] on the thread doing the operation
+pub type AuditObserver =
+	Box<dyn Fn(&RepoPath, &AuditEvent) + Send + Sync>;
+
+static OBSERVER: RwLock<Option<AuditObserver>> = RwLock::new(None);
+
+/// installs (or with `None` removes) the observer
+pub fn set_audit_observer(observer: Option<AuditObserver>) {
+	*OBSERVER.write().unwrap_or_else(PoisonError::into_inner) =
+		observer;
+}
+
+/// name of the ref `HEAD` points to, `HEAD` itself if detached
+pub fn head_ref(repo: &Repository) -> String {
+	get_head_refname(repo).unwrap_or_else(|_| String::from("HEAD"))
+}
+
+/// target of the ref `name`, peeled to a commit
+pub fn ref_target(repo: &Repository, name: &str) -> Option<CommitId> {
+	repo.find_reference(name)
+		.and_then(|r| r.peel_to_commit())
+		.ok()
+		.map(|c| c.id().into())
+}
+
+/// passes the event on to the observer, if there is one
+pub fn record(
+	repo_path: &RepoPath,
+	operation: AuditOperation,
+	refs: &[&str],
+	old: Option<CommitId>,
+	new: Option<CommitId>,
+) {
+	let observer =
+		OBSERVER.read().unwrap_or_else(PoisonError::into_inner);
+
+	if let Some(observer) = observer.as_ref() {
+		observer(
+			repo_path,
+			&AuditEvent {
+				operation,
+				refs: refs.iter().map(ToString::to_string).collect(),
+				old,
+				new,
+			},
+		);
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::sync::{
+		create_branch, delete_branch, reset_repo, stash_drop,
+		stash_save, tag_commit,
+		tests::{repo_init, write_commit_file},
+	};
+	use git2::ResetType;
+	use pretty_assertions::assert_eq;
+	use std::{
+		fs::File,
+		io::Write,
+		sync::{Arc, Mutex},
+	};
+
+	#[test]
+	fn test_observed_operations() {
+		let (_td, repo) = repo_init().unwrap();
+		let root = repo.path().parent().unwrap();
+		let repo_path: RepoPath =
+			repo.workdir().unwrap().to_str().unwrap().into();
+		let c1 = write_commit_file(&repo, "a.txt", "a", "c1");
+
+		let events = Arc::new(Mutex::new(Vec::new()));
+		{
+			let events = Arc::clone(&events);
+			let observed = repo_path.clone();
+			set_audit_observer(Some(Box::new(move |path, event| {
+				// other tests run concurrently
+				if path.gitpath() == observed.gitpath() {
+					events.lock().unwrap().push(event.clone());
+				}
+			})));
+		}
+
+		let c2 = write_commit_file(&repo, "a.txt", "b", "c2");
+
+		let branch = create_branch(&repo_path, "feature").unwrap();
+		tag_commit(&repo_path, &c2, "v1", Some("tag message"))
+			.unwrap();
+		reset_repo(&repo_path, c1, ResetType::Hard).unwrap();
+
+		File::create(root.join("a.txt"))
+			.unwrap()
+			.write_all(b"c")
+			.unwrap();
+		let stash =
+			stash_save(&repo_path, None, false, false).unwrap();
+		stash_drop(&repo_path, stash).unwrap();
+
+		repo.set_head("refs/heads/master").unwrap();
+		delete_branch(&repo_path, &branch).unwrap();
+
+		set_audit_observer(None);
+
+		let events = events.lock().unwrap();
+		let summary = events
+			.iter()
+			.map(|e| {
+				(
+					e.operation,
+					e.refs.join(" "),
+					e.old,
+					e.new.is_some(),
+				)
+			})
+			.collect::<Vec<_>>();
+
+		assert_eq!(
+			summary,
+			vec![
+				(
+					AuditOperation::Commit,
+					String::from("refs/heads/master"),
+					Some(c1),
+					true
+				),
+				(
+					AuditOperation::BranchCreate,
+					String::from("refs/heads/feature"),
+					None,
+					true
+				),
+				(
+					AuditOperation::TagCreate,
+					String::from("refs/tags/v1"),
+					None,
+					true
+				),
+				(
+					AuditOperation::Reset,
+					String::from("refs/heads/feature"),
+					Some(c2),
+					true
+				),
+				(
+					AuditOperation::StashSave,
+					String::from("refs/stash"),
+					None,
+					true
+				),
+				(
+					AuditOperation::StashDrop,
+					String::from("refs/stash"),
+					Some(stash),
+					false
+				),
+				(
+					AuditOperation::BranchDelete,
+					String::from("refs/heads/feature"),
+					Some(c1),
+					false
+				),
+			]
+		);
+		assert_eq!(events[0].new, Some(c2));
+		assert_eq!(events[3].new, Some(c1));
+	}
+}
@@ -120,7 +120,7 @@ pub fn blame_file(
 				{
 					let hunk = BlameHunk {
 						commit_id,
-						author: commit_info.author.clone(),
+						author: commit_info.author().to_string(),
 						time: commit_info.time,
 						start_line,
 						end_line,
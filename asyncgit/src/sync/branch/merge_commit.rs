@@ -3,7 +3,10 @@
 use super::BranchType;
 use crate::{
 	error::{Error, Result},
-	sync::{merge_msg, repository::repo, CommitId, RepoPath},
+	sync::{
+		merge_msg, repository::repo, state::ensure_clean_state,
+		CommitId, RepoPath,
+	},
 };
 use git2::Commit;
 use scopetime::scope_time;
@@ -18,6 +21,7 @@ pub fn merge_upstream_commit(
 	scope_time!("merge_upstream_commit");
 
 	let repo = repo(repo_path)?;
+	ensure_clean_state(&repo)?;
 
 	let branch = repo.find_branch(branch_name, BranchType::Local)?;
 	let upstream = branch.upstream()?;
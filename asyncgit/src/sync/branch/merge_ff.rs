@@ -3,7 +3,7 @@
 use super::BranchType;
 use crate::{
 	error::{Error, Result},
-	sync::{repository::repo, RepoPath},
+	sync::{repository::repo, state::ensure_clean_state, RepoPath},
 };
 use scopetime::scope_time;
 
@@ -15,6 +15,7 @@ pub fn branch_merge_upstream_fastforward(
 	scope_time!("branch_merge_upstream");
 
 	let repo = repo(repo_path)?;
+	ensure_clean_state(&repo)?;
 
 	let branch = repo.find_branch(branch, BranchType::Local)?;
 	let upstream = branch.upstream()?;
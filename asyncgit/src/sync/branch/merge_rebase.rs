@@ -3,8 +3,10 @@
 use crate::{
 	error::{Error, Result},
 	sync::{
-		rebase::conflict_free_rebase, repository::repo, CommitId,
-		RepoPath,
+		rebase::{conflict_free_rebase, pre_rebase},
+		repository::repo,
+		state::ensure_clean_state,
+		CommitId, RepoPath,
 	},
 };
 use git2::BranchType;
@@ -18,6 +20,7 @@ pub fn merge_upstream_rebase(
 	scope_time!("merge_upstream_rebase");
 
 	let repo = repo(repo_path)?;
+	ensure_clean_state(&repo)?;
 	if super::get_branch_name_repo(&repo)? != branch_name {
 		return Err(Error::Generic(String::from(
 			"can only rebase in head branch",
@@ -26,6 +29,12 @@ pub fn merge_upstream_rebase(
 
 	let branch = repo.find_branch(branch_name, BranchType::Local)?;
 	let upstream = branch.upstream()?;
+
+	pre_rebase(
+		repo_path,
+		upstream.get().shorthand().unwrap_or_default(),
+	)?;
+
 	let upstream_commit = upstream.get().peel_to_commit()?;
 	let annotated_upstream =
 		repo.find_annotated_commit(upstream_commit.id())?;
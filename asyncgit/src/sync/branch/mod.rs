@@ -3,19 +3,36 @@
 pub mod merge_commit;
 pub mod merge_ff;
 pub mod merge_rebase;
+pub mod move_changes;
+pub mod recent;
 pub mod rename;
 
 use super::{utils::bytes2string, RepoPath};
 use crate::{
 	error::{Error, Result},
 	sync::{
-		remotes::get_default_remote_for_push_in_repo,
-		repository::repo, utils::get_head_repo, CommitId,
+		audit::{self, AuditOperation},
+		commit_cache::commit_meta,
+		config::get_config_string,
+		hooks::{with_reference_transaction, RefUpdate},
+		remotes::{
+			ensure_ref_not_broken, get_default_remote,
+			get_default_remote_in_repo, get_remote_default_branch,
+			ref_target_missing,
+		},
+		repository::repo,
+		state::ensure_clean_state,
+		utils::get_head_repo,
+		CommitId,
 	},
 };
-use git2::{Branch, BranchType, Repository};
+use git2::{Branch, BranchType, Oid, Repository};
 use scopetime::scope_time;
-use std::collections::HashSet;
+use std::{
+	collections::{HashMap, HashSet},
+	path::PathBuf,
+	sync::{Mutex, OnceLock},
+};
 
 /// returns the branch-name head is currently pointing to
 /// this might be expensive, see `cached::BranchName`
@@ -69,6 +86,9 @@ pub struct UpstreamBranch {
 pub struct RemoteBranch {
 	///
 	pub has_tracking: bool,
+	/// the commit it points at is missing locally, see
+	/// `sync::repair_remote_ref`
+	pub broken: bool,
 }
 
 ///
@@ -147,7 +167,19 @@ pub fn get_branches_info(
 		.branches(Some(filter))?
 		.map(|b| {
 			let branch = b?.0;
-			let top_commit = branch.get().peel_to_commit()?;
+			// symbolic refs (e.g. `origin/HEAD`) have no direct target
+			let top_commit_id = match branch.get().target() {
+				Some(id) => id,
+				None => branch.get().peel_to_commit()?.id(),
+			};
+			let broken =
+				!local && ref_target_missing(&repo, branch.get())?;
+			let (top_commit_message, top_commit) = if broken {
+				(String::new(), top_commit_id.into())
+			} else {
+				let meta = commit_meta(&repo, top_commit_id.into())?;
+				(meta.summary(), meta.id)
+			};
 			let reference = bytes2string(branch.get().name_bytes())?;
 			let upstream = branch.upstream();
 
@@ -178,16 +210,15 @@ pub fn get_branches_info(
 				BranchDetails::Remote(RemoteBranch {
 					has_tracking: remotes_with_tracking
 						.contains(name_bytes),
+					broken,
 				})
 			};
 
 			Ok(BranchInfo {
 				name: bytes2string(name_bytes)?,
 				reference,
-				top_commit_message: bytes2string(
-					top_commit.summary_bytes().unwrap_or_default(),
-				)?,
-				top_commit: top_commit.id().into(),
+				top_commit_message,
+				top_commit,
 				details,
 			})
 		})
@@ -208,10 +239,13 @@ pub struct BranchCompare {
 	pub behind: usize,
 }
 
-///
+/// tracks the branch just pushed to `remote` there unless it has an
+/// upstream already or `remote` is not the one it is fetched from (a
+/// mirror or a `remote.pushDefault` fork)
 pub(crate) fn branch_set_upstream_after_push(
 	repo: &Repository,
 	branch_name: &str,
+	remote: &str,
 ) -> Result<()> {
 	scope_time!("branch_set_upstream");
 
@@ -219,7 +253,12 @@ pub(crate) fn branch_set_upstream_after_push(
 		repo.find_branch(branch_name, BranchType::Local)?;
 
 	if branch.upstream().is_err() {
-		let remote = get_default_remote_for_push_in_repo(repo)?;
+		let fetch_remote = get_default_remote_in_repo(repo)
+			.unwrap_or_else(|_| remote.to_string());
+		if fetch_remote != remote {
+			return Ok(());
+		}
+
 		let upstream_name = format!("{remote}/{branch_name}");
 		branch.set_upstream(Some(upstream_name.as_str()))?;
 	}
@@ -288,6 +327,7 @@ pub fn branch_compare_upstream(
 	let branch = repo.find_branch(branch, BranchType::Local)?;
 
 	let upstream = branch.upstream()?;
+	ensure_ref_not_broken(&repo, upstream.get())?;
 
 	let branch_commit =
 		branch.into_reference().peel_to_commit()?.id();
@@ -301,6 +341,157 @@ pub fn branch_compare_upstream(
 	Ok(BranchCompare { ahead, behind })
 }
 
+/// compares local `branch` to an arbitrary `base` revision
+/// (e.g. `origin/main`), see [`branch_compare_upstream`]
+pub fn branch_compare(
+	repo_path: &RepoPath,
+	branch: &str,
+	base: &str,
+) -> Result<BranchCompare> {
+	scope_time!("branch_compare");
+
+	let repo = repo(repo_path)?;
+
+	let branch_commit = repo
+		.find_branch(branch, BranchType::Local)?
+		.into_reference()
+		.peel_to_commit()?
+		.id();
+
+	if let Ok(base) = repo.resolve_reference_from_short_name(base) {
+		ensure_ref_not_broken(&repo, &base)?;
+	}
+	let base_commit =
+		repo.revparse_single(base)?.peel_to_commit()?.id();
+
+	let (ahead, behind) =
+		repo.graph_ahead_behind(branch_commit, base_commit)?;
+
+	Ok(BranchCompare { ahead, behind })
+}
+
+fn branch_description_key(branch: &str) -> String {
+	format!("branch.{branch}.description")
+}
+
+/// description of local branch `branch` (`branch.<name>.description`,
+/// as edited by `git branch --edit-description`)
+pub fn get_branch_description(
+	repo_path: &RepoPath,
+	branch: &str,
+) -> Result<Option<String>> {
+	scope_time!("get_branch_description");
+
+	Ok(
+		get_config_string(
+			repo_path,
+			&branch_description_key(branch),
+		)?
+		.map(|description| description.trim_end().to_string())
+		.filter(|description| !description.is_empty()),
+	)
+}
+
+/// sets the description of local branch `branch`,
+/// an empty `description` removes it
+pub fn set_branch_description(
+	repo_path: &RepoPath,
+	branch: &str,
+	description: &str,
+) -> Result<()> {
+	scope_time!("set_branch_description");
+
+	let repo = repo(repo_path)?;
+	let mut config = repo.config()?;
+	let key = branch_description_key(branch);
+	let description = description.trim_end();
+
+	if description.is_empty() {
+		match config.remove(&key) {
+			Err(e) if e.code() != git2::ErrorCode::NotFound => {
+				return Err(e.into());
+			}
+			_ => (),
+		}
+	} else {
+		// git terminates the description with a newline as well
+		config.set_str(&key, &format!("{description}\n"))?;
+	}
+
+	Ok(())
+}
+
+/// the default branch of the repository, to be used as the base for
+/// anything that needs one (comparing, merging, cleaning up branches).
+///
+/// looked up in this order:
+/// * default branch of the default remote (see [`get_remote_default_branch`])
+/// * local branch named like `init.defaultBranch`
+/// * local branch `main` or `master`
+///
+/// a found branch is cached per repository for the lifetime of the
+/// process. not finding one (or failing to look) is not, so a branch or
+/// remote added later is picked up by the next call.
+pub fn default_branch(repo_path: &RepoPath) -> Option<String> {
+	static CACHE: OnceLock<Mutex<HashMap<PathBuf, String>>> =
+		OnceLock::new();
+
+	let key = repo_path.gitpath().to_path_buf();
+	let cache = CACHE.get_or_init(Mutex::default);
+
+	if let Some(cached) =
+		cache.lock().ok().and_then(|cache| cache.get(&key).cloned())
+	{
+		return Some(cached);
+	}
+
+	let branch =
+		detect_default_branch(repo_path).unwrap_or_else(|e| {
+			log::error!("default branch detection failed: {e}");
+			None
+		})?;
+
+	if let Ok(mut cache) = cache.lock() {
+		cache.insert(key, branch.clone());
+	}
+
+	Some(branch)
+}
+
+fn detect_default_branch(
+	repo_path: &RepoPath,
+) -> Result<Option<String>> {
+	scope_time!("detect_default_branch");
+
+	let remote_default = get_default_remote(repo_path)
+		.and_then(|remote| {
+			get_remote_default_branch(repo_path, &remote)
+		})
+		.unwrap_or_else(|e| {
+			log::trace!("no remote default branch: {e}");
+			None
+		});
+
+	if remote_default.is_some() {
+		return Ok(remote_default);
+	}
+
+	let repo = repo(repo_path)?;
+
+	let candidates =
+		get_config_string(repo_path, "init.defaultBranch")?
+			.into_iter()
+			.chain(["main".to_string(), "master".to_string()]);
+
+	for candidate in candidates {
+		if repo.find_branch(&candidate, BranchType::Local).is_ok() {
+			return Ok(Some(candidate));
+		}
+	}
+
+	Ok(None)
+}
+
 /// Switch branch to given `branch_name`.
 ///
 /// Method will fail if there are conflicting changes between current and target branch. However,
@@ -313,6 +504,7 @@ pub fn checkout_branch(
 	scope_time!("checkout_branch");
 
 	let repo = repo(repo_path)?;
+	ensure_clean_state(&repo)?;
 
 	let branch = repo.find_branch(branch_name, BranchType::Local)?;
 
@@ -345,6 +537,7 @@ pub fn checkout_commit(
 	scope_time!("checkout_commit");
 
 	let repo = repo(repo_path)?;
+	ensure_clean_state(&repo)?;
 	let cur_ref = repo.head()?;
 	let statuses = repo.statuses(Some(
 		git2::StatusOptions::new().include_ignored(false),
@@ -375,6 +568,7 @@ pub fn checkout_remote_branch(
 	scope_time!("checkout_remote_branch");
 
 	let repo = repo(repo_path)?;
+	ensure_clean_state(&repo)?;
 	let cur_ref = repo.head()?;
 
 	if !repo
@@ -423,11 +617,23 @@ pub fn delete_branch(
 	if branch.is_head() {
 		return Err(Error::Generic("You cannot be on the branch you want to delete, switch branch, then delete this branch".to_string()));
 	}
+	let old = audit::ref_target(&repo, branch_ref);
 	branch.delete()?;
+
+	audit::record(
+		repo_path,
+		AuditOperation::BranchDelete,
+		&[branch_ref],
+		old,
+		None,
+	);
+
 	Ok(())
 }
 
-/// creates a new branch pointing to current HEAD commit and updating HEAD to new branch
+/// creates a new branch pointing to current HEAD commit and updating HEAD to new branch.
+/// creating the branch runs the `reference-transaction` hook, which can
+/// abort it
 pub fn create_branch(
 	repo_path: &RepoPath,
 	name: &str,
@@ -439,11 +645,27 @@ pub fn create_branch(
 	let head_id = get_head_repo(&repo)?;
 	let head_commit = repo.find_commit(head_id.into())?;
 
-	let branch = repo.branch(name, &head_commit, false)?;
-	let branch_ref = branch.into_reference();
-	let branch_ref_name = bytes2string(branch_ref.name_bytes())?;
+	let update = RefUpdate {
+		old_oid: Oid::zero(),
+		new_oid: head_id.into(),
+		refname: format!("refs/heads/{name}"),
+	};
+	let branch_ref_name =
+		with_reference_transaction(repo_path, &[update], || {
+			let branch = repo.branch(name, &head_commit, false)?;
+
+			bytes2string(branch.into_reference().name_bytes())
+		})?;
 	repo.set_head(branch_ref_name.as_str())?;
 
+	audit::record(
+		repo_path,
+		AuditOperation::BranchCreate,
+		&[&branch_ref_name],
+		None,
+		Some(head_id),
+	);
+
 	Ok(branch_ref_name)
 }
 
@@ -498,12 +720,51 @@ mod tests_create_branch {
 			"branch1"
 		);
 	}
+
+	#[test]
+	fn test_reference_transaction() {
+		let (td, repo) = repo_init().unwrap();
+		let root = repo.path().parent().unwrap();
+		let repo_path: &RepoPath =
+			&root.as_os_str().to_str().unwrap().into();
+		let log = td.path().join("transaction.log");
+
+		// rejects `prepared` of the second branch
+		let hook = format!(
+			"#!/bin/sh\ninput=$(cat)\necho \"$1 $input\" >> '{}'\ntest \"$1\" != prepared || ! echo \"$input\" | grep -q rejected\n",
+			log.display(),
+		);
+		git2_hooks::create_hook(
+			&repo,
+			git2_hooks::HOOK_REFERENCE_TRANSACTION,
+			hook.as_bytes(),
+		);
+
+		let head = get_head_repo(&repo).unwrap();
+		let zero = Oid::zero();
+		create_branch(repo_path, "branch1").unwrap();
+		assert_eq!(
+			std::fs::read_to_string(&log).unwrap(),
+			format!(
+				"prepared {zero} {head} refs/heads/branch1\ncommitted {zero} {head} refs/heads/branch1\n"
+			)
+		);
+
+		assert!(create_branch(repo_path, "rejected").is_err());
+		assert!(repo
+			.find_branch("rejected", BranchType::Local)
+			.is_err());
+		assert_eq!(
+			get_branch_name(repo_path).unwrap().as_str(),
+			"branch1"
+		);
+	}
 }
 
 #[cfg(test)]
 mod tests_branch_compare {
 	use super::*;
-	use crate::sync::tests::repo_init;
+	use crate::sync::tests::{repo_init, write_commit_file};
 
 	#[test]
 	fn test_smoke() {
@@ -518,6 +779,151 @@ mod tests_branch_compare {
 
 		assert!(res.is_err());
 	}
+
+	#[test]
+	fn test_compare_base() {
+		let (_td, repo) = repo_init().unwrap();
+		let root = repo.path().parent().unwrap();
+		let repo_path: &RepoPath =
+			&root.as_os_str().to_str().unwrap().into();
+
+		create_branch(repo_path, "feature").unwrap();
+		write_commit_file(&repo, "a.txt", "a", "commit a");
+		write_commit_file(&repo, "b.txt", "b", "commit b");
+
+		let res =
+			branch_compare(repo_path, "feature", "master").unwrap();
+
+		assert_eq!((res.ahead, res.behind), (2, 0));
+
+		checkout_branch(repo_path, "master").unwrap();
+		write_commit_file(&repo, "c.txt", "c", "commit c");
+
+		let res =
+			branch_compare(repo_path, "feature", "master").unwrap();
+
+		assert_eq!((res.ahead, res.behind), (2, 1));
+
+		assert!(branch_compare(repo_path, "feature", "foo").is_err());
+	}
+}
+
+#[cfg(test)]
+mod tests_branch_description {
+	use super::*;
+	use crate::sync::tests::repo_init;
+
+	#[test]
+	fn test_set_and_get() {
+		let (_td, repo) = repo_init().unwrap();
+		let root = repo.path().parent().unwrap();
+		let repo_path: &RepoPath =
+			&root.as_os_str().to_str().unwrap().into();
+
+		assert_eq!(
+			get_branch_description(repo_path, "master").unwrap(),
+			None
+		);
+
+		set_branch_description(repo_path, "master", "foo\n\nbar\n\n")
+			.unwrap();
+
+		assert_eq!(
+			get_branch_description(repo_path, "master")
+				.unwrap()
+				.as_deref(),
+			Some("foo\n\nbar")
+		);
+		assert_eq!(
+			repo.config()
+				.unwrap()
+				.get_string("branch.master.description")
+				.unwrap(),
+			"foo\n\nbar\n"
+		);
+
+		set_branch_description(repo_path, "master", "  \n").unwrap();
+
+		assert_eq!(
+			get_branch_description(repo_path, "master").unwrap(),
+			None
+		);
+
+		// removing a description that does not exist is fine
+		set_branch_description(repo_path, "master", "").unwrap();
+	}
+}
+
+#[cfg(test)]
+mod tests_default_branch {
+	use super::*;
+	use crate::sync::tests::repo_init;
+
+	#[test]
+	fn test_heuristics() {
+		let (_td, repo) = repo_init().unwrap();
+		let root = repo.path().parent().unwrap();
+		let repo_path: &RepoPath =
+			&root.as_os_str().to_str().unwrap().into();
+
+		assert_eq!(
+			default_branch(repo_path).as_deref(),
+			Some("master")
+		);
+
+		// cached
+		create_branch(repo_path, "develop").unwrap();
+		repo.config()
+			.unwrap()
+			.set_str("init.defaultBranch", "develop")
+			.unwrap();
+
+		assert_eq!(
+			default_branch(repo_path).as_deref(),
+			Some("master")
+		);
+	}
+
+	#[test]
+	fn test_not_found_is_not_cached() {
+		let (_td, repo) = repo_init().unwrap();
+		let root = repo.path().parent().unwrap();
+		let repo_path: &RepoPath =
+			&root.as_os_str().to_str().unwrap().into();
+
+		repo.find_branch("master", BranchType::Local)
+			.unwrap()
+			.rename("trunk", false)
+			.unwrap();
+
+		assert_eq!(default_branch(repo_path), None);
+
+		create_branch(repo_path, "main").unwrap();
+
+		assert_eq!(
+			default_branch(repo_path).as_deref(),
+			Some("main")
+		);
+	}
+
+	#[test]
+	fn test_init_default_branch() {
+		let (_td, repo) = repo_init().unwrap();
+		let root = repo.path().parent().unwrap();
+		let repo_path: &RepoPath =
+			&root.as_os_str().to_str().unwrap().into();
+
+		create_branch(repo_path, "develop").unwrap();
+		repo.config()
+			.unwrap()
+			.set_str("init.defaultBranch", "develop")
+			.unwrap();
+
+		assert_eq!(
+			default_branch(repo_path).as_deref(),
+			Some("develop")
+		);
+	}
 }
 
 #[cfg(test)]
@@ -0,0 +1,232 @@
+//! moving uncommitted changes (and local commits) to a new branch
+
+use super::super::utils::bytes2string;
+use crate::{
+	error::{Error, Result},
+	sync::{repository::repo, RepoPath},
+};
+use git2::{Branch, Oid, Repository, RepositoryState};
+use scopetime::scope_time;
+
+/// upstream commit of the checked out branch, `None` without upstream
+fn head_upstream(repo: &Repository) -> Result<Option<Oid>> {
+	let Ok(upstream) = Branch::wrap(repo.head()?).upstream() else {
+		return Ok(None);
+	};
+
+	Ok(Some(upstream.get().peel_to_commit()?.id()))
+}
+
+/// commits of the checked out branch not on its upstream, the ones
+/// [`move_changes_to_branch`] can carry (`0` without upstream)
+pub fn local_commits(repo_path: &RepoPath) -> Result<usize> {
+	let repo = repo(repo_path)?;
+
+	let Some(upstream) = head_upstream(&repo)? else {
+		return Ok(0);
+	};
+	let head = repo.head()?.peel_to_commit()?.id();
+
+	Ok(repo.graph_ahead_behind(head, upstream)?.0)
+}
+
+/// creates the branch `name` at `HEAD` and checks it out. the tip does
+/// not change, so uncommitted changes stay in the work dir and index
+/// as they are.
+///
+/// with `carry_commits` the previous branch is reset to its upstream
+/// afterwards, so its local commits are only on the new branch. if any
+/// step fails the steps before are undone.
+pub fn move_changes_to_branch(
+	repo_path: &RepoPath,
+	name: &str,
+	carry_commits: bool,
+) -> Result<String> {
+	scope_time!("move_changes_to_branch");
+
+	let repo = repo(repo_path)?;
+
+	if repo.state() != RepositoryState::Clean {
+		return Err(Error::Generic(String::from(
+			"cannot move changes while repo is not in a clean state",
+		)));
+	}
+
+	let head = repo.head()?;
+	if !head.is_branch() {
+		return Err(Error::NoBranch);
+	}
+	let prev_ref = bytes2string(head.name_bytes())?;
+	let head_commit = head.peel_to_commit()?;
+
+	let upstream = if carry_commits {
+		Some(head_upstream(&repo)?.ok_or_else(|| {
+			Error::Generic(String::from(
+				"cannot carry commits of a branch without upstream",
+			))
+		})?)
+	} else {
+		None
+	};
+
+	let mut branch = repo.branch(name, &head_commit, false)?;
+	let branch_ref = bytes2string(branch.get().name_bytes())?;
+
+	if let Err(e) = repo.set_head(&branch_ref) {
+		rollback(&repo, &prev_ref, &mut branch);
+		return Err(e.into());
+	}
+
+	if let Some(upstream) = upstream {
+		if let Err(e) =
+			repo.find_reference(&prev_ref).and_then(|mut prev| {
+				prev.set_target(
+					upstream,
+					&format!("branch: moved local commits to {name}"),
+				)
+			}) {
+			rollback(&repo, &prev_ref, &mut branch);
+			return Err(e.into());
+		}
+	}
+
+	Ok(branch_ref)
+}
+
+/// checks out `prev_ref` again and deletes the new `branch`
+fn rollback(repo: &Repository, prev_ref: &str, branch: &mut Branch) {
+	if let Err(e) = repo.set_head(prev_ref) {
+		log::error!("move changes: restoring HEAD failed: {e}");
+		return;
+	}
+	if let Err(e) = branch.delete() {
+		log::error!("move changes: deleting new branch failed: {e}");
+	}
+}
+
+#[cfg(test)]
+mod test {
+	use super::*;
+	use crate::sync::{
+		remotes::push::push_branch,
+		status::{get_status, StatusType},
+		tests::{repo_init, repo_init_bare, write_commit_file},
+		utils::{get_head, stage_add_file},
+	};
+	use std::{fs, path::Path};
+
+	fn repo_path(repo: &Repository) -> RepoPath {
+		repo.workdir().unwrap().to_str().unwrap().into()
+	}
+
+	fn branch_tip(repo: &Repository, name: &str) -> Oid {
+		repo.find_branch(name, git2::BranchType::Local)
+			.unwrap()
+			.get()
+			.peel_to_commit()
+			.unwrap()
+			.id()
+	}
+
+	#[test]
+	fn test_move_changes() {
+		let (_td, repo) = repo_init().unwrap();
+		let repo_path = repo_path(&repo);
+		let root = repo.workdir().unwrap();
+
+		let tip = write_commit_file(&repo, "a.txt", "a", "c1");
+
+		fs::write(root.join("a.txt"), "changed").unwrap();
+		fs::write(root.join("b.txt"), "staged").unwrap();
+		stage_add_file(&repo_path, Path::new("b.txt")).unwrap();
+
+		assert_eq!(
+			move_changes_to_branch(&repo_path, "feature", false)
+				.unwrap(),
+			"refs/heads/feature"
+		);
+
+		assert_eq!(repo.head().unwrap().shorthand(), Some("feature"));
+		assert_eq!(get_head(&repo_path).unwrap(), tip);
+		assert_eq!(branch_tip(&repo, "master"), tip.get_oid());
+
+		assert_eq!(
+			fs::read_to_string(root.join("a.txt")).unwrap(),
+			"changed"
+		);
+		assert_eq!(
+			get_status(&repo_path, StatusType::Stage, None)
+				.unwrap()
+				.len(),
+			1
+		);
+		assert_eq!(
+			get_status(&repo_path, StatusType::WorkingDir, None)
+				.unwrap()
+				.len(),
+			1
+		);
+	}
+
+	#[test]
+	fn test_move_changes_carry_commits() {
+		let (_td, repo) = repo_init().unwrap();
+		let (upstream_dir, _) = repo_init_bare().unwrap();
+		let repo_path = repo_path(&repo);
+		let root = repo.workdir().unwrap();
+
+		repo.remote("origin", upstream_dir.path().to_str().unwrap())
+			.unwrap();
+		let pushed = write_commit_file(&repo, "a.txt", "a", "c1");
+		push_branch(
+			&repo_path, "origin", "master", false, false, None, None,
+		)
+		.unwrap();
+
+		write_commit_file(&repo, "a.txt", "ab", "c2");
+		let tip = write_commit_file(&repo, "a.txt", "abc", "c3");
+		fs::write(root.join("a.txt"), "abcd").unwrap();
+
+		assert_eq!(local_commits(&repo_path).unwrap(), 2);
+
+		move_changes_to_branch(&repo_path, "feature", true).unwrap();
+
+		assert_eq!(repo.head().unwrap().shorthand(), Some("feature"));
+		assert_eq!(get_head(&repo_path).unwrap(), tip);
+		assert_eq!(branch_tip(&repo, "master"), pushed.get_oid());
+		assert_eq!(
+			fs::read_to_string(root.join("a.txt")).unwrap(),
+			"abcd"
+		);
+
+		// the new branch has no upstream
+		assert_eq!(local_commits(&repo_path).unwrap(), 0);
+	}
+
+	#[test]
+	fn test_move_changes_refused() {
+		let (_td, repo) = repo_init().unwrap();
+		let repo_path = repo_path(&repo);
+
+		let tip = write_commit_file(&repo, "a.txt", "a", "c1");
+		repo.branch(
+			"existing",
+			&repo.find_commit(tip.into()).unwrap(),
+			false,
+		)
+		.unwrap();
+
+		assert!(move_changes_to_branch(
+			&repo_path, "existing", false
+		)
+		.is_err());
+		// there is no upstream to reset to
+		assert!(move_changes_to_branch(&repo_path, "feature", true)
+			.is_err());
+
+		assert_eq!(repo.head().unwrap().shorthand(), Some("master"));
+		assert!(repo
+			.find_branch("feature", git2::BranchType::Local)
+			.is_err());
+	}
+}
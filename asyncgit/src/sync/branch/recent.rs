@@ -0,0 +1,172 @@
+//! recently checked out branches (like `git checkout -`)
+
+use super::get_branch_name_repo;
+use crate::{
+	error::Result,
+	sync::{repository::repo, RepoPath},
+};
+use git2::BranchType;
+use scopetime::scope_time;
+
+const CHECKOUT_PREFIX: &str = "checkout: moving from ";
+
+/// local branches that were checked out before the current one,
+/// most recent first (at most `limit`), parsed from the reflog of `HEAD`.
+///
+/// branches that no longer exist and the current branch are skipped.
+pub fn recent_branches(
+	repo_path: &RepoPath,
+	limit: usize,
+) -> Result<Vec<String>> {
+	scope_time!("recent_branches");
+
+	let repo = repo(repo_path)?;
+	let current = get_branch_name_repo(&repo).ok();
+	let reflog = repo.reflog("HEAD")?;
+
+	let messages = reflog
+		.iter()
+		.filter_map(|entry| entry.message().map(String::from));
+
+	Ok(collect_recent(
+		messages,
+		current.as_deref(),
+		limit,
+		|name| repo.find_branch(name, BranchType::Local).is_ok(),
+	))
+}
+
+/// `(from, to)` of a reflog message written by a checkout
+fn parse_checkout_msg(msg: &str) -> Option<(&str, &str)> {
+	msg.strip_prefix(CHECKOUT_PREFIX)?.split_once(" to ")
+}
+
+/// `messages` are expected newest first, as the reflog stores them
+fn collect_recent<I, F>(
+	messages: I,
+	current: Option<&str>,
+	limit: usize,
+	exists: F,
+) -> Vec<String>
+where
+	I: IntoIterator<Item = String>,
+	F: Fn(&str) -> bool,
+{
+	let mut result: Vec<String> = Vec::new();
+
+	for msg in messages {
+		if result.len() >= limit {
+			break;
+		}
+
+		let Some((from, _)) = parse_checkout_msg(&msg) else {
+			continue;
+		};
+
+		if Some(from) == current
+			|| result.iter().any(|branch| branch == from)
+			|| !exists(from)
+		{
+			continue;
+		}
+
+		result.push(from.to_string());
+	}
+
+	result
+}
+
+#[cfg(test)]
+mod test {
+	use super::{
+		collect_recent, parse_checkout_msg, recent_branches,
+	};
+	use crate::sync::{
+		checkout_branch, create_branch, tests::repo_init, RepoPath,
+	};
+
+	fn reflog(entries: &[&str]) -> Vec<String> {
+		entries.iter().map(|e| (*e).to_string()).collect()
+	}
+
+	#[test]
+	fn test_parse_checkout_msg() {
+		assert_eq!(
+			parse_checkout_msg(
+				"checkout: moving from main to feat/a"
+			),
+			Some(("main", "feat/a"))
+		);
+		assert_eq!(parse_checkout_msg("commit: foo to bar"), None);
+		assert_eq!(
+			parse_checkout_msg("checkout: moving from main"),
+			None
+		);
+	}
+
+	#[test]
+	fn test_collect_recent() {
+		let messages = reflog(&[
+			"checkout: moving from b to a",
+			"commit: fix things",
+			"checkout: moving from c to b",
+			"checkout: moving from a to c",
+			"reset: moving to HEAD~1",
+			"checkout: moving from gone to a",
+			"checkout: moving from b to gone",
+			"checkout: moving from d to b",
+		]);
+
+		assert_eq!(
+			collect_recent(messages.clone(), Some("a"), 10, |name| {
+				name != "gone"
+			}),
+			vec!["b", "c", "d"]
+		);
+
+		assert_eq!(
+			collect_recent(messages, Some("a"), 2, |_| true),
+			vec!["b", "c"]
+		);
+	}
+
+	#[test]
+	fn test_collect_recent_detached() {
+		let messages = reflog(&[
+			"checkout: moving from main to 1a2b3c4",
+			"checkout: moving from feat to main",
+		]);
+
+		assert_eq!(
+			collect_recent(messages, None, 10, |name| {
+				name != "1a2b3c4"
+			}),
+			vec!["main", "feat"]
+		);
+	}
+
+	#[test]
+	fn test_recent_branches() {
+		let (_td, repo) = repo_init().unwrap();
+		let root = repo.path().parent().unwrap();
+		let repo_path: &RepoPath =
+			&root.as_os_str().to_str().unwrap().into();
+
+		assert!(recent_branches(repo_path, 10).unwrap().is_empty());
+
+		create_branch(repo_path, "b1").unwrap();
+		create_branch(repo_path, "b2").unwrap();
+		checkout_branch(repo_path, "master").unwrap();
+		checkout_branch(repo_path, "b1").unwrap();
+
+		assert_eq!(
+			recent_branches(repo_path, 10).unwrap(),
+			vec!["master", "b2"]
+		);
+
+		assert_eq!(
+			recent_branches(repo_path, 1).unwrap(),
+			vec!["master"]
+		);
+	}
+}
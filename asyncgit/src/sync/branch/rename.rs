@@ -24,7 +24,10 @@ pub fn rename_branch(
 
 #[cfg(test)]
 mod test {
-	use super::super::{checkout_branch, create_branch, RepoPath};
+	use super::super::{
+		checkout_branch, create_branch, get_branch_remote,
+		get_branch_upstream_merge, RepoPath,
+	};
 	use super::rename_branch;
 	use crate::sync::tests::repo_init;
 
@@ -68,4 +71,55 @@ mod test {
 			"AnotherName"
 		);
 	}
+
+	#[test]
+	fn test_rename_current_branch_keeps_upstream() {
+		let (_td, repo) = repo_init().unwrap();
+		let root = repo.path().parent().unwrap();
+		let repo_path: &RepoPath =
+			&root.as_os_str().to_str().unwrap().into();
+
+		create_branch(repo_path, "feature/tpyo").unwrap();
+		{
+			let mut config = repo.config().unwrap();
+			config
+				.set_str("branch.feature/tpyo.remote", "origin")
+				.unwrap();
+			config
+				.set_str(
+					"branch.feature/tpyo.merge",
+					"refs/heads/feature/tpyo",
+				)
+				.unwrap();
+		}
+
+		rename_branch(
+			repo_path,
+			"refs/heads/feature/tpyo",
+			"feature/typo",
+		)
+		.unwrap();
+
+		assert_eq!(
+			repo.head().unwrap().shorthand().unwrap(),
+			"feature/typo"
+		);
+		assert_eq!(
+			get_branch_remote(repo_path, "feature/typo")
+				.unwrap()
+				.as_deref(),
+			Some("origin")
+		);
+		assert_eq!(
+			get_branch_upstream_merge(repo_path, "feature/typo")
+				.unwrap()
+				.as_deref(),
+			Some("refs/heads/feature/tpyo")
+		);
+		assert!(repo
+			.config()
+			.unwrap()
+			.get_string("branch.feature/tpyo.remote")
+			.is_err());
+	}
 }
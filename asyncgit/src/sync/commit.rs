@@ -3,10 +3,17 @@ use super::{CommitId, RepoPath};
 use crate::sync::sign::{SignBuilder, SignError};
 use crate::{
 	error::{Error, Result},
-	sync::{repository::repo, utils::get_head_repo},
+	sync::{
+		audit::{self, AuditOperation},
+		hooks::{with_reference_transaction, RefUpdate},
+		merge::SQUASH_MSG,
+		repository::repo,
+		utils::{get_head_repo, head_target_ref},
+	},
 };
 use git2::{
-	message_prettify, ErrorCode, ObjectType, Repository, Signature,
+	message_prettify, ErrorCode, ObjectType, Oid, Repository,
+	Signature,
 };
 use scopetime::scope_time;
 
@@ -49,6 +56,14 @@ pub fn amend(
 		Some(&tree),
 	)?;
 
+	audit::record(
+		repo_path,
+		AuditOperation::Amend,
+		&[&audit::head_ref(&repo)],
+		Some(id),
+		Some(new_id.into()),
+	);
+
 	Ok(CommitId::new(new_id))
 }
 
@@ -89,7 +104,8 @@ pub fn commit(repo_path: &RepoPath, msg: &str) -> Result<CommitId> {
 	let tree_id = index.write_tree()?;
 	let tree = repo.find_tree(tree_id)?;
 
-	let parents = if let Ok(id) = get_head_repo(&repo) {
+	let head = get_head_repo(&repo).ok();
+	let parents = if let Some(id) = head {
 		vec![repo.find_commit(id.into())?]
 	} else {
 		Vec::new()
@@ -115,33 +131,14 @@ pub fn commit(repo_path: &RepoPath, msg: &str) -> Result<CommitId> {
 
 		let signer = SignBuilder::from_gitconfig(&repo, &config)?;
 		let (signature, signature_field) = signer.sign(&buffer)?;
-		let commit_id = repo.commit_signed(
+		repo.commit_signed(
 			commit,
 			&signature,
 			signature_field.as_deref(),
-		)?;
-
-		// manually advance to the new commit ID
-		// repo.commit does that on its own, repo.commit_signed does not
-		// if there is no head, read default branch or default to "master"
-		if let Ok(mut head) = repo.head() {
-			head.set_target(commit_id, msg)?;
-		} else {
-			let default_branch_name = config
-				.get_str("init.defaultBranch")
-				.unwrap_or("master");
-			repo.reference(
-				&format!("refs/heads/{default_branch_name}"),
-				commit_id,
-				true,
-				msg,
-			)?;
-		}
-
-		commit_id
+		)?
 	} else {
 		repo.commit(
-			Some("HEAD"),
+			None,
 			&signature,
 			&signature,
 			msg,
@@ -150,6 +147,40 @@ pub fn commit(repo_path: &RepoPath, msg: &str) -> Result<CommitId> {
 		)?
 	};
 
+	// advancing the branch ourselves (instead of `repo.commit` doing
+	// it) lets the `reference-transaction` hook see and veto it
+	let refname = head_target_ref(&repo)?;
+	let update = RefUpdate {
+		old_oid: head.map_or_else(Oid::zero, Into::into),
+		new_oid: commit_id,
+		refname: refname.clone(),
+	};
+	with_reference_transaction(repo_path, &[update], || {
+		let summary = msg.lines().next().unwrap_or_default();
+		let log_message = if head.is_some() {
+			format!("commit: {summary}")
+		} else {
+			format!("commit (initial): {summary}")
+		};
+		repo.reference(&refname, commit_id, true, &log_message)?;
+
+		Ok(())
+	})?;
+
+	// like git, a commit concludes a pending `merge --squash`
+	let squash_msg = repo.path().join(SQUASH_MSG);
+	if squash_msg.exists() {
+		std::fs::remove_file(squash_msg)?;
+	}
+
+	audit::record(
+		repo_path,
+		AuditOperation::Commit,
+		&[&audit::head_ref(&repo)],
+		head,
+		Some(commit_id.into()),
+	);
+
 	Ok(commit_id.into())
 }
 
@@ -178,6 +209,14 @@ pub fn tag_commit(
 		repo.tag_lightweight(tag, &target, false)?.into()
 	};
 
+	audit::record(
+		repo_path,
+		AuditOperation::TagCreate,
+		&[&format!("refs/tags/{tag}")],
+		None,
+		Some(*commit_id),
+	);
+
 	Ok(c)
 }
 
@@ -199,6 +238,7 @@ pub fn commit_message_prettify(
 #[cfg(test)]
 mod tests {
 	use crate::error::Result;
+	use crate::sync::merge::SQUASH_MSG;
 	use crate::sync::tags::Tag;
 	use crate::sync::RepoPath;
 	use crate::sync::{
@@ -243,6 +283,66 @@ mod tests {
 		assert_eq!(get_statuses(repo_path), (0, 0));
 	}
 
+	#[test]
+	fn test_commit_reference_transaction() {
+		let (td, repo) = repo_init().unwrap();
+		let root = repo.path().parent().unwrap();
+		let repo_path: &RepoPath =
+			&root.as_os_str().to_str().unwrap().into();
+		let log = td.path().join("transaction.log");
+
+		// logs every state with its input, rejects `prepared` while
+		// there is a `reject` file
+		let hook = format!(
+			"#!/bin/sh\necho \"$1\" >> '{log}'\ncat >> '{log}'\ntest \"$1\" != prepared || test ! -e '{reject}'\n",
+			log = log.display(),
+			reject = td.path().join("reject").display(),
+		);
+		git2_hooks::create_hook(
+			&repo,
+			git2_hooks::HOOK_REFERENCE_TRANSACTION,
+			hook.as_bytes(),
+		);
+
+		let old = get_head(repo_path).unwrap();
+		let new = commit(repo_path, "first").unwrap();
+		assert_eq!(get_head(repo_path).unwrap(), new);
+		assert_eq!(
+			std::fs::read_to_string(&log).unwrap(),
+			format!(
+				"prepared\n{old} {new} refs/heads/master\ncommitted\n{old} {new} refs/heads/master\n"
+			)
+		);
+
+		std::fs::remove_file(&log).unwrap();
+		File::create(td.path().join("reject")).unwrap();
+
+		assert!(commit(repo_path, "second").is_err());
+		// the branch is not moved
+		assert_eq!(get_head(repo_path).unwrap(), new);
+		let log = std::fs::read_to_string(&log).unwrap();
+		let states = log
+			.lines()
+			.filter(|line| !line.contains(' '))
+			.collect::<Vec<_>>();
+		assert_eq!(states, vec!["prepared", "aborted"]);
+	}
+
+	#[test]
+	fn test_commit_concludes_squash() {
+		let (_td, repo) = repo_init().unwrap();
+		let root = repo.path().parent().unwrap();
+		let repo_path: &RepoPath =
+			&root.as_os_str().to_str().unwrap().into();
+
+		let squash_msg = repo.path().join(SQUASH_MSG);
+		std::fs::write(&squash_msg, "squashed").unwrap();
+
+		commit(repo_path, "squashed").unwrap();
+
+		assert!(!squash_msg.exists());
+	}
+
 	#[test]
 	fn test_commit_in_empty_repo() {
 		let file_path = Path::new("foo");
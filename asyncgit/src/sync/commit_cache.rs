@@ -0,0 +1,234 @@
+//! least recently used cache of parsed commit metadata.
+//!
+//! commits are immutable, entries are never invalidated. every
+//! repository gets its own cache (and only the most recently used
+//! repositories are kept) so switching repositories does not mix up
+//! or leak entries.
+
+use super::{commit_details::CommitSignature, CommitId};
+use crate::{error::Result, lru::Lru};
+use git2::{Commit, Mailmap, Oid, Repository, Signature, Time};
+use std::{
+	path::PathBuf,
+	sync::{
+		atomic::{AtomicU64, AtomicUsize, Ordering},
+		Arc, Mutex, MutexGuard, OnceLock,
+	},
+};
+
+/// default amount of commits cached per repository
+pub const DEFAULT_COMMIT_CACHE_CAPACITY: usize = 4096;
+
+/// amount of repositories a cache is kept for
+const MAX_REPOSITORIES: usize = 4;
+
+static CAPACITY: AtomicUsize =
+	AtomicUsize::new(DEFAULT_COMMIT_CACHE_CAPACITY);
+static HITS: AtomicU64 = AtomicU64::new(0);
+static MISSES: AtomicU64 = AtomicU64::new(0);
+
+/// parsed metadata of a commit, signatures are stored without
+/// applying the mailmap (see [`CommitMeta::author_with_mailmap`])
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CommitMeta {
+	///
+	pub id: CommitId,
+	///
+	pub author: CommitSignature,
+	///
+	pub committer: CommitSignature,
+	/// whole message (invalid utf8 replaced)
+	pub message: String,
+	///
+	pub parents: Vec<CommitId>,
+}
+
+impl CommitMeta {
+	fn from_commit(commit: &Commit) -> Self {
+		Self {
+			id: commit.id().into(),
+			author: CommitSignature::from(&commit.author()),
+			committer: CommitSignature::from(&commit.committer()),
+			message: String::from_utf8_lossy(commit.message_bytes())
+				.into_owned(),
+			parents: commit.parent_ids().map(CommitId::new).collect(),
+		}
+	}
+
+	/// first paragraph of the message joined into one line, like
+	/// `git2::Commit::summary`
+	pub fn summary(&self) -> String {
+		self.message
+			.trim()
+			.split("\n\n")
+			.next()
+			.unwrap_or_default()
+			.lines()
+			.map(str::trim)
+			.collect::<Vec<_>>()
+			.join(" ")
+	}
+
+	///
+	pub fn author_with_mailmap(
+		&self,
+		mailmap: &Mailmap,
+	) -> CommitSignature {
+		apply_mailmap(&self.author, mailmap)
+	}
+
+	///
+	pub fn committer_with_mailmap(
+		&self,
+		mailmap: &Mailmap,
+	) -> CommitSignature {
+		apply_mailmap(&self.committer, mailmap)
+	}
+}
+
+fn apply_mailmap(
+	signature: &CommitSignature,
+	mailmap: &Mailmap,
+) -> CommitSignature {
+	// the offset does not matter, only name and email are mapped
+	let resolved = Signature::new(
+		&signature.name,
+		&signature.email,
+		&Time::new(signature.time, 0),
+	)
+	.and_then(|s| mailmap.resolve_signature(&s));
+
+	// e.g. an empty name can not be used to build a `Signature`
+	resolved.map_or_else(
+		|_| signature.clone(),
+		|resolved| CommitSignature {
+			time: signature.time,
+			..CommitSignature::from(&resolved)
+		},
+	)
+}
+
+/// hit/miss counters of the commit cache (over all repositories)
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct CommitCacheStats {
+	///
+	pub hits: u64,
+	///
+	pub misses: u64,
+}
+
+///
+pub fn commit_cache_stats() -> CommitCacheStats {
+	CommitCacheStats {
+		hits: HITS.load(Ordering::Relaxed),
+		misses: MISSES.load(Ordering::Relaxed),
+	}
+}
+
+/// sets the amount of commits cached per repository (at least one),
+/// existing caches are shrunk if needed
+pub fn set_commit_cache_capacity(capacity: usize) {
+	let capacity = capacity.max(1);
+
+	CAPACITY.store(capacity, Ordering::Relaxed);
+
+	for cache in caches().values_mut() {
+		cache.set_capacity(capacity);
+	}
+}
+
+/// metadata of commit `id`, parsed only if not cached yet
+pub fn commit_meta(
+	repo: &Repository,
+	id: CommitId,
+) -> Result<Arc<CommitMeta>> {
+	let key = repo.path().to_path_buf();
+
+	let hit = caches()
+		.get_mut(&key)
+		.and_then(|cache| cache.get_mut(&id.get_oid()))
+		.map(|meta| Arc::clone(meta));
+
+	if let Some(meta) = hit {
+		HITS.fetch_add(1, Ordering::Relaxed);
+		return Ok(meta);
+	}
+
+	MISSES.fetch_add(1, Ordering::Relaxed);
+
+	let meta = Arc::new(CommitMeta::from_commit(
+		&repo.find_commit(id.into())?,
+	));
+
+	let mut caches = caches();
+	if caches.get_mut(&key).is_none() {
+		caches.insert(
+			key.clone(),
+			Lru::new(CAPACITY.load(Ordering::Relaxed)),
+		);
+	}
+	if let Some(cache) = caches.get_mut(&key) {
+		cache.insert(id.get_oid(), Arc::clone(&meta));
+	}
+
+	Ok(meta)
+}
+
+type CommitLru = Lru<Oid, Arc<CommitMeta>>;
+
+fn caches() -> MutexGuard<'static, Lru<PathBuf, CommitLru>> {
+	static CACHES: OnceLock<Mutex<Lru<PathBuf, CommitLru>>> =
+		OnceLock::new();
+
+	CACHES
+		.get_or_init(|| Mutex::new(Lru::new(MAX_REPOSITORIES)))
+		.lock()
+		.unwrap_or_else(std::sync::PoisonError::into_inner)
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::sync::{
+		tests::{repo_init, write_commit_file},
+		utils::get_head_repo,
+	};
+
+	#[test]
+	fn test_commit_meta() {
+		let (_td, repo) = repo_init().unwrap();
+		write_commit_file(
+			&repo,
+			"a.txt",
+			"a",
+			"subject\ncontinued\n\nbody",
+		);
+		let id = get_head_repo(&repo).unwrap();
+
+		let first = commit_meta(&repo, id).unwrap();
+
+		assert_eq!(first.summary(), "subject continued");
+		assert_eq!(first.author.name, "name");
+		assert_eq!(first.parents.len(), 1);
+
+		let stats = commit_cache_stats();
+		let second = commit_meta(&repo, id).unwrap();
+
+		assert!(Arc::ptr_eq(&first, &second));
+		// other tests might run at the same time
+		assert!(commit_cache_stats().hits > stats.hits);
+	}
+
+	#[test]
+	fn test_commit_meta_per_repository() {
+		let (_td1, repo1) = repo_init().unwrap();
+		let (_td2, repo2) = repo_init().unwrap();
+		write_commit_file(&repo1, "a.txt", "only in repo1", "c1");
+
+		let id = get_head_repo(&repo1).unwrap();
+		commit_meta(&repo1, id).unwrap();
+
+		// same oid is not known in the other repository
+		assert!(commit_meta(&repo2, id).is_err());
+	}
+}
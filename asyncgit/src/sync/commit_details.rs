@@ -1,4 +1,7 @@
-use super::{commits_info::get_message, CommitId, RepoPath};
+use super::{
+	commit_cache::commit_meta, commits_info::limit_message, CommitId,
+	RepoPath,
+};
 use crate::{error::Result, sync::repository::repo};
 use git2::Signature;
 use scopetime::scope_time;
@@ -105,24 +108,6 @@ pub fn get_author_of_commit<'a>(
 	}
 }
 
-/// Get the committer of a commit.
-pub fn get_committer_of_commit<'a>(
-	commit: &'a git2::Commit<'a>,
-	mailmap: &git2::Mailmap,
-) -> git2::Signature<'a> {
-	match commit.committer_with_mailmap(mailmap) {
-		Ok(committer) => committer,
-		Err(e) => {
-			log::error!(
-				"Couldn't get committer with mailmap for {} (message: {:?}): {e}",
-				commit.id(),
-				commit.message(),
-			);
-			commit.committer()
-		}
-	}
-}
-
 ///
 pub fn get_commit_details(
 	repo_path: &RepoPath,
@@ -133,14 +118,10 @@ pub fn get_commit_details(
 	let repo = repo(repo_path)?;
 	let mailmap = repo.mailmap()?;
 
-	let commit = repo.find_commit(id.into())?;
+	let commit = commit_meta(&repo, id)?;
 
-	let author = CommitSignature::from(&get_author_of_commit(
-		&commit, &mailmap,
-	));
-	let committer = CommitSignature::from(&get_committer_of_commit(
-		&commit, &mailmap,
-	));
+	let author = commit.author_with_mailmap(&mailmap);
+	let committer = commit.committer_with_mailmap(&mailmap);
 
 	let committer = if author == committer {
 		None
@@ -148,8 +129,9 @@ pub fn get_commit_details(
 		Some(committer)
 	};
 
-	let msg =
-		CommitMessage::from(get_message(&commit, None).as_str());
+	let msg = CommitMessage::from(
+		limit_message(&commit.message, None).as_str(),
+	);
 
 	let details = CommitDetails {
 		author,
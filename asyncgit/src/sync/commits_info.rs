@@ -1,11 +1,15 @@
-use std::fmt::Display;
+use std::{fmt::Display, sync::Arc};
 
 use super::RepoPath;
 use crate::{
 	error::Result,
-	sync::{commit_details::get_author_of_commit, repository::repo},
+	sync::{
+		commit_cache::{commit_meta, CommitMeta},
+		intern::intern_name,
+		repository::repo,
+	},
 };
-use git2::{Commit, Error, Oid};
+use git2::{Mailmap, Oid};
 use scopetime::scope_time;
 use unicode_truncate::UnicodeTruncateStr;
 
@@ -91,12 +95,19 @@ pub struct CommitInfo {
 	pub message: String,
 	///
 	pub time: i64,
-	///
-	pub author: String,
+	/// interned, most commits share their author with many others
+	pub author: Arc<str>,
 	///
 	pub id: CommitId,
 }
 
+impl CommitInfo {
+	///
+	pub fn author(&self) -> &str {
+		&self.author
+	}
+}
+
 ///
 pub fn get_commits_info(
 	repo_path: &RepoPath,
@@ -108,31 +119,21 @@ pub fn get_commits_info(
 	let repo = repo(repo_path)?;
 	let mailmap = repo.mailmap()?;
 
-	let commits = ids
-		.iter()
-		.map(|id| repo.find_commit((*id).into()))
-		.collect::<std::result::Result<Vec<Commit>, Error>>()?
-		.into_iter();
-
-	let res = commits
-		.map(|c: Commit| {
-			let message = get_message(&c, Some(message_length_limit));
-			let author = get_author_of_commit(&c, &mailmap)
-				.name()
-				.map_or_else(
-					|| String::from("<unknown>"),
-					String::from,
-				);
-			CommitInfo {
-				message,
-				author,
-				time: c.time().seconds(),
-				id: CommitId(c.id()),
-			}
+	ids.iter()
+		.map(|id| {
+			let meta = commit_meta(&repo, *id)?;
+
+			Ok(CommitInfo {
+				message: limit_message(
+					&meta.message,
+					Some(message_length_limit),
+				),
+				author: author_name(&meta, &mailmap),
+				time: meta.committer.time,
+				id: meta.id,
+			})
 		})
-		.collect::<Vec<_>>();
-
-	Ok(res)
+		.collect()
 }
 
 ///
@@ -145,24 +146,32 @@ pub fn get_commit_info(
 	let repo = repo(repo_path)?;
 	let mailmap = repo.mailmap()?;
 
-	let commit = repo.find_commit((*commit_id).into())?;
-	let author = get_author_of_commit(&commit, &mailmap);
+	let meta = commit_meta(&repo, *commit_id)?;
 
 	Ok(CommitInfo {
-		message: commit.message().unwrap_or("").into(),
-		author: author.name().unwrap_or("<unknown>").into(),
-		time: commit.time().seconds(),
-		id: CommitId(commit.id()),
+		message: meta.message.clone(),
+		author: author_name(&meta, &mailmap),
+		time: meta.committer.time,
+		id: meta.id,
 	})
 }
 
+fn author_name(meta: &CommitMeta, mailmap: &Mailmap) -> Arc<str> {
+	let author = meta.author_with_mailmap(mailmap);
+
+	if author.name.is_empty() {
+		intern_name("<unknown>")
+	} else {
+		intern_name(&author.name)
+	}
+}
+
 /// if `message_limit` is set the message will be
 /// limited to the first line and truncated to fit
-pub fn get_message(
-	c: &Commit,
+pub fn limit_message(
+	msg: &str,
 	message_limit: Option<usize>,
 ) -> String {
-	let msg = String::from_utf8_lossy(c.message_bytes());
 	let msg = msg.trim();
 
 	message_limit.map_or_else(
@@ -180,11 +189,69 @@ mod tests {
 	use crate::{
 		error::Result,
 		sync::{
-			commit, stage_add_file, tests::repo_init_empty,
-			utils::get_head_repo, CommitId, RepoPath,
+			commit, stage_add_file,
+			tests::{repo_init, repo_init_empty},
+			utils::get_head_repo,
+			CommitId, RepoPath,
 		},
 	};
-	use std::{fs::File, io::Write, path::Path};
+	use git2::Signature;
+	use std::{
+		collections::HashSet, fs::File, io::Write, path::Path,
+		sync::Arc,
+	};
+
+	#[test]
+	fn test_authors_interned() {
+		const COMMITS: usize = 500;
+
+		let (_td, repo) = repo_init().unwrap();
+		let repo_path: &RepoPath =
+			&repo.workdir().unwrap().to_str().unwrap().into();
+
+		let authors = [
+			Signature::now("a rather long author name", "a@b.c")
+				.unwrap(),
+			Signature::now("another long author name", "d@e.f")
+				.unwrap(),
+		];
+
+		let tree = repo
+			.find_tree(repo.index().unwrap().write_tree().unwrap())
+			.unwrap();
+		let mut ids = Vec::with_capacity(COMMITS);
+		for i in 0..COMMITS {
+			let author = &authors[i % authors.len()];
+			let parent =
+				repo.head().unwrap().peel_to_commit().unwrap();
+			let id = repo
+				.commit(
+					Some("HEAD"),
+					author,
+					author,
+					&format!("c{i}"),
+					&tree,
+					&[&parent],
+				)
+				.unwrap();
+			ids.push(CommitId::new(id));
+		}
+
+		let infos = get_commits_info(repo_path, &ids, 50).unwrap();
+
+		let distinct: HashSet<*const u8> = infos
+			.iter()
+			.map(|info| Arc::as_ptr(&info.author).cast::<u8>())
+			.collect();
+		assert_eq!(distinct.len(), authors.len());
+
+		// heap used for author names compared to one `String` each
+		let unshared: usize =
+			infos.iter().map(|info| info.author().len()).sum();
+		let shared: usize =
+			authors.iter().map(|a| a.name().unwrap().len()).sum();
+		assert!(shared * 100 < unshared);
+	}
 
 	#[test]
 	fn test_log() -> Result<()> {
@@ -205,14 +272,14 @@ mod tests {
 
 		assert_eq!(res.len(), 2);
 		assert_eq!(res[0].message.as_str(), "commit2");
-		assert_eq!(res[0].author.as_str(), "name");
+		assert_eq!(res[0].author(), "name");
 		assert_eq!(res[1].message.as_str(), "commit1");
 
 		File::create(root.join(".mailmap"))?
 			.write_all(b"new name <newemail> <email>")?;
 		let res = get_commits_info(repo_path, &[c2], 50).unwrap();
 
-		assert_eq!(res[0].author.as_str(), "new name");
+		assert_eq!(res[0].author(), "new name");
 
 		Ok(())
 	}
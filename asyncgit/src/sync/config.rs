@@ -8,10 +8,11 @@ use super::{repository::repo, RepoPath};
 // see https://git-scm.com/docs/git-config#Documentation/git-config.txt-statusshowUntrackedFiles
 /// represents the `status.showUntrackedFiles` git config state
 #[derive(
-	Hash, Copy, Clone, PartialEq, Eq, Serialize, Deserialize,
+	Hash, Copy, Clone, PartialEq, Eq, Serialize, Deserialize, Default,
 )]
 pub enum ShowUntrackedFilesConfig {
 	///
+	#[default]
 	No,
 	///
 	Normal,
@@ -19,12 +20,6 @@ pub enum ShowUntrackedFilesConfig {
 	All,
 }
 
-impl Default for ShowUntrackedFilesConfig {
-	fn default() -> Self {
-		Self::No
-	}
-}
-
 impl ShowUntrackedFilesConfig {
 	///
 	pub const fn include_none(self) -> bool {
@@ -61,21 +56,16 @@ pub fn untracked_files_config_repo(
 
 // see https://git-scm.com/docs/git-config#Documentation/git-config.txt-pushdefault
 /// represents `push.default` git config
-#[derive(PartialEq, Eq)]
+#[derive(PartialEq, Eq, Default)]
 pub enum PushDefaultStrategyConfig {
 	Nothing,
 	Current,
 	Upstream,
+	#[default]
 	Simple,
 	Matching,
 }
 
-impl Default for PushDefaultStrategyConfig {
-	fn default() -> Self {
-		Self::Simple
-	}
-}
-
 impl<'a> TryFrom<&'a str> for PushDefaultStrategyConfig {
 	type Error = crate::Error;
 	fn try_from(
@@ -0,0 +1,224 @@
+//! conflict markers left in the staged changes by a half resolved
+//! merge, see [`staged_conflict_markers`].
+//!
+//! only the lines a commit would add are searched, which keeps this
+//! cheap enough to do before every commit.
+
+use super::{
+	repository::repo,
+	staged_summary::{
+		is_conflict_marker, matches, multivar, pathspec,
+		DEFAULT_CONFLICT_EXCLUDE,
+	},
+	RepoPath,
+};
+use crate::error::Result;
+use git2::{DiffOptions, Repository};
+use scopetime::scope_time;
+
+const CONFIG_CHECK: &str = "gitui.conflictMarkers.check";
+const CONFIG_EXCLUDE: &str = "gitui.conflictMarkers.exclude";
+
+/// what to do about staged conflict markers when committing,
+/// `gitui.conflictMarkers.check`
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum ConflictMarkerCheck {
+	/// do not look for them
+	Off,
+	/// ask before committing them
+	#[default]
+	Warn,
+	/// refuse to commit them
+	Block,
+}
+
+impl ConflictMarkerCheck {
+	/// anything unknown is [`Self::Warn`]
+	fn from_config_value(value: &str) -> Self {
+		match value.to_lowercase().as_str() {
+			"off" | "false" => Self::Off,
+			"block" => Self::Block,
+			_ => Self::Warn,
+		}
+	}
+}
+
+/// lines of a staged file adding a conflict marker
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ConflictMarkers {
+	///
+	pub path: String,
+	/// 1-based lines in the staged version of the file
+	pub lines: Vec<u32>,
+}
+
+/// reads `gitui.conflictMarkers.check` of the repo
+pub fn conflict_marker_check(
+	repo_path: &RepoPath,
+) -> Result<ConflictMarkerCheck> {
+	let config = repo(repo_path)?.config()?;
+
+	Ok(config
+		.get_string(CONFIG_CHECK)
+		.map(|value| ConflictMarkerCheck::from_config_value(&value))
+		.unwrap_or_default())
+}
+
+/// the conflict markers the staged changes add compared to `HEAD`,
+/// leaving out files matching `gitui.conflictMarkers.exclude` (which
+/// are lockfiles by default)
+pub fn staged_conflict_markers(
+	repo_path: &RepoPath,
+) -> Result<Vec<ConflictMarkers>> {
+	scope_time!("staged_conflict_markers");
+
+	let repo = repo(repo_path)?;
+
+	find_markers(&repo)
+}
+
+pub fn find_markers(
+	repo: &Repository,
+) -> Result<Vec<ConflictMarkers>> {
+	let config = repo.config()?;
+	let exclude = pathspec(
+		&multivar(&config, CONFIG_EXCLUDE)?.unwrap_or_else(|| {
+			DEFAULT_CONFLICT_EXCLUDE
+				.iter()
+				.map(ToString::to_string)
+				.collect()
+		}),
+	)?;
+
+	let head =
+		repo.head().ok().and_then(|head| head.peel_to_tree().ok());
+	let diff = repo.diff_tree_to_index(
+		head.as_ref(),
+		None,
+		Some(
+			DiffOptions::new()
+				.context_lines(0)
+				.ignore_submodules(true),
+		),
+	)?;
+
+	let mut found: Vec<ConflictMarkers> = Vec::new();
+
+	diff.foreach(
+		&mut |_, _| true,
+		None,
+		None,
+		Some(&mut |delta, _, line| {
+			if line.origin() != '+'
+				|| !is_conflict_marker(line.content())
+			{
+				return true;
+			}
+
+			let (Some(path), Some(number)) =
+				(delta.new_file().path(), line.new_lineno())
+			else {
+				return true;
+			};
+
+			if !matches(exclude.as_ref(), path) {
+				let path = path.to_string_lossy();
+				match found.last_mut() {
+					Some(markers) if markers.path == path => {
+						markers.lines.push(number);
+					}
+					_ => found.push(ConflictMarkers {
+						path: path.to_string(),
+						lines: vec![number],
+					}),
+				}
+			}
+
+			true
+		}),
+	)?;
+
+	Ok(found)
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::sync::{
+		stage_add_file,
+		tests::{repo_init, write_commit_file},
+	};
+	use pretty_assertions::assert_eq;
+	use std::{fs, path::Path};
+
+	#[test]
+	fn test_check_config() {
+		let (_td, repo) = repo_init().unwrap();
+		let root = repo.workdir().unwrap();
+		let repo_path: &RepoPath = &root.to_str().unwrap().into();
+		let mut config = repo.config().unwrap();
+
+		assert_eq!(
+			conflict_marker_check(repo_path).unwrap(),
+			ConflictMarkerCheck::Warn
+		);
+
+		for (value, check) in [
+			("off", ConflictMarkerCheck::Off),
+			("Block", ConflictMarkerCheck::Block),
+			("warn", ConflictMarkerCheck::Warn),
+			("whatever", ConflictMarkerCheck::Warn),
+		] {
+			config.set_str(CONFIG_CHECK, value).unwrap();
+			assert_eq!(
+				conflict_marker_check(repo_path).unwrap(),
+				check
+			);
+		}
+	}
+
+	#[test]
+	fn test_staged_conflict_markers() {
+		let (_td, repo) = repo_init().unwrap();
+		let root = repo.workdir().unwrap();
+		let repo_path: &RepoPath = &root.to_str().unwrap().into();
+
+		// committed markers are not reported again
+		write_commit_file(&repo, "a.txt", "a\n=======\nb\n", "c1");
+
+		for (file, content) in [
+			(
+				"a.txt",
+				"a\n=======\nb\n<<<<<<< HEAD\nc\n=======\nd\n>>>>>>> f\n",
+			),
+			("b.lock", "<<<<<<< HEAD\n"),
+			("c.txt", "a <<<<<<< HEAD\n"),
+		] {
+			fs::write(root.join(file), content).unwrap();
+			stage_add_file(repo_path, Path::new(file)).unwrap();
+		}
+		// not staged
+		fs::write(root.join("d.txt"), "<<<<<<< HEAD\n").unwrap();
+
+		assert_eq!(
+			staged_conflict_markers(repo_path).unwrap(),
+			vec![ConflictMarkers {
+				path: String::from("a.txt"),
+				lines: vec![4, 6, 8],
+			}]
+		);
+
+		repo.config()
+			.unwrap()
+			.set_str(CONFIG_EXCLUDE, "a.*")
+			.unwrap();
+
+		assert_eq!(
+			staged_conflict_markers(repo_path).unwrap(),
+			vec![ConflictMarkers {
+				path: String::from("b.lock"),
+				lines: vec![1],
+			}]
+		);
+	}
+}
@@ -4,6 +4,7 @@ use super::{
 	commit_files::{
 		get_commit_diff, get_compare_commits_diff, OldNew,
 	},
+	index_cache::index_snapshot,
 	utils::{get_head_repo, work_dir},
 	CommitId, RepoPath,
 };
@@ -15,16 +16,21 @@ use crate::{
 };
 use easy_cast::Conv;
 use git2::{
-	Delta, Diff, DiffDelta, DiffFormat, DiffHunk, Patch, Repository,
+	Delta, Diff, DiffDelta, DiffFormat, DiffHunk, ErrorCode,
+	ObjectType, Oid, Patch, Repository, Tree, TreeEntry,
 };
 use scopetime::scope_time;
 use serde::{Deserialize, Serialize};
-use std::{cell::RefCell, fs, path::Path, rc::Rc};
+use std::{
+	fs,
+	path::{Path, PathBuf},
+};
 
 /// type of diff of a single line
-#[derive(Copy, Clone, PartialEq, Eq, Hash, Debug)]
+#[derive(Copy, Clone, PartialEq, Eq, Hash, Debug, Default)]
 pub enum DiffLineType {
 	/// just surrounding line, no change
+	#[default]
 	None,
 	/// header of the hunk
 	Header,
@@ -47,12 +53,6 @@ impl From<git2::DiffLineType> for DiffLineType {
 	}
 }
 
-impl Default for DiffLineType {
-	fn default() -> Self {
-		Self::None
-	}
-}
-
 ///
 #[derive(Default, Clone, Hash, Debug)]
 pub struct DiffLine {
@@ -173,6 +173,9 @@ pub(crate) fn get_diff_raw<'a>(
 	opt.pathspec(p);
 	opt.reverse(reverse);
 
+	let snapshot = index_snapshot(repo)?;
+	let index = snapshot.index();
+
 	let diff = if stage {
 		// diff against head
 		if let Ok(id) = get_head_repo(repo) {
@@ -181,20 +184,20 @@ pub(crate) fn get_diff_raw<'a>(
 			let tree = parent.tree()?;
 			repo.diff_tree_to_index(
 				Some(&tree),
-				Some(&repo.index()?),
+				Some(index),
 				Some(&mut opt),
 			)?
 		} else {
 			repo.diff_tree_to_index(
 				None,
-				Some(&repo.index()?),
+				Some(index),
 				Some(&mut opt),
 			)?
 		}
 	} else {
 		opt.include_untracked(true);
 		opt.recurse_untracked_dirs(true);
-		repo.diff_index_to_workdir(None, Some(&mut opt))?
+		repo.diff_index_to_workdir(Some(index), Some(&mut opt))?
 	};
 
 	Ok(diff)
@@ -256,144 +259,280 @@ pub fn get_diff_commits(
 	raw_diff_to_file_diff(&diff, work_dir)
 }
 
-///
-//TODO: refactor into helper type with the inline closures as dedicated functions
-#[allow(clippy::too_many_lines)]
-fn raw_diff_to_file_diff(
-	diff: &Diff,
-	work_dir: &Path,
+/// one version of a file in a [`DiffRequest`]
+#[derive(Debug, Hash, Clone, Copy, PartialEq, Eq)]
+pub struct BlobId {
+	id: Oid,
+	/// the file is a submodule, `id` is the commit it points to
+	submodule: bool,
+}
+
+impl BlobId {
+	fn from_entry(entry: &TreeEntry) -> Option<Self> {
+		match entry.kind()? {
+			ObjectType::Blob => Some(Self {
+				id: entry.id(),
+				submodule: false,
+			}),
+			ObjectType::Commit => Some(Self {
+				id: entry.id(),
+				submodule: true,
+			}),
+			_ => None,
+		}
+	}
+}
+
+/// a diff of two versions of a single file, no matter if they come from
+/// a commit, a stash or two commits that are compared
+#[derive(Debug, Hash, Clone, PartialEq, Eq)]
+pub struct DiffRequest {
+	/// path of the file on both sides
+	pub path: String,
+	/// `None` if the file is added
+	pub old_blob: Option<BlobId>,
+	/// `None` if the file is deleted
+	pub new_blob: Option<BlobId>,
+	/// context lines and whitespace handling
+	pub options: DiffOptions,
+}
+
+/// version of `path` in `tree`, `None` if it does not exist there
+fn tree_blob(tree: &Tree, path: &Path) -> Result<Option<BlobId>> {
+	match tree.get_path(path) {
+		Ok(entry) => Ok(BlobId::from_entry(&entry)),
+		Err(e) if e.code() == ErrorCode::NotFound => Ok(None),
+		Err(e) => Err(e.into()),
+	}
+}
+
+/// [`DiffRequest`] of `path` in commit `id` against its first parent.
+/// see `get_commit_diff`, untracked files of a stash are taken from the
+/// commit they are stashed in.
+pub fn diff_request_commit(
+	repo_path: &RepoPath,
+	id: CommitId,
+	path: &str,
+	options: DiffOptions,
+) -> Result<DiffRequest> {
+	let repo = repo(repo_path)?;
+	let commit = repo.find_commit(id.into())?;
+	let file = Path::new(path);
+
+	let old_blob = match commit.parents().next() {
+		Some(parent) => tree_blob(&parent.tree()?, file)?,
+		None => None,
+	};
+
+	let mut new_blob = tree_blob(&commit.tree()?, file)?;
+	if new_blob.is_none() && get_stashes(repo_path)?.contains(&id) {
+		if let Ok(untracked) = commit.parent(2) {
+			new_blob = tree_blob(&untracked.tree()?, file)?;
+		}
+	}
+
+	Ok(DiffRequest {
+		path: path.to_string(),
+		old_blob,
+		new_blob,
+		options,
+	})
+}
+
+/// [`DiffRequest`] of `path` between two commits
+pub fn diff_request_commits(
+	repo_path: &RepoPath,
+	ids: OldNew<CommitId>,
+	path: &str,
+	options: DiffOptions,
+) -> Result<DiffRequest> {
+	let repo = repo(repo_path)?;
+	let file = Path::new(path);
+
+	let old_tree = repo.find_commit(ids.old.into())?.tree()?;
+	let new_tree = repo.find_commit(ids.new.into())?.tree()?;
+
+	Ok(DiffRequest {
+		path: path.to_string(),
+		old_blob: tree_blob(&old_tree, file)?,
+		new_blob: tree_blob(&new_tree, file)?,
+		options,
+	})
+}
+
+fn blob_content(
+	repo: &Repository,
+	blob: Option<BlobId>,
+) -> Result<Vec<u8>> {
+	Ok(match blob {
+		None => Vec::new(),
+		// what git shows for submodules
+		Some(BlobId {
+			id,
+			submodule: true,
+		}) => format!("Subproject commit {id}\n").into_bytes(),
+		Some(BlobId { id, .. }) => {
+			repo.find_blob(id)?.content().to_vec()
+		}
+	})
+}
+
+/// returns the diff of the two file versions of `request`
+pub fn get_diff_blobs(
+	repo_path: &RepoPath,
+	request: &DiffRequest,
 ) -> Result<FileDiff> {
-	let res = Rc::new(RefCell::new(FileDiff::default()));
-	{
-		let mut current_lines = Vec::new();
-		let mut current_hunk: Option<HunkHeader> = None;
-
-		let res_cell = Rc::clone(&res);
-		let adder = move |header: &HunkHeader,
-		                  lines: &Vec<DiffLine>| {
-			let mut res = res_cell.borrow_mut();
-			res.hunks.push(Hunk {
-				header_hash: hash(header),
-				lines: lines.clone(),
-			});
-			res.lines += lines.len();
-		};
-
-		let res_cell = Rc::clone(&res);
-		let mut put = |delta: DiffDelta,
-		               hunk: Option<DiffHunk>,
-		               line: git2::DiffLine| {
-			{
-				let mut res = res_cell.borrow_mut();
-				res.sizes = (
-					delta.old_file().size(),
-					delta.new_file().size(),
-				);
-				//TODO: use try_conv
-				res.size_delta = (i64::conv(res.sizes.1))
-					.saturating_sub(i64::conv(res.sizes.0));
-			}
-			if let Some(hunk) = hunk {
-				let hunk_header = HunkHeader::from(hunk);
-
-				match current_hunk {
-					None => current_hunk = Some(hunk_header),
-					Some(h) => {
-						if h != hunk_header {
-							adder(&h, &current_lines);
-							current_lines.clear();
-							current_hunk = Some(hunk_header);
-						}
-					}
-				}
+	scope_time!("get_diff_blobs");
 
-				let diff_line = DiffLine {
-					position: DiffLinePosition::from(&line),
-					content: String::from_utf8_lossy(line.content())
-						//Note: trim await trailing newline characters
-						.trim_matches(is_newline)
-						.into(),
-					line_type: line.origin_value().into(),
-				};
+	let repo = repo(repo_path)?;
+	let old = blob_content(&repo, request.old_blob)?;
+	let new = blob_content(&repo, request.new_blob)?;
+	let file = Path::new(&request.path);
 
-				current_lines.push(diff_line);
-			}
-		};
-
-		let new_file_diff = if diff.deltas().len() == 1 {
-			if let Some(delta) = diff.deltas().next() {
-				if delta.status() == Delta::Untracked {
-					let relative_path =
-						delta.new_file().path().ok_or_else(|| {
-							Error::Generic(
-								"new file path is unspecified."
-									.to_string(),
-							)
-						})?;
-
-					let newfile_path = work_dir.join(relative_path);
-
-					if let Some(newfile_content) =
-						new_file_content(&newfile_path)
-					{
-						let mut patch = Patch::from_buffers(
-							&[],
-							None,
-							newfile_content.as_slice(),
-							Some(&newfile_path),
-							None,
-						)?;
-
-						patch.print(
-							&mut |delta,
-							      hunk: Option<DiffHunk>,
-							      line: git2::DiffLine| {
-								put(delta, hunk, line);
-								true
-							},
-						)?;
-
-						true
-					} else {
-						false
+	let mut opt = git2::DiffOptions::new();
+	opt.context_lines(request.options.context);
+	opt.ignore_whitespace(request.options.ignore_whitespace);
+	opt.interhunk_lines(request.options.interhunk_lines);
+
+	let mut patch = Patch::from_buffers(
+		&old,
+		Some(file),
+		&new,
+		Some(file),
+		Some(&mut opt),
+	)?;
+
+	let mut builder = FileDiffBuilder::default();
+	patch.print(&mut |delta, hunk, line| {
+		builder.put(&delta, hunk, &line);
+		true
+	})?;
+
+	builder.finish()
+}
+
+/// collects the lines a [`Diff`] or [`Patch`] of a single file prints
+#[derive(Default)]
+struct FileDiffBuilder {
+	res: FileDiff,
+	current_hunk: Option<HunkHeader>,
+	current_lines: Vec<DiffLine>,
+}
+
+impl FileDiffBuilder {
+	fn put(
+		&mut self,
+		delta: &DiffDelta,
+		hunk: Option<DiffHunk>,
+		line: &git2::DiffLine,
+	) {
+		self.res.sizes =
+			(delta.old_file().size(), delta.new_file().size());
+		//TODO: use try_conv
+		self.res.size_delta = (i64::conv(self.res.sizes.1))
+			.saturating_sub(i64::conv(self.res.sizes.0));
+
+		if let Some(hunk) = hunk {
+			let hunk_header = HunkHeader::from(hunk);
+
+			match self.current_hunk {
+				None => self.current_hunk = Some(hunk_header),
+				Some(h) => {
+					if h != hunk_header {
+						self.add_hunk(&h);
+						self.current_hunk = Some(hunk_header);
 					}
-				} else {
-					false
 				}
-			} else {
-				false
 			}
-		} else {
-			false
-		};
-
-		if !new_file_diff {
-			diff.print(
-				DiffFormat::Patch,
-				move |delta, hunk, line: git2::DiffLine| {
-					put(delta, hunk, line);
-					true
-				},
-			)?;
-		}
 
-		if !current_lines.is_empty() {
-			adder(
-				&current_hunk.map_or_else(
-					|| Err(Error::Generic("invalid hunk".to_owned())),
-					Ok,
-				)?,
-				&current_lines,
-			);
+			self.current_lines.push(DiffLine {
+				position: DiffLinePosition::from(line),
+				content: String::from_utf8_lossy(line.content())
+					//Note: trim await trailing newline characters
+					.trim_matches(is_newline)
+					.into(),
+				line_type: line.origin_value().into(),
+			});
 		}
+	}
+
+	fn add_hunk(&mut self, header: &HunkHeader) {
+		let lines = std::mem::take(&mut self.current_lines);
+
+		self.res.lines += lines.len();
+		self.res.hunks.push(Hunk {
+			header_hash: hash(header),
+			lines,
+		});
+	}
 
-		if new_file_diff {
-			res.borrow_mut().untracked = true;
+	fn finish(mut self) -> Result<FileDiff> {
+		if !self.current_lines.is_empty() {
+			let header = self.current_hunk.ok_or_else(|| {
+				Error::Generic("invalid hunk".to_owned())
+			})?;
+			self.add_hunk(&header);
 		}
+
+		Ok(self.res)
+	}
+}
+
+/// content of the file if `diff` consists of a single untracked one,
+/// which has to be diffed against nothing to get any hunks
+fn untracked_file(
+	diff: &Diff,
+	work_dir: &Path,
+) -> Result<Option<(PathBuf, Vec<u8>)>> {
+	if diff.deltas().len() != 1 {
+		return Ok(None);
+	}
+	let Some(delta) = diff.deltas().next() else {
+		return Ok(None);
+	};
+	if delta.status() != Delta::Untracked {
+		return Ok(None);
+	}
+
+	let relative_path = delta.new_file().path().ok_or_else(|| {
+		Error::Generic("new file path is unspecified.".to_string())
+	})?;
+	let path = work_dir.join(relative_path);
+
+	Ok(new_file_content(&path).map(|content| (path, content)))
+}
+
+fn raw_diff_to_file_diff(
+	diff: &Diff,
+	work_dir: &Path,
+) -> Result<FileDiff> {
+	let mut builder = FileDiffBuilder::default();
+
+	if let Some((path, content)) = untracked_file(diff, work_dir)? {
+		let mut patch = Patch::from_buffers(
+			&[],
+			None,
+			content.as_slice(),
+			Some(&path),
+			None,
+		)?;
+
+		patch.print(&mut |delta, hunk, line| {
+			builder.put(&delta, hunk, &line);
+			true
+		})?;
+
+		let mut res = builder.finish()?;
+		res.untracked = true;
+		return Ok(res);
 	}
-	let res = Rc::try_unwrap(res)
-		.map_err(|_| Error::Generic("rc unwrap error".to_owned()))?;
-	Ok(res.into_inner())
+
+	diff.print(DiffFormat::Patch, |delta, hunk, line| {
+		builder.put(&delta, hunk, &line);
+		true
+	})?;
+
+	builder.finish()
 }
 
 const fn is_newline(c: char) -> bool {
@@ -420,13 +559,22 @@ fn new_file_content(path: &Path) -> Option<Vec<u8>> {
 
 #[cfg(test)]
 mod tests {
-	use super::{get_diff, get_diff_commit};
+	use super::{
+		diff_request_commit, diff_request_commits, get_diff,
+		get_diff_blobs, get_diff_commit, DiffOptions,
+	};
 	use crate::{
 		error::Result,
+		hash,
 		sync::{
-			commit, stage_add_file,
+			commit,
+			commit_files::OldNew,
+			stage_add_file, stash_save,
 			status::{get_status, StatusType},
-			tests::{get_statuses, repo_init, repo_init_empty},
+			tests::{
+				get_statuses, repo_init, repo_init_empty,
+				write_commit_file,
+			},
 			RepoPath,
 		},
 	};
@@ -671,4 +819,111 @@ mod tests {
 
 		Ok(())
 	}
+
+	#[test]
+	fn test_diff_request_commit() {
+		let (_td, repo) = repo_init_empty().unwrap();
+		let root = repo.path().parent().unwrap();
+		let repo_path: &RepoPath =
+			&root.as_os_str().to_str().unwrap().into();
+
+		write_commit_file(&repo, "a.txt", "a\nb\nc\n", "c1");
+		let id = write_commit_file(&repo, "a.txt", "a\nx\nc\n", "c2");
+
+		let request = diff_request_commit(
+			repo_path,
+			id,
+			"a.txt",
+			DiffOptions::default(),
+		)
+		.unwrap();
+		assert!(request.old_blob.is_some());
+		assert!(request.new_blob.is_some());
+
+		let diff = get_diff_blobs(repo_path, &request).unwrap();
+		let lines = diff.hunks[0]
+			.lines
+			.iter()
+			.map(|line| line.content.as_ref())
+			.collect::<Vec<_>>();
+		assert_eq!(lines[1..], ["a", "b", "x", "c"]);
+
+		// the same as diffing the trees
+		assert_eq!(
+			hash(&diff),
+			hash(
+				&get_diff_commit(
+					repo_path,
+					id,
+					String::from("a.txt"),
+					None
+				)
+				.unwrap()
+			)
+		);
+	}
+
+	#[test]
+	fn test_diff_request_added_and_deleted() {
+		let (_td, repo) = repo_init_empty().unwrap();
+		let root = repo.path().parent().unwrap();
+		let repo_path: &RepoPath =
+			&root.as_os_str().to_str().unwrap().into();
+
+		let c1 = write_commit_file(&repo, "a.txt", "a\n", "c1");
+		let c2 = write_commit_file(&repo, "b.txt", "b\n", "c2");
+
+		let added = diff_request_commit(
+			repo_path,
+			c1,
+			"a.txt",
+			DiffOptions::default(),
+		)
+		.unwrap();
+		assert_eq!(added.old_blob, None);
+		assert_eq!(
+			get_diff_blobs(repo_path, &added).unwrap().hunks[0].lines
+				[1]
+			.content
+			.as_ref(),
+			"a"
+		);
+
+		let deleted = diff_request_commits(
+			repo_path,
+			OldNew { old: c2, new: c1 },
+			"b.txt",
+			DiffOptions::default(),
+		)
+		.unwrap();
+		assert_eq!(deleted.new_blob, None);
+		assert_eq!(
+			get_diff_blobs(repo_path, &deleted).unwrap().lines,
+			2
+		);
+	}
+
+	#[test]
+	fn test_diff_request_stash_untracked() {
+		let (_td, repo) = repo_init().unwrap();
+		let root = repo.path().parent().unwrap();
+		let repo_path: &RepoPath =
+			&root.as_os_str().to_str().unwrap().into();
+
+		fs::write(root.join("new.txt"), "untracked\n").unwrap();
+
+		let stash = stash_save(repo_path, None, true, false).unwrap();
+
+		let request = diff_request_commit(
+			repo_path,
+			stash,
+			"new.txt",
+			DiffOptions::default(),
+		)
+		.unwrap();
+		assert!(request.new_blob.is_some());
+
+		let diff = get_diff_blobs(repo_path, &request).unwrap();
+		assert_eq!(&*diff.hunks[0].lines[1].content, "untracked");
+	}
 }
@@ -0,0 +1,227 @@
+//! dropping a single commit from the checked out branch
+
+use super::{
+	rebase::{run_rebase, RebaseState},
+	repo,
+	squash::{first_parent_range, is_on_upstream},
+	CommitId, RepoPath,
+};
+use crate::error::{Error, Result};
+use git2::{Commit, Repository, RepositoryState, StatusOptions};
+use scopetime::scope_time;
+
+/// what dropping a commit rewrites, to be confirmed before calling
+/// [`drop_commit`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DropCommitInfo {
+	/// commits on top of the dropped one that are replayed
+	pub descendants: usize,
+	/// the commit is already on the upstream of the branch
+	pub pushed: bool,
+}
+
+/// `commit` and the commits on top of it up to `HEAD`, fails for merges
+/// and the root commit
+fn droppable(
+	repo: &Repository,
+	commit: CommitId,
+) -> Result<(Commit<'_>, Vec<Commit<'_>>)> {
+	let (mut range, above) =
+		first_parent_range(repo, commit, commit)?;
+	let commit = range.pop().ok_or(Error::NoParent)?;
+
+	if commit.parent_count() == 0 {
+		return Err(Error::NoParent);
+	}
+
+	Ok((commit, above))
+}
+
+/// checks that `commit` can be dropped and tells what it rewrites
+pub fn drop_commit_info(
+	repo_path: &RepoPath,
+	commit: CommitId,
+) -> Result<DropCommitInfo> {
+	let repo = repo(repo_path)?;
+	let (_, above) = droppable(&repo, commit)?;
+
+	Ok(DropCommitInfo {
+		descendants: above.len(),
+		pushed: is_on_upstream(&repo, commit.get_oid())?,
+	})
+}
+
+/// removes `commit` from the checked out branch by rebasing the commits
+/// on top of it onto its parent.
+///
+/// refuses merges in that range like [`super::squash_range`] does. if a
+/// later commit depended on the dropped one the rebase stops with
+/// conflicts, to be continued or aborted like any other rebase.
+pub fn drop_commit(
+	repo_path: &RepoPath,
+	commit: CommitId,
+) -> Result<RebaseState> {
+	scope_time!("drop_commit");
+
+	let repo = repo(repo_path)?;
+
+	if repo.state() != RepositoryState::Clean {
+		return Err(Error::Generic(String::from(
+			"cannot drop a commit while repo is not in a clean state",
+		)));
+	}
+	if !repo.head()?.is_branch() {
+		return Err(Error::NoBranch);
+	}
+	if !repo
+		.statuses(Some(
+			StatusOptions::new()
+				.include_ignored(false)
+				.include_untracked(false),
+		))?
+		.is_empty()
+	{
+		return Err(Error::UncommittedChanges);
+	}
+
+	let (dropped, _) = droppable(&repo, commit)?;
+
+	let branch = repo.reference_to_annotated_commit(&repo.head()?)?;
+	let upstream = repo.find_annotated_commit(dropped.id())?;
+	let onto = repo.find_annotated_commit(dropped.parent_id(0)?)?;
+
+	let rebase = repo.rebase(
+		Some(&branch),
+		Some(&upstream),
+		Some(&onto),
+		None,
+	)?;
+
+	run_rebase(&repo, rebase)
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::sync::{
+		get_commit_info, repo_state,
+		tests::{repo_init_empty, write_commit_file},
+		utils::get_head,
+		LogWalker, RepoState,
+	};
+	use pretty_assertions::assert_eq;
+
+	fn repo_path(repo: &Repository) -> RepoPath {
+		repo.workdir().unwrap().to_str().unwrap().into()
+	}
+
+	fn messages(
+		repo_path: &RepoPath,
+		repo: &Repository,
+	) -> Vec<String> {
+		let mut items = Vec::new();
+		LogWalker::new(repo, 10).unwrap().read(&mut items).unwrap();
+		items
+			.iter()
+			.map(|id| get_commit_info(repo_path, id).unwrap().message)
+			.collect()
+	}
+
+	#[test]
+	fn test_drop_commit() {
+		let (_td, repo) = repo_init_empty().unwrap();
+		let repo_path = repo_path(&repo);
+
+		write_commit_file(&repo, "a.txt", "a", "c1");
+		let c2 = write_commit_file(&repo, "b.txt", "b", "c2");
+		write_commit_file(&repo, "c.txt", "c", "c3");
+		write_commit_file(&repo, "a.txt", "aa", "c4");
+
+		assert_eq!(
+			drop_commit_info(&repo_path, c2).unwrap(),
+			DropCommitInfo {
+				descendants: 2,
+				pushed: false
+			}
+		);
+
+		assert_eq!(
+			drop_commit(&repo_path, c2).unwrap(),
+			RebaseState::Finished
+		);
+
+		assert_eq!(
+			messages(&repo_path, &repo),
+			vec!["c4", "c3", "c1"]
+		);
+		assert!(!repo.workdir().unwrap().join("b.txt").exists());
+		assert_eq!(repo_state(&repo_path).unwrap(), RepoState::Clean);
+	}
+
+	#[test]
+	fn test_drop_head() {
+		let (_td, repo) = repo_init_empty().unwrap();
+		let repo_path = repo_path(&repo);
+
+		let c1 = write_commit_file(&repo, "a.txt", "a", "c1");
+		let c2 = write_commit_file(&repo, "a.txt", "ab", "c2");
+
+		assert_eq!(
+			drop_commit_info(&repo_path, c2).unwrap().descendants,
+			0
+		);
+		drop_commit(&repo_path, c2).unwrap();
+
+		assert_eq!(get_head(&repo_path).unwrap(), c1);
+		assert_eq!(
+			std::fs::read_to_string(
+				repo.workdir().unwrap().join("a.txt")
+			)
+			.unwrap(),
+			"a"
+		);
+	}
+
+	#[test]
+	fn test_drop_commit_conflict() {
+		let (_td, repo) = repo_init_empty().unwrap();
+		let repo_path = repo_path(&repo);
+
+		write_commit_file(&repo, "a.txt", "a", "c1");
+		let c2 = write_commit_file(&repo, "a.txt", "ab", "c2");
+		write_commit_file(&repo, "a.txt", "abc", "c3");
+
+		// c3 changes what c2 introduced
+		assert_eq!(
+			drop_commit(&repo_path, c2).unwrap(),
+			RebaseState::Conflicted
+		);
+		assert_eq!(
+			repo_state(&repo_path).unwrap(),
+			RepoState::Rebase
+		);
+	}
+
+	#[test]
+	fn test_drop_commit_refused() {
+		let (_td, repo) = repo_init_empty().unwrap();
+		let repo_path = repo_path(&repo);
+
+		let c1 = write_commit_file(&repo, "a.txt", "a", "c1");
+		let c2 = write_commit_file(&repo, "a.txt", "ab", "c2");
+
+		// the root commit
+		assert!(drop_commit_info(&repo_path, c1).is_err());
+		assert!(drop_commit(&repo_path, c1).is_err());
+
+		// uncommitted changes
+		std::fs::write(repo.workdir().unwrap().join("a.txt"), "x")
+			.unwrap();
+		assert!(matches!(
+			drop_commit(&repo_path, c2),
+			Err(Error::UncommittedChanges)
+		));
+
+		assert_eq!(get_head(&repo_path).unwrap(), c2);
+	}
+}
@@ -0,0 +1,278 @@
+//! support for `filter.<driver>.clean` commands configured via
+//! the `filter` attribute, libgit2 only applies its builtin filters
+//! (`crlf`, `ident`) and ignores external drivers.
+
+use super::{
+	index_cache::invalidate_index_cache_repo, utils::work_dir,
+};
+use crate::error::{Error, Result};
+use easy_cast::Conv;
+use git2::{AttrCheckFlags, AttrValue, Repository};
+use std::{
+	io::Write,
+	path::Path,
+	process::{Command, Stdio},
+	thread,
+};
+
+struct CleanFilter {
+	command: String,
+	required: bool,
+}
+
+fn clean_filter(
+	repo: &Repository,
+	file_path: &str,
+) -> Result<Option<CleanFilter>> {
+	let attr = repo.get_attr(
+		Path::new(file_path),
+		"filter",
+		AttrCheckFlags::FILE_THEN_INDEX,
+	)?;
+
+	let AttrValue::String(driver) = AttrValue::from_string(attr)
+	else {
+		return Ok(None);
+	};
+
+	let config = repo.config()?;
+
+	let Ok(command) =
+		config.get_string(&format!("filter.{driver}.clean"))
+	else {
+		return Ok(None);
+	};
+
+	let required = config
+		.get_bool(&format!("filter.{driver}.required"))
+		.unwrap_or_default();
+
+	Ok(Some(CleanFilter { command, required }))
+}
+
+/// runs the clean filter configured for `file_path` (if any) on
+/// `content`, just like `git add` would do.
+///
+/// a failing filter leaves `content` untouched unless it is marked as
+/// `required` (see `gitattributes(5)`).
+pub fn apply_clean_filter(
+	repo: &Repository,
+	file_path: &str,
+	content: Vec<u8>,
+) -> Result<Vec<u8>> {
+	let Some(filter) = clean_filter(repo, file_path)? else {
+		return Ok(content);
+	};
+
+	match run_filter(repo, file_path, &filter.command, &content) {
+		Ok(cleaned) => Ok(cleaned),
+		Err(e) if filter.required => Err(e),
+		Err(e) => {
+			log::warn!("clean filter failed for {file_path}: {e}");
+			Ok(content)
+		}
+	}
+}
+
+/// re-writes the index entry of `file_path` through its clean filter,
+/// used after applying worktree hunks to the index
+pub fn clean_index_entry(
+	repo: &Repository,
+	file_path: &str,
+) -> Result<()> {
+	if clean_filter(repo, file_path)?.is_none() {
+		return Ok(());
+	}
+
+	let mut index = repo.index()?;
+	index.read(true)?;
+
+	let Some(mut entry) = index.get_path(Path::new(file_path), 0)
+	else {
+		return Ok(());
+	};
+
+	let content = repo.find_blob(entry.id)?.content().to_vec();
+	let cleaned = apply_clean_filter(repo, file_path, content)?;
+
+	entry.id = repo.blob(&cleaned)?;
+	entry.file_size = u32::try_conv(cleaned.len())?;
+	index.add(&entry)?;
+	index.write()?;
+	invalidate_index_cache_repo(repo);
+
+	Ok(())
+}
+
+fn run_filter(
+	repo: &Repository,
+	file_path: &str,
+	command: &str,
+	content: &[u8],
+) -> Result<Vec<u8>> {
+	// `%f` is replaced by the (quoted) path of the file
+	let quoted_path =
+		format!("'{}'", file_path.replace('\'', "'\\''"));
+	let command = command.replace("%f", &quoted_path);
+
+	let mut child = Command::new("sh")
+		.arg("-c")
+		.arg(&command)
+		.current_dir(work_dir(repo)?)
+		.stdin(Stdio::piped())
+		.stdout(Stdio::piped())
+		.stderr(Stdio::piped())
+		.spawn()?;
+
+	let mut stdin = child.stdin.take().ok_or_else(|| {
+		Error::Generic(String::from("clean filter: no stdin"))
+	})?;
+
+	// feed stdin from a separate thread, the filter might not read
+	// everything before writing to a (full) stdout pipe
+	let output = thread::scope(|s| {
+		let writer = s.spawn(move || stdin.write_all(content));
+		let output = child.wait_with_output();
+		// a filter not reading its input is not an error on its own
+		let _ = writer.join();
+		output
+	})?;
+
+	if !output.status.success() {
+		return Err(Error::Generic(format!(
+			"clean filter '{command}' failed: {}",
+			String::from_utf8_lossy(&output.stderr).trim()
+		)));
+	}
+
+	Ok(output.stdout)
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::sync::{
+		diff::{get_diff, DiffLineType},
+		stage_hunk, stage_lines,
+		tests::{repo_init, write_commit_file},
+		utils::repo_write_file,
+		RepoPath,
+	};
+	use git2::Oid;
+
+	const FILE: &str = "test.txt";
+
+	/// strips trailing whitespace of every line
+	fn repo_with_filter() -> (tempfile::TempDir, Repository) {
+		let (td, repo) = repo_init().unwrap();
+
+		repo.config()
+			.unwrap()
+			.set_str(
+				"filter.strip.clean",
+				"sed -e 's/[[:space:]]*$//'",
+			)
+			.unwrap();
+
+		write_commit_file(
+			&repo,
+			".gitattributes",
+			"*.txt filter=strip\n",
+			"attributes",
+		);
+
+		let content: String =
+			(0..10).map(|i| format!("line {i}\n")).collect();
+		write_commit_file(&repo, FILE, &content, "c1");
+
+		let content: String = (0..10)
+			.map(|i| {
+				if i == 0 || i == 9 {
+					format!("line {i} changed  \n")
+				} else {
+					format!("line {i}\n")
+				}
+			})
+			.collect();
+		repo_write_file(&repo, FILE, &content).unwrap();
+
+		(td, repo)
+	}
+
+	fn index_blob_id(repo: &Repository) -> Oid {
+		let mut index = repo.index().unwrap();
+		index.read(true).unwrap();
+		index.get_path(Path::new(FILE), 0).unwrap().id
+	}
+
+	/// blob id `git add` produces for the current worktree
+	fn git_cli_blob_id(repo: &Repository) -> Oid {
+		let output = Command::new("git")
+			.args(["add", FILE])
+			.current_dir(repo.workdir().unwrap())
+			.output()
+			.unwrap();
+		assert!(output.status.success(), "{output:?}");
+
+		index_blob_id(repo)
+	}
+
+	fn repo_path(repo: &Repository) -> RepoPath {
+		repo.workdir().unwrap().to_str().unwrap().into()
+	}
+
+	#[test]
+	fn test_stage_lines_clean_filter() {
+		let (_td, repo) = repo_with_filter();
+		let path = &repo_path(&repo);
+
+		let diff = get_diff(path, FILE, false, None).unwrap();
+		let lines: Vec<_> = diff
+			.hunks
+			.iter()
+			.flat_map(|hunk| hunk.lines.iter())
+			.filter(|line| {
+				matches!(
+					line.line_type,
+					DiffLineType::Add | DiffLineType::Delete
+				)
+			})
+			.map(|line| line.position)
+			.collect();
+
+		stage_lines(path, FILE, false, &lines).unwrap();
+
+		let staged = index_blob_id(&repo);
+
+		assert_eq!(staged, git_cli_blob_id(&repo));
+	}
+
+	#[test]
+	fn test_stage_hunk_clean_filter() {
+		let (_td, repo) = repo_with_filter();
+		let path = &repo_path(&repo);
+
+		let diff = get_diff(path, FILE, false, None).unwrap();
+		assert_eq!(diff.hunks.len(), 2);
+
+		// last one first, so the header of the other stays the same
+		for hunk in diff.hunks.iter().rev() {
+			stage_hunk(path, FILE, hunk.header_hash, None).unwrap();
+		}
+
+		let staged = index_blob_id(&repo);
+
+		assert_eq!(staged, git_cli_blob_id(&repo));
+	}
+
+	#[test]
+	fn test_no_filter() {
+		let (_td, repo) = repo_init().unwrap();
+
+		assert_eq!(
+			apply_clean_filter(&repo, FILE, b"foo  \n".to_vec())
+				.unwrap(),
+			b"foo  \n"
+		);
+	}
+}
@@ -1,44 +1,471 @@
-use super::{repository::repo, RepoPath};
+use super::{
+	merge::SQUASH_MSG, repository::repo, CommitId, RepoPath,
+};
 use crate::error::Result;
-pub use git2_hooks::PrepareCommitMsgSource;
+use git2::{Config, ConfigLevel, Repository};
+pub use git2_hooks::{
+	CancellationToken, HookContext, HookOutputLine, HookType,
+	HooksConfig, OutputStream, PostRewriteSource, PrePushRef,
+	PrepareCommitMsgSource, RefUpdate, ReferenceTransactionState,
+	TimeoutSource,
+};
 use scopetime::scope_time;
-use std::time::Duration;
+use std::{
+	collections::BTreeMap,
+	path::{Path, PathBuf},
+	sync::{Mutex, PoisonError},
+	time::Duration,
+};
+
+/// set for every hook run through here so they can tell they were
+/// run by gitui, more can be passed to [`run_hook_cancellable`]
+pub const HOOK_ENV: &[(&str, &str)] = &[("GITUI", "1")];
+
+/// how long each hook ran the last time, see [`last_hook_duration`]
+static HOOK_DURATIONS: Mutex<BTreeMap<String, Duration>> =
+	Mutex::new(BTreeMap::new());
+
+/// wall-clock time `hook` took the last time it ran to completion
+/// (passing or failing) in any repository, `None` if it never did
+pub fn last_hook_duration(hook: &str) -> Option<Duration> {
+	HOOK_DURATIONS
+		.lock()
+		.unwrap_or_else(PoisonError::into_inner)
+		.get(hook)
+		.copied()
+}
 
 ///
-#[derive(Debug, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub enum HookResult {
 	/// Everything went fine
 	Ok,
 	/// Hook returned error
-	NotOk(String),
+	NotOk(HookFailure),
 	/// Hook timed out
 	TimedOut {
+		/// path of the hook that was killed
+		hook: PathBuf,
 		/// Stdout
 		stdout: String,
 		/// Stderr
 		stderr: String,
+		/// the timeout it ran into
+		timeout: Duration,
+		/// where `timeout` was set
+		source: TimeoutSource,
+	},
+	/// Hook was killed (or never started) because it was cancelled, see
+	/// [`run_hook_cancellable`]
+	Cancelled {
+		/// path of the hook that was cancelled
+		hook: PathBuf,
 	},
 }
 
-impl From<git2_hooks::HookResult> for HookResult {
-	fn from(v: git2_hooks::HookResult) -> Self {
+/// a hook that ran and failed, see [`HookResult::NotOk`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct HookFailure {
+	/// path of the hook that was run
+	pub hook: PathBuf,
+	/// exit code, `None` if the hook was killed by a signal
+	pub code: Option<i32>,
+	/// stdout exactly as the hook wrote it, see [`HookFailure::msg`]
+	pub stdout: Vec<u8>,
+	/// stderr exactly as the hook wrote it, see [`HookFailure::msg`]
+	pub stderr: Vec<u8>,
+	/// signal the hook was killed by (unix only)
+	pub signal: Option<i32>,
+	/// killed by `SIGKILL` without any output, most likely by the OOM
+	/// killer of the OS
+	pub likely_oom: bool,
+}
+
+impl HookFailure {
+	/// what to show the user: the output of the hook (stdout first,
+	/// invalid UTF-8 replaced), saying how it was killed or that it
+	/// printed nothing if needed
+	pub fn msg(&self) -> String {
+		let output = format!(
+			"{}{}",
+			String::from_utf8_lossy(&self.stdout),
+			String::from_utf8_lossy(&self.stderr)
+		);
+
+		if let Some(signal) = self.signal {
+			return format!(
+				"{}\n{output}",
+				terminated_msg(&self.hook, signal, self.likely_oom)
+			);
+		}
+
+		// a hook failing without any output would otherwise leave the
+		// user without any clue what happened
+		if output.trim().is_empty() {
+			silent_failure_msg(&self.hook, self.code)
+		} else {
+			output
+		}
+	}
+}
+
+/// whether the hooks checking a commit run at all, `NoVerify` is
+/// `git commit --no-verify`.
+///
+/// only pre-commit, commit-msg and prepare-commit-msg are affected,
+/// post-commit runs either way. hooks a repo never wants to run are
+/// skipped by `gitui.hooks.deny` instead, see [`HooksConfig`]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum HookBehavior {
+	///
+	#[default]
+	Verify,
+	/// skip the hook without running anything
+	NoVerify,
+}
+
+impl HookBehavior {
+	///
+	pub const fn from_verify(verify: bool) -> Self {
+		if verify {
+			Self::Verify
+		} else {
+			Self::NoVerify
+		}
+	}
+
+	fn skips(self, hook: &str) -> bool {
+		if matches!(self, Self::NoVerify) {
+			log::trace!("hook '{hook}' skipped (no-verify)");
+			true
+		} else {
+			false
+		}
+	}
+}
+
+impl HookResult {
+	/// the hook passed, does not exist or is disabled
+	pub const fn is_ok(&self) -> bool {
+		matches!(self, Self::Ok)
+	}
+
+	/// output of a failed hook, see [`HookFailure::msg`]
+	pub fn msg(&self) -> Option<String> {
+		match self {
+			Self::NotOk(failure) => Some(failure.msg()),
+			_ => None,
+		}
+	}
+
+	/// exit code of a failed hook, `None` if it passed, timed out or
+	/// was killed by a signal
+	pub const fn code(&self) -> Option<i32> {
+		match self {
+			Self::NotOk(failure) => failure.code,
+			_ => None,
+		}
+	}
+
+	/// path of the hook that failed, timed out or was cancelled
+	pub fn hook(&self) -> Option<&Path> {
+		match self {
+			Self::Ok => None,
+			Self::NotOk(HookFailure { hook, .. })
+			| Self::TimedOut { hook, .. }
+			| Self::Cancelled { hook } => Some(hook),
+		}
+	}
+
+	/// `v` of a hook that was run with `timeout` set by `source`
+	fn from_run(
+		v: git2_hooks::HookResult,
+		timeout: Duration,
+		source: TimeoutSource,
+	) -> Self {
 		match v {
 			git2_hooks::HookResult::Ok { .. }
 			| git2_hooks::HookResult::NoHookFound => Self::Ok,
 			git2_hooks::HookResult::RunNotSuccessful {
-				stdout,
-				stderr,
+				stdout_bytes,
+				stderr_bytes,
+				code,
+				hook,
 				..
-			} => Self::NotOk(format!("{stdout}{stderr}")),
+			} => Self::NotOk(HookFailure {
+				hook,
+				code,
+				stdout: stdout_bytes,
+				stderr: stderr_bytes,
+				signal: None,
+				likely_oom: false,
+			}),
 			git2_hooks::HookResult::TimedOut {
 				stdout,
 				stderr,
-				..
-			} => Self::TimedOut { stdout, stderr },
+				hook,
+			} => Self::TimedOut {
+				hook,
+				stdout,
+				stderr,
+				timeout,
+				source,
+			},
+			git2_hooks::HookResult::Cancelled { hook, .. } => {
+				Self::Cancelled { hook }
+			}
+			git2_hooks::HookResult::Terminated {
+				signal,
+				likely_oom,
+				stdout,
+				stderr,
+				hook,
+			} => Self::NotOk(HookFailure {
+				hook,
+				code: None,
+				stdout: stdout.into_bytes(),
+				stderr: stderr.into_bytes(),
+				signal: Some(signal),
+				likely_oom,
+			}),
+		}
+	}
+}
+
+fn terminated_msg(
+	hook: &Path,
+	signal: i32,
+	likely_oom: bool,
+) -> String {
+	let name = hook.file_name().unwrap_or(hook.as_os_str());
+	let name = name.to_string_lossy();
+
+	if likely_oom {
+		format!(
+			"hook {name} was killed (signal {signal}), most likely by the system running out of memory"
+		)
+	} else {
+		format!("hook {name} was killed by signal {signal}")
+	}
+}
+
+fn silent_failure_msg(hook: &Path, code: Option<i32>) -> String {
+	let name = hook.file_name().unwrap_or(hook.as_os_str());
+	let name = name.to_string_lossy();
+
+	code.map_or_else(
+		|| format!("hook {name} was terminated without output"),
+		|code| {
+			format!(
+				"hook {name} exited with code {code} without output"
+			)
+		},
+	)
+}
+
+/// loads the `git2_hooks::HooksConfig` of the repo and calls `run`
+/// with its `other_paths` and timeout (see [`effective_hook_timeout`])
+/// if `hook` is not disabled by it.
+///
+/// a repository opened from a subdirectory runs its hooks with that
+/// directory as `GIT_PREFIX`, like git does. [`HOOK_ENV`] is set for
+/// all of them.
+fn run_configured_hook<F>(
+	repo_path: &RepoPath,
+	hook: &str,
+	timeout: Option<Duration>,
+	run: F,
+) -> Result<HookResult>
+where
+	F: FnOnce(
+		&Repository,
+		Option<&[&str]>,
+		&HookContext,
+		Option<Duration>,
+	) -> std::result::Result<
+		git2_hooks::HookResult,
+		git2_hooks::HooksError,
+	>,
+{
+	run_configured_hook_with(repo_path, hook, timeout, &[], None, run)
+}
+
+/// [`run_configured_hook`] with `env` set on top of [`HOOK_ENV`] and the
+/// hook killed once `cancel` is cancelled
+fn run_configured_hook_with<F>(
+	repo_path: &RepoPath,
+	hook: &str,
+	timeout: Option<Duration>,
+	env: &[(&str, &str)],
+	cancel: Option<&CancellationToken>,
+	run: F,
+) -> Result<HookResult>
+where
+	F: FnOnce(
+		&Repository,
+		Option<&[&str]>,
+		&HookContext,
+		Option<Duration>,
+	) -> std::result::Result<
+		git2_hooks::HookResult,
+		git2_hooks::HooksError,
+	>,
+{
+	let repo = repo(repo_path)?;
+	let config = HooksConfig::from_repo(&repo)?;
+
+	if !config.is_enabled(hook) {
+		log::trace!("hook '{hook}' disabled by config");
+		return Ok(HookResult::Ok);
+	}
+
+	let other_paths = config.other_paths();
+	let (timeout, source) =
+		configured_timeout(&config, hook, timeout);
+	let env = [HOOK_ENV, env].concat();
+	let ctx = HookContext {
+		env: &env,
+		invocation_dir: match repo_path {
+			RepoPath::Path(dir) => Some(dir),
+			RepoPath::Workdir { .. } => None,
+		},
+		cancel,
+	};
+
+	let result = run(
+		&repo,
+		Some(other_paths.as_slice()),
+		&ctx,
+		Some(timeout),
+	)?;
+
+	if let Some(duration) = result.duration() {
+		log::debug!("hook '{hook}' took {duration:?}");
+		HOOK_DURATIONS
+			.lock()
+			.unwrap_or_else(PoisonError::into_inner)
+			.insert(hook.to_string(), duration);
+	}
+
+	Ok(HookResult::from_run(result, timeout, source))
+}
+
+/// why [`run_pre_operation_hook`] did not let an operation proceed
+#[derive(Debug, thiserror::Error)]
+pub enum HookRejection {
+	/// the hook ran and failed, timed out or was killed,
+	/// `result` is never [`HookResult::Ok`]
+	#[error("{hook} hook rejected the operation{}", rejected_output(.result))]
+	Rejected {
+		///
+		hook: HookType,
+		///
+		result: HookResult,
+	},
+	/// the hook could not be run at all, which aborts the operation
+	/// just the same since it was not checked
+	#[error("{hook} hook could not be run: {source}")]
+	Failed {
+		///
+		hook: HookType,
+		///
+		source: Box<crate::Error>,
+	},
+}
+
+/// what a rejecting hook printed, to be shown along with the rejection
+fn rejected_output(result: &HookResult) -> String {
+	match result {
+		HookResult::Ok => String::new(),
+		HookResult::NotOk(failure) => format!(":\n{}", failure.msg()),
+		HookResult::TimedOut { stdout, stderr, .. } => {
+			format!(
+				" (timed out), see output below.\n{stdout}\n{stderr}"
+			)
 		}
+		HookResult::Cancelled { .. } => String::from(" (cancelled)"),
+	}
+}
+
+/// runs the `hook_type` hook with `args` (respecting the hooks config of
+/// the repo like all `hooks_*` functions) as gate of an operation.
+///
+/// this is the commit flow (run `pre-commit`, commit only if it passed)
+/// made reusable for merge, rebase, push and the like: callers run it
+/// *before* mutating anything and return on `Err` right away, so a
+/// rejecting hook leaves the repository untouched. `Ok` means proceed,
+/// that is the hook passed, does not exist or is disabled by config.
+/// everything else aborts: a hook that failed, timed out or was killed
+/// as well as one that could not be run in the first place.
+pub fn run_pre_operation_hook(
+	repo_path: &RepoPath,
+	hook_type: HookType,
+	args: &[&str],
+) -> std::result::Result<(), HookRejection> {
+	scope_time!("run_pre_operation_hook");
+
+	let result = run_configured_hook(
+		repo_path,
+		hook_type.filename(),
+		None,
+		|repo, other_paths, ctx, timeout| {
+			git2_hooks::run_hooks(
+				repo,
+				other_paths,
+				hook_type.filename(),
+				args,
+				ctx,
+				timeout,
+			)
+		},
+	)
+	.map_err(|e| HookRejection::Failed {
+		hook: hook_type,
+		source: Box::new(e),
+	})?;
+
+	match result {
+		HookResult::Ok => Ok(()),
+		result => Err(HookRejection::Rejected {
+			hook: hook_type,
+			result,
+		}),
 	}
 }
 
+/// runs the `hook_type` hook with `args` like [`run_pre_operation_hook`]
+/// does, but the hook is killed once `cancel` is cancelled (from
+/// another thread) which results in [`HookResult::Cancelled`].
+///
+/// `env` is set for the hook on top of [`HOOK_ENV`]
+pub fn run_hook_cancellable(
+	repo_path: &RepoPath,
+	hook_type: HookType,
+	args: &[&str],
+	env: &[(&str, &str)],
+	cancel: &CancellationToken,
+) -> Result<HookResult> {
+	scope_time!("run_hook_cancellable");
+
+	run_configured_hook_with(
+		repo_path,
+		hook_type.filename(),
+		None,
+		env,
+		Some(cancel),
+		|repo, other_paths, ctx, timeout| {
+			git2_hooks::run_hooks(
+				repo,
+				other_paths,
+				hook_type.filename(),
+				args,
+				ctx,
+				timeout,
+			)
+		},
+	)
+}
+
 /// see `git2_hooks::hooks_commit_msg`
 pub fn hooks_commit_msg(
 	repo_path: &RepoPath,
@@ -48,19 +475,177 @@ pub fn hooks_commit_msg(
 }
 
 /// see `git2_hooks::hooks_commit_msg`
-#[allow(unused)]
 pub fn hooks_commit_msg_with_timeout(
 	repo_path: &RepoPath,
 	msg: &mut String,
 	timeout: Option<Duration>,
+) -> Result<HookResult> {
+	hooks_commit_msg_with_behavior(
+		repo_path,
+		msg,
+		HookBehavior::Verify,
+		timeout,
+	)
+}
+
+/// like [`hooks_commit_msg_with_timeout`], not running anything for
+/// [`HookBehavior::NoVerify`]
+pub fn hooks_commit_msg_with_behavior(
+	repo_path: &RepoPath,
+	msg: &mut String,
+	behavior: HookBehavior,
+	timeout: Option<Duration>,
+) -> Result<HookResult> {
+	hooks_commit_msg_with(repo_path, msg, behavior, timeout, None)
+}
+
+/// like [`hooks_commit_msg_with_behavior`], but the hook is killed
+/// once `cancel` is cancelled, see [`run_hook_cancellable`]
+pub fn hooks_commit_msg_cancellable(
+	repo_path: &RepoPath,
+	msg: &mut String,
+	behavior: HookBehavior,
+	timeout: Option<Duration>,
+	cancel: &CancellationToken,
+) -> Result<HookResult> {
+	hooks_commit_msg_with(
+		repo_path,
+		msg,
+		behavior,
+		timeout,
+		Some(cancel),
+	)
+}
+
+fn hooks_commit_msg_with(
+	repo_path: &RepoPath,
+	msg: &mut String,
+	behavior: HookBehavior,
+	timeout: Option<Duration>,
+	cancel: Option<&CancellationToken>,
 ) -> Result<HookResult> {
 	scope_time!("hooks_commit_msg");
 
-	let repo = repo(repo_path)?;
-	Ok(git2_hooks::hooks_commit_msg_with_timeout(
-		&repo, None, msg, timeout,
-	)?
-	.into())
+	if behavior.skips(git2_hooks::HOOK_COMMIT_MSG) {
+		return Ok(HookResult::Ok);
+	}
+
+	run_configured_hook_with(
+		repo_path,
+		git2_hooks::HOOK_COMMIT_MSG,
+		timeout,
+		&[],
+		cancel,
+		|repo, other_paths, ctx, timeout| {
+			git2_hooks::hooks_commit_msg_with_timeout(
+				repo,
+				other_paths,
+				msg,
+				ctx,
+				timeout,
+			)
+		},
+	)
+}
+
+/// see `git2_hooks::hooks_commit_msg_preview`
+pub fn hooks_commit_msg_preview(
+	repo_path: &RepoPath,
+	msg: &str,
+	timeout: Option<Duration>,
+) -> Result<HookResult> {
+	scope_time!("hooks_commit_msg_preview");
+
+	run_configured_hook(
+		repo_path,
+		git2_hooks::HOOK_COMMIT_MSG,
+		timeout,
+		|repo, other_paths, ctx, timeout| {
+			git2_hooks::hooks_commit_msg_preview(
+				repo,
+				other_paths,
+				msg,
+				ctx,
+				timeout,
+			)
+		},
+	)
+}
+
+/// see `git2_hooks::hooks_applypatch_msg`
+pub fn hooks_applypatch_msg(
+	repo_path: &RepoPath,
+	msg: &mut String,
+) -> Result<HookResult> {
+	hooks_applypatch_msg_with_timeout(repo_path, msg, None)
+}
+
+/// see `git2_hooks::hooks_applypatch_msg`
+pub fn hooks_applypatch_msg_with_timeout(
+	repo_path: &RepoPath,
+	msg: &mut String,
+	timeout: Option<Duration>,
+) -> Result<HookResult> {
+	scope_time!("hooks_applypatch_msg");
+
+	run_configured_hook(
+		repo_path,
+		git2_hooks::HOOK_APPLYPATCH_MSG,
+		timeout,
+		|repo, other_paths, ctx, timeout| {
+			git2_hooks::hooks_applypatch_msg_with_timeout(
+				repo,
+				other_paths,
+				msg,
+				ctx,
+				timeout,
+			)
+		},
+	)
+}
+
+/// see `git2_hooks::hooks_pre_applypatch`
+pub fn hooks_pre_applypatch(
+	repo_path: &RepoPath,
+) -> Result<HookResult> {
+	hooks_pre_applypatch_with_timeout(repo_path, None)
+}
+
+/// see `git2_hooks::hooks_pre_applypatch`
+pub fn hooks_pre_applypatch_with_timeout(
+	repo_path: &RepoPath,
+	timeout: Option<Duration>,
+) -> Result<HookResult> {
+	scope_time!("hooks_pre_applypatch");
+
+	run_configured_hook(
+		repo_path,
+		git2_hooks::HOOK_PRE_APPLYPATCH,
+		timeout,
+		git2_hooks::hooks_pre_applypatch_with_timeout,
+	)
+}
+
+/// see `git2_hooks::hooks_post_applypatch`
+pub fn hooks_post_applypatch(
+	repo_path: &RepoPath,
+) -> Result<HookResult> {
+	hooks_post_applypatch_with_timeout(repo_path, None)
+}
+
+/// see `git2_hooks::hooks_post_applypatch`
+pub fn hooks_post_applypatch_with_timeout(
+	repo_path: &RepoPath,
+	timeout: Option<Duration>,
+) -> Result<HookResult> {
+	scope_time!("hooks_post_applypatch");
+
+	run_configured_hook(
+		repo_path,
+		git2_hooks::HOOK_POST_APPLYPATCH,
+		timeout,
+		git2_hooks::hooks_post_applypatch_with_timeout,
+	)
 }
 
 /// see `git2_hooks::hooks_pre_commit`
@@ -69,19 +654,86 @@ pub fn hooks_pre_commit(repo_path: &RepoPath) -> Result<HookResult> {
 }
 
 /// see `git2_hooks::hooks_pre_commit`
-#[allow(unused)]
 pub fn hooks_pre_commit_with_timeout(
 	repo_path: &RepoPath,
 	timeout: Option<Duration>,
+) -> Result<HookResult> {
+	hooks_pre_commit_with_behavior(
+		repo_path,
+		HookBehavior::Verify,
+		timeout,
+	)
+}
+
+/// like [`hooks_pre_commit_with_timeout`], not running anything for
+/// [`HookBehavior::NoVerify`]
+pub fn hooks_pre_commit_with_behavior(
+	repo_path: &RepoPath,
+	behavior: HookBehavior,
+	timeout: Option<Duration>,
+) -> Result<HookResult> {
+	hooks_pre_commit_with(repo_path, behavior, timeout, None)
+}
+
+/// like [`hooks_pre_commit_with_behavior`], but the hook is killed
+/// once `cancel` is cancelled, see [`run_hook_cancellable`]
+pub fn hooks_pre_commit_cancellable(
+	repo_path: &RepoPath,
+	behavior: HookBehavior,
+	timeout: Option<Duration>,
+	cancel: &CancellationToken,
+) -> Result<HookResult> {
+	hooks_pre_commit_with(repo_path, behavior, timeout, Some(cancel))
+}
+
+fn hooks_pre_commit_with(
+	repo_path: &RepoPath,
+	behavior: HookBehavior,
+	timeout: Option<Duration>,
+	cancel: Option<&CancellationToken>,
 ) -> Result<HookResult> {
 	scope_time!("hooks_pre_commit");
 
-	let repo = repo(repo_path)?;
+	if behavior.skips(git2_hooks::HOOK_PRE_COMMIT) {
+		return Ok(HookResult::Ok);
+	}
 
-	Ok(git2_hooks::hooks_pre_commit_with_timeout(
-		&repo, None, timeout,
-	)?
-	.into())
+	run_configured_hook_with(
+		repo_path,
+		git2_hooks::HOOK_PRE_COMMIT,
+		timeout,
+		&[],
+		cancel,
+		git2_hooks::hooks_pre_commit_with_timeout,
+	)
+}
+
+/// like [`hooks_pre_commit_with_timeout`] but hands every line the hook
+/// writes to `on_line` while it runs, see
+/// `git2_hooks::run_hook_streaming_with_timeout`
+pub fn hooks_pre_commit_streaming(
+	repo_path: &RepoPath,
+	timeout: Option<Duration>,
+	on_line: impl FnMut(HookOutputLine),
+) -> Result<HookResult> {
+	scope_time!("hooks_pre_commit_streaming");
+
+	run_configured_hook(
+		repo_path,
+		git2_hooks::HOOK_PRE_COMMIT,
+		timeout,
+		|repo, other_paths, ctx, timeout| {
+			git2_hooks::run_hook_streaming_with_timeout(
+				repo,
+				other_paths,
+				git2_hooks::HOOK_PRE_COMMIT,
+				&[],
+				ctx,
+				timeout,
+				on_line,
+			)
+		},
+	)
 }
 
 /// see `git2_hooks::hooks_post_commit`
@@ -90,19 +742,300 @@ pub fn hooks_post_commit(repo_path: &RepoPath) -> Result<HookResult> {
 }
 
 /// see `git2_hooks::hooks_post_commit`
-#[allow(unused)]
 pub fn hooks_post_commit_with_timeout(
 	repo_path: &RepoPath,
 	timeout: Option<Duration>,
 ) -> Result<HookResult> {
 	scope_time!("hooks_post_commit");
 
-	let repo = repo(repo_path)?;
+	run_configured_hook(
+		repo_path,
+		git2_hooks::HOOK_POST_COMMIT,
+		timeout,
+		git2_hooks::hooks_post_commit_with_timeout,
+	)
+}
+
+/// see `git2_hooks::hooks_post_checkout`
+pub fn hooks_post_checkout(
+	repo_path: &RepoPath,
+	prev_head: CommitId,
+	new_head: CommitId,
+	branch_checkout: bool,
+) -> Result<HookResult> {
+	hooks_post_checkout_with_timeout(
+		repo_path,
+		prev_head,
+		new_head,
+		branch_checkout,
+		None,
+	)
+}
+
+/// see `git2_hooks::hooks_post_checkout`
+pub fn hooks_post_checkout_with_timeout(
+	repo_path: &RepoPath,
+	prev_head: CommitId,
+	new_head: CommitId,
+	branch_checkout: bool,
+	timeout: Option<Duration>,
+) -> Result<HookResult> {
+	scope_time!("hooks_post_checkout");
+
+	run_configured_hook(
+		repo_path,
+		git2_hooks::HOOK_POST_CHECKOUT,
+		timeout,
+		|repo, other_paths, ctx, timeout| {
+			git2_hooks::hooks_post_checkout_with_timeout(
+				repo,
+				other_paths,
+				prev_head.into(),
+				new_head.into(),
+				branch_checkout,
+				ctx,
+				timeout,
+			)
+		},
+	)
+}
+
+/// see `git2_hooks::hooks_post_merge`
+pub fn hooks_post_merge(
+	repo_path: &RepoPath,
+	is_squash: bool,
+) -> Result<HookResult> {
+	hooks_post_merge_with_timeout(repo_path, is_squash, None)
+}
+
+/// see `git2_hooks::hooks_post_merge`
+pub fn hooks_post_merge_with_timeout(
+	repo_path: &RepoPath,
+	is_squash: bool,
+	timeout: Option<Duration>,
+) -> Result<HookResult> {
+	scope_time!("hooks_post_merge");
+
+	run_configured_hook(
+		repo_path,
+		git2_hooks::HOOK_POST_MERGE,
+		timeout,
+		|repo, other_paths, ctx, timeout| {
+			git2_hooks::hooks_post_merge_with_timeout(
+				repo,
+				other_paths,
+				is_squash,
+				ctx,
+				timeout,
+			)
+		},
+	)
+}
+
+/// see `git2_hooks::hooks_post_rewrite`
+pub fn hooks_post_rewrite(
+	repo_path: &RepoPath,
+	source: PostRewriteSource,
+	rewritten: &[(CommitId, CommitId)],
+) -> Result<HookResult> {
+	hooks_post_rewrite_with_timeout(
+		repo_path, source, rewritten, None,
+	)
+}
+
+/// see `git2_hooks::hooks_post_rewrite`
+pub fn hooks_post_rewrite_with_timeout(
+	repo_path: &RepoPath,
+	source: PostRewriteSource,
+	rewritten: &[(CommitId, CommitId)],
+	timeout: Option<Duration>,
+) -> Result<HookResult> {
+	scope_time!("hooks_post_rewrite");
+
+	let rewritten = rewritten
+		.iter()
+		.map(|(old, new)| ((*old).into(), (*new).into()))
+		.collect::<Vec<_>>();
+
+	run_configured_hook(
+		repo_path,
+		git2_hooks::HOOK_POST_REWRITE,
+		timeout,
+		|repo, other_paths, ctx, timeout| {
+			git2_hooks::hooks_post_rewrite_with_timeout(
+				repo,
+				other_paths,
+				source,
+				&rewritten,
+				ctx,
+				timeout,
+			)
+		},
+	)
+}
+
+/// see `git2_hooks::hooks_pre_rebase`
+pub fn hooks_pre_rebase(
+	repo_path: &RepoPath,
+	upstream: &str,
+	branch: Option<&str>,
+) -> Result<HookResult> {
+	hooks_pre_rebase_with_timeout(repo_path, upstream, branch, None)
+}
+
+/// see `git2_hooks::hooks_pre_rebase`
+pub fn hooks_pre_rebase_with_timeout(
+	repo_path: &RepoPath,
+	upstream: &str,
+	branch: Option<&str>,
+	timeout: Option<Duration>,
+) -> Result<HookResult> {
+	scope_time!("hooks_pre_rebase");
+
+	run_configured_hook(
+		repo_path,
+		git2_hooks::HOOK_PRE_REBASE,
+		timeout,
+		|repo, other_paths, ctx, timeout| {
+			git2_hooks::hooks_pre_rebase_with_timeout(
+				repo,
+				other_paths,
+				upstream,
+				branch,
+				ctx,
+				timeout,
+			)
+		},
+	)
+}
+
+/// see `git2_hooks::hooks_pre_push`
+pub fn hooks_pre_push(
+	repo_path: &RepoPath,
+	remote_name: &str,
+	remote_url: &str,
+	refs: &[PrePushRef],
+) -> Result<HookResult> {
+	hooks_pre_push_with_timeout(
+		repo_path,
+		remote_name,
+		remote_url,
+		refs,
+		None,
+	)
+}
+
+/// see `git2_hooks::hooks_pre_push`
+pub fn hooks_pre_push_with_timeout(
+	repo_path: &RepoPath,
+	remote_name: &str,
+	remote_url: &str,
+	refs: &[PrePushRef],
+	timeout: Option<Duration>,
+) -> Result<HookResult> {
+	scope_time!("hooks_pre_push");
+
+	run_configured_hook(
+		repo_path,
+		git2_hooks::HOOK_PRE_PUSH,
+		timeout,
+		|repo, other_paths, ctx, timeout| {
+			git2_hooks::hooks_pre_push_with_timeout(
+				repo,
+				other_paths,
+				remote_name,
+				remote_url,
+				refs,
+				ctx,
+				timeout,
+			)
+		},
+	)
+}
 
-	Ok(git2_hooks::hooks_post_commit_with_timeout(
-		&repo, None, timeout,
-	)?
-	.into())
+/// see `git2_hooks::hooks_reference_transaction`
+pub fn hooks_reference_transaction(
+	repo_path: &RepoPath,
+	state: ReferenceTransactionState,
+	updates: &[RefUpdate],
+) -> Result<HookResult> {
+	scope_time!("hooks_reference_transaction");
+
+	run_configured_hook(
+		repo_path,
+		git2_hooks::HOOK_REFERENCE_TRANSACTION,
+		None,
+		|repo, other_paths, ctx, timeout| {
+			git2_hooks::hooks_reference_transaction_with_timeout(
+				repo,
+				other_paths,
+				state,
+				updates,
+				ctx,
+				timeout,
+			)
+		},
+	)
+}
+
+/// runs `update` (which applies `updates`) as a reference transaction
+/// like git does: the `reference-transaction` hook runs with `prepared`
+/// first, a rejection of it aborts before `update` is called. then it
+/// runs with `committed`, or `aborted` if `update` failed, whose results
+/// can not change anything anymore and are only logged.
+///
+/// the hook runs synchronously on the calling thread, can not be
+/// cancelled and only gets the configured timeout (see
+/// [`HooksConfig::timeout_for`]), not the session one of the app. this
+/// is used by `commit`, `create_branch` and `reset_repo`, which the app
+/// calls on its UI thread, so a slow `reference-transaction` hook blocks
+/// it until it exits or that timeout kills it
+pub fn with_reference_transaction<T>(
+	repo_path: &RepoPath,
+	updates: &[RefUpdate],
+	update: impl FnOnce() -> Result<T>,
+) -> Result<T> {
+	let finish = |state| match hooks_reference_transaction(
+		repo_path, state, updates,
+	) {
+		Ok(HookResult::Ok) => (),
+		Ok(result) => log::warn!(
+			"reference-transaction hook ({state:?}) failed:{}",
+			rejected_output(&result)
+		),
+		Err(e) => {
+			log::error!("reference-transaction hook error: {e}");
+		}
+	};
+
+	let rejection = match hooks_reference_transaction(
+		repo_path,
+		ReferenceTransactionState::Prepared,
+		updates,
+	) {
+		Ok(HookResult::Ok) => None,
+		Ok(result) => Some(HookRejection::Rejected {
+			hook: HookType::ReferenceTransaction,
+			result,
+		}),
+		Err(e) => Some(HookRejection::Failed {
+			hook: HookType::ReferenceTransaction,
+			source: Box::new(e),
+		}),
+	};
+	if let Some(rejection) = rejection {
+		finish(ReferenceTransactionState::Aborted);
+		return Err(rejection.into());
+	}
+
+	let result = update();
+	finish(if result.is_ok() {
+		ReferenceTransactionState::Committed
+	} else {
+		ReferenceTransactionState::Aborted
+	});
+
+	result
 }
 
 /// see `git2_hooks::hooks_prepare_commit_msg`
@@ -111,54 +1044,215 @@ pub fn hooks_prepare_commit_msg(
 	source: PrepareCommitMsgSource,
 	msg: &mut String,
 ) -> Result<HookResult> {
-	scope_time!("hooks_prepare_commit_msg");
-
-	let repo = repo(repo_path)?;
-
-	Ok(git2_hooks::hooks_prepare_commit_msg_with_timeout(
-		&repo, None, source, msg, None,
-	)?
-	.into())
+	hooks_prepare_commit_msg_with_timeout(
+		repo_path, source, msg, None,
+	)
 }
 
 /// see `git2_hooks::hooks_prepare_commit_msg`
-#[allow(unused)]
 pub fn hooks_prepare_commit_msg_with_timeout(
 	repo_path: &RepoPath,
 	source: PrepareCommitMsgSource,
 	msg: &mut String,
 	timeout: Option<Duration>,
+) -> Result<HookResult> {
+	hooks_prepare_commit_msg_with_behavior(
+		repo_path,
+		source,
+		msg,
+		HookBehavior::Verify,
+		timeout,
+	)
+}
+
+/// like [`hooks_prepare_commit_msg_with_timeout`], not running
+/// anything for [`HookBehavior::NoVerify`]
+pub fn hooks_prepare_commit_msg_with_behavior(
+	repo_path: &RepoPath,
+	source: PrepareCommitMsgSource,
+	msg: &mut String,
+	behavior: HookBehavior,
+	timeout: Option<Duration>,
 ) -> Result<HookResult> {
 	scope_time!("hooks_prepare_commit_msg");
 
-	let repo = repo(repo_path)?;
+	if behavior.skips(git2_hooks::HOOK_PREPARE_COMMIT_MSG) {
+		return Ok(HookResult::Ok);
+	}
 
-	Ok(git2_hooks::hooks_prepare_commit_msg_with_timeout(
-		&repo, None, source, msg, timeout,
-	)?
-	.into())
+	run_configured_hook(
+		repo_path,
+		git2_hooks::HOOK_PREPARE_COMMIT_MSG,
+		timeout,
+		|repo, other_paths, ctx, timeout| {
+			git2_hooks::hooks_prepare_commit_msg_with_timeout(
+				repo,
+				other_paths,
+				source,
+				msg,
+				ctx,
+				timeout,
+			)
+		},
+	)
 }
 
-#[cfg(test)]
-mod tests {
-	use std::{ffi::OsString, io::Write as _, path::Path};
+/// what a commit message is written for
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CommitMsgFor {
+	/// a new commit, `template` if the message was prefilled from
+	/// `commit.template`
+	New {
+		///
+		template: bool,
+	},
+	/// amending or rewording a commit, starting with its message
+	Commit(CommitId),
+}
 
-	use git2::Repository;
-	use tempfile::{tempdir, TempDir};
+/// source to pass to prepare-commit-msg for a message written `for_`
+///
+/// chosen like `git commit` does: an existing commit's message comes
+/// first, then a pending merge (`MERGE_MSG`, also written by reverts and
+/// cherry-picks), a pending squash (`SQUASH_MSG`) and the template
+pub fn prepare_commit_msg_source(
+	repo_path: &RepoPath,
+	for_: CommitMsgFor,
+) -> Result<PrepareCommitMsgSource> {
+	scope_time!("prepare_commit_msg_source");
 
-	use super::*;
-	use crate::sync::tests::repo_init_with_prefix;
+	let template = match for_ {
+		CommitMsgFor::Commit(id) => {
+			return Ok(PrepareCommitMsgSource::Commit(id.into()));
+		}
+		CommitMsgFor::New { template } => template,
+	};
 
-	fn repo_init() -> Result<(TempDir, Repository)> {
-		let mut os_string: OsString = OsString::new();
+	let repo = repo(repo_path)?;
 
-		os_string.push("gitui $# ' ");
+	Ok(if repo.message().is_ok() {
+		PrepareCommitMsgSource::Merge
+	} else if repo.path().join(SQUASH_MSG).exists() {
+		PrepareCommitMsgSource::Squash
+	} else if template {
+		PrepareCommitMsgSource::Template
+	} else {
+		PrepareCommitMsgSource::Message
+	})
+}
 
-		#[cfg(target_os = "linux")]
-		{
-			use std::os::unix::ffi::OsStrExt;
+/// the default hook timeout of the repo (or the global config), see
+/// `git2_hooks::HooksConfig::timeout`. [`Duration::ZERO`] meaning no
+/// timeout if it is not set or invalid, negative values count as zero
+pub fn read_hook_timeout(repo_path: &RepoPath) -> Duration {
+	scope_time!("read_hook_timeout");
 
-			const INVALID_UTF8: &[u8] = b"\xED\xA0\x80";
+	hooks_config(repo_path)
+		.ok()
+		.and_then(|config| config.timeout)
+		.unwrap_or_default()
+}
+
+/// the timeout `hook` (e.g. `pre-commit`) runs with, see
+/// [`configured_timeout`]. [`Duration::ZERO`] means no timeout
+pub fn effective_hook_timeout(
+	repo_path: &RepoPath,
+	hook: &str,
+	timeout: Option<Duration>,
+) -> Duration {
+	scope_time!("effective_hook_timeout");
+
+	let config = hooks_config(repo_path).unwrap_or_default();
+
+	configured_timeout(&config, hook, timeout).0
+}
+
+/// `timeout` if it is passed explicitly (the session timeout of the
+/// app), otherwise what the config sets for `hook`, see
+/// `git2_hooks::HooksConfig::timeout_for`. zero if nothing is set
+fn configured_timeout(
+	config: &HooksConfig,
+	hook: &str,
+	timeout: Option<Duration>,
+) -> (Duration, TimeoutSource) {
+	timeout.map_or_else(
+		|| config.timeout_for(hook).unwrap_or_default(),
+		|timeout| (timeout, TimeoutSource::Explicit),
+	)
+}
+
+/// the effective `[gitui "hooks"]` settings, see `git2_hooks::HooksConfig`
+pub fn hooks_config(repo_path: &RepoPath) -> Result<HooksConfig> {
+	scope_time!("hooks_config");
+
+	Ok(HooksConfig::from_repo(&repo(repo_path)?)?)
+}
+
+/// sets `gitui.hooks.timeout` in the repository local config,
+/// `None` removes it there
+pub fn set_hooks_timeout(
+	repo_path: &RepoPath,
+	timeout: Option<Duration>,
+) -> Result<()> {
+	scope_time!("set_hooks_timeout");
+
+	Ok(HooksConfig::write_timeout(
+		&mut local_config(repo_path)?,
+		timeout,
+	)?)
+}
+
+/// sets `gitui.hooks.disabled` in the repository local config
+pub fn set_hooks_disabled(
+	repo_path: &RepoPath,
+	disabled: bool,
+) -> Result<()> {
+	scope_time!("set_hooks_disabled");
+
+	Ok(HooksConfig::write_disabled(
+		&mut local_config(repo_path)?,
+		disabled,
+	)?)
+}
+
+/// replaces the `gitui.hooks.deny` entries of the repository local
+/// config by `hooks`
+pub fn set_hooks_deny(
+	repo_path: &RepoPath,
+	hooks: &[String],
+) -> Result<()> {
+	scope_time!("set_hooks_deny");
+
+	Ok(HooksConfig::write_deny(
+		&mut local_config(repo_path)?,
+		hooks,
+	)?)
+}
+
+fn local_config(repo_path: &RepoPath) -> Result<Config> {
+	Ok(repo(repo_path)?.config()?.open_level(ConfigLevel::Local)?)
+}
+
+#[cfg(test)]
+mod tests {
+	use std::{ffi::OsString, io::Write as _, path::Path};
+
+	use git2::Repository;
+	use tempfile::{tempdir, TempDir};
+
+	use super::*;
+	use crate::sync::tests::repo_init_with_prefix;
+
+	fn repo_init() -> Result<(TempDir, Repository)> {
+		let mut os_string: OsString = OsString::new();
+
+		os_string.push("gitui $# ' ");
+
+		#[cfg(target_os = "linux")]
+		{
+			use std::os::unix::ffi::OsStrExt;
+
+			const INVALID_UTF8: &[u8] = b"\xED\xA0\x80";
 
 			os_string.push(std::ffi::OsStr::from_bytes(INVALID_UTF8));
 
@@ -193,7 +1287,7 @@ mod tests {
 		let root = repo.workdir().unwrap();
 
 		let hook = b"#!/usr/bin/env sh
-	echo 'rejected'
+	echo \"rejected in $GIT_PREFIX of $GIT_DIR\"
 	exit 1
 			";
 
@@ -203,117 +1297,809 @@ mod tests {
 			hook,
 		);
 
-		let subfolder = root.join("foo/");
-		std::fs::create_dir_all(&subfolder).unwrap();
+		let subfolder = root.join("foo/");
+		std::fs::create_dir_all(&subfolder).unwrap();
+
+		let res = hooks_post_commit(&subfolder.into()).unwrap();
+
+		assert_eq!(res.code(), Some(1));
+		assert_eq!(
+			res.msg(),
+			Some(format!(
+				"rejected in foo/ of {}\n",
+				root.join(".git").display()
+			))
+		);
+	}
+
+	#[test]
+	fn test_post_checkout_after_branch_switch() {
+		let (_td, repo) = repo_init().unwrap();
+		let root = repo.workdir().unwrap();
+		let repo_path: &RepoPath = &root.to_path_buf().into();
+
+		let hook = b"#!/bin/sh
+echo \"$@\"
+exit 1
+";
+		git2_hooks::create_hook(
+			&repo,
+			git2_hooks::HOOK_POST_CHECKOUT,
+			hook,
+		);
+
+		let prev_head = crate::sync::get_head(repo_path).unwrap();
+		crate::sync::create_branch(repo_path, "feature").unwrap();
+		let new_head = crate::sync::commit(repo_path, "c2").unwrap();
+		crate::sync::checkout_branch(repo_path, "master").unwrap();
+
+		// the checkout stays no matter what the hook returns
+		let res =
+			hooks_post_checkout(repo_path, new_head, prev_head, true)
+				.unwrap();
+
+		assert_eq!(res.code(), Some(1));
+		assert_eq!(
+			res.msg(),
+			Some(format!("{new_head} {prev_head} 1\n"))
+		);
+		assert_eq!(
+			crate::sync::get_head(repo_path).unwrap(),
+			prev_head
+		);
+	}
+
+	#[test]
+	fn test_post_merge_failure_keeps_merge() {
+		let (_td, repo) = repo_init().unwrap();
+		let root = repo.workdir().unwrap();
+		let repo_path: &RepoPath = &root.to_path_buf().into();
+
+		let hook = b"#!/bin/sh
+echo \"post-merge $1\"
+exit 1
+";
+		git2_hooks::create_hook(
+			&repo,
+			git2_hooks::HOOK_POST_MERGE,
+			hook,
+		);
+
+		let head = crate::sync::get_head(repo_path).unwrap();
+
+		let res = hooks_post_merge(repo_path, false).unwrap();
+
+		assert_eq!(res.code(), Some(1));
+		assert_eq!(res.msg(), Some(String::from("post-merge 0\n")));
+		assert_eq!(crate::sync::get_head(repo_path).unwrap(), head);
+	}
+
+	#[test]
+	fn test_post_merge_records_squash_flag() {
+		let (_td, repo) = repo_init().unwrap();
+		let root = repo.workdir().unwrap();
+		let repo_path: &RepoPath = &root.to_path_buf().into();
+
+		let hook = b"#!/bin/sh
+echo \"$1\" >> \"$(dirname \"$0\")/../post-merge-flags\"
+";
+		git2_hooks::create_hook(
+			&repo,
+			git2_hooks::HOOK_POST_MERGE,
+			hook,
+		);
+
+		assert_eq!(
+			hooks_post_merge(repo_path, false).unwrap(),
+			HookResult::Ok
+		);
+		assert_eq!(
+			hooks_post_merge(repo_path, true).unwrap(),
+			HookResult::Ok
+		);
+
+		assert_eq!(
+			std::fs::read_to_string(
+				repo.path().join("post-merge-flags")
+			)
+			.unwrap(),
+			"0\n1\n"
+		);
+	}
+
+	#[test]
+	fn test_post_rewrite_after_amend() {
+		let (_td, repo) = repo_init().unwrap();
+		let root = repo.workdir().unwrap();
+		let repo_path: &RepoPath = &root.to_path_buf().into();
+
+		let hook = b"#!/bin/sh
+echo \"$1\"
+cat
+exit 1
+";
+		git2_hooks::create_hook(
+			&repo,
+			git2_hooks::HOOK_POST_REWRITE,
+			hook,
+		);
+
+		let old = crate::sync::get_head(repo_path).unwrap();
+		let new =
+			crate::sync::amend(repo_path, old, "amended").unwrap();
+
+		let res = hooks_post_rewrite(
+			repo_path,
+			PostRewriteSource::Amend,
+			&[(old, new)],
+		)
+		.unwrap();
+
+		assert_eq!(res.code(), Some(1));
+		assert_eq!(res.msg(), Some(format!("amend\n{old} {new}\n")));
+		assert_eq!(crate::sync::get_head(repo_path).unwrap(), new);
+	}
+
+	#[test]
+	fn test_hook_silent_failure() {
+		let (_td, repo) = repo_init().unwrap();
+		let root = repo.workdir().unwrap();
+
+		let hook = b"#!/usr/bin/env sh
+	exit 3
+			";
+
+		git2_hooks::create_hook(
+			&repo,
+			git2_hooks::HOOK_PRE_COMMIT,
+			hook,
+		);
+
+		let res =
+			hooks_pre_commit(&root.to_path_buf().into()).unwrap();
+
+		assert_eq!(res.code(), Some(3));
+		assert_eq!(
+			res.msg(),
+			Some(String::from(
+				"hook pre-commit exited with code 3 without output"
+			))
+		);
+	}
+
+	#[test]
+	fn test_hook_env() {
+		let (_td, repo) = repo_init().unwrap();
+		let root = repo.workdir().unwrap();
+		let repo_path: &RepoPath = &root.to_path_buf().into();
+
+		git2_hooks::create_hook(
+			&repo,
+			git2_hooks::HOOK_PRE_COMMIT,
+			b"#!/bin/sh\necho \"$GITUI $PROJECT\"\nexit 1\n",
+		);
+
+		assert_eq!(
+			hooks_pre_commit(repo_path).unwrap().msg().as_deref(),
+			Some("1 \n")
+		);
+
+		let res = run_hook_cancellable(
+			repo_path,
+			HookType::PreCommit,
+			&[],
+			&[("PROJECT", "gitui")],
+			&CancellationToken::new(),
+		)
+		.unwrap();
+		assert_eq!(res.msg().as_deref(), Some("1 gitui\n"));
+	}
+
+	#[test]
+	fn test_hook_failure_keeps_streams_apart() {
+		let (_td, repo) = repo_init().unwrap();
+		let root = repo.workdir().unwrap();
+
+		let hook = b"#!/bin/sh
+echo '{\"errors\":1}'
+echo 'run the linter locally' >&2
+exit 2
+";
+
+		git2_hooks::create_hook(
+			&repo,
+			git2_hooks::HOOK_PRE_COMMIT,
+			hook,
+		);
+
+		let res =
+			hooks_pre_commit(&root.to_path_buf().into()).unwrap();
+
+		assert_eq!(
+			res,
+			HookResult::NotOk(HookFailure {
+				hook: repo
+					.path()
+					.join("hooks")
+					.join(git2_hooks::HOOK_PRE_COMMIT),
+				code: Some(2),
+				stdout: b"{\"errors\":1}\n".to_vec(),
+				stderr: b"run the linter locally\n".to_vec(),
+				signal: None,
+				likely_oom: false,
+			})
+		);
+		assert_eq!(
+			res.msg().as_deref(),
+			Some("{\"errors\":1}\nrun the linter locally\n")
+		);
+	}
+
+	#[test]
+	fn test_hook_failure_raw_output() {
+		let (_td, repo) = repo_init().unwrap();
+		let root = repo.workdir().unwrap();
+
+		// latin-1 encoded, e.g. by a localized tool
+		let hook = b"#!/bin/sh
+printf 'gr\\374n'
+exit 1
+";
+
+		git2_hooks::create_hook(
+			&repo,
+			git2_hooks::HOOK_PRE_COMMIT,
+			hook,
+		);
+
+		let res =
+			hooks_pre_commit(&root.to_path_buf().into()).unwrap();
+
+		let HookResult::NotOk(failure) = &res else {
+			unreachable!("{res:?}")
+		};
+		assert_eq!(failure.stdout, b"gr\xfcn");
+		assert_eq!(failure.msg(), "gr\u{fffd}n");
+	}
+
+	#[test]
+	fn test_hook_exit_code() {
+		let (_td, repo) = repo_init().unwrap();
+		let root = repo.workdir().unwrap();
+
+		// e.g. a tool the hook calls is not installed
+		let hook = b"#!/usr/bin/env sh
+	echo 'lint: not found'
+	exit 127
+			";
+
+		git2_hooks::create_hook(
+			&repo,
+			git2_hooks::HOOK_PRE_COMMIT,
+			hook,
+		);
+
+		let res =
+			hooks_pre_commit(&root.to_path_buf().into()).unwrap();
+
+		assert!(!res.is_ok());
+		assert_eq!(res.code(), Some(127));
+		assert_eq!(res.msg().as_deref(), Some("lint: not found\n"));
+	}
+
+	#[test]
+	#[cfg(unix)]
+	fn test_hook_likely_oom() {
+		let (_td, repo) = repo_init().unwrap();
+		let root = repo.workdir().unwrap();
+
+		let hook = b"#!/bin/sh
+kill -KILL $$
+			";
+
+		git2_hooks::create_hook(
+			&repo,
+			git2_hooks::HOOK_PRE_COMMIT,
+			hook,
+		);
+
+		let res =
+			hooks_pre_commit(&root.to_path_buf().into()).unwrap();
+
+		assert_eq!(res.code(), None);
+		assert_eq!(res.msg(), Some(String::from(
+					"hook pre-commit was killed (signal 9), most likely by the system running out of memory\n"
+				)));
+	}
+
+	#[test]
+	fn test_hooks_config_deny() {
+		let (_td, repo) = repo_init().unwrap();
+		let root = repo.workdir().unwrap();
+
+		let hook = b"#!/usr/bin/env sh
+	echo 'rejected'
+	exit 1
+			";
+
+		git2_hooks::create_hook(
+			&repo,
+			git2_hooks::HOOK_PRE_COMMIT,
+			hook,
+		);
+
+		repo.config()
+			.unwrap()
+			.set_str("gitui.hooks.deny", git2_hooks::HOOK_PRE_COMMIT)
+			.unwrap();
+
+		let res =
+			hooks_pre_commit(&root.to_path_buf().into()).unwrap();
+
+		assert_eq!(res, HookResult::Ok);
+	}
+
+	// make sure we run the hooks with the correct pwd.
+	// for non-bare repos this is the dir of the worktree
+	// unfortunately does not work on windows
+	#[test]
+	#[cfg(unix)]
+	fn test_pre_commit_workdir() {
+		let (_td, repo) = repo_init().unwrap();
+		let root = repo.workdir().unwrap();
+		let repo_path: &RepoPath = &root.to_path_buf().into();
+
+		let hook = b"#!/bin/sh
+	echo \"$(pwd)\"
+	exit 1
+		";
+		git2_hooks::create_hook(
+			&repo,
+			git2_hooks::HOOK_PRE_COMMIT,
+			hook,
+		);
+		let res = hooks_pre_commit(repo_path).unwrap();
+		if let Some(res) = res.msg() {
+			assert_eq!(
+				res.trim_end().trim_end_matches('/'),
+				// TODO: fix if output isn't utf8.
+				root.to_string_lossy().trim_end_matches('/'),
+			);
+		} else {
+			assert!(false);
+		}
+	}
+
+	#[test]
+	fn test_pre_commit_streaming() {
+		let (_td, repo) = repo_init().unwrap();
+		let root = repo.workdir().unwrap();
+		let repo_path: &RepoPath = &root.to_path_buf().into();
+
+		let hook = b"#!/bin/sh
+	echo 'linting'
+	echo 'lint failed' >&2
+	printf 'no newline'
+	exit 1
+		";
+		git2_hooks::create_hook(
+			&repo,
+			git2_hooks::HOOK_PRE_COMMIT,
+			hook,
+		);
+
+		let mut lines = Vec::new();
+		let res =
+			hooks_pre_commit_streaming(repo_path, None, |line| {
+				lines.push(line);
+			})
+			.unwrap();
+
+		assert!(matches!(
+			res,
+			HookResult::NotOk(HookFailure { code: Some(1), .. })
+		));
+
+		let stdout = lines
+			.iter()
+			.filter(|l| l.stream == OutputStream::Stdout)
+			.map(|l| l.line.as_str())
+			.collect::<Vec<_>>();
+		let stderr = lines
+			.iter()
+			.filter(|l| l.stream == OutputStream::Stderr)
+			.map(|l| l.line.as_str())
+			.collect::<Vec<_>>();
+
+		assert_eq!(stdout, vec!["linting", "no newline"]);
+		assert_eq!(stderr, vec!["lint failed"]);
+	}
+
+	#[test]
+	fn test_hooks_commit_msg_reject_in_subfolder() {
+		let (_td, repo) = repo_init().unwrap();
+		let root = repo.workdir().unwrap();
+
+		let hook = b"#!/bin/sh
+	echo 'msg' > \"$1\"
+	echo 'rejected'
+	exit 1
+		";
+
+		git2_hooks::create_hook(
+			&repo,
+			git2_hooks::HOOK_COMMIT_MSG,
+			hook,
+		);
+
+		let subfolder = root.join("foo/");
+		std::fs::create_dir_all(&subfolder).unwrap();
+
+		let mut msg = String::from("test");
+		let res =
+			hooks_commit_msg(&subfolder.into(), &mut msg).unwrap();
+
+		assert_eq!(res.code(), Some(1));
+		assert_eq!(res.msg(), Some(String::from("rejected\n")));
+
+		assert_eq!(msg, String::from("msg\n"));
+	}
+
+	#[test]
+	fn test_hooks_applypatch_msg_in_subfolder() {
+		let (_td, repo) = repo_init().unwrap();
+		let root = repo.workdir().unwrap();
+
+		let hook = b"#!/bin/sh
+	echo \"edited in $GIT_PREFIX\" > \"$1\"
+	exit 0
+		";
+
+		git2_hooks::create_hook(
+			&repo,
+			git2_hooks::HOOK_APPLYPATCH_MSG,
+			hook,
+		);
+
+		let subfolder = root.join("foo/");
+		std::fs::create_dir_all(&subfolder).unwrap();
+
+		let mut msg = String::from("test");
+		let res = hooks_applypatch_msg(&subfolder.into(), &mut msg)
+			.unwrap();
+
+		assert_eq!(res, HookResult::Ok);
+		assert_eq!(msg, String::from("edited in foo/\n"));
+	}
+
+	#[test]
+	fn test_hooks_commit_msg_reject_in_hooks_folder_githooks_moved_absolute(
+	) {
+		let (_td, repo) = repo_init().unwrap();
+		let root = repo.workdir().unwrap();
+		let mut config = repo.config().unwrap();
+
+		const HOOKS_DIR: &str = "my_hooks";
+		config.set_str("core.hooksPath", HOOKS_DIR).unwrap();
+
+		let hook = b"#!/bin/sh
+	echo 'msg' > \"$1\"
+	echo 'rejected'
+	exit 1
+	        ";
+		let hooks_folder = root.join(HOOKS_DIR);
+		std::fs::create_dir_all(&hooks_folder).unwrap();
+		create_hook_in_path(&hooks_folder.join("commit-msg"), hook);
+
+		let mut msg = String::from("test");
+		let res =
+			hooks_commit_msg(&hooks_folder.into(), &mut msg).unwrap();
+		assert_eq!(res.code(), Some(1));
+		assert_eq!(res.msg(), Some(String::from("rejected\n")));
+
+		assert_eq!(msg, String::from("msg\n"));
+	}
+
+	fn pre_rebase(
+		repo: &Repository,
+		hook: Option<&[u8]>,
+	) -> std::result::Result<(), HookRejection> {
+		if let Some(hook) = hook {
+			git2_hooks::create_hook(
+				repo,
+				git2_hooks::HOOK_PRE_REBASE,
+				hook,
+			);
+		}
+
+		let root = repo.path().parent().unwrap();
+		run_pre_operation_hook(
+			&root.to_path_buf().into(),
+			HookType::PreRebase,
+			&["main"],
+		)
+	}
+
+	/// runs a prepare-commit-msg hook recording its source (and commit)
+	/// for a message written `for_`
+	fn recorded_source(
+		repo: &Repository,
+		for_: CommitMsgFor,
+	) -> String {
+		let hook = b"#!/bin/sh
+echo \"$2 $3\" > \"$(dirname \"$1\")/prepare-commit-msg-args\"
+";
+		git2_hooks::create_hook(
+			repo,
+			git2_hooks::HOOK_PREPARE_COMMIT_MSG,
+			hook,
+		);
+
+		let repo_path: RepoPath =
+			repo.workdir().unwrap().to_path_buf().into();
+		let source =
+			prepare_commit_msg_source(&repo_path, for_).unwrap();
+
+		let mut msg = String::from("msg");
+		let res =
+			hooks_prepare_commit_msg(&repo_path, source, &mut msg)
+				.unwrap();
+		assert_eq!(res, HookResult::Ok);
+
+		std::fs::read_to_string(
+			repo.path().join("prepare-commit-msg-args"),
+		)
+		.unwrap()
+		.trim()
+		.to_string()
+	}
+
+	#[test]
+	fn test_prepare_commit_msg_failure_keeps_msg() {
+		let (_td, repo) = crate::sync::tests::repo_init().unwrap();
+		let repo_path: RepoPath =
+			repo.workdir().unwrap().to_path_buf().into();
+
+		let hook = b"#!/bin/sh
+printf 'fix: something\\n\\n# ticket missing\\n' > \"$1\"
+echo 'no ticket found'
+exit 1
+";
+		git2_hooks::create_hook(
+			&repo,
+			git2_hooks::HOOK_PREPARE_COMMIT_MSG,
+			hook,
+		);
+
+		let mut msg = String::from("fix: something");
+		let res = hooks_prepare_commit_msg(
+			&repo_path,
+			PrepareCommitMsgSource::Message,
+			&mut msg,
+		)
+		.unwrap();
+
+		assert_eq!(res.code(), Some(1));
+		assert_eq!(
+			res.msg(),
+			Some(String::from("no ticket found\n"))
+		);
+		assert_eq!(msg, "fix: something\n\n# ticket missing\n");
+	}
+
+	#[test]
+	fn test_prepare_commit_msg_source_new() {
+		let (_td, repo) = crate::sync::tests::repo_init().unwrap();
+
+		assert_eq!(
+			recorded_source(
+				&repo,
+				CommitMsgFor::New { template: false }
+			),
+			"message"
+		);
+		assert_eq!(
+			recorded_source(
+				&repo,
+				CommitMsgFor::New { template: true }
+			),
+			"template"
+		);
+	}
+
+	#[test]
+	fn test_prepare_commit_msg_source_amend() {
+		let (_td, repo) = crate::sync::tests::repo_init().unwrap();
+		let head = repo.head().unwrap().target().unwrap();
+
+		// an edited commit wins over a pending merge
+		std::fs::write(repo.path().join("MERGE_MSG"), "merge")
+			.unwrap();
+
+		assert_eq!(
+			recorded_source(&repo, CommitMsgFor::Commit(head.into())),
+			format!("commit {head}")
+		);
+	}
+
+	#[test]
+	fn test_prepare_commit_msg_source_merge() {
+		let (_td, repo) = crate::sync::tests::repo_init().unwrap();
+
+		std::fs::write(repo.path().join("MERGE_MSG"), "merge")
+			.unwrap();
+		std::fs::write(repo.path().join(SQUASH_MSG), "squash")
+			.unwrap();
+
+		assert_eq!(
+			recorded_source(
+				&repo,
+				CommitMsgFor::New { template: true }
+			),
+			"merge"
+		);
+	}
+
+	#[test]
+	fn test_prepare_commit_msg_source_squash() {
+		let (_td, repo) = crate::sync::tests::repo_init().unwrap();
+		let repo_path: RepoPath =
+			repo.workdir().unwrap().to_path_buf().into();
 
-		let res = hooks_post_commit(&subfolder.into()).unwrap();
+		assert_eq!(
+			crate::sync::squash_msg(&repo_path).unwrap(),
+			None
+		);
+
+		std::fs::write(repo.path().join(SQUASH_MSG), "squashed")
+			.unwrap();
 
 		assert_eq!(
-			res,
-			HookResult::NotOk(String::from("rejected\n"))
+			crate::sync::squash_msg(&repo_path).unwrap().as_deref(),
+			Some("squashed")
+		);
+		assert_eq!(
+			recorded_source(
+				&repo,
+				CommitMsgFor::New { template: true }
+			),
+			"squash"
 		);
 	}
 
-	// make sure we run the hooks with the correct pwd.
-	// for non-bare repos this is the dir of the worktree
-	// unfortunately does not work on windows
 	#[test]
-	#[cfg(unix)]
-	fn test_pre_commit_workdir() {
+	fn test_pre_operation_hook_proceeds() {
 		let (_td, repo) = repo_init().unwrap();
-		let root = repo.workdir().unwrap();
-		let repo_path: &RepoPath = &root.to_path_buf().into();
 
-		let hook = b"#!/bin/sh
-	echo \"$(pwd)\"
-	exit 1
+		// no hook at all
+		assert!(pre_rebase(&repo, None).is_ok());
+
+		let hook = b"#!/usr/bin/env sh
+test \"$1\" = main
 		";
-		git2_hooks::create_hook(
-			&repo,
-			git2_hooks::HOOK_PRE_COMMIT,
-			hook,
-		);
-		let res = hooks_pre_commit(repo_path).unwrap();
-		if let HookResult::NotOk(res) = res {
-			assert_eq!(
-				res.trim_end().trim_end_matches('/'),
-				// TODO: fix if output isn't utf8.
-				root.to_string_lossy().trim_end_matches('/'),
-			);
-		} else {
-			assert!(false);
-		}
+		assert!(pre_rebase(&repo, Some(hook)).is_ok());
 	}
 
 	#[test]
-	fn test_hooks_commit_msg_reject_in_subfolder() {
+	fn test_pre_operation_hook_disabled_proceeds() {
 		let (_td, repo) = repo_init().unwrap();
-		let root = repo.workdir().unwrap();
 
-		let hook = b"#!/bin/sh
-	echo 'msg' > \"$1\"
-	echo 'rejected'
-	exit 1
-		";
+		repo.config()
+			.unwrap()
+			.set_str("gitui.hooks.deny", git2_hooks::HOOK_PRE_REBASE)
+			.unwrap();
 
-		git2_hooks::create_hook(
+		assert!(pre_rebase(&repo, Some(b"#!/bin/sh\nexit 1")).is_ok());
+	}
+
+	#[test]
+	fn test_pre_operation_hook_rejects() {
+		let (_td, repo) = repo_init().unwrap();
+
+		let res = pre_rebase(
 			&repo,
-			git2_hooks::HOOK_COMMIT_MSG,
-			hook,
+			Some(b"#!/usr/bin/env sh\necho 'not now'\nexit 1"),
 		);
 
-		let subfolder = root.join("foo/");
-		std::fs::create_dir_all(&subfolder).unwrap();
+		assert!(matches!(
+			res,
+			Err(HookRejection::Rejected {
+				hook: HookType::PreRebase,
+				result: HookResult::NotOk(HookFailure {
+					ref stdout,
+					code: Some(1),
+					..
+				}),
+			}) if stdout == b"not now\n"
+		));
+	}
+
+	#[test]
+	fn test_pre_operation_hook_timeout_aborts() {
+		let (_td, repo) = repo_init().unwrap();
+
+		repo.config()
+			.unwrap()
+			.set_i64("gitui.hooks.timeout", 100)
+			.unwrap();
 
-		let mut msg = String::from("test");
 		let res =
-			hooks_commit_msg(&subfolder.into(), &mut msg).unwrap();
+			pre_rebase(&repo, Some(b"#!/usr/bin/env sh\nsleep 1"));
 
-		assert_eq!(
+		assert!(matches!(
 			res,
-			HookResult::NotOk(String::from("rejected\n"))
-		);
+			Err(HookRejection::Rejected {
+				result: HookResult::TimedOut { .. },
+				..
+			})
+		));
+	}
 
-		assert_eq!(msg, String::from("msg\n"));
+	#[test]
+	fn test_pre_operation_hook_failing_to_run_aborts() {
+		let (_td, repo) = repo_init().unwrap();
+
+		std::fs::create_dir_all(
+			repo.path()
+				.join("hooks")
+				.join(git2_hooks::HOOK_PRE_REBASE),
+		)
+		.unwrap();
+
+		assert!(matches!(
+			pre_rebase(&repo, None),
+			Err(HookRejection::Failed {
+				hook: HookType::PreRebase,
+				..
+			})
+		));
 	}
 
 	#[test]
-	fn test_hooks_commit_msg_reject_in_hooks_folder_githooks_moved_absolute(
-	) {
+	fn test_hooks_respect_timeout() {
 		let (_td, repo) = repo_init().unwrap();
-		let root = repo.workdir().unwrap();
-		let mut config = repo.config().unwrap();
+		let root = repo.path().parent().unwrap();
 
-		const HOOKS_DIR: &str = "my_hooks";
-		config.set_str("core.hooksPath", HOOKS_DIR).unwrap();
+		let hook = b"#!/usr/bin/env sh
+    sleep 0.250
+        ";
 
-		let hook = b"#!/bin/sh
-	echo 'msg' > \"$1\"
-	echo 'rejected'
-	exit 1
-	        ";
-		let hooks_folder = root.join(HOOKS_DIR);
-		std::fs::create_dir_all(&hooks_folder).unwrap();
-		create_hook_in_path(&hooks_folder.join("commit-msg"), hook);
+		git2_hooks::create_hook(
+			&repo,
+			git2_hooks::HOOK_PRE_COMMIT,
+			hook,
+		);
+
+		let res = hooks_pre_commit_with_timeout(
+			&root.to_path_buf().into(),
+			Some(Duration::from_millis(200)),
+		)
+		.unwrap();
 
-		let mut msg = String::from("test");
-		let res =
-			hooks_commit_msg(&hooks_folder.into(), &mut msg).unwrap();
 		assert_eq!(
 			res,
-			HookResult::NotOk(String::from("rejected\n"))
+			HookResult::TimedOut {
+				hook: repo
+					.path()
+					.join("hooks")
+					.join(git2_hooks::HOOK_PRE_COMMIT),
+				stdout: String::new(),
+				stderr: String::new(),
+				timeout: Duration::from_millis(200),
+				source: TimeoutSource::Explicit,
+			}
 		);
-
-		assert_eq!(msg, String::from("msg\n"));
 	}
 
 	#[test]
-	fn test_hooks_respect_timeout() {
+	fn test_hooks_timeout_keeps_output() {
 		let (_td, repo) = repo_init().unwrap();
 		let root = repo.path().parent().unwrap();
 
 		let hook = b"#!/usr/bin/env sh
-    sleep 0.250
+echo 'linting'
+echo 'stalled' >&2
+sleep 5
         ";
 
 		git2_hooks::create_hook(
@@ -323,7 +2109,7 @@ mod tests {
 		);
 
 		let res = hooks_pre_commit_with_timeout(
-			&root.to_str().unwrap().into(),
+			&root.to_path_buf().into(),
 			Some(Duration::from_millis(200)),
 		)
 		.unwrap();
@@ -331,8 +2117,14 @@ mod tests {
 		assert_eq!(
 			res,
 			HookResult::TimedOut {
-				stdout: String::new(),
-				stderr: String::new()
+				hook: repo
+					.path()
+					.join("hooks")
+					.join(git2_hooks::HOOK_PRE_COMMIT),
+				stdout: String::from("linting\n"),
+				stderr: String::from("stalled\n"),
+				timeout: Duration::from_millis(200),
+				source: TimeoutSource::Explicit,
 			}
 		);
 	}
@@ -353,7 +2145,7 @@ mod tests {
 		);
 
 		let res = hooks_pre_commit_with_timeout(
-			&root.to_str().unwrap().into(),
+			&root.to_path_buf().into(),
 			Some(Duration::from_millis(150)),
 		)
 		.unwrap();
@@ -377,7 +2169,7 @@ mod tests {
 		);
 
 		let res = hooks_post_commit_with_timeout(
-			&root.to_str().unwrap().into(),
+			&root.to_path_buf().into(),
 			Some(Duration::ZERO),
 		)
 		.unwrap();
@@ -407,7 +2199,7 @@ echo 'after sleep' > {}
 		);
 
 		let res = hooks_pre_commit_with_timeout(
-			&root.to_str().unwrap().into(),
+			&root.to_path_buf().into(),
 			Some(Duration::from_millis(100)),
 		);
 
@@ -433,7 +2225,7 @@ sleep 30
 
 		let time_start = std::time::Instant::now();
 		let res = hooks_pre_commit_with_timeout(
-			&root.to_str().unwrap().into(),
+			&root.to_path_buf().into(),
 			Some(Duration::from_millis(150)),
 		)
 		.unwrap();
@@ -446,4 +2238,282 @@ sleep 30
 		assert!(elapsed.as_secs() < 15);
 		assert!(matches!(res, HookResult::TimedOut { .. }))
 	}
+
+	#[test]
+	fn test_no_verify_never_runs_hooks() {
+		let (_td, repo) = repo_init().unwrap();
+		let root = repo.workdir().unwrap();
+		let repo_path: &RepoPath = &root.to_path_buf().into();
+		let sentinels = tempdir().unwrap();
+
+		let sentinel = |hook: &str| sentinels.path().join(hook);
+		for hook in [
+			git2_hooks::HOOK_PRE_COMMIT,
+			git2_hooks::HOOK_COMMIT_MSG,
+			git2_hooks::HOOK_PREPARE_COMMIT_MSG,
+			git2_hooks::HOOK_POST_COMMIT,
+		] {
+			git2_hooks::create_hook(
+				&repo,
+				hook,
+				format!(
+					"#!/bin/sh\ntouch '{}'\nexit 1\n",
+					sentinel(hook).display()
+				)
+				.as_bytes(),
+			);
+		}
+
+		let behavior = HookBehavior::from_verify(false);
+		let mut msg = String::from("msg");
+
+		assert_eq!(
+			hooks_pre_commit_with_behavior(repo_path, behavior, None)
+				.unwrap(),
+			HookResult::Ok
+		);
+		assert_eq!(
+			hooks_commit_msg_with_behavior(
+				repo_path, &mut msg, behavior, None
+			)
+			.unwrap(),
+			HookResult::Ok
+		);
+		assert_eq!(
+			hooks_prepare_commit_msg_with_behavior(
+				repo_path,
+				PrepareCommitMsgSource::Message,
+				&mut msg,
+				behavior,
+				None
+			)
+			.unwrap(),
+			HookResult::Ok
+		);
+		assert_eq!(msg, "msg");
+
+		// post-commit still runs, like with `git commit --no-verify`
+		assert!(!hooks_post_commit(repo_path).unwrap().is_ok());
+
+		assert!(!sentinel(git2_hooks::HOOK_PRE_COMMIT).exists());
+		assert!(!sentinel(git2_hooks::HOOK_COMMIT_MSG).exists());
+		assert!(
+			!sentinel(git2_hooks::HOOK_PREPARE_COMMIT_MSG).exists()
+		);
+		assert!(sentinel(git2_hooks::HOOK_POST_COMMIT).exists());
+
+		assert!(!hooks_pre_commit_with_behavior(
+			repo_path,
+			HookBehavior::Verify,
+			None
+		)
+		.unwrap()
+		.is_ok());
+		assert!(sentinel(git2_hooks::HOOK_PRE_COMMIT).exists());
+	}
+
+	#[test]
+	fn test_last_hook_duration() {
+		let (_td, repo) = repo_init().unwrap();
+		let root = repo.workdir().unwrap();
+		let repo_path: &RepoPath = &root.to_path_buf().into();
+
+		// no other test runs this hook
+		git2_hooks::create_hook(
+			&repo,
+			git2_hooks::HOOK_PRE_APPLYPATCH,
+			b"#!/bin/sh\nsleep 0.2\nexit 1\n",
+		);
+
+		assert_eq!(
+			last_hook_duration(git2_hooks::HOOK_PRE_APPLYPATCH),
+			None
+		);
+		assert!(!hooks_pre_applypatch(repo_path).unwrap().is_ok());
+		assert!(
+			last_hook_duration(git2_hooks::HOOK_PRE_APPLYPATCH)
+				.unwrap() >= Duration::from_millis(200)
+		);
+	}
+
+	#[test]
+	fn test_read_hook_timeout() {
+		let (_td, repo) = repo_init().unwrap();
+		let root = repo.path().parent().unwrap();
+		let repo_path: &RepoPath = &root.to_path_buf().into();
+		let mut config = repo.config().unwrap();
+
+		assert_eq!(read_hook_timeout(repo_path), Duration::ZERO);
+
+		config.set_i64("gitui.hooksTimeout", 1500).unwrap();
+		assert_eq!(
+			read_hook_timeout(repo_path),
+			Duration::from_millis(1500)
+		);
+
+		config.set_i64("gitui.hooksTimeout", -20).unwrap();
+		assert_eq!(read_hook_timeout(repo_path), Duration::ZERO);
+
+		config.set_str("gitui.hooksTimeout", "soon").unwrap();
+		assert_eq!(read_hook_timeout(repo_path), Duration::ZERO);
+
+		config.remove("gitui.hooksTimeout").unwrap();
+		assert_eq!(read_hook_timeout(repo_path), Duration::ZERO);
+	}
+
+	#[test]
+	fn test_effective_hook_timeout() {
+		let (_td, repo) = repo_init().unwrap();
+		let root = repo.path().parent().unwrap();
+		let repo_path: &RepoPath = &root.to_path_buf().into();
+		let mut config = repo.config().unwrap();
+		let timeout = |hook: &str| {
+			effective_hook_timeout(repo_path, hook, None).as_millis()
+		};
+
+		assert_eq!(timeout("pre-commit"), 0);
+
+		config.set_i64("gitui.hooksTimeout", 1500).unwrap();
+		assert_eq!(timeout("pre-commit"), 1500);
+		assert_eq!(timeout("commit-msg"), 1500);
+
+		config
+			.set_i64("gitui.hooksTimeout.pre-commit", 60_000)
+			.unwrap();
+		assert_eq!(timeout("pre-commit"), 60_000);
+		assert_eq!(timeout("commit-msg"), 1500);
+
+		// zero turns the timeout off for that hook only
+		config.set_i64("gitui.hooksTimeout.commit-msg", 0).unwrap();
+		assert_eq!(timeout("commit-msg"), 0);
+
+		// and for all of them without falling back any further
+		set_hooks_timeout(repo_path, Some(Duration::ZERO)).unwrap();
+		assert_eq!(timeout("post-commit"), 0);
+
+		assert_eq!(
+			effective_hook_timeout(
+				repo_path,
+				"pre-commit",
+				Some(Duration::from_secs(1))
+			),
+			Duration::from_secs(1)
+		);
+	}
+
+	#[test]
+	fn test_hook_timeout_precedence() {
+		let (_td, repo) = repo_init().unwrap();
+		let root = repo.path().parent().unwrap();
+		let repo_path: &RepoPath = &root.to_path_buf().into();
+		let mut config = repo.config().unwrap();
+		let timeout = |hook: &str| {
+			effective_hook_timeout(repo_path, hook, None).as_millis()
+		};
+
+		config.set_i64("gitui.hooksTimeout", 1500).unwrap();
+		set_hooks_timeout(repo_path, Some(Duration::from_secs(20)))
+			.unwrap();
+		config
+			.set_i64("gitui.hooksTimeout.pre-commit", 60_000)
+			.unwrap();
+
+		// the per-hook key beats both global ones
+		assert_eq!(timeout("pre-commit"), 60_000);
+		// `gitui.hooks.timeout` beats `gitui.hooksTimeout`
+		assert_eq!(timeout("commit-msg"), 20_000);
+	}
+
+	#[test]
+	fn test_per_hook_timeout_kills() {
+		let (_td, repo) = repo_init().unwrap();
+		let root = repo.path().parent().unwrap();
+		let repo_path: &RepoPath = &root.to_path_buf().into();
+
+		git2_hooks::create_hook(
+			&repo,
+			git2_hooks::HOOK_PRE_COMMIT,
+			b"#!/bin/sh\nsleep 5\n",
+		);
+
+		repo.config()
+			.unwrap()
+			.set_i64("gitui.hooksTimeout.pre-commit", 200)
+			.unwrap();
+
+		let res = hooks_pre_commit(repo_path).unwrap();
+		let HookResult::TimedOut {
+			timeout, source, ..
+		} = res
+		else {
+			panic!("expected a timeout, got {res:?}");
+		};
+		assert_eq!(timeout, Duration::from_millis(200));
+		assert_eq!(source, TimeoutSource::HookConfig);
+	}
+
+	#[test]
+	fn test_hooks_timeout_from_config() {
+		let (_td, repo) = repo_init().unwrap();
+		let root = repo.path().parent().unwrap();
+		let repo_path: &RepoPath = &root.to_path_buf().into();
+
+		git2_hooks::create_hook(
+			&repo,
+			git2_hooks::HOOK_PRE_COMMIT,
+			b"#!/usr/bin/env sh\nsleep 30\n",
+		);
+		repo.config()
+			.unwrap()
+			.set_i64("gitui.hooksTimeout", 150)
+			.unwrap();
+
+		let res = hooks_pre_commit(repo_path).unwrap();
+		assert!(matches!(res, HookResult::TimedOut { .. }));
+
+		// gitui.hooks.timeout takes precedence
+		set_hooks_timeout(repo_path, Some(Duration::from_secs(60)))
+			.unwrap();
+		git2_hooks::create_hook(
+			&repo,
+			git2_hooks::HOOK_PRE_COMMIT,
+			b"#!/usr/bin/env sh\nsleep 0.3\n",
+		);
+
+		let res = hooks_pre_commit(repo_path).unwrap();
+		assert_eq!(res, HookResult::Ok);
+	}
+
+	#[test]
+	fn test_set_hooks_config() {
+		let (_td, repo) = repo_init().unwrap();
+		let root = repo.path().parent().unwrap();
+		let repo_path: &RepoPath = &root.to_path_buf().into();
+
+		set_hooks_timeout(repo_path, Some(Duration::from_millis(50)))
+			.unwrap();
+		set_hooks_disabled(repo_path, true).unwrap();
+		set_hooks_deny(repo_path, &[String::from("pre-commit")])
+			.unwrap();
+
+		let local = repo
+			.config()
+			.unwrap()
+			.open_level(ConfigLevel::Local)
+			.unwrap();
+		assert_eq!(local.get_i64("gitui.hooks.timeout").unwrap(), 50);
+
+		let config = hooks_config(repo_path).unwrap();
+		assert_eq!(config.timeout, Some(Duration::from_millis(50)));
+		assert!(config.disabled);
+		assert_eq!(config.deny, vec!["pre-commit"]);
+
+		set_hooks_timeout(repo_path, None).unwrap();
+		set_hooks_disabled(repo_path, false).unwrap();
+
+		let config = hooks_config(repo_path).unwrap();
+		assert_eq!(config.timeout, None);
+		assert!(config.is_enabled("post-commit"));
+		assert!(!config.is_enabled("pre-commit"));
+	}
 }
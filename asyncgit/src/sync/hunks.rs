@@ -1,5 +1,7 @@
 use super::{
 	diff::{get_diff_raw, DiffOptions, HunkHeader},
+	filter::clean_index_entry,
+	index_cache::invalidate_index_cache_repo,
 	RepoPath,
 };
 use crate::{
@@ -32,6 +34,10 @@ pub fn stage_hunk(
 	});
 
 	repo.apply(&diff, ApplyLocation::Index, Some(&mut opt))?;
+	invalidate_index_cache_repo(&repo);
+
+	// the applied lines come straight from the worktree
+	clean_index_entry(&repo, file_path)?;
 
 	Ok(())
 }
@@ -143,6 +149,7 @@ pub fn unstage_hunk(
 		});
 
 		repo.apply(&diff, ApplyLocation::Index, Some(&mut opt))?;
+		invalidate_index_cache_repo(&repo);
 	}
 
 	Ok(count == 1)
@@ -153,14 +160,119 @@ mod tests {
 	use super::*;
 	use crate::{
 		error::Result,
-		sync::{diff::get_diff, tests::repo_init_empty},
+		sync::{
+			diff::get_diff,
+			tests::{repo_init, repo_init_empty, write_commit_file},
+			utils::repo_write_file,
+		},
 	};
+	use git2::Repository;
 	use std::{
 		fs::{self, File},
 		io::Write,
 		path::Path,
 	};
 
+	const FILE: &str = "foo.txt";
+
+	/// first and last line differ, so that we get two hunks
+	fn content(first: &str, last: &str) -> String {
+		let mid: String =
+			(1..10).map(|i| format!("line {i}\n")).collect();
+		format!("{first}\n{mid}{last}")
+	}
+
+	fn index_blob(repo: &Repository) -> Vec<u8> {
+		let mut index = repo.index().unwrap();
+		index.read(true).unwrap();
+		let entry = index.get_path(Path::new(FILE), 0).unwrap();
+		repo.find_blob(entry.id).unwrap().content().to_vec()
+	}
+
+	/// stages the last hunk of the worktree change from `old` to `new`,
+	/// checks the index content and unstages it again
+	fn stage_unstage_last_hunk(old: &str, new: &str, staged: &str) {
+		let (_td, repo) = repo_init().unwrap();
+		let root = repo.path().parent().unwrap();
+		let repo_path: &RepoPath =
+			&root.as_os_str().to_str().unwrap().into();
+
+		write_commit_file(&repo, FILE, old, "c1");
+		repo_write_file(&repo, FILE, new).unwrap();
+
+		let diff = get_diff(repo_path, FILE, false, None).unwrap();
+		assert_eq!(diff.hunks.len(), 2);
+
+		stage_hunk(repo_path, FILE, diff.hunks[1].header_hash, None)
+			.unwrap();
+
+		assert_eq!(
+			String::from_utf8(index_blob(&repo)).unwrap(),
+			staged
+		);
+
+		let diff = get_diff(repo_path, FILE, true, None).unwrap();
+		assert_eq!(diff.hunks.len(), 1);
+
+		assert!(unstage_hunk(
+			repo_path,
+			FILE,
+			diff.hunks[0].header_hash,
+			None,
+		)
+		.unwrap());
+
+		assert_eq!(
+			String::from_utf8(index_blob(&repo)).unwrap(),
+			old
+		);
+	}
+
+	#[test]
+	fn test_hunk_remove_newline_at_eof() {
+		stage_unstage_last_hunk(
+			&content("a", "end\n"),
+			&content("b", "end"),
+			&content("a", "end"),
+		);
+	}
+
+	#[test]
+	fn test_hunk_add_newline_at_eof() {
+		stage_unstage_last_hunk(
+			&content("a", "end"),
+			&content("b", "end\n"),
+			&content("a", "end\n"),
+		);
+	}
+
+	#[test]
+	fn test_hunk_modify_last_line_without_newline() {
+		stage_unstage_last_hunk(
+			&content("a", "end"),
+			&content("b", "END"),
+			&content("a", "END"),
+		);
+	}
+
+	#[test]
+	fn test_hunk_modify_last_line_and_remove_newline() {
+		stage_unstage_last_hunk(
+			&content("a", "end\n"),
+			&content("b", "END"),
+			&content("a", "END"),
+		);
+	}
+
+	#[test]
+	fn test_hunk_modify_last_line_and_add_newline() {
+		stage_unstage_last_hunk(
+			&content("a", "end"),
+			&content("b", "END\n"),
+			&content("a", "END\n"),
+		);
+	}
+
 	#[test]
 	fn reset_untracked_file_which_will_not_find_hunk() -> Result<()> {
 		let file_path = Path::new("foo/foo.txt");
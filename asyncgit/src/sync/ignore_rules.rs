@@ -0,0 +1,765 @@
+//! which ignore rule excludes a file and where it comes from. the rules
+//! are looked up like git does: the `.gitignore` files from the file's
+//! directory up to the work dir, then `.git/info/exclude` and last the
+//! global `core.excludesFile` (see [`global_excludes_file`]).
+//! see [`check_ignore`] and [`get_ignored_files`]
+
+use super::{utils::work_dir, RepoPath};
+use crate::{
+	error::{Error, Result},
+	sync::repository::repo,
+};
+use git2::{Repository, StatusOptions, StatusShow};
+use gix_glob::{pattern::Case, wildmatch};
+use scopetime::scope_time;
+use std::{
+	collections::HashMap,
+	fs,
+	path::{Path, PathBuf},
+};
+
+/// a line of an ignore file
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct IgnoreRule {
+	/// the ignore file, relative to the work dir if it is inside of it
+	pub source: String,
+	/// 1-based
+	pub line: usize,
+	/// as written in the file, including a leading `!`
+	pub pattern: String,
+}
+
+impl IgnoreRule {
+	/// a `!pattern` taking back what other rules ignore
+	pub fn is_negated(&self) -> bool {
+		self.pattern.starts_with('!')
+	}
+}
+
+/// an ignored file or directory (ending in `/`) of the work dir
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct IgnoredFile {
+	/// relative to the work dir
+	pub path: String,
+	/// the rule ignoring it, `None` if it is ignored by something other
+	/// than an ignore file
+	pub rule: Option<IgnoreRule>,
+}
+
+/// what [`check_ignore`] found out about a path
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct IgnoreCheck {
+	/// the path is in the index, ignore rules do not apply to it
+	pub tracked: bool,
+	/// the rule deciding about the path: the one ignoring it or a
+	/// negated one taking that back, `None` if no rule matches
+	pub rule: Option<IgnoreRule>,
+}
+
+impl IgnoreCheck {
+	/// the path is ignored
+	pub fn is_ignored(&self) -> bool {
+		!self.tracked
+			&& self
+				.rule
+				.as_ref()
+				.is_some_and(|rule| !rule.is_negated())
+	}
+}
+
+/// `git check-ignore -v`: the rule deciding whether `path` is ignored.
+///
+/// `path` is relative to the work dir (or absolute inside of it) and
+/// does not need to exist, a trailing `/` checks it as a directory
+/// (existing directories are detected anyway). like git the rules are
+/// reported for tracked files as well, see [`IgnoreCheck::tracked`]
+pub fn check_ignore(
+	repo_path: &RepoPath,
+	path: &str,
+) -> Result<IgnoreCheck> {
+	scope_time!("check_ignore");
+
+	let repo = repo(repo_path)?;
+	let workdir = work_dir(&repo)?;
+
+	let path = path.trim();
+	let path = Path::new(path)
+		.strip_prefix(workdir)
+		.ok()
+		.and_then(Path::to_str)
+		.unwrap_or(path);
+	let path = path.trim_start_matches("./");
+	let is_dir = path.ends_with('/') || workdir.join(path).is_dir();
+	let path = path.trim_end_matches('/');
+
+	if path.is_empty() {
+		return Err(Error::Generic(String::from(
+			"no path to check given",
+		)));
+	}
+
+	let tracked =
+		repo.index()?.get_path(Path::new(path), 0).is_some();
+	let rule = IgnoreRules::new(&repo)?.find(&if is_dir {
+		format!("{path}/")
+	} else {
+		path.to_string()
+	});
+
+	Ok(IgnoreCheck { tracked, rule })
+}
+
+/// `core.excludesFile`, git's default `$XDG_CONFIG_HOME/git/ignore`
+/// (`~/.config/git/ignore` without `XDG_CONFIG_HOME`) if it is not set.
+/// `~/` is expanded, relative paths are relative to the work dir
+pub fn global_excludes_file(
+	repo: &Repository,
+) -> Result<Option<PathBuf>> {
+	let home = dirs::home_dir();
+
+	if let Ok(path) = repo.config()?.get_string("core.excludesFile") {
+		let path = match path.strip_prefix("~/") {
+			Some(rest) => match &home {
+				Some(home) => home.join(rest),
+				None => return Ok(None),
+			},
+			None => PathBuf::from(path),
+		};
+
+		return Ok(Some(match repo.workdir() {
+			Some(workdir) if path.is_relative() => workdir.join(path),
+			_ => path,
+		}));
+	}
+
+	Ok(std::env::var_os("XDG_CONFIG_HOME")
+		.filter(|dir| !dir.is_empty())
+		.map(PathBuf::from)
+		.or_else(|| home.map(|home| home.join(".config")))
+		.map(|config| config.join("git").join("ignore")))
+}
+
+/// adds the rules of [`global_excludes_file`] to the ignore rules of
+/// `repo` (only this instance).
+///
+/// libgit2 finds that file through its own global search path, which is
+/// resolved once per process and does not necessarily match where git
+/// looks
+pub fn apply_global_excludes(repo: &Repository) -> Result<()> {
+	if let Some(file) = global_excludes_file(repo)? {
+		if let Ok(rules) = fs::read_to_string(file) {
+			repo.add_ignore_rule(&rules)?;
+		}
+	}
+
+	Ok(())
+}
+
+/// all ignored files and directories of the work dir (the content of
+/// ignored directories is not listed), along with the rule ignoring
+/// them. sorted by path
+pub fn get_ignored_files(
+	repo_path: &RepoPath,
+) -> Result<Vec<IgnoredFile>> {
+	scope_time!("get_ignored_files");
+
+	let repo = repo(repo_path)?;
+	apply_global_excludes(&repo)?;
+
+	let mut options = StatusOptions::default();
+	options
+		.show(StatusShow::Workdir)
+		.include_untracked(true)
+		.recurse_untracked_dirs(true)
+		.include_ignored(true)
+		.recurse_ignored_dirs(false);
+
+	let statuses = repo.statuses(Some(&mut options))?;
+	let mut rules = IgnoreRules::new(&repo)?;
+
+	let mut res = statuses
+		.iter()
+		.filter(|e| e.status().is_ignored())
+		.filter_map(|e| e.path().map(String::from))
+		.map(|path| {
+			let rule = rules.find(&path);
+			IgnoredFile { path, rule }
+		})
+		.collect::<Vec<_>>();
+
+	res.sort_by(|a, b| a.path.cmp(&b.path));
+
+	Ok(res)
+}
+
+/// the lines of an ignore file applying to the paths below `base`
+struct IgnoreFile {
+	source: String,
+	/// `""` or a directory ending in `/`
+	base: String,
+	lines: Vec<String>,
+}
+
+impl IgnoreFile {
+	fn read(
+		source: String,
+		base: String,
+		path: &Path,
+	) -> Option<Self> {
+		let content = fs::read_to_string(path).ok()?;
+
+		Some(Self {
+			source,
+			base,
+			lines: content.lines().map(String::from).collect(),
+		})
+	}
+
+	/// the last line matching `path`, relative to the work dir
+	fn matching(
+		&self,
+		path: &str,
+		is_dir: bool,
+		case: Case,
+	) -> Option<IgnoreRule> {
+		let path = path.strip_prefix(&self.base)?;
+
+		self.lines.iter().enumerate().rev().find_map(|(idx, line)| {
+			let pattern = trim_pattern(line)?;
+			let glob = gix_glob::parse(pattern)?;
+
+			glob.matches_repo_relative_path(
+				path.into(),
+				path.rfind('/').map(|pos| pos + 1),
+				Some(is_dir),
+				case,
+				wildmatch::Mode::NO_MATCH_SLASH_LITERAL,
+			)
+			.then(|| IgnoreRule {
+				source: self.source.clone(),
+				line: idx + 1,
+				pattern: pattern.to_string(),
+			})
+		})
+	}
+}
+
+/// `None` for comments and empty lines, trailing spaces are dropped
+/// unless escaped
+fn trim_pattern(line: &str) -> Option<&str> {
+	if line.starts_with('#') {
+		return None;
+	}
+
+	let mut pattern = line.trim_end_matches(['\r', '\n']);
+	while pattern.ends_with(' ') && !pattern.ends_with("\\ ") {
+		pattern = &pattern[..pattern.len() - 1];
+	}
+
+	(!pattern.is_empty()).then_some(pattern)
+}
+
+/// the ignore files of a repo, `.gitignore` files are read on demand
+struct IgnoreRules {
+	workdir: PathBuf,
+	/// `.git/info/exclude` and the global excludes, in this order
+	global: Vec<IgnoreFile>,
+	/// `.gitignore` by directory (`""` or ending in `/`)
+	gitignores: HashMap<String, Option<IgnoreFile>>,
+	case: Case,
+}
+
+impl IgnoreRules {
+	fn new(repo: &Repository) -> Result<Self> {
+		let workdir = work_dir(repo)?.to_path_buf();
+
+		let mut global = Vec::new();
+		let exclude = repo.path().join("info").join("exclude");
+		global.extend(IgnoreFile::read(
+			display_path(&workdir, &exclude),
+			String::new(),
+			&exclude,
+		));
+		if let Some(file) = global_excludes_file(repo)? {
+			global.extend(IgnoreFile::read(
+				display_path(&workdir, &file),
+				String::new(),
+				&file,
+			));
+		}
+
+		let case = if repo
+			.config()?
+			.get_bool("core.ignoreCase")
+			.unwrap_or_default()
+		{
+			Case::Fold
+		} else {
+			Case::Sensitive
+		};
+
+		Ok(Self {
+			workdir,
+			global,
+			gitignores: HashMap::new(),
+			case,
+		})
+	}
+
+	/// the rule deciding about `path` (relative to the work dir, a
+	/// directory ends in `/`). an ignored parent directory ignores
+	/// everything in it, no matter what rules there are for the content
+	fn find(&mut self, path: &str) -> Option<IgnoreRule> {
+		let (path, is_dir) = path
+			.strip_suffix('/')
+			.map_or((path, false), |dir| (dir, true));
+
+		for (pos, _) in path.match_indices('/') {
+			if let Some(rule) = self
+				.matching(&path[..pos], true)
+				.filter(|rule| !rule.is_negated())
+			{
+				return Some(rule);
+			}
+		}
+
+		self.matching(path, is_dir)
+	}
+
+	/// the rule matching `path` itself, ignoring its parents
+	fn matching(
+		&mut self,
+		path: &str,
+		is_dir: bool,
+	) -> Option<IgnoreRule> {
+		let dirs = std::iter::once(0)
+			.chain(path.match_indices('/').map(|(pos, _)| pos + 1))
+			.map(|end| path[..end].to_string())
+			.collect::<Vec<_>>();
+
+		for dir in dirs.into_iter().rev() {
+			let case = self.case;
+			if let Some(rule) = self
+				.gitignore(dir)
+				.and_then(|file| file.matching(path, is_dir, case))
+			{
+				return Some(rule);
+			}
+		}
+
+		self.global
+			.iter()
+			.find_map(|file| file.matching(path, is_dir, self.case))
+	}
+
+	fn gitignore(&mut self, dir: String) -> Option<&IgnoreFile> {
+		let workdir = &self.workdir;
+
+		self.gitignores
+			.entry(dir)
+			.or_insert_with_key(|dir| {
+				let source = format!("{dir}.gitignore");
+				let path = workdir.join(&source);
+				IgnoreFile::read(source, dir.clone(), &path)
+			})
+			.as_ref()
+	}
+}
+
+fn display_path(workdir: &Path, path: &Path) -> String {
+	path.strip_prefix(workdir)
+		.unwrap_or(path)
+		.to_string_lossy()
+		.into_owned()
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::sync::{
+		status::{get_status, StatusType},
+		tests::repo_init,
+		utils::repo_write_file,
+	};
+	use pretty_assertions::assert_eq;
+	use serial_test::serial;
+	use std::ffi::OsString;
+	use tempfile::TempDir;
+
+	/// runs `f` with `HOME` pointing at a temp dir (and no
+	/// `XDG_CONFIG_HOME`), git's global ignore file is written to the
+	/// default location if given
+	fn with_home<T>(
+		global_ignore: Option<&str>,
+		f: impl FnOnce(&Path) -> T,
+	) -> T {
+		let home = TempDir::new().unwrap();
+		if let Some(rules) = global_ignore {
+			let dir = home.path().join(".config").join("git");
+			fs::create_dir_all(&dir).unwrap();
+			fs::write(dir.join("ignore"), rules).unwrap();
+		}
+
+		let saved: Vec<(&str, Option<OsString>)> =
+			["HOME", "XDG_CONFIG_HOME"]
+				.into_iter()
+				.map(|var| (var, std::env::var_os(var)))
+				.collect();
+		std::env::set_var("HOME", home.path());
+		std::env::remove_var("XDG_CONFIG_HOME");
+
+		let res = f(home.path());
+
+		for (var, value) in saved {
+			match value {
+				Some(value) => std::env::set_var(var, value),
+				None => std::env::remove_var(var),
+			}
+		}
+
+		res
+	}
+
+	fn paths(repo_path: &RepoPath) -> Vec<String> {
+		get_status(repo_path, StatusType::WorkingDir, None)
+			.unwrap()
+			.into_iter()
+			.map(|item| item.path)
+			.collect()
+	}
+
+	#[test]
+	#[serial]
+	fn test_global_ignore_in_status() {
+		let (_td, repo) = repo_init().unwrap();
+		let repo_path: &RepoPath =
+			&repo.workdir().unwrap().to_str().unwrap().into();
+
+		repo_write_file(&repo, "a.txt", "a").unwrap();
+		repo_write_file(&repo, ".a.txt.swp", "swap").unwrap();
+
+		with_home(Some("# editors\n*.swp\n"), |home| {
+			assert_eq!(paths(repo_path), vec![String::from("a.txt")]);
+
+			assert_eq!(
+				get_ignored_files(repo_path).unwrap(),
+				vec![IgnoredFile {
+					path: String::from(".a.txt.swp"),
+					rule: Some(IgnoreRule {
+						source: home
+							.join(".config/git/ignore")
+							.to_string_lossy()
+							.into_owned(),
+						line: 2,
+						pattern: String::from("*.swp"),
+					}),
+				}]
+			);
+		});
+	}
+
+	#[test]
+	#[serial]
+	fn test_core_excludes_file() {
+		let (_td, repo) = repo_init().unwrap();
+		let repo_path: &RepoPath =
+			&repo.workdir().unwrap().to_str().unwrap().into();
+
+		repo_write_file(&repo, "a.txt", "a").unwrap();
+		repo_write_file(&repo, "a.bak", "backup").unwrap();
+		repo_write_file(&repo, "a.swp", "swap").unwrap();
+
+		repo.config()
+			.unwrap()
+			.set_str("core.excludesFile", "~/my-ignore")
+			.unwrap();
+
+		// the default location is not used once it is configured
+		with_home(Some("*.swp\n"), |home| {
+			fs::write(home.join("my-ignore"), "*.bak\n").unwrap();
+
+			assert_eq!(
+				global_excludes_file(&repo).unwrap(),
+				Some(home.join("my-ignore"))
+			);
+			assert_eq!(
+				paths(repo_path),
+				vec![String::from("a.swp"), String::from("a.txt")]
+			);
+		});
+	}
+
+	#[test]
+	#[serial]
+	fn test_ignored_files_rules() {
+		let (_td, repo) = repo_init().unwrap();
+		let repo_path: &RepoPath =
+			&repo.workdir().unwrap().to_str().unwrap().into();
+
+		for dir in ["build", "sub"] {
+			fs::create_dir(repo.workdir().unwrap().join(dir))
+				.unwrap();
+		}
+		repo_write_file(
+			&repo,
+			".gitignore",
+			"build/\n*.log\n!keep.log\n",
+		)
+		.unwrap();
+		repo_write_file(&repo, "sub/.gitignore", "*.tmp\n").unwrap();
+		repo_write_file(&repo, "build/out.o", "").unwrap();
+		repo_write_file(&repo, "a.log", "").unwrap();
+		repo_write_file(&repo, "sub/keep.log", "").unwrap();
+		repo_write_file(&repo, "sub/x.tmp", "").unwrap();
+		fs::write(repo.path().join("info").join("exclude"), "*.o\n")
+			.unwrap();
+		repo_write_file(&repo, "main.o", "").unwrap();
+
+		let rule = |source: &str, line: usize, pattern: &str| {
+			Some(IgnoreRule {
+				source: source.to_string(),
+				line,
+				pattern: pattern.to_string(),
+			})
+		};
+
+		let ignored = with_home(None, |_| {
+			get_ignored_files(repo_path).unwrap()
+		});
+
+		assert_eq!(
+			ignored
+				.into_iter()
+				.map(|file| (file.path, file.rule))
+				.collect::<Vec<_>>(),
+			vec![
+				(
+					String::from("a.log"),
+					rule(".gitignore", 2, "*.log")
+				),
+				(
+					String::from("build/"),
+					rule(".gitignore", 1, "build/")
+				),
+				(
+					String::from("main.o"),
+					rule(".git/info/exclude", 1, "*.o")
+				),
+				(
+					String::from("sub/x.tmp"),
+					rule("sub/.gitignore", 1, "*.tmp")
+				),
+			]
+		);
+	}
+
+	/// `(source, line, pattern)` of the rule deciding about `path`,
+	/// with whether it is ignored
+	fn checked(
+		repo_path: &RepoPath,
+		path: &str,
+	) -> (bool, Option<(String, usize, String)>) {
+		let check = check_ignore(repo_path, path).unwrap();
+
+		(
+			check.is_ignored(),
+			check
+				.rule
+				.map(|rule| (rule.source, rule.line, rule.pattern)),
+		)
+	}
+
+	fn rule(
+		source: &str,
+		line: usize,
+		pattern: &str,
+	) -> Option<(String, usize, String)> {
+		Some((source.to_string(), line, pattern.to_string()))
+	}
+
+	fn init_with_dirs(
+		dirs: &[&str],
+	) -> (TempDir, git2::Repository, RepoPath) {
+		let (td, repo) = repo_init().unwrap();
+		let repo_path: RepoPath =
+			repo.workdir().unwrap().to_str().unwrap().into();
+
+		for dir in dirs {
+			fs::create_dir_all(repo.workdir().unwrap().join(dir))
+				.unwrap();
+		}
+
+		(td, repo, repo_path)
+	}
+
+	#[test]
+	#[serial]
+	fn test_check_ignore_negation() {
+		let (_td, repo, repo_path) = init_with_dirs(&["sub", "out"]);
+		let repo_path = &repo_path;
+
+		repo_write_file(
+			&repo,
+			".gitignore",
+			"*.log\n!keep.log\n*.tmp\n!*.tmp\nlate.tmp\nout/\n!out/x.txt\n",
+		)
+		.unwrap();
+		repo_write_file(&repo, "sub/.gitignore", "!debug.log\n")
+			.unwrap();
+
+		with_home(None, |_| {
+			assert_eq!(
+				checked(repo_path, "a.log"),
+				(true, rule(".gitignore", 1, "*.log"))
+			);
+			// the last matching line wins
+			assert_eq!(
+				checked(repo_path, "keep.log"),
+				(false, rule(".gitignore", 2, "!keep.log"))
+			);
+			assert_eq!(
+				checked(repo_path, "a.tmp"),
+				(false, rule(".gitignore", 4, "!*.tmp"))
+			);
+			assert_eq!(
+				checked(repo_path, "late.tmp"),
+				(true, rule(".gitignore", 5, "late.tmp"))
+			);
+			// a deeper .gitignore takes precedence
+			assert_eq!(
+				checked(repo_path, "sub/debug.log"),
+				(false, rule("sub/.gitignore", 1, "!debug.log"))
+			);
+			assert_eq!(
+				checked(repo_path, "sub/other.log"),
+				(true, rule(".gitignore", 1, "*.log"))
+			);
+			// nothing inside an ignored directory can be re-included
+			assert_eq!(
+				checked(repo_path, "out/x.txt"),
+				(true, rule(".gitignore", 6, "out/"))
+			);
+		});
+	}
+
+	#[test]
+	#[serial]
+	fn test_check_ignore_directories() {
+		let (_td, repo, repo_path) = init_with_dirs(&[
+			"build",
+			"src/build",
+			"a/b/logs",
+			"doc/sub",
+		]);
+		let repo_path = &repo_path;
+
+		repo_write_file(
+			&repo,
+			".gitignore",
+			"build/\n/root.txt\ndoc/*.txt\n**/logs\n",
+		)
+		.unwrap();
+		repo_write_file(&repo, "docs-build", "").unwrap();
+
+		with_home(None, |_| {
+			for (path, expected) in [
+				("build", rule(".gitignore", 1, "build/")),
+				("build/", rule(".gitignore", 1, "build/")),
+				("build/x/y.o", rule(".gitignore", 1, "build/")),
+				("src/build", rule(".gitignore", 1, "build/")),
+				// not existing, so not a directory
+				("gone/build", None),
+				("root.txt", rule(".gitignore", 2, "/root.txt")),
+				("src/root.txt", None),
+				("doc/a.txt", rule(".gitignore", 3, "doc/*.txt")),
+				("doc/sub/b.txt", None),
+				("a/b/logs", rule(".gitignore", 4, "**/logs")),
+				("a/b/logs/today", rule(".gitignore", 4, "**/logs")),
+			] {
+				assert_eq!(
+					checked(repo_path, path),
+					(expected.is_some(), expected),
+					"{path}"
+				);
+			}
+
+			// absolute paths inside the work dir work as well
+			let absolute = repo.workdir().unwrap().join("build");
+			assert_eq!(
+				checked(repo_path, absolute.to_str().unwrap()),
+				(true, rule(".gitignore", 1, "build/"))
+			);
+		});
+	}
+
+	#[test]
+	#[serial]
+	fn test_check_ignore_sources_and_syntax() {
+		let (_td, repo, repo_path) = init_with_dirs(&[]);
+		let repo_path = &repo_path;
+
+		repo_write_file(
+			&repo,
+			".gitignore",
+			"# comment\n\\#hash\n\\!bang\n!main.o\ntrailing   \n",
+		)
+		.unwrap();
+		fs::write(
+			repo.path().join("info").join("exclude"),
+			"*.o\n*.a\n",
+		)
+		.unwrap();
+
+		with_home(Some("*.swp\n*.a\n"), |home| {
+			let global = home
+				.join(".config/git/ignore")
+				.to_string_lossy()
+				.into_owned();
+
+			for (path, expected) in [
+				("# comment", None),
+				("#hash", rule(".gitignore", 2, "\\#hash")),
+				("!bang", rule(".gitignore", 3, "\\!bang")),
+				("trailing", rule(".gitignore", 5, "trailing")),
+				("lib.o", rule(".git/info/exclude", 1, "*.o")),
+				// .git/info/exclude before the global excludes
+				("lib.a", rule(".git/info/exclude", 2, "*.a")),
+				("a.swp", rule(&global, 1, "*.swp")),
+			] {
+				assert_eq!(
+					checked(repo_path, path),
+					(expected.is_some(), expected),
+					"{path}"
+				);
+			}
+
+			// .gitignore before .git/info/exclude
+			assert_eq!(
+				checked(repo_path, "main.o"),
+				(false, rule(".gitignore", 4, "!main.o"))
+			);
+		});
+	}
+
+	#[test]
+	#[serial]
+	fn test_check_ignore_tracked() {
+		let (_td, repo, repo_path) = init_with_dirs(&[]);
+
+		repo_write_file(&repo, ".gitignore", "*.log\n").unwrap();
+		repo_write_file(&repo, "a.log", "").unwrap();
+		let mut index = repo.index().unwrap();
+		index.add_path(Path::new("a.log")).unwrap();
+		index.write().unwrap();
+
+		let check =
+			with_home(None, |_| check_ignore(&repo_path, "a.log"))
+				.unwrap();
+
+		assert!(check.tracked);
+		assert!(!check.is_ignored());
+		assert_eq!(check.rule.unwrap().pattern, "*.log");
+
+		assert!(check_ignore(&repo_path, " ./ ").is_err());
+	}
+}
@@ -0,0 +1,344 @@
+//! per thread cache of parsed index files.
+//!
+//! opening a `Repository` and calling `Repository::index` parses the
+//! whole index file, which adds up on big repositories when several
+//! sync functions run during the same refresh. snapshots are keyed by
+//! size, mtime and trailing checksum of the index file, so a snapshot
+//! is reused only as long as the file on disk did not change.
+//!
+//! `git2::Index` can not be shared between threads, every (worker)
+//! thread keeps its own snapshots. invalidating bumps a process wide
+//! generation of the index file which drops them on all threads.
+
+use super::{repository::repo, RepoPath};
+use crate::error::Result;
+use git2::{Index, Repository};
+use std::{
+	cell::RefCell,
+	collections::HashMap,
+	env,
+	fs::File,
+	io::{Read, Seek, SeekFrom},
+	path::{Path, PathBuf},
+	rc::Rc,
+	sync::{Mutex, OnceLock},
+	time::SystemTime,
+};
+
+/// the index file ends with a checksum over its content (sha1 or
+/// sha256), reading the last 32 bytes covers both
+const CHECKSUM_TAIL_LEN: usize = 32;
+
+/// identifies the on-disk state of an index file
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+struct IndexStamp {
+	len: u64,
+	modified: SystemTime,
+	checksum: [u8; CHECKSUM_TAIL_LEN],
+}
+
+impl IndexStamp {
+	/// `None` if there is no index file (yet)
+	fn read(path: &Path) -> Option<Self> {
+		let mut file = File::open(path).ok()?;
+		let meta = file.metadata().ok()?;
+		let len = meta.len();
+
+		let mut checksum = [0_u8; CHECKSUM_TAIL_LEN];
+		let tail = usize::try_from(len)
+			.map_or(CHECKSUM_TAIL_LEN, |len| {
+				len.min(CHECKSUM_TAIL_LEN)
+			});
+		file.seek(SeekFrom::End(-i64::try_from(tail).ok()?)).ok()?;
+		file.read_exact(&mut checksum[..tail]).ok()?;
+
+		Some(Self {
+			len,
+			modified: meta.modified().ok()?,
+			checksum,
+		})
+	}
+}
+
+/// a parsed index file, see [`index_snapshot`]
+pub struct IndexSnapshot {
+	stamp: Option<IndexStamp>,
+	generation: u64,
+	index: Index,
+}
+
+impl IndexSnapshot {
+	/// the parsed index, not owned by any `Repository`
+	pub const fn index(&self) -> &Index {
+		&self.index
+	}
+
+	///
+	pub fn has_conflicts(&self) -> bool {
+		self.index.has_conflicts()
+	}
+}
+
+thread_local! {
+	static CACHE: RefCell<HashMap<PathBuf, Rc<IndexSnapshot>>> =
+		RefCell::default();
+}
+
+fn generation(path: &Path) -> u64 {
+	generations(|generations| {
+		generations.get(path).copied().unwrap_or_default()
+	})
+}
+
+fn generations<T>(
+	f: impl FnOnce(&mut HashMap<PathBuf, u64>) -> T,
+) -> T {
+	static GENERATIONS: OnceLock<Mutex<HashMap<PathBuf, u64>>> =
+		OnceLock::new();
+
+	let mut generations = GENERATIONS
+		.get_or_init(Mutex::default)
+		.lock()
+		.unwrap_or_else(std::sync::PoisonError::into_inner);
+
+	f(&mut generations)
+}
+
+/// path `Repository::index` reads from
+fn index_path(repo: &Repository) -> PathBuf {
+	env::var_os("GIT_INDEX_FILE")
+		.map_or_else(|| repo.path().join("index"), PathBuf::from)
+}
+
+/// returns the cached snapshot of the index of `repo` or (re-)reads
+/// it if the index file changed or was invalidated since.
+///
+/// an index file written while reading it is not cached, the next call
+/// reads it again.
+pub fn index_snapshot(
+	repo: &Repository,
+) -> Result<Rc<IndexSnapshot>> {
+	let path = index_path(repo);
+	let generation = generation(&path);
+	let stamp = IndexStamp::read(&path);
+
+	let cached = CACHE.with_borrow(|cache| {
+		cache
+			.get(&path)
+			.filter(|snapshot| {
+				stamp.is_some()
+					&& snapshot.stamp == stamp
+					&& snapshot.generation == generation
+			})
+			.cloned()
+	});
+
+	if let Some(snapshot) = cached {
+		return Ok(snapshot);
+	}
+
+	let index = Index::open(&path)?;
+
+	let stable = stamp.is_some() && IndexStamp::read(&path) == stamp;
+
+	let snapshot = Rc::new(IndexSnapshot {
+		stamp,
+		generation,
+		index,
+	});
+
+	CACHE.with_borrow_mut(|cache| {
+		if stable {
+			cache.insert(path, Rc::clone(&snapshot));
+		} else {
+			cache.remove(&path);
+		}
+	});
+
+	Ok(snapshot)
+}
+
+/// drops the cached index of `repo_path` (on all threads), used after
+/// writing the index and when the watcher reports changes
+pub fn invalidate_index_cache(repo_path: &RepoPath) -> Result<()> {
+	let repo = repo(repo_path)?;
+
+	invalidate_index_cache_repo(&repo);
+
+	Ok(())
+}
+
+pub fn invalidate_index_cache_repo(repo: &Repository) {
+	let path = index_path(repo);
+
+	CACHE.with_borrow_mut(|cache| cache.remove(&path));
+
+	generations(|generations| {
+		*generations.entry(path).or_default() += 1;
+	});
+}
+
+/// whether the index of `repo_path` contains conflicts
+pub fn index_has_conflicts(repo_path: &RepoPath) -> Result<bool> {
+	let repo = repo(repo_path)?;
+
+	Ok(index_snapshot(&repo)?.has_conflicts())
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::sync::{
+		stage_add_file,
+		tests::{repo_init, write_commit_file},
+		utils::repo_write_file,
+	};
+	use git2::{IndexEntry, IndexTime};
+	use std::time::Instant;
+
+	fn repo_path(repo: &Repository) -> RepoPath {
+		repo.workdir().unwrap().to_str().unwrap().into()
+	}
+
+	#[test]
+	fn test_snapshot_reused() {
+		let (_td, repo) = repo_init().unwrap();
+		write_commit_file(&repo, "a.txt", "a", "c1");
+
+		let first = index_snapshot(&repo).unwrap();
+		let second = index_snapshot(&repo).unwrap();
+
+		assert!(Rc::ptr_eq(&first, &second));
+	}
+
+	#[test]
+	fn test_snapshot_reread_after_write() {
+		let (_td, repo) = repo_init().unwrap();
+		let path = &repo_path(&repo);
+
+		let before = index_snapshot(&repo).unwrap();
+		assert!(before
+			.index()
+			.get_path(Path::new("a.txt"), 0)
+			.is_none());
+
+		repo_write_file(&repo, "a.txt", "a").unwrap();
+		stage_add_file(path, Path::new("a.txt")).unwrap();
+
+		let after = index_snapshot(&repo).unwrap();
+		assert!(!Rc::ptr_eq(&before, &after));
+		assert!(after
+			.index()
+			.get_path(Path::new("a.txt"), 0)
+			.is_some());
+	}
+
+	#[test]
+	fn test_snapshot_reread_after_foreign_write() {
+		let (_td, repo) = repo_init().unwrap();
+		write_commit_file(&repo, "a.txt", "a", "c1");
+
+		let before = index_snapshot(&repo).unwrap();
+
+		// same size and (likely) same mtime, only the content differs
+		repo_write_file(&repo, "a.txt", "b").unwrap();
+		let mut index = repo.index().unwrap();
+		index.add_path(Path::new("a.txt")).unwrap();
+		index.write().unwrap();
+
+		let after = index_snapshot(&repo).unwrap();
+		assert!(!Rc::ptr_eq(&before, &after));
+
+		let id = |s: &IndexSnapshot| {
+			s.index().get_path(Path::new("a.txt"), 0).unwrap().id
+		};
+		assert_ne!(id(&before), id(&after));
+	}
+
+	#[test]
+	fn test_invalidate() {
+		let (_td, repo) = repo_init().unwrap();
+
+		let first = index_snapshot(&repo).unwrap();
+		invalidate_index_cache(&repo_path(&repo)).unwrap();
+		let second = index_snapshot(&repo).unwrap();
+
+		assert!(!Rc::ptr_eq(&first, &second));
+	}
+
+	#[test]
+	fn test_invalidate_other_thread() {
+		let (_td, repo) = repo_init().unwrap();
+
+		let first = index_snapshot(&repo).unwrap();
+
+		let path = repo_path(&repo);
+		std::thread::spawn(move || {
+			invalidate_index_cache(&path).unwrap();
+		})
+		.join()
+		.unwrap();
+
+		let second = index_snapshot(&repo).unwrap();
+
+		assert!(!Rc::ptr_eq(&first, &second));
+	}
+
+	/// `cargo test -p asyncgit --release -- --ignored bench_index_snapshot --nocapture`
+	#[test]
+	#[ignore = "benchmark"]
+	fn bench_index_snapshot() {
+		const ENTRIES: u32 = 100_000;
+		const REFRESHES: u32 = 20;
+
+		let (_td, repo) = repo_init().unwrap();
+		let blob = repo.blob(b"content").unwrap();
+
+		let mut index = repo.index().unwrap();
+		for i in 0..ENTRIES {
+			index
+				.add(&IndexEntry {
+					ctime: IndexTime::new(0, 0),
+					mtime: IndexTime::new(0, 0),
+					dev: 0,
+					ino: 0,
+					mode: 0o100_644,
+					uid: 0,
+					gid: 0,
+					file_size: 7,
+					id: blob,
+					flags: 0,
+					flags_extended: 0,
+					path: format!("dir{}/file{i}", i / 1000).into(),
+				})
+				.unwrap();
+		}
+		index.write().unwrap();
+
+		let start = Instant::now();
+		for _ in 0..REFRESHES {
+			let repo = reopen(&repo);
+			assert_eq!(repo.index().unwrap().len(), ENTRIES as usize);
+		}
+		let uncached = start.elapsed();
+
+		let start = Instant::now();
+		for _ in 0..REFRESHES {
+			let repo = reopen(&repo);
+			assert_eq!(
+				index_snapshot(&repo).unwrap().index().len(),
+				ENTRIES as usize
+			);
+		}
+		let cached = start.elapsed();
+
+		println!(
+			"{ENTRIES} entries, {REFRESHES} refreshes: uncached {uncached:?}, cached {cached:?}"
+		);
+		assert!(cached < uncached);
+	}
+
+	fn reopen(repo: &Repository) -> Repository {
+		Repository::open(repo.path()).unwrap()
+	}
+}
@@ -0,0 +1,118 @@
+//! process wide string interning.
+//!
+//! big histories usually have only a handful of distinct authors and
+//! big trees only a few thousand distinct directories, storing every
+//! occurrence as its own `String`/`PathBuf` wastes most of the memory
+//! of the log and file lists. interned values are shared `Arc`s instead.
+
+use std::{
+	collections::HashSet,
+	hash::Hash,
+	path::Path,
+	sync::{Arc, Mutex, OnceLock, PoisonError},
+};
+
+/// pools are not pruned below this size
+const MIN_PRUNE_LEN: usize = 1024;
+
+/// set of shared values, see [`Interner::intern`]
+pub struct Interner<T: ?Sized> {
+	pool: Mutex<Pool<T>>,
+}
+
+struct Pool<T: ?Sized> {
+	values: HashSet<Arc<T>>,
+	/// size at which values nobody else uses any more are dropped
+	prune_at: usize,
+}
+
+impl<T> Interner<T>
+where
+	T: ?Sized + Eq + Hash,
+	for<'a> Arc<T>: From<&'a T>,
+{
+	fn new() -> Self {
+		Self {
+			pool: Mutex::new(Pool {
+				values: HashSet::new(),
+				prune_at: MIN_PRUNE_LEN,
+			}),
+		}
+	}
+
+	/// returns the shared copy of `value`, allocating it only the first
+	/// time it is seen
+	pub fn intern(&self, value: &T) -> Arc<T> {
+		let mut pool =
+			self.pool.lock().unwrap_or_else(PoisonError::into_inner);
+
+		if let Some(interned) = pool.values.get(value) {
+			return Arc::clone(interned);
+		}
+
+		if pool.values.len() >= pool.prune_at {
+			pool.values.retain(|value| Arc::strong_count(value) > 1);
+			pool.prune_at =
+				(pool.values.len() * 2).max(MIN_PRUNE_LEN);
+		}
+
+		let interned = Arc::<T>::from(value);
+		pool.values.insert(Arc::clone(&interned));
+
+		interned
+	}
+
+	#[cfg(test)]
+	fn len(&self) -> usize {
+		self.pool
+			.lock()
+			.unwrap_or_else(PoisonError::into_inner)
+			.values
+			.len()
+	}
+}
+
+/// author (and committer) names
+pub fn intern_name(name: &str) -> Arc<str> {
+	static NAMES: OnceLock<Interner<str>> = OnceLock::new();
+
+	NAMES.get_or_init(Interner::new).intern(name)
+}
+
+/// directories of file lists
+pub fn intern_dir(dir: &Path) -> Arc<Path> {
+	static DIRS: OnceLock<Interner<Path>> = OnceLock::new();
+
+	DIRS.get_or_init(Interner::new).intern(dir)
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn test_intern_shares() {
+		let interner = Interner::<str>::new();
+
+		let a = interner.intern("name");
+		let b = interner.intern(&String::from("name"));
+
+		assert!(Arc::ptr_eq(&a, &b));
+		assert_eq!(interner.len(), 1);
+	}
+
+	#[test]
+	fn test_prune_unused() {
+		let interner = Interner::<str>::new();
+
+		let kept = interner.intern("kept");
+		for i in 1..MIN_PRUNE_LEN {
+			interner.intern(&i.to_string());
+		}
+		// triggers pruning everything but `kept`
+		interner.intern("new");
+
+		assert_eq!(interner.len(), 2);
+		assert!(Arc::ptr_eq(&kept, &interner.intern("kept")));
+	}
+}
@@ -6,17 +6,23 @@ use crate::{
 			abort_rebase, continue_rebase, get_rebase_progress,
 		},
 		repository::repo,
-		reset_stage, reset_workdir, CommitId,
+		reset_stage, reset_workdir,
+		state::ensure_clean_state,
+		CommitId,
 	},
 };
 use git2::{BranchType, Commit, MergeOptions, Repository};
 use scopetime::scope_time;
+use std::fs::read_to_string;
 
 use super::{
 	rebase::{RebaseProgress, RebaseState},
 	RepoPath,
 };
 
+/// file in the git dir holding the message of a pending squash
+pub const SQUASH_MSG: &str = "SQUASH_MSG";
+
 ///
 pub fn mergehead_ids(repo_path: &RepoPath) -> Result<Vec<CommitId>> {
 	scope_time!("mergehead_ids");
@@ -58,6 +64,7 @@ pub fn merge_branch(
 	scope_time!("merge_branch");
 
 	let repo = repo(repo_path)?;
+	ensure_clean_state(&repo)?;
 
 	merge_branch_repo(&repo, branch, branch_type)?;
 
@@ -130,6 +137,16 @@ pub fn merge_msg(repo_path: &RepoPath) -> Result<String> {
 	Ok(content)
 }
 
+/// message prepared by `git merge --squash` (`SQUASH_MSG`), if any
+pub fn squash_msg(repo_path: &RepoPath) -> Result<Option<String>> {
+	scope_time!("squash_msg");
+
+	let repo = repo(repo_path)?;
+	let path = repo.path().join(SQUASH_MSG);
+
+	Ok(path.exists().then(|| read_to_string(path)).transpose()?)
+}
+
 ///
 pub fn merge_commit(
 	repo_path: &RepoPath,
@@ -3,29 +3,41 @@
 //TODO: remove once we have this activated on the toplevel
 #![deny(clippy::expect_used)]
 
+mod audit;
 pub mod blame;
 pub mod branch;
 pub mod commit;
+mod commit_cache;
 mod commit_details;
 pub mod commit_files;
 mod commit_filter;
 mod commit_revert;
 mod commits_info;
 mod config;
+mod conflict_markers;
 pub mod cred;
 pub mod diff;
+mod drop_commit;
+mod filter;
 mod hooks;
 mod hunks;
 mod ignore;
+mod ignore_rules;
+mod index_cache;
+mod intern;
 mod logwalker;
 mod merge;
 mod patches;
+mod pseudo_refs;
 mod rebase;
 pub mod remotes;
 mod repository;
 mod reset;
 mod reword;
 pub mod sign;
+mod squash;
+mod staged_checks;
+mod staged_summary;
 mod staging;
 mod stash;
 mod state;
@@ -35,17 +47,29 @@ mod tags;
 mod tree;
 pub mod utils;
 
+pub use audit::{
+	set_audit_observer, AuditEvent, AuditObserver, AuditOperation,
+};
 pub use blame::{blame_file, BlameHunk, FileBlame};
 pub use branch::{
-	branch_compare_upstream, checkout_branch, checkout_commit,
-	config_is_pull_rebase, create_branch, delete_branch,
+	branch_compare, branch_compare_upstream, checkout_branch,
+	checkout_commit, config_is_pull_rebase, create_branch,
+	default_branch, delete_branch, get_branch_description,
 	get_branch_remote, get_branch_upstream_merge, get_branches_info,
 	merge_commit::merge_upstream_commit,
 	merge_ff::branch_merge_upstream_fastforward,
-	merge_rebase::merge_upstream_rebase, rename::rename_branch,
-	validate_branch_name, BranchCompare, BranchDetails, BranchInfo,
+	merge_rebase::merge_upstream_rebase,
+	move_changes::{local_commits, move_changes_to_branch},
+	recent::recent_branches,
+	rename::rename_branch,
+	set_branch_description, validate_branch_name, BranchCompare,
+	BranchDetails, BranchInfo,
 };
 pub use commit::{amend, commit, tag_commit};
+pub use commit_cache::{
+	commit_cache_stats, set_commit_cache_capacity, CommitCacheStats,
+	CommitMeta, DEFAULT_COMMIT_CACHE_CAPACITY,
+};
 pub use commit_details::{
 	get_commit_details, CommitDetails, CommitMessage, CommitSignature,
 };
@@ -63,34 +87,82 @@ pub use config::{
 	get_config_string, untracked_files_config,
 	ShowUntrackedFilesConfig,
 };
+pub use conflict_markers::{
+	conflict_marker_check, staged_conflict_markers,
+	ConflictMarkerCheck, ConflictMarkers,
+};
 pub use diff::get_diff_commit;
+pub use drop_commit::{
+	drop_commit, drop_commit_info, DropCommitInfo,
+};
 pub use git2::BranchType;
 pub use hooks::{
-	hooks_commit_msg, hooks_commit_msg_with_timeout,
-	hooks_post_commit, hooks_post_commit_with_timeout,
-	hooks_pre_commit, hooks_pre_commit_with_timeout,
-	hooks_prepare_commit_msg, hooks_prepare_commit_msg_with_timeout,
-	HookResult, PrepareCommitMsgSource,
+	effective_hook_timeout, hooks_applypatch_msg,
+	hooks_applypatch_msg_with_timeout, hooks_commit_msg,
+	hooks_commit_msg_cancellable, hooks_commit_msg_preview,
+	hooks_commit_msg_with_behavior, hooks_commit_msg_with_timeout,
+	hooks_config, hooks_post_applypatch,
+	hooks_post_applypatch_with_timeout, hooks_post_checkout,
+	hooks_post_checkout_with_timeout, hooks_post_commit,
+	hooks_post_commit_with_timeout, hooks_post_merge,
+	hooks_post_merge_with_timeout, hooks_post_rewrite,
+	hooks_post_rewrite_with_timeout, hooks_pre_applypatch,
+	hooks_pre_applypatch_with_timeout, hooks_pre_commit,
+	hooks_pre_commit_cancellable, hooks_pre_commit_streaming,
+	hooks_pre_commit_with_behavior, hooks_pre_commit_with_timeout,
+	hooks_pre_push, hooks_pre_push_with_timeout, hooks_pre_rebase,
+	hooks_pre_rebase_with_timeout, hooks_prepare_commit_msg,
+	hooks_prepare_commit_msg_with_behavior,
+	hooks_prepare_commit_msg_with_timeout,
+	hooks_reference_transaction, last_hook_duration,
+	prepare_commit_msg_source, read_hook_timeout,
+	run_hook_cancellable, run_pre_operation_hook, set_hooks_deny,
+	set_hooks_disabled, set_hooks_timeout, CancellationToken,
+	CommitMsgFor, HookBehavior, HookFailure, HookOutputLine,
+	HookRejection, HookResult, HookType, HooksConfig, OutputStream,
+	PostRewriteSource, PrePushRef, PrepareCommitMsgSource, RefUpdate,
+	ReferenceTransactionState, TimeoutSource, HOOK_ENV,
 };
 pub use hunks::{reset_hunk, stage_hunk, unstage_hunk};
 pub use ignore::add_to_ignore;
+pub use ignore_rules::{
+	apply_global_excludes, check_ignore, get_ignored_files,
+	global_excludes_file, IgnoreCheck, IgnoreRule, IgnoredFile,
+};
+pub use index_cache::{
+	index_has_conflicts, index_snapshot, invalidate_index_cache,
+	IndexSnapshot,
+};
 pub use logwalker::{LogWalker, LogWalkerWithoutFilter};
 pub use merge::{
 	abort_pending_rebase, abort_pending_state,
 	continue_pending_rebase, merge_branch, merge_commit, merge_msg,
-	mergehead_ids, rebase_progress,
+	mergehead_ids, rebase_progress, squash_msg,
 };
-pub use rebase::rebase_branch;
+pub use pseudo_refs::{pseudo_ref, pseudo_refs, PseudoRef};
+pub use rebase::{rebase_branch, RebaseState};
 pub use remotes::{
-	add_remote, delete_remote, get_default_remote,
+	add_remote, delete_remote, fetch_tags, fetch_target,
+	get_broken_remote_refs, get_default_remote,
 	get_default_remote_for_fetch, get_default_remote_for_push,
-	get_remote_url, get_remotes, push::AsyncProgress, rename_remote,
-	tags::PushTagsProgress, update_remote_url, validate_remote_name,
+	get_push_all_remotes, get_push_remote_for_branch,
+	get_remote_default_branch, get_remote_fetch_tags, get_remote_url,
+	get_remotes, push::AsyncProgress, rename_remote,
+	repair_remote_ref, tags::PushTagsProgress, update_remote_url,
+	validate_refspec, validate_remote_name, FetchTags, FetchTarget,
 };
 pub(crate) use repository::repo;
 pub use repository::{RepoPath, RepoPathRef};
 pub use reset::{reset_repo, reset_stage, reset_workdir};
 pub use reword::reword;
+pub use squash::{squash_message, squash_range};
+pub use staged_checks::{
+	staged_checks, staged_large_files, LargeFile, StagedChecks,
+};
+pub use staged_summary::{
+	is_conflict_marker, staged_summary, staged_summary_config,
+	StagedSummary, StagedSummaryConfig, StagedWarning,
+};
 pub use staging::{discard_lines, stage_lines};
 pub use stash::{
 	get_stashes, stash_apply, stash_drop, stash_pop, stash_save,
@@ -105,7 +177,10 @@ pub use tags::{
 	delete_tag, get_tags, get_tags_with_metadata, CommitTags, Tag,
 	TagWithMetadata, Tags,
 };
-pub use tree::{tree_file_content, tree_files, TreeFile};
+pub use tree::{
+	checkout_tree_file, tree_file_at, tree_file_content, tree_files,
+	TreeFile, MAX_TREE_FILE_SIZE,
+};
 pub use utils::{
 	get_head, get_head_tuple, repo_dir, repo_open_error,
 	stage_add_all, stage_add_file, stage_addremoved, Head,
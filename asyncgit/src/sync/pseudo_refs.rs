@@ -0,0 +1,162 @@
+//! pseudo-refs like `ORIG_HEAD` that git leaves in the git dir after
+//! history changing operations
+
+use super::{repository::repo, CommitId, RepoPath};
+use crate::error::Result;
+use git2::{ErrorCode, Oid, Repository};
+use scopetime::scope_time;
+
+/// a pseudo-ref pointing to a single commit
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PseudoRef {
+	/// `HEAD` before the last reset, rebase or merge
+	OrigHead,
+	/// the commit being merged (the first one of an octopus merge)
+	MergeHead,
+	/// the commit being cherry-picked
+	CherryPickHead,
+	/// the commit being reverted
+	RevertHead,
+}
+
+impl PseudoRef {
+	/// all of them, in the order [`pseudo_refs`] returns them
+	pub const ALL: [Self; 4] = [
+		Self::OrigHead,
+		Self::MergeHead,
+		Self::CherryPickHead,
+		Self::RevertHead,
+	];
+
+	/// the file name in the git dir
+	pub const fn name(self) -> &'static str {
+		match self {
+			Self::OrigHead => "ORIG_HEAD",
+			Self::MergeHead => "MERGE_HEAD",
+			Self::CherryPickHead => "CHERRY_PICK_HEAD",
+			Self::RevertHead => "REVERT_HEAD",
+		}
+	}
+}
+
+fn read_pseudo_ref(
+	repo: &Repository,
+	pseudo_ref: PseudoRef,
+) -> Result<Option<CommitId>> {
+	match repo.refname_to_id(pseudo_ref.name()) {
+		Ok(id) => Ok(Some(id.into())),
+		Err(e) if e.code() == ErrorCode::NotFound => Ok(None),
+		Err(e) => Err(e.into()),
+	}
+}
+
+/// the commit `pseudo_ref` points to, `None` if it does not exist
+pub fn pseudo_ref(
+	repo_path: &RepoPath,
+	pseudo_ref: PseudoRef,
+) -> Result<Option<CommitId>> {
+	scope_time!("pseudo_ref");
+
+	read_pseudo_ref(&repo(repo_path)?, pseudo_ref)
+}
+
+/// the pseudo-refs that currently exist and what they point to
+pub fn pseudo_refs(
+	repo_path: &RepoPath,
+) -> Result<Vec<(PseudoRef, CommitId)>> {
+	scope_time!("pseudo_refs");
+
+	let repo = repo(repo_path)?;
+
+	let mut refs = Vec::new();
+	for pseudo_ref in PseudoRef::ALL {
+		if let Some(id) = read_pseudo_ref(&repo, pseudo_ref)? {
+			refs.push((pseudo_ref, id));
+		}
+	}
+
+	Ok(refs)
+}
+
+/// remembers `head` as `ORIG_HEAD` like git does before moving `HEAD`
+/// in a reset
+pub fn set_orig_head(repo: &Repository, head: Oid) -> Result<()> {
+	repo.reference(
+		PseudoRef::OrigHead.name(),
+		head,
+		true,
+		"updating ORIG_HEAD",
+	)?;
+
+	Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::sync::{
+		reset_repo,
+		tests::{repo_init, write_commit_file},
+		utils::get_head,
+		ResetType,
+	};
+	use pretty_assertions::assert_eq;
+
+	fn repo_path(repo: &Repository) -> RepoPath {
+		repo.workdir().unwrap().to_str().unwrap().into()
+	}
+
+	#[test]
+	fn test_no_pseudo_refs() {
+		let (_td, repo) = repo_init().unwrap();
+		let repo_path = repo_path(&repo);
+
+		assert_eq!(pseudo_refs(&repo_path).unwrap(), vec![]);
+		assert_eq!(
+			pseudo_ref(&repo_path, PseudoRef::OrigHead).unwrap(),
+			None
+		);
+	}
+
+	#[test]
+	fn test_orig_head_after_reset() {
+		let (_td, repo) = repo_init().unwrap();
+		let repo_path = repo_path(&repo);
+
+		let c1 = write_commit_file(&repo, "a.txt", "a", "c1");
+		let c2 = write_commit_file(&repo, "a.txt", "ab", "c2");
+
+		reset_repo(&repo_path, c1, ResetType::Hard).unwrap();
+
+		assert_eq!(
+			pseudo_refs(&repo_path).unwrap(),
+			vec![(PseudoRef::OrigHead, c2)]
+		);
+
+		// going back undoes the reset
+		reset_repo(&repo_path, c2, ResetType::Hard).unwrap();
+		assert_eq!(get_head(&repo_path).unwrap(), c2);
+		assert_eq!(
+			pseudo_ref(&repo_path, PseudoRef::OrigHead).unwrap(),
+			Some(c1)
+		);
+	}
+
+	#[test]
+	fn test_merge_head() {
+		let (_td, repo) = repo_init().unwrap();
+		let repo_path = repo_path(&repo);
+
+		let id = write_commit_file(&repo, "a.txt", "a", "c1");
+		std::fs::write(
+			repo.path().join(PseudoRef::MergeHead.name()),
+			format!("{id}\n{id}\n"),
+		)
+		.unwrap();
+
+		assert_eq!(
+			pseudo_refs(&repo_path).unwrap(),
+			vec![(PseudoRef::MergeHead, id)]
+		);
+	}
+}
@@ -3,10 +3,29 @@ use scopetime::scope_time;
 
 use crate::{
 	error::{Error, Result},
-	sync::repository::repo,
+	sync::{repository::repo, state::ensure_clean_state},
 };
 
-use super::{CommitId, RepoPath};
+use super::{
+	hooks_pre_rebase, CommitId, HookRejection, HookResult, HookType,
+	RepoPath,
+};
+
+/// runs the `pre-rebase` hook for rebasing the current branch on
+/// `upstream`, a rejection cancels the rebase before it started
+pub fn pre_rebase(
+	repo_path: &RepoPath,
+	upstream: &str,
+) -> Result<()> {
+	match hooks_pre_rebase(repo_path, upstream, None)? {
+		HookResult::Ok => Ok(()),
+		result => Err(HookRejection::Rejected {
+			hook: HookType::PreRebase,
+			result,
+		}
+		.into()),
+	}
+}
 
 /// rebase current HEAD on `branch`
 pub fn rebase_branch(
@@ -17,6 +36,9 @@ pub fn rebase_branch(
 	scope_time!("rebase_branch");
 
 	let repo = repo(repo_path)?;
+	ensure_clean_state(&repo)?;
+
+	pre_rebase(repo_path, branch)?;
 
 	rebase_branch_repo(&repo, branch, branch_type)
 }
@@ -82,7 +104,17 @@ pub fn rebase(
 	repo: &git2::Repository,
 	commit: &git2::AnnotatedCommit,
 ) -> Result<RebaseState> {
-	let mut rebase = repo.rebase(None, Some(commit), None, None)?;
+	let rebase = repo.rebase(None, Some(commit), None, None)?;
+
+	run_rebase(repo, rebase)
+}
+
+/// applies the operations of `rebase` until they are done or one of
+/// them conflicts, which leaves the rebase pending
+pub fn run_rebase(
+	repo: &git2::Repository,
+	mut rebase: git2::Rebase,
+) -> Result<RebaseState> {
 	let signature =
 		crate::sync::commit::signature_allow_undefined_name(repo)?;
 
@@ -186,13 +218,15 @@ pub fn abort_rebase(repo: &git2::Repository) -> Result<()> {
 #[cfg(test)]
 mod test_conflict_free_rebase {
 	use crate::sync::{
-		checkout_branch, create_branch,
+		checkout_branch, create_branch, get_head,
 		rebase::{rebase_branch, RebaseState},
 		repo_state,
 		repository::repo,
 		tests::{repo_init, write_commit_file},
-		CommitId, RepoPath, RepoState,
+		CommitId, HookFailure, HookRejection, HookResult, HookType,
+		RepoPath, RepoState,
 	};
+	use crate::Error;
 	use git2::{BranchType, Repository};
 
 	use super::conflict_free_rebase;
@@ -280,6 +314,48 @@ mod test_conflict_free_rebase {
 
 		assert_eq!(repo_state(repo_path).unwrap(), RepoState::Rebase);
 	}
+
+	#[test]
+	fn test_pre_rebase_rejects() {
+		let (_td, repo) = repo_init().unwrap();
+		let root = repo.path().parent().unwrap();
+		let repo_path: &RepoPath =
+			&root.as_os_str().to_str().unwrap().into();
+
+		write_commit_file(&repo, "test1.txt", "test", "commit1");
+		create_branch(repo_path, "foo").unwrap();
+		let c2 =
+			write_commit_file(&repo, "test2.txt", "test", "commit2");
+		checkout_branch(repo_path, "master").unwrap();
+		write_commit_file(&repo, "test3.txt", "test", "commit3");
+		checkout_branch(repo_path, "foo").unwrap();
+
+		let hook = b"#!/bin/sh
+echo \"published: $1 $#\"
+exit 1
+";
+		git2_hooks::create_hook(
+			&repo,
+			git2_hooks::HOOK_PRE_REBASE,
+			hook,
+		);
+
+		let res =
+			rebase_branch(repo_path, "master", BranchType::Local);
+
+		assert!(matches!(
+			res,
+			Err(Error::HookRejected(HookRejection::Rejected {
+				hook: HookType::PreRebase,
+				result: HookResult::NotOk(HookFailure {
+					ref stdout,
+					..
+				}),
+			})) if stdout == b"published: master 1\n"
+		));
+		assert_eq!(repo_state(repo_path).unwrap(), RepoState::Clean);
+		assert_eq!(get_head(repo_path).unwrap(), c2);
+	}
 }
 
 #[cfg(test)]
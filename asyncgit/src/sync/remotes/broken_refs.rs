@@ -0,0 +1,253 @@
+//! remote tracking branches pointing at objects missing locally, e.g.
+//! after the history was rewritten on the server and the objects got
+//! pruned. see [`get_broken_remote_refs`] and [`repair_remote_ref`]
+
+use super::FetchTarget;
+use crate::{
+	error::{Error, Result},
+	sync::{repository::repo, utils::bytes2string, RepoPath},
+};
+use git2::{Reference, Repository};
+use scopetime::scope_time;
+
+/// `reference` resolves to an object id that is not in the object
+/// database. symbolic refs (like `origin/HEAD`) are never broken
+pub fn ref_target_missing(
+	repo: &Repository,
+	reference: &Reference,
+) -> Result<bool> {
+	Ok(match reference.target() {
+		Some(id) => !repo.odb()?.exists(id),
+		None => false,
+	})
+}
+
+/// errors with [`Error::BrokenRef`] if [`ref_target_missing`]
+pub fn ensure_ref_not_broken(
+	repo: &Repository,
+	reference: &Reference,
+) -> Result<()> {
+	if ref_target_missing(repo, reference)? {
+		return Err(Error::BrokenRef(bytes2string(
+			reference.name_bytes(),
+		)?));
+	}
+
+	Ok(())
+}
+
+/// the full names of all remote tracking refs whose commit is missing
+pub fn get_broken_remote_refs(
+	repo_path: &RepoPath,
+) -> Result<Vec<String>> {
+	scope_time!("get_broken_remote_refs");
+
+	let repo = repo(repo_path)?;
+	let mut broken = Vec::new();
+
+	for reference in repo.references_glob("refs/remotes/*")? {
+		let reference = reference?;
+		if ref_target_missing(&repo, &reference)? {
+			broken.push(bytes2string(reference.name_bytes())?);
+		}
+	}
+
+	Ok(broken)
+}
+
+/// deletes the broken remote tracking ref `reference` (its full name)
+/// and returns what to fetch to get it back, `None` if it does not
+/// belong to a remote anymore. healthy refs are left alone
+pub fn repair_remote_ref(
+	repo_path: &RepoPath,
+	reference: &str,
+) -> Result<Option<FetchTarget>> {
+	scope_time!("repair_remote_ref");
+
+	let repo = repo(repo_path)?;
+	let mut found = repo.find_reference(reference)?;
+
+	if !ref_target_missing(&repo, &found)? {
+		return Err(Error::Generic(format!(
+			"'{reference}' is not broken"
+		)));
+	}
+
+	let remote = repo
+		.branch_remote_name(reference)
+		.ok()
+		.and_then(|remote| remote.as_str().map(String::from));
+
+	found.delete()?;
+
+	Ok(remote.and_then(|remote| {
+		let branch = reference
+			.strip_prefix(&format!("refs/remotes/{remote}/"))?;
+
+		Some(FetchTarget {
+			refspec: format!("+refs/heads/{branch}:{reference}"),
+			remote,
+		})
+	}))
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::sync::{
+		branch_compare_upstream, get_branches_info,
+		remotes::push::push_branch,
+		tests::{repo_clone, repo_init_bare, write_commit_file},
+		BranchDetails, BranchType, CommitId,
+	};
+	use std::fs;
+
+	/// `refs/remotes/origin/<name>` pointing at a new commit whose
+	/// loose object is deleted again
+	fn broken_remote_ref(repo: &Repository, name: &str) -> CommitId {
+		let head = repo.head().unwrap().peel_to_commit().unwrap();
+		let id = repo
+			.commit(
+				None,
+				&head.author(),
+				&head.committer(),
+				"gone",
+				&head.tree().unwrap(),
+				&[&head],
+			)
+			.unwrap();
+		repo.reference(
+			&format!("refs/remotes/origin/{name}"),
+			id,
+			true,
+			"",
+		)
+		.unwrap();
+
+		let hex = id.to_string();
+		fs::remove_file(
+			repo.path()
+				.join("objects")
+				.join(&hex[..2])
+				.join(&hex[2..]),
+		)
+		.unwrap();
+
+		id.into()
+	}
+
+	#[test]
+	fn test_broken_remote_refs() {
+		let (upstream_dir, _upstream) = repo_init_bare().unwrap();
+		let (clone_dir, clone) =
+			repo_clone(upstream_dir.path().to_str().unwrap())
+				.unwrap();
+		let repo_path: &RepoPath =
+			&clone_dir.path().to_str().unwrap().into();
+
+		write_commit_file(&clone, "a.txt", "a", "c1");
+		push_branch(
+			repo_path, "origin", "master", false, false, None, None,
+		)
+		.unwrap();
+
+		assert!(get_broken_remote_refs(repo_path)
+			.unwrap()
+			.is_empty());
+
+		let id = broken_remote_ref(&clone, "gone");
+
+		assert_eq!(
+			get_broken_remote_refs(repo_path).unwrap(),
+			vec![String::from("refs/remotes/origin/gone")]
+		);
+
+		// listed with a marker instead of failing or being dropped
+		let branches = get_branches_info(repo_path, false).unwrap();
+		let broken = |name: &str| {
+			branches.iter().find(|b| b.name == name).map(|b| {
+				matches!(
+					&b.details,
+					BranchDetails::Remote(remote) if remote.broken
+				)
+			})
+		};
+		assert_eq!(broken("origin/master"), Some(false));
+		assert_eq!(broken("origin/gone"), Some(true));
+		assert_eq!(
+			branches
+				.iter()
+				.find(|b| b.name == "origin/gone")
+				.unwrap()
+				.top_commit,
+			id
+		);
+	}
+
+	#[test]
+	fn test_compare_to_broken_upstream() {
+		let (upstream_dir, _upstream) = repo_init_bare().unwrap();
+		let (clone_dir, clone) =
+			repo_clone(upstream_dir.path().to_str().unwrap())
+				.unwrap();
+		let repo_path: &RepoPath =
+			&clone_dir.path().to_str().unwrap().into();
+
+		write_commit_file(&clone, "a.txt", "a", "c1");
+		broken_remote_ref(&clone, "master");
+		clone
+			.find_branch("master", BranchType::Local)
+			.unwrap()
+			.set_upstream(Some("origin/master"))
+			.unwrap();
+
+		assert!(matches!(
+			branch_compare_upstream(repo_path, "master"),
+			Err(Error::BrokenRef(name))
+				if name == "refs/remotes/origin/master"
+		));
+	}
+
+	#[test]
+	fn test_repair_remote_ref() {
+		let (upstream_dir, _upstream) = repo_init_bare().unwrap();
+		let (clone_dir, clone) =
+			repo_clone(upstream_dir.path().to_str().unwrap())
+				.unwrap();
+		let repo_path: &RepoPath =
+			&clone_dir.path().to_str().unwrap().into();
+
+		write_commit_file(&clone, "a.txt", "a", "c1");
+		push_branch(
+			repo_path, "origin", "master", false, false, None, None,
+		)
+		.unwrap();
+		broken_remote_ref(&clone, "feature/x");
+
+		assert!(repair_remote_ref(
+			repo_path,
+			"refs/remotes/origin/master"
+		)
+		.is_err());
+		assert!(clone
+			.find_reference("refs/remotes/origin/master")
+			.is_ok());
+
+		assert_eq!(
+			repair_remote_ref(
+				repo_path,
+				"refs/remotes/origin/feature/x"
+			)
+			.unwrap(),
+			Some(FetchTarget {
+				remote: String::from("origin"),
+				refspec: String::from(
+					"+refs/heads/feature/x:refs/remotes/origin/feature/x"
+				),
+			})
+		);
+		assert!(get_broken_remote_refs(repo_path)
+			.unwrap()
+			.is_empty());
+	}
+}
@@ -1,7 +1,13 @@
 use super::push::ProgressNotification;
-use crate::{error::Result, sync::cred::BasicAuthCredential};
+use crate::{
+	error::Result,
+	sync::{
+		cred::BasicAuthCredential, hooks::hooks_pre_push, HookResult,
+		PrePushRef, RepoPath,
+	},
+};
 use crossbeam_channel::Sender;
-use git2::{Cred, Error as GitError, RemoteCallbacks};
+use git2::{Cred, Error as GitError, PushUpdate, RemoteCallbacks};
 use std::sync::{
 	atomic::{AtomicBool, Ordering},
 	Arc, Mutex,
@@ -11,6 +17,16 @@ use std::sync::{
 #[derive(Default, Clone)]
 pub struct CallbackStats {
 	pub push_rejected_msg: Option<(String, String)>,
+	/// what the `pre-push` hook returned if it rejected the push
+	pub pre_push_rejection: Option<HookResult>,
+}
+
+/// where the `pre-push` hook runs and what it is told about the remote
+#[derive(Clone)]
+struct PrePush {
+	repo_path: RepoPath,
+	remote_name: String,
+	remote_url: String,
 }
 
 ///
@@ -20,6 +36,7 @@ pub struct Callbacks {
 	basic_credential: Option<BasicAuthCredential>,
 	stats: Arc<Mutex<CallbackStats>>,
 	first_call_to_credentials: Arc<AtomicBool>,
+	pre_push: Option<PrePush>,
 }
 
 impl Callbacks {
@@ -37,9 +54,28 @@ impl Callbacks {
 			first_call_to_credentials: Arc::new(AtomicBool::new(
 				true,
 			)),
+			pre_push: None,
 		}
 	}
 
+	/// runs the `pre-push` hook of `repo_path` once the refs to push
+	/// are known, before anything is sent. a rejection ends up in
+	/// [`CallbackStats::pre_push_rejection`]
+	#[must_use]
+	pub fn with_pre_push_hook(
+		mut self,
+		repo_path: RepoPath,
+		remote_name: &str,
+		remote_url: &str,
+	) -> Self {
+		self.pre_push = Some(PrePush {
+			repo_path,
+			remote_name: remote_name.to_string(),
+			remote_url: remote_url.to_string(),
+		});
+		self
+	}
+
 	///
 	pub fn get_stats(&self) -> Result<CallbackStats> {
 		let stats = self.stats.lock()?;
@@ -80,6 +116,11 @@ impl Callbacks {
 			Ok(())
 		});
 
+		let this = self.clone();
+		callbacks.push_negotiation(move |updates| {
+			this.push_negotiation(updates)
+		});
+
 		let this = self.clone();
 		callbacks.credentials(
 			move |url, username_from_url, allowed_types| {
@@ -119,6 +160,61 @@ impl Callbacks {
 		}
 	}
 
+	fn push_negotiation(
+		&self,
+		updates: &[PushUpdate],
+	) -> std::result::Result<(), GitError> {
+		let Some(pre_push) = &self.pre_push else {
+			return Ok(());
+		};
+
+		// libgit2 calls the current oid of the remote ref `src` and the
+		// one it is updated to `dst`
+		let refs = updates
+			.iter()
+			.map(|update| {
+				let deleted = update.dst().is_zero();
+				PrePushRef {
+					local_ref: if deleted {
+						PrePushRef::DELETE.to_string()
+					} else {
+						update
+							.src_refname()
+							.unwrap_or_default()
+							.to_string()
+					},
+					local_oid: update.dst(),
+					remote_ref: update
+						.dst_refname()
+						.unwrap_or_default()
+						.to_string(),
+					remote_oid: update.src(),
+				}
+			})
+			.collect::<Vec<_>>();
+
+		let result = hooks_pre_push(
+			&pre_push.repo_path,
+			&pre_push.remote_name,
+			&pre_push.remote_url,
+			&refs,
+		)
+		.map_err(|e| {
+			GitError::from_str(&format!(
+				"pre-push hook could not be run: {e}"
+			))
+		})?;
+
+		if result == HookResult::Ok {
+			return Ok(());
+		}
+
+		if let Ok(mut stats) = self.stats.lock() {
+			stats.pre_push_rejection = Some(result);
+		}
+		Err(GitError::from_str("pre-push hook rejected the push"))
+	}
+
 	fn pack_progress(
 		&self,
 		stage: git2::PackBuilderStage,
@@ -0,0 +1,340 @@
+//! fetching a single branch or refspec instead of everything, see
+//! [`FetchTarget`].
+
+use super::{
+	get_default_remote_for_fetch_in_repo, proxy_auto, Callbacks,
+};
+use crate::{
+	error::{Error, Result},
+	sync::{cred::BasicAuthCredential, repository::repo, RepoPath},
+	ProgressPercent,
+};
+use crossbeam_channel::Sender;
+use git2::{FetchOptions, Reference, ReferenceFormat};
+use scopetime::scope_time;
+
+/// what [`fetch_target`] fetches: `refspec` from `remote`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FetchTarget {
+	///
+	pub remote: String,
+	/// a full refspec, a plain branch name is expanded by
+	/// [`FetchTarget::parse`]
+	pub refspec: String,
+}
+
+impl FetchTarget {
+	/// parses what was typed to fetch, one of
+	/// * `<branch>` from the default remote (for fetching)
+	/// * `<remote>/<branch>` like the remote tracking branch
+	/// * `<remote> <branch or refspec>`
+	/// * `<refspec>` from the default remote
+	///
+	/// the refspec is validated, so typos are reported before
+	/// connecting to the remote
+	pub fn parse(repo_path: &RepoPath, input: &str) -> Result<Self> {
+		let repo = repo(repo_path)?;
+		let input = input.trim();
+		let remotes = repo
+			.remotes()?
+			.iter()
+			.flatten()
+			.map(String::from)
+			.collect::<Vec<_>>();
+
+		if input.is_empty() {
+			return Err(Error::Generic(String::from(
+				"nothing to fetch: enter a branch or refspec",
+			)));
+		}
+
+		let (remote, spec) = if let Some((remote, spec)) =
+			input.split_once(char::is_whitespace)
+		{
+			if !remotes.iter().any(|r| r == remote) {
+				return Err(Error::Generic(format!(
+					"unknown remote '{remote}'"
+				)));
+			}
+
+			(remote.to_string(), spec.trim())
+		} else if let Some((remote, branch)) =
+			remote_branch(&remotes, input)
+		{
+			(remote.to_string(), branch)
+		} else {
+			(get_default_remote_for_fetch_in_repo(&repo)?, input)
+		};
+
+		validate_refspec(spec)?;
+
+		Ok(Self {
+			refspec: expand_refspec(&remote, spec),
+			remote,
+		})
+	}
+}
+
+/// `origin/main` split into the longest matching remote and the
+/// branch, refspecs are left alone
+fn remote_branch<'a>(
+	remotes: &'a [String],
+	input: &'a str,
+) -> Option<(&'a str, &'a str)> {
+	if input.contains(':') || input.starts_with("refs/") {
+		return None;
+	}
+
+	remotes
+		.iter()
+		.filter_map(|remote| {
+			input
+				.strip_prefix(remote.as_str())
+				.and_then(|rest| rest.strip_prefix('/'))
+				.filter(|branch| !branch.is_empty())
+				.map(|branch| (remote.as_str(), branch))
+		})
+		.max_by_key(|(remote, _)| remote.len())
+}
+
+/// a branch name is fetched into its remote tracking branch (like the
+/// default refspec does), anything else is used as is
+fn expand_refspec(remote: &str, spec: &str) -> String {
+	if spec.contains(':') {
+		return spec.to_string();
+	}
+
+	let src = if spec.starts_with("refs/") {
+		spec.to_string()
+	} else {
+		format!("refs/heads/{spec}")
+	};
+
+	src.strip_prefix("refs/heads/").map_or_else(
+		|| src.clone(),
+		|branch| format!("+{src}:refs/remotes/{remote}/{branch}"),
+	)
+}
+
+/// checks `spec` is `[+]<src>[:<dst>]` with valid ref names on both
+/// sides and a `*` in either both or neither of them
+pub fn validate_refspec(spec: &str) -> Result<()> {
+	let invalid = |reason: String| {
+		Err(Error::Generic(format!(
+			"invalid refspec '{spec}': {reason}"
+		)))
+	};
+
+	let body = spec.strip_prefix('+').unwrap_or(spec);
+	let (src, dst) = match body.split_once(':') {
+		Some((src, dst)) => (src, Some(dst)),
+		None => (body, None),
+	};
+
+	if src.is_empty() {
+		return invalid(String::from("the source is empty"));
+	}
+	if dst.is_some_and(|dst| dst.contains(':')) {
+		return invalid(String::from("more than one ':'"));
+	}
+
+	let dst = dst.filter(|dst| !dst.is_empty());
+	for name in std::iter::once(src).chain(dst) {
+		if name.matches('*').count() > 1
+			|| Reference::normalize_name(
+				name,
+				ReferenceFormat::ALLOW_ONELEVEL
+					| ReferenceFormat::REFSPEC_PATTERN
+					| ReferenceFormat::REFSPEC_SHORTHAND,
+			)
+			.is_err()
+		{
+			return invalid(format!(
+				"'{name}' is not a valid ref name"
+			));
+		}
+	}
+
+	if let Some(dst) = dst {
+		if src.contains('*') != dst.contains('*') {
+			return invalid(String::from(
+				"a '*' has to be on both sides or on neither",
+			));
+		}
+	}
+
+	Ok(())
+}
+
+/// fetches only `target`: no tags and no pruning, so nothing but the
+/// ref(s) matching the refspec is touched
+pub fn fetch_target(
+	repo_path: &RepoPath,
+	target: &FetchTarget,
+	basic_credential: Option<BasicAuthCredential>,
+	progress_sender: Option<Sender<ProgressPercent>>,
+) -> Result<()> {
+	scope_time!("fetch_target");
+
+	let repo = repo(repo_path)?;
+	let mut remote = repo.find_remote(&target.remote)?;
+
+	let mut options = FetchOptions::new();
+	let callbacks = Callbacks::new(None, basic_credential);
+	options.proxy_options(proxy_auto());
+	options.download_tags(git2::AutotagOption::None);
+	options.remote_callbacks(callbacks.callbacks());
+	remote.fetch(
+		&[target.refspec.as_str()],
+		Some(&mut options),
+		None,
+	)?;
+
+	if let Some(sender) = progress_sender {
+		sender.send(ProgressPercent::full())?;
+	}
+
+	Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::sync::remotes::push::push_branch;
+	use crate::sync::tests::{
+		repo_clone, repo_init_bare, write_commit_file,
+	};
+
+	#[test]
+	fn test_validate_refspec() {
+		for spec in [
+			"main",
+			"+refs/heads/main:refs/remotes/origin/main",
+			"refs/heads/*:refs/remotes/origin/*",
+			"refs/pull/1/head",
+			"refs/heads/main:",
+		] {
+			assert!(validate_refspec(spec).is_ok(), "{spec}");
+		}
+
+		for (spec, reason) in [
+			("", "the source is empty"),
+			(":refs/heads/main", "the source is empty"),
+			("a:b:c", "more than one ':'"),
+			("main..x", "'main..x' is not a valid ref name"),
+			("refs/heads/*:refs/remotes/x", "a '*' has to be"),
+			("refs/*/*", "'refs/*/*' is not a valid ref name"),
+		] {
+			let err = validate_refspec(spec).unwrap_err().to_string();
+			assert!(err.contains(reason), "{spec}: {err}");
+		}
+	}
+
+	#[test]
+	fn test_parse() {
+		let (upstream_dir, _upstream) = repo_init_bare().unwrap();
+		let (clone_dir, clone) =
+			repo_clone(upstream_dir.path().to_str().unwrap())
+				.unwrap();
+		let repo_path: &RepoPath =
+			&clone_dir.path().to_str().unwrap().into();
+
+		clone.remote("origin/fork", "https://example.com").unwrap();
+
+		let target = |remote: &str, refspec: &str| FetchTarget {
+			remote: remote.to_string(),
+			refspec: refspec.to_string(),
+		};
+
+		assert_eq!(
+			FetchTarget::parse(repo_path, "feature/x").unwrap(),
+			target(
+				"origin",
+				"+refs/heads/feature/x:refs/remotes/origin/feature/x"
+			)
+		);
+		assert_eq!(
+			FetchTarget::parse(repo_path, "origin/main").unwrap(),
+			target(
+				"origin",
+				"+refs/heads/main:refs/remotes/origin/main"
+			)
+		);
+		// the longest remote wins
+		assert_eq!(
+			FetchTarget::parse(repo_path, "origin/fork/main")
+				.unwrap(),
+			target(
+				"origin/fork",
+				"+refs/heads/main:refs/remotes/origin/fork/main"
+			)
+		);
+		assert_eq!(
+			FetchTarget::parse(
+				repo_path,
+				" origin/fork  refs/pull/1/head"
+			)
+			.unwrap(),
+			target("origin/fork", "refs/pull/1/head")
+		);
+		assert_eq!(
+			FetchTarget::parse(
+				repo_path,
+				"refs/tags/v1:refs/tags/v1"
+			)
+			.unwrap(),
+			target("origin", "refs/tags/v1:refs/tags/v1")
+		);
+
+		assert!(FetchTarget::parse(repo_path, "").is_err());
+		assert!(FetchTarget::parse(repo_path, "nope main").is_err());
+		assert!(
+			FetchTarget::parse(repo_path, "origin a:b:c").is_err()
+		);
+	}
+
+	#[test]
+	fn test_fetch_target_only_fetches_target() {
+		let (upstream_dir, _upstream) = repo_init_bare().unwrap();
+		let upstream_path = upstream_dir.path().to_str().unwrap();
+
+		let (clone_dir, clone) = repo_clone(upstream_path).unwrap();
+		let clone_path: &RepoPath =
+			&clone_dir.path().to_str().unwrap().into();
+
+		let (pusher_dir, pusher) = repo_clone(upstream_path).unwrap();
+		let pusher_path: &RepoPath =
+			&pusher_dir.path().to_str().unwrap().into();
+		write_commit_file(&pusher, "a.txt", "a", "c1");
+		let head = pusher.head().unwrap().peel_to_commit().unwrap();
+		pusher.branch("other", &head, false).unwrap();
+		for branch in ["master", "other"] {
+			push_branch(
+				pusher_path,
+				"origin",
+				branch,
+				false,
+				false,
+				None,
+				None,
+			)
+			.unwrap();
+		}
+
+		fetch_target(
+			clone_path,
+			&FetchTarget::parse(clone_path, "other").unwrap(),
+			None,
+			None,
+		)
+		.unwrap();
+
+		assert!(clone
+			.find_reference("refs/remotes/origin/other")
+			.is_ok());
+		// master is not fetched along with it
+		assert!(clone
+			.find_reference("refs/remotes/origin/master")
+			.is_err());
+	}
+}
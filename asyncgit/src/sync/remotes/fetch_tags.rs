@@ -0,0 +1,215 @@
+//! which tags a fetch downloads (see [`FetchTags`]) and fetching just
+//! the tags, see [`fetch_tags`].
+
+use super::{proxy_auto, Callbacks};
+use crate::{
+	error::Result,
+	sync::{cred::BasicAuthCredential, repository::repo, RepoPath},
+	ProgressPercent,
+};
+use crossbeam_channel::Sender;
+use git2::{AutotagOption, FetchOptions, Repository};
+use scopetime::scope_time;
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+
+/// the tags a fetch downloads along with the branches, `--tags` and
+/// `--no-tags` of `git fetch`
+#[derive(
+	Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize,
+)]
+pub enum FetchTags {
+	/// the tags pointing into the fetched history (git's default)
+	Auto,
+	/// no tags at all
+	None,
+	/// every tag of the remote, tags deleted on the remote are deleted
+	/// locally as well
+	#[default]
+	All,
+}
+
+impl FetchTags {
+	/// the next one to toggle to
+	#[must_use]
+	pub const fn next(self) -> Self {
+		match self {
+			Self::All => Self::Auto,
+			Self::Auto => Self::None,
+			Self::None => Self::All,
+		}
+	}
+
+	/// `remote.<name>.tagOpt`, which only knows `--tags` and
+	/// `--no-tags`
+	fn from_tag_opt(value: &str) -> Option<Self> {
+		match value {
+			"--tags" => Some(Self::All),
+			"--no-tags" => Some(Self::None),
+			_ => None,
+		}
+	}
+
+	pub(crate) const fn autotag(self) -> AutotagOption {
+		match self {
+			Self::Auto => AutotagOption::Auto,
+			Self::None => AutotagOption::None,
+			Self::All => AutotagOption::All,
+		}
+	}
+}
+
+/// what `remote.<remote>.tagOpt` asks for, all tags if it is not set
+pub fn get_remote_fetch_tags(
+	repo_path: &RepoPath,
+	remote: &str,
+) -> Result<FetchTags> {
+	scope_time!("get_remote_fetch_tags");
+
+	remote_fetch_tags_in_repo(&repo(repo_path)?, remote)
+}
+
+pub fn remote_fetch_tags_in_repo(
+	repo: &Repository,
+	remote: &str,
+) -> Result<FetchTags> {
+	Ok(repo
+		.config()?
+		.get_string(&format!("remote.{remote}.tagOpt"))
+		.ok()
+		.and_then(|value| FetchTags::from_tag_opt(&value))
+		.unwrap_or_default())
+}
+
+/// fetches every tag of every remote no matter which [`FetchTags`] they
+/// use, without touching the branches. tags deleted on a remote are
+/// kept. returns the amount of tags created
+pub fn fetch_tags(
+	repo_path: &RepoPath,
+	basic_credential: &Option<BasicAuthCredential>,
+	progress_sender: &Option<Sender<ProgressPercent>>,
+) -> Result<usize> {
+	scope_time!("fetch_tags");
+
+	let repo = repo(repo_path)?;
+	let remotes = repo
+		.remotes()?
+		.iter()
+		.flatten()
+		.map(String::from)
+		.collect::<Vec<_>>();
+
+	count_created_tags(&repo, || {
+		for (idx, remote) in remotes.iter().enumerate() {
+			let mut remote = repo.find_remote(remote)?;
+
+			let mut options = FetchOptions::new();
+			let callbacks =
+				Callbacks::new(None, basic_credential.clone());
+			options.proxy_options(proxy_auto());
+			options.download_tags(AutotagOption::All);
+			options.remote_callbacks(callbacks.callbacks());
+			remote.fetch(
+				&["refs/tags/*:refs/tags/*"],
+				Some(&mut options),
+				None,
+			)?;
+
+			if let Some(sender) = progress_sender {
+				sender
+					.send(ProgressPercent::new(idx, remotes.len()))?;
+			}
+		}
+
+		Ok(())
+	})
+}
+
+/// runs `fetch` and returns how many tags it created
+pub fn count_created_tags(
+	repo: &Repository,
+	fetch: impl FnOnce() -> Result<()>,
+) -> Result<usize> {
+	let tags = |repo: &Repository| -> Result<HashSet<String>> {
+		Ok(repo
+			.tag_names(None)?
+			.iter()
+			.flatten()
+			.map(String::from)
+			.collect())
+	};
+
+	let before = tags(repo)?;
+	fetch()?;
+
+	Ok(tags(repo)?.difference(&before).count())
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::sync::{
+		self,
+		remotes::{fetch_all, push::push_branch, tags::push_tags},
+		tests::{repo_clone, repo_init_bare, write_commit_file},
+	};
+	use std::collections::BTreeMap;
+
+	#[test]
+	fn test_fetch_tags() {
+		let (upstream_dir, _upstream) = repo_init_bare().unwrap();
+		let upstream_dir = upstream_dir.path().to_str().unwrap();
+
+		let (clone1_dir, clone1) = repo_clone(upstream_dir).unwrap();
+		let clone1_dir: &RepoPath =
+			&clone1_dir.path().to_str().unwrap().into();
+		let (clone2_dir, clone2) = repo_clone(upstream_dir).unwrap();
+		let clone2_dir: &RepoPath =
+			&clone2_dir.path().to_str().unwrap().into();
+
+		let commit1 =
+			write_commit_file(&clone1, "a.txt", "a", "commit1");
+		push_branch(
+			clone1_dir, "origin", "master", false, false, None, None,
+		)
+		.unwrap();
+		sync::tag_commit(clone1_dir, &commit1, "tag1", None).unwrap();
+		push_tags(clone1_dir, "origin", None, None).unwrap();
+
+		let mut config = clone2.config().unwrap();
+		assert_eq!(
+			get_remote_fetch_tags(clone2_dir, "origin").unwrap(),
+			FetchTags::All
+		);
+		config.set_str("remote.origin.tagOpt", "--no-tags").unwrap();
+		assert_eq!(
+			get_remote_fetch_tags(clone2_dir, "origin").unwrap(),
+			FetchTags::None
+		);
+
+		assert_eq!(
+			fetch_all(clone2_dir, &BTreeMap::new(), &None, &None)
+				.unwrap(),
+			0
+		);
+		assert!(sync::get_tags(clone2_dir).unwrap().is_empty());
+
+		let auto = BTreeMap::from([(
+			String::from("origin"),
+			FetchTags::Auto,
+		)]);
+		assert_eq!(
+			fetch_all(clone2_dir, &auto, &None, &None).unwrap(),
+			1
+		);
+
+		// only reachable through its tag
+		let commit2 =
+			write_commit_file(&clone1, "a.txt", "b", "commit2");
+		sync::tag_commit(clone1_dir, &commit2, "tag2", None).unwrap();
+		push_tags(clone1_dir, "origin", None, None).unwrap();
+
+		assert_eq!(fetch_tags(clone2_dir, &None, &None).unwrap(), 1);
+		assert_eq!(sync::get_tags(clone2_dir).unwrap().len(), 2);
+	}
+}
@@ -1,6 +1,9 @@
 //!
 
+mod broken_refs;
 mod callbacks;
+mod fetch_ref;
+mod fetch_tags;
 pub(crate) mod push;
 pub(crate) mod tags;
 
@@ -8,18 +11,27 @@ use crate::{
 	error::{Error, Result},
 	sync::{
 		cred::BasicAuthCredential,
-		remotes::push::ProgressNotification, repository::repo, utils,
+		remotes::push::ProgressNotification, repository::repo,
+		staged_summary::multivar, utils,
 	},
 	ProgressPercent,
 };
 use crossbeam_channel::Sender;
+use fetch_tags::{count_created_tags, remote_fetch_tags_in_repo};
 use git2::{
 	BranchType, FetchOptions, ProxyOptions, Remote, Repository,
 };
 use scopetime::scope_time;
+use std::collections::BTreeMap;
 use utils::bytes2string;
 
+pub(crate) use broken_refs::{
+	ensure_ref_not_broken, ref_target_missing,
+};
+pub use broken_refs::{get_broken_remote_refs, repair_remote_ref};
 pub use callbacks::Callbacks;
+pub use fetch_ref::{fetch_target, validate_refspec, FetchTarget};
+pub use fetch_tags::{fetch_tags, get_remote_fetch_tags, FetchTags};
 pub use tags::tags_missing_remote;
 
 use super::RepoPath;
@@ -27,6 +39,10 @@ use super::RepoPath;
 /// origin
 pub const DEFAULT_REMOTE_NAME: &str = "origin";
 
+/// remotes pushed to when pushing to all of them, see
+/// [`get_push_all_remotes`]
+const CONFIG_PUSH_ALL_REMOTE: &str = "gitui.pushAll.remote";
+
 ///
 pub fn proxy_auto<'a>() -> ProxyOptions<'a> {
 	let mut proxy = ProxyOptions::new();
@@ -115,12 +131,84 @@ pub fn get_default_remote(repo_path: &RepoPath) -> Result<String> {
 	get_default_remote_in_repo(&repo)
 }
 
+/// resolves the default branch of `remote` as the name of its remote
+/// tracking branch (e.g. `origin/main`).
+///
+/// follows `refs/remotes/<remote>/HEAD`, which is set up by `git clone`,
+/// `git remote set-head` or fetching (see [`fetch_all`]). this never
+/// asks the remote itself and so returns `None` if that does not exist
+/// (or the remote tracking branch it points at is gone).
+pub fn get_remote_default_branch(
+	repo_path: &RepoPath,
+	remote_name: &str,
+) -> Result<Option<String>> {
+	scope_time!("get_remote_default_branch");
+
+	let repo = repo(repo_path)?;
+	// fails for unknown remotes
+	repo.find_remote(remote_name)?;
+
+	let head_ref = format!("refs/remotes/{remote_name}/HEAD");
+	let Ok(head) = repo.find_reference(&head_ref) else {
+		return Ok(None);
+	};
+
+	Ok(head
+		.symbolic_target()
+		.and_then(|target| target.strip_prefix("refs/remotes/"))
+		.filter(|name| {
+			repo.find_branch(name, BranchType::Remote).is_ok()
+		})
+		.map(String::from))
+}
+
+/// points `refs/remotes/<remote>/HEAD` at the default branch the
+/// remote advertised while we were connected to it (like `git fetch`
+/// does), unless it exists already.
+///
+/// failing to do so is only logged, it must not fail the fetch
+fn set_missing_remote_head(repo: &Repository, remote: &Remote) {
+	let Some(name) = remote.name() else {
+		return;
+	};
+	let head_ref = format!("refs/remotes/{name}/HEAD");
+	if repo.find_reference(&head_ref).is_ok() {
+		return;
+	}
+
+	let result = remote
+		.default_branch()
+		.map_err(Error::from)
+		.and_then(|branch| bytes2string(&branch))
+		.and_then(|branch| {
+			let Some(branch) = branch.strip_prefix("refs/heads/")
+			else {
+				return Ok(());
+			};
+			let target = format!("refs/remotes/{name}/{branch}");
+			if repo.find_reference(&target).is_ok() {
+				repo.reference_symbolic(
+					&head_ref,
+					&target,
+					false,
+					"fetch: set remote HEAD",
+				)?;
+			}
+
+			Ok(())
+		});
+
+	if let Err(e) = result {
+		log::warn!("could not set {head_ref}: {e}");
+	}
+}
+
 /// Gets the current branch the user is on.
 /// Returns none if they are not on a branch
 /// and Err if there was a problem finding the branch
 fn get_current_branch(
 	repo: &Repository,
-) -> Result<Option<git2::Branch>> {
+) -> Result<Option<git2::Branch<'_>>> {
 	for b in repo.branches(None)? {
 		let branch = b?.0;
 		if branch.is_head() {
@@ -204,26 +292,46 @@ pub(crate) fn get_default_remote_for_push_in_repo(
 ) -> Result<String> {
 	scope_time!("get_default_remote_for_push_in_repo");
 
-	let config = repo.config()?;
-
 	let branch = get_current_branch(repo)?;
 
 	if let Some(branch) = branch {
-		let remote_name = bytes2string(branch.name_bytes()?)?;
+		let branch_name = bytes2string(branch.name_bytes()?)?;
 
-		let entry_name =
-			format!("branch.{}.pushRemote", &remote_name);
+		return get_push_remote_for_branch_in_repo(
+			repo,
+			&branch_name,
+		);
+	}
 
-		if let Ok(entry) = config.get_entry(&entry_name) {
-			return bytes2string(entry.value_bytes());
-		}
+	get_default_remote_in_repo(repo)
+}
 
-		if let Ok(entry) = config.get_entry("remote.pushDefault") {
-			return bytes2string(entry.value_bytes());
-		}
+/// the remote `branch` is pushed to
+///
+/// resolved like git does: its `branch.<name>.pushRemote`, then `remote.pushDefault`, then the
+/// remote it tracks (`branch.<name>.remote`) and finally
+/// [`get_default_remote`]
+pub fn get_push_remote_for_branch(
+	repo_path: &RepoPath,
+	branch: &str,
+) -> Result<String> {
+	let repo = repo(repo_path)?;
+	get_push_remote_for_branch_in_repo(&repo, branch)
+}
 
-		let entry_name = format!("branch.{}.remote", &remote_name);
+fn get_push_remote_for_branch_in_repo(
+	repo: &Repository,
+	branch: &str,
+) -> Result<String> {
+	scope_time!("get_push_remote_for_branch_in_repo");
 
+	let config = repo.config()?;
+
+	for entry_name in [
+		format!("branch.{branch}.pushRemote"),
+		String::from("remote.pushDefault"),
+		format!("branch.{branch}.remote"),
+	] {
 		if let Ok(entry) = config.get_entry(&entry_name) {
 			return bytes2string(entry.value_bytes());
 		}
@@ -232,6 +340,38 @@ pub(crate) fn get_default_remote_for_push_in_repo(
 	get_default_remote_in_repo(repo)
 }
 
+/// the remotes pushed to when pushing to all remotes at once:
+/// `gitui.pushAll.remote` (can be repeated) or every remote of the
+/// repo if that is not set
+pub fn get_push_all_remotes(
+	repo_path: &RepoPath,
+) -> Result<Vec<String>> {
+	scope_time!("get_push_all_remotes");
+
+	let repo = repo(repo_path)?;
+	let config = repo.config()?;
+
+	let mut remotes: Vec<String> = Vec::new();
+	for remote in
+		multivar(&config, CONFIG_PUSH_ALL_REMOTE)?.unwrap_or_default()
+	{
+		if !remotes.contains(&remote) {
+			remotes.push(remote);
+		}
+	}
+
+	if remotes.is_empty() {
+		remotes = repo
+			.remotes()?
+			.iter()
+			.flatten()
+			.map(String::from)
+			.collect();
+	}
+
+	Ok(remotes)
+}
+
 /// see `get_default_remote`
 pub(crate) fn get_default_remote_in_repo(
 	repo: &Repository,
@@ -266,40 +406,52 @@ pub(crate) fn get_default_remote_in_repo(
 	Err(Error::NoDefaultRemoteFound)
 }
 
-///
+/// `tags` falls back to `remote.<remote>.tagOpt`
 fn fetch_from_remote(
-	repo_path: &RepoPath,
+	repo: &Repository,
 	remote: &str,
+	tags: Option<FetchTags>,
 	basic_credential: Option<BasicAuthCredential>,
 	progress_sender: Option<Sender<ProgressNotification>>,
 ) -> Result<()> {
-	let repo = repo(repo_path)?;
-
+	let tags = match tags {
+		Some(tags) => tags,
+		None => remote_fetch_tags_in_repo(repo, remote)?,
+	};
 	let mut remote = repo.find_remote(remote)?;
 
 	let mut options = FetchOptions::new();
 	let callbacks = Callbacks::new(progress_sender, basic_credential);
 	options.prune(git2::FetchPrune::On);
 	options.proxy_options(proxy_auto());
-	options.download_tags(git2::AutotagOption::All);
+	options.download_tags(tags.autotag());
 	options.remote_callbacks(callbacks.callbacks());
 	remote.fetch(&[] as &[&str], Some(&mut options), None)?;
-	// fetch tags (also removing remotely deleted ones)
-	remote.fetch(
-		&["refs/tags/*:refs/tags/*"],
-		Some(&mut options),
-		None,
-	)?;
+	set_missing_remote_head(repo, &remote);
+	if tags == FetchTags::All {
+		// fetch tags (also removing remotely deleted ones)
+		remote.fetch(
+			&["refs/tags/*:refs/tags/*"],
+			Some(&mut options),
+			None,
+		)?;
+	}
 
 	Ok(())
 }
 
-/// updates/prunes all branches from all remotes
+/// updates/prunes all branches from all remotes, fetching the tags
+/// `tags` asks for (by remote name) or `remote.<name>.tagOpt` says.
+/// returns the amount of tags created.
+///
+/// a missing `refs/remotes/<remote>/HEAD` is set up along the way, see
+/// [`get_remote_default_branch`]
 pub fn fetch_all(
 	repo_path: &RepoPath,
+	tags: &BTreeMap<String, FetchTags>,
 	basic_credential: &Option<BasicAuthCredential>,
 	progress_sender: &Option<Sender<ProgressPercent>>,
-) -> Result<()> {
+) -> Result<usize> {
 	scope_time!("fetch_all");
 
 	let repo = repo(repo_path)?;
@@ -311,21 +463,25 @@ pub fn fetch_all(
 		.collect::<Vec<_>>();
 	let remotes_count = remotes.len();
 
-	for (idx, remote) in remotes.into_iter().enumerate() {
-		fetch_from_remote(
-			repo_path,
-			&remote,
-			basic_credential.clone(),
-			None,
-		)?;
-
-		if let Some(sender) = progress_sender {
-			let progress = ProgressPercent::new(idx, remotes_count);
-			sender.send(progress)?;
+	count_created_tags(&repo, || {
+		for (idx, remote) in remotes.into_iter().enumerate() {
+			fetch_from_remote(
+				&repo,
+				&remote,
+				tags.get(&remote).copied(),
+				basic_credential.clone(),
+				None,
+			)?;
+
+			if let Some(sender) = progress_sender {
+				let progress =
+					ProgressPercent::new(idx, remotes_count);
+				sender.send(progress)?;
+			}
 		}
-	}
 
-	Ok(())
+		Ok(())
+	})
 }
 
 /// fetches from upstream/remote for local `branch`
@@ -353,6 +509,7 @@ pub(crate) fn fetch(
 	options.proxy_options(proxy_auto());
 
 	remote.fetch(&[branch], Some(&mut options), None)?;
+	set_missing_remote_head(&repo, &remote);
 
 	Ok(remote.stats().received_bytes())
 }
@@ -383,6 +540,52 @@ mod tests {
 		fetch(repo_path, "master", None, None).unwrap();
 	}
 
+	#[test]
+	fn test_remote_default_branch() {
+		let (remote_dir, _remote) = repo_init().unwrap();
+		let remote_path = remote_dir.path().to_str().unwrap();
+		let (repo_dir, repo) = repo_clone(remote_path).unwrap();
+		let repo_path: &RepoPath =
+			&repo_dir.path().to_path_buf().into();
+
+		assert_eq!(
+			get_remote_default_branch(repo_path, "origin").unwrap(),
+			Some(String::from("origin/master"))
+		);
+
+		// without `origin/HEAD` the remote is not asked
+		repo.find_reference("refs/remotes/origin/HEAD")
+			.unwrap()
+			.delete()
+			.unwrap();
+
+		assert_eq!(
+			get_remote_default_branch(repo_path, "origin").unwrap(),
+			None
+		);
+
+		// but fetching sets it up again
+		fetch(repo_path, "master", None, None).unwrap();
+
+		assert_eq!(
+			get_remote_default_branch(repo_path, "origin").unwrap(),
+			Some(String::from("origin/master"))
+		);
+
+		repo.find_reference("refs/remotes/origin/HEAD")
+			.unwrap()
+			.delete()
+			.unwrap();
+		fetch_all(repo_path, &BTreeMap::new(), &None, &None).unwrap();
+
+		assert_eq!(
+			get_remote_default_branch(repo_path, "origin").unwrap(),
+			Some(String::from("origin/master"))
+		);
+
+		assert!(get_remote_default_branch(repo_path, "foo").is_err());
+	}
+
 	#[test]
 	fn test_default_remote() {
 		let (remote_dir, _remote) = repo_init().unwrap();
@@ -580,4 +783,78 @@ mod tests {
 			matches!(default_push_remote, Ok(remote_name) if remote_name == "branchpushremote")
 		);
 	}
+
+	#[test]
+	fn test_push_remote_for_branch() {
+		let (_td, repo) = repo_init().unwrap();
+		let root = repo.path().parent().unwrap();
+		let repo_path: &RepoPath =
+			&root.as_os_str().to_str().unwrap().into();
+
+		repo.remote("origin", "https://example.com/origin").unwrap();
+		repo.remote("mirror", "https://example.com/mirror").unwrap();
+
+		let mut config = repo.config().unwrap();
+
+		assert_eq!(
+			get_push_remote_for_branch(repo_path, "other").unwrap(),
+			"origin"
+		);
+
+		config.set_str("branch.other.remote", "mirror").unwrap();
+		assert_eq!(
+			get_push_remote_for_branch(repo_path, "other").unwrap(),
+			"mirror"
+		);
+
+		config.set_str("remote.pushDefault", "pushdefault").unwrap();
+		assert_eq!(
+			get_push_remote_for_branch(repo_path, "other").unwrap(),
+			"pushdefault"
+		);
+
+		// only for the branch it is set for
+		config
+			.set_str("branch.master.pushRemote", "branchpushremote")
+			.unwrap();
+		assert_eq!(
+			get_push_remote_for_branch(repo_path, "other").unwrap(),
+			"pushdefault"
+		);
+		assert_eq!(
+			get_push_remote_for_branch(repo_path, "master").unwrap(),
+			"branchpushremote"
+		);
+	}
+
+	#[test]
+	fn test_push_all_remotes() {
+		let (_td, repo) = repo_init().unwrap();
+		let root = repo.path().parent().unwrap();
+		let repo_path: &RepoPath =
+			&root.as_os_str().to_str().unwrap().into();
+
+		assert!(get_push_all_remotes(repo_path).unwrap().is_empty());
+
+		repo.remote("origin", "https://example.com/origin").unwrap();
+		repo.remote("mirror", "https://example.com/mirror").unwrap();
+		repo.remote("fork", "https://example.com/fork").unwrap();
+
+		assert_eq!(
+			get_push_all_remotes(repo_path).unwrap(),
+			vec!["fork", "mirror", "origin"]
+		);
+
+		let mut config = repo.config().unwrap();
+		for remote in ["origin", "mirror", "origin"] {
+			config
+				.set_multivar(CONFIG_PUSH_ALL_REMOTE, "^$", remote)
+				.unwrap();
+		}
+
+		assert_eq!(
+			get_push_all_remotes(repo_path).unwrap(),
+			vec!["origin", "mirror"]
+		);
+	}
 }
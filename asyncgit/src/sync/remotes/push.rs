@@ -2,6 +2,7 @@ use crate::{
 	error::{Error, Result},
 	progress::ProgressPercent,
 	sync::{
+		audit::{self, AuditOperation},
 		branch::branch_set_upstream_after_push,
 		config::{
 			push_default_strategy_config_repo,
@@ -11,7 +12,7 @@ use crate::{
 		get_branch_upstream_merge,
 		remotes::{proxy_auto, Callbacks},
 		repository::repo,
-		CommitId, RepoPath,
+		CommitId, HookRejection, HookType, RepoPath,
 	},
 };
 use crossbeam_channel::Sender;
@@ -98,20 +99,15 @@ impl AsyncProgress for ProgressNotification {
 }
 
 ///
-#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Default)]
 pub enum PushType {
 	///
+	#[default]
 	Branch,
 	///
 	Tag,
 }
 
-impl Default for PushType {
-	fn default() -> Self {
-		Self::Branch
-	}
-}
-
 #[cfg(test)]
 pub fn push_branch(
 	repo_path: &RepoPath,
@@ -157,7 +153,13 @@ pub fn push_raw(
 	let mut options = PushOptions::new();
 	options.proxy_options(proxy_auto());
 
-	let callbacks = Callbacks::new(progress_sender, basic_credential);
+	let remote_url = remote.pushurl().or_else(|| remote.url());
+	let callbacks = Callbacks::new(progress_sender, basic_credential)
+		.with_pre_push_hook(
+			repo_path.clone(),
+			remote.name().unwrap_or_default(),
+			remote_url.unwrap_or_default(),
+		);
 	options.remote_callbacks(callbacks.callbacks());
 	options.packbuilder_parallelism(0);
 
@@ -172,8 +174,8 @@ pub fn push_raw(
 		PushType::Tag => "tags",
 	};
 
-	let mut push_ref =
-		format!("{branch_modifier}refs/{git_ref_type}/{branch}");
+	let local_ref = format!("refs/{git_ref_type}/{branch}");
+	let mut push_ref = format!("{branch_modifier}{local_ref}");
 
 	if !delete
 		&& ref_type == PushType::Branch
@@ -188,20 +190,45 @@ pub fn push_raw(
 	}
 
 	log::debug!("push to: {push_ref}");
-	remote.push(&[push_ref], Some(&mut options))?;
+	let pushed = remote.push(&[push_ref], Some(&mut options));
 
-	if let Some((reference, msg)) =
-		callbacks.get_stats()?.push_rejected_msg
-	{
+	let stats = callbacks.get_stats()?;
+	if let Some(result) = stats.pre_push_rejection {
+		return Err(HookRejection::Rejected {
+			hook: HookType::PrePush,
+			result,
+		}
+		.into());
+	}
+	pushed?;
+
+	if let Some((reference, msg)) = stats.push_rejected_msg {
 		return Err(Error::Generic(format!(
 			"push to '{reference}' rejected: {msg}"
 		)));
 	}
 
 	if !delete {
-		branch_set_upstream_after_push(&repo, branch)?;
+		branch_set_upstream_after_push(
+			&repo,
+			branch,
+			remote.name().unwrap_or_default(),
+		)?;
 	}
 
+	// the remote name only, its url may contain credentials
+	audit::record(
+		repo_path,
+		AuditOperation::Push,
+		&[remote.name().unwrap_or_default(), &local_ref],
+		None,
+		if delete {
+			None
+		} else {
+			audit::ref_target(&repo, &local_ref)
+		},
+	);
+
 	Ok(())
 }
 
@@ -524,4 +551,135 @@ mod tests {
 			.map(|(i, _)| i.name().unwrap().unwrap().to_string())
 			.any(|i| &i == "test_branch"));
 	}
+
+	#[test]
+	fn test_push_to_mirror_keeps_upstream() {
+		let (origin_dir, _origin) = repo_init_bare().unwrap();
+		let (mirror_dir, _mirror) = repo_init_bare().unwrap();
+		let (tmp_repo_dir, repo) = repo_init().unwrap();
+		let repo_path: &RepoPath =
+			&tmp_repo_dir.path().to_str().unwrap().into();
+
+		repo.remote("origin", origin_dir.path().to_str().unwrap())
+			.unwrap();
+		repo.remote("mirror", mirror_dir.path().to_str().unwrap())
+			.unwrap();
+		repo.config()
+			.unwrap()
+			.set_str("remote.pushDefault", "mirror")
+			.unwrap();
+
+		let upstream = || {
+			repo.find_branch("master", git2::BranchType::Local)
+				.unwrap()
+				.upstream()
+				.ok()
+				.map(|b| b.name().unwrap().unwrap().to_string())
+		};
+
+		push_branch(
+			repo_path, "mirror", "master", false, false, None, None,
+		)
+		.unwrap();
+		assert_eq!(upstream(), None);
+
+		push_branch(
+			repo_path, "origin", "master", false, false, None, None,
+		)
+		.unwrap();
+		assert_eq!(upstream().as_deref(), Some("origin/master"));
+
+		push_branch(
+			repo_path, "mirror", "master", false, false, None, None,
+		)
+		.unwrap();
+		assert_eq!(upstream().as_deref(), Some("origin/master"));
+	}
+
+	#[test]
+	fn test_pre_push_hook_rejects() {
+		let (upstream_dir, upstream_repo) = repo_init_bare().unwrap();
+		let (tmp_repo_dir, repo) =
+			repo_clone(upstream_dir.path().to_str().unwrap())
+				.unwrap();
+		let repo_path: &RepoPath =
+			&tmp_repo_dir.path().to_str().unwrap().into();
+
+		write_commit_file(&repo, "temp_file.txt", "a", "commit");
+
+		git2_hooks::create_hook(
+			&repo,
+			git2_hooks::HOOK_PRE_PUSH,
+			b"#!/bin/sh\necho 'no pushing today'\nexit 1\n",
+		);
+
+		let err = push_branch(
+			repo_path, "origin", "master", false, false, None, None,
+		)
+		.unwrap_err();
+
+		assert!(matches!(err, Error::HookRejected(_)));
+		assert!(err.to_string().contains("no pushing today"));
+		assert!(upstream_repo
+			.branches(None)
+			.unwrap()
+			.next()
+			.is_none());
+	}
+
+	#[test]
+	fn test_pre_push_hook_input() {
+		let (upstream_dir, _upstream_repo) =
+			repo_init_bare().unwrap();
+		let (tmp_repo_dir, repo) =
+			repo_clone(upstream_dir.path().to_str().unwrap())
+				.unwrap();
+		let repo_path: &RepoPath =
+			&tmp_repo_dir.path().to_str().unwrap().into();
+		let input = repo.path().join("pre-push-input");
+
+		git2_hooks::create_hook(
+			&repo,
+			git2_hooks::HOOK_PRE_PUSH,
+			format!(
+				"#!/bin/sh\n(echo \"$1 $2\"; cat) > '{}'\n",
+				input.display()
+			)
+			.as_bytes(),
+		);
+
+		let first =
+			write_commit_file(&repo, "temp_file.txt", "a", "first");
+		push_branch(
+			repo_path, "origin", "master", false, false, None, None,
+		)
+		.unwrap();
+
+		let url = upstream_dir.path().to_str().unwrap();
+		let zero = "0".repeat(40);
+		assert_eq!(
+			std::fs::read_to_string(&input).unwrap(),
+			format!("origin {url}\nrefs/heads/master {first} refs/heads/master {zero}\n")
+		);
+
+		let second =
+			write_commit_file(&repo, "temp_file.txt", "b", "second");
+		push_branch(
+			repo_path, "origin", "master", false, false, None, None,
+		)
+		.unwrap();
+		assert_eq!(
+			std::fs::read_to_string(&input).unwrap(),
+			format!("origin {url}\nrefs/heads/master {second} refs/heads/master {first}\n")
+		);
+
+		push_branch(
+			repo_path, "origin", "master", false, true, None, None,
+		)
+		.unwrap();
+		assert_eq!(
+			std::fs::read_to_string(&input).unwrap(),
+			format!("origin {url}\n(delete) {zero} refs/heads/master {second}\n")
+		);
+	}
 }
@@ -168,6 +168,7 @@ mod tests {
 		PushType,
 	};
 	use pretty_assertions::assert_eq;
+	use std::collections::BTreeMap;
 	use sync::tests::write_commit_file;
 
 	#[test]
@@ -365,7 +366,8 @@ mod tests {
 
 		// clone 2 - pull
 
-		fetch_all(clone2_dir, &None, &None).unwrap();
+		fetch_all(clone2_dir, &BTreeMap::new(), &None, &None)
+			.unwrap();
 
 		let tags2 = sync::get_tags(clone2_dir).unwrap();
 
@@ -399,7 +401,8 @@ mod tests {
 
 		// clone 2 - pull
 
-		fetch_all(clone2_dir, &None, &None).unwrap();
+		fetch_all(clone2_dir, &BTreeMap::new(), &None, &None)
+			.unwrap();
 		assert_eq!(sync::get_tags(clone2_dir).unwrap().len(), 1);
 
 		// delete on clone 1
@@ -422,7 +425,8 @@ mod tests {
 
 		// clone 2
 
-		fetch_all(clone2_dir, &None, &None).unwrap();
+		fetch_all(clone2_dir, &BTreeMap::new(), &None, &None)
+			.unwrap();
 		assert_eq!(sync::get_tags(clone2_dir).unwrap().len(), 0);
 	}
 }
@@ -1,6 +1,14 @@
-use super::{utils::get_head_repo, CommitId, RepoPath};
+use super::{
+	audit::{self, AuditOperation},
+	hooks::{with_reference_transaction, RefUpdate},
+	index_cache::invalidate_index_cache_repo,
+	pseudo_refs::set_orig_head,
+	state::ensure_clean_state,
+	utils::{get_head_repo, head_target_ref},
+	CommitId, RepoPath,
+};
 use crate::{error::Result, sync::repository::repo};
-use git2::{build::CheckoutBuilder, ObjectType, ResetType};
+use git2::{build::CheckoutBuilder, ObjectType, Oid, ResetType};
 use scopetime::scope_time;
 
 ///
@@ -18,6 +26,8 @@ pub fn reset_stage(repo_path: &RepoPath, path: &str) -> Result<()> {
 		repo.reset_default(None, [path])?;
 	}
 
+	invalidate_index_cache_repo(&repo);
+
 	Ok(())
 }
 
@@ -38,7 +48,8 @@ pub fn reset_workdir(repo_path: &RepoPath, path: &str) -> Result<()> {
 	Ok(())
 }
 
-///
+/// moves `HEAD` (and the branch it points at) to `commit`, running the
+/// `reference-transaction` hook which can abort it
 pub fn reset_repo(
 	repo_path: &RepoPath,
 	commit: CommitId,
@@ -47,27 +58,52 @@ pub fn reset_repo(
 	scope_time!("reset_repo");
 
 	let repo = repo(repo_path)?;
+	ensure_clean_state(&repo)?;
 
 	let c = repo.find_commit(commit.into())?;
+	let head = get_head_repo(&repo).ok();
+
+	let update = RefUpdate {
+		old_oid: head.map_or_else(Oid::zero, Into::into),
+		new_oid: commit.into(),
+		refname: head_target_ref(&repo)?,
+	};
+	with_reference_transaction(repo_path, &[update], || {
+		repo.reset(c.as_object(), kind, None)?;
+
+		Ok(())
+	})?;
+
+	if let Some(head) = head {
+		set_orig_head(&repo, head.into())?;
+	}
 
-	repo.reset(c.as_object(), kind, None)?;
+	audit::record(
+		repo_path,
+		AuditOperation::Reset,
+		&[&audit::head_ref(&repo)],
+		head,
+		Some(commit),
+	);
 
 	Ok(())
 }
 
 #[cfg(test)]
 mod tests {
-	use super::{reset_stage, reset_workdir};
+	use super::{reset_repo, reset_stage, reset_workdir};
 	use crate::error::Result;
 	use crate::sync::{
-		commit,
+		commit, get_head,
 		status::{get_status, StatusType},
 		tests::{
-			debug_cmd_print, get_statuses, repo_init, repo_init_empty,
+			debug_cmd_print, get_statuses, repo_init,
+			repo_init_empty, write_commit_file,
 		},
 		utils::{stage_add_all, stage_add_file},
 		RepoPath,
 	};
+	use git2::ResetType;
 	use std::{
 		fs::{self, File},
 		io::Write,
@@ -336,4 +372,48 @@ mod tests {
 
 		assert_eq!(get_statuses(repo_path), (0, 0));
 	}
+
+	#[test]
+	fn test_reset_repo_reference_transaction() {
+		let (td, repo) = repo_init().unwrap();
+		let root = repo.path().parent().unwrap();
+		let repo_path: &RepoPath =
+			&root.as_os_str().to_str().unwrap().into();
+		let log = td.path().join("transaction.log");
+
+		let first = get_head(repo_path).unwrap();
+		let second = write_commit_file(&repo, "a.txt", "a", "second");
+
+		// logs every state with its input, rejects `prepared` while
+		// there is a `reject` file
+		let hook = format!(
+			"#!/bin/sh\necho \"$1\" >> '{log}'\ncat >> '{log}'\ntest \"$1\" != prepared || test ! -e '{reject}'\n",
+			log = log.display(),
+			reject = td.path().join("reject").display(),
+		);
+		git2_hooks::create_hook(
+			&repo,
+			git2_hooks::HOOK_REFERENCE_TRANSACTION,
+			hook.as_bytes(),
+		);
+
+		File::create(td.path().join("reject")).unwrap();
+		assert!(
+			reset_repo(repo_path, first, ResetType::Hard).is_err()
+		);
+		// `HEAD` is not moved
+		assert_eq!(get_head(repo_path).unwrap(), second);
+
+		fs::remove_file(td.path().join("reject")).unwrap();
+		fs::remove_file(&log).unwrap();
+
+		reset_repo(repo_path, first, ResetType::Hard).unwrap();
+		assert_eq!(get_head(repo_path).unwrap(), first);
+		assert_eq!(
+			fs::read_to_string(&log).unwrap(),
+			format!(
+				"prepared\n{second} {first} refs/heads/master\ncommitted\n{second} {first} refs/heads/master\n"
+			)
+		);
+	}
 }
@@ -0,0 +1,389 @@
+//! squashing a range of commits into one without an interactive rebase
+
+use super::{
+	commit::signature_allow_undefined_name, hooks::hooks_commit_msg,
+	repo, CommitId, HookRejection, HookResult, RepoPath,
+};
+use crate::error::{Error, Result};
+use git2::{Commit, Oid, Repository, RepositoryState};
+use git2_hooks::HookType;
+use scopetime::scope_time;
+use std::fmt::Write;
+
+/// commits `base..=tip` (oldest first) and the ones on top of `tip` up
+/// to `HEAD` (oldest first), following first parents only. fails if
+/// any of them is a merge.
+pub fn first_parent_range(
+	repo: &Repository,
+	base: CommitId,
+	tip: CommitId,
+) -> Result<(Vec<Commit<'_>>, Vec<Commit<'_>>)> {
+	let mut range = Vec::new();
+	let mut above = Vec::new();
+	let mut in_range = false;
+
+	let mut next = Some(repo.head()?.peel_to_commit()?);
+	while let Some(commit) = next {
+		if commit.parent_count() > 1 {
+			return Err(Error::Generic(format!(
+				"cannot squash across merge commit {}",
+				CommitId::from(commit.id()).get_short_string()
+			)));
+		}
+
+		in_range |= commit.id() == tip.get_oid();
+		let is_base = commit.id() == base.get_oid();
+		next = if is_base { None } else { commit.parent(0).ok() };
+
+		if in_range {
+			range.push(commit);
+		} else {
+			above.push(commit);
+		}
+
+		if is_base {
+			break;
+		}
+	}
+
+	if !in_range
+		|| range.last().map(Commit::id) != Some(base.get_oid())
+	{
+		return Err(Error::Generic(String::from(
+			"commits to squash are not a range of the current branch",
+		)));
+	}
+
+	range.reverse();
+	above.reverse();
+
+	Ok((range, above))
+}
+
+/// whether `commit` is already on the upstream of the checked out
+/// branch (`false` if it has none)
+pub fn is_on_upstream(
+	repo: &Repository,
+	commit: Oid,
+) -> Result<bool> {
+	let Ok(upstream) = git2::Branch::wrap(repo.head()?).upstream()
+	else {
+		return Ok(false);
+	};
+	let upstream = upstream.get().peel_to_commit()?.id();
+
+	Ok(upstream == commit
+		|| repo.graph_descendant_of(upstream, commit)?)
+}
+
+/// fails if any of `commits` is already on the upstream of the checked
+/// out branch
+fn ensure_unpushed(
+	repo: &Repository,
+	commits: &[Commit],
+) -> Result<()> {
+	for commit in commits {
+		if is_on_upstream(repo, commit.id())? {
+			return Err(Error::Generic(format!(
+				"cannot squash commit {} which is already on the upstream",
+				CommitId::from(commit.id()).get_short_string()
+			)));
+		}
+	}
+
+	Ok(())
+}
+
+/// the messages of `base..=tip` in the format `git rebase` offers
+/// them when squashing, the comment lines are removed when committing.
+/// fails for ranges [`squash_range`] refuses.
+pub fn squash_message(
+	repo_path: &RepoPath,
+	base: CommitId,
+	tip: CommitId,
+) -> Result<String> {
+	let repo = repo(repo_path)?;
+	let (range, _) = first_parent_range(&repo, base, tip)?;
+	ensure_unpushed(&repo, &range)?;
+
+	let mut msg = format!(
+		"# This is a combination of {} commits.",
+		range.len()
+	);
+	for (index, commit) in range.iter().enumerate() {
+		if index == 0 {
+			msg.push_str("\n# This is the 1st commit message:\n\n");
+		} else {
+			let _ = write!(
+				msg,
+				"\n\n# This is the commit message #{}:\n\n",
+				index + 1
+			);
+		}
+		msg.push_str(
+			String::from_utf8_lossy(commit.message_bytes())
+				.trim_end(),
+		);
+	}
+	msg.push('\n');
+
+	Ok(msg)
+}
+
+/// replaces the commits `base..=tip` of the checked out branch by a
+/// single commit with `message` and the tree of `tip`.
+///
+/// the commits on top of `tip` are replayed with their trees unchanged,
+/// so neither the work dir nor the index are touched. refuses ranges
+/// containing (or followed by) merges and commits already on the
+/// upstream. the `commit-msg` hook runs on `message` first and rejects
+/// the squash like it rejects a commit.
+pub fn squash_range(
+	repo_path: &RepoPath,
+	base: CommitId,
+	tip: CommitId,
+	message: &str,
+) -> Result<CommitId> {
+	scope_time!("squash_range");
+
+	let repo = repo(repo_path)?;
+
+	if repo.state() != RepositoryState::Clean {
+		return Err(Error::Generic(String::from(
+			"cannot squash while repo is not in a clean state",
+		)));
+	}
+	if !repo.head()?.is_branch() {
+		return Err(Error::NoBranch);
+	}
+	if repo.config()?.get_bool("commit.gpgsign").unwrap_or(false) {
+		return Err(Error::SignSquash);
+	}
+
+	let (range, above) = first_parent_range(&repo, base, tip)?;
+	if range.len() < 2 {
+		return Err(Error::Generic(String::from(
+			"at least two commits are needed to squash",
+		)));
+	}
+	ensure_unpushed(&repo, &range)?;
+
+	let mut message = message.to_string();
+	match hooks_commit_msg(repo_path, &mut message)? {
+		HookResult::Ok => {}
+		result => {
+			return Err(HookRejection::Rejected {
+				hook: HookType::CommitMsg,
+				result,
+			}
+			.into())
+		}
+	}
+
+	let committer = signature_allow_undefined_name(&repo)?;
+	let parents = range[0].parents().collect::<Vec<_>>();
+	let tip_commit = &range[range.len() - 1];
+
+	let squashed = repo.commit(
+		None,
+		&range[0].author(),
+		&committer,
+		&message,
+		&tip_commit.tree()?,
+		parents.iter().collect::<Vec<_>>().as_slice(),
+	)?;
+
+	let mut head = squashed;
+	for commit in &above {
+		head = repo.commit(
+			None,
+			&commit.author(),
+			&commit.committer(),
+			&String::from_utf8_lossy(commit.message_raw_bytes()),
+			&commit.tree()?,
+			&[&repo.find_commit(head)?],
+		)?;
+	}
+
+	move_head_branch(&repo, head, range.len())?;
+
+	Ok(squashed.into())
+}
+
+fn move_head_branch(
+	repo: &Repository,
+	target: Oid,
+	squashed: usize,
+) -> Result<()> {
+	let head = repo.head()?;
+	let name = head.name().ok_or(Error::NoBranch)?;
+
+	repo.find_reference(name)?.set_target(
+		target,
+		&format!("squash: combined {squashed} commits"),
+	)?;
+
+	Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::sync::{
+		get_commit_info,
+		remotes::push::push_branch,
+		tests::{repo_init_bare, repo_init_empty, write_commit_file},
+		utils::get_head,
+		LogWalker,
+	};
+	use pretty_assertions::assert_eq;
+
+	fn repo_path(repo: &Repository) -> RepoPath {
+		repo.workdir().unwrap().to_str().unwrap().into()
+	}
+
+	fn tree_of(repo: &Repository, id: CommitId) -> Oid {
+		repo.find_commit(id.into()).unwrap().tree_id()
+	}
+
+	fn log(repo: &Repository) -> Vec<CommitId> {
+		let mut items = Vec::new();
+		LogWalker::new(repo, 10).unwrap().read(&mut items).unwrap();
+		items
+	}
+
+	#[test]
+	fn test_squash_range() {
+		let (_td, repo) = repo_init_empty().unwrap();
+		let repo_path = repo_path(&repo);
+
+		let c1 = write_commit_file(&repo, "a.txt", "1", "c1");
+		let c2 = write_commit_file(&repo, "a.txt", "12", "c2");
+		let c3 = write_commit_file(&repo, "b.txt", "3", "c3");
+		let c4 = write_commit_file(&repo, "a.txt", "124", "c4");
+
+		let squashed =
+			squash_range(&repo_path, c2, c3, "c2 and c3").unwrap();
+
+		// the squashed commit has exactly the tree of the old tip
+		assert_eq!(tree_of(&repo, squashed), tree_of(&repo, c3));
+		assert_eq!(
+			get_commit_info(&repo_path, &squashed).unwrap().message,
+			"c2 and c3"
+		);
+
+		let head = get_head(&repo_path).unwrap();
+		assert_eq!(tree_of(&repo, head), tree_of(&repo, c4));
+		assert_eq!(log(&repo), vec![head, squashed, c1]);
+		assert_eq!(
+			get_commit_info(&repo_path, &head).unwrap().message,
+			"c4"
+		);
+	}
+
+	#[test]
+	fn test_squash_range_up_to_head() {
+		let (_td, repo) = repo_init_empty().unwrap();
+		let repo_path = repo_path(&repo);
+
+		let c1 = write_commit_file(&repo, "a.txt", "1", "c1");
+		let c2 = write_commit_file(&repo, "a.txt", "12", "c2");
+
+		let squashed =
+			squash_range(&repo_path, c1, c2, "all").unwrap();
+
+		// squashing the root commit gives a new root commit
+		assert_eq!(log(&repo), vec![squashed]);
+		assert_eq!(tree_of(&repo, squashed), tree_of(&repo, c2));
+		assert_eq!(get_head(&repo_path).unwrap(), squashed);
+	}
+
+	#[test]
+	fn test_squash_message() {
+		let (_td, repo) = repo_init_empty().unwrap();
+		let repo_path = repo_path(&repo);
+
+		write_commit_file(&repo, "a.txt", "1", "c1");
+		let c2 = write_commit_file(&repo, "a.txt", "12", "c2");
+		let c3 =
+			write_commit_file(&repo, "a.txt", "123", "c3\n\nbody");
+
+		assert_eq!(
+			squash_message(&repo_path, c2, c3).unwrap(),
+			"# This is a combination of 2 commits.\n# This is the 1st commit message:\n\nc2\n\n# This is the commit message #2:\n\nc3\n\nbody\n"
+		);
+	}
+
+	#[test]
+	fn test_squash_range_refuses_non_range() {
+		let (_td, repo) = repo_init_empty().unwrap();
+		let repo_path = repo_path(&repo);
+
+		let c1 = write_commit_file(&repo, "a.txt", "1", "c1");
+		let c2 = write_commit_file(&repo, "a.txt", "12", "c2");
+
+		// base and tip swapped
+		assert!(squash_range(&repo_path, c2, c1, "msg").is_err());
+		// a single commit
+		assert!(squash_range(&repo_path, c2, c2, "msg").is_err());
+		assert_eq!(get_head(&repo_path).unwrap(), c2);
+	}
+
+	#[test]
+	fn test_squash_range_refuses_pushed() {
+		let (_td, repo) = repo_init_empty().unwrap();
+		let (upstream_dir, _) = repo_init_bare().unwrap();
+		let repo_path = repo_path(&repo);
+
+		repo.remote("origin", upstream_dir.path().to_str().unwrap())
+			.unwrap();
+
+		let c1 = write_commit_file(&repo, "a.txt", "1", "c1");
+		let c2 = write_commit_file(&repo, "a.txt", "12", "c2");
+		let c3 = write_commit_file(&repo, "a.txt", "123", "c3");
+
+		push_branch(
+			&repo_path, "origin", "master", false, false, None, None,
+		)
+		.unwrap();
+		let c4 = write_commit_file(&repo, "a.txt", "1234", "c4");
+
+		assert!(squash_message(&repo_path, c2, c4).is_err());
+		assert!(squash_range(&repo_path, c2, c4, "msg").is_err());
+		assert_eq!(get_head(&repo_path).unwrap(), c4);
+
+		// commits not on the upstream can still be squashed
+		let c5 = write_commit_file(&repo, "a.txt", "12345", "c5");
+		let squashed =
+			squash_range(&repo_path, c4, c5, "msg").unwrap();
+		assert_eq!(log(&repo), vec![squashed, c3, c2, c1]);
+	}
+
+	#[test]
+	fn test_squash_range_commit_msg_hook() {
+		let (_td, repo) = repo_init_empty().unwrap();
+		let repo_path = repo_path(&repo);
+
+		let c1 = write_commit_file(&repo, "a.txt", "1", "c1");
+		let c2 = write_commit_file(&repo, "a.txt", "12", "c2");
+
+		git2_hooks::create_hook(
+			&repo,
+			git2_hooks::HOOK_COMMIT_MSG,
+			b"#!/bin/sh\ngrep -q reject \"$1\" && exit 1\nprintf hooked > \"$1\"\n",
+		);
+
+		assert!(matches!(
+			squash_range(&repo_path, c1, c2, "reject"),
+			Err(Error::HookRejected(_))
+		));
+		assert_eq!(get_head(&repo_path).unwrap(), c2);
+
+		let squashed =
+			squash_range(&repo_path, c1, c2, "msg").unwrap();
+		assert_eq!(
+			get_commit_info(&repo_path, &squashed).unwrap().message,
+			"hooked"
+		);
+	}
+}
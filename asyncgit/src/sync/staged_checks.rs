@@ -0,0 +1,223 @@
+//! checks of the staged changes run before committing them, what
+//! they find is shown to be fixed first (or committed anyway).
+//!
+//! besides the conflict markers (see
+//! [`super::staged_conflict_markers`]) staged files bigger than
+//! `gitui.largeFiles.threshold` (10 MiB by default, `0` disables it)
+//! are looked for. only the sizes recorded in the index are compared,
+//! no content is read.
+
+use super::{
+	conflict_marker_check, conflict_markers::find_markers,
+	repository::repo, ConflictMarkerCheck, ConflictMarkers, RepoPath,
+};
+use crate::error::Result;
+use git2::{Delta, DiffOptions, Repository};
+use scopetime::scope_time;
+
+const CONFIG_LARGE_FILES_THRESHOLD: &str =
+	"gitui.largeFiles.threshold";
+
+const DEFAULT_LARGE_FILES_THRESHOLD: u64 = 10 * 1024 * 1024;
+
+/// a staged file bigger than `gitui.largeFiles.threshold`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LargeFile {
+	///
+	pub path: String,
+	/// in bytes
+	pub size: u64,
+}
+
+/// what [`staged_checks`] found
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct StagedChecks {
+	/// see [`super::staged_conflict_markers`], empty if turned off
+	pub conflict_markers: Vec<ConflictMarkers>,
+	/// `gitui.conflictMarkers.check`
+	pub conflict_check: ConflictMarkerCheck,
+	///
+	pub large_files: Vec<LargeFile>,
+	/// the repo tracks files with git LFS already, which large files
+	/// are better off with
+	pub uses_lfs: bool,
+}
+
+impl StagedChecks {
+	/// nothing worth stopping the commit for
+	pub fn is_empty(&self) -> bool {
+		self.conflict_markers.is_empty()
+			&& self.large_files.is_empty()
+	}
+
+	/// the commit must not be made until these are fixed
+	pub fn blocks(&self) -> bool {
+		self.conflict_check == ConflictMarkerCheck::Block
+			&& !self.conflict_markers.is_empty()
+	}
+}
+
+/// runs every check on the staged changes of the repo
+pub fn staged_checks(repo_path: &RepoPath) -> Result<StagedChecks> {
+	scope_time!("staged_checks");
+
+	let repo = repo(repo_path)?;
+	let conflict_check = conflict_marker_check(repo_path)?;
+
+	Ok(StagedChecks {
+		conflict_markers: if conflict_check
+			== ConflictMarkerCheck::Off
+		{
+			Vec::new()
+		} else {
+			find_markers(&repo)?
+		},
+		conflict_check,
+		large_files: find_large_files(&repo)?,
+		uses_lfs: uses_lfs(&repo),
+	})
+}
+
+/// staged files (added or changed compared to `HEAD`) bigger than
+/// `gitui.largeFiles.threshold`
+pub fn staged_large_files(
+	repo_path: &RepoPath,
+) -> Result<Vec<LargeFile>> {
+	scope_time!("staged_large_files");
+
+	find_large_files(&repo(repo_path)?)
+}
+
+fn find_large_files(repo: &Repository) -> Result<Vec<LargeFile>> {
+	let threshold = repo
+		.config()?
+		.get_i64(CONFIG_LARGE_FILES_THRESHOLD)
+		.map_or(DEFAULT_LARGE_FILES_THRESHOLD, |threshold| {
+			u64::try_from(threshold).unwrap_or_default()
+		});
+
+	if threshold == 0 {
+		return Ok(Vec::new());
+	}
+
+	let index = repo.index()?;
+	let head =
+		repo.head().ok().and_then(|head| head.peel_to_tree().ok());
+	let diff = repo.diff_tree_to_index(
+		head.as_ref(),
+		Some(&index),
+		Some(DiffOptions::new().ignore_submodules(true)),
+	)?;
+
+	Ok(diff
+		.deltas()
+		.filter(|delta| {
+			!matches!(delta.status(), Delta::Deleted | Delta::Ignored)
+		})
+		.filter_map(|delta| {
+			let path = delta.new_file().path()?;
+			let entry = index.get_path(path, 0)?;
+			let size = u64::from(entry.file_size);
+
+			(size > threshold).then(|| LargeFile {
+				path: path.to_string_lossy().to_string(),
+				size,
+			})
+		})
+		.collect())
+}
+
+/// the `.gitattributes` of the work tree route files through the LFS
+/// filter
+fn uses_lfs(repo: &Repository) -> bool {
+	repo.workdir()
+		.and_then(|workdir| {
+			std::fs::read_to_string(workdir.join(".gitattributes"))
+				.ok()
+		})
+		.is_some_and(|attributes| attributes.contains("filter=lfs"))
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::sync::{
+		stage_add_file, staged_conflict_markers, tests::repo_init,
+	};
+	use pretty_assertions::assert_eq;
+	use std::{fs, path::Path};
+
+	#[test]
+	fn test_staged_large_files() {
+		let (_td, repo) = repo_init().unwrap();
+		let root = repo.workdir().unwrap();
+		let repo_path: &RepoPath = &root.to_str().unwrap().into();
+
+		repo.config()
+			.unwrap()
+			.set_i64(CONFIG_LARGE_FILES_THRESHOLD, 10)
+			.unwrap();
+
+		for (file, content) in [
+			("small.txt", "0123456789"),
+			("large.bin", "0123456789a"),
+		] {
+			fs::write(root.join(file), content).unwrap();
+			stage_add_file(repo_path, Path::new(file)).unwrap();
+		}
+		// not staged
+		fs::write(root.join("other.bin"), "0123456789a").unwrap();
+
+		assert_eq!(
+			staged_large_files(repo_path).unwrap(),
+			vec![LargeFile {
+				path: String::from("large.bin"),
+				size: 11,
+			}]
+		);
+
+		repo.config()
+			.unwrap()
+			.set_i64(CONFIG_LARGE_FILES_THRESHOLD, 0)
+			.unwrap();
+
+		assert!(staged_large_files(repo_path).unwrap().is_empty());
+	}
+
+	#[test]
+	fn test_staged_checks() {
+		let (_td, repo) = repo_init().unwrap();
+		let root = repo.workdir().unwrap();
+		let repo_path: &RepoPath = &root.to_str().unwrap().into();
+
+		assert_eq!(
+			staged_checks(repo_path).unwrap(),
+			StagedChecks::default()
+		);
+
+		fs::write(root.join("a.txt"), "<<<<<<< HEAD\n").unwrap();
+		stage_add_file(repo_path, Path::new("a.txt")).unwrap();
+		fs::write(root.join(".gitattributes"), "*.bin filter=lfs\n")
+			.unwrap();
+
+		let checks = staged_checks(repo_path).unwrap();
+		assert!(!checks.is_empty());
+		assert!(!checks.blocks());
+		assert!(checks.uses_lfs);
+		assert_eq!(
+			checks.conflict_markers,
+			staged_conflict_markers(repo_path).unwrap()
+		);
+
+		let mut config = repo.config().unwrap();
+		config
+			.set_str("gitui.conflictMarkers.check", "block")
+			.unwrap();
+		assert!(staged_checks(repo_path).unwrap().blocks());
+
+		config
+			.set_str("gitui.conflictMarkers.check", "off")
+			.unwrap();
+		assert!(staged_checks(repo_path).unwrap().is_empty());
+	}
+}
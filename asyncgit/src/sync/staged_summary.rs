@@ -0,0 +1,477 @@
+//! what committing the staged changes would add, shown before a commit
+//! is made.
+//!
+//! besides the size of the change the staged files are checked for
+//! things that are most likely not meant to be committed, see
+//! [`StagedWarning`] and [`StagedSummaryConfig`] for the git config
+//! tuning these checks.
+
+use super::{
+	branch::get_branch_name_repo, repository::repo, RepoPath,
+};
+use crate::error::Result;
+use git2::{
+	Config, Delta, DiffOptions, Odb, Oid, Pathspec, PathspecFlags,
+	Repository,
+};
+use scopetime::scope_time;
+use std::{
+	io::{BufRead, BufReader},
+	path::Path,
+};
+
+const CONFIG_ENABLED: &str = "gitui.commitSummary.enabled";
+const CONFIG_MAX_FILE_SIZE: &str = "gitui.commitSummary.maxFileSize";
+const CONFIG_SECRET_PATTERN: &str =
+	"gitui.commitSummary.secretPattern";
+const CONFIG_CONFLICT_EXCLUDE: &str =
+	"gitui.commitSummary.conflictExclude";
+
+const DEFAULT_MAX_FILE_SIZE: u64 = 1024 * 1024;
+const DEFAULT_SECRET_PATTERNS: &[&str] =
+	&["*.secret", "*.pem", "*.key", ".env"];
+/// lockfiles can contain lines looking like conflict markers
+pub const DEFAULT_CONFLICT_EXCLUDE: &[&str] =
+	&["*.lock", "*-lock.json", "*-lock.yaml"];
+
+/// bytes searched for conflict markers in all staged files together,
+/// files beyond that are not searched
+const MAX_SCANNED_BYTES: u64 = 32 * 1024 * 1024;
+
+/// the `[gitui "commitSummary"]` git config
+///
+/// ```gitconfig
+/// [gitui "commitSummary"]
+///     enabled = true
+///     maxFileSize = 1m
+///     secretPattern = *.secret
+///     secretPattern = *.pem
+///     conflictExclude = *.lock
+/// ```
+///
+/// both pattern lists replace their defaults once set, the patterns
+/// are matched like git pathspecs
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StagedSummaryConfig {
+	/// show the summary before committing, `gitui.commitSummary.enabled`
+	pub enabled: bool,
+	/// staged files bigger than this (in bytes) are warned about
+	pub max_file_size: u64,
+	/// staged files matching any of these are warned about
+	pub secret_patterns: Vec<String>,
+	/// files matching any of these are not searched for conflict
+	/// markers
+	pub conflict_exclude: Vec<String>,
+}
+
+impl Default for StagedSummaryConfig {
+	fn default() -> Self {
+		Self {
+			enabled: true,
+			max_file_size: DEFAULT_MAX_FILE_SIZE,
+			secret_patterns: strings(DEFAULT_SECRET_PATTERNS),
+			conflict_exclude: strings(DEFAULT_CONFLICT_EXCLUDE),
+		}
+	}
+}
+
+impl StagedSummaryConfig {
+	fn from_config(config: &Config) -> Result<Self> {
+		let default = Self::default();
+
+		Ok(Self {
+			enabled: config
+				.get_bool(CONFIG_ENABLED)
+				.unwrap_or(default.enabled),
+			max_file_size: config
+				.get_i64(CONFIG_MAX_FILE_SIZE)
+				.ok()
+				.and_then(|size| u64::try_from(size).ok())
+				.unwrap_or(default.max_file_size),
+			secret_patterns: multivar(config, CONFIG_SECRET_PATTERN)?
+				.unwrap_or(default.secret_patterns),
+			conflict_exclude: multivar(
+				config,
+				CONFIG_CONFLICT_EXCLUDE,
+			)?
+			.unwrap_or(default.conflict_exclude),
+		})
+	}
+}
+
+/// something about a staged file that is worth a second look
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum StagedWarning {
+	/// bigger than [`StagedSummaryConfig::max_file_size`]
+	LargeFile {
+		///
+		path: String,
+		/// in bytes
+		size: u64,
+	},
+	/// matches one of [`StagedSummaryConfig::secret_patterns`]
+	Secret {
+		///
+		path: String,
+	},
+	/// a conflict marker was left in the staged content
+	ConflictMarker {
+		///
+		path: String,
+		/// 1-based line of the first marker
+		line: usize,
+	},
+}
+
+/// see [`staged_summary`]
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct StagedSummary {
+	/// branch the commit goes to, `None` if `HEAD` is detached or unborn
+	pub branch: Option<String>,
+	/// number of staged files
+	pub files: usize,
+	///
+	pub insertions: usize,
+	///
+	pub deletions: usize,
+	///
+	pub warnings: Vec<StagedWarning>,
+	/// not all staged files were searched for conflict markers, see
+	/// [`MAX_SCANNED_BYTES`]
+	pub truncated: bool,
+}
+
+/// reads the [`StagedSummaryConfig`] of the repo
+pub fn staged_summary_config(
+	repo_path: &RepoPath,
+) -> Result<StagedSummaryConfig> {
+	StagedSummaryConfig::from_config(&repo(repo_path)?.config()?)
+}
+
+/// summarizes the staged changes (compared to `HEAD`) and checks them
+/// according to the [`StagedSummaryConfig`] of the repo
+pub fn staged_summary(repo_path: &RepoPath) -> Result<StagedSummary> {
+	scope_time!("staged_summary");
+
+	let repo = repo(repo_path)?;
+	let config = StagedSummaryConfig::from_config(&repo.config()?)?;
+
+	summarize(&repo, &config)
+}
+
+fn summarize(
+	repo: &Repository,
+	config: &StagedSummaryConfig,
+) -> Result<StagedSummary> {
+	let head =
+		repo.head().ok().and_then(|head| head.peel_to_tree().ok());
+	let diff = repo.diff_tree_to_index(
+		head.as_ref(),
+		None,
+		Some(DiffOptions::new().ignore_submodules(true)),
+	)?;
+	let stats = diff.stats()?;
+
+	let secret = pathspec(&config.secret_patterns)?;
+	let conflict_exclude = pathspec(&config.conflict_exclude)?;
+	let odb = repo.odb()?;
+
+	let mut summary = StagedSummary {
+		branch: get_branch_name_repo(repo)
+			.ok()
+			.filter(|_| !repo.head_detached().unwrap_or_default()),
+		files: stats.files_changed(),
+		insertions: stats.insertions(),
+		deletions: stats.deletions(),
+		..StagedSummary::default()
+	};
+	let mut budget = MAX_SCANNED_BYTES;
+
+	for delta in diff.deltas() {
+		if delta.status() == Delta::Deleted {
+			continue;
+		}
+
+		let file = delta.new_file();
+		let Some(path) = file.path() else {
+			continue;
+		};
+		let name = path.to_string_lossy().to_string();
+
+		if matches(secret.as_ref(), path) {
+			summary
+				.warnings
+				.push(StagedWarning::Secret { path: name.clone() });
+		}
+
+		let (size, _) = odb.read_header(file.id())?;
+		let size = size as u64;
+		if size > config.max_file_size {
+			summary
+				.warnings
+				.push(StagedWarning::LargeFile { path: name, size });
+			continue;
+		}
+
+		if matches(conflict_exclude.as_ref(), path) {
+			continue;
+		}
+
+		if size > budget {
+			summary.truncated = true;
+			continue;
+		}
+		budget -= size;
+
+		if let Some(line) = blob_conflict_marker(&odb, file.id())? {
+			summary.warnings.push(StagedWarning::ConflictMarker {
+				path: name,
+				line,
+			});
+		}
+	}
+
+	Ok(summary)
+}
+
+/// `<<<<<<<`, `|||||||`, `=======` or `>>>>>>>` on a line of its own or
+/// followed by a space, like git writes them
+pub fn is_conflict_marker(line: &[u8]) -> bool {
+	let line = line.strip_suffix(b"\n").unwrap_or(line);
+	let line = line.strip_suffix(b"\r").unwrap_or(line);
+
+	let Some(&first) = line.first() else {
+		return false;
+	};
+	if !matches!(first, b'<' | b'|' | b'=' | b'>')
+		|| line.len() < 7
+		|| line[..7].iter().any(|c| *c != first)
+	{
+		return false;
+	}
+
+	match &line[7..] {
+		[] => true,
+		// `=======` has nothing after it
+		[b' ', ..] => first != b'=',
+		_ => false,
+	}
+}
+
+/// 1-based line of the first conflict marker in the blob `id`, which
+/// is streamed if the object database allows it
+fn blob_conflict_marker(odb: &Odb, id: Oid) -> Result<Option<usize>> {
+	// packed objects cannot be streamed
+	Ok(match odb.reader(id) {
+		Ok((reader, _, _)) => {
+			first_conflict_marker(BufReader::new(reader))?
+		}
+		Err(_) => first_conflict_marker(odb.read(id)?.data())?,
+	})
+}
+
+/// `None` as well for binary content (containing a NUL byte)
+fn first_conflict_marker(
+	mut content: impl BufRead,
+) -> std::io::Result<Option<usize>> {
+	let mut line = Vec::new();
+	let mut number = 0;
+
+	loop {
+		line.clear();
+		if content.read_until(b'\n', &mut line)? == 0 {
+			return Ok(None);
+		}
+		number += 1;
+
+		if line.contains(&0) {
+			return Ok(None);
+		}
+		if is_conflict_marker(&line) {
+			return Ok(Some(number));
+		}
+	}
+}
+
+pub fn pathspec(patterns: &[String]) -> Result<Option<Pathspec>> {
+	if patterns.is_empty() {
+		return Ok(None);
+	}
+
+	Ok(Some(Pathspec::new(patterns.iter())?))
+}
+
+pub fn matches(pathspec: Option<&Pathspec>, path: &Path) -> bool {
+	pathspec.is_some_and(|pathspec| {
+		pathspec.matches_path(path, PathspecFlags::DEFAULT)
+	})
+}
+
+/// `None` if `key` is not set at all
+pub fn multivar(
+	config: &Config,
+	key: &str,
+) -> Result<Option<Vec<String>>> {
+	let mut values = Vec::new();
+	let mut entries = config.multivar(key, None)?;
+	let mut found = false;
+
+	while let Some(entry) = entries.next() {
+		found = true;
+		if let Some(value) = entry?.value() {
+			if !value.is_empty() {
+				values.push(value.to_string());
+			}
+		}
+	}
+
+	Ok(found.then_some(values))
+}
+
+fn strings(values: &[&str]) -> Vec<String> {
+	values.iter().map(ToString::to_string).collect()
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::sync::{
+		stage_add_file,
+		tests::{repo_init, write_commit_file},
+	};
+	use pretty_assertions::assert_eq;
+	use std::{fs, path::Path};
+
+	fn stage(
+		repo_path: &RepoPath,
+		root: &Path,
+		file: &str,
+		content: &str,
+	) {
+		let path = root.join(file);
+		fs::create_dir_all(path.parent().unwrap()).unwrap();
+		fs::write(&path, content).unwrap();
+		stage_add_file(repo_path, Path::new(file)).unwrap();
+	}
+
+	#[test]
+	fn test_is_conflict_marker() {
+		assert!(is_conflict_marker(b"<<<<<<< HEAD\n"));
+		assert!(is_conflict_marker(b"=======\r\n"));
+		assert!(is_conflict_marker(b">>>>>>> feature"));
+		assert!(is_conflict_marker(b"||||||| base\n"));
+		assert!(is_conflict_marker(b"<<<<<<<\n"));
+
+		assert!(!is_conflict_marker(b"======= x\n"));
+		assert!(!is_conflict_marker(b"========\n"));
+		assert!(!is_conflict_marker(b" <<<<<<< HEAD\n"));
+		assert!(!is_conflict_marker(b"<<<<<<<<\n"));
+		assert!(!is_conflict_marker(b"<<<\n"));
+		assert!(!is_conflict_marker(b""));
+	}
+
+	#[test]
+	fn test_first_conflict_marker() {
+		assert_eq!(
+			first_conflict_marker(&b"a\nb\n<<<<<<< HEAD\nc\n"[..])
+				.unwrap(),
+			Some(3)
+		);
+		assert_eq!(
+			first_conflict_marker(&b"a\nb\n"[..]).unwrap(),
+			None
+		);
+		assert_eq!(
+			first_conflict_marker(&b"\0\n<<<<<<< HEAD\n"[..])
+				.unwrap(),
+			None
+		);
+	}
+
+	#[test]
+	fn test_staged_summary() {
+		let (_td, repo) = repo_init().unwrap();
+		let root = repo.workdir().unwrap();
+		let repo_path: &RepoPath = &root.to_str().unwrap().into();
+
+		write_commit_file(&repo, "a.txt", "1\n2\n3\n", "c1");
+
+		let mut config = repo.config().unwrap();
+		config.set_i64(CONFIG_MAX_FILE_SIZE, 100).unwrap();
+
+		stage(repo_path, root, "a.txt", "1\n3\n4\n5\n");
+		stage(repo_path, root, "big.bin", &"x".repeat(200));
+		stage(repo_path, root, "conf/db.secret", "pw\n");
+		stage(
+			repo_path,
+			root,
+			"b.txt",
+			"ok\n<<<<<<< HEAD\nx\n=======\ny\n>>>>>>> f\n",
+		);
+		stage(repo_path, root, "Cargo.lock", "=======\n");
+		// not staged
+		fs::write(root.join("c.txt"), "<<<<<<< HEAD\n").unwrap();
+
+		let summary = staged_summary(repo_path).unwrap();
+
+		assert_eq!(summary.branch.as_deref(), Some("master"));
+		assert_eq!(summary.files, 5);
+		assert_eq!(summary.insertions, 11);
+		assert_eq!(summary.deletions, 1);
+		assert!(!summary.truncated);
+		assert_eq!(
+			summary.warnings,
+			vec![
+				StagedWarning::ConflictMarker {
+					path: String::from("b.txt"),
+					line: 2,
+				},
+				StagedWarning::LargeFile {
+					path: String::from("big.bin"),
+					size: 200,
+				},
+				StagedWarning::Secret {
+					path: String::from("conf/db.secret"),
+				},
+			]
+		);
+
+		config.set_str(CONFIG_CONFLICT_EXCLUDE, "b.*").unwrap();
+		config.set_str(CONFIG_SECRET_PATTERN, "").unwrap();
+		config.set_bool(CONFIG_ENABLED, false).unwrap();
+
+		assert!(!staged_summary_config(repo_path).unwrap().enabled);
+		assert_eq!(
+			staged_summary(repo_path).unwrap().warnings,
+			vec![
+				StagedWarning::ConflictMarker {
+					path: String::from("Cargo.lock"),
+					line: 1,
+				},
+				StagedWarning::LargeFile {
+					path: String::from("big.bin"),
+					size: 200,
+				},
+			]
+		);
+	}
+
+	#[test]
+	fn test_staged_summary_detached() {
+		let (_td, repo) = repo_init().unwrap();
+		let root = repo.workdir().unwrap();
+		let repo_path: &RepoPath = &root.to_str().unwrap().into();
+
+		repo.set_head_detached(
+			repo.head().unwrap().target().unwrap(),
+		)
+		.unwrap();
+		stage(repo_path, root, "a.txt", "a\n");
+
+		let summary = staged_summary(repo_path).unwrap();
+
+		assert_eq!(summary.branch, None);
+		assert_eq!(summary.files, 1);
+		assert_eq!(summary.warnings, Vec::new());
+	}
+}
@@ -1,4 +1,4 @@
-use super::{apply_selection, load_file};
+use super::{apply_selection, load_file, split_lines};
 use crate::{
 	error::Result,
 	sync::{
@@ -32,7 +32,7 @@ pub fn discard_lines(
 		let hunks = patch_get_hunklines(&patch)?;
 
 		let working_content = load_file(&repo, file_path)?;
-		let old_lines = working_content.lines().collect::<Vec<_>>();
+		let old_lines = split_lines(&working_content);
 
 		apply_selection(lines, &hunks, &old_lines, false, true)?
 	};
@@ -13,6 +13,8 @@ use std::{collections::HashSet, fs::File, io::Read};
 
 const NEWLINE: char = '\n';
 
+/// lines keep their line ending, so that a missing newline at the end
+/// of the file survives (see [`split_lines`])
 #[derive(Default)]
 struct NewFromOldContent {
 	lines: Vec<String>,
@@ -20,16 +22,22 @@ struct NewFromOldContent {
 }
 
 impl NewFromOldContent {
+	fn push(&mut self, line: String) {
+		// only the last line of a file may lack a newline,
+		// if anything follows it, it is not the last one anymore
+		if let Some(last) = self.lines.last_mut() {
+			if !last.ends_with(NEWLINE) {
+				last.push(NEWLINE);
+			}
+		}
+
+		self.lines.push(line);
+	}
+
 	fn add_from_hunk(&mut self, line: &DiffLine) -> Result<()> {
 		let line = String::from_utf8(line.content().into())?;
 
-		let line = if line.ends_with(NEWLINE) {
-			line[0..line.len() - 1].to_string()
-		} else {
-			line
-		};
-
-		self.lines.push(line);
+		self.push(line);
 
 		Ok(())
 	}
@@ -39,7 +47,7 @@ impl NewFromOldContent {
 	}
 
 	fn add_old_line(&mut self, old_lines: &[&str]) {
-		self.lines.push(old_lines[self.old_index].to_string());
+		self.push(old_lines[self.old_index].to_string());
 		self.old_index += 1;
 	}
 
@@ -55,21 +63,22 @@ impl NewFromOldContent {
 
 	fn finish(mut self, old_lines: &[&str]) -> String {
 		for line in old_lines.iter().skip(self.old_index) {
-			self.lines.push((*line).to_string());
-		}
-		let lines = self.lines.join("\n");
-		if lines.ends_with(NEWLINE) {
-			lines
-		} else {
-			let mut lines = lines;
-			lines.push(NEWLINE);
-			lines
+			self.push((*line).to_string());
 		}
+
+		self.lines.concat()
 	}
 }
 
+/// splits `content` into lines keeping their line endings
+pub fn split_lines(content: &str) -> Vec<&str> {
+	content.split_inclusive(NEWLINE).collect()
+}
+
 // this is the heart of the per line discard,stage,unstage. heavily inspired by the great work in
 // nodegit: https://github.com/nodegit/nodegit
+//
+// `old_lines` are expected to keep their line endings (see `split_lines`)
 pub fn apply_selection(
 	lines: &[DiffLinePosition],
 	hunks: &[HunkLines],
@@ -128,12 +137,16 @@ pub fn apply_selection(
 						.trim()
 				);
 
-				if hunk_line.origin_value()
-					== DiffLineType::DeleteEOFNL
-					|| hunk_line.origin_value()
-						== DiffLineType::AddEOFNL
-				{
-					break;
+				// the `\ No newline at end of file` markers can be
+				// followed by more lines of the other side, the
+				// missing newline itself is part of the line content
+				if matches!(
+					hunk_line.origin_value(),
+					DiffLineType::DeleteEOFNL
+						| DiffLineType::AddEOFNL
+						| DiffLineType::ContextEOFNL
+				) {
+					continue;
 				}
 
 				if (is_staged && !selected_line)
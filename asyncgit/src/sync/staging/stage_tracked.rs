@@ -1,9 +1,11 @@
-use super::apply_selection;
+use super::{apply_selection, split_lines};
 use crate::{
 	error::{Error, Result},
 	sync::{
-		diff::DiffLinePosition, patches::get_file_diff_patch,
-		patches::patch_get_hunklines, repository::repo, RepoPath,
+		diff::DiffLinePosition, filter::apply_clean_filter,
+		index_cache::invalidate_index_cache_repo,
+		patches::get_file_diff_patch, patches::patch_get_hunklines,
+		repository::repo, RepoPath,
 	},
 };
 use easy_cast::Conv;
@@ -42,12 +44,23 @@ pub fn stage_lines(
 			get_file_diff_patch(&repo, file_path, is_stage, false)?;
 		let hunks = patch_get_hunklines(&patch)?;
 
-		let old_lines = indexed_content.lines().collect::<Vec<_>>();
+		let old_lines = split_lines(&indexed_content);
 
 		apply_selection(lines, &hunks, &old_lines, is_stage, false)?
 	};
 
-	let blob_id = repo.blob(new_content.as_bytes())?;
+	// the added lines come straight from the worktree
+	let new_content = if is_stage {
+		new_content.into_bytes()
+	} else {
+		apply_clean_filter(
+			&repo,
+			file_path,
+			new_content.into_bytes(),
+		)?
+	};
+
+	let blob_id = repo.blob(&new_content)?;
 
 	idx.id = blob_id;
 	idx.file_size = u32::try_conv(new_content.len())?;
@@ -55,6 +68,7 @@ pub fn stage_lines(
 
 	index.write()?;
 	index.read(true)?;
+	invalidate_index_cache_repo(&repo);
 
 	Ok(())
 }
@@ -63,10 +77,118 @@ pub fn stage_lines(
 mod test {
 	use super::*;
 	use crate::sync::{
-		diff::get_diff,
+		diff::{get_diff, DiffLineType},
 		tests::{get_statuses, repo_init, write_commit_file},
 		utils::{repo_write_file, stage_add_file},
 	};
+	use git2::Repository;
+
+	/// first and last line differ, so that we get two hunks
+	fn content(first: &str, last: &str) -> String {
+		let mid: String =
+			(1..10).map(|i| format!("line {i}\n")).collect();
+		format!("{first}\n{mid}{last}")
+	}
+
+	fn index_blob(repo: &Repository) -> Vec<u8> {
+		let mut index = repo.index().unwrap();
+		index.read(true).unwrap();
+		let entry = index.get_path(Path::new("test.txt"), 0).unwrap();
+		repo.find_blob(entry.id).unwrap().content().to_vec()
+	}
+
+	/// all changed lines of the last hunk
+	fn last_hunk_lines(
+		path: &RepoPath,
+		stage: bool,
+	) -> Vec<DiffLinePosition> {
+		let diff = get_diff(path, "test.txt", stage, None).unwrap();
+
+		diff.hunks
+			.last()
+			.unwrap()
+			.lines
+			.iter()
+			.filter(|line| {
+				matches!(
+					line.line_type,
+					DiffLineType::Add | DiffLineType::Delete
+				)
+			})
+			.map(|line| line.position)
+			.collect()
+	}
+
+	/// stages the lines of the last hunk of the worktree change from
+	/// `old` to `new`, checks the index content and unstages them again
+	fn stage_unstage_last_lines(old: &str, new: &str, staged: &str) {
+		let (path, repo) = repo_init().unwrap();
+		let path: &RepoPath = &path.path().to_str().unwrap().into();
+
+		write_commit_file(&repo, "test.txt", old, "c1");
+		repo_write_file(&repo, "test.txt", new).unwrap();
+
+		let lines = last_hunk_lines(path, false);
+		stage_lines(path, "test.txt", false, &lines).unwrap();
+
+		assert_eq!(
+			String::from_utf8(index_blob(&repo)).unwrap(),
+			staged
+		);
+
+		let lines = last_hunk_lines(path, true);
+		stage_lines(path, "test.txt", true, &lines).unwrap();
+
+		assert_eq!(
+			String::from_utf8(index_blob(&repo)).unwrap(),
+			old
+		);
+	}
+
+	#[test]
+	fn test_stage_lines_remove_newline_at_eof() {
+		stage_unstage_last_lines(
+			&content("a", "end\n"),
+			&content("b", "end"),
+			&content("a", "end"),
+		);
+	}
+
+	#[test]
+	fn test_stage_lines_add_newline_at_eof() {
+		stage_unstage_last_lines(
+			&content("a", "end"),
+			&content("b", "end\n"),
+			&content("a", "end\n"),
+		);
+	}
+
+	#[test]
+	fn test_stage_lines_modify_last_line_without_newline() {
+		stage_unstage_last_lines(
+			&content("a", "end"),
+			&content("b", "END"),
+			&content("a", "END"),
+		);
+	}
+
+	#[test]
+	fn test_stage_lines_modify_last_line_and_remove_newline() {
+		stage_unstage_last_lines(
+			&content("a", "end\n"),
+			&content("b", "END"),
+			&content("a", "END"),
+		);
+	}
+
+	#[test]
+	fn test_stage_lines_modify_last_line_and_add_newline() {
+		stage_unstage_last_lines(
+			&content("a", "end"),
+			&content("b", "END\n"),
+			&content("a", "END\n"),
+		);
+	}
 
 	#[test]
 	fn test_stage() {
@@ -138,8 +260,12 @@ c = 4";
 
 		let diff = get_diff(path, "test.txt", true, None).unwrap();
 
-		assert_eq!(diff.lines, 5);
-		assert_eq!(&*diff.hunks[0].lines[0].content, "@@ -1,2 +1 @@");
+		// both lines removed, no empty line left behind
+		assert_eq!(diff.lines, 4);
+		assert_eq!(
+			&*diff.hunks[0].lines[0].content,
+			"@@ -1,2 +0,0 @@"
+		);
 	}
 
 	#[test]
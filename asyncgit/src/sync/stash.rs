@@ -1,4 +1,7 @@
-use super::{CommitId, RepoPath};
+use super::{
+	audit::{self, AuditOperation},
+	CommitId, RepoPath,
+};
 use crate::{
 	error::{Error, Result},
 	sync::repository::repo,
@@ -9,6 +12,8 @@ use git2::{
 };
 use scopetime::scope_time;
 
+const STASH_REF: &str = "refs/stash";
+
 ///
 pub fn get_stashes(repo_path: &RepoPath) -> Result<Vec<CommitId>> {
 	scope_time!("get_stashes");
@@ -36,6 +41,14 @@ pub fn stash_drop(
 
 	repo.stash_drop(index)?;
 
+	audit::record(
+		repo_path,
+		AuditOperation::StashDrop,
+		&[STASH_REF],
+		Some(stash_id),
+		None,
+	);
+
 	Ok(())
 }
 
@@ -52,6 +65,14 @@ pub fn stash_pop(
 
 	repo.stash_pop(index, None)?;
 
+	audit::record(
+		repo_path,
+		AuditOperation::StashPop,
+		&[STASH_REF],
+		Some(stash_id),
+		None,
+	);
+
 	Ok(())
 }
 
@@ -121,6 +142,14 @@ pub fn stash_save(
 
 	let id = repo.stash_save2(&sig, message, Some(options))?;
 
+	audit::record(
+		repo_path,
+		AuditOperation::StashSave,
+		&[STASH_REF],
+		None,
+		Some(id.into()),
+	);
+
 	Ok(CommitId::new(id))
 }
 
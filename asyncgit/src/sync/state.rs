@@ -1,10 +1,13 @@
 use super::RepoPath;
-use crate::{error::Result, sync::repository::repo};
-use git2::RepositoryState;
+use crate::{
+	error::{Error, Result},
+	sync::repository::repo,
+};
+use git2::{Repository, RepositoryState};
 use scopetime::scope_time;
 
 ///
-#[derive(Debug, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub enum RepoState {
 	///
 	Clean,
@@ -43,3 +46,88 @@ pub fn repo_state(repo_path: &RepoPath) -> Result<RepoState> {
 
 	Ok(state.into())
 }
+
+/// fails with [`Error::PendingOperation`] while a merge, rebase, revert
+/// (or anything else git keeps state for) is in progress, no matter if
+/// gitui or another tool started it.
+///
+/// guards the operations that would wreck that state: checkouts,
+/// resets and starting another merge or rebase. staging and committing
+/// stay possible, they are needed to resolve the conflicts.
+pub fn ensure_clean_state(repo: &Repository) -> Result<()> {
+	match RepoState::from(repo.state()) {
+		RepoState::Clean => Ok(()),
+		state => Err(Error::PendingOperation(
+			format!("{state:?}").to_lowercase(),
+		)),
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::sync::{
+		branch_merge_upstream_fastforward, checkout_branch,
+		checkout_commit, create_branch, merge_branch, rebase_branch,
+		reset_repo, stage_add_file,
+		tests::{repo_init, write_commit_file},
+	};
+	use git2::{BranchType, ResetType};
+	use std::{fs, path::Path};
+
+	/// what `git rebase` leaves behind while it waits for the user
+	fn simulate_rebase(repo: &Repository) {
+		let dir = repo.path().join("rebase-merge");
+		fs::create_dir(&dir).unwrap();
+		fs::write(dir.join("head-name"), "refs/heads/master\n")
+			.unwrap();
+		fs::write(
+			dir.join("onto"),
+			"0000000000000000000000000000000000000000\n",
+		)
+		.unwrap();
+		fs::write(dir.join("msgnum"), "1\n").unwrap();
+		fs::write(dir.join("end"), "2\n").unwrap();
+	}
+
+	#[test]
+	fn test_gated_while_rebasing() {
+		let (_td, repo) = repo_init().unwrap();
+		let root = repo.path().parent().unwrap();
+		let repo_path: &RepoPath =
+			&root.as_os_str().to_str().unwrap().into();
+
+		let c1 = write_commit_file(&repo, "a.txt", "a", "c1");
+		create_branch(repo_path, "other").unwrap();
+		checkout_branch(repo_path, "master").unwrap();
+
+		simulate_rebase(&repo);
+		assert_eq!(repo_state(repo_path).unwrap(), RepoState::Rebase);
+
+		let pending = |res: Result<()>| matches!(res, Err(Error::PendingOperation(state)) if state == "rebase");
+
+		assert!(pending(checkout_branch(repo_path, "other")));
+		assert!(pending(checkout_commit(repo_path, c1)));
+		assert!(pending(reset_repo(repo_path, c1, ResetType::Hard)));
+		assert!(pending(merge_branch(
+			repo_path,
+			"other",
+			BranchType::Local
+		)));
+		assert!(pending(
+			rebase_branch(repo_path, "other", BranchType::Local)
+				.map(|_| ())
+		));
+		assert!(pending(branch_merge_upstream_fastforward(
+			repo_path, "master"
+		)));
+
+		// resolving conflicts needs staging
+		fs::write(root.join("a.txt"), "resolved").unwrap();
+		stage_add_file(repo_path, Path::new("a.txt")).unwrap();
+
+		fs::remove_dir_all(repo.path().join("rebase-merge")).unwrap();
+		assert_eq!(repo_state(repo_path).unwrap(), RepoState::Clean);
+		checkout_branch(repo_path, "other").unwrap();
+	}
+}
@@ -3,7 +3,10 @@
 use crate::{
 	error::Error,
 	error::Result,
-	sync::{config::untracked_files_config_repo, repository::repo},
+	sync::{
+		config::untracked_files_config_repo, ignore_rules,
+		repository::repo,
+	},
 };
 use git2::{Delta, Status, StatusOptions, StatusShow};
 use scopetime::scope_time;
@@ -12,7 +15,9 @@ use std::path::Path;
 use super::{RepoPath, ShowUntrackedFilesConfig};
 
 ///
-#[derive(Copy, Clone, Hash, PartialEq, Eq, Debug)]
+#[derive(
+	Copy, Clone, Hash, PartialEq, Eq, PartialOrd, Ord, Debug,
+)]
 pub enum StatusItemType {
 	///
 	New,
@@ -68,9 +73,10 @@ pub struct StatusItem {
 }
 
 ///
-#[derive(Copy, Clone, Hash, PartialEq, Eq, Debug)]
+#[derive(Copy, Clone, Hash, PartialEq, Eq, Debug, Default)]
 pub enum StatusType {
 	///
+	#[default]
 	WorkingDir,
 	///
 	Stage,
@@ -78,12 +84,6 @@ pub enum StatusType {
 	Both,
 }
 
-impl Default for StatusType {
-	fn default() -> Self {
-		Self::WorkingDir
-	}
-}
-
 impl From<StatusType> for StatusShow {
 	fn from(s: StatusType) -> Self {
 		match s {
@@ -111,6 +111,8 @@ pub fn is_workdir_clean(
 		untracked_files_config_repo(&repo)?
 	};
 
+	ignore_rules::apply_global_excludes(&repo)?;
+
 	let mut options = StatusOptions::default();
 	options
 		.show(StatusShow::Workdir)
@@ -126,7 +128,9 @@ pub fn is_workdir_clean(
 	Ok(statuses.is_empty())
 }
 
-/// guarantees sorting
+/// guarantees sorting: by path (component wise, so `a/b` comes before
+/// `a-b`), equal paths by status. this keeps the order stable across
+/// refreshes.
 pub fn get_status(
 	repo_path: &RepoPath,
 	status_type: StatusType,
@@ -146,6 +150,8 @@ pub fn get_status(
 		untracked_files_config_repo(&repo)?
 	};
 
+	ignore_rules::apply_global_excludes(&repo)?;
+
 	let mut options = StatusOptions::default();
 	options
 		.show(status_type.into())
@@ -190,7 +196,9 @@ pub fn get_status(
 	}
 
 	res.sort_by(|a, b| {
-		Path::new(a.path.as_str()).cmp(Path::new(b.path.as_str()))
+		Path::new(a.path.as_str())
+			.cmp(Path::new(b.path.as_str()))
+			.then_with(|| a.status.cmp(&b.status))
 	});
 
 	Ok(res)
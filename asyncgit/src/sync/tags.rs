@@ -1,7 +1,11 @@
 use super::{get_commits_info, CommitId, RepoPath};
 use crate::{
 	error::Result,
-	sync::{repository::repo, utils::bytes2string},
+	sync::{
+		audit::{self, AuditOperation},
+		repository::repo,
+		utils::bytes2string,
+	},
 };
 use scopetime::scope_time;
 use std::{
@@ -159,7 +163,7 @@ pub fn get_tags_with_metadata(
 			unique_commit_infos.get(commit_id).map(|commit_info| {
 				TagWithMetadata {
 					name: String::from(tag),
-					author: commit_info.author.clone(),
+					author: commit_info.author().to_string(),
 					time: commit_info.time,
 					message: commit_info.message.clone(),
 					commit_id: *commit_id,
@@ -169,7 +173,7 @@ pub fn get_tags_with_metadata(
 		})
 		.collect();
 
-	tags.sort_unstable_by(|a, b| b.time.cmp(&a.time));
+	tags.sort_unstable_by_key(|tag| std::cmp::Reverse(tag.time));
 
 	Ok(tags)
 }
@@ -182,8 +186,18 @@ pub fn delete_tag(
 	scope_time!("delete_tag");
 
 	let repo = repo(repo_path)?;
+	let tag_ref = format!("refs/tags/{tag_name}");
+	let old = audit::ref_target(&repo, &tag_ref);
 	repo.tag_delete(tag_name)?;
 
+	audit::record(
+		repo_path,
+		AuditOperation::TagDelete,
+		&[&tag_ref],
+		old,
+		None,
+	);
+
 	Ok(())
 }
 
@@ -1,26 +1,58 @@
 use super::{CommitId, RepoPath};
 use crate::{
 	error::{Error, Result},
-	sync::repository::repo,
+	sync::{intern::intern_dir, repository::repo},
 };
-use git2::{Oid, Repository, Tree};
+use git2::{build::CheckoutBuilder, Oid, Repository, Tree};
 use scopetime::scope_time;
 use std::{
 	cmp::Ordering,
-	path::{Path, PathBuf},
+	ffi::OsStr,
+	iter,
+	path::{Component, Path, PathBuf},
+	sync::Arc,
 };
 
+/// files larger than this are not loaded by [`tree_file_content`]
+pub const MAX_TREE_FILE_SIZE: usize = 2 * 1024 * 1024;
+
 /// `tree_files` returns a list of `FileTree`
 #[derive(Debug, PartialEq, Eq, Clone)]
 pub struct TreeFile {
-	/// path of this file
-	pub path: PathBuf,
+	/// directory of this file, interned as it is shared by all
+	/// files in it
+	dir: Arc<Path>,
+	/// file name
+	name: Box<str>,
 	/// unix filemode
 	pub filemode: i32,
 	// internal object id
 	id: Oid,
 }
 
+impl TreeFile {
+	/// path of this file
+	pub fn path(&self) -> PathBuf {
+		self.dir.join(&*self.name)
+	}
+
+	/// directory of this file
+	pub fn dir(&self) -> &Path {
+		&self.dir
+	}
+
+	/// file name of this file
+	pub fn name(&self) -> &str {
+		&self.name
+	}
+
+	fn components(&self) -> impl Iterator<Item = Component<'_>> {
+		self.dir.components().chain(iter::once(Component::Normal(
+			OsStr::new(&*self.name),
+		)))
+	}
+}
+
 /// guarantees sorting the result
 pub fn tree_files(
 	repo_path: &RepoPath,
@@ -43,13 +75,16 @@ pub fn tree_files(
 }
 
 fn sort_file_list(files: &mut [TreeFile]) {
-	files.sort_by(|a, b| path_cmp(&a.path, &b.path));
+	files.sort_by(|a, b| path_cmp(a.components(), b.components()));
 }
 
 // applies topologically order on paths sorting
-fn path_cmp(a: &Path, b: &Path) -> Ordering {
-	let mut comp_a = a.components().peekable();
-	let mut comp_b = b.components().peekable();
+fn path_cmp<'a>(
+	a: impl Iterator<Item = Component<'a>>,
+	b: impl Iterator<Item = Component<'a>>,
+) -> Ordering {
+	let mut comp_a = a.peekable();
+	let mut comp_b = b.peekable();
 
 	loop {
 		let a = comp_a.next();
@@ -71,7 +106,38 @@ fn path_cmp(a: &Path, b: &Path) -> Ordering {
 	}
 }
 
-/// will only work on utf8 content
+/// the file at `path` in the tree of `commit`
+pub fn tree_file_at(
+	repo_path: &RepoPath,
+	commit: CommitId,
+	path: &Path,
+) -> Result<TreeFile> {
+	scope_time!("tree_file_at");
+
+	let repo = repo(repo_path)?;
+
+	let tree = repo.find_commit(commit.into())?.tree()?;
+	let entry = tree.get_path(path)?;
+
+	if entry.kind() != Some(git2::ObjectType::Blob) {
+		return Err(Error::Generic(format!(
+			"not a file: {}",
+			path.display()
+		)));
+	}
+
+	let dir = Path::new("./").join(path.parent().unwrap_or(path));
+
+	Ok(TreeFile {
+		dir: intern_dir(&dir),
+		name: String::from_utf8_lossy(entry.name_bytes()).into(),
+		filemode: entry.filemode(),
+		id: entry.id(),
+	})
+}
+
+/// will only work on utf8 content, files larger than
+/// [`MAX_TREE_FILE_SIZE`] are refused with [`Error::FileTooLarge`]
 pub fn tree_file_content(
 	repo_path: &RepoPath,
 	file: &TreeFile,
@@ -82,6 +148,10 @@ pub fn tree_file_content(
 
 	let blob = repo.find_blob(file.id)?;
 
+	if blob.size() > MAX_TREE_FILE_SIZE {
+		return Err(Error::FileTooLarge(blob.size()));
+	}
+
 	if blob.is_binary() {
 		return Err(Error::BinaryFile);
 	}
@@ -91,6 +161,27 @@ pub fn tree_file_content(
 	Ok(content)
 }
 
+/// overwrites `path` in the index and the workdir with its version
+/// in `commit`, like `git checkout <commit> -- <path>`
+pub fn checkout_tree_file(
+	repo_path: &RepoPath,
+	commit: CommitId,
+	path: &str,
+) -> Result<()> {
+	scope_time!("checkout_tree_file");
+
+	let repo = repo(repo_path)?;
+
+	let commit = repo.find_commit(commit.into())?;
+
+	let mut checkout_opts = CheckoutBuilder::new();
+	checkout_opts.force().path(path);
+
+	repo.checkout_tree(commit.as_object(), Some(&mut checkout_opts))?;
+
+	Ok(())
+}
+
 ///
 fn tree_recurse(
 	repo: &Repository,
@@ -100,19 +191,23 @@ fn tree_recurse(
 ) -> Result<()> {
 	out.reserve(tree.len());
 
+	let dir = intern_dir(path);
+
 	for e in tree {
-		let p = String::from_utf8_lossy(e.name_bytes());
-		let path = path.join(p.to_string());
+		let name = String::from_utf8_lossy(e.name_bytes());
 		match e.kind() {
 			Some(git2::ObjectType::Blob) => {
-				let id = e.id();
-				let filemode = e.filemode();
-				out.push(TreeFile { path, filemode, id });
+				out.push(TreeFile {
+					dir: Arc::clone(&dir),
+					name: name.into(),
+					filemode: e.filemode(),
+					id: e.id(),
+				});
 			}
 			Some(git2::ObjectType::Tree) => {
 				let obj = e.to_object(repo)?;
 				let tree = obj.peel_to_tree()?;
-				tree_recurse(repo, &path, &tree, out)?;
+				tree_recurse(repo, &path.join(&*name), &tree, out)?;
 			}
 			Some(_) | None => (),
 		}
@@ -126,6 +221,17 @@ mod tests {
 	use crate::sync::tests::{repo_init, write_commit_file};
 	use pretty_assertions::{assert_eq, assert_ne};
 
+	fn tree_file(path: &str) -> TreeFile {
+		let path = Path::new(path);
+
+		TreeFile {
+			dir: intern_dir(path.parent().unwrap()),
+			name: path.file_name().unwrap().to_str().unwrap().into(),
+			filemode: 0,
+			id: Oid::zero(),
+		}
+	}
+
 	#[test]
 	fn test_smoke() {
 		let (_td, repo) = repo_init().unwrap();
@@ -139,7 +245,7 @@ mod tests {
 		let files = tree_files(repo_path, c1).unwrap();
 
 		assert_eq!(files.len(), 1);
-		assert_eq!(files[0].path, PathBuf::from("./test.txt"));
+		assert_eq!(files[0].path(), PathBuf::from("./test.txt"));
 
 		let c2 =
 			write_commit_file(&repo, "test.txt", "content2", "c2");
@@ -154,22 +260,84 @@ mod tests {
 		assert_ne!(files_c2[0], files[0]);
 	}
 
+	#[test]
+	fn test_tree_file_at() {
+		let (_td, repo) = repo_init().unwrap();
+		let root = repo.path().parent().unwrap();
+		let repo_path: &RepoPath =
+			&root.as_os_str().to_str().unwrap().into();
+
+		std::fs::create_dir(root.join("dir")).unwrap();
+		let c1 =
+			write_commit_file(&repo, "dir/test.txt", "content", "c1");
+
+		let files = tree_files(repo_path, c1).unwrap();
+		let file =
+			tree_file_at(repo_path, c1, Path::new("dir/test.txt"))
+				.unwrap();
+
+		assert_eq!(file, files[0]);
+		assert!(
+			tree_file_at(repo_path, c1, Path::new("dir")).is_err()
+		);
+		assert!(tree_file_at(repo_path, c1, Path::new("missing"))
+			.is_err());
+	}
+
+	#[test]
+	fn test_content_too_large() {
+		let (_td, repo) = repo_init().unwrap();
+		let root = repo.path().parent().unwrap();
+		let repo_path: &RepoPath =
+			&root.as_os_str().to_str().unwrap().into();
+
+		let content = "a".repeat(MAX_TREE_FILE_SIZE + 1);
+		let c1 =
+			write_commit_file(&repo, "large.txt", &content, "c1");
+
+		let file =
+			tree_file_at(repo_path, c1, Path::new("large.txt"))
+				.unwrap();
+
+		assert!(matches!(
+			tree_file_content(repo_path, &file),
+			Err(Error::FileTooLarge(size)) if size == content.len()
+		));
+	}
+
+	#[test]
+	fn test_checkout_tree_file() {
+		let (_td, repo) = repo_init().unwrap();
+		let root = repo.path().parent().unwrap();
+		let repo_path: &RepoPath =
+			&root.as_os_str().to_str().unwrap().into();
+
+		let c1 = write_commit_file(&repo, "test.txt", "old", "c1");
+		write_commit_file(&repo, "test.txt", "new", "c2");
+		write_commit_file(&repo, "other.txt", "other", "c3");
+
+		checkout_tree_file(repo_path, c1, "test.txt").unwrap();
+
+		assert_eq!(
+			std::fs::read_to_string(root.join("test.txt")).unwrap(),
+			"old"
+		);
+		// other files are left alone
+		assert!(root.join("other.txt").exists());
+	}
+
 	#[test]
 	fn test_sorting() {
 		let mut list = ["file", "folder/file", "folder/afile"]
 			.iter()
-			.map(|f| TreeFile {
-				path: PathBuf::from(f),
-				filemode: 0,
-				id: Oid::zero(),
-			})
+			.map(|f| tree_file(f))
 			.collect::<Vec<_>>();
 
 		sort_file_list(&mut list);
 
 		assert_eq!(
 			list.iter()
-				.map(|f| f.path.to_string_lossy())
+				.map(|f| f.path().to_string_lossy().into_owned())
 				.collect::<Vec<_>>(),
 			vec![
 				String::from("folder/afile"),
@@ -183,18 +351,14 @@ mod tests {
 	fn test_sorting_folders() {
 		let mut list = ["bfolder/file", "afolder/file"]
 			.iter()
-			.map(|f| TreeFile {
-				path: PathBuf::from(f),
-				filemode: 0,
-				id: Oid::zero(),
-			})
+			.map(|f| tree_file(f))
 			.collect::<Vec<_>>();
 
 		sort_file_list(&mut list);
 
 		assert_eq!(
 			list.iter()
-				.map(|f| f.path.to_string_lossy())
+				.map(|f| f.path().to_string_lossy().into_owned())
 				.collect::<Vec<_>>(),
 			vec![
 				String::from("afolder/file"),
@@ -207,18 +371,14 @@ mod tests {
 	fn test_sorting_folders2() {
 		let mut list = ["bfolder/sub/file", "afolder/file"]
 			.iter()
-			.map(|f| TreeFile {
-				path: PathBuf::from(f),
-				filemode: 0,
-				id: Oid::zero(),
-			})
+			.map(|f| tree_file(f))
 			.collect::<Vec<_>>();
 
 		sort_file_list(&mut list);
 
 		assert_eq!(
 			list.iter()
-				.map(|f| f.path.to_string_lossy())
+				.map(|f| f.path().to_string_lossy().into_owned())
 				.collect::<Vec<_>>(),
 			vec![
 				String::from("afolder/file"),
@@ -231,8 +391,8 @@ mod tests {
 	fn test_path_cmp() {
 		assert_eq!(
 			path_cmp(
-				&PathBuf::from("bfolder/sub/file"),
-				&PathBuf::from("afolder/file")
+				Path::new("bfolder/sub/file").components(),
+				Path::new("afolder/file").components()
 			),
 			Ordering::Greater
 		);
@@ -242,8 +402,8 @@ mod tests {
 	fn test_path_file_cmp() {
 		assert_eq!(
 			path_cmp(
-				&PathBuf::from("a"),
-				&PathBuf::from("afolder/file")
+				Path::new("a").components(),
+				Path::new("afolder/file").components()
 			),
 			Ordering::Greater
 		);
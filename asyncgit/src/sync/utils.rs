@@ -1,11 +1,15 @@
 //! sync git api (various methods)
 
 use super::{
-	repository::repo, CommitId, RepoPath, ShowUntrackedFilesConfig,
+	index_cache::invalidate_index_cache_repo, repository::repo,
+	CommitId, RepoPath, ShowUntrackedFilesConfig,
 };
 use crate::{
 	error::{Error, Result},
-	sync::config::untracked_files_config_repo,
+	sync::{
+		config::untracked_files_config_repo,
+		ignore_rules::apply_global_excludes,
+	},
 };
 use git2::{IndexAddOption, Repository, RepositoryOpenFlags};
 use scopetime::scope_time;
@@ -86,6 +90,17 @@ pub fn get_head_repo(repo: &Repository) -> Result<CommitId> {
 	head.map_or(Err(Error::NoHead), |head_id| Ok(head_id.into()))
 }
 
+/// the ref moved along with `HEAD` (by a commit or reset): the branch
+/// `HEAD` points at (even if it does not exist yet) or `HEAD` itself if
+/// detached
+pub(crate) fn head_target_ref(repo: &Repository) -> Result<String> {
+	let head = repo.find_reference("HEAD")?;
+
+	Ok(head
+		.symbolic_target()
+		.map_or_else(|| String::from("HEAD"), String::from))
+}
+
 /// add a file diff from workingdir to stage (will not add removed files see `stage_addremoved`)
 pub fn stage_add_file(
 	repo_path: &RepoPath,
@@ -99,6 +114,7 @@ pub fn stage_add_file(
 
 	index.add_path(path)?;
 	index.write()?;
+	invalidate_index_cache_repo(&repo);
 
 	Ok(())
 }
@@ -122,6 +138,7 @@ pub fn stage_add_all(
 	};
 
 	if stage_untracked.include_untracked() {
+		apply_global_excludes(&repo)?;
 		index.add_all(
 			vec![pattern],
 			IndexAddOption::DEFAULT,
@@ -132,6 +149,7 @@ pub fn stage_add_all(
 	}
 
 	index.write()?;
+	invalidate_index_cache_repo(&repo);
 
 	Ok(())
 }
@@ -164,6 +182,7 @@ pub fn stage_addremoved(
 
 	index.remove_path(path)?;
 	index.write()?;
+	invalidate_index_cache_repo(&repo);
 
 	Ok(())
 }
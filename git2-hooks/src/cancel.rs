@@ -0,0 +1,30 @@
+//! cancelling hooks from another thread, e.g. because the user gave up
+//! waiting for them. see [`crate::HookContext::cancel`]
+
+use std::sync::{
+	atomic::{AtomicBool, Ordering},
+	Arc,
+};
+
+/// shared flag to cancel hooks run with it as
+/// [`crate::HookContext::cancel`], clones cancel the same hooks
+#[derive(Debug, Clone, Default)]
+pub struct CancellationToken(Arc<AtomicBool>);
+
+impl CancellationToken {
+	/// a token not cancelled yet
+	pub fn new() -> Self {
+		Self::default()
+	}
+
+	/// kills the hook running with this token (and everything it
+	/// started), hooks not started yet are not run at all
+	pub fn cancel(&self) {
+		self.0.store(true, Ordering::SeqCst);
+	}
+
+	/// [`Self::cancel`] was called on this token or one of its clones
+	pub fn is_cancelled(&self) -> bool {
+		self.0.load(Ordering::SeqCst)
+	}
+}
@@ -0,0 +1,542 @@
+use git2::{Config, Repository};
+
+use crate::{error::Result, HookType};
+
+use std::{collections::BTreeMap, path::PathBuf, time::Duration};
+
+const CONFIG_HOOKS_TIMEOUT: &str = "gitui.hooks.timeout";
+/// older spelling of [`CONFIG_HOOKS_TIMEOUT`], only read if that is unset.
+/// also the section of the per-hook timeouts, `gitui.hooksTimeout.<hook>`
+const CONFIG_HOOKS_TIMEOUT_FALLBACK: &str = "gitui.hooksTimeout";
+const CONFIG_HOOKS_SHELL: &str = "gitui.hooks.shell";
+const CONFIG_HOOKS_DISABLED: &str = "gitui.hooks.disabled";
+const CONFIG_HOOKS_ALLOW: &str = "gitui.hooks.allow";
+const CONFIG_HOOKS_DENY: &str = "gitui.hooks.deny";
+const CONFIG_HOOKS_PATH: &str = "gitui.hooks.path";
+const CONFIG_HOOKS_TERMINATED: &str = "gitui.hooks.terminated";
+const CONFIG_HOOKS_PREFIX: &str = "gitui.hooks.prefix";
+const CONFIG_HOOKS_KILL_GRACE: &str = "gitui.hooks.killGrace";
+
+/// how long a timed out hook has to exit after `SIGTERM` before it is
+/// killed, see [`HooksConfig::kill_grace`]
+pub const DEFAULT_KILL_GRACE: Duration = Duration::from_millis(200);
+
+/// how a hook killed by a signal that was not sent by gitui (e.g. by
+/// the OOM killer) is reported
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum TerminatedHandling {
+	/// as [`crate::HookResult::Terminated`]
+	#[default]
+	Report,
+	/// like any other failure, as [`crate::HookResult::RunNotSuccessful`]
+	/// without an exit code
+	Failure,
+}
+
+impl TerminatedHandling {
+	fn from_config_value(value: &str) -> Self {
+		match value {
+			"failure" => Self::Failure,
+			"report" => Self::Report,
+			_ => {
+				log::warn!(
+					"unknown {CONFIG_HOOKS_TERMINATED} value: {value}"
+				);
+				Self::default()
+			}
+		}
+	}
+}
+
+/// gitui specific hook settings, read from the `[gitui "hooks"]`
+/// section of the git config:
+///
+/// ```text
+/// [gitui "hooks"]
+///     # kill hooks running longer than this (in ms), `0` disables it.
+///     # `gitui.hooksTimeout` is read instead if this is not set
+///     timeout = 5000
+///     # shell used for hooks that cannot be executed directly
+///     shell = /bin/bash
+///     # skip all hooks
+///     disabled = false
+///     # only run these hooks (can be repeated)
+///     allow = pre-commit
+///     # never run these hooks (can be repeated)
+///     deny = post-commit
+///     # additional folders to search hooks in (can be repeated)
+///     path = .githooks
+///     # hooks killed by a signal (e.g. out of memory): report | failure
+///     terminated = report
+///     # run every hook through this command (split at whitespace)
+///     prefix = /usr/bin/time -v
+///     # time (in ms) a timed out hook gets to clean up before it is
+///     # killed, `0` kills it right away
+///     killGrace = 200
+///
+/// # timeout of a single hook (in ms), wins over `gitui.hooks.timeout`
+/// [gitui "hooksTimeout"]
+///     pre-commit = 60000
+/// ```
+///
+/// every key is optional, the defaults (see [`Default`]) resemble
+/// the behaviour of running hooks without any of these settings.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct HooksConfig {
+	/// `gitui.hooks.timeout` (or `gitui.hooksTimeout` if that is not
+	/// set), [`Duration::ZERO`] if it is turned off
+	pub timeout: Option<Duration>,
+	/// `gitui.hooks.shell`
+	pub shell: Option<PathBuf>,
+	/// `gitui.hooks.disabled`
+	pub disabled: bool,
+	/// `gitui.hooks.allow`
+	pub allow: Vec<String>,
+	/// `gitui.hooks.deny`
+	pub deny: Vec<String>,
+	/// `gitui.hooks.path`
+	pub additional_paths: Vec<String>,
+	/// `gitui.hooks.terminated`
+	pub terminated: TerminatedHandling,
+	/// `gitui.hooks.prefix`, program and arguments every hook is run
+	/// with (e.g. `strace -f`), empty to run hooks as they are
+	pub prefix: Vec<String>,
+	/// `gitui.hooks.killGrace`, timed out hooks are sent `SIGTERM`
+	/// first and only killed if they did not exit after this long
+	/// ([`DEFAULT_KILL_GRACE`] if not set). windows has no `SIGTERM`,
+	/// hooks are killed right away there
+	pub kill_grace: Option<Duration>,
+	/// `gitui.hooksTimeout.<hook>` of every hook that has one set,
+	/// see [`Self::timeout_for`]
+	pub hook_timeouts: BTreeMap<String, Duration>,
+}
+
+/// where the timeout a hook runs with is set, see
+/// [`HooksConfig::timeout_for`]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum TimeoutSource {
+	/// passed explicitly by the caller (or none at all)
+	#[default]
+	Explicit,
+	/// `gitui.hooksTimeout.<hook>`
+	HookConfig,
+	/// `gitui.hooks.timeout` or its older spelling `gitui.hooksTimeout`
+	Config,
+}
+
+impl TimeoutSource {
+	/// the config key to raise the timeout of `hook` with, `None` if it
+	/// is not set in the config. for [`Self::Config`] this is always
+	/// `gitui.hooks.timeout`, which wins over `gitui.hooksTimeout`
+	pub fn config_key(self, hook: &str) -> Option<String> {
+		match self {
+			Self::Explicit => None,
+			Self::HookConfig => Some(format!(
+				"{CONFIG_HOOKS_TIMEOUT_FALLBACK}.{hook}"
+			)),
+			Self::Config => Some(CONFIG_HOOKS_TIMEOUT.to_string()),
+		}
+	}
+}
+
+impl HooksConfig {
+	/// read hooks config of `repo` (including global and system config)
+	pub fn from_repo(repo: &Repository) -> Result<Self> {
+		Self::from_config(&repo.config()?)
+	}
+
+	/// read hooks config from an already opened `config`
+	pub fn from_config(config: &Config) -> Result<Self> {
+		Ok(Self {
+			timeout: timeout_value(config, CONFIG_HOOKS_TIMEOUT)
+				.or_else(|| {
+					timeout_value(
+						config,
+						CONFIG_HOOKS_TIMEOUT_FALLBACK,
+					)
+				}),
+			shell: config
+				.get_path(CONFIG_HOOKS_SHELL)
+				.ok()
+				.filter(|shell| !shell.as_os_str().is_empty()),
+			disabled: config
+				.get_bool(CONFIG_HOOKS_DISABLED)
+				.unwrap_or_default(),
+			allow: multivar(config, CONFIG_HOOKS_ALLOW)?,
+			deny: multivar(config, CONFIG_HOOKS_DENY)?,
+			additional_paths: multivar(config, CONFIG_HOOKS_PATH)?,
+			terminated: config
+				.get_string(CONFIG_HOOKS_TERMINATED)
+				.map(|value| {
+					TerminatedHandling::from_config_value(&value)
+				})
+				.unwrap_or_default(),
+			prefix: config
+				.get_string(CONFIG_HOOKS_PREFIX)
+				.map(|prefix| {
+					prefix
+						.split_whitespace()
+						.map(String::from)
+						.collect()
+				})
+				.unwrap_or_default(),
+			kill_grace: timeout_value(
+				config,
+				CONFIG_HOOKS_KILL_GRACE,
+			),
+			hook_timeouts: HookType::ALL
+				.iter()
+				.filter_map(|hook| {
+					let hook = hook.filename();
+					timeout_value(
+						config,
+						&format!(
+							"{CONFIG_HOOKS_TIMEOUT_FALLBACK}.{hook}"
+						),
+					)
+					.map(|timeout| (hook.to_string(), timeout))
+				})
+				.collect(),
+		})
+	}
+
+	/// the timeout `hook` runs with unless one is passed explicitly
+	/// and where it is set: its `gitui.hooksTimeout.<hook>`, otherwise
+	/// [`Self::timeout`]. a zero per-hook timeout turns it off for
+	/// that hook instead of falling back
+	pub fn timeout_for(
+		&self,
+		hook: &str,
+	) -> Option<(Duration, TimeoutSource)> {
+		self.hook_timeouts
+			.get(hook)
+			.map(|timeout| (*timeout, TimeoutSource::HookConfig))
+			.or_else(|| {
+				self.timeout
+					.map(|timeout| (timeout, TimeoutSource::Config))
+			})
+	}
+
+	/// `false` if `hook` is not supposed to be run according to
+	/// `disabled`, `deny` or `allow`
+	pub fn is_enabled(&self, hook: &str) -> bool {
+		!self.disabled
+			&& !self.deny.iter().any(|h| h == hook)
+			&& (self.allow.is_empty()
+				|| self.allow.iter().any(|h| h == hook))
+	}
+
+	/// `additional_paths` in the shape expected as `other_paths`
+	/// by the `hooks_*` functions
+	pub fn other_paths(&self) -> Vec<&str> {
+		self.additional_paths.iter().map(String::as_str).collect()
+	}
+
+	/// writes `gitui.hooks.timeout` to `config`, `None` removes it
+	/// (a zero `timeout` disables the timeout)
+	pub fn write_timeout(
+		config: &mut Config,
+		timeout: Option<Duration>,
+	) -> Result<()> {
+		match timeout {
+			Some(timeout) => config.set_i64(
+				CONFIG_HOOKS_TIMEOUT,
+				i64::try_from(timeout.as_millis())
+					.unwrap_or(i64::MAX),
+			)?,
+			None => remove(config, CONFIG_HOOKS_TIMEOUT)?,
+		}
+
+		Ok(())
+	}
+
+	/// writes `gitui.hooks.disabled` to `config`
+	pub fn write_disabled(
+		config: &mut Config,
+		disabled: bool,
+	) -> Result<()> {
+		Ok(config.set_bool(CONFIG_HOOKS_DISABLED, disabled)?)
+	}
+
+	/// replaces the `gitui.hooks.deny` entries of `config` by `hooks`
+	pub fn write_deny(
+		config: &mut Config,
+		hooks: &[String],
+	) -> Result<()> {
+		match config.remove_multivar(CONFIG_HOOKS_DENY, ".*") {
+			Err(e) if e.code() != git2::ErrorCode::NotFound => {
+				return Err(e.into());
+			}
+			_ => (),
+		}
+
+		for hook in hooks {
+			config.set_multivar(CONFIG_HOOKS_DENY, "^$", hook)?;
+		}
+
+		Ok(())
+	}
+}
+
+fn remove(config: &mut Config, key: &str) -> Result<()> {
+	match config.remove(key) {
+		Err(e) if e.code() != git2::ErrorCode::NotFound => {
+			Err(e.into())
+		}
+		_ => Ok(()),
+	}
+}
+
+/// milliseconds in `key`, negative values count as zero
+fn timeout_value(config: &Config, key: &str) -> Option<Duration> {
+	config.get_i64(key).ok().map(|ms| {
+		Duration::from_millis(u64::try_from(ms).unwrap_or_default())
+	})
+}
+
+fn multivar(config: &Config, key: &str) -> Result<Vec<String>> {
+	let mut values = Vec::new();
+
+	match config.multivar(key, None) {
+		Ok(entries) => {
+			entries.for_each(|entry| {
+				if let Some(value) = entry.value() {
+					values.push(value.to_string());
+				}
+			})?;
+		}
+		Err(e) if e.code() == git2::ErrorCode::NotFound => {}
+		Err(e) => return Err(e.into()),
+	}
+
+	Ok(values)
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use git2_testing::repo_init;
+	use pretty_assertions::assert_eq;
+
+	#[test]
+	fn test_defaults() {
+		let (_td, repo) = repo_init();
+
+		let config = HooksConfig::from_repo(&repo).unwrap();
+
+		assert_eq!(config, HooksConfig::default());
+		assert!(config.is_enabled("pre-commit"));
+	}
+
+	#[test]
+	fn test_section() {
+		let (_td, repo) = repo_init();
+
+		{
+			let mut config = repo.config().unwrap();
+			config.set_i64(CONFIG_HOOKS_TIMEOUT, 1500).unwrap();
+			config.set_str(CONFIG_HOOKS_SHELL, "/bin/bash").unwrap();
+			config.set_bool(CONFIG_HOOKS_DISABLED, false).unwrap();
+			config
+				.set_multivar(CONFIG_HOOKS_ALLOW, "^$", "pre-commit")
+				.unwrap();
+			config
+				.set_multivar(CONFIG_HOOKS_ALLOW, "^$", "commit-msg")
+				.unwrap();
+			config
+				.set_multivar(CONFIG_HOOKS_DENY, "^$", "commit-msg")
+				.unwrap();
+			config
+				.set_multivar(CONFIG_HOOKS_PATH, "^$", ".githooks")
+				.unwrap();
+			config
+				.set_str(CONFIG_HOOKS_TERMINATED, "failure")
+				.unwrap();
+			config
+				.set_str(CONFIG_HOOKS_PREFIX, " strace  -f ")
+				.unwrap();
+			config.set_i64(CONFIG_HOOKS_KILL_GRACE, 50).unwrap();
+			config
+				.set_i64("gitui.hooksTimeout.pre-commit", 60_000)
+				.unwrap();
+		}
+
+		let config = HooksConfig::from_repo(&repo).unwrap();
+
+		assert_eq!(
+			config,
+			HooksConfig {
+				timeout: Some(Duration::from_millis(1500)),
+				shell: Some(PathBuf::from("/bin/bash")),
+				disabled: false,
+				allow: vec![
+					String::from("pre-commit"),
+					String::from("commit-msg")
+				],
+				deny: vec![String::from("commit-msg")],
+				additional_paths: vec![String::from(".githooks")],
+				terminated: TerminatedHandling::Failure,
+				prefix: vec![
+					String::from("strace"),
+					String::from("-f")
+				],
+				kill_grace: Some(Duration::from_millis(50)),
+				hook_timeouts: BTreeMap::from([(
+					String::from("pre-commit"),
+					Duration::from_secs(60)
+				)]),
+			}
+		);
+		assert_eq!(config.other_paths(), vec![".githooks"]);
+
+		assert!(config.is_enabled("pre-commit"));
+		assert!(!config.is_enabled("commit-msg"));
+		assert!(!config.is_enabled("post-commit"));
+	}
+
+	#[test]
+	fn test_disabled_and_zero_timeout() {
+		let (_td, repo) = repo_init();
+
+		{
+			let mut config = repo.config().unwrap();
+			config.set_i64(CONFIG_HOOKS_TIMEOUT, 0).unwrap();
+			config.set_bool(CONFIG_HOOKS_DISABLED, true).unwrap();
+			config.set_i64(CONFIG_HOOKS_KILL_GRACE, 0).unwrap();
+		}
+
+		let config = HooksConfig::from_repo(&repo).unwrap();
+
+		assert_eq!(config.timeout, Some(Duration::ZERO));
+		assert_eq!(config.kill_grace, Some(Duration::ZERO));
+		assert!(!config.is_enabled("pre-commit"));
+	}
+
+	#[test]
+	fn test_timeout_fallback() {
+		let (_td, repo) = repo_init();
+		let mut config = repo.config().unwrap();
+		let timeout =
+			|| HooksConfig::from_repo(&repo).unwrap().timeout;
+
+		config.set_i64(CONFIG_HOOKS_TIMEOUT_FALLBACK, 1500).unwrap();
+		assert_eq!(timeout(), Some(Duration::from_millis(1500)));
+
+		// negative values count as zero, invalid ones as unset
+		config.set_i64(CONFIG_HOOKS_TIMEOUT_FALLBACK, -20).unwrap();
+		assert_eq!(timeout(), Some(Duration::ZERO));
+		config
+			.set_str(CONFIG_HOOKS_TIMEOUT_FALLBACK, "soon")
+			.unwrap();
+		assert_eq!(timeout(), None);
+
+		// the section key wins, even when it turns the timeout off
+		config.set_i64(CONFIG_HOOKS_TIMEOUT_FALLBACK, 1500).unwrap();
+		config.set_i64(CONFIG_HOOKS_TIMEOUT, 2000).unwrap();
+		assert_eq!(timeout(), Some(Duration::from_secs(2)));
+		config.set_i64(CONFIG_HOOKS_TIMEOUT, 0).unwrap();
+		assert_eq!(timeout(), Some(Duration::ZERO));
+	}
+
+	#[test]
+	fn test_timeout_for() {
+		let (_td, repo) = repo_init();
+		let mut config = repo.config().unwrap();
+		let timeout = |hook: &str| {
+			HooksConfig::from_repo(&repo).unwrap().timeout_for(hook)
+		};
+
+		assert_eq!(timeout("pre-commit"), None);
+
+		config.set_i64(CONFIG_HOOKS_TIMEOUT_FALLBACK, 1500).unwrap();
+		config.set_i64(CONFIG_HOOKS_TIMEOUT, 2000).unwrap();
+		config
+			.set_i64("gitui.hooksTimeout.pre-commit", 60_000)
+			.unwrap();
+		config.set_i64("gitui.hooksTimeout.commit-msg", 0).unwrap();
+
+		// the per-hook key beats both global ones
+		assert_eq!(
+			timeout("pre-commit"),
+			Some((
+				Duration::from_secs(60),
+				TimeoutSource::HookConfig
+			))
+		);
+		// zero turns it off for that hook only
+		assert_eq!(
+			timeout("commit-msg"),
+			Some((Duration::ZERO, TimeoutSource::HookConfig))
+		);
+		assert_eq!(
+			timeout("post-commit"),
+			Some((Duration::from_secs(2), TimeoutSource::Config))
+		);
+	}
+
+	#[test]
+	fn test_timeout_source_config_key() {
+		assert_eq!(
+			TimeoutSource::HookConfig.config_key("pre-commit"),
+			Some("gitui.hooksTimeout.pre-commit".to_string())
+		);
+		// the fallback alias is reported as the key that wins over it
+		assert_eq!(
+			TimeoutSource::Config.config_key("pre-commit"),
+			Some(CONFIG_HOOKS_TIMEOUT.to_string())
+		);
+		assert_eq!(
+			TimeoutSource::Explicit.config_key("pre-commit"),
+			None
+		);
+	}
+
+	#[test]
+	fn test_write() {
+		let (_td, repo) = repo_init();
+		let mut config = repo.config().unwrap();
+
+		HooksConfig::write_timeout(
+			&mut config,
+			Some(Duration::from_secs(2)),
+		)
+		.unwrap();
+		HooksConfig::write_disabled(&mut config, true).unwrap();
+		HooksConfig::write_deny(
+			&mut config,
+			&[
+				String::from("pre-commit"),
+				String::from("post-commit"),
+			],
+		)
+		.unwrap();
+
+		let read = HooksConfig::from_repo(&repo).unwrap();
+		assert_eq!(read.timeout, Some(Duration::from_secs(2)));
+		assert!(read.disabled);
+		assert_eq!(read.deny, vec!["pre-commit", "post-commit"]);
+
+		// rewriting replaces instead of appending
+		HooksConfig::write_timeout(&mut config, None).unwrap();
+		HooksConfig::write_timeout(&mut config, None).unwrap();
+		HooksConfig::write_deny(&mut config, &[String::from("a")])
+			.unwrap();
+
+		let read = HooksConfig::from_repo(&repo).unwrap();
+		assert_eq!(read.timeout, None);
+		assert_eq!(read.deny, vec!["a"]);
+
+		HooksConfig::write_timeout(&mut config, Some(Duration::ZERO))
+			.unwrap();
+		assert_eq!(
+			HooksConfig::from_repo(&repo).unwrap().timeout,
+			Some(Duration::ZERO)
+		);
+
+		HooksConfig::write_deny(&mut config, &[]).unwrap();
+		assert!(HooksConfig::from_repo(&repo)
+			.unwrap()
+			.deny
+			.is_empty());
+	}
+}
@@ -12,6 +12,9 @@ pub enum HooksError {
 	#[error("path string conversion error")]
 	PathToString,
 
+	#[error("hook is a directory: {}", path.display())]
+	HookIsDirectory { path: std::path::PathBuf },
+
 	#[error("shellexpand error:{0}")]
 	ShellExpand(#[from] shellexpand::LookupError<std::env::VarError>),
 }
@@ -0,0 +1,189 @@
+use crate::{
+	HOOK_APPLYPATCH_MSG, HOOK_COMMIT_MSG, HOOK_POST_APPLYPATCH,
+	HOOK_POST_CHECKOUT, HOOK_POST_COMMIT, HOOK_POST_MERGE,
+	HOOK_POST_RECEIVE, HOOK_POST_REWRITE, HOOK_POST_UPDATE,
+	HOOK_PREPARE_COMMIT_MSG, HOOK_PRE_APPLYPATCH, HOOK_PRE_COMMIT,
+	HOOK_PRE_PUSH, HOOK_PRE_REBASE, HOOK_PRE_RECEIVE,
+	HOOK_PROC_RECEIVE, HOOK_REFERENCE_TRANSACTION, HOOK_UPDATE,
+};
+
+/// directory git runs a hook from, see the "DESCRIPTION" section of
+/// `githooks(5)`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum HookWorkingDir {
+	/// root of the working tree (`$GIT_DIR` in a bare repository)
+	WorkTree,
+	/// `$GIT_DIR`, even in a non-bare repository
+	GitDir,
+}
+
+/// every hook known to this crate, see <https://git-scm.com/docs/githooks>
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum HookType {
+	/// `applypatch-msg`
+	ApplypatchMsg,
+	/// `pre-applypatch`
+	PreApplypatch,
+	/// `post-applypatch`
+	PostApplypatch,
+	/// `pre-commit`
+	PreCommit,
+	/// `prepare-commit-msg`
+	PrepareCommitMsg,
+	/// `commit-msg`
+	CommitMsg,
+	/// `post-commit`
+	PostCommit,
+	/// `pre-rebase`
+	PreRebase,
+	/// `post-checkout`
+	PostCheckout,
+	/// `post-merge`
+	PostMerge,
+	/// `pre-push`
+	PrePush,
+	/// `pre-receive` (server side)
+	PreReceive,
+	/// `update` (server side)
+	Update,
+	/// `proc-receive` (server side)
+	ProcReceive,
+	/// `post-receive` (server side)
+	PostReceive,
+	/// `post-update` (server side)
+	PostUpdate,
+	/// `post-rewrite`
+	PostRewrite,
+	/// `reference-transaction`
+	ReferenceTransaction,
+}
+
+impl HookType {
+	/// all variants in the order git documents them
+	pub const ALL: [Self; 18] = [
+		Self::ApplypatchMsg,
+		Self::PreApplypatch,
+		Self::PostApplypatch,
+		Self::PreCommit,
+		Self::PrepareCommitMsg,
+		Self::CommitMsg,
+		Self::PostCommit,
+		Self::PreRebase,
+		Self::PostCheckout,
+		Self::PostMerge,
+		Self::PrePush,
+		Self::PreReceive,
+		Self::Update,
+		Self::ProcReceive,
+		Self::PostReceive,
+		Self::PostUpdate,
+		Self::PostRewrite,
+		Self::ReferenceTransaction,
+	];
+
+	/// file name of the hook inside the hooks folder
+	pub const fn filename(self) -> &'static str {
+		match self {
+			Self::ApplypatchMsg => HOOK_APPLYPATCH_MSG,
+			Self::PreApplypatch => HOOK_PRE_APPLYPATCH,
+			Self::PostApplypatch => HOOK_POST_APPLYPATCH,
+			Self::PreCommit => HOOK_PRE_COMMIT,
+			Self::PrepareCommitMsg => HOOK_PREPARE_COMMIT_MSG,
+			Self::CommitMsg => HOOK_COMMIT_MSG,
+			Self::PostCommit => HOOK_POST_COMMIT,
+			Self::PreRebase => HOOK_PRE_REBASE,
+			Self::PostCheckout => HOOK_POST_CHECKOUT,
+			Self::PostMerge => HOOK_POST_MERGE,
+			Self::PrePush => HOOK_PRE_PUSH,
+			Self::PreReceive => HOOK_PRE_RECEIVE,
+			Self::Update => HOOK_UPDATE,
+			Self::ProcReceive => HOOK_PROC_RECEIVE,
+			Self::PostReceive => HOOK_POST_RECEIVE,
+			Self::PostUpdate => HOOK_POST_UPDATE,
+			Self::PostRewrite => HOOK_POST_REWRITE,
+			Self::ReferenceTransaction => HOOK_REFERENCE_TRANSACTION,
+		}
+	}
+
+	/// directory git runs the hook from: the server side hooks
+	/// run in `$GIT_DIR`, all others in the root of the working tree
+	pub const fn working_dir(self) -> HookWorkingDir {
+		match self {
+			Self::PreReceive
+			| Self::Update
+			| Self::ProcReceive
+			| Self::PostReceive
+			| Self::PostUpdate => HookWorkingDir::GitDir,
+			_ => HookWorkingDir::WorkTree,
+		}
+	}
+
+	/// a failing hook stops the operation, the hooks running after the
+	/// fact (like `post-commit`) cannot do that anymore
+	pub const fn can_abort(self) -> bool {
+		!matches!(
+			self,
+			Self::PostApplypatch
+				| Self::PostCommit
+				| Self::PostCheckout
+				| Self::PostMerge
+				| Self::PostReceive
+				| Self::PostUpdate
+				| Self::PostRewrite
+		)
+	}
+
+	/// inverse of [`Self::filename`]
+	pub fn from_filename(filename: &str) -> Option<Self> {
+		Self::ALL
+			.into_iter()
+			.find(|hook| hook.filename() == filename)
+	}
+}
+
+impl std::fmt::Display for HookType {
+	fn fmt(
+		&self,
+		f: &mut std::fmt::Formatter<'_>,
+	) -> std::fmt::Result {
+		f.write_str(self.filename())
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use pretty_assertions::assert_eq;
+
+	#[test]
+	fn test_filename_roundtrip() {
+		for hook in HookType::ALL {
+			assert_eq!(
+				HookType::from_filename(hook.filename()),
+				Some(hook)
+			);
+		}
+
+		assert_eq!(
+			HookType::from_filename("pre-commit.sample"),
+			None
+		);
+		assert_eq!(HookType::from_filename(""), None);
+	}
+
+	#[test]
+	fn test_working_dir() {
+		assert_eq!(
+			HookType::PreCommit.working_dir(),
+			HookWorkingDir::WorkTree
+		);
+		assert_eq!(
+			HookType::PreReceive.working_dir(),
+			HookWorkingDir::GitDir
+		);
+		assert_eq!(
+			HookType::PostUpdate.working_dir(),
+			HookWorkingDir::GitDir
+		);
+	}
+}
@@ -1,36 +1,184 @@
 use git2::Repository;
 
-use crate::{error::Result, HookResult, HooksError};
+use crate::{
+	cancel::CancellationToken,
+	error::Result,
+	process_tree::ProcessTree,
+	registry,
+	transcript::{
+		HookOutputLine, OutputBuffering, OutputStream, Transcript,
+		TranscriptReader,
+	},
+	HookResult, HookType, HookWorkingDir, HooksConfig, HooksError,
+	TerminatedHandling, DEFAULT_KILL_GRACE,
+};
 
 use std::{
 	ffi::{OsStr, OsString},
-	io::Read,
+	io::{Read, Write},
 	path::{Path, PathBuf},
 	process::{Child, Command, Stdio},
-	str::FromStr,
+	sync::mpsc,
 	thread,
-	time::Duration,
+	time::{Duration, Instant},
 };
 
 #[cfg(unix)]
 use {
 	nix::{
-		sys::signal::{killpg, SIGKILL},
-		unistd::Pid,
+		fcntl::{fcntl, FcntlArg, FdFlag},
+		sys::signal::SIGKILL,
+		unistd::pipe,
+	},
+	std::os::{
+		fd::{AsRawFd, OwnedFd, RawFd},
+		unix::process::CommandExt as _,
 	},
-	std::os::unix::process::CommandExt as _,
 };
 
+#[cfg(not(unix))]
+type RawFd = i32;
+
+#[derive(Clone)]
 pub struct HookPaths {
 	pub git: PathBuf,
 	pub hook: PathBuf,
+	/// `<hook>.d` next to `hook`, every executable in there is run
+	/// after `hook` by [`Self::run_hooks`]
+	pub hook_dir: PathBuf,
 	pub pwd: PathBuf,
+	/// shell to fall back to, see [`HooksConfig::shell`]
+	pub shell: Option<PathBuf>,
+	/// see [`HooksConfig::terminated`]
+	pub terminated: TerminatedHandling,
+	/// see [`HooksConfig::prefix`]
+	pub prefix: Vec<String>,
+	/// see [`HooksConfig::kill_grace`]
+	pub kill_grace: Duration,
+	/// git variables exported to the hook, `GIT_PREFIX` is only
+	/// filled in when it is run (see [`HookContext::invocation_dir`])
+	pub git_env: HookGitEnv,
+}
+
+/// what a hook is run with besides its arguments: passed to every run
+/// explicitly, so it is the same no matter which thread runs the hook
+#[derive(Debug, Default, Clone, Copy)]
+pub struct HookContext<'a> {
+	/// set on top of the inherited environment (and the git variables),
+	/// later entries win, e.g. to let hooks know who runs them
+	pub env: &'a [(&'a str, &'a str)],
+	/// the directory git would have been invoked from, which
+	/// determines `GIT_PREFIX`. `None` is the current directory.
+	///
+	/// e.g. for a repository opened from a subdirectory that is not
+	/// the current one.
+	pub invocation_dir: Option<&'a Path>,
+	/// once cancelled (usually from another thread) the hook is stopped
+	/// like a timed out one, which results in
+	/// [`HookResult::Cancelled`]. hooks not started yet are not run.
+	pub cancel: Option<&'a CancellationToken>,
+}
+
+/// the variables git itself sets for hooks, so that scripts behave the
+/// same no matter if git or gitui runs them
+#[derive(Debug, Default, Clone)]
+pub struct HookGitEnv {
+	/// `GIT_DIR`, `.git/worktrees/<name>` in linked worktrees
+	pub git_dir: Option<PathBuf>,
+	/// `GIT_WORK_TREE`, not set for bare repositories
+	pub work_tree: Option<PathBuf>,
+	/// `GIT_PREFIX`: the subdirectory of the work tree git was invoked
+	/// from with a trailing `/`, not set at its top
+	pub prefix: Option<OsString>,
+}
+
+impl HookGitEnv {
+	fn of_repo(repo: &Repository) -> Self {
+		// `repo.path()` ends in a separator
+		let git_dir = repo.path().components().collect::<PathBuf>();
+		let work_tree = repo
+			.workdir()
+			.map(|dir| dir.components().collect::<PathBuf>());
+
+		Self {
+			git_dir: Some(git_dir),
+			work_tree,
+			prefix: None,
+		}
+	}
+
+	/// with `GIT_PREFIX` for git being invoked from `dir`, the current
+	/// directory if `None`
+	fn invoked_from(&self, dir: Option<&Path>) -> Self {
+		let prefix =
+			self.work_tree.as_deref().and_then(|work_tree| {
+				let invoked = dir.map_or_else(
+					|| std::env::current_dir().ok(),
+					|dir| Some(dir.to_path_buf()),
+				)?;
+
+				Self::prefix(work_tree, &invoked)
+			});
+
+		Self {
+			prefix,
+			..self.clone()
+		}
+	}
+
+	/// `invoked` relative to `work_tree` as git puts it in `GIT_PREFIX`
+	fn prefix(work_tree: &Path, invoked: &Path) -> Option<OsString> {
+		let canonical = |path: &Path| {
+			std::fs::canonicalize(path)
+				.unwrap_or_else(|_| path.to_path_buf())
+		};
+
+		let relative = canonical(invoked)
+			.strip_prefix(canonical(work_tree))
+			.ok()?
+			.to_path_buf();
+
+		// joined by `/` on windows as well, like git does
+		let mut prefix = OsString::new();
+		for component in relative.components() {
+			prefix.push(component.as_os_str());
+			prefix.push("/");
+		}
+
+		(!prefix.is_empty()).then_some(prefix)
+	}
+
+	fn vars(&self) -> impl Iterator<Item = (&'static str, &OsStr)> {
+		[
+			("GIT_DIR", self.git_dir.as_deref().map(Path::as_os_str)),
+			(
+				"GIT_WORK_TREE",
+				self.work_tree.as_deref().map(Path::as_os_str),
+			),
+			("GIT_PREFIX", self.prefix.as_deref()),
+		]
+		.into_iter()
+		.filter_map(|(key, value)| Some((key, value?)))
+	}
 }
 
 const CONFIG_HOOKS_PATH: &str = "core.hooksPath";
 const DEFAULT_HOOKS_PATH: &str = "hooks";
 const ENOEXEC: i32 = 8;
 
+/// extensions of hooks made to run natively on windows (e.g. shims
+/// generated by husky or lefthook), looked for in this order if there
+/// is no hook file without extension
+const WINDOWS_HOOK_EXTENSIONS: &[&str] =
+	&["exe", "bat", "cmd", "ps1"];
+
+/// name and value of the variable set by
+/// [`CommandExt::with_windows_path_fix`], the value itself does not matter
+const WINDOWS_PATH_FIX_ENV: (&str, &str) = (
+	"DUMMY_ENV_TO_FIX_WINDOWS_CMD_RUNS",
+	"FixPathHandlingOnWindows",
+);
+
 impl HookPaths {
 	/// `core.hooksPath` always takes precedence.
 	/// If its defined and there is no hook `hook` this is not considered
@@ -39,48 +187,98 @@ impl HookPaths {
 	/// first check standard `.git/hooks` folder and any sub path provided in `other_paths`.
 	///
 	/// Note: we try to model as closely as possible what git shell is doing.
+	/// This includes the directory the hook is run from, see [`HookType::working_dir`].
 	pub fn new(
 		repo: &Repository,
 		other_paths: Option<&[&str]>,
 		hook: &str,
 	) -> Result<Self> {
-		let pwd = repo
-			.workdir()
-			.unwrap_or_else(|| repo.path())
-			.to_path_buf();
+		let pwd = match HookType::from_filename(hook)
+			.map(HookType::working_dir)
+		{
+			Some(HookWorkingDir::GitDir) => repo.path(),
+			_ => repo.workdir().unwrap_or_else(|| repo.path()),
+		}
+		.to_path_buf();
 
 		let git_dir = repo.path().to_path_buf();
-
-		if let Some(config_path) = Self::config_hook_path(repo)? {
-			let hooks_path = PathBuf::from(config_path);
-
-			let hook =
-				Self::expand_path(&hooks_path.join(hook), &pwd)?;
+		let git_env = HookGitEnv::of_repo(repo);
+		let HooksConfig {
+			shell,
+			terminated,
+			prefix,
+			kill_grace,
+			..
+		} = HooksConfig::from_repo(repo)?;
+		let kill_grace = kill_grace.unwrap_or(DEFAULT_KILL_GRACE);
+
+		if let Some(hooks_path) = Self::expanded_config_path(repo)? {
+			let mut path = hooks_path.join(hook);
+			match name_match_of(&path) {
+				NameMatch::Exact => (),
+				name_match => {
+					if let Some(found) =
+						with_hook_extension(&path, hook_extensions())
+					{
+						path = found;
+					} else if let NameMatch::OtherCase(name) =
+						name_match
+					{
+						warn_other_case(
+							&path.with_file_name(name),
+							hook,
+						);
+					}
+				}
+			}
 
 			return Ok(Self {
 				git: git_dir,
-				hook,
+				hook_dir: path.with_file_name(format!("{hook}.d")),
+				hook: path,
 				pwd,
+				shell,
+				terminated,
+				prefix,
+				kill_grace,
+				git_env,
 			});
 		}
 
+		let path = Self::find_hook(repo, other_paths, hook);
+
 		Ok(Self {
 			git: git_dir,
-			hook: Self::find_hook(repo, other_paths, hook),
+			hook_dir: path.with_file_name(format!("{hook}.d")),
+			hook: path,
 			pwd,
+			shell,
+			terminated,
+			prefix,
+			kill_grace,
+			git_env,
 		})
 	}
 
+	/// `core.hooksPath` (if set) expanded by [`Self::expand_path`]
+	pub fn expanded_config_path(
+		repo: &Repository,
+	) -> Result<Option<PathBuf>> {
+		let pwd = repo.workdir().unwrap_or_else(|| repo.path());
+
+		Self::config_hook_path(repo)?
+			.map(|config_path| Self::expand_path(&config_path, pwd))
+			.transpose()
+	}
+
 	/// Expand path according to the rule of githooks and config
 	/// core.hooksPath
-	fn expand_path(path: &Path, pwd: &Path) -> Result<PathBuf> {
-		let hook_expanded = shellexpand::full(
-			path.as_os_str()
-				.to_str()
-				.ok_or(HooksError::PathToString)?,
-		)?;
-		let hook_expanded = PathBuf::from_str(hook_expanded.as_ref())
-			.map_err(|_| HooksError::PathToString)?;
+	///
+	/// only the configured `path` is expanded, `pwd` is joined as is so
+	/// it does not need to be valid UTF-8
+	fn expand_path(path: &str, pwd: &Path) -> Result<PathBuf> {
+		let hook_expanded =
+			PathBuf::from(shellexpand::full(path)?.as_ref());
 
 		// `man git-config`:
 		//
@@ -106,61 +304,199 @@ impl HookPaths {
 		})
 	}
 
+	/// folder hooks are looked up in first: `core.hooksPath` if set,
+	/// the standard `.git/hooks` otherwise
+	pub fn hooks_dir(repo: &Repository) -> Result<PathBuf> {
+		Ok(Self::expanded_config_path(repo)?
+			.unwrap_or_else(|| Self::default_hooks_dir(repo)))
+	}
+
+	/// `hooks` in the common dir: linked worktrees share the hooks of
+	/// the main repository instead of having their own in
+	/// `.git/worktrees/<name>`
+	fn default_hooks_dir(repo: &Repository) -> PathBuf {
+		repo.commondir().join(DEFAULT_HOOKS_PATH)
+	}
+
 	fn config_hook_path(repo: &Repository) -> Result<Option<String>> {
 		Ok(repo.config()?.get_string(CONFIG_HOOKS_PATH).ok())
 	}
 
-	/// check default hook path first and then followed by `other_paths`.
-	/// if no hook is found we return the default hook path
+	/// check default hook path first and then followed by `other_paths`
+	/// (relative to `GIT_DIR`). if no hook is found we return the
+	/// default hook path
 	fn find_hook(
 		repo: &Repository,
 		other_paths: Option<&[&str]>,
 		hook: &str,
 	) -> PathBuf {
-		let mut paths = vec![DEFAULT_HOOKS_PATH.to_string()];
+		let mut paths = vec![Self::default_hooks_dir(repo)];
 		if let Some(others) = other_paths {
 			paths.extend(
-				others
-					.iter()
-					.map(|p| p.trim_end_matches('/').to_string()),
+				others.iter().map(|p| {
+					repo.path().join(p.trim_end_matches('/'))
+				}),
 			);
 		}
 
+		let mut other_case = Vec::new();
+
 		for p in paths {
-			let p = repo.path().to_path_buf().join(p).join(hook);
-			if p.exists() {
-				return p;
+			let p = p.join(hook);
+			match name_match_of(&p) {
+				NameMatch::Exact => return p,
+				name_match => {
+					if let Some(found) =
+						with_hook_extension(&p, hook_extensions())
+					{
+						return found;
+					}
+					if let NameMatch::OtherCase(name) = name_match {
+						other_case.push(p.with_file_name(name));
+					}
+				}
 			}
 		}
 
-		repo.path()
-			.to_path_buf()
-			.join(DEFAULT_HOOKS_PATH)
-			.join(hook)
+		for path in other_case {
+			warn_other_case(&path, hook);
+		}
+
+		Self::default_hooks_dir(repo).join(hook)
 	}
 
 	/// was a hook file found and is it executable
+	///
+	/// the file name has to match the hook name exactly, also on
+	/// case-insensitive file systems (where e.g. `Pre-Commit` would
+	/// otherwise be run as `pre-commit` hook).
 	pub fn found(&self) -> bool {
-		self.hook.exists() && is_executable(&self.hook)
+		self.hook.exists()
+			&& name_match_of(&self.hook) == NameMatch::Exact
+			&& is_executable(&self.hook)
+	}
+
+	/// the executable files in [`Self::hook_dir`] in lexical order,
+	/// anything else in there (like directories) is skipped
+	pub fn dir_hooks(&self) -> Vec<PathBuf> {
+		let Ok(entries) = std::fs::read_dir(&self.hook_dir) else {
+			return Vec::new();
+		};
+
+		let mut hooks = entries
+			.flatten()
+			.map(|entry| entry.path())
+			.filter(|path| path.is_file() && is_executable(path))
+			.collect::<Vec<_>>();
+		hooks.sort();
+
+		hooks
+	}
+
+	/// [`Self::found`] or there is something to run in
+	/// [`Self::hook_dir`]
+	pub fn found_any(&self) -> bool {
+		self.found() || !self.dir_hooks().is_empty()
+	}
+
+	/// runs the hook (if [`Self::found`]) and then every one of
+	/// [`Self::dir_hooks`], all of them with the same `args` and
+	/// `stdin`. `timeout` is for all of them together.
+	///
+	/// the first failure is returned, its `hook` says which script it
+	/// was. hooks able to abort the operation (see
+	/// [`HookType::can_abort`]) stop at it, the others run the remaining
+	/// scripts anyway. if all succeed, `Ok` has the accumulated duration
+	/// and [`Self::hook_dir`] as `hook`, unless just the plain hook ran
+	pub fn run_hooks<I, S>(
+		&self,
+		args: I,
+		stdin: Option<&[u8]>,
+		ctx: &HookContext,
+		timeout: Option<Duration>,
+	) -> Result<HookResult>
+	where
+		I: IntoIterator<Item = S> + Copy,
+		S: AsRef<OsStr>,
+	{
+		let deadline = deadline_from_timeout(timeout);
+		let dir_hooks = self.dir_hooks();
+		let run = |hooks: &Self| {
+			hooks
+				.run_hook_inner(
+					args,
+					stdin,
+					ctx,
+					deadline,
+					false,
+					OutputBuffering::Separate,
+					None,
+				)
+				.map(|output| output.result)
+		};
+
+		if dir_hooks.is_empty() {
+			return run(self);
+		}
+
+		let stop_at_failure = self
+			.hook_dir
+			.file_name()
+			.and_then(OsStr::to_str)
+			.and_then(|name| name.strip_suffix(".d"))
+			.and_then(HookType::from_filename)
+			.map_or(true, HookType::can_abort);
+
+		let mut failure = None;
+		let mut duration = Duration::ZERO;
+		let scripts =
+			self.found().then(|| self.hook.clone()).into_iter();
+
+		for script in scripts.chain(dir_hooks) {
+			let result = run(&Self {
+				hook: script,
+				..self.clone()
+			})?;
+			duration += result.duration().unwrap_or_default();
+
+			if !result.is_ok() {
+				if stop_at_failure {
+					return Ok(result);
+				}
+				failure.get_or_insert(result);
+			}
+		}
+
+		Ok(failure.unwrap_or_else(|| HookResult::Ok {
+			hook: self.hook_dir.clone(),
+			duration,
+		}))
 	}
 
 	/// this function calls hook scripts based on conventions documented here
 	/// see <https://git-scm.com/docs/githooks>
 	#[inline]
-	#[allow(unused)]
-	pub fn run_hook(&self, args: &[&str]) -> Result<HookResult> {
-		self.run_hook_os_str(args)
+	pub fn run_hook(
+		&self,
+		args: &[&str],
+		ctx: &HookContext,
+	) -> Result<HookResult> {
+		self.run_hook_os_str(args, ctx)
 	}
 
 	/// this function calls hook scripts based on conventions documented here
 	/// see <https://git-scm.com/docs/githooks>
 	#[inline]
-	pub fn run_hook_os_str<I, S>(&self, args: I) -> Result<HookResult>
+	pub fn run_hook_os_str<I, S>(
+		&self,
+		args: I,
+		ctx: &HookContext,
+	) -> Result<HookResult>
 	where
 		I: IntoIterator<Item = S> + Copy,
 		S: AsRef<OsStr>,
 	{
-		self.run_hook_with_timeout_os_str(args, None)
+		self.run_hook_with_timeout_os_str(args, ctx, None)
 	}
 
 	/// this function calls hook scripts based on conventions documented here
@@ -175,9 +511,10 @@ impl HookPaths {
 	pub fn run_hook_with_timeout(
 		&self,
 		args: &[&str],
+		ctx: &HookContext,
 		timeout: Option<Duration>,
 	) -> Result<HookResult> {
-		self.run_hook_with_timeout_os_str(args, timeout)
+		self.run_hook_with_timeout_os_str(args, ctx, timeout)
 	}
 
 	/// this function calls hook scripts based on conventions documented here
@@ -191,56 +528,603 @@ impl HookPaths {
 	pub fn run_hook_with_timeout_os_str<I, S>(
 		&self,
 		args: I,
+		ctx: &HookContext,
+		timeout: Option<Duration>,
+	) -> Result<HookResult>
+	where
+		I: IntoIterator<Item = S> + Copy,
+		S: AsRef<OsStr>,
+	{
+		self.run_hook_inner(
+			args,
+			None,
+			ctx,
+			deadline_from_timeout(timeout),
+			false,
+			OutputBuffering::Separate,
+			None,
+		)
+		.map(|output| output.result)
+	}
+
+	/// like [`Self::run_hook_with_timeout_os_str`] but writes `stdin` to
+	/// the standard input of the hook (which is closed afterwards).
+	pub fn run_hook_with_stdin_os_str<I, S>(
+		&self,
+		args: I,
+		stdin: &[u8],
+		ctx: &HookContext,
 		timeout: Option<Duration>,
 	) -> Result<HookResult>
 	where
 		I: IntoIterator<Item = S> + Copy,
 		S: AsRef<OsStr>,
 	{
+		self.run_hook_inner(
+			args,
+			Some(stdin),
+			ctx,
+			deadline_from_timeout(timeout),
+			false,
+			OutputBuffering::Separate,
+			None,
+		)
+		.map(|output| output.result)
+	}
+
+	/// like [`Self::run_hook_with_timeout_os_str`] but additionally opens
+	/// a pipe on fd [`crate::HOOK_PROGRESS_FD`] inside the hook process.
+	/// Everything the hook writes there is returned separately from its
+	/// stdout/stderr.
+	///
+	/// Only supported on unix, elsewhere the returned progress is always empty.
+	pub fn run_hook_with_progress_os_str<I, S>(
+		&self,
+		args: I,
+		ctx: &HookContext,
+		timeout: Option<Duration>,
+	) -> Result<(HookResult, String)>
+	where
+		I: IntoIterator<Item = S> + Copy,
+		S: AsRef<OsStr>,
+	{
+		self.run_hook_inner(
+			args,
+			None,
+			ctx,
+			deadline_from_timeout(timeout),
+			true,
+			OutputBuffering::Separate,
+			None,
+		)
+		.map(|output| (output.result, output.progress))
+	}
+
+	/// like [`Self::run_hook_with_timeout_os_str`] but collects
+	/// stdout/stderr as configured by `buffering`, the [`Transcript`] is
+	/// only returned for [`OutputBuffering::Timestamped`].
+	pub fn run_hook_with_buffering_os_str<I, S>(
+		&self,
+		args: I,
+		ctx: &HookContext,
+		timeout: Option<Duration>,
+		buffering: OutputBuffering,
+	) -> Result<(HookResult, Option<Transcript>)>
+	where
+		I: IntoIterator<Item = S> + Copy,
+		S: AsRef<OsStr>,
+	{
+		self.run_hook_inner(
+			args,
+			None,
+			ctx,
+			deadline_from_timeout(timeout),
+			false,
+			buffering,
+			None,
+		)
+		.map(|output| (output.result, output.transcript))
+	}
+
+	/// like [`Self::run_hook_with_timeout_os_str`] but the hook is killed
+	/// once `deadline` passed, e.g. when several hooks share one deadline.
+	///
+	/// A deadline in the past times out right away (unless the hook
+	/// already exited by then).
+	pub fn run_hook_with_deadline<I, S>(
+		&self,
+		args: I,
+		ctx: &HookContext,
+		deadline: Instant,
+	) -> Result<HookResult>
+	where
+		I: IntoIterator<Item = S> + Copy,
+		S: AsRef<OsStr>,
+	{
+		self.run_hook_inner(
+			args,
+			None,
+			ctx,
+			Some(deadline),
+			false,
+			OutputBuffering::Separate,
+			None,
+		)
+		.map(|output| output.result)
+	}
+
+	/// like [`Self::run_hook_with_timeout`] but calls `on_line` with
+	/// every line of output as soon as the hook wrote it, see
+	/// [`crate::run_hook_streaming`]
+	pub fn run_hook_streaming(
+		&self,
+		args: &[&str],
+		ctx: &HookContext,
+		on_line: impl FnMut(HookOutputLine),
+	) -> Result<HookResult> {
+		self.run_hook_streaming_with_timeout(args, ctx, None, on_line)
+	}
+
+	/// see [`Self::run_hook_streaming`]
+	pub fn run_hook_streaming_with_timeout(
+		&self,
+		args: &[&str],
+		ctx: &HookContext,
+		timeout: Option<Duration>,
+		mut on_line: impl FnMut(HookOutputLine),
+	) -> Result<HookResult> {
+		self.run_hook_inner(
+			args,
+			None,
+			ctx,
+			deadline_from_timeout(timeout),
+			false,
+			OutputBuffering::Timestamped,
+			Some(&mut on_line),
+		)
+		.map(|output| output.result)
+	}
+
+	/// `on_line` is only called with [`OutputBuffering::Timestamped`],
+	/// which reads the pipes while the hook runs
+	#[allow(clippy::too_many_arguments)]
+	fn run_hook_inner<I, S>(
+		&self,
+		args: I,
+		stdin: Option<&[u8]>,
+		ctx: &HookContext,
+		deadline: Option<Instant>,
+		with_progress: bool,
+		buffering: OutputBuffering,
+		on_line: Option<&mut dyn FnMut(HookOutputLine)>,
+	) -> Result<HookRunOutput>
+	where
+		I: IntoIterator<Item = S> + Copy,
+		S: AsRef<OsStr>,
+	{
+		// a directory would be reported as executable but fails
+		// to spawn with a rather confusing error
+		if self.hook.is_dir() {
+			return Err(HooksError::HookIsDirectory {
+				path: self.hook.clone(),
+			});
+		}
+
+		let cancel = ctx.cancel;
+		if cancel.is_some_and(CancellationToken::is_cancelled) {
+			return Ok(HookRunOutput::cancelled(self.hook.clone()));
+		}
+
+		#[cfg(unix)]
+		let progress =
+			with_progress.then(ProgressPipe::open).transpose()?;
+		#[cfg(unix)]
+		let progress_fd = progress.as_ref().map(ProgressPipe::writer);
+		#[cfg(not(unix))]
+		let (progress_fd, _) = (None, with_progress);
+
 		let hook = self.hook.clone();
-		let mut child = spawn_hook_process(&self.pwd, &hook, args)?;
+		let start = Instant::now();
+		let mut child = spawn_hook_process(
+			&self.pwd,
+			&hook,
+			self.shell.as_deref(),
+			&self.prefix,
+			&self.git_env.invoked_from(ctx.invocation_dir),
+			ctx.env,
+			args,
+			progress_fd,
+		)?;
 
-		let output = if timeout.is_none()
-			|| timeout.is_some_and(|t| t.is_zero())
-		{
-			child.wait_with_output()?
-		} else {
-			let timeout = timeout.unwrap();
-			if !timeout_with_quadratic_backoff(timeout, || {
-				Ok(child.try_wait()?.is_some())
-			})? {
-				if cfg!(unix) {
-					match i32::try_from(child.id()) {
-						Ok(pid) => {
-							killpg(Pid::from_raw(pid), SIGKILL)
-								.expect("killpg failed");
-						}
-						Err(_) => child.kill()?,
+		let tree = ProcessTree::track(&child);
+		let active = registry::register(&hook, &self.git, child.id());
+
+		let (lines_tx, lines_rx) =
+			on_line.is_some().then(mpsc::channel).unzip();
+
+		// with the pipes taken `wait_for_hook` collects no output
+		let transcript_reader =
+			(buffering == OutputBuffering::Timestamped).then(|| {
+				TranscriptReader::spawn(
+					start,
+					child.stdout.take(),
+					child.stderr.take(),
+					lines_tx,
+				)
+			});
+
+		// stdin is always closed so that hooks reading it do not block,
+		// it is written from a separate thread as the hook might not
+		// read it before filling up its stdout pipe
+		let stdin_pipe = child.stdin.take();
+		let result = thread::scope(|s| {
+			if let (Some(mut pipe), Some(input)) = (stdin_pipe, stdin)
+			{
+				s.spawn(move || {
+					if let Err(e) = pipe.write_all(input) {
+						// e.g. the hook exited without reading it
+						log::trace!("writing hook stdin failed: {e}");
 					}
-				} else {
-					child.kill()?;
-				}
+				});
+			}
 
-				let mut stdout = String::new();
-				let mut stderr = String::new();
-				if let Some(mut pipe) = child.stdout {
-					pipe.read_to_string(&mut stdout)?;
-				}
-				if let Some(mut pipe) = child.stderr {
-					pipe.read_to_string(&mut stderr)?;
-				}
+			let (terminated, kill_grace) =
+				(self.terminated, self.kill_grace);
+			let (Some(on_line), Some(lines)) = (on_line, lines_rx)
+			else {
+				return wait_for_hook(
+					hook, child, &tree, start, deadline, cancel,
+					terminated, kill_grace,
+				);
+			};
+
+			// the lines are passed on while waiting for the hook,
+			// they stop once both pipes are closed
+			let tree = &tree;
+			let waiting = s.spawn(move || {
+				wait_for_hook(
+					hook, child, tree, start, deadline, cancel,
+					terminated, kill_grace,
+				)
+			});
+			for line in lines {
+				on_line(line);
+			}
+			waiting
+				.join()
+				.unwrap_or_else(|e| std::panic::resume_unwind(e))
+		});
+		let transcript =
+			transcript_reader.map(TranscriptReader::finish);
+		let result = result.map(|result| match &transcript {
+			Some(transcript) => with_output(result, transcript),
+			None => result,
+		});
+		let result = active.finish(result)?;
+
+		#[cfg(unix)]
+		let progress =
+			progress.map(ProgressPipe::finish).unwrap_or_default();
+		#[cfg(not(unix))]
+		let progress = String::new();
+
+		Ok(HookRunOutput {
+			result,
+			progress,
+			transcript,
+		})
+	}
+}
+
+/// everything collected from a single hook run
+struct HookRunOutput {
+	result: HookResult,
+	/// see [`HookPaths::run_hook_with_progress_os_str`]
+	progress: String,
+	/// see [`HookPaths::run_hook_with_buffering_os_str`]
+	transcript: Option<Transcript>,
+}
+
+impl HookRunOutput {
+	/// the hook was cancelled before it was even spawned
+	const fn cancelled(hook: PathBuf) -> Self {
+		Self {
+			result: HookResult::Cancelled {
+				stdout: String::new(),
+				stderr: String::new(),
+				hook,
+			},
+			progress: String::new(),
+			transcript: None,
+		}
+	}
+}
+
+/// fills in stdout/stderr of `result` from `transcript`
+fn with_output(
+	result: HookResult,
+	transcript: &Transcript,
+) -> HookResult {
+	let stdout = transcript.output(OutputStream::Stdout);
+	let stderr = transcript.output(OutputStream::Stderr);
+
+	match result {
+		HookResult::RunNotSuccessful {
+			code,
+			hook,
+			duration,
+			..
+		} => HookResult::RunNotSuccessful {
+			code,
+			stdout,
+			stderr,
+			stdout_bytes: transcript
+				.output_bytes(OutputStream::Stdout),
+			stderr_bytes: transcript
+				.output_bytes(OutputStream::Stderr),
+			hook,
+			duration,
+		},
+		HookResult::TimedOut { hook, .. } => HookResult::TimedOut {
+			stdout,
+			stderr,
+			hook,
+		},
+		HookResult::Cancelled { hook, .. } => HookResult::Cancelled {
+			stdout,
+			stderr,
+			hook,
+		},
+		HookResult::Terminated { signal, hook, .. } => {
+			HookResult::Terminated {
+				signal,
+				likely_oom: likely_oom(signal, &stdout, &stderr),
+				stdout,
+				stderr,
+				hook,
+			}
+		}
+		result => result,
+	}
+}
+
+/// the OOM killer sends `SIGKILL`, a hook has no chance to write
+/// anything about it
+const fn likely_oom(signal: i32, stdout: &str, stderr: &str) -> bool {
+	#[cfg(unix)]
+	let sigkill = signal == SIGKILL as i32;
+	#[cfg(not(unix))]
+	let sigkill = {
+		let _ = signal;
+		false
+	};
+
+	sigkill && stdout.is_empty() && stderr.is_empty()
+}
+
+/// reads stdout and stderr of a hook on separate threads while it runs,
+/// so a hook writing more than fits into the pipe buffers never blocks
+/// on them and what it wrote before being stopped is kept
+struct OutputReader {
+	stdout: Option<thread::JoinHandle<Vec<u8>>>,
+	stderr: Option<thread::JoinHandle<Vec<u8>>>,
+}
+
+impl OutputReader {
+	/// takes the pipes of `child` (if they were not taken before)
+	fn spawn(child: &mut Child) -> Self {
+		Self {
+			stdout: child.stdout.take().map(Self::read),
+			stderr: child.stderr.take().map(Self::read),
+		}
+	}
+
+	fn read(
+		mut pipe: impl Read + Send + 'static,
+	) -> thread::JoinHandle<Vec<u8>> {
+		thread::spawn(move || {
+			let mut output = Vec::new();
+			if let Err(e) = pipe.read_to_end(&mut output) {
+				log::trace!("reading hook output failed: {e}");
+			}
+			output
+		})
+	}
+
+	/// waits for both pipes to be closed, must only be called once the
+	/// hook exited (or was killed)
+	fn finish(self) -> (Vec<u8>, Vec<u8>) {
+		let join = |reader: Option<thread::JoinHandle<Vec<u8>>>| {
+			reader.map_or_else(Vec::new, |reader| {
+				reader.join().unwrap_or_else(|_| {
+					log::error!("hook output reader panicked");
+					Vec::new()
+				})
+			})
+		};
+
+		(join(self.stdout), join(self.stderr))
+	}
+}
 
-				return Ok(HookResult::TimedOut {
+/// `start` is when `child` was spawned, past the `deadline` or once
+/// `cancel` is cancelled it is stopped by [`stop_hook`]
+#[allow(clippy::too_many_arguments)]
+fn wait_for_hook(
+	hook: PathBuf,
+	mut child: Child,
+	tree: &ProcessTree,
+	start: Instant,
+	deadline: Option<Instant>,
+	cancel: Option<&CancellationToken>,
+	terminated: TerminatedHandling,
+	kill_grace: Duration,
+) -> Result<HookResult> {
+	let output = OutputReader::spawn(&mut child);
+
+	if deadline.is_some() || cancel.is_some() {
+		let timeout = deadline.map_or(Duration::MAX, |deadline| {
+			deadline.saturating_duration_since(Instant::now())
+		});
+		let cancelled =
+			|| cancel.is_some_and(CancellationToken::is_cancelled);
+		let exited = timeout_with_quadratic_backoff(timeout, || {
+			Ok(child.try_wait()?.is_some() || cancelled())
+		})? && child.try_wait()?.is_some();
+
+		if !exited {
+			stop_hook(&mut child, tree, kill_grace)?;
+
+			let (stdout, stderr) = output.finish();
+			let stdout =
+				String::from_utf8_lossy(&stdout).into_owned();
+			let stderr =
+				String::from_utf8_lossy(&stderr).into_owned();
+
+			return Ok(if cancelled() {
+				HookResult::Cancelled {
 					hook,
 					stdout,
 					stderr,
-				});
+				}
+			} else {
+				HookResult::TimedOut {
+					hook,
+					stdout,
+					stderr,
+				}
+			});
+		}
+	}
+
+	let status = child.wait()?;
+	let (stdout, stderr) = output.finish();
+	let output = std::process::Output {
+		status,
+		stdout,
+		stderr,
+	};
+
+	Ok(hook_result_from_output(
+		hook,
+		&output,
+		terminated,
+		start.elapsed(),
+	))
+}
+
+/// asks the hook to exit (`SIGTERM`) so it can clean up, it is killed
+/// if it is still running after `grace`. what it started is killed in
+/// any case so that no orphan keeps the output pipes open
+fn stop_hook(
+	child: &mut Child,
+	tree: &ProcessTree,
+	grace: Duration,
+) -> Result<()> {
+	if !grace.is_zero()
+		&& tree.terminate(child)
+		&& timeout_with_quadratic_backoff(grace, || {
+			Ok(child.try_wait()?.is_some())
+		})? {
+		log::trace!("hook exited after SIGTERM");
+	}
+
+	tree.kill(child)?;
+
+	Ok(())
+}
+
+/// a timeout of zero (or too large to be represented) means no timeout
+fn deadline_from_timeout(
+	timeout: Option<Duration>,
+) -> Option<Instant> {
+	timeout
+		.filter(|t| !t.is_zero())
+		.and_then(|t| Instant::now().checked_add(t))
+}
+
+/// pipe a hook can report progress through, see
+/// [`HookPaths::run_hook_with_progress_os_str`].
+///
+/// the read end is drained on a separate thread so a chatty hook can never
+/// block on a full pipe while we wait for it.
+#[cfg(unix)]
+struct ProgressPipe {
+	writer: OwnedFd,
+	reader: thread::JoinHandle<String>,
+}
+
+#[cfg(unix)]
+impl ProgressPipe {
+	fn open() -> Result<Self> {
+		let (read, write) = pipe().map_err(std::io::Error::from)?;
+
+		// keep both ends out of any other process we (or other threads)
+		// spawn, the hook gets its own copy of the write end in `pre_exec`
+		for fd in [&read, &write] {
+			fcntl(fd, FcntlArg::F_SETFD(FdFlag::FD_CLOEXEC))
+				.map_err(std::io::Error::from)?;
+		}
+
+		let reader = thread::spawn(move || {
+			let mut progress = Vec::new();
+			if let Err(e) =
+				std::fs::File::from(read).read_to_end(&mut progress)
+			{
+				log::error!("reading hook progress failed: {e}");
 			}
+			String::from_utf8_lossy(&progress).into_owned()
+		});
 
-			child.wait_with_output()?
-		};
+		Ok(Self {
+			writer: write,
+			reader,
+		})
+	}
 
-		Ok(hook_result_from_output(hook, &output))
+	fn writer(&self) -> RawFd {
+		self.writer.as_raw_fd()
+	}
+
+	/// closes our write end and collects everything the hook wrote,
+	/// must only be called once the hook exited
+	fn finish(self) -> String {
+		drop(self.writer);
+		self.reader.join().unwrap_or_else(|_| {
+			log::error!("hook progress reader panicked");
+			String::new()
+		})
+	}
+}
+
+/// makes `fd` available as [`crate::HOOK_PROGRESS_FD`] in the process
+/// spawned by `command`
+#[cfg(unix)]
+#[allow(unsafe_code)]
+fn map_progress_fd(command: &mut Command, fd: RawFd) {
+	use nix::libc;
+
+	let target = crate::HOOK_PROGRESS_FD;
+
+	// SAFETY: the closure runs in the forked child right before `exec`,
+	// it only calls `dup2`/`fcntl` which are async-signal-safe and does
+	// not allocate.
+	unsafe {
+		command.pre_exec(move || {
+			let res = if fd == target {
+				// already in place, only needs to survive the `exec`
+				libc::fcntl(fd, libc::F_SETFD, 0)
+			} else {
+				// the duplicate does not inherit `FD_CLOEXEC`
+				libc::dup2(fd, target)
+			};
+
+			if res == -1 {
+				Err(std::io::Error::last_os_error())
+			} else {
+				Ok(())
+			}
+		});
 	}
 }
 
@@ -327,28 +1211,58 @@ where
 fn hook_result_from_output(
 	hook: PathBuf,
 	output: &std::process::Output,
+	terminated: TerminatedHandling,
+	duration: Duration,
 ) -> HookResult {
 	if output.status.success() {
-		HookResult::Ok { hook }
+		HookResult::Ok { hook, duration }
 	} else {
 		let stderr =
 			String::from_utf8_lossy(&output.stderr).to_string();
 		let stdout =
 			String::from_utf8_lossy(&output.stdout).to_string();
 
+		// hooks we kill ourselves are reported as `TimedOut` already
+		#[cfg(unix)]
+		if let (TerminatedHandling::Report, Some(signal)) = (
+			terminated,
+			std::os::unix::process::ExitStatusExt::signal(
+				&output.status,
+			),
+		) {
+			return HookResult::Terminated {
+				signal,
+				likely_oom: likely_oom(signal, &stdout, &stderr),
+				stdout,
+				stderr,
+				hook,
+			};
+		}
+		#[cfg(not(unix))]
+		let _ = terminated;
+
 		HookResult::RunNotSuccessful {
 			code: output.status.code(),
 			stdout,
 			stderr,
+			stdout_bytes: output.stdout.clone(),
+			stderr_bytes: output.stderr.clone(),
 			hook,
+			duration,
 		}
 	}
 }
 
+#[allow(clippy::too_many_arguments)]
 fn spawn_hook_process<I, S>(
 	directory: &PathBuf,
 	hook: &PathBuf,
+	shell: Option<&Path>,
+	prefix: &[String],
+	git_env: &HookGitEnv,
+	env: &[(&str, &str)],
 	args: I,
+	progress_fd: Option<RawFd>,
 ) -> Result<Child>
 where
 	I: IntoIterator<Item = S> + Copy,
@@ -357,22 +1271,36 @@ where
 	log::trace!("run hook '{:?}' in '{:?}'", hook, directory);
 
 	let spawn_command = |command: &mut Command| {
-		if cfg!(unix) {
-			command.process_group(0);
+		ProcessTree::prepare(command);
+
+		#[cfg(unix)]
+		if let Some(fd) = progress_fd {
+			map_progress_fd(command, fd);
 		}
 
 		command
 			.args(args)
 			.current_dir(directory)
+			.envs(git_env.vars())
+			.envs(env.iter().copied())
 			.with_no_window()
+			.with_windows_path_fix()
 			.stdout(Stdio::piped())
 			.stderr(Stdio::piped())
 			.stdin(Stdio::piped())
 			.spawn()
 	};
 
-	let child = if cfg!(windows) {
-		// execute hook in shell
+	if let Some(command) =
+		cfg!(windows).then(|| interpreter_command(hook)).flatten()
+	{
+		// `.bat`, `.cmd` and `.ps1` hooks do not need a shell
+		return Ok(spawn_command(&mut with_prefix(command, prefix))?);
+	}
+
+	let child = if cfg!(windows) && !is_native_executable(hook) {
+		// scripts can only be run by a shell on windows, their shebang
+		// is not understood there
 		let command = {
 			// SEE: https://pubs.opengroup.org/onlinepubs/9699919799/utilities/V3_chap02.html#tag_18_02_02
 			// Enclosing characters in single-quotes ( '' ) shall preserve the literal value of each character within the single-quotes.
@@ -408,12 +1336,27 @@ where
 
 			os_str
 		};
-		spawn_command(sh_command().arg("-c").arg(command).arg(hook))
+		let mut command_in_shell = sh_command(shell);
+		command_in_shell.arg("-c").arg(command).arg(hook);
+
+		spawn_command(&mut with_prefix(command_in_shell, prefix))
+	} else if !prefix.is_empty() {
+		// the prefix executes the hook, so a failure to do so can not
+		// be detected: go through the shell which falls back to running
+		// hooks that cannot be executed directly as a shell script itself
+		let mut command_in_shell = sh_command(shell);
+		command_in_shell.arg("-c").arg(r#""$0" "$@""#).arg(hook);
+
+		spawn_command(&mut with_prefix(command_in_shell, prefix))
 	} else {
-		// execute hook directly
+		// execute hook directly, its shebang picks the interpreter
 		match spawn_command(&mut Command::new(hook)) {
-			Err(err) if err.raw_os_error() == Some(ENOEXEC) => {
-				spawn_command(sh_command().arg(hook))
+			Err(err)
+				if cfg!(windows)
+					|| err.raw_os_error() == Some(ENOEXEC) =>
+			{
+				log::trace!("running {:?} in shell: {}", hook, err);
+				spawn_command(sh_command(shell).arg(hook))
 			}
 			result => result,
 		}
@@ -422,18 +1365,144 @@ where
 	Ok(child)
 }
 
-fn sh_command() -> Command {
-	let mut command = Command::new(gix_path::env::shell());
+/// how the name of a hook file matches the entries of its folder
+#[derive(Debug, PartialEq, Eq)]
+enum NameMatch {
+	Exact,
+	/// only an entry differing in case, e.g. `Pre-Commit`
+	OtherCase(OsString),
+	None,
+}
+
+/// `name` looked up in the file names of a folder, hook names are matched
+/// case sensitively no matter the file system
+fn name_match<I>(entries: I, name: &OsStr) -> NameMatch
+where
+	I: IntoIterator<Item = OsString>,
+{
+	let mut other_case = None;
+
+	for entry in entries {
+		if entry == name {
+			return NameMatch::Exact;
+		}
+
+		let same_ignoring_case =
+			entry.to_str().zip(name.to_str()).is_some_and(
+				|(entry, name)| entry.eq_ignore_ascii_case(name),
+			);
+
+		if same_ignoring_case {
+			other_case = Some(entry);
+		}
+	}
+
+	other_case.map_or(NameMatch::None, NameMatch::OtherCase)
+}
+
+/// [`name_match`] of `path` in its folder, `exists` can not be relied on
+/// as it ignores case on case-insensitive file systems
+fn name_match_of(path: &Path) -> NameMatch {
+	let (Some(dir), Some(name)) = (path.parent(), path.file_name())
+	else {
+		return NameMatch::None;
+	};
+
+	let Ok(entries) = std::fs::read_dir(dir) else {
+		return NameMatch::None;
+	};
+
+	name_match(
+		entries.filter_map(|entry| Some(entry.ok()?.file_name())),
+		name,
+	)
+}
+
+fn warn_other_case(path: &Path, hook: &str) {
+	log::warn!(
+		"ignoring {:?}: hook names are case sensitive, rename it to '{hook}' to run it as '{hook}' hook",
+		path
+	);
+}
 
+/// [`WINDOWS_HOOK_EXTENSIONS`] on windows, hooks have no extension
+/// anywhere else
+const fn hook_extensions() -> &'static [&'static str] {
 	if cfg!(windows) {
-		// This call forces Command to handle the Path environment correctly on windows,
-		// the specific env set here does not matter
-		// see https://github.com/rust-lang/rust/issues/37519
-		command.env(
-			"DUMMY_ENV_TO_FIX_WINDOWS_CMD_RUNS",
-			"FixPathHandlingOnWindows",
-		);
+		WINDOWS_HOOK_EXTENSIONS
+	} else {
+		&[]
+	}
+}
+
+/// the first file at `path` with one of the `extensions` added
+fn with_hook_extension(
+	path: &Path,
+	extensions: &[&str],
+) -> Option<PathBuf> {
+	extensions.iter().find_map(|extension| {
+		let mut name = path.as_os_str().to_owned();
+		name.push(".");
+		name.push(extension);
+		let path = PathBuf::from(name);
+
+		(name_match_of(&path) == NameMatch::Exact).then_some(path)
+	})
+}
 
+/// the program running a windows script `hook` (and its arguments), `None`
+/// for anything else
+fn interpreter_command(hook: &Path) -> Option<Command> {
+	let extension = hook.extension()?.to_str()?.to_ascii_lowercase();
+
+	let mut command = match extension.as_str() {
+		"bat" | "cmd" => {
+			let mut command = Command::new("cmd");
+			command.arg("/C");
+			command
+		}
+		"ps1" => {
+			let mut command = Command::new("powershell");
+			command.args([
+				"-NoProfile",
+				"-ExecutionPolicy",
+				"Bypass",
+				"-File",
+			]);
+			command
+		}
+		_ => return None,
+	};
+	command.arg(hook);
+
+	Some(command)
+}
+
+/// `command` run by the `prefix` program, see [`HooksConfig::prefix`]
+fn with_prefix(command: Command, prefix: &[String]) -> Command {
+	let Some((program, prefix_args)) = prefix.split_first() else {
+		return command;
+	};
+
+	let mut prefixed = Command::new(program);
+	prefixed
+		.args(prefix_args)
+		.arg(command.get_program())
+		.args(command.get_args());
+
+	prefixed
+}
+
+/// `shell` or the shell git uses, which is only looked up once per
+/// process (on windows that means searching for the one of git for
+/// windows)
+fn sh_command(shell: Option<&Path>) -> Command {
+	let mut command = shell.map_or_else(
+		|| Command::new(gix_path::env::shell()),
+		Command::new,
+	);
+
+	if cfg!(windows) {
 		// Use -l to avoid "command not found"
 		command.arg("-l");
 	}
@@ -441,6 +1510,29 @@ fn sh_command() -> Command {
 	command
 }
 
+/// whether `path` starts like a binary the OS can start by itself
+/// (PE, ELF or Mach-O) as opposed to a script
+fn is_native_executable(path: &Path) -> bool {
+	const MAGIC: &[&[u8]] = &[
+		b"MZ",
+		b"\x7fELF",
+		&[0xfe, 0xed, 0xfa, 0xce],
+		&[0xfe, 0xed, 0xfa, 0xcf],
+		&[0xce, 0xfa, 0xed, 0xfe],
+		&[0xcf, 0xfa, 0xed, 0xfe],
+		&[0xca, 0xfe, 0xba, 0xbe],
+	];
+
+	let mut start = [0_u8; 4];
+	let Ok(read) = std::fs::File::open(path)
+		.and_then(|mut file| file.read(&mut start))
+	else {
+		return false;
+	};
+
+	MAGIC.iter().any(|magic| start[..read].starts_with(magic))
+}
+
 #[cfg(unix)]
 fn is_executable(path: &Path) -> bool {
 	use std::os::unix::fs::PermissionsExt;
@@ -474,9 +1566,13 @@ trait CommandExt {
 	/// or if it used with either `CREATE_NEW_CONSOLE` or `DETACHED_PROCESS`.
 	///
 	/// See: <https://learn.microsoft.com/en-us/windows/win32/procthread/process-creation-flags>
+	#[cfg(windows)]
 	const CREATE_NO_WINDOW: u32 = 0x0800_0000;
 
 	fn with_no_window(&mut self) -> &mut Self;
+
+	/// see [`WINDOWS_PATH_FIX_ENV`]
+	fn with_windows_path_fix(&mut self) -> &mut Self;
 }
 
 impl CommandExt for Command {
@@ -494,6 +1590,26 @@ impl CommandExt for Command {
 
 		self
 	}
+
+	/// On Windows, `Command` only resolves the program using the `PATH`
+	/// of the child environment if that environment was modified in any
+	/// way, otherwise the parent `PATH` is used and e.g. `sh` may not be
+	/// found. Setting a dummy variable forces the correct behavior.
+	///
+	/// This has to stay the last modification of the environment before
+	/// spawning, so that clearing or replacing the environment
+	/// beforehand does not drop it.
+	///
+	/// See: <https://github.com/rust-lang/rust/issues/37519>
+	#[inline]
+	fn with_windows_path_fix(&mut self) -> &mut Self {
+		if cfg!(windows) {
+			let (key, value) = WINDOWS_PATH_FIX_ENV;
+			self.env(key, value);
+		}
+
+		self
+	}
 }
 
 #[cfg(test)]
@@ -502,11 +1618,154 @@ mod test {
 	use pretty_assertions::assert_eq;
 	use std::path::Path;
 
+	#[test]
+	#[cfg(windows)]
+	fn test_windows_path_fix_survives_cleared_env() {
+		let (key, value) = WINDOWS_PATH_FIX_ENV;
+
+		let mut command = Command::new("sh");
+		command.env_clear().with_windows_path_fix();
+
+		assert!(command
+			.get_envs()
+			.any(|env| env
+				== (OsStr::new(key), Some(OsStr::new(value)))));
+	}
+
+	#[test]
+	fn test_default_shell_is_looked_up_once() {
+		// the lookup is memoized by `gix_path`, every call returns the
+		// very same path
+		assert!(std::ptr::eq(
+			gix_path::env::shell(),
+			gix_path::env::shell()
+		));
+		assert_eq!(
+			sh_command(None).get_program(),
+			gix_path::env::shell()
+		);
+		assert_eq!(
+			sh_command(Some(Path::new("/bin/bash"))).get_program(),
+			OsStr::new("/bin/bash")
+		);
+	}
+
+	#[test]
+	fn test_is_native_executable() {
+		let td = tempfile::TempDir::new().unwrap();
+		let script = td.path().join("script");
+		std::fs::write(&script, "#!/bin/sh\nexit 0\n").unwrap();
+
+		assert!(is_native_executable(
+			&std::env::current_exe().unwrap()
+		));
+		assert!(!is_native_executable(&script));
+		assert!(!is_native_executable(&td.path().join("missing")));
+	}
+
+	#[test]
+	#[cfg(not(windows))]
+	fn test_windows_path_fix_is_noop() {
+		let mut command = Command::new("sh");
+		command.with_windows_path_fix();
+
+		assert_eq!(command.get_envs().count(), 0);
+	}
+
+	#[test]
+	fn test_name_match_case_insensitive_listing() {
+		let names = |names: &[&str]| {
+			names.iter().map(OsString::from).collect::<Vec<_>>()
+		};
+		let hook = OsStr::new("pre-commit");
+
+		// what a case-insensitive file system might hold: `exists`
+		// would be true for `pre-commit` in all of these
+		assert_eq!(
+			name_match(names(&["Pre-Commit", "post-commit"]), hook),
+			NameMatch::OtherCase(OsString::from("Pre-Commit"))
+		);
+		assert_eq!(
+			name_match(names(&["PRE-COMMIT", "pre-commit"]), hook),
+			NameMatch::Exact
+		);
+		assert_eq!(
+			name_match(names(&["pre-commit.sample"]), hook),
+			NameMatch::None
+		);
+	}
+
+	#[test]
+	fn test_with_hook_extension() {
+		let td = tempfile::TempDir::new().unwrap();
+		let hook = td.path().join("pre-commit");
+
+		assert_eq!(
+			with_hook_extension(&hook, WINDOWS_HOOK_EXTENSIONS),
+			None
+		);
+
+		std::fs::write(td.path().join("pre-commit.ps1"), "").unwrap();
+		std::fs::write(td.path().join("pre-commit.cmd"), "").unwrap();
+		std::fs::write(td.path().join("pre-commit.sample"), "")
+			.unwrap();
+
+		assert_eq!(
+			with_hook_extension(&hook, WINDOWS_HOOK_EXTENSIONS),
+			Some(td.path().join("pre-commit.cmd"))
+		);
+		assert_eq!(with_hook_extension(&hook, &[]), None);
+	}
+
+	#[test]
+	fn test_interpreter_command() {
+		let args = |command: &Command| {
+			std::iter::once(command.get_program())
+				.chain(command.get_args())
+				.map(|arg| arg.to_string_lossy().into_owned())
+				.collect::<Vec<_>>()
+		};
+
+		assert_eq!(
+			args(
+				&interpreter_command(Path::new("pre-commit.CMD"))
+					.unwrap()
+			),
+			vec!["cmd", "/C", "pre-commit.CMD"]
+		);
+		assert_eq!(
+			args(
+				&interpreter_command(Path::new("pre-commit.bat"))
+					.unwrap()
+			),
+			vec!["cmd", "/C", "pre-commit.bat"]
+		);
+		assert_eq!(
+			args(
+				&interpreter_command(Path::new("pre-commit.ps1"))
+					.unwrap()
+			),
+			vec![
+				"powershell",
+				"-NoProfile",
+				"-ExecutionPolicy",
+				"Bypass",
+				"-File",
+				"pre-commit.ps1"
+			]
+		);
+		assert!(
+			interpreter_command(Path::new("pre-commit")).is_none()
+		);
+		assert!(interpreter_command(Path::new("pre-commit.exe"))
+			.is_none());
+	}
+
 	#[test]
 	fn test_hookspath_relative() {
 		assert_eq!(
 			HookPaths::expand_path(
-				Path::new("pre-commit"),
+				"pre-commit",
 				Path::new("example_git_root"),
 			)
 			.unwrap(),
@@ -520,7 +1779,7 @@ mod test {
 			std::env::current_dir().unwrap().join("pre-commit");
 		assert_eq!(
 			HookPaths::expand_path(
-				&absolute_hook,
+				absolute_hook.to_str().unwrap(),
 				Path::new("example_git_root"),
 			)
 			.unwrap(),
@@ -574,7 +1833,25 @@ mod test {
 		let _ = timeout_with_quadratic_backoff(TIMEOUT, || Ok(false));
 		let elapsed = start.elapsed();
 
-		let overhead = (elapsed - TIMEOUT).as_micros();
+		let overhead =
+			elapsed.checked_sub(TIMEOUT).unwrap().as_micros();
 		assert!(overhead < TARGET_ATTEMPTS * 15);
 	}
+
+	#[test]
+	fn test_git_prefix() {
+		let dir = tempfile::tempdir().unwrap();
+		let work_tree = dir.path();
+		std::fs::create_dir_all(work_tree.join("a/b")).unwrap();
+
+		assert_eq!(
+			HookGitEnv::prefix(work_tree, &work_tree.join("a/b")),
+			Some(OsString::from("a/b/"))
+		);
+		assert_eq!(HookGitEnv::prefix(work_tree, work_tree), None);
+		assert_eq!(
+			HookGitEnv::prefix(&work_tree.join("a"), work_tree),
+			None
+		);
+	}
 }
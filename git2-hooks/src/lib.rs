@@ -7,9 +7,17 @@
 //!
 //! most basic hook is: [`hooks_pre_commit`]. see also other `hooks_*` functions.
 //!
+//! hook names are matched case sensitively, also on case-insensitive file
+//! systems: a `Pre-Commit` file is never run as `pre-commit` hook (a warning
+//! is logged if it is the only candidate).
+//!
 //! [`create_hook`] is useful to create git hooks from code (unittest make heavy usage of it)
+//!
+//! [`HooksConfig`] bundles the gitui specific hook settings (`[gitui "hooks"]` git config section)
+//!
+//! [`active_hooks`] and [`recent_hooks`] list the hooks run by this process (e.g. for a status bar)
 
-#![forbid(unsafe_code)]
+#![deny(unsafe_code)]
 #![deny(
 	unused_imports,
 	unused_must_use,
@@ -24,28 +32,71 @@
 	clippy::module_name_repetitions
 )]
 
+mod cancel;
+mod config;
 mod error;
+mod hook_type;
 mod hookspath;
+mod process_tree;
+mod registry;
+mod transcript;
 
 use std::{
+	ffi::OsStr,
 	fs::File,
-	io::{Read, Write},
+	io::{ErrorKind, Read, Write},
 	path::{Path, PathBuf},
-	time::Duration,
+	sync::atomic::{AtomicUsize, Ordering},
+	time::{Duration, Instant},
 };
 
+pub use cancel::CancellationToken;
+pub use config::{
+	HooksConfig, TerminatedHandling, TimeoutSource,
+	DEFAULT_KILL_GRACE,
+};
 pub use error::HooksError;
 use error::Result;
-use hookspath::HookPaths;
+pub use hook_type::{HookType, HookWorkingDir};
+pub use hookspath::HookContext;
+use hookspath::{HookGitEnv, HookPaths};
+pub use registry::{
+	active_hooks, recent_hooks, FinishedHookRun, HookOutcome, HookRun,
+};
+pub use transcript::{
+	HookOutputLine, OutputBuffering, OutputChunk, OutputStream,
+	Transcript,
+};
 
-use git2::Repository;
+use git2::{Oid, Repository};
 
 pub const HOOK_POST_COMMIT: &str = "post-commit";
 pub const HOOK_PRE_COMMIT: &str = "pre-commit";
 pub const HOOK_COMMIT_MSG: &str = "commit-msg";
 pub const HOOK_PREPARE_COMMIT_MSG: &str = "prepare-commit-msg";
+pub const HOOK_APPLYPATCH_MSG: &str = "applypatch-msg";
+pub const HOOK_PRE_APPLYPATCH: &str = "pre-applypatch";
+pub const HOOK_POST_APPLYPATCH: &str = "post-applypatch";
+pub const HOOK_PRE_REBASE: &str = "pre-rebase";
+pub const HOOK_POST_CHECKOUT: &str = "post-checkout";
+pub const HOOK_POST_MERGE: &str = "post-merge";
+pub const HOOK_PRE_PUSH: &str = "pre-push";
+pub const HOOK_PRE_RECEIVE: &str = "pre-receive";
+pub const HOOK_UPDATE: &str = "update";
+pub const HOOK_PROC_RECEIVE: &str = "proc-receive";
+pub const HOOK_POST_RECEIVE: &str = "post-receive";
+pub const HOOK_POST_UPDATE: &str = "post-update";
+pub const HOOK_POST_REWRITE: &str = "post-rewrite";
+pub const HOOK_REFERENCE_TRANSACTION: &str = "reference-transaction";
+
+/// file descriptor a hook can write progress to when run through
+/// [`run_hook_with_progress`] (e.g. `echo "linting" >&3`)
+pub const HOOK_PROGRESS_FD: i32 = 3;
 
 const HOOK_COMMIT_MSG_TEMP_FILE: &str = "COMMIT_EDITMSG";
+const HOOK_APPLYPATCH_MSG_TEMP_FILE: &str = "APPLYPATCH_EDITMSG";
+const HOOK_COMMIT_MSG_PREVIEW_TEMP_FILE: &str =
+	"GITUI_PREVIEW_EDITMSG";
 
 #[derive(Debug, PartialEq, Eq)]
 pub enum HookResult {
@@ -55,17 +106,25 @@ pub enum HookResult {
 	Ok {
 		/// path of the hook that was run
 		hook: PathBuf,
+		/// wall-clock time from spawning the hook until it exited
+		duration: Duration,
 	},
 	/// Hook executed and returned an error code
 	RunNotSuccessful {
 		/// exit code as reported back from process calling the hook
 		code: Option<i32>,
-		/// stdout output emitted by hook
+		/// stdout output emitted by hook, invalid UTF-8 is replaced
 		stdout: String,
-		/// stderr output emitted by hook
+		/// stderr output emitted by hook, invalid UTF-8 is replaced
 		stderr: String,
+		/// `stdout` exactly as the hook wrote it
+		stdout_bytes: Vec<u8>,
+		/// `stderr` exactly as the hook wrote it
+		stderr_bytes: Vec<u8>,
 		/// path of the hook that was run
 		hook: PathBuf,
+		/// wall-clock time from spawning the hook until it exited
+		duration: Duration,
 	},
 	/// Hook took too long to execute and was killed
 	TimedOut {
@@ -76,6 +135,31 @@ pub enum HookResult {
 		/// path of the hook that was run
 		hook: PathBuf,
 	},
+	/// Hook was killed (or not even started) because its
+	/// [`CancellationToken`] was cancelled, see [`HookContext::cancel`]
+	Cancelled {
+		/// stdout output emitted by hook
+		stdout: String,
+		/// stderr output emitted by hook
+		stderr: String,
+		/// path of the hook that was run
+		hook: PathBuf,
+	},
+	/// Hook was killed by a signal not sent by us (unix only), see
+	/// [`TerminatedHandling`]
+	Terminated {
+		/// number of the signal
+		signal: i32,
+		/// killed by `SIGKILL` without any output, which is what the
+		/// OOM killer of the OS does to a hook running out of memory
+		likely_oom: bool,
+		/// stdout output emitted by hook
+		stdout: String,
+		/// stderr output emitted by hook
+		stderr: String,
+		/// path of the hook that was run
+		hook: PathBuf,
+	},
 }
 
 impl HookResult {
@@ -84,6 +168,16 @@ impl HookResult {
 		matches!(self, Self::Ok { .. })
 	}
 
+	/// how long the hook ran, `None` if it was not run or did not
+	/// exit on its own
+	pub const fn duration(&self) -> Option<Duration> {
+		match self {
+			Self::Ok { duration, .. }
+			| Self::RunNotSuccessful { duration, .. } => Some(*duration),
+			_ => None,
+		}
+	}
+
 	/// helper to check if result was run and not rejected
 	pub const fn is_not_successful(&self) -> bool {
 		matches!(self, Self::RunNotSuccessful { .. })
@@ -93,6 +187,59 @@ impl HookResult {
 	pub const fn is_timeout(&self) -> bool {
 		matches!(self, Self::TimedOut { .. })
 	}
+
+	/// helper to check if the hook was cancelled
+	pub const fn is_cancelled(&self) -> bool {
+		matches!(self, Self::Cancelled { .. })
+	}
+
+	/// helper to check if the hook was killed by someone else
+	pub const fn is_terminated(&self) -> bool {
+		matches!(self, Self::Terminated { .. })
+	}
+}
+
+/// result of [`run_hook_with_progress`]
+#[derive(Debug, PartialEq, Eq)]
+pub struct HookProgressResult {
+	/// how the hook run ended
+	pub result: HookResult,
+	/// everything the hook wrote to [`HOOK_PROGRESS_FD`]
+	pub progress: String,
+}
+
+/// result of [`run_hook_with_buffering`]
+#[derive(Debug, PartialEq, Eq)]
+pub struct HookOutputResult {
+	/// how the hook run ended
+	pub result: HookResult,
+	/// only recorded with [`OutputBuffering::Timestamped`]
+	pub transcript: Option<Transcript>,
+}
+
+/// a [`HookResult`] together with how long the hook took to run
+#[derive(Debug, PartialEq, Eq)]
+pub struct TimedHookResult {
+	/// how the hook run ended
+	pub result: HookResult,
+	/// wall clock time from spawning the hook until it exited
+	pub duration: Duration,
+}
+
+/// result of [`hooks_warmup`]
+#[derive(Debug, PartialEq, Eq)]
+pub struct HookWarmupResult {
+	/// first run, pays for shell/interpreter startup and cold caches
+	pub cold: TimedHookResult,
+	/// second run right after the first one
+	pub warm: TimedHookResult,
+}
+
+impl HookWarmupResult {
+	/// how much longer the cold run took than the warm one
+	pub const fn cold_start_penalty(&self) -> Duration {
+		self.cold.duration.saturating_sub(self.warm.duration)
+	}
 }
 
 /// helper method to create git hooks programmatically (heavy used in unittests)
@@ -127,6 +274,63 @@ fn create_hook_in_path(path: &Path, hook_script: &[u8]) {
 	}
 }
 
+/// the folder `core.hooksPath` points to after expanding it the same way
+/// as it is when looking up hooks (`~`, environment variables and paths
+/// relative to the directory hooks run in).
+///
+/// returns `None` if `core.hooksPath` is not set.
+pub fn expanded_hooks_path(
+	repo: &Repository,
+) -> Result<Option<PathBuf>> {
+	HookPaths::expanded_config_path(repo)
+}
+
+/// makes every hook in the active hooks folder (see
+/// [`expanded_hooks_path`]) executable that is not yet, hooks without the
+/// executable bit are silently skipped by git (and gitui).
+///
+/// `*.sample` files and folders are left alone. returns the files whose
+/// permissions were changed (to `0o755`), always empty on windows where
+/// every file is considered executable.
+pub fn make_hooks_executable(
+	repo: &Repository,
+) -> Result<Vec<PathBuf>> {
+	let dir = HookPaths::hooks_dir(repo)?;
+	if !dir.is_dir() {
+		return Ok(Vec::new());
+	}
+
+	#[cfg_attr(not(unix), allow(unused_mut))]
+	let mut changed = Vec::new();
+
+	for entry in std::fs::read_dir(&dir)? {
+		let path = entry?.path();
+
+		let is_sample =
+			path.extension().is_some_and(|ext| ext == "sample");
+		if is_sample || !path.is_file() {
+			continue;
+		}
+
+		#[cfg(unix)]
+		{
+			use std::os::unix::fs::PermissionsExt;
+
+			if path.metadata()?.permissions().mode() & 0o111 == 0 {
+				std::fs::set_permissions(
+					&path,
+					std::fs::Permissions::from_mode(0o755),
+				)?;
+				changed.push(path);
+			}
+		}
+	}
+
+	changed.sort();
+
+	Ok(changed)
+}
+
 macro_rules! find_hook {
 	($repo:expr, $other_paths:expr, $hook_type:expr) => {{
 		let hook = HookPaths::new($repo, $other_paths, $hook_type)?;
@@ -137,18 +341,60 @@ macro_rules! find_hook {
 	}};
 }
 
+/// for hooks git calls without arguments
+const NO_ARGS: [&str; 0] = [];
+
+/// like [`find_hook`] but also returns the hook if only its `<hook>.d`
+/// directory has something to run, see [`HookPaths::run_hooks`]
+macro_rules! find_hooks {
+	($repo:expr, $other_paths:expr, $hook_type:expr) => {{
+		let hook = HookPaths::new($repo, $other_paths, $hook_type)?;
+		if !hook.found_any() {
+			return Ok(HookResult::NoHookFound);
+		}
+		hook
+	}};
+}
+
+/// reads back a message file a hook might have altered.
+///
+/// messages are always written as UTF-8 (`msg` is a `String`), but
+/// hooks may write arbitrary bytes. Those are not rejected: invalid
+/// sequences are replaced with `U+FFFD` so that the commit still gets
+/// the rest of the message and gitui shows what will be committed.
+fn read_msg_file(path: &Path) -> Result<String> {
+	let mut bytes = Vec::new();
+	File::open(path)?.read_to_end(&mut bytes)?;
+
+	Ok(String::from_utf8(bytes).unwrap_or_else(|e| {
+		log::warn!(
+			"hook wrote invalid utf-8 to {}: {e}",
+			path.display()
+		);
+		String::from_utf8_lossy(e.as_bytes()).into_owned()
+	}))
+}
+
 /// Git hook: `commit_msg`
 ///
 /// This hook is documented here <https://git-scm.com/docs/githooks#_commit_msg>.
 /// We use the same convention as other git clients to create a temp file containing
 /// the commit message at `<.git|hooksPath>/COMMIT_EDITMSG` and pass it's relative path as the only
 /// parameter to the hook script.
+///
+/// Invalid UTF-8 the hook writes to the message file is replaced with `U+FFFD`.
 pub fn hooks_commit_msg(
 	repo: &Repository,
 	other_paths: Option<&[&str]>,
 	msg: &mut String,
 ) -> Result<HookResult> {
-	hooks_commit_msg_with_timeout(repo, other_paths, msg, None)
+	hooks_commit_msg_with_timeout(
+		repo,
+		other_paths,
+		msg,
+		&HookContext::default(),
+		None,
+	)
 }
 
 /// Git hook: `commit_msg`
@@ -158,40 +404,342 @@ pub fn hooks_commit_msg_with_timeout(
 	repo: &Repository,
 	other_paths: Option<&[&str]>,
 	msg: &mut String,
+	ctx: &HookContext,
 	timeout: Option<Duration>,
 ) -> Result<HookResult> {
-	let hook = find_hook!(repo, other_paths, HOOK_COMMIT_MSG);
+	let hook = find_hooks!(repo, other_paths, HOOK_COMMIT_MSG);
 
 	let temp_file = hook.git.join(HOOK_COMMIT_MSG_TEMP_FILE);
 	File::create(&temp_file)?.write_all(msg.as_bytes())?;
 
-	let res =
-		hook.run_hook_with_timeout_os_str(&temp_file, timeout)?;
+	let res = hook.run_hooks([&temp_file], None, ctx, timeout)?;
 
 	// load possibly altered msg
-	msg.clear();
-	File::open(temp_file)?.read_to_string(msg)?;
+	*msg = read_msg_file(&temp_file)?;
 
 	Ok(res)
 }
 
+/// Git hook: `commit_msg` (preview)
+///
+/// Runs the `commit_msg` hook against `msg` to see whether it would be accepted,
+/// without touching `COMMIT_EDITMSG`: the message is written to a separate temp file
+/// that is removed afterwards and any changes the hook applies to it are discarded.
+///
+/// Meant to be run repeatedly while the message is still being edited,
+/// the actual commit should still go through [`hooks_commit_msg`].
+pub fn hooks_commit_msg_preview(
+	repo: &Repository,
+	other_paths: Option<&[&str]>,
+	msg: &str,
+	ctx: &HookContext,
+	timeout: Option<Duration>,
+) -> Result<HookResult> {
+	let hook = find_hooks!(repo, other_paths, HOOK_COMMIT_MSG);
+
+	let temp_file = hook.git.join(HOOK_COMMIT_MSG_PREVIEW_TEMP_FILE);
+	File::create(&temp_file)?.write_all(msg.as_bytes())?;
+
+	let res = hook.run_hooks([&temp_file], None, ctx, timeout);
+
+	if let Err(e) = std::fs::remove_file(&temp_file) {
+		log::warn!("failed to remove commit-msg preview file: {e}");
+	}
+
+	res
+}
+
+/// escape hatch: runs hook `hook` with exactly `argv` as its arguments,
+/// bypassing whatever convention git defines for that hook.
+///
+/// Useful for experimenting with custom hooks or hooks not modeled by
+/// a dedicated `hooks_*` function yet.
+pub fn run_hook_raw(
+	repo: &Repository,
+	other_paths: Option<&[&str]>,
+	hook: &str,
+	argv: &[&str],
+	ctx: &HookContext,
+	timeout: Option<Duration>,
+) -> Result<HookResult> {
+	let hook = find_hook!(repo, other_paths, hook);
+
+	hook.run_hook_with_timeout(argv, ctx, timeout)
+}
+
+/// like [`run_hook_raw`] but also runs the executable files in the
+/// `<hook>.d` directory next to the hook, in lexical order after the
+/// hook itself. `timeout` applies to all of them together.
+///
+/// the first failure is returned (its `hook` is the failing script),
+/// abortable hooks (see [`HookType::can_abort`]) do not run the
+/// remaining scripts after it. all `hooks_*` functions run hooks
+/// this way.
+pub fn run_hooks(
+	repo: &Repository,
+	other_paths: Option<&[&str]>,
+	hook: &str,
+	argv: &[&str],
+	ctx: &HookContext,
+	timeout: Option<Duration>,
+) -> Result<HookResult> {
+	let hook = find_hooks!(repo, other_paths, hook);
+
+	hook.run_hooks(argv, None, ctx, timeout)
+}
+
+/// like [`run_hook_raw`] but with an absolute `deadline` instead of a
+/// timeout, which composes better when several hooks have to finish by
+/// the same point in time.
+///
+/// A deadline in the past times out right away.
+pub fn run_hook_with_deadline(
+	repo: &Repository,
+	other_paths: Option<&[&str]>,
+	hook: &str,
+	argv: &[&str],
+	ctx: &HookContext,
+	deadline: Instant,
+) -> Result<HookResult> {
+	let hook = find_hook!(repo, other_paths, hook);
+
+	hook.run_hook_with_deadline(argv, ctx, deadline)
+}
+
+/// runs the candidate hook `script` once with `args` and `stdin`, e.g.
+/// for testing a hook before installing it.
+///
+/// The script is written to a temporary directory (which the hook also
+/// runs in) and removed afterwards, the repository is never touched.
+pub fn test_hook_script(
+	script: &[u8],
+	args: &[&str],
+	stdin: Option<&[u8]>,
+) -> Result<HookResult> {
+	let dir = TempHookDir::create()?;
+	let hook = dir.0.join(TEST_HOOK_NAME);
+
+	File::create(&hook)?.write_all(script)?;
+
+	#[cfg(unix)]
+	{
+		use std::os::unix::fs::PermissionsExt;
+		std::fs::set_permissions(
+			&hook,
+			std::fs::Permissions::from_mode(0o755),
+		)?;
+	}
+
+	let hook = HookPaths {
+		git: dir.0.clone(),
+		hook_dir: hook.with_file_name(format!("{TEST_HOOK_NAME}.d")),
+		hook,
+		pwd: dir.0.clone(),
+		shell: None,
+		terminated: TerminatedHandling::default(),
+		prefix: Vec::new(),
+		kill_grace: DEFAULT_KILL_GRACE,
+		git_env: HookGitEnv::default(),
+	};
+
+	hook.run_hook_with_stdin_os_str(
+		args,
+		stdin.unwrap_or_default(),
+		&HookContext::default(),
+		None,
+	)
+}
+
+/// file name of the hook written by [`test_hook_script`]
+const TEST_HOOK_NAME: &str = "test-hook";
+
+/// temporary directory removed again on drop
+struct TempHookDir(PathBuf);
+
+impl TempHookDir {
+	fn create() -> Result<Self> {
+		static COUNTER: AtomicUsize = AtomicUsize::new(0);
+
+		loop {
+			let path = std::env::temp_dir().join(format!(
+				"git2-hooks-{}-{}",
+				std::process::id(),
+				COUNTER.fetch_add(1, Ordering::Relaxed)
+			));
+
+			match std::fs::create_dir(&path) {
+				Ok(()) => return Ok(Self(path)),
+				// left over by a previous process with the same pid
+				Err(e) if e.kind() == ErrorKind::AlreadyExists => {}
+				Err(e) => return Err(e.into()),
+			}
+		}
+	}
+}
+
+impl Drop for TempHookDir {
+	fn drop(&mut self) {
+		if let Err(e) = std::fs::remove_dir_all(&self.0) {
+			log::warn!("failed to remove {}: {e}", self.0.display());
+		}
+	}
+}
+
+/// runs any hook `hook` with `args` and an extra pipe on [`HOOK_PROGRESS_FD`].
+///
+/// The hook can report progress through that pipe. The progress is captured separately from stdout/stderr so it can be
+/// shown while stdout/stderr are kept for the final result.
+///
+/// Only supported on unix, elsewhere the progress is always empty.
+pub fn run_hook_with_progress(
+	repo: &Repository,
+	other_paths: Option<&[&str]>,
+	hook: &str,
+	args: &[&str],
+	ctx: &HookContext,
+	timeout: Option<Duration>,
+) -> Result<HookProgressResult> {
+	let hook = HookPaths::new(repo, other_paths, hook)?;
+	if !hook.found() {
+		return Ok(HookProgressResult {
+			result: HookResult::NoHookFound,
+			progress: String::new(),
+		});
+	}
+
+	let (result, progress) =
+		hook.run_hook_with_progress_os_str(args, ctx, timeout)?;
+
+	Ok(HookProgressResult { result, progress })
+}
+
+/// runs any hook `hook` with `args`, collecting its output as configured
+/// by `buffering`.
+///
+/// With [`OutputBuffering::Timestamped`] the relative order of stdout and
+/// stderr is kept in a [`Transcript`], e.g. for debugging a hook that
+/// reports errors in between regular output.
+pub fn run_hook_with_buffering(
+	repo: &Repository,
+	other_paths: Option<&[&str]>,
+	hook: &str,
+	args: &[&str],
+	ctx: &HookContext,
+	timeout: Option<Duration>,
+	buffering: OutputBuffering,
+) -> Result<HookOutputResult> {
+	let hook = HookPaths::new(repo, other_paths, hook)?;
+	if !hook.found() {
+		return Ok(HookOutputResult {
+			result: HookResult::NoHookFound,
+			transcript: None,
+		});
+	}
+
+	let (result, transcript) = hook.run_hook_with_buffering_os_str(
+		args, ctx, timeout, buffering,
+	)?;
+
+	Ok(HookOutputResult { result, transcript })
+}
+
+/// runs any hook `hook` with `args`, passing every line of its output to
+/// `on_line` as soon as it was written.
+///
+/// Both pipes are read on separate threads, `on_line` is called on the
+/// calling thread while the hook runs. The returned [`HookResult`] still
+/// contains the complete stdout/stderr.
+pub fn run_hook_streaming(
+	repo: &Repository,
+	other_paths: Option<&[&str]>,
+	hook: &str,
+	args: &[&str],
+	ctx: &HookContext,
+	on_line: impl FnMut(HookOutputLine),
+) -> Result<HookResult> {
+	let hook = HookPaths::new(repo, other_paths, hook)?;
+	if !hook.found() {
+		return Ok(HookResult::NoHookFound);
+	}
+
+	hook.run_hook_streaming(args, ctx, on_line)
+}
+
+/// see [`run_hook_streaming`]
+pub fn run_hook_streaming_with_timeout(
+	repo: &Repository,
+	other_paths: Option<&[&str]>,
+	hook: &str,
+	args: &[&str],
+	ctx: &HookContext,
+	timeout: Option<Duration>,
+	on_line: impl FnMut(HookOutputLine),
+) -> Result<HookResult> {
+	let hook = HookPaths::new(repo, other_paths, hook)?;
+	if !hook.found() {
+		return Ok(HookResult::NoHookFound);
+	}
+
+	hook.run_hook_streaming_with_timeout(args, ctx, timeout, on_line)
+}
+
+/// diagnostic: runs hook `hook` twice in a row and reports both durations.
+///
+/// The difference exposes the cold-start overhead (login shell profile
+/// sourcing, interpreter startup, filesystem caches) that every hook run
+/// pays on top of the actual hook work.
+///
+/// returns `None` if no hook `hook` was found.
+pub fn hooks_warmup(
+	repo: &Repository,
+	other_paths: Option<&[&str]>,
+	hook: &str,
+	args: &[&str],
+) -> Result<Option<HookWarmupResult>> {
+	let hook = HookPaths::new(repo, other_paths, hook)?;
+	if !hook.found() {
+		return Ok(None);
+	}
+
+	let timed_run = || -> Result<TimedHookResult> {
+		let start = Instant::now();
+		let result = hook.run_hook(args, &HookContext::default())?;
+
+		Ok(TimedHookResult {
+			result,
+			duration: start.elapsed(),
+		})
+	};
+
+	let cold = timed_run()?;
+	let warm = timed_run()?;
+
+	Ok(Some(HookWarmupResult { cold, warm }))
+}
+
 /// this hook is documented here <https://git-scm.com/docs/githooks#_pre_commit>
 pub fn hooks_pre_commit(
 	repo: &Repository,
 	other_paths: Option<&[&str]>,
 ) -> Result<HookResult> {
-	hooks_pre_commit_with_timeout(repo, other_paths, None)
+	hooks_pre_commit_with_timeout(
+		repo,
+		other_paths,
+		&HookContext::default(),
+		None,
+	)
 }
 
 /// this hook is documented here <https://git-scm.com/docs/githooks#_pre_commit>
 pub fn hooks_pre_commit_with_timeout(
 	repo: &Repository,
 	other_paths: Option<&[&str]>,
+	ctx: &HookContext,
 	timeout: Option<Duration>,
 ) -> Result<HookResult> {
-	let hook = find_hook!(repo, other_paths, HOOK_PRE_COMMIT);
+	let hook = find_hooks!(repo, other_paths, HOOK_PRE_COMMIT);
 
-	hook.run_hook_with_timeout(&[], timeout)
+	hook.run_hooks(NO_ARGS, None, ctx, timeout)
 }
 
 /// this hook is documented here <https://git-scm.com/docs/githooks#_post_commit>
@@ -199,98 +747,546 @@ pub fn hooks_post_commit(
 	repo: &Repository,
 	other_paths: Option<&[&str]>,
 ) -> Result<HookResult> {
-	hooks_post_commit_with_timeout(repo, other_paths, None)
+	hooks_post_commit_with_timeout(
+		repo,
+		other_paths,
+		&HookContext::default(),
+		None,
+	)
 }
 
 /// this hook is documented here <https://git-scm.com/docs/githooks#_post_commit>
 pub fn hooks_post_commit_with_timeout(
 	repo: &Repository,
 	other_paths: Option<&[&str]>,
+	ctx: &HookContext,
 	timeout: Option<Duration>,
 ) -> Result<HookResult> {
-	let hook = find_hook!(repo, other_paths, HOOK_POST_COMMIT);
+	let hook = find_hooks!(repo, other_paths, HOOK_POST_COMMIT);
 
-	hook.run_hook_with_timeout(&[], timeout)
-}
-
-#[derive(Clone, Copy)]
-pub enum PrepareCommitMsgSource {
-	Message,
-	Template,
-	Merge,
-	Squash,
-	Commit(git2::Oid),
+	hook.run_hooks(NO_ARGS, None, ctx, timeout)
 }
 
-/// this hook is documented here <https://git-scm.com/docs/githooks#_prepare_commit_msg>
-#[allow(clippy::needless_pass_by_value)]
-pub fn hooks_prepare_commit_msg(
+/// Git hook: `applypatch-msg`
+///
+/// This hook is documented here <https://git-scm.com/docs/githooks#_applypatch_msg>.
+/// Like [`hooks_commit_msg`] the message of the patch is written to a temp file
+/// at `<.git|hooksPath>/APPLYPATCH_EDITMSG` the hook may edit, a failing hook
+/// rejects the patch.
+pub fn hooks_applypatch_msg(
 	repo: &Repository,
 	other_paths: Option<&[&str]>,
-	source: PrepareCommitMsgSource,
 	msg: &mut String,
 ) -> Result<HookResult> {
-	hooks_prepare_commit_msg_with_timeout(
+	hooks_applypatch_msg_with_timeout(
 		repo,
 		other_paths,
-		source,
 		msg,
+		&HookContext::default(),
 		None,
 	)
 }
 
-/// this hook is documented here <https://git-scm.com/docs/githooks#_prepare_commit_msg>
-pub fn hooks_prepare_commit_msg_with_timeout(
+/// Git hook: `applypatch-msg`
+///
+/// See [`hooks_applypatch_msg`] for more details.
+pub fn hooks_applypatch_msg_with_timeout(
 	repo: &Repository,
 	other_paths: Option<&[&str]>,
-	source: PrepareCommitMsgSource,
 	msg: &mut String,
+	ctx: &HookContext,
 	timeout: Option<Duration>,
 ) -> Result<HookResult> {
-	let hook = find_hook!(repo, other_paths, HOOK_PREPARE_COMMIT_MSG);
+	let hook = find_hooks!(repo, other_paths, HOOK_APPLYPATCH_MSG);
 
-	let temp_file = hook.git.join(HOOK_COMMIT_MSG_TEMP_FILE);
+	let temp_file = hook.git.join(HOOK_APPLYPATCH_MSG_TEMP_FILE);
 	File::create(&temp_file)?.write_all(msg.as_bytes())?;
 
-	let temp_file_path = temp_file.as_os_str().to_string_lossy();
-
-	let vec = vec![
-		temp_file_path.as_ref(),
-		match source {
-			PrepareCommitMsgSource::Message => "message",
-			PrepareCommitMsgSource::Template => "template",
-			PrepareCommitMsgSource::Merge => "merge",
-			PrepareCommitMsgSource::Squash => "squash",
-			PrepareCommitMsgSource::Commit(_) => "commit",
-		},
-	];
-	let mut args = vec;
-
-	let id = if let PrepareCommitMsgSource::Commit(id) = &source {
-		Some(id.to_string())
-	} else {
-		None
-	};
-
-	if let Some(id) = &id {
-		args.push(id);
-	}
-
-	let res = hook.run_hook_with_timeout(args.as_slice(), timeout)?;
+	let res = hook.run_hooks([&temp_file], None, ctx, timeout)?;
 
 	// load possibly altered msg
-	msg.clear();
-	File::open(temp_file)?.read_to_string(msg)?;
+	*msg = read_msg_file(&temp_file)?;
 
 	Ok(res)
 }
 
-#[cfg(test)]
-mod tests {
-	use super::*;
-	use git2_testing::{repo_init, repo_init_bare};
-	use pretty_assertions::assert_eq;
-	use tempfile::{tempdir, TempDir};
+/// this hook is documented here <https://git-scm.com/docs/githooks#_pre_applypatch>
+///
+/// runs after the patch is applied but before it is committed, a
+/// failing hook leaves the working tree as is and aborts the commit.
+pub fn hooks_pre_applypatch(
+	repo: &Repository,
+	other_paths: Option<&[&str]>,
+) -> Result<HookResult> {
+	hooks_pre_applypatch_with_timeout(
+		repo,
+		other_paths,
+		&HookContext::default(),
+		None,
+	)
+}
+
+/// this hook is documented here <https://git-scm.com/docs/githooks#_pre_applypatch>
+pub fn hooks_pre_applypatch_with_timeout(
+	repo: &Repository,
+	other_paths: Option<&[&str]>,
+	ctx: &HookContext,
+	timeout: Option<Duration>,
+) -> Result<HookResult> {
+	let hook = find_hooks!(repo, other_paths, HOOK_PRE_APPLYPATCH);
+
+	hook.run_hooks(NO_ARGS, None, ctx, timeout)
+}
+
+/// this hook is documented here <https://git-scm.com/docs/githooks#_post_applypatch>
+///
+/// purely informational, the patch is committed already.
+pub fn hooks_post_applypatch(
+	repo: &Repository,
+	other_paths: Option<&[&str]>,
+) -> Result<HookResult> {
+	hooks_post_applypatch_with_timeout(
+		repo,
+		other_paths,
+		&HookContext::default(),
+		None,
+	)
+}
+
+/// this hook is documented here <https://git-scm.com/docs/githooks#_post_applypatch>
+pub fn hooks_post_applypatch_with_timeout(
+	repo: &Repository,
+	other_paths: Option<&[&str]>,
+	ctx: &HookContext,
+	timeout: Option<Duration>,
+) -> Result<HookResult> {
+	let hook = find_hooks!(repo, other_paths, HOOK_POST_APPLYPATCH);
+
+	hook.run_hooks(NO_ARGS, None, ctx, timeout)
+}
+
+/// this hook is documented here <https://git-scm.com/docs/githooks#_post_checkout>
+pub fn hooks_post_checkout(
+	repo: &Repository,
+	other_paths: Option<&[&str]>,
+	prev_head: Oid,
+	new_head: Oid,
+	branch_checkout: bool,
+) -> Result<HookResult> {
+	hooks_post_checkout_with_timeout(
+		repo,
+		other_paths,
+		prev_head,
+		new_head,
+		branch_checkout,
+		&HookContext::default(),
+		None,
+	)
+}
+
+/// this hook is documented here <https://git-scm.com/docs/githooks#_post_checkout>
+///
+/// `branch_checkout` is unset for checking out single files, `prev_head`
+/// and `new_head` are the same then. like in git the checkout is done
+/// already, the result of the hook can not undo it.
+pub fn hooks_post_checkout_with_timeout(
+	repo: &Repository,
+	other_paths: Option<&[&str]>,
+	prev_head: Oid,
+	new_head: Oid,
+	branch_checkout: bool,
+	ctx: &HookContext,
+	timeout: Option<Duration>,
+) -> Result<HookResult> {
+	let hook = find_hooks!(repo, other_paths, HOOK_POST_CHECKOUT);
+
+	let prev_head = prev_head.to_string();
+	let new_head = new_head.to_string();
+	let flag = if branch_checkout { "1" } else { "0" };
+
+	hook.run_hooks(
+		[prev_head.as_str(), new_head.as_str(), flag],
+		None,
+		ctx,
+		timeout,
+	)
+}
+
+/// this hook is documented here <https://git-scm.com/docs/githooks#_post_merge>
+pub fn hooks_post_merge(
+	repo: &Repository,
+	other_paths: Option<&[&str]>,
+	is_squash: bool,
+) -> Result<HookResult> {
+	hooks_post_merge_with_timeout(
+		repo,
+		other_paths,
+		is_squash,
+		&HookContext::default(),
+		None,
+	)
+}
+
+/// this hook is documented here <https://git-scm.com/docs/githooks#_post_merge>
+///
+/// runs after a merge completed, fast-forwards included. like in git the
+/// merge is committed already, the result of the hook can not undo it.
+pub fn hooks_post_merge_with_timeout(
+	repo: &Repository,
+	other_paths: Option<&[&str]>,
+	is_squash: bool,
+	ctx: &HookContext,
+	timeout: Option<Duration>,
+) -> Result<HookResult> {
+	let hook = find_hooks!(repo, other_paths, HOOK_POST_MERGE);
+
+	let flag = if is_squash { "1" } else { "0" };
+
+	hook.run_hooks([flag], None, ctx, timeout)
+}
+
+/// this hook is documented here <https://git-scm.com/docs/githooks#_pre_rebase>
+pub fn hooks_pre_rebase(
+	repo: &Repository,
+	other_paths: Option<&[&str]>,
+	upstream: &str,
+	branch: Option<&str>,
+) -> Result<HookResult> {
+	hooks_pre_rebase_with_timeout(
+		repo,
+		other_paths,
+		upstream,
+		branch,
+		&HookContext::default(),
+		None,
+	)
+}
+
+/// this hook is documented here <https://git-scm.com/docs/githooks#_pre_rebase>
+///
+/// `branch` is the branch being rebased, `None` when rebasing the current
+/// branch in which case the hook only gets the `upstream` argument. a
+/// failing hook is meant to cancel the rebase before it started.
+pub fn hooks_pre_rebase_with_timeout(
+	repo: &Repository,
+	other_paths: Option<&[&str]>,
+	upstream: &str,
+	branch: Option<&str>,
+	ctx: &HookContext,
+	timeout: Option<Duration>,
+) -> Result<HookResult> {
+	let hook = find_hooks!(repo, other_paths, HOOK_PRE_REBASE);
+
+	let mut args = vec![upstream];
+	args.extend(branch);
+
+	hook.run_hooks(&args, None, ctx, timeout)
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PrepareCommitMsgSource {
+	Message,
+	Template,
+	Merge,
+	Squash,
+	Commit(git2::Oid),
+}
+
+/// this hook is documented here <https://git-scm.com/docs/githooks#_prepare_commit_msg>
+#[allow(clippy::needless_pass_by_value)]
+pub fn hooks_prepare_commit_msg(
+	repo: &Repository,
+	other_paths: Option<&[&str]>,
+	source: PrepareCommitMsgSource,
+	msg: &mut String,
+) -> Result<HookResult> {
+	hooks_prepare_commit_msg_with_timeout(
+		repo,
+		other_paths,
+		source,
+		msg,
+		&HookContext::default(),
+		None,
+	)
+}
+
+/// this hook is documented here <https://git-scm.com/docs/githooks#_prepare_commit_msg>
+///
+/// `msg` is replaced by what the hook left in the message file even if
+/// it failed or timed out, tools use it to explain what went wrong
+pub fn hooks_prepare_commit_msg_with_timeout(
+	repo: &Repository,
+	other_paths: Option<&[&str]>,
+	source: PrepareCommitMsgSource,
+	msg: &mut String,
+	ctx: &HookContext,
+	timeout: Option<Duration>,
+) -> Result<HookResult> {
+	let hook =
+		find_hooks!(repo, other_paths, HOOK_PREPARE_COMMIT_MSG);
+
+	let temp_file = hook.git.join(HOOK_COMMIT_MSG_TEMP_FILE);
+	File::create(&temp_file)?.write_all(msg.as_bytes())?;
+
+	let mut args = vec![
+		temp_file.as_os_str(),
+		OsStr::new(match source {
+			PrepareCommitMsgSource::Message => "message",
+			PrepareCommitMsgSource::Template => "template",
+			PrepareCommitMsgSource::Merge => "merge",
+			PrepareCommitMsgSource::Squash => "squash",
+			PrepareCommitMsgSource::Commit(_) => "commit",
+		}),
+	];
+
+	let id = if let PrepareCommitMsgSource::Commit(id) = &source {
+		Some(id.to_string())
+	} else {
+		None
+	};
+
+	if let Some(id) = &id {
+		args.push(OsStr::new(id));
+	}
+
+	let res = hook.run_hooks(args.as_slice(), None, ctx, timeout)?;
+
+	// load possibly altered msg
+	*msg = read_msg_file(&temp_file)?;
+
+	Ok(res)
+}
+
+/// a ref about to be pushed, one line of the standard input of the
+/// `pre-push` hook
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PrePushRef {
+	/// [`PrePushRef::DELETE`] if `remote_ref` is deleted
+	pub local_ref: String,
+	/// zero if `remote_ref` is deleted
+	pub local_oid: Oid,
+	/// ref on the remote that is updated
+	pub remote_ref: String,
+	/// zero if `remote_ref` does not exist yet
+	pub remote_oid: Oid,
+}
+
+impl PrePushRef {
+	/// what git passes as local ref of a deleted remote ref
+	pub const DELETE: &'static str = "(delete)";
+
+	/// `<local ref> SP <local oid> SP <remote ref> SP <remote oid> LF`
+	fn line(&self) -> String {
+		format!(
+			"{} {} {} {}\n",
+			self.local_ref,
+			self.local_oid,
+			self.remote_ref,
+			self.remote_oid
+		)
+	}
+}
+
+/// this hook is documented here <https://git-scm.com/docs/githooks#_pre_push>
+pub fn hooks_pre_push(
+	repo: &Repository,
+	other_paths: Option<&[&str]>,
+	remote_name: &str,
+	remote_url: &str,
+	refs: &[PrePushRef],
+) -> Result<HookResult> {
+	hooks_pre_push_with_timeout(
+		repo,
+		other_paths,
+		remote_name,
+		remote_url,
+		refs,
+		&HookContext::default(),
+		None,
+	)
+}
+
+/// this hook is documented here <https://git-scm.com/docs/githooks#_pre_push>
+///
+/// `refs` are written to the standard input of the hook, one line each
+pub fn hooks_pre_push_with_timeout(
+	repo: &Repository,
+	other_paths: Option<&[&str]>,
+	remote_name: &str,
+	remote_url: &str,
+	refs: &[PrePushRef],
+	ctx: &HookContext,
+	timeout: Option<Duration>,
+) -> Result<HookResult> {
+	let hook = find_hooks!(repo, other_paths, HOOK_PRE_PUSH);
+
+	let stdin = refs.iter().map(PrePushRef::line).collect::<String>();
+
+	hook.run_hooks(
+		[remote_name, remote_url],
+		Some(stdin.as_bytes()),
+		ctx,
+		timeout,
+	)
+}
+
+/// the command that rewrote commits, the argument of the `post-rewrite`
+/// hook
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PostRewriteSource {
+	Amend,
+	Rebase,
+}
+
+impl PostRewriteSource {
+	const fn as_str(self) -> &'static str {
+		match self {
+			Self::Amend => "amend",
+			Self::Rebase => "rebase",
+		}
+	}
+}
+
+/// this hook is documented here <https://git-scm.com/docs/githooks#_post_rewrite>
+pub fn hooks_post_rewrite(
+	repo: &Repository,
+	other_paths: Option<&[&str]>,
+	source: PostRewriteSource,
+	rewritten: &[(Oid, Oid)],
+) -> Result<HookResult> {
+	hooks_post_rewrite_with_timeout(
+		repo,
+		other_paths,
+		source,
+		rewritten,
+		&HookContext::default(),
+		None,
+	)
+}
+
+/// this hook is documented here <https://git-scm.com/docs/githooks#_post_rewrite>
+///
+/// the `(old, new)` ids of the `rewritten` commits are written to the
+/// standard input of the hook, one pair per line. like in git the
+/// commits are rewritten already, the result of the hook can not undo it.
+pub fn hooks_post_rewrite_with_timeout(
+	repo: &Repository,
+	other_paths: Option<&[&str]>,
+	source: PostRewriteSource,
+	rewritten: &[(Oid, Oid)],
+	ctx: &HookContext,
+	timeout: Option<Duration>,
+) -> Result<HookResult> {
+	use std::fmt::Write as _;
+
+	let hook = find_hooks!(repo, other_paths, HOOK_POST_REWRITE);
+
+	let mut stdin = String::new();
+	for (old, new) in rewritten {
+		let _ = writeln!(stdin, "{old} {new}");
+	}
+
+	hook.run_hooks(
+		[source.as_str()],
+		Some(stdin.as_bytes()),
+		ctx,
+		timeout,
+	)
+}
+
+/// the state argument of the `reference-transaction` hook
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ReferenceTransactionState {
+	/// all refs are locked, a failing hook aborts the transaction
+	Prepared,
+	/// the refs are updated
+	Committed,
+	/// the transaction was aborted, no ref was updated
+	Aborted,
+}
+
+impl ReferenceTransactionState {
+	const fn as_str(self) -> &'static str {
+		match self {
+			Self::Prepared => "prepared",
+			Self::Committed => "committed",
+			Self::Aborted => "aborted",
+		}
+	}
+}
+
+/// a ref updated by a transaction, one line of the standard input of
+/// the `reference-transaction` hook
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RefUpdate {
+	/// zero if `refname` is created
+	pub old_oid: Oid,
+	/// zero if `refname` is deleted
+	pub new_oid: Oid,
+	/// full name of the ref, e.g. `refs/heads/main`
+	pub refname: String,
+}
+
+impl RefUpdate {
+	/// `<old-oid> SP <new-oid> SP <ref-name> LF`
+	fn line(&self) -> String {
+		format!(
+			"{} {} {}\n",
+			self.old_oid, self.new_oid, self.refname
+		)
+	}
+}
+
+/// this hook is documented here <https://git-scm.com/docs/githooks#_reference_transaction>
+pub fn hooks_reference_transaction(
+	repo: &Repository,
+	other_paths: Option<&[&str]>,
+	state: ReferenceTransactionState,
+	updates: &[RefUpdate],
+) -> Result<HookResult> {
+	hooks_reference_transaction_with_timeout(
+		repo,
+		other_paths,
+		state,
+		updates,
+		&HookContext::default(),
+		None,
+	)
+}
+
+/// this hook is documented here <https://git-scm.com/docs/githooks#_reference_transaction>
+///
+/// `updates` are written to the standard input of the hook, one line
+/// each. only the result of the [`ReferenceTransactionState::Prepared`]
+/// run can abort the transaction.
+pub fn hooks_reference_transaction_with_timeout(
+	repo: &Repository,
+	other_paths: Option<&[&str]>,
+	state: ReferenceTransactionState,
+	updates: &[RefUpdate],
+	ctx: &HookContext,
+	timeout: Option<Duration>,
+) -> Result<HookResult> {
+	let hook =
+		find_hooks!(repo, other_paths, HOOK_REFERENCE_TRANSACTION);
+
+	let stdin =
+		updates.iter().map(RefUpdate::line).collect::<String>();
+
+	hook.run_hooks(
+		[state.as_str()],
+		Some(stdin.as_bytes()),
+		ctx,
+		timeout,
+	)
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use git2_testing::{repo_init, repo_init_bare};
+	use pretty_assertions::assert_eq;
+	use tempfile::{tempdir, TempDir};
 
 	#[test]
 	fn test_smoke() {
@@ -378,13 +1374,15 @@ exit 0
 
 		assert!(hook.found());
 
-		let result = hook.run_hook(&[TEXT]).unwrap();
+		let result =
+			hook.run_hook(&[TEXT], &HookContext::default()).unwrap();
 
 		let HookResult::RunNotSuccessful {
 			code,
 			stdout,
 			stderr,
 			hook: h,
+			..
 		} = result
 		else {
 			unreachable!("run_hook should've failed");
@@ -476,33 +1474,96 @@ exit 1
 	}
 
 	#[test]
-	fn test_env_containing_path() {
-		const PATH_EXPORT: &str = "export PATH";
-
+	fn test_hook_prefix() {
 		let (_td, repo) = repo_init();
 
+		repo.config()
+			.unwrap()
+			.set_str(
+				"gitui.hooks.prefix",
+				"env GITUI_PREFIX_SENTINEL=set",
+			)
+			.unwrap();
+
 		let hook = b"#!/bin/sh
-export
+echo \"sentinel: $GITUI_PREFIX_SENTINEL\"
+echo \"args: $1\"
 exit 1
         ";
 
-		create_hook(&repo, HOOK_PRE_COMMIT, hook);
-		let res = hooks_pre_commit(&repo, None).unwrap();
+		create_hook(&repo, HOOK_COMMIT_MSG, hook);
+
+		let mut msg = String::from("test");
+		let res = hooks_commit_msg(&repo, None, &mut msg).unwrap();
 
 		let HookResult::RunNotSuccessful { stdout, .. } = res else {
-			unreachable!()
+			unreachable!("{res:?}")
 		};
 
-		assert!(
-			stdout
-				.lines()
-				.any(|line| line.starts_with(PATH_EXPORT)),
-			"Could not find line starting with {PATH_EXPORT:?} in: {stdout:?}"
-		);
+		let mut lines = stdout.lines();
+		assert_eq!(lines.next(), Some("sentinel: set"));
+		// arguments of the hook are passed through the prefix
+		assert!(lines
+			.next()
+			.is_some_and(|args| args.ends_with("COMMIT_EDITMSG")));
 	}
 
 	#[test]
-	fn test_pre_commit_fail_hookspath() {
+	#[cfg(unix)]
+	fn test_hook_prefix_without_shebang() {
+		let (_td, repo) = repo_init();
+
+		repo.config()
+			.unwrap()
+			.set_str(
+				"gitui.hooks.prefix",
+				"env GITUI_PREFIX_SENTINEL=set",
+			)
+			.unwrap();
+
+		create_hook(
+			&repo,
+			HOOK_PRE_COMMIT,
+			b"echo \"$GITUI_PREFIX_SENTINEL\"; exit 1",
+		);
+
+		let res = hooks_pre_commit(&repo, None).unwrap();
+
+		let HookResult::RunNotSuccessful { stdout, .. } = res else {
+			unreachable!("{res:?}")
+		};
+
+		assert_eq!(stdout, "set\n");
+	}
+
+	#[test]
+	fn test_env_containing_path() {
+		const PATH_EXPORT: &str = "export PATH";
+
+		let (_td, repo) = repo_init();
+
+		let hook = b"#!/bin/sh
+export
+exit 1
+        ";
+
+		create_hook(&repo, HOOK_PRE_COMMIT, hook);
+		let res = hooks_pre_commit(&repo, None).unwrap();
+
+		let HookResult::RunNotSuccessful { stdout, .. } = res else {
+			unreachable!()
+		};
+
+		assert!(
+			stdout
+				.lines()
+				.any(|line| line.starts_with(PATH_EXPORT)),
+			"Could not find line starting with {PATH_EXPORT:?} in: {stdout:?}"
+		);
+	}
+
+	#[test]
+	fn test_pre_commit_fail_hookspath() {
 		let (_td, repo) = repo_init();
 		let hooks = TempDir::new().unwrap();
 
@@ -532,6 +1593,283 @@ exit 1
 		assert_eq!(&stdout, "rejected\n");
 	}
 
+	#[test]
+	fn test_hookspath_with_spaces() {
+		let (_td, repo) = repo_init();
+		let hooks = TempDir::new().unwrap();
+		let hooks_path = hooks.path().join("my hooks");
+		std::fs::create_dir(&hooks_path).unwrap();
+
+		let hook = b"#!/bin/sh
+echo \"[$1]\"
+exit 1
+        ";
+
+		create_hook_in_path(&hooks_path.join("commit-msg"), hook);
+
+		repo.config()
+			.unwrap()
+			.set_str("core.hooksPath", hooks_path.to_str().unwrap())
+			.unwrap();
+
+		let hook =
+			HookPaths::new(&repo, None, HOOK_COMMIT_MSG).unwrap();
+		let res = hook
+			.run_hook(&["with space"], &HookContext::default())
+			.unwrap();
+
+		let HookResult::RunNotSuccessful { code, stdout, .. } = res
+		else {
+			unreachable!()
+		};
+
+		assert_eq!(code.unwrap(), 1);
+		assert_eq!(&stdout, "[with space]\n");
+	}
+
+	#[test]
+	#[cfg(windows)]
+	fn test_windows_native_hook() {
+		let (_td, repo) = repo_init();
+		let hooks = repo.path().join("hooks");
+
+		std::fs::write(
+			hooks.join("pre-commit.cmd"),
+			"@echo off\r\necho from cmd %1\r\nexit /b 3\r\n",
+		)
+		.unwrap();
+
+		let hook =
+			HookPaths::new(&repo, None, HOOK_PRE_COMMIT).unwrap();
+		assert!(hook.found());
+		assert_eq!(hook.hook, hooks.join("pre-commit.cmd"));
+
+		let res = hook.run_hook(&["arg"]).unwrap();
+		let HookResult::RunNotSuccessful { code, stdout, .. } = res
+		else {
+			unreachable!("{res:?}")
+		};
+		assert_eq!(code, Some(3));
+		assert_eq!(stdout.trim_end(), "from cmd arg");
+	}
+
+	#[test]
+	fn test_expanded_hooks_path() {
+		let (_td, repo) = repo_init();
+
+		assert_eq!(expanded_hooks_path(&repo).unwrap(), None);
+
+		repo.config()
+			.unwrap()
+			.set_str("core.hooksPath", "~/myhooks")
+			.unwrap();
+
+		let home = shellexpand::tilde("~");
+
+		assert_eq!(
+			expanded_hooks_path(&repo).unwrap(),
+			Some(Path::new(home.as_ref()).join("myhooks"))
+		);
+
+		repo.config()
+			.unwrap()
+			.set_str("core.hooksPath", ".githooks")
+			.unwrap();
+
+		assert_eq!(
+			expanded_hooks_path(&repo).unwrap(),
+			Some(repo.workdir().unwrap().join(".githooks"))
+		);
+	}
+
+	#[test]
+	fn test_hook_duration() {
+		let (_td, repo) = repo_init();
+
+		create_hook(
+			&repo,
+			HOOK_PRE_COMMIT,
+			b"#!/bin/sh\nsleep 0.2\n",
+		);
+		let res = hooks_pre_commit(&repo, None).unwrap();
+		assert!(res.is_ok());
+		assert!(
+			res.duration().unwrap() >= Duration::from_millis(200)
+		);
+
+		create_hook(
+			&repo,
+			HOOK_PRE_COMMIT,
+			b"#!/bin/sh\nsleep 0.2\nexit 1\n",
+		);
+		let res = hooks_pre_commit(&repo, None).unwrap();
+		let HookResult::RunNotSuccessful { duration, .. } = res
+		else {
+			unreachable!()
+		};
+		assert!(duration >= Duration::from_millis(200));
+
+		// there is no post-commit hook to time
+		let res = hooks_post_commit(&repo, None).unwrap();
+		assert_eq!(res.duration(), None);
+	}
+
+	#[test]
+	#[cfg(unix)]
+	fn test_non_utf8_repo_path() {
+		use std::os::unix::ffi::OsStrExt;
+
+		let td = tempdir().unwrap();
+		let root = td.path().join(OsStr::from_bytes(b"repo-\xe9"));
+		let repo = Repository::init(&root).unwrap();
+		let subfolder = root.join(OsStr::from_bytes(b"sub-\xff"));
+		std::fs::create_dir(&subfolder).unwrap();
+
+		let hook = b"#!/bin/sh
+echo \"$GIT_PREFIX\"
+pwd
+exit 1
+";
+		let ctx = HookContext {
+			invocation_dir: Some(&subfolder),
+			..Default::default()
+		};
+		let run = || {
+			hooks_pre_commit_with_timeout(&repo, None, &ctx, None)
+				.unwrap()
+		};
+		let expected = |root: &Path| {
+			let mut expected = b"sub-\xff/\n".to_vec();
+			expected.extend(
+				root.canonicalize().unwrap().as_os_str().as_bytes(),
+			);
+			expected.push(b'\n');
+			expected
+		};
+
+		create_hook(&repo, HOOK_PRE_COMMIT, hook);
+		assert!(HookPaths::new(&repo, None, HOOK_PRE_COMMIT)
+			.unwrap()
+			.found());
+		let HookResult::RunNotSuccessful { stdout_bytes, .. } = run()
+		else {
+			unreachable!()
+		};
+		assert_eq!(stdout_bytes, expected(&root));
+
+		// a relative `core.hooksPath` is joined to the work tree
+		std::fs::create_dir(root.join(".githooks")).unwrap();
+		create_hook_in_path(
+			&root.join(".githooks").join(HOOK_PRE_COMMIT),
+			hook,
+		);
+		std::fs::remove_file(repo.path().join("hooks/pre-commit"))
+			.unwrap();
+		repo.config()
+			.unwrap()
+			.set_str("core.hooksPath", ".githooks")
+			.unwrap();
+
+		let HookResult::RunNotSuccessful { stdout_bytes, .. } = run()
+		else {
+			unreachable!()
+		};
+		assert_eq!(stdout_bytes, expected(&root));
+
+		// the message file is passed on as it is
+		create_hook_in_path(
+			&root.join(".githooks").join(HOOK_PREPARE_COMMIT_MSG),
+			b"#!/bin/sh
+test -f \"$1\" || exit 1
+echo \"$2\" > \"$1\"
+",
+		);
+
+		let mut msg = String::from("test");
+		let res = hooks_prepare_commit_msg(
+			&repo,
+			None,
+			PrepareCommitMsgSource::Message,
+			&mut msg,
+		)
+		.unwrap();
+
+		assert!(res.is_ok());
+		assert_eq!(msg, "message\n");
+	}
+
+	#[test]
+	fn test_hooks_path_from_subfolder() {
+		let (_td, repo) = repo_init();
+		let root = repo.workdir().unwrap().to_path_buf();
+		let subfolder = root.join("a/b");
+		std::fs::create_dir_all(&subfolder).unwrap();
+
+		// opened like gitui started in `a/b` would
+		let sub_repo = Repository::discover(&subfolder).unwrap();
+		let ctx = HookContext {
+			invocation_dir: Some(&subfolder),
+			..Default::default()
+		};
+		let run = || {
+			hooks_pre_commit_with_timeout(&sub_repo, None, &ctx, None)
+				.unwrap()
+		};
+		let hook = b"#!/bin/sh
+echo \"rejected from $(pwd)\"
+exit 1
+        ";
+		let rejected = format!(
+			"rejected from {}\n",
+			root.canonicalize().unwrap().display()
+		);
+
+		std::fs::create_dir(root.join(".githooks")).unwrap();
+		create_hook_in_path(
+			&root.join(".githooks").join("pre-commit"),
+			hook,
+		);
+		repo.config()
+			.unwrap()
+			.set_str("core.hooksPath", ".githooks")
+			.unwrap();
+
+		let HookResult::RunNotSuccessful { stdout, .. } = run()
+		else {
+			unreachable!()
+		};
+		assert_eq!(stdout, rejected);
+
+		let hooks = TempDir::new().unwrap();
+		create_hook_in_path(&hooks.path().join("pre-commit"), hook);
+		repo.config()
+			.unwrap()
+			.set_str("core.hooksPath", hooks.path().to_str().unwrap())
+			.unwrap();
+
+		let HookResult::RunNotSuccessful { stdout, .. } = run()
+		else {
+			unreachable!()
+		};
+		assert_eq!(stdout, rejected);
+
+		// never falls back to the relative path below the subfolder
+		let tilde = subfolder.join("~").join("gitui-hooks");
+		std::fs::create_dir_all(&tilde).unwrap();
+		create_hook_in_path(&tilde.join("pre-commit"), hook);
+		repo.config()
+			.unwrap()
+			.set_str("core.hooksPath", "~/gitui-hooks")
+			.unwrap();
+
+		let home = shellexpand::tilde("~");
+		assert_eq!(
+			expanded_hooks_path(&sub_repo).unwrap(),
+			Some(Path::new(home.as_ref()).join("gitui-hooks"))
+		);
+		assert_eq!(run(), HookResult::NoHookFound);
+	}
+
 	#[test]
 	fn test_pre_commit_fail_bare() {
 		let (_td, repo) = repo_init_bare();
@@ -588,6 +1926,34 @@ sys.exit(1)
 		assert!(res.is_not_successful());
 	}
 
+	#[test]
+	#[cfg(unix)]
+	fn test_pre_commit_env_python3() {
+		let (_td, repo) = repo_init();
+
+		let hook = b"#!/usr/bin/env python3
+import sys
+print(sys.argv[1])
+sys.exit(3)
+        ";
+
+		create_hook(&repo, HOOK_PRE_COMMIT, hook);
+
+		let hook =
+			HookPaths::new(&repo, None, HOOK_PRE_COMMIT).unwrap();
+		let res = hook
+			.run_hook(&["from python"], &HookContext::default())
+			.unwrap();
+
+		let HookResult::RunNotSuccessful { code, stdout, .. } = res
+		else {
+			unreachable!("{res:?}")
+		};
+
+		assert_eq!(code, Some(3));
+		assert_eq!(&stdout, "from python\n");
+	}
+
 	#[test]
 	fn test_hooks_commit_msg_reject() {
 		let (_td, repo) = repo_init();
@@ -615,112 +1981,374 @@ sys.exit(1)
 	}
 
 	#[test]
-	fn test_commit_msg_no_block_but_alter() {
+	fn test_applypatch_msg_alter() {
 		let (_td, repo) = repo_init();
 
-		let hook = b"#!/bin/sh
-echo 'msg' > \"$1\"
+		let hook = br#"#!/bin/sh
+MSG="$(cat "$1")"
+printf "$MSG" | sed 's/sth/applied/g' > "$1"
 exit 0
-        ";
+        "#;
 
-		create_hook(&repo, HOOK_COMMIT_MSG, hook);
+		create_hook(&repo, HOOK_APPLYPATCH_MSG, hook);
 
-		let mut msg = String::from("test");
-		let res = hooks_commit_msg(&repo, None, &mut msg).unwrap();
+		let mut msg = String::from("patch_sth");
+		let res =
+			hooks_applypatch_msg(&repo, None, &mut msg).unwrap();
 
 		assert!(res.is_ok());
-		assert_eq!(msg, String::from("msg\n"));
+		assert_eq!(msg, String::from("patch_applied"));
+		// the commit message is left alone
+		assert!(!repo
+			.path()
+			.join(HOOK_COMMIT_MSG_TEMP_FILE)
+			.exists());
 	}
 
 	#[test]
-	fn test_hook_pwd_in_bare_without_workdir() {
-		let (_td, repo) = repo_init_bare();
-		let git_root = repo.path().to_path_buf();
+	fn test_applypatch_msg_reject() {
+		let (_td, repo) = repo_init();
 
-		let hook =
-			HookPaths::new(&repo, None, HOOK_POST_COMMIT).unwrap();
+		let hook = b"#!/bin/sh
+	echo 'msg' > \"$1\"
+	echo 'rejected'
+	exit 1
+        ";
 
-		assert_eq!(hook.pwd, git_root);
-	}
+		create_hook(&repo, HOOK_APPLYPATCH_MSG, hook);
 
-	#[test]
-	fn test_hook_pwd() {
-		let (_td, repo) = repo_init();
-		let git_root = repo.path().to_path_buf();
+		let mut msg = String::from("test");
+		let res =
+			hooks_applypatch_msg(&repo, None, &mut msg).unwrap();
 
-		let hook =
-			HookPaths::new(&repo, None, HOOK_POST_COMMIT).unwrap();
+		let HookResult::RunNotSuccessful { code, stdout, .. } = res
+		else {
+			unreachable!()
+		};
 
-		assert_eq!(hook.pwd, git_root.parent().unwrap());
+		assert_eq!(code.unwrap(), 1);
+		assert_eq!(&stdout, "rejected\n");
+		assert_eq!(msg, String::from("msg\n"));
 	}
 
 	#[test]
-	fn test_hooks_prep_commit_msg_success() {
+	fn test_pre_and_post_applypatch() {
+		let (_td, repo) = repo_init();
+
+		create_hook(
+			&repo,
+			HOOK_PRE_APPLYPATCH,
+			b"#!/bin/sh\necho 'no'\nexit 1",
+		);
+		create_hook(
+			&repo,
+			HOOK_POST_APPLYPATCH,
+			b"#!/bin/sh\nexit 0",
+		);
+
+		let res = hooks_pre_applypatch(&repo, None).unwrap();
+		assert!(matches!(
+			res,
+			HookResult::RunNotSuccessful { code: Some(1), .. }
+		));
+
+		assert!(hooks_post_applypatch(&repo, None).unwrap().is_ok());
+	}
+
+	#[test]
+	fn test_hooks_commit_msg_file_arg() {
 		let (_td, repo) = repo_init();
 
 		let hook = b"#!/bin/sh
-echo \"msg:$2\" > \"$1\"
+echo \"$# $1\" > \"$1\"
 exit 0
         ";
 
-		create_hook(&repo, HOOK_PREPARE_COMMIT_MSG, hook);
+		create_hook(&repo, HOOK_COMMIT_MSG, hook);
 
 		let mut msg = String::from("test");
-		let res = hooks_prepare_commit_msg(
+		let res = hooks_commit_msg(&repo, None, &mut msg).unwrap();
+
+		assert!(res.is_ok());
+		assert_eq!(
+			msg,
+			format!(
+				"1 {}\n",
+				repo.path().join(HOOK_COMMIT_MSG_TEMP_FILE).display()
+			)
+		);
+	}
+
+	#[test]
+	fn test_commit_msg_no_block_but_alter() {
+		let (_td, repo) = repo_init();
+
+		let hook = b"#!/bin/sh
+echo 'msg' > \"$1\"
+exit 0
+        ";
+
+		create_hook(&repo, HOOK_COMMIT_MSG, hook);
+
+		let mut msg = String::from("test");
+		let res = hooks_commit_msg(&repo, None, &mut msg).unwrap();
+
+		assert!(res.is_ok());
+		assert_eq!(msg, String::from("msg\n"));
+	}
+
+	#[test]
+	fn test_commit_msg_utf8_round_trip() {
+		let (_td, repo) = repo_init();
+
+		let hook = b"#!/bin/sh
+exit 0
+        ";
+
+		create_hook(&repo, HOOK_COMMIT_MSG, hook);
+
+		let original = "fix: caf\u{e9} \u{1f680}\n\nd\u{e9}tails\n";
+		let mut msg = String::from(original);
+		let res = hooks_commit_msg(&repo, None, &mut msg).unwrap();
+
+		assert!(res.is_ok());
+		assert_eq!(msg, original);
+	}
+
+	#[test]
+	fn test_commit_msg_invalid_utf8_from_hook() {
+		let (_td, repo) = repo_init();
+
+		// latin-1 encoded `\xe9`
+		let hook = b"#!/bin/sh
+printf 'fix: caf\\351\\n' > \"$1\"
+exit 0
+        ";
+
+		create_hook(&repo, HOOK_COMMIT_MSG, hook);
+
+		let mut msg = String::from("test");
+		let res = hooks_commit_msg(&repo, None, &mut msg).unwrap();
+
+		assert!(res.is_ok());
+		assert_eq!(msg, "fix: caf\u{fffd}\n");
+	}
+
+	#[test]
+	fn test_commit_msg_preview() {
+		let (_td, repo) = repo_init();
+
+		let hook = b"#!/bin/sh
+grep -q 'fixup' \"$1\" && echo 'rejected' && exit 1
+echo 'msg' > \"$1\"
+exit 0
+        ";
+
+		create_hook(&repo, HOOK_COMMIT_MSG, hook);
+
+		let res = hooks_commit_msg_preview(
 			&repo,
 			None,
-			PrepareCommitMsgSource::Message,
-			&mut msg,
+			"test",
+			&HookContext::default(),
+			None,
 		)
 		.unwrap();
 
-		assert!(matches!(res, HookResult::Ok { .. }));
-		assert_eq!(msg, String::from("msg:message\n"));
+		assert!(res.is_ok());
+
+		let res = hooks_commit_msg_preview(
+			&repo,
+			None,
+			"fixup",
+			&HookContext::default(),
+			None,
+		)
+		.unwrap();
+
+		let HookResult::RunNotSuccessful { stdout, .. } = res else {
+			unreachable!()
+		};
+
+		assert_eq!(&stdout, "rejected\n");
+
+		let git_dir = repo.path();
+		assert!(!git_dir.join(HOOK_COMMIT_MSG_TEMP_FILE).exists());
+		assert!(!git_dir
+			.join(HOOK_COMMIT_MSG_PREVIEW_TEMP_FILE)
+			.exists());
 	}
 
 	#[test]
-	fn test_hooks_prep_commit_msg_reject() {
+	#[cfg(unix)]
+	fn test_hook_progress_fd() {
 		let (_td, repo) = repo_init();
 
 		let hook = b"#!/bin/sh
-echo \"$2,$3\" > \"$1\"
-echo 'rejected'
-exit 2
+echo 'step 1' >&3
+echo 'some output'
+echo 'step 2' >&3
+echo 'failed' >&2
+exit 1
         ";
 
-		create_hook(&repo, HOOK_PREPARE_COMMIT_MSG, hook);
+		create_hook(&repo, HOOK_PRE_COMMIT, hook);
 
-		let mut msg = String::from("test");
-		let res = hooks_prepare_commit_msg(
+		let res = run_hook_with_progress(
 			&repo,
 			None,
-			PrepareCommitMsgSource::Commit(git2::Oid::zero()),
-			&mut msg,
+			HOOK_PRE_COMMIT,
+			&[],
+			&HookContext::default(),
+			None,
 		)
 		.unwrap();
 
-		let HookResult::RunNotSuccessful { code, stdout, .. } = res
+		assert_eq!(res.progress, "step 1\nstep 2\n");
+
+		let HookResult::RunNotSuccessful { stdout, stderr, .. } =
+			res.result
 		else {
 			unreachable!()
 		};
 
-		assert_eq!(code.unwrap(), 2);
-		assert_eq!(&stdout, "rejected\n");
+		assert_eq!(stdout, "some output\n");
+		assert_eq!(stderr, "failed\n");
+
+		// the regular runners do not open the extra fd
+		let res = hooks_pre_commit(&repo, None).unwrap();
+		let HookResult::RunNotSuccessful { stderr, .. } = res else {
+			unreachable!()
+		};
+		assert!(stderr.contains('3'));
+	}
+
+	#[test]
+	fn test_hook_output_transcript() {
+		let (_td, repo) = repo_init();
+
+		let hook = b"#!/bin/sh
+echo 'out 1'
+sleep 0.1
+echo 'err 1' >&2
+sleep 0.1
+echo 'out 2'
+sleep 0.1
+echo 'err 2' >&2
+exit 1
+        ";
+
+		create_hook(&repo, HOOK_PRE_COMMIT, hook);
+
+		let res = run_hook_with_buffering(
+			&repo,
+			None,
+			HOOK_PRE_COMMIT,
+			&[],
+			&HookContext::default(),
+			None,
+			OutputBuffering::Timestamped,
+		)
+		.unwrap();
+
+		let transcript = res.transcript.unwrap();
 
 		assert_eq!(
-			msg,
-			String::from(
-				"commit,0000000000000000000000000000000000000000\n"
-			)
+			transcript.lines(),
+			vec![
+				(OutputStream::Stdout, String::from("out 1")),
+				(OutputStream::Stderr, String::from("err 1")),
+				(OutputStream::Stdout, String::from("out 2")),
+				(OutputStream::Stderr, String::from("err 2")),
+			]
 		);
+		assert_eq!(
+			transcript.interleaved(),
+			"out 1\nerr 1\nout 2\nerr 2\n"
+		);
+		assert!(transcript
+			.chunks
+			.windows(2)
+			.all(|pair| pair[0].at <= pair[1].at));
+
+		// the separate streams are still reported
+		let HookResult::RunNotSuccessful { stdout, stderr, .. } =
+			res.result
+		else {
+			unreachable!()
+		};
+
+		assert_eq!(stdout, "out 1\nout 2\n");
+		assert_eq!(stderr, "err 1\nerr 2\n");
+
+		let res = run_hook_with_buffering(
+			&repo,
+			None,
+			HOOK_PRE_COMMIT,
+			&[],
+			&HookContext::default(),
+			None,
+			OutputBuffering::Separate,
+		)
+		.unwrap();
+
+		assert!(res.transcript.is_none());
+		assert!(res.result.is_not_successful());
 	}
 
 	#[test]
-	fn test_hooks_timeout_kills() {
+	fn test_hook_output_raw_bytes() {
 		let (_td, repo) = repo_init();
 
-		let hook = b"#!/usr/bin/env sh
-sleep 0.25
+		// latin-1 encoded, e.g. by a localized tool
+		let hook = b"#!/bin/sh
+printf 'gr\\374n'
+printf '\\377err' >&2
+exit 1
+        ";
+
+		create_hook(&repo, HOOK_PRE_COMMIT, hook);
+
+		for buffering in
+			[OutputBuffering::Separate, OutputBuffering::Timestamped]
+		{
+			let res = run_hook_with_buffering(
+				&repo,
+				None,
+				HOOK_PRE_COMMIT,
+				&[],
+				&HookContext::default(),
+				None,
+				buffering,
+			)
+			.unwrap();
+
+			let HookResult::RunNotSuccessful {
+				stdout,
+				stderr,
+				stdout_bytes,
+				stderr_bytes,
+				..
+			} = res.result
+			else {
+				unreachable!("{:?}", res.result)
+			};
+
+			assert_eq!(stdout_bytes, b"gr\xfcn");
+			assert_eq!(stderr_bytes, b"\xfferr");
+			assert_eq!(stdout, "gr\u{fffd}n");
+			assert_eq!(stderr, "\u{fffd}err");
+		}
+	}
+
+	#[test]
+	fn test_timed_out_hook_output_not_utf8() {
+		let (_td, repo) = repo_init();
+
+		let hook = b"#!/bin/sh
+printf 'gr\\374n'
+printf '\\377err' >&2
+sleep 1
         ";
 
 		create_hook(&repo, HOOK_PRE_COMMIT, hook);
@@ -728,59 +2356,1695 @@ sleep 0.25
 		let res = hooks_pre_commit_with_timeout(
 			&repo,
 			None,
+			&HookContext::default(),
 			Some(Duration::from_millis(200)),
 		)
 		.unwrap();
 
-		assert!(res.is_timeout());
+		let HookResult::TimedOut { stdout, stderr, .. } = res else {
+			unreachable!("{res:?}")
+		};
+
+		assert_eq!(stdout, "gr\u{fffd}n");
+		assert_eq!(stderr, "\u{fffd}err");
 	}
 
 	#[test]
-	fn test_hooks_timeout_with_zero() {
+	fn test_hook_output_streamed() {
 		let (_td, repo) = repo_init();
 
-		let hook = b"#!/usr/bin/env sh
-sleep 0.25
+		let hook = b"#!/bin/sh
+echo 'out 1'
+sleep 0.3
+echo 'err 1' >&2
+sleep 0.3
+printf 'out 2'
+exit 1
         ";
 
 		create_hook(&repo, HOOK_PRE_COMMIT, hook);
 
-		let res = hooks_pre_commit_with_timeout(
+		let mut lines = Vec::new();
+		let res = run_hook_streaming(
 			&repo,
 			None,
-			Some(Duration::ZERO),
+			HOOK_PRE_COMMIT,
+			&[],
+			&HookContext::default(),
+			|line| lines.push((Instant::now(), line)),
+		)
+		.unwrap();
+
+		assert_eq!(
+			lines
+				.iter()
+				.map(|(_, line)| line.clone())
+				.collect::<Vec<_>>(),
+			vec![
+				HookOutputLine {
+					stream: OutputStream::Stdout,
+					line: String::from("out 1")
+				},
+				HookOutputLine {
+					stream: OutputStream::Stderr,
+					line: String::from("err 1")
+				},
+				HookOutputLine {
+					stream: OutputStream::Stdout,
+					line: String::from("out 2")
+				},
+			]
 		);
 
-		assert!(res.is_ok());
+		// every line arrived while the hook was still sleeping, not all
+		// of them once it exited
+		assert!(lines.windows(2).all(|pair| pair[1].0 - pair[0].0
+			>= Duration::from_millis(200)));
+
+		let HookResult::RunNotSuccessful { stdout, stderr, .. } = res
+		else {
+			unreachable!("{res:?}")
+		};
+		assert_eq!(stdout, "out 1\nout 2");
+		assert_eq!(stderr, "err 1\n");
 	}
 
 	#[test]
-	fn test_hooks_timeout_faster_than_timeout() {
+	#[cfg(unix)]
+	fn test_make_hooks_executable() {
+		use std::os::unix::fs::PermissionsExt;
+
 		let (_td, repo) = repo_init();
+		let hooks = repo.path().join("hooks");
+		std::fs::create_dir_all(hooks.join("folder")).unwrap();
 
-		let temp_dir = tempdir().expect("temp dir");
-		let file = temp_dir.path().join("test");
-		let hook = format!(
-			"#!/usr/bin/env sh
-sleep 0.1
-echo 'after sleep' > {}
-        ",
-			file.to_str().unwrap()
+		let mode = |name: &str| {
+			hooks.join(name).metadata().unwrap().permissions().mode()
+				& 0o777
+		};
+		let write = |name: &str, mode: u32| {
+			let path = hooks.join(name);
+			std::fs::write(&path, "#!/bin/sh\nexit 0").unwrap();
+			std::fs::set_permissions(
+				&path,
+				std::fs::Permissions::from_mode(mode),
+			)
+			.unwrap();
+		};
+
+		write(HOOK_PRE_COMMIT, 0o644);
+		write(HOOK_COMMIT_MSG, 0o600);
+		write(HOOK_POST_COMMIT, 0o700);
+		write("pre-push.sample", 0o644);
+
+		let changed = make_hooks_executable(&repo).unwrap();
+
+		assert_eq!(
+			changed,
+			vec![
+				hooks.join(HOOK_COMMIT_MSG),
+				hooks.join(HOOK_PRE_COMMIT)
+			]
 		);
+		assert_eq!(mode(HOOK_PRE_COMMIT), 0o755);
+		assert_eq!(mode(HOOK_COMMIT_MSG), 0o755);
+		assert_eq!(mode(HOOK_POST_COMMIT), 0o700);
+		assert_eq!(mode("pre-push.sample"), 0o644);
 
-		create_hook(&repo, HOOK_PRE_COMMIT, hook.as_bytes());
+		assert!(hooks_pre_commit(&repo, None).unwrap().is_ok());
+		assert!(make_hooks_executable(&repo).unwrap().is_empty());
+	}
 
-		let res = hooks_pre_commit_with_timeout(
-			&repo,
-			None,
-			Some(Duration::from_millis(150)),
+	#[test]
+	fn test_hook_name_other_case() {
+		let (_td, repo) = repo_init();
+
+		let hooks = repo.path().join("hooks");
+		create_hook_in_path(
+			&hooks.join("Pre-Commit"),
+			b"#!/bin/sh\nexit 1",
 		);
 
-		assert!(res.is_ok());
-		assert!(file.exists());
+		let hook =
+			HookPaths::new(&repo, None, HOOK_PRE_COMMIT).unwrap();
+		assert!(!hook.found());
+		assert_eq!(hook.hook, hooks.join(HOOK_PRE_COMMIT));
 
-		let mut str = String::new();
-		File::open(file).unwrap().read_to_string(&mut str).unwrap();
-		assert!(str == "after sleep\n");
+		assert_eq!(
+			hooks_pre_commit(&repo, None).unwrap(),
+			HookResult::NoHookFound
+		);
+
+		// an exact match in another path is preferred
+		let other = repo.path().join("other");
+		std::fs::create_dir_all(&other).unwrap();
+		create_hook_in_path(
+			&other.join(HOOK_PRE_COMMIT),
+			b"#!/bin/sh\nexit 0",
+		);
+
+		let hook =
+			HookPaths::new(&repo, Some(&["other"]), HOOK_PRE_COMMIT)
+				.unwrap();
+		assert!(hook.found());
+		assert_eq!(hook.hook, other.join(HOOK_PRE_COMMIT));
+	}
+
+	#[test]
+	fn test_hook_is_directory() {
+		let (_td, repo) = repo_init();
+
+		let hook_dir =
+			repo.path().join("hooks").join(HOOK_PRE_COMMIT);
+		std::fs::create_dir_all(&hook_dir).unwrap();
+
+		#[cfg(unix)]
+		{
+			use std::os::unix::fs::PermissionsExt;
+
+			std::fs::set_permissions(
+				&hook_dir,
+				std::fs::Permissions::from_mode(0o755),
+			)
+			.unwrap();
+		}
+
+		let res = hooks_pre_commit(&repo, None);
+
+		assert!(
+			matches!(
+				res,
+				Err(HooksError::HookIsDirectory { ref path })
+					if path == &hook_dir
+			),
+			"{res:?}"
+		);
+	}
+
+	#[test]
+	fn test_active_hooks() {
+		let (_td, repo) = repo_init();
+		let git_dir = repo.path().to_path_buf();
+
+		let hook = b"#!/bin/sh
+sleep 1
+exit 0
+        ";
+
+		create_hook(&repo, "registry-hook", hook);
+
+		let ours = |run: &HookRun| run.git_dir == git_dir;
+
+		let handle = std::thread::spawn(move || {
+			run_hook_raw(
+				&repo,
+				None,
+				"registry-hook",
+				&[],
+				&HookContext::default(),
+				None,
+			)
+		});
+
+		let start = Instant::now();
+		let run = loop {
+			if let Some(run) = active_hooks().into_iter().find(ours) {
+				break run;
+			}
+			assert!(start.elapsed() < Duration::from_secs(5));
+			std::thread::sleep(Duration::from_millis(10));
+		};
+
+		assert_eq!(run.name, "registry-hook");
+		assert_ne!(run.pid, 0);
+
+		assert!(handle.join().unwrap().unwrap().is_ok());
+
+		assert!(!active_hooks().iter().any(ours));
+
+		let finished = recent_hooks()
+			.into_iter()
+			.find(|finished| ours(&finished.run))
+			.unwrap();
+
+		assert_eq!(finished.run, run);
+		assert_eq!(finished.outcome, HookOutcome::Ok);
+		assert!(finished.duration >= Duration::from_millis(500));
+	}
+
+	#[test]
+	fn test_run_hook_raw() {
+		let (_td, repo) = repo_init();
+
+		let argv = [
+			"plain",
+			"with space",
+			"single ' quote",
+			"double \" quote",
+			"$HOME",
+			"",
+			"--flag=*",
+		];
+
+		assert_eq!(
+			run_hook_raw(
+				&repo,
+				None,
+				"my-hook",
+				&argv,
+				&HookContext::default(),
+				None
+			)
+			.unwrap(),
+			HookResult::NoHookFound
+		);
+
+		let hook = br#"#!/bin/sh
+for arg in "$@"; do
+	echo "[$arg]"
+done
+exit 1
+        "#;
+
+		create_hook(&repo, "my-hook", hook);
+
+		let res = run_hook_raw(
+			&repo,
+			None,
+			"my-hook",
+			&argv,
+			&HookContext::default(),
+			None,
+		)
+		.unwrap();
+
+		let HookResult::RunNotSuccessful { stdout, .. } = res else {
+			unreachable!("{res:?}")
+		};
+
+		let expected: String = argv
+			.iter()
+			.map(|arg| ["[", arg, "]\n"].concat())
+			.collect();
+
+		assert_eq!(stdout, expected);
+	}
+
+	#[test]
+	fn test_test_hook_script() {
+		let script = br#"#!/bin/sh
+for arg in "$@"; do
+	echo "[$arg]"
+done
+cat
+echo "pwd: $(pwd)" >&2
+exit 1
+        "#;
+
+		let res = test_hook_script(
+			script,
+			&["plain", "with space"],
+			Some(b"from stdin\n"),
+		)
+		.unwrap();
+
+		let HookResult::RunNotSuccessful {
+			stdout,
+			stderr,
+			hook,
+			..
+		} = res
+		else {
+			unreachable!("{res:?}")
+		};
+
+		assert_eq!(stdout, "[plain]\n[with space]\nfrom stdin\n");
+
+		// ran inside the temporary directory which is gone again
+		let dir = hook.parent().unwrap();
+		assert!(!dir.exists());
+		assert!(stderr
+			.contains(dir.file_name().unwrap().to_str().unwrap()));
+	}
+
+	#[test]
+	fn test_test_hook_script_no_stdin() {
+		let res =
+			test_hook_script(b"#!/bin/sh\ncat\nexit 0\n", &[], None)
+				.unwrap();
+
+		assert!(res.is_ok());
+	}
+
+	#[test]
+	fn test_hooks_warmup() {
+		let (_td, repo) = repo_init();
+
+		assert_eq!(
+			hooks_warmup(&repo, None, HOOK_PRE_COMMIT, &[]).unwrap(),
+			None
+		);
+
+		let hook = b"#!/bin/sh
+sleep 0.1
+exit 0
+        ";
+
+		create_hook(&repo, HOOK_PRE_COMMIT, hook);
+
+		let res = hooks_warmup(&repo, None, HOOK_PRE_COMMIT, &[])
+			.unwrap()
+			.unwrap();
+
+		assert!(res.cold.result.is_ok());
+		assert!(res.warm.result.is_ok());
+		assert!(res.cold.duration >= Duration::from_millis(100));
+		assert!(res.warm.duration >= Duration::from_millis(100));
+		assert!(res.cold_start_penalty() <= res.cold.duration);
+	}
+
+	#[test]
+	fn test_hook_pwd_in_bare_without_workdir() {
+		let (_td, repo) = repo_init_bare();
+		let git_root = repo.path().to_path_buf();
+
+		let hook =
+			HookPaths::new(&repo, None, HOOK_POST_COMMIT).unwrap();
+
+		assert_eq!(hook.pwd, git_root);
+	}
+
+	#[test]
+	fn test_hook_pwd() {
+		let (_td, repo) = repo_init();
+		let git_root = repo.path().to_path_buf();
+
+		let hook =
+			HookPaths::new(&repo, None, HOOK_POST_COMMIT).unwrap();
+
+		assert_eq!(hook.pwd, git_root.parent().unwrap());
+	}
+
+	#[test]
+	fn test_hook_pwd_server_side() {
+		let (_td, repo) = repo_init();
+		let git_root = repo.path().to_path_buf();
+
+		for hook in [
+			HOOK_PRE_RECEIVE,
+			HOOK_UPDATE,
+			HOOK_PROC_RECEIVE,
+			HOOK_POST_RECEIVE,
+			HOOK_POST_UPDATE,
+		] {
+			let hook = HookPaths::new(&repo, None, hook).unwrap();
+			assert_eq!(hook.pwd, git_root);
+		}
+	}
+
+	#[test]
+	fn test_hook_cwd_per_hook_type() {
+		let (_td, repo) = repo_init();
+
+		let hook = b"#!/bin/sh
+pwd
+exit 1
+        ";
+
+		let cwd_of = |name: &str| {
+			create_hook(&repo, name, hook);
+
+			let res = run_hook_raw(
+				&repo,
+				None,
+				name,
+				&[],
+				&HookContext::default(),
+				None,
+			)
+			.unwrap();
+			let HookResult::RunNotSuccessful { stdout, .. } = res
+			else {
+				unreachable!("{res:?}")
+			};
+
+			Path::new(stdout.trim()).canonicalize().unwrap()
+		};
+
+		let git_dir = repo.path().canonicalize().unwrap();
+		let workdir = repo.workdir().unwrap().canonicalize().unwrap();
+
+		assert_eq!(cwd_of(HOOK_PRE_RECEIVE), git_dir);
+		assert_eq!(cwd_of(HOOK_POST_UPDATE), git_dir);
+		assert_eq!(cwd_of(HOOK_PRE_COMMIT), workdir);
+		assert_eq!(cwd_of(HOOK_PRE_PUSH), workdir);
+	}
+
+	#[test]
+	fn test_hooks_prep_commit_msg_success() {
+		let (_td, repo) = repo_init();
+
+		let hook = b"#!/bin/sh
+echo \"msg:$2\" > \"$1\"
+exit 0
+        ";
+
+		create_hook(&repo, HOOK_PREPARE_COMMIT_MSG, hook);
+
+		let mut msg = String::from("test");
+		let res = hooks_prepare_commit_msg(
+			&repo,
+			None,
+			PrepareCommitMsgSource::Message,
+			&mut msg,
+		)
+		.unwrap();
+
+		assert!(matches!(res, HookResult::Ok { .. }));
+		assert_eq!(msg, String::from("msg:message\n"));
+	}
+
+	#[test]
+	fn test_hooks_prep_commit_msg_reject() {
+		let (_td, repo) = repo_init();
+
+		let hook = b"#!/bin/sh
+echo \"$2,$3\" > \"$1\"
+echo 'rejected'
+exit 2
+        ";
+
+		create_hook(&repo, HOOK_PREPARE_COMMIT_MSG, hook);
+
+		let mut msg = String::from("test");
+		let res = hooks_prepare_commit_msg(
+			&repo,
+			None,
+			PrepareCommitMsgSource::Commit(git2::Oid::zero()),
+			&mut msg,
+		)
+		.unwrap();
+
+		let HookResult::RunNotSuccessful { code, stdout, .. } = res
+		else {
+			unreachable!()
+		};
+
+		assert_eq!(code.unwrap(), 2);
+		assert_eq!(&stdout, "rejected\n");
+
+		assert_eq!(
+			msg,
+			String::from(
+				"commit,0000000000000000000000000000000000000000\n"
+			)
+		);
+	}
+
+	#[test]
+	fn test_hooks_prep_commit_msg_timeout_keeps_msg() {
+		let (_td, repo) = repo_init();
+
+		let hook = b"#!/bin/sh
+echo 'rewritten' > \"$1\"
+sleep 5
+        ";
+
+		create_hook(&repo, HOOK_PREPARE_COMMIT_MSG, hook);
+
+		let mut msg = String::from("test");
+		let res = hooks_prepare_commit_msg_with_timeout(
+			&repo,
+			None,
+			PrepareCommitMsgSource::Message,
+			&mut msg,
+			&HookContext::default(),
+			Some(Duration::from_millis(500)),
+		)
+		.unwrap();
+
+		assert!(matches!(res, HookResult::TimedOut { .. }));
+		assert_eq!(msg, String::from("rewritten\n"));
+	}
+
+	/// runs a pre-push hook printing its arguments and standard input
+	/// before rejecting the push, returns what it printed
+	fn post_checkout_args(
+		repo: &Repository,
+		prev_head: Oid,
+		new_head: Oid,
+		branch_checkout: bool,
+	) -> String {
+		let hook = b"#!/bin/sh
+echo \"$@\"
+exit 1
+";
+		create_hook(repo, HOOK_POST_CHECKOUT, hook);
+
+		let result = hooks_post_checkout(
+			repo,
+			None,
+			prev_head,
+			new_head,
+			branch_checkout,
+		)
+		.unwrap();
+
+		let HookResult::RunNotSuccessful { stdout, .. } = result
+		else {
+			unreachable!("{result:?}")
+		};
+		stdout
+	}
+
+	#[test]
+	fn test_post_checkout_branch() {
+		let (_td, repo) = repo_init();
+
+		let prev_head = repo.head().unwrap().target().unwrap();
+		let new_head = Oid::from_str(&"1".repeat(40)).unwrap();
+
+		assert_eq!(
+			post_checkout_args(&repo, prev_head, new_head, true),
+			format!("{prev_head} {new_head} 1\n")
+		);
+	}
+
+	#[test]
+	fn test_post_checkout_file() {
+		let (_td, repo) = repo_init();
+
+		let head = repo.head().unwrap().target().unwrap();
+
+		assert_eq!(
+			post_checkout_args(&repo, head, head, false),
+			format!("{head} {head} 0\n")
+		);
+	}
+
+	#[test]
+	fn test_post_merge_squash_flag() {
+		let (_td, repo) = repo_init();
+
+		// only accepts merges that are not squashed
+		let hook = b"#!/bin/sh
+test \"$1\" = 0
+";
+		create_hook(&repo, HOOK_POST_MERGE, hook);
+
+		assert!(hooks_post_merge(&repo, None, false)
+			.unwrap()
+			.is_ok());
+		assert!(matches!(
+			hooks_post_merge(&repo, None, true).unwrap(),
+			HookResult::RunNotSuccessful { code: Some(1), .. }
+		));
+	}
+
+	#[test]
+	fn test_post_merge_fails() {
+		let (_td, repo) = repo_init();
+
+		let hook = b"#!/bin/sh
+echo 'rebuild failed' >&2
+exit 2
+";
+		create_hook(&repo, HOOK_POST_MERGE, hook);
+
+		let result = hooks_post_merge(&repo, None, false).unwrap();
+
+		let HookResult::RunNotSuccessful { code, stderr, .. } =
+			result
+		else {
+			unreachable!("{result:?}")
+		};
+		assert_eq!(code, Some(2));
+		assert_eq!(stderr, "rebuild failed\n");
+	}
+
+	/// the arguments a rejecting pre-rebase hook got, one per line
+	/// after their count
+	fn pre_rebase_args(
+		repo: &Repository,
+		upstream: &str,
+		branch: Option<&str>,
+	) -> String {
+		let hook = b"#!/bin/sh
+echo $#
+for arg in \"$@\"; do echo \"$arg\"; done
+exit 1
+";
+		create_hook(repo, HOOK_PRE_REBASE, hook);
+
+		let result =
+			hooks_pre_rebase(repo, None, upstream, branch).unwrap();
+
+		let HookResult::RunNotSuccessful { stdout, .. } = result
+		else {
+			unreachable!("{result:?}")
+		};
+		stdout
+	}
+
+	#[test]
+	fn test_pre_rebase_current_branch() {
+		let (_td, repo) = repo_init();
+
+		assert_eq!(
+			pre_rebase_args(&repo, "origin/main", None),
+			"1\norigin/main\n"
+		);
+	}
+
+	#[test]
+	fn test_pre_rebase_other_branch() {
+		let (_td, repo) = repo_init();
+
+		assert_eq!(
+			pre_rebase_args(&repo, "main", Some("feature")),
+			"2\nmain\nfeature\n"
+		);
+	}
+
+	#[test]
+	fn test_pre_rebase_ok() {
+		let (_td, repo) = repo_init();
+
+		create_hook(&repo, HOOK_PRE_REBASE, b"#!/bin/sh\nexit 0");
+
+		assert!(hooks_pre_rebase(&repo, None, "main", None)
+			.unwrap()
+			.is_ok());
+	}
+
+	#[test]
+	fn test_post_rewrite_args_and_stdin() {
+		let (_td, repo) = repo_init();
+
+		let hook = b"#!/bin/sh
+echo \"$# $1\"
+cat
+exit 1
+";
+		create_hook(&repo, HOOK_POST_REWRITE, hook);
+
+		let first = (
+			Oid::from_str("1111111111111111111111111111111111111111")
+				.unwrap(),
+			Oid::from_str("2222222222222222222222222222222222222222")
+				.unwrap(),
+		);
+		let second = (
+			Oid::from_str("3333333333333333333333333333333333333333")
+				.unwrap(),
+			Oid::from_str("4444444444444444444444444444444444444444")
+				.unwrap(),
+		);
+
+		let result = hooks_post_rewrite(
+			&repo,
+			None,
+			PostRewriteSource::Rebase,
+			&[first, second],
+		)
+		.unwrap();
+
+		let HookResult::RunNotSuccessful { stdout, .. } = result
+		else {
+			unreachable!("{result:?}")
+		};
+		assert_eq!(
+			stdout,
+			"1 rebase
+1111111111111111111111111111111111111111 2222222222222222222222222222222222222222
+3333333333333333333333333333333333333333 4444444444444444444444444444444444444444
+"
+		);
+	}
+
+	#[test]
+	fn test_post_rewrite_amend() {
+		let (_td, repo) = repo_init();
+
+		let hook = b"#!/bin/sh
+test \"$1\" = amend || exit 2
+cat
+exit 1
+";
+		create_hook(&repo, HOOK_POST_REWRITE, hook);
+
+		let head = repo.head().unwrap().target().unwrap();
+		let result = hooks_post_rewrite(
+			&repo,
+			None,
+			PostRewriteSource::Amend,
+			&[(head, head)],
+		)
+		.unwrap();
+
+		let HookResult::RunNotSuccessful { code, stdout, .. } =
+			result
+		else {
+			unreachable!("{result:?}")
+		};
+		assert_eq!(code, Some(1));
+		assert_eq!(stdout, format!("{head} {head}\n"));
+	}
+
+	#[test]
+	fn test_reference_transaction_states_and_stdin() {
+		let (_td, repo) = repo_init();
+
+		let hook = b"#!/bin/sh
+echo \"$# $1\"
+cat
+exit 1
+";
+		create_hook(&repo, HOOK_REFERENCE_TRANSACTION, hook);
+
+		let head = repo.head().unwrap().target().unwrap();
+		let updates = [
+			RefUpdate {
+				old_oid: head,
+				new_oid: Oid::zero(),
+				refname: String::from("refs/heads/old"),
+			},
+			RefUpdate {
+				old_oid: Oid::zero(),
+				new_oid: head,
+				refname: String::from("refs/heads/new"),
+			},
+		];
+		let zero = Oid::zero();
+
+		for (state, arg) in [
+			(ReferenceTransactionState::Prepared, "prepared"),
+			(ReferenceTransactionState::Committed, "committed"),
+			(ReferenceTransactionState::Aborted, "aborted"),
+		] {
+			let result = hooks_reference_transaction(
+				&repo, None, state, &updates,
+			)
+			.unwrap();
+
+			let HookResult::RunNotSuccessful { stdout, .. } = result
+			else {
+				unreachable!("{result:?}")
+			};
+			assert_eq!(
+				stdout,
+				format!(
+					"1 {arg}\n{head} {zero} refs/heads/old\n{zero} {head} refs/heads/new\n"
+				)
+			);
+		}
+	}
+
+	fn pre_push_input(
+		repo: &Repository,
+		refs: &[PrePushRef],
+	) -> String {
+		let hook = b"#!/bin/sh
+echo \"$1 $2\"
+cat
+exit 1
+";
+		create_hook(repo, HOOK_PRE_PUSH, hook);
+
+		let result = hooks_pre_push(
+			repo,
+			None,
+			"origin",
+			"https://example.com/repo.git",
+			refs,
+		)
+		.unwrap();
+
+		let HookResult::RunNotSuccessful { stdout, .. } = result
+		else {
+			unreachable!("{result:?}")
+		};
+		stdout
+	}
+
+	#[test]
+	fn test_pre_push_refs() {
+		let (_td, repo) = repo_init();
+
+		let head = repo.head().unwrap().target().unwrap();
+		let remote = Oid::from_str(&"1".repeat(40)).unwrap();
+
+		let refs = [
+			PrePushRef {
+				local_ref: String::from("refs/heads/master"),
+				local_oid: head,
+				remote_ref: String::from("refs/heads/master"),
+				remote_oid: remote,
+			},
+			PrePushRef {
+				local_ref: String::from("refs/heads/feature"),
+				local_oid: head,
+				remote_ref: String::from("refs/heads/new"),
+				remote_oid: Oid::zero(),
+			},
+			PrePushRef {
+				local_ref: String::from(PrePushRef::DELETE),
+				local_oid: Oid::zero(),
+				remote_ref: String::from("refs/heads/old"),
+				remote_oid: remote,
+			},
+		];
+
+		let zero = "0".repeat(40);
+		assert_eq!(
+			pre_push_input(&repo, &refs),
+			format!(
+				"origin https://example.com/repo.git
+refs/heads/master {head} refs/heads/master {remote}
+refs/heads/feature {head} refs/heads/new {zero}
+(delete) {zero} refs/heads/old {remote}
+"
+			)
+		);
+	}
+
+	#[test]
+	fn test_pre_push_no_refs() {
+		let (_td, repo) = repo_init();
+
+		assert_eq!(
+			pre_push_input(&repo, &[]),
+			"origin https://example.com/repo.git\n"
+		);
+	}
+
+	#[test]
+	fn test_pre_push_many_refs() {
+		let (_td, repo) = repo_init();
+
+		// far more than fits into a pipe buffer, the hook echoes its
+		// input while it is still being written
+		let head = repo.head().unwrap().target().unwrap();
+		let refs = (0..5000)
+			.map(|i| PrePushRef {
+				local_ref: format!("refs/heads/branch-{i}"),
+				local_oid: head,
+				remote_ref: format!("refs/heads/branch-{i}"),
+				remote_oid: Oid::zero(),
+			})
+			.collect::<Vec<_>>();
+
+		let output = pre_push_input(&repo, &refs);
+
+		assert_eq!(output.lines().count(), refs.len() + 1);
+		assert_eq!(
+			output.lines().last().unwrap(),
+			format!(
+				"refs/heads/branch-4999 {head} refs/heads/branch-4999 {}",
+				Oid::zero()
+			)
+		);
+	}
+
+	#[test]
+	fn test_pre_push_ok() {
+		let (_td, repo) = repo_init();
+
+		// a hook not reading its input must not block on it
+		create_hook(&repo, HOOK_PRE_PUSH, b"#!/bin/sh\nexit 0\n");
+
+		let res = hooks_pre_push(&repo, None, "origin", "url", &[])
+			.unwrap();
+		assert!(res.is_ok());
+	}
+
+	#[test]
+	fn test_hooks_timeout_kills() {
+		let (_td, repo) = repo_init();
+
+		let hook = b"#!/usr/bin/env sh
+sleep 0.25
+        ";
+
+		create_hook(&repo, HOOK_PRE_COMMIT, hook);
+
+		let res = hooks_pre_commit_with_timeout(
+			&repo,
+			None,
+			&HookContext::default(),
+			Some(Duration::from_millis(200)),
+		)
+		.unwrap();
+
+		assert!(res.is_timeout());
+	}
+
+	#[test]
+	fn test_hooks_timeout_kills_process_tree() {
+		let (_td, repo) = repo_init();
+
+		let temp_dir = tempdir().expect("temp dir");
+		let file = temp_dir.path().join("test");
+		// the sleeper in the background outlives the hook's shell if
+		// only that is killed
+		let hook = format!(
+			"#!/usr/bin/env sh
+(sleep 0.5; echo 'after sleep' > {}) &
+sleep 5
+        ",
+			file.to_str().unwrap()
+		);
+
+		create_hook(&repo, HOOK_PRE_COMMIT, hook.as_bytes());
+
+		let res = hooks_pre_commit_with_timeout(
+			&repo,
+			None,
+			&HookContext::default(),
+			Some(Duration::from_millis(200)),
+		)
+		.unwrap();
+		assert!(res.is_timeout());
+
+		std::thread::sleep(Duration::from_millis(700));
+		assert!(!file.exists());
+	}
+
+	#[test]
+	fn test_hooks_timeout_keeps_output() {
+		let (_td, repo) = repo_init();
+
+		let hook = b"#!/usr/bin/env sh
+echo 'before stalling'
+sleep 5
+        ";
+
+		create_hook(&repo, HOOK_PRE_COMMIT, hook);
+
+		let res = hooks_pre_commit_with_timeout(
+			&repo,
+			None,
+			&HookContext::default(),
+			Some(Duration::from_millis(200)),
+		)
+		.unwrap();
+
+		let HookResult::TimedOut { stdout, stderr, .. } = res else {
+			unreachable!("{res:?}")
+		};
+		assert_eq!(stdout, "before stalling\n");
+		assert!(stderr.is_empty());
+	}
+
+	#[test]
+	fn test_hooks_timeout_keeps_large_output() {
+		let (_td, repo) = repo_init();
+
+		// more than fits into the pipe buffer
+		let hook = b"#!/usr/bin/env sh
+head -c 200000 /dev/zero | tr '\\0' a
+echo 'to stderr' >&2
+sleep 5
+        ";
+
+		create_hook(&repo, HOOK_PRE_COMMIT, hook);
+
+		let start = Instant::now();
+		let res = hooks_pre_commit_with_timeout(
+			&repo,
+			None,
+			&HookContext::default(),
+			Some(Duration::from_millis(500)),
+		)
+		.unwrap();
+
+		assert!(start.elapsed() < Duration::from_secs(4));
+		let HookResult::TimedOut { stdout, stderr, .. } = res else {
+			unreachable!("{res:?}")
+		};
+		assert_eq!(stdout.len(), 200_000);
+		assert!(stdout.bytes().all(|b| b == b'a'));
+		assert_eq!(stderr, "to stderr\n");
+	}
+
+	#[test]
+	fn test_hooks_large_output_within_timeout() {
+		let (_td, repo) = repo_init();
+
+		let hook = b"#!/usr/bin/env sh
+head -c 200000 /dev/zero | tr '\\0' a
+exit 0
+        ";
+
+		create_hook(&repo, HOOK_PRE_COMMIT, hook);
+
+		let res = hooks_pre_commit_with_timeout(
+			&repo,
+			None,
+			&HookContext::default(),
+			Some(Duration::from_secs(5)),
+		)
+		.unwrap();
+
+		assert!(res.is_ok(), "{res:?}");
+	}
+
+	#[test]
+	fn test_hooks_cancelled() {
+		let (_td, repo) = repo_init();
+
+		let hook = b"#!/usr/bin/env sh
+echo 'before stalling'
+sleep 5
+        ";
+
+		create_hook(&repo, HOOK_PRE_COMMIT, hook);
+
+		let token = CancellationToken::new();
+		let cancel = {
+			let token = token.clone();
+			std::thread::spawn(move || {
+				std::thread::sleep(Duration::from_millis(200));
+				token.cancel();
+			})
+		};
+
+		let ctx = HookContext {
+			cancel: Some(&token),
+			..Default::default()
+		};
+		let start = Instant::now();
+		let res =
+			hooks_pre_commit_with_timeout(&repo, None, &ctx, None)
+				.unwrap();
+		cancel.join().unwrap();
+
+		assert!(start.elapsed() < Duration::from_secs(4));
+		let HookResult::Cancelled { stdout, .. } = res else {
+			unreachable!("{res:?}")
+		};
+		assert_eq!(stdout, "before stalling\n");
+
+		// not even started once cancelled
+		let res =
+			hooks_pre_commit_with_timeout(&repo, None, &ctx, None)
+				.unwrap();
+		assert!(res.is_cancelled());
+
+		// only runs given the token are affected
+		create_hook(&repo, HOOK_PRE_COMMIT, b"#!/bin/sh\nexit 0\n");
+		assert!(hooks_pre_commit(&repo, None).unwrap().is_ok());
+	}
+
+	#[test]
+	#[cfg(unix)]
+	fn test_hooks_timeout_terminates_first() {
+		let (_td, repo) = repo_init();
+
+		let temp_dir = tempdir().expect("temp dir");
+		let file = temp_dir.path().join("cleaned");
+		// `wait` is interrupted by the trap unlike a foreground `sleep`
+		let hook = format!(
+			"#!/usr/bin/env sh
+trap 'echo cleaned > {}; exit 1' TERM
+sleep 5 &
+wait
+        ",
+			file.to_str().unwrap()
+		);
+
+		create_hook(&repo, HOOK_PRE_COMMIT, hook.as_bytes());
+
+		let res = hooks_pre_commit_with_timeout(
+			&repo,
+			None,
+			&HookContext::default(),
+			Some(Duration::from_millis(200)),
+		)
+		.unwrap();
+		assert!(res.is_timeout());
+		assert!(file.exists());
+
+		// no time to clean up
+		repo.config()
+			.unwrap()
+			.set_i64("gitui.hooks.killGrace", 0)
+			.unwrap();
+		std::fs::remove_file(&file).unwrap();
+
+		let res = hooks_pre_commit_with_timeout(
+			&repo,
+			None,
+			&HookContext::default(),
+			Some(Duration::from_millis(200)),
+		)
+		.unwrap();
+		assert!(res.is_timeout());
+		std::thread::sleep(Duration::from_millis(100));
+		assert!(!file.exists());
+	}
+
+	#[test]
+	#[cfg(unix)]
+	fn test_hooks_timeout_kills_after_grace() {
+		let (_td, repo) = repo_init();
+
+		let hook = b"#!/usr/bin/env sh
+trap '' TERM
+sleep 5
+        ";
+
+		create_hook(&repo, HOOK_PRE_COMMIT, hook);
+
+		let start = Instant::now();
+		let res = hooks_pre_commit_with_timeout(
+			&repo,
+			None,
+			&HookContext::default(),
+			Some(Duration::from_millis(200)),
+		)
+		.unwrap();
+		assert!(res.is_timeout());
+		assert!(start.elapsed() < Duration::from_secs(2));
+	}
+
+	#[test]
+	fn test_hooks_timeout_with_zero() {
+		let (_td, repo) = repo_init();
+
+		let hook = b"#!/usr/bin/env sh
+sleep 0.25
+        ";
+
+		create_hook(&repo, HOOK_PRE_COMMIT, hook);
+
+		let res = hooks_pre_commit_with_timeout(
+			&repo,
+			None,
+			&HookContext::default(),
+			Some(Duration::ZERO),
+		);
+
+		assert!(res.is_ok());
+	}
+
+	#[test]
+	fn test_hooks_timeout_faster_than_timeout() {
+		let (_td, repo) = repo_init();
+
+		let temp_dir = tempdir().expect("temp dir");
+		let file = temp_dir.path().join("test");
+		let hook = format!(
+			"#!/usr/bin/env sh
+sleep 0.1
+echo 'after sleep' > {}
+        ",
+			file.to_str().unwrap()
+		);
+
+		create_hook(&repo, HOOK_PRE_COMMIT, hook.as_bytes());
+
+		let res = hooks_pre_commit_with_timeout(
+			&repo,
+			None,
+			&HookContext::default(),
+			Some(Duration::from_millis(150)),
+		);
+
+		assert!(res.is_ok());
+		assert!(file.exists());
+
+		let mut str = String::new();
+		File::open(file).unwrap().read_to_string(&mut str).unwrap();
+		assert!(str == "after sleep\n");
+	}
+
+	#[test]
+	fn test_hooks_deadline() {
+		let (_td, repo) = repo_init();
+
+		let hook = b"#!/usr/bin/env sh
+sleep 0.2
+        ";
+
+		create_hook(&repo, HOOK_PRE_COMMIT, hook);
+
+		// already passed: killed right away
+		let start = Instant::now();
+		let res = run_hook_with_deadline(
+			&repo,
+			None,
+			HOOK_PRE_COMMIT,
+			&[],
+			&HookContext::default(),
+			start
+				.checked_sub(Duration::from_secs(1))
+				.unwrap_or(start),
+		)
+		.unwrap();
+
+		assert!(res.is_timeout());
+		assert!(start.elapsed() < Duration::from_millis(200));
+
+		let res = run_hook_with_deadline(
+			&repo,
+			None,
+			HOOK_PRE_COMMIT,
+			&[],
+			&HookContext::default(),
+			Instant::now() + Duration::from_secs(5),
+		)
+		.unwrap();
+
+		assert!(res.is_ok());
+	}
+
+	#[test]
+	#[cfg(unix)]
+	fn test_hook_killed_externally() {
+		let (_td, repo) = repo_init();
+
+		// stands in for the OOM killer
+		create_hook(
+			&repo,
+			HOOK_PRE_COMMIT,
+			b"#!/bin/sh\nkill -KILL $$\n",
+		);
+
+		let res = hooks_pre_commit(&repo, None).unwrap();
+
+		let HookResult::Terminated {
+			signal, likely_oom, ..
+		} = res
+		else {
+			unreachable!("{res:?}")
+		};
+		assert_eq!(signal, 9);
+		assert!(likely_oom);
+
+		// a hook writing output before is not what the OOM killer
+		// leaves behind
+		create_hook(
+			&repo,
+			HOOK_PRE_COMMIT,
+			b"#!/bin/sh\necho 'working'\nkill -KILL $$\n",
+		);
+
+		let res = hooks_pre_commit(&repo, None).unwrap();
+
+		let HookResult::Terminated {
+			likely_oom, stdout, ..
+		} = res
+		else {
+			unreachable!("{res:?}")
+		};
+		assert!(!likely_oom);
+		assert_eq!(stdout, "working\n");
+
+		create_hook(
+			&repo,
+			HOOK_PRE_COMMIT,
+			b"#!/bin/sh\nkill -TERM $$\n",
+		);
+
+		let res = hooks_pre_commit(&repo, None).unwrap();
+
+		assert!(matches!(
+			res,
+			HookResult::Terminated {
+				signal: 15,
+				likely_oom: false,
+				..
+			}
+		));
+
+		repo.config()
+			.unwrap()
+			.set_str("gitui.hooks.terminated", "failure")
+			.unwrap();
+
+		let res = hooks_pre_commit(&repo, None).unwrap();
+
+		assert!(matches!(
+			res,
+			HookResult::RunNotSuccessful { code: None, .. }
+		));
+	}
+
+	/// `GIT_DIR`, `GIT_WORK_TREE` and `GIT_PREFIX` as seen by a hook
+	const PRINT_GIT_ENV: &[u8] = b"#!/bin/sh
+echo \"dir=$GIT_DIR\"
+echo \"work_tree=${GIT_WORK_TREE-unset}\"
+echo \"prefix=${GIT_PREFIX-unset}\"
+exit 1
+";
+
+	fn git_env_of(res: HookResult) -> String {
+		let HookResult::RunNotSuccessful { stdout, .. } = res else {
+			unreachable!("{res:?}")
+		};
+		stdout
+	}
+
+	fn without_separator(path: &Path) -> String {
+		path.components()
+			.collect::<PathBuf>()
+			.to_string_lossy()
+			.into_owned()
+	}
+
+	#[test]
+	fn test_git_env() {
+		let (_td, repo) = repo_init();
+		let workdir = repo.workdir().unwrap();
+		let subfolder = workdir.join("sub/dir");
+		std::fs::create_dir_all(&subfolder).unwrap();
+
+		create_hook(&repo, HOOK_PRE_COMMIT, PRINT_GIT_ENV);
+
+		let ctx = HookContext {
+			invocation_dir: Some(&subfolder),
+			..Default::default()
+		};
+		let res =
+			hooks_pre_commit_with_timeout(&repo, None, &ctx, None)
+				.unwrap();
+
+		assert_eq!(
+			git_env_of(res),
+			format!(
+				"dir={}\nwork_tree={}\nprefix=sub/dir/\n",
+				without_separator(repo.path()),
+				without_separator(workdir),
+			)
+		);
+
+		// the top of the work tree has no prefix
+		let ctx = HookContext {
+			invocation_dir: Some(workdir),
+			..Default::default()
+		};
+		let res =
+			hooks_pre_commit_with_timeout(&repo, None, &ctx, None)
+				.unwrap();
+
+		assert!(git_env_of(res).ends_with("prefix=unset\n"));
+	}
+
+	#[test]
+	fn test_hook_env() {
+		let (_td, repo) = repo_init();
+
+		create_hook(
+			&repo,
+			HOOK_PRE_COMMIT,
+			b"#!/bin/sh\necho \"$GITUI $TICKET\"\nexit 1\n",
+		);
+
+		let ctx = HookContext {
+			// later variables win
+			env: &[("GITUI", "1"), ("TICKET", "a"), ("TICKET", "b")],
+			..Default::default()
+		};
+		let res =
+			hooks_pre_commit_with_timeout(&repo, None, &ctx, None)
+				.unwrap();
+		assert_eq!(git_env_of(res), "1 b\n");
+
+		// only set for runs given them
+		let res = hooks_pre_commit(&repo, None).unwrap();
+		assert_eq!(git_env_of(res), " \n");
+	}
+
+	#[test]
+	fn test_git_env_bare() {
+		let (_td, repo) = repo_init_bare();
+
+		create_hook(&repo, HOOK_PRE_COMMIT, PRINT_GIT_ENV);
+
+		let res = hooks_pre_commit(&repo, None).unwrap();
+
+		assert_eq!(
+			git_env_of(res),
+			format!(
+				"dir={}\nwork_tree=unset\nprefix=unset\n",
+				without_separator(repo.path()),
+			)
+		);
+	}
+
+	/// `<hook>.d/<name>` next to `hook`, appending `name` to `order` in
+	/// the work tree before running `rest`
+	fn create_dir_hook(
+		repo: &Repository,
+		hook: &str,
+		name: &str,
+		rest: &str,
+	) -> PathBuf {
+		let dir = HookPaths::new(repo, None, hook).unwrap().hook_dir;
+		std::fs::create_dir_all(&dir).unwrap();
+
+		let path = dir.join(name);
+		create_hook_in_path(
+			&path,
+			format!("#!/bin/sh\necho {name} >> order\n{rest}\n")
+				.as_bytes(),
+		);
+
+		path
+	}
+
+	fn hooks_order(repo: &Repository) -> String {
+		std::fs::read_to_string(repo.workdir().unwrap().join("order"))
+			.unwrap_or_default()
+	}
+
+	#[test]
+	#[cfg(unix)]
+	fn test_dir_hooks_order() {
+		let (_td, repo) = repo_init();
+
+		create_hook(
+			&repo,
+			HOOK_PRE_COMMIT,
+			b"#!/bin/sh\necho plain >> order\n",
+		);
+		for name in ["b", "a", "10-x"] {
+			create_dir_hook(&repo, HOOK_PRE_COMMIT, name, "");
+		}
+
+		// skipped: not executable and a directory
+		let hooks =
+			HookPaths::new(&repo, None, HOOK_PRE_COMMIT).unwrap();
+		File::create(hooks.hook_dir.join("c"))
+			.unwrap()
+			.write_all(b"#!/bin/sh\necho c >> order\n")
+			.unwrap();
+		std::fs::create_dir(hooks.hook_dir.join("d")).unwrap();
+
+		let res = hooks_pre_commit(&repo, None).unwrap();
+
+		assert!(
+			matches!(&res, HookResult::Ok { hook, .. } if *hook == hooks.hook_dir),
+			"{res:?}"
+		);
+		assert_eq!(hooks_order(&repo), "plain\n10-x\na\nb\n");
+	}
+
+	#[test]
+	fn test_dir_hooks_stop_at_first_failure() {
+		let (_td, repo) = repo_init();
+
+		let failing = create_dir_hook(
+			&repo,
+			HOOK_PRE_COMMIT,
+			"a",
+			"echo 'a failed'\nexit 1",
+		);
+		create_dir_hook(&repo, HOOK_PRE_COMMIT, "b", "");
+
+		let res = hooks_pre_commit(&repo, None).unwrap();
+
+		let HookResult::RunNotSuccessful { hook, stdout, .. } = res
+		else {
+			unreachable!("{res:?}")
+		};
+		assert_eq!(hook, failing);
+		assert_eq!(stdout, "a failed\n");
+		assert_eq!(hooks_order(&repo), "a\n");
+	}
+
+	#[test]
+	fn test_dir_hooks_run_on_after_failure_if_not_abortable() {
+		let (_td, repo) = repo_init();
+
+		let failing =
+			create_dir_hook(&repo, HOOK_POST_COMMIT, "a", "exit 1");
+		create_dir_hook(&repo, HOOK_POST_COMMIT, "b", "");
+
+		let res = hooks_post_commit(&repo, None).unwrap();
+
+		assert!(
+			matches!(&res, HookResult::RunNotSuccessful { hook, .. } if *hook == failing),
+			"{res:?}"
+		);
+		assert_eq!(hooks_order(&repo), "a\nb\n");
+	}
+
+	#[test]
+	fn test_empty_dir_hooks() {
+		let (_td, repo) = repo_init();
+
+		let hooks =
+			HookPaths::new(&repo, None, HOOK_PRE_COMMIT).unwrap();
+		std::fs::create_dir_all(&hooks.hook_dir).unwrap();
+
+		assert_eq!(
+			hooks_pre_commit(&repo, None).unwrap(),
+			HookResult::NoHookFound
+		);
+
+		let plain = create_hook(
+			&repo,
+			HOOK_PRE_COMMIT,
+			b"#!/bin/sh\necho plain >> order\n",
+		);
+		let res = hooks_pre_commit(&repo, None).unwrap();
+
+		// just like without the directory
+		assert!(
+			matches!(&res, HookResult::Ok { hook, .. } if *hook == plain),
+			"{res:?}"
+		);
+		assert_eq!(hooks_order(&repo), "plain\n");
+	}
+
+	#[test]
+	fn test_dir_hooks_share_timeout() {
+		let (_td, repo) = repo_init();
+
+		create_dir_hook(&repo, HOOK_PRE_COMMIT, "a", "sleep 1");
+		let second =
+			create_dir_hook(&repo, HOOK_PRE_COMMIT, "b", "sleep 1");
+
+		// enough for each of them, but not for both
+		let res = hooks_pre_commit_with_timeout(
+			&repo,
+			None,
+			&HookContext::default(),
+			Some(Duration::from_millis(1500)),
+		)
+		.unwrap();
+
+		// the first one passed, the second one ran out of time
+		assert!(
+			matches!(&res, HookResult::TimedOut { hook, .. } if *hook == second),
+			"{res:?}"
+		);
+		assert_eq!(hooks_order(&repo), "a\nb\n");
+	}
+
+	#[test]
+	fn test_chatty_dir_hooks_with_timeout() {
+		let (_td, repo) = repo_init();
+
+		// each more than fits into the pipe buffer
+		for name in ["a", "b"] {
+			create_dir_hook(
+				&repo,
+				HOOK_PRE_COMMIT,
+				name,
+				"head -c 200000 /dev/zero | tr '\\0' a",
+			);
+		}
+
+		let start = Instant::now();
+		let res = hooks_pre_commit_with_timeout(
+			&repo,
+			None,
+			&HookContext::default(),
+			Some(Duration::from_secs(5)),
+		)
+		.unwrap();
+
+		assert!(res.is_ok(), "{res:?}");
+		assert!(start.elapsed() < Duration::from_secs(4));
+		assert_eq!(hooks_order(&repo), "a\nb\n");
+	}
+
+	#[test]
+	fn test_hooks_of_main_repo_in_linked_worktree() {
+		let (_td, repo) = repo_init();
+		let worktrees = tempdir().unwrap();
+		let worktree_path = worktrees.path().join("wt");
+
+		repo.worktree("wt", &worktree_path, None).unwrap();
+		let worktree = Repository::open(&worktree_path).unwrap();
+
+		// installed in the main repository only
+		let hook = create_hook(
+			&repo,
+			HOOK_PRE_COMMIT,
+			b"#!/bin/sh\npwd\nexit 1\n",
+		);
+		assert_eq!(
+			HookPaths::hooks_dir(&worktree).unwrap(),
+			repo.path().join("hooks")
+		);
+		assert_eq!(
+			HookPaths::new(&worktree, None, HOOK_PRE_COMMIT)
+				.unwrap()
+				.hook,
+			hook
+		);
+
+		let res = hooks_pre_commit(&worktree, None).unwrap();
+		let HookResult::RunNotSuccessful { stdout, .. } = res else {
+			unreachable!("{res:?}")
+		};
+
+		// still run in the worktree
+		assert_eq!(
+			stdout,
+			format!(
+				"{}\n",
+				without_separator(
+					&worktree
+						.workdir()
+						.unwrap()
+						.canonicalize()
+						.unwrap()
+				)
+			)
+		);
+	}
+
+	#[test]
+	fn test_git_env_linked_worktree() {
+		let (_td, repo) = repo_init();
+		let hooks = tempdir().unwrap();
+		let worktrees = tempdir().unwrap();
+		let worktree_path = worktrees.path().join("wt");
+
+		repo.config()
+			.unwrap()
+			.set_str("core.hooksPath", hooks.path().to_str().unwrap())
+			.unwrap();
+		repo.worktree("wt", &worktree_path, None).unwrap();
+		let worktree = Repository::open(&worktree_path).unwrap();
+
+		create_hook(&worktree, HOOK_PRE_COMMIT, PRINT_GIT_ENV);
+
+		let res = hooks_pre_commit(&worktree, None).unwrap();
+
+		// not `<workdir>/.git`
+		assert_eq!(
+			git_env_of(res),
+			format!(
+				"dir={}\nwork_tree={}\nprefix=unset\n",
+				without_separator(&repo.path().join("worktrees/wt")),
+				without_separator(worktree.workdir().unwrap()),
+			)
+		);
 	}
 }
@@ -0,0 +1,196 @@
+//! a hook and every process it started, to be killed as a whole on
+//! timeout. killing just the shell running the hook would leave e.g. a
+//! backgrounded linter running (and holding on to the output pipes).
+//!
+//! on unix the hook is the leader of a new process group that is
+//! signalled as a whole. on windows there are no process groups to kill,
+//! the hook is put into a job object instead which its children join
+//! automatically.
+
+use std::{
+	io,
+	process::{Child, Command},
+};
+
+#[cfg(unix)]
+use {
+	nix::{
+		errno::Errno,
+		sys::signal::{killpg, Signal, SIGKILL, SIGTERM},
+		unistd::Pid,
+	},
+	std::os::unix::process::CommandExt as _,
+};
+
+/// the processes of a single hook run
+pub struct ProcessTree {
+	#[cfg(windows)]
+	job: Option<job::Job>,
+}
+
+impl ProcessTree {
+	/// makes the process spawned by `command` the root of its own tree
+	pub fn prepare(command: &mut Command) {
+		#[cfg(unix)]
+		command.process_group(0);
+		#[cfg(not(unix))]
+		let _ = command;
+	}
+
+	/// the tree of `child`, which has to be spawned from a command passed
+	/// to [`Self::prepare`]
+	// only does something on windows
+	#[allow(clippy::missing_const_for_fn)]
+	pub fn track(child: &Child) -> Self {
+		#[cfg(windows)]
+		{
+			// a child started by the hook before it is assigned escapes
+			// the job, hooks are run through a shell so that window is
+			// tiny compared to its startup
+			let job = job::Job::with_process(child)
+				.map_err(|e| {
+					log::warn!("hook job object failed: {e}");
+				})
+				.ok();
+
+			Self { job }
+		}
+		#[cfg(not(windows))]
+		{
+			let _ = child;
+			Self {}
+		}
+	}
+
+	/// asks `child` and everything it started to exit, returns `false`
+	/// if that is not possible (on windows there is nothing like
+	/// `SIGTERM`) so that only [`Self::kill`] is left
+	// `self` is only needed on windows
+	#[allow(clippy::unused_self)]
+	pub fn terminate(&self, child: &Child) -> bool {
+		#[cfg(unix)]
+		{
+			signal_group(child, SIGTERM).is_ok_and(|sent| sent)
+		}
+		#[cfg(not(unix))]
+		{
+			let _ = child;
+			false
+		}
+	}
+
+	/// kills `child` and everything it started, falls back to killing
+	/// just `child` if that fails
+	// `self` is only needed on windows
+	#[allow(clippy::unused_self)]
+	pub fn kill(&self, child: &mut Child) -> io::Result<()> {
+		#[cfg(unix)]
+		{
+			match signal_group(child, SIGKILL) {
+				Ok(_) => return Ok(()),
+				Err(e) => {
+					log::warn!(
+						"killing hook process group failed: {e}"
+					);
+				}
+			}
+		}
+
+		#[cfg(windows)]
+		if let Some(job) = &self.job {
+			match job.terminate() {
+				Ok(()) => return Ok(()),
+				Err(e) => {
+					log::warn!("terminating hook job failed: {e}");
+				}
+			}
+		}
+
+		child.kill()
+	}
+}
+
+/// sends `signal` to the process group `child` leads, `Ok(false)` if
+/// none of its processes is left
+#[cfg(unix)]
+fn signal_group(child: &Child, signal: Signal) -> nix::Result<bool> {
+	let pid = i32::try_from(child.id()).map_err(|_| Errno::EINVAL)?;
+
+	match killpg(Pid::from_raw(pid), signal) {
+		Ok(()) => Ok(true),
+		Err(Errno::ESRCH) => Ok(false),
+		Err(e) => Err(e),
+	}
+}
+
+#[cfg(windows)]
+mod job {
+	use std::{
+		io, os::windows::io::AsRawHandle, process::Child, ptr,
+	};
+	use windows_sys::Win32::{
+		Foundation::{CloseHandle, HANDLE},
+		System::JobObjects::{
+			AssignProcessToJobObject, CreateJobObjectW,
+			TerminateJobObject,
+		},
+	};
+
+	/// exit code of the processes terminated with the job, what `kill`
+	/// of the std uses as well
+	const TERMINATED_EXIT_CODE: u32 = 1;
+
+	/// an anonymous job object, closing it does not affect its processes
+	pub struct Job(HANDLE);
+
+	impl Job {
+		#[allow(unsafe_code)]
+		pub fn with_process(child: &Child) -> io::Result<Self> {
+			// SAFETY: no security attributes and no name are valid
+			// arguments, the returned handle is owned by `Job`
+			let handle =
+				unsafe { CreateJobObjectW(ptr::null(), ptr::null()) };
+			if handle.is_null() {
+				return Err(io::Error::last_os_error());
+			}
+			let job = Self(handle);
+
+			// SAFETY: both handles are valid for the duration of the
+			// call, `child` is not waited on yet
+			let assigned = unsafe {
+				AssignProcessToJobObject(
+					job.0,
+					child.as_raw_handle() as HANDLE,
+				)
+			};
+			if assigned == 0 {
+				return Err(io::Error::last_os_error());
+			}
+
+			Ok(job)
+		}
+
+		#[allow(unsafe_code)]
+		pub fn terminate(&self) -> io::Result<()> {
+			// SAFETY: the handle is valid until `Job` is dropped
+			if unsafe {
+				TerminateJobObject(self.0, TERMINATED_EXIT_CODE)
+			} == 0
+			{
+				return Err(io::Error::last_os_error());
+			}
+
+			Ok(())
+		}
+	}
+
+	impl Drop for Job {
+		#[allow(unsafe_code)]
+		fn drop(&mut self) {
+			// SAFETY: the handle is owned by `Job` and closed only here
+			unsafe {
+				CloseHandle(self.0);
+			}
+		}
+	}
+}
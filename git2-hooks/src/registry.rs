@@ -0,0 +1,176 @@
+//! in-process bookkeeping of running and recently finished hooks,
+//! see [`active_hooks`] and [`recent_hooks`]
+
+use crate::{error::Result, HookResult};
+use std::{
+	collections::VecDeque,
+	path::{Path, PathBuf},
+	sync::{Mutex, MutexGuard, OnceLock},
+	time::{Duration, Instant},
+};
+
+/// amount of finished runs kept for [`recent_hooks`]
+const RECENT_HOOKS_CAPACITY: usize = 16;
+
+/// a hook process spawned by this crate
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct HookRun {
+	/// file name of the hook (e.g. `pre-commit`)
+	pub name: String,
+	/// git dir of the repository the hook runs for
+	pub git_dir: PathBuf,
+	/// id of the hook process
+	pub pid: u32,
+	/// when the hook was spawned
+	pub started: Instant,
+}
+
+impl HookRun {
+	/// time since the hook was spawned
+	pub fn elapsed(&self) -> Duration {
+		self.started.elapsed()
+	}
+}
+
+/// how a finished hook run ended
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HookOutcome {
+	/// exited successfully
+	Ok,
+	/// exited with a non zero exit code (`None` if killed by a signal)
+	Failed(Option<i32>),
+	/// killed after exceeding its timeout
+	TimedOut,
+	/// killed because it was cancelled
+	Cancelled,
+	/// killed by a signal not sent by us
+	Terminated {
+		/// see [`HookResult::Terminated`]
+		likely_oom: bool,
+	},
+	/// waiting for the hook failed
+	Error,
+}
+
+impl HookOutcome {
+	const fn from_result(result: &Result<HookResult>) -> Self {
+		match result {
+			Ok(HookResult::Ok { .. } | HookResult::NoHookFound) => {
+				Self::Ok
+			}
+			Ok(HookResult::RunNotSuccessful { code, .. }) => {
+				Self::Failed(*code)
+			}
+			Ok(HookResult::TimedOut { .. }) => Self::TimedOut,
+			Ok(HookResult::Cancelled { .. }) => Self::Cancelled,
+			Ok(HookResult::Terminated { likely_oom, .. }) => {
+				Self::Terminated {
+					likely_oom: *likely_oom,
+				}
+			}
+			Err(_) => Self::Error,
+		}
+	}
+}
+
+/// a hook run that already finished
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FinishedHookRun {
+	/// the run as it was listed in [`active_hooks`]
+	pub run: HookRun,
+	/// how long the hook was running
+	pub duration: Duration,
+	/// how the run ended
+	pub outcome: HookOutcome,
+}
+
+#[derive(Default)]
+struct Registry {
+	active: Vec<HookRun>,
+	recent: VecDeque<FinishedHookRun>,
+}
+
+fn registry() -> MutexGuard<'static, Registry> {
+	static REGISTRY: OnceLock<Mutex<Registry>> = OnceLock::new();
+
+	REGISTRY
+		.get_or_init(Mutex::default)
+		.lock()
+		.unwrap_or_else(std::sync::PoisonError::into_inner)
+}
+
+/// hooks currently running (in any repository), oldest first
+pub fn active_hooks() -> Vec<HookRun> {
+	registry().active.clone()
+}
+
+/// the last finished hook runs (in any repository), most recent first
+pub fn recent_hooks() -> Vec<FinishedHookRun> {
+	registry().recent.iter().cloned().collect()
+}
+
+/// registers a spawned hook, it is moved to the recent hooks once
+/// [`ActiveHookGuard::finish`] is called or the guard is dropped
+pub fn register(
+	hook: &Path,
+	git_dir: &Path,
+	pid: u32,
+) -> ActiveHookGuard {
+	let name = hook
+		.file_name()
+		.map(|name| name.to_string_lossy().to_string())
+		.unwrap_or_default();
+
+	registry().active.push(HookRun {
+		name,
+		git_dir: git_dir.to_path_buf(),
+		pid,
+		started: Instant::now(),
+	});
+
+	ActiveHookGuard {
+		pid,
+		outcome: HookOutcome::Error,
+	}
+}
+
+/// deregisters the hook when dropped, so that a hook failing to be
+/// waited for does not stay in the active list forever
+pub struct ActiveHookGuard {
+	pid: u32,
+	outcome: HookOutcome,
+}
+
+impl ActiveHookGuard {
+	/// records the outcome of the hook run and passes `result` through
+	pub fn finish(
+		mut self,
+		result: Result<HookResult>,
+	) -> Result<HookResult> {
+		self.outcome = HookOutcome::from_result(&result);
+		result
+	}
+}
+
+impl Drop for ActiveHookGuard {
+	fn drop(&mut self) {
+		let mut registry = registry();
+
+		let Some(idx) = registry
+			.active
+			.iter()
+			.position(|run| run.pid == self.pid)
+		else {
+			return;
+		};
+
+		let run = registry.active.remove(idx);
+
+		registry.recent.push_front(FinishedHookRun {
+			duration: run.elapsed(),
+			run,
+			outcome: self.outcome,
+		});
+		registry.recent.truncate(RECENT_HOOKS_CAPACITY);
+	}
+}
@@ -0,0 +1,306 @@
+//! timestamped capturing of hook output, see [`Transcript`].
+//!
+//! stdout and stderr of a hook are separate pipes, reading each of them
+//! to its end loses which output came first. here both pipes are read
+//! concurrently and every chunk is stamped on arrival, which is enough to
+//! reconstruct the order in which a hook wrote to them (as long as it did
+//! not write to both within the same few microseconds).
+
+use std::{
+	io::Read,
+	sync::{mpsc::Sender, Arc, Mutex, PoisonError},
+	thread,
+	time::{Duration, Instant},
+};
+
+/// size of the buffer every pipe is read with
+const CHUNK_SIZE: usize = 8 * 1024;
+
+/// how stdout/stderr of a hook are collected
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum OutputBuffering {
+	/// each stream is read to its end on its own
+	#[default]
+	Separate,
+	/// chunks of both streams are timestamped as they arrive, which
+	/// additionally produces a [`Transcript`]
+	Timestamped,
+}
+
+/// which pipe a chunk was read from
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OutputStream {
+	/// standard output of the hook
+	Stdout,
+	/// standard error of the hook
+	Stderr,
+}
+
+/// output read from one of the pipes in a single `read`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct OutputChunk {
+	/// pipe the chunk was read from
+	pub stream: OutputStream,
+	/// time since the hook was spawned
+	pub at: Duration,
+	/// raw bytes as written by the hook
+	pub data: Vec<u8>,
+}
+
+/// a line of output as it is streamed while the hook runs
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct HookOutputLine {
+	/// pipe the line was read from
+	pub stream: OutputStream,
+	/// the line without its trailing newline
+	pub line: String,
+}
+
+/// output of a hook in the (approximate) order it was written
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Transcript {
+	/// ordered by [`OutputChunk::at`]
+	pub chunks: Vec<OutputChunk>,
+}
+
+impl Transcript {
+	/// everything written to `stream`
+	pub fn output(&self, stream: OutputStream) -> String {
+		String::from_utf8_lossy(&self.output_bytes(stream))
+			.into_owned()
+	}
+
+	/// [`Self::output`] without decoding it
+	pub fn output_bytes(&self, stream: OutputStream) -> Vec<u8> {
+		self.chunks
+			.iter()
+			.filter(|chunk| chunk.stream == stream)
+			.flat_map(|chunk| chunk.data.iter().copied())
+			.collect()
+	}
+
+	/// both streams merged into one text
+	pub fn interleaved(&self) -> String {
+		let data: Vec<u8> = self
+			.chunks
+			.iter()
+			.flat_map(|chunk| chunk.data.iter().copied())
+			.collect();
+
+		String::from_utf8_lossy(&data).into_owned()
+	}
+
+	/// complete lines labeled with the stream they were written to,
+	/// ordered by the time their last chunk arrived
+	pub fn lines(&self) -> Vec<(OutputStream, String)> {
+		let mut lines = Vec::new();
+		let mut pending_out = Vec::new();
+		let mut pending_err = Vec::new();
+
+		for chunk in &self.chunks {
+			let pending = match chunk.stream {
+				OutputStream::Stdout => &mut pending_out,
+				OutputStream::Stderr => &mut pending_err,
+			};
+
+			for &byte in &chunk.data {
+				if byte == b'\n' {
+					lines.push((chunk.stream, take_line(pending)));
+				} else {
+					pending.push(byte);
+				}
+			}
+		}
+
+		// output not terminated by a newline
+		for (stream, mut pending) in [
+			(OutputStream::Stdout, pending_out),
+			(OutputStream::Stderr, pending_err),
+		] {
+			if !pending.is_empty() {
+				lines.push((stream, take_line(&mut pending)));
+			}
+		}
+
+		lines
+	}
+}
+
+fn take_line(pending: &mut Vec<u8>) -> String {
+	let line = String::from_utf8_lossy(pending).into_owned();
+	pending.clear();
+	line
+}
+
+/// reads both pipes of a hook on separate threads until they are closed
+pub struct TranscriptReader {
+	chunks: Arc<Mutex<Vec<OutputChunk>>>,
+	readers: Vec<thread::JoinHandle<()>>,
+}
+
+impl TranscriptReader {
+	/// `start` is when the hook was spawned, complete lines are
+	/// additionally sent to `lines` as soon as they were read
+	pub fn spawn(
+		start: Instant,
+		stdout: Option<impl Read + Send + 'static>,
+		stderr: Option<impl Read + Send + 'static>,
+		lines: Option<Sender<HookOutputLine>>,
+	) -> Self {
+		let chunks = Arc::new(Mutex::new(Vec::new()));
+
+		let mut readers = Vec::with_capacity(2);
+		if let Some(pipe) = stdout {
+			readers.push(read_pipe(
+				start,
+				pipe,
+				OutputStream::Stdout,
+				Arc::clone(&chunks),
+				lines.clone(),
+			));
+		}
+		if let Some(pipe) = stderr {
+			readers.push(read_pipe(
+				start,
+				pipe,
+				OutputStream::Stderr,
+				Arc::clone(&chunks),
+				lines,
+			));
+		}
+
+		Self { chunks, readers }
+	}
+
+	/// waits for both pipes to be closed, must only be called once the
+	/// hook exited (or was killed)
+	pub fn finish(self) -> Transcript {
+		for reader in self.readers {
+			if reader.join().is_err() {
+				log::error!("hook output reader panicked");
+			}
+		}
+
+		let chunks = Arc::try_unwrap(self.chunks).map_or_else(
+			|chunks| {
+				chunks
+					.lock()
+					.unwrap_or_else(PoisonError::into_inner)
+					.clone()
+			},
+			|chunks| {
+				chunks
+					.into_inner()
+					.unwrap_or_else(PoisonError::into_inner)
+			},
+		);
+
+		Transcript { chunks }
+	}
+}
+
+fn read_pipe(
+	start: Instant,
+	mut pipe: impl Read + Send + 'static,
+	stream: OutputStream,
+	chunks: Arc<Mutex<Vec<OutputChunk>>>,
+	lines: Option<Sender<HookOutputLine>>,
+) -> thread::JoinHandle<()> {
+	thread::spawn(move || {
+		let mut buf = [0_u8; CHUNK_SIZE];
+		let mut pending = Vec::new();
+
+		let send = |pending: &mut Vec<u8>| {
+			if let Some(lines) = &lines {
+				// the receiver only goes away once the hook is done
+				let _ = lines.send(HookOutputLine {
+					stream,
+					line: take_line(pending),
+				});
+			}
+		};
+
+		loop {
+			match pipe.read(&mut buf) {
+				Ok(0) => break,
+				Ok(read) => {
+					if lines.is_some() {
+						for &byte in &buf[..read] {
+							if byte == b'\n' {
+								send(&mut pending);
+							} else {
+								pending.push(byte);
+							}
+						}
+					}
+
+					let mut chunks = chunks
+						.lock()
+						.unwrap_or_else(PoisonError::into_inner);
+					// stamped while holding the lock, so the list stays
+					// sorted by time
+					chunks.push(OutputChunk {
+						stream,
+						at: start.elapsed(),
+						data: buf[..read].to_vec(),
+					});
+				}
+				Err(e)
+					if e.kind()
+						== std::io::ErrorKind::Interrupted => {}
+				Err(e) => {
+					log::error!("reading hook output failed: {e}");
+					break;
+				}
+			}
+		}
+
+		// output not terminated by a newline
+		if !pending.is_empty() {
+			send(&mut pending);
+		}
+	})
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	fn chunk(stream: OutputStream, data: &str) -> OutputChunk {
+		OutputChunk {
+			stream,
+			at: Duration::ZERO,
+			data: data.as_bytes().to_vec(),
+		}
+	}
+
+	#[test]
+	fn test_lines_split_across_chunks() {
+		let transcript = Transcript {
+			chunks: vec![
+				chunk(OutputStream::Stdout, "out "),
+				chunk(OutputStream::Stderr, "err1\n"),
+				chunk(OutputStream::Stdout, "line\nrest"),
+				chunk(OutputStream::Stderr, "err2"),
+			],
+		};
+
+		assert_eq!(
+			transcript.lines(),
+			vec![
+				(OutputStream::Stderr, String::from("err1")),
+				(OutputStream::Stdout, String::from("out line")),
+				(OutputStream::Stdout, String::from("rest")),
+				(OutputStream::Stderr, String::from("err2")),
+			]
+		);
+		assert_eq!(
+			transcript.output(OutputStream::Stdout),
+			"out line\nrest"
+		);
+		assert_eq!(
+			transcript.interleaved(),
+			"out err1\nline\nresterr2"
+		);
+	}
+}
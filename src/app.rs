@@ -1,25 +1,30 @@
 use crate::{
-	accessors,
+	accessors, audit,
 	cmdbar::CommandBar,
 	components::{
 		command_pump, event_pump, CommandInfo, Component,
-		DrawableComponent, FuzzyFinderTarget,
+		DrawableComponent, FuzzyFinderTarget, SharedKillRing,
 	},
 	input::{Input, InputEvent, InputState},
-	keys::{key_match, KeyConfig, SharedKeyConfig},
+	keys::{key_match, GituiKeyEvent, KeyConfig, SharedKeyConfig},
+	macros::{MacroAction, Macros, MAX_REPLAY_DEPTH},
 	options::{Options, SharedOptions},
 	popup_stack::PopupStack,
 	popups::{
-		AppOption, BlameFilePopup, BranchListPopup, CommitPopup,
-		CompareCommitsPopup, ConfirmPopup, CreateBranchPopup,
-		CreateRemotePopup, ExternalEditorPopup, FetchPopup,
-		FileRevlogPopup, FuzzyFindPopup, HelpPopup,
-		InspectCommitPopup, LogSearchPopupPopup, MsgPopup,
-		OptionsPopup, PullPopup, PushPopup, PushTagsPopup,
+		block_pending_operation, AppOption, BlameFilePopup,
+		BranchDescriptionPopup, BranchListPopup, CheckIgnorePopup,
+		CommitPopup, CommitPresetsPopup, CommitSummaryPopup,
+		CompareBasePopup, CompareCommitsPopup, ConfirmPopup,
+		CreateBranchPopup, CreateRemotePopup, ExternalEditorPopup,
+		FetchPopup, FetchRefPopup, FileRevlogPopup, FuzzyFindPopup,
+		HelpPopup, IgnoredFilesPopup, InspectCommitPopup,
+		LogSearchPopupPopup, MsgPopup, OptionsPopup,
+		PendingOperationPopup, PerformancePopup, PullPopup,
+		PushPopup, PushTagsPopup, RecentBranchesPopup,
 		RemoteListPopup, RenameBranchPopup, RenameRemotePopup,
-		ResetPopup, RevisionFilesPopup, StashMsgPopup,
-		SubmodulesListPopup, TagCommitPopup, TagListPopup,
-		UpdateRemoteUrlPopup,
+		ResetPopup, RevisionFilesPopup, StagedChecksPopup,
+		StashMsgPopup, SubmodulesListPopup, TagCommitPopup,
+		TagListPopup, UpdateRemoteUrlPopup,
 	},
 	queue::{
 		Action, AppTabs, InternalEvent, NeedsUpdate, Queue,
@@ -37,6 +42,7 @@ use asyncgit::{
 	sync::{
 		self,
 		utils::{repo_work_dir, undo_last_commit},
+		CommitId, HookResult, PostRewriteSource, RebaseState,
 		RepoPath, RepoPathRef,
 	},
 	AsyncGitNotification, PushType,
@@ -55,9 +61,13 @@ use std::{
 	cell::{Cell, RefCell},
 	path::{Path, PathBuf},
 	rc::Rc,
+	time::{Duration, Instant},
 };
 use unicode_width::UnicodeWidthStr;
 
+/// pastes larger than this (in bytes) have to be confirmed
+const MAX_UNCONFIRMED_PASTE_LEN: usize = 64 * 1024;
+
 #[derive(Clone)]
 pub enum QuitState {
 	None,
@@ -72,6 +82,9 @@ pub struct App {
 	help_popup: HelpPopup,
 	msg_popup: MsgPopup,
 	confirm_popup: ConfirmPopup,
+	pending_operation_popup: PendingOperationPopup,
+	commit_summary_popup: CommitSummaryPopup,
+	staged_checks_popup: StagedChecksPopup,
 	commit_popup: CommitPopup,
 	blame_file_popup: BlameFilePopup,
 	file_revlog_popup: FileRevlogPopup,
@@ -86,6 +99,7 @@ pub struct App {
 	push_tags_popup: PushTagsPopup,
 	pull_popup: PullPopup,
 	fetch_popup: FetchPopup,
+	fetch_ref_popup: FetchRefPopup,
 	tag_commit_popup: TagCommitPopup,
 	create_branch_popup: CreateBranchPopup,
 	create_remote_popup: CreateRemotePopup,
@@ -93,8 +107,15 @@ pub struct App {
 	update_remote_url_popup: UpdateRemoteUrlPopup,
 	remotes_popup: RemoteListPopup,
 	rename_branch_popup: RenameBranchPopup,
+	branch_description_popup: BranchDescriptionPopup,
+	recent_branches_popup: RecentBranchesPopup,
+	ignored_files_popup: IgnoredFilesPopup,
+	check_ignore_popup: CheckIgnorePopup,
+	compare_base_popup: CompareBasePopup,
+	commit_presets_popup: CommitPresetsPopup,
 	select_branch_popup: BranchListPopup,
 	options_popup: OptionsPopup,
+	performance_popup: PerformancePopup,
 	submodule_popup: SubmodulesListPopup,
 	tags_popup: TagListPopup,
 	reset_popup: ResetPopup,
@@ -109,6 +130,7 @@ pub struct App {
 	theme: SharedTheme,
 	key_config: SharedKeyConfig,
 	input: Input,
+	macros: Macros,
 	popup_stack: PopupStack,
 	options: SharedOptions,
 	repo_path_text: String,
@@ -116,6 +138,7 @@ pub struct App {
 	// "Flags"
 	requires_redraw: Cell<bool>,
 	file_to_open: Option<String>,
+	shell_to_open: bool,
 }
 
 pub struct Environment {
@@ -126,6 +149,8 @@ pub struct Environment {
 	pub options: SharedOptions,
 	pub sender_git: Sender<AsyncGitNotification>,
 	pub sender_app: Sender<AsyncAppNotification>,
+	/// shared by all text inputs
+	pub kill_ring: SharedKillRing,
 }
 
 /// The need to construct a "whatever" environment only arises in testing right now
@@ -141,6 +166,7 @@ impl Environment {
 			options: Rc::new(RefCell::new(Options::test_env())),
 			sender_git: unbounded().0,
 			sender_app: unbounded().0,
+			kill_ring: Rc::default(),
 		}
 	}
 }
@@ -162,14 +188,18 @@ impl App {
 		let repo_path_text =
 			repo_work_dir(&repo.borrow()).unwrap_or_default();
 
+		let queue = Queue::new();
+		let options = Options::new(repo.clone(), &queue);
+
 		let env = Environment {
-			queue: Queue::new(),
+			queue,
 			theme: Rc::new(theme),
 			key_config: Rc::new(key_config),
-			options: Options::new(repo.clone()),
+			options,
 			repo,
 			sender_git,
 			sender_app,
+			kill_ring: Rc::default(),
 		};
 
 		let tab = env.options.borrow().current_tab();
@@ -177,6 +207,9 @@ impl App {
 		let mut app = Self {
 			input,
 			confirm_popup: ConfirmPopup::new(&env),
+			pending_operation_popup: PendingOperationPopup::new(&env),
+			commit_summary_popup: CommitSummaryPopup::new(&env),
+			staged_checks_popup: StagedChecksPopup::new(&env),
 			commit_popup: CommitPopup::new(&env),
 			blame_file_popup: BlameFilePopup::new(
 				&env,
@@ -193,6 +226,7 @@ impl App {
 			reset_popup: ResetPopup::new(&env),
 			pull_popup: PullPopup::new(&env),
 			fetch_popup: FetchPopup::new(&env),
+			fetch_ref_popup: FetchRefPopup::new(&env),
 			tag_commit_popup: TagCommitPopup::new(&env),
 			create_branch_popup: CreateBranchPopup::new(&env),
 			create_remote_popup: CreateRemotePopup::new(&env),
@@ -200,9 +234,18 @@ impl App {
 			update_remote_url_popup: UpdateRemoteUrlPopup::new(&env),
 			remotes_popup: RemoteListPopup::new(&env),
 			rename_branch_popup: RenameBranchPopup::new(&env),
+			recent_branches_popup: RecentBranchesPopup::new(&env),
+			ignored_files_popup: IgnoredFilesPopup::new(&env),
+			check_ignore_popup: CheckIgnorePopup::new(&env),
+			branch_description_popup: BranchDescriptionPopup::new(
+				&env,
+			),
+			compare_base_popup: CompareBasePopup::new(&env),
+			commit_presets_popup: CommitPresetsPopup::new(&env),
 			select_branch_popup: BranchListPopup::new(&env),
 			tags_popup: TagListPopup::new(&env),
 			options_popup: OptionsPopup::new(&env),
+			performance_popup: PerformancePopup::new(&env),
 			submodule_popup: SubmodulesListPopup::new(&env),
 			log_search_popup: LogSearchPopupPopup::new(&env),
 			fuzzy_find_popup: FuzzyFindPopup::new(&env),
@@ -225,8 +268,10 @@ impl App {
 			key_config: env.key_config,
 			requires_redraw: Cell::new(false),
 			file_to_open: None,
+			shell_to_open: false,
 			repo: env.repo,
 			repo_path_text,
+			macros: Macros::default(),
 			popup_stack: PopupStack::default(),
 		};
 
@@ -288,29 +333,107 @@ impl App {
 		log::trace!("event: {:?}", ev);
 
 		if let InputEvent::Input(ev) = ev {
-			if self.check_hard_exit(&ev) || self.check_quit(&ev) {
+			if self.check_hard_exit(&ev) {
 				return Ok(());
 			}
 
-			let mut flags = NeedsUpdate::empty();
+			if let Event::Key(k) = &ev {
+				if self.macros.is_replaying() {
+					if key_match(k, self.key_config.keys.macro_abort)
+					{
+						self.macros.stop_replay();
+					} else {
+						log::trace!(
+							"key ignored during macro replay"
+						);
+					}
+					return Ok(());
+				}
+
+				if self.macro_key(k, None)? {
+					return Ok(());
+				}
+			}
 
-			if event_pump(&ev, self.components_mut().as_mut_slice())?
+			self.dispatch(&ev)?;
+		} else if let InputEvent::State(polling_state) = ev {
+			self.external_editor_popup.hide();
+			if matches!(polling_state, InputState::Paused) {
+				let result =
+					if std::mem::take(&mut self.shell_to_open) {
+						// the shell might have finished the pending operation
+						self.queue.push(InternalEvent::Update(
+							NeedsUpdate::ALL,
+						));
+						ExternalEditorPopup::open_shell(
+							&self.repo.borrow(),
+						)
+					} else if let Some(path) =
+						self.file_to_open.take()
+					{
+						ExternalEditorPopup::open_file_in_editor(
+							&self.repo.borrow(),
+							Path::new(&path),
+						)
+					} else {
+						let changes =
+							self.status_tab.get_files_changes()?;
+						self.commit_popup.show_editor(changes)
+					};
+
+				if let Err(e) = result {
+					let msg =
+						format!("failed to launch editor:\n{e}");
+					log::error!("{}", msg.as_str());
+					self.msg_popup.show_error(msg.as_str())?;
+				}
+
+				self.requires_redraw.set(true);
+				self.input.set_polling(true);
+			}
+		}
+
+		Ok(())
+	}
+
+	/// passes `ev` to the popups, tabs and global keys,
+	/// returns whether any of them handled it
+	fn dispatch(&mut self, ev: &Event) -> Result<bool> {
+		if self.check_quit(ev) {
+			return Ok(true);
+		}
+
+		let mut flags = NeedsUpdate::empty();
+
+		if let Event::Paste(text) = ev {
+			if text.len() > MAX_UNCONFIRMED_PASTE_LEN {
+				self.queue.push(InternalEvent::ConfirmAction(
+					Action::LargePaste(text.clone()),
+				));
+				self.process_queue(flags)?;
+				return Ok(true);
+			}
+		}
+
+		let consumed =
+			if event_pump(ev, self.components_mut().as_mut_slice())?
 				.is_consumed()
 			{
 				flags.insert(NeedsUpdate::COMMANDS);
-			} else if let Event::Key(k) = &ev {
+				true
+			} else if let Event::Key(k) = ev {
 				let new_flags = if key_match(
 					k,
 					self.key_config.keys.tab_toggle,
 				) {
 					self.toggle_tabs(false)?;
-					NeedsUpdate::COMMANDS
+					Some(NeedsUpdate::COMMANDS)
 				} else if key_match(
 					k,
 					self.key_config.keys.tab_toggle_reverse,
 				) {
 					self.toggle_tabs(true)?;
-					NeedsUpdate::COMMANDS
+					Some(NeedsUpdate::COMMANDS)
 				} else if key_match(
 					k,
 					self.key_config.keys.tab_status,
@@ -328,52 +451,137 @@ impl App {
 					self.key_config.keys.tab_stashes,
 				) {
 					self.switch_tab(k)?;
-					NeedsUpdate::COMMANDS
+					Some(NeedsUpdate::COMMANDS)
 				} else if key_match(
 					k,
 					self.key_config.keys.cmd_bar_toggle,
 				) {
 					self.cmdbar.borrow_mut().toggle_more();
-					NeedsUpdate::empty()
+					Some(NeedsUpdate::empty())
 				} else if key_match(
 					k,
 					self.key_config.keys.open_options,
 				) {
 					self.options_popup.show()?;
-					NeedsUpdate::ALL
+					Some(NeedsUpdate::ALL)
+				} else if key_match(
+					k,
+					self.key_config.keys.open_performance,
+				) {
+					self.performance_popup.show()?;
+					Some(NeedsUpdate::ALL)
+				} else if key_match(
+					k,
+					self.key_config.keys.open_audit_log,
+				) {
+					self.show_audit_log()?;
+					Some(NeedsUpdate::ALL)
 				} else {
-					NeedsUpdate::empty()
+					None
 				};
 
-				flags.insert(new_flags);
-			}
+				let consumed = new_flags.is_some();
+				flags.insert(
+					new_flags.unwrap_or_else(NeedsUpdate::empty),
+				);
+				consumed
+			} else {
+				false
+			};
 
-			self.process_queue(flags)?;
-		} else if let InputEvent::State(polling_state) = ev {
-			self.external_editor_popup.hide();
-			if matches!(polling_state, InputState::Paused) {
-				let result =
-					if let Some(path) = self.file_to_open.take() {
-						ExternalEditorPopup::open_file_in_editor(
-							&self.repo.borrow(),
-							Path::new(&path),
-						)
-					} else {
-						let changes =
-							self.status_tab.get_files_changes()?;
-						self.commit_popup.show_editor(changes)
-					};
+		self.process_queue(flags)?;
 
-				if let Err(e) = result {
-					let msg =
-						format!("failed to launch editor:\n{e}");
-					log::error!("{}", msg.as_str());
-					self.msg_popup.show_error(msg.as_str())?;
-				}
+		Ok(consumed)
+	}
 
-				self.requires_redraw.set(true);
-				self.input.set_polling(true);
+	/// handles the macro keys, returns whether `k` was one of them.
+	/// `depth` is the nesting depth of a replayed key, see
+	/// [`Macros::key`]
+	fn macro_key(
+		&mut self,
+		k: &KeyEvent,
+		depth: Option<usize>,
+	) -> Result<bool> {
+		let macro_keys = self.global_keys_enabled();
+
+		match self.macros.key(
+			k,
+			&self.key_config.keys,
+			depth,
+			macro_keys,
+		) {
+			MacroAction::Dispatch => return Ok(false),
+			MacroAction::Consumed => (),
+			MacroAction::Recorded { register, keys } => {
+				self.options.borrow_mut().set_macro(register, keys);
 			}
+			MacroAction::Replay {
+				register,
+				count,
+				depth,
+			} => self.replay_macro(register, count, depth),
+		}
+
+		self.process_queue(NeedsUpdate::COMMANDS)?;
+
+		Ok(true)
+	}
+
+	fn replay_macro(
+		&mut self,
+		register: char,
+		count: usize,
+		depth: usize,
+	) {
+		if depth > MAX_REPLAY_DEPTH {
+			self.macros.stop_replay();
+			self.queue.push(InternalEvent::ShowErrorMsg(
+				strings::macro_replay_too_deep(MAX_REPLAY_DEPTH),
+			));
+			return;
+		}
+
+		let keys = self
+			.options
+			.borrow()
+			.macro_keys(register)
+			.map(<[GituiKeyEvent]>::to_vec);
+
+		if let Some(keys) = keys {
+			self.macros.replay(register, &keys, count, depth);
+		} else {
+			self.macros.stop_replay();
+			self.queue.push(InternalEvent::ShowErrorMsg(
+				strings::macro_not_recorded(register),
+			));
+		}
+	}
+
+	///
+	pub fn is_replaying_macro(&self) -> bool {
+		self.macros.is_replaying()
+	}
+
+	/// feeds the next key of the macro replay through the normal
+	/// dispatch, the replay stops once a key is not handled
+	pub fn replay_macro_step(&mut self) -> Result<()> {
+		let Some((k, depth)) = self.macros.next_replayed() else {
+			return Ok(());
+		};
+
+		if self.macro_key(&k, Some(depth))? {
+			return Ok(());
+		}
+
+		if !self.dispatch(&Event::Key(k))? {
+			self.macros.stop_replay();
+			self.queue.push(InternalEvent::ShowErrorMsg(
+				strings::macro_replay_stopped(
+					&self.key_config,
+					GituiKeyEvent::new(k.code, k.modifiers),
+				),
+			));
+			self.process_queue(NeedsUpdate::COMMANDS)?;
 		}
 
 		Ok(())
@@ -384,6 +592,9 @@ impl App {
 	pub fn update(&mut self) -> Result<()> {
 		log::trace!("update");
 
+		// the watcher (or ticker) reported changes made outside of gitui
+		sync::invalidate_index_cache(&self.repo.borrow())?;
+
 		self.commit_popup.update();
 		self.status_tab.update()?;
 		self.revlog.update()?;
@@ -391,12 +602,27 @@ impl App {
 		self.stashing_tab.update()?;
 		self.stashlist_tab.update()?;
 		self.reset_popup.update()?;
+		self.options_popup.update()?;
 
 		self.update_commands();
 
 		Ok(())
 	}
 
+	/// time until a component has work due in [`Self::tick`],
+	/// `None` if none is scheduled
+	pub fn next_tick_in(&self, now: Instant) -> Option<Duration> {
+		self.commit_popup.next_tick_in(now)
+	}
+
+	/// runs work components scheduled for later (e.g. debounced
+	/// while typing) once it is due
+	pub fn tick(&mut self) {
+		log::trace!("tick");
+
+		self.commit_popup.tick(Instant::now());
+	}
+
 	///
 	pub fn update_async(
 		&mut self,
@@ -416,6 +642,7 @@ impl App {
 			self.pull_popup.update_git(ev);
 			self.fetch_popup.update_git(ev);
 			self.select_branch_popup.update_git(ev)?;
+			self.commit_popup.update_git(ev);
 		}
 
 		self.files_tab.update_async(ev)?;
@@ -458,6 +685,7 @@ impl App {
 			|| self.fetch_popup.any_work_pending()
 			|| self.revision_files_popup.any_work_pending()
 			|| self.tags_popup.any_work_pending()
+			|| self.commit_popup.any_work_pending()
 	}
 
 	///
@@ -480,6 +708,10 @@ impl App {
 			fuzzy_find_popup,
 			msg_popup,
 			confirm_popup,
+			pending_operation_popup,
+			commit_summary_popup,
+			staged_checks_popup,
+			commit_presets_popup,
 			commit_popup,
 			blame_file_popup,
 			file_revlog_popup,
@@ -491,6 +723,7 @@ impl App {
 			push_tags_popup,
 			pull_popup,
 			fetch_popup,
+			fetch_ref_popup,
 			tag_commit_popup,
 			reset_popup,
 			create_branch_popup,
@@ -499,11 +732,17 @@ impl App {
 			update_remote_url_popup,
 			remotes_popup,
 			rename_branch_popup,
+			branch_description_popup,
+			compare_base_popup,
+			recent_branches_popup,
+			ignored_files_popup,
+			check_ignore_popup,
 			select_branch_popup,
 			revision_files_popup,
 			submodule_popup,
 			tags_popup,
 			options_popup,
+			performance_popup,
 			help_popup,
 			revlog,
 			status_tab,
@@ -517,6 +756,7 @@ impl App {
 		self,
 		[
 			commit_popup,
+			commit_presets_popup,
 			stashmsg_popup,
 			help_popup,
 			inspect_commit_popup,
@@ -526,6 +766,9 @@ impl App {
 			external_editor_popup,
 			tag_commit_popup,
 			select_branch_popup,
+			recent_branches_popup,
+			ignored_files_popup,
+			check_ignore_popup,
 			remotes_popup,
 			create_remote_popup,
 			rename_remote_popup,
@@ -535,6 +778,8 @@ impl App {
 			reset_popup,
 			create_branch_popup,
 			rename_branch_popup,
+			branch_description_popup,
+			compare_base_popup,
 			revision_files_popup,
 			fuzzy_find_popup,
 			log_search_popup,
@@ -542,14 +787,25 @@ impl App {
 			push_tags_popup,
 			pull_popup,
 			fetch_popup,
+			fetch_ref_popup,
 			options_popup,
+			performance_popup,
+			commit_summary_popup,
+			staged_checks_popup,
+			pending_operation_popup,
 			confirm_popup,
 			msg_popup
 		]
 	);
 
+	/// `false` while a popup or text input might take the keys
+	fn global_keys_enabled(&self) -> bool {
+		!self.any_popup_visible()
+			&& !self.status_tab.is_diff_searching()
+	}
+
 	fn check_quit(&mut self, ev: &Event) -> bool {
-		if self.any_popup_visible() {
+		if !self.global_keys_enabled() {
 			return false;
 		}
 		if let Event::Key(e) = ev {
@@ -741,6 +997,21 @@ impl App {
 			InternalEvent::RewordCommit(id) => {
 				self.commit_popup.open(Some(id))?;
 			}
+			InternalEvent::SquashCommits { base, tip } => {
+				self.commit_popup.open_squash(base, tip)?;
+			}
+			InternalEvent::PostCheckout {
+				prev_head,
+				branch_checkout,
+			} => {
+				self.post_checkout(prev_head, branch_checkout);
+			}
+			InternalEvent::PostMerge { is_squash } => {
+				self.post_merge(is_squash);
+			}
+			InternalEvent::PostRewrite { source, rewritten } => {
+				self.post_rewrite(source, &rewritten);
+			}
 			InternalEvent::PopupStashing(opts) => {
 				self.stashmsg_popup.options(opts);
 				self.stashmsg_popup.show()?;
@@ -768,9 +1039,41 @@ impl App {
 				self.rename_branch_popup
 					.open(branch_ref, cur_name)?;
 			}
+			InternalEvent::RenameCurrentBranch => {
+				self.rename_branch_popup.open_current()?;
+			}
+			InternalEvent::MoveChangesToBranch => {
+				self.create_branch_popup.open_move_changes()?;
+			}
+			InternalEvent::EditBranchDescription(branch) => {
+				self.branch_description_popup.open(branch)?;
+			}
 			InternalEvent::SelectBranch => {
 				self.select_branch_popup.open()?;
 			}
+			InternalEvent::RecentBranches => {
+				self.recent_branches_popup.open()?;
+			}
+			InternalEvent::ShowIgnoredFiles => {
+				self.ignored_files_popup.open()?;
+			}
+			InternalEvent::CheckIgnore(path) => {
+				self.check_ignore_popup.open(path)?;
+			}
+			InternalEvent::CheckoutPreviousBranch => {
+				self.recent_branches_popup.checkout_previous();
+			}
+			InternalEvent::OpenCommitPresetsPopup(names) => {
+				self.commit_presets_popup.open(names)?;
+				flags.insert(NeedsUpdate::COMMANDS);
+			}
+			InternalEvent::ApplyCommitPreset(idx) => {
+				self.commit_popup.apply_preset(idx);
+				flags.insert(NeedsUpdate::COMMANDS);
+			}
+			InternalEvent::OpenCompareBasePopup => {
+				self.compare_base_popup.open()?;
+			}
 			InternalEvent::ViewSubmodules => {
 				self.submodule_popup.open()?;
 			}
@@ -798,11 +1101,44 @@ impl App {
 				self.file_to_open = path;
 				flags.insert(NeedsUpdate::COMMANDS);
 			}
+			InternalEvent::OpenShell => {
+				self.input.set_polling(false);
+				self.external_editor_popup.show()?;
+				self.shell_to_open = true;
+				flags.insert(NeedsUpdate::COMMANDS);
+			}
+			InternalEvent::ShowPendingOperation(state) => {
+				self.pending_operation_popup.open(state);
+				flags.insert(NeedsUpdate::COMMANDS);
+			}
+			InternalEvent::ShowCommitSummary(summary) => {
+				self.commit_summary_popup.open(summary);
+				flags.insert(NeedsUpdate::COMMANDS);
+			}
+			InternalEvent::ContinueCommit(step) => {
+				self.commit_popup.continue_commit(step);
+				flags.insert(NeedsUpdate::COMMANDS);
+			}
+			InternalEvent::ShowStagedChecks(checks) => {
+				self.staged_checks_popup.open(checks);
+				flags.insert(NeedsUpdate::COMMANDS);
+			}
+			InternalEvent::SelectStagedFile(path) => {
+				self.commit_popup.hide();
+				self.set_tab(0)?;
+				self.status_tab.select_staged_file(&path)?;
+				flags
+					.insert(NeedsUpdate::ALL | NeedsUpdate::COMMANDS);
+			}
 			InternalEvent::Push(branch, push_type, force, delete) => {
 				self.push_popup
 					.push(branch, push_type, force, delete)?;
 				flags.insert(NeedsUpdate::ALL);
 			}
+			InternalEvent::PushAllRemotes(branch) => {
+				self.push_popup.push_all_remotes(branch)?;
+				flags.insert(NeedsUpdate::ALL);
+			}
 			InternalEvent::Pull(branch) => {
 				if let Err(error) = self.pull_popup.fetch(branch) {
 					self.queue.push(InternalEvent::ShowErrorMsg(
@@ -819,6 +1155,28 @@ impl App {
 				}
 				flags.insert(NeedsUpdate::ALL);
 			}
+			InternalEvent::OpenFetchRef => {
+				self.fetch_ref_popup.open()?;
+				flags.insert(NeedsUpdate::COMMANDS);
+			}
+			InternalEvent::FetchTarget(target) => {
+				if let Err(error) =
+					self.fetch_popup.fetch_target(target)
+				{
+					self.queue.push(InternalEvent::ShowErrorMsg(
+						error.to_string(),
+					));
+				}
+				flags.insert(NeedsUpdate::ALL);
+			}
+			InternalEvent::FetchTags => {
+				if let Err(error) = self.fetch_popup.fetch_tags() {
+					self.queue.push(InternalEvent::ShowErrorMsg(
+						error.to_string(),
+					));
+				}
+				flags.insert(NeedsUpdate::ALL);
+			}
 			InternalEvent::PushTags => {
 				self.push_tags_popup.push_tags()?;
 				flags.insert(NeedsUpdate::ALL);
@@ -846,7 +1204,13 @@ impl App {
 					| AppOption::DiffInterhunkLines => {
 						self.status_tab.update_diff()?;
 					}
-					AppOption::HookTimeout => {}
+					AppOption::DiffWordDiff
+					| AppOption::HookTimeout
+					| AppOption::HookConfigTimeout
+					| AppOption::HooksEnabled
+					| AppOption::HooksSkipped
+					| AppOption::HookCommitMsgPreview
+					| AppOption::CommitLineNumbers => {}
 				}
 
 				flags.insert(NeedsUpdate::ALL);
@@ -901,7 +1265,12 @@ impl App {
 					QuitState::OpenSubmodule(submodule_repo_path);
 			}
 			InternalEvent::OpenResetPopup(id) => {
-				self.reset_popup.open(id)?;
+				if !block_pending_operation(
+					&self.repo.borrow(),
+					&self.queue,
+				) {
+					self.reset_popup.open(id)?;
+				}
 			}
 			InternalEvent::CommitSearch(options) => {
 				self.revlog.search(options);
@@ -998,6 +1367,18 @@ impl App {
 					undo_last_commit(&self.repo.borrow())
 				);
 			}
+			Action::DropCommit(id, _) => {
+				self.drop_commit(id);
+			}
+			Action::CheckoutFile(id, path) => {
+				self.checkout_file(id, &path);
+			}
+			Action::LargePaste(text) => {
+				event_pump(
+					&Event::Paste(text),
+					self.components_mut().as_mut_slice(),
+				)?;
+			}
 		}
 
 		flags.insert(NeedsUpdate::ALL);
@@ -1024,6 +1405,136 @@ impl App {
 		Ok(())
 	}
 
+	/// runs the `post-checkout` hook, its failure is only reported as
+	/// the checkout is done already
+	fn post_checkout(
+		&self,
+		prev_head: CommitId,
+		branch_checkout: bool,
+	) {
+		let repo = self.repo.borrow();
+		let timeout = self.options.borrow().hook_timeout();
+
+		let result = sync::get_head(&repo).and_then(|new_head| {
+			sync::hooks_post_checkout_with_timeout(
+				&repo,
+				prev_head,
+				new_head,
+				branch_checkout,
+				timeout,
+			)
+		});
+
+		self.show_post_hook_result("post-checkout", result);
+	}
+
+	fn post_merge(&self, is_squash: bool) {
+		let timeout = self.options.borrow().hook_timeout();
+
+		let result = sync::hooks_post_merge_with_timeout(
+			&self.repo.borrow(),
+			is_squash,
+			timeout,
+		);
+
+		self.show_post_hook_result("post-merge", result);
+	}
+
+	fn post_rewrite(
+		&self,
+		source: PostRewriteSource,
+		rewritten: &[(CommitId, CommitId)],
+	) {
+		let timeout = self.options.borrow().hook_timeout();
+
+		let result = sync::hooks_post_rewrite_with_timeout(
+			&self.repo.borrow(),
+			source,
+			rewritten,
+			timeout,
+		);
+
+		self.show_post_hook_result("post-rewrite", result);
+	}
+
+	/// a hook running after an operation can not undo it, a failure is
+	/// only shown
+	fn show_post_hook_result(
+		&self,
+		hook: &str,
+		result: asyncgit::Result<HookResult>,
+	) {
+		match result {
+			Ok(HookResult::Ok | HookResult::Cancelled { .. }) => {}
+			Ok(HookResult::NotOk(failure)) => {
+				let e = failure.msg();
+				log::error!("{hook} hook error: {}", e);
+				self.queue.push(InternalEvent::ShowErrorMsg(
+					format!("{hook} hook error:\n{e}"),
+				));
+			}
+			Ok(HookResult::TimedOut {
+				hook: path,
+				stdout,
+				stderr,
+				timeout,
+				source,
+			}) => {
+				log::error!("{hook} hook timed out");
+				self.queue.push(InternalEvent::ShowErrorMsg(
+					strings::hook_timed_out(
+						hook, timeout, source, &path, &stdout,
+						&stderr,
+					),
+				));
+			}
+			Err(e) => {
+				self.queue.push(InternalEvent::ShowErrorMsg(
+					format!("{hook} hook failed:\n{e}"),
+				));
+			}
+		}
+	}
+
+	fn drop_commit(&self, id: CommitId) {
+		match sync::drop_commit(&self.repo.borrow(), id) {
+			Ok(RebaseState::Finished) => {}
+			Ok(RebaseState::Conflicted) => {
+				self.queue.push(InternalEvent::ShowInfoMsg(
+					strings::drop_commit_conflicted(),
+				));
+				self.queue.push(InternalEvent::TabSwitchStatus);
+			}
+			Err(e) => {
+				self.queue.push(InternalEvent::ShowErrorMsg(
+					format!("drop commit failed:\n{e}"),
+				));
+			}
+		}
+	}
+
+	fn show_audit_log(&mut self) -> Result<()> {
+		match audit::recent(&self.repo.borrow())? {
+			None => self
+				.msg_popup
+				.show_info(&strings::audit_log_disabled()),
+			Some(log) if log.is_empty() => {
+				self.msg_popup.show_info(&strings::audit_log_empty())
+			}
+			Some(log) => self.msg_popup.show_audit_log(&log),
+		}
+	}
+
+	fn checkout_file(&self, id: CommitId, path: &str) {
+		if let Err(e) =
+			sync::checkout_tree_file(&self.repo.borrow(), id, path)
+		{
+			self.queue.push(InternalEvent::ShowErrorMsg(format!(
+				"checkout file failed:\n{e}"
+			)));
+		}
+	}
+
 	fn delete_remote_branch(
 		&mut self,
 		branch_ref: &str,
@@ -1111,6 +1622,40 @@ impl App {
 			.order(order::NAV),
 		);
 
+		res.push(
+			CommandInfo::new(
+				strings::commands::performance_popup(
+					&self.key_config,
+				),
+				true,
+				!self.any_popup_visible(),
+			)
+			.order(order::NAV),
+		);
+
+		res.push(
+			CommandInfo::new(
+				strings::commands::audit_log_popup(&self.key_config),
+				true,
+				!self.any_popup_visible(),
+			)
+			.order(order::RARE_ACTION),
+		);
+
+		res.push(CommandInfo::new(
+			strings::commands::macro_record(
+				&self.key_config,
+				self.macros.recording().is_some(),
+			),
+			true,
+			self.global_keys_enabled() || force_all,
+		));
+		res.push(CommandInfo::new(
+			strings::commands::macro_replay(&self.key_config),
+			true,
+			self.global_keys_enabled() || force_all,
+		));
+
 		res.push(
 			CommandInfo::new(
 				strings::commands::quit(&self.key_config),
@@ -1181,14 +1726,31 @@ impl App {
 			table_area,
 		);
 
+		let macro_state = self
+			.macros
+			.recording()
+			.map(strings::macro_recording)
+			.or_else(|| {
+				self.macros.replaying().map(strings::macro_replaying)
+			})
+			.map(|state| format!("{state}  "))
+			.unwrap_or_default();
+
 		f.render_widget(
-			Paragraph::new(Line::from(vec![Span::styled(
-				ellipsis_trim_start(
-					&self.repo_path_text,
-					text_area.width as usize,
+			Paragraph::new(Line::from(vec![
+				Span::styled(
+					macro_state.as_str(),
+					self.theme.text_danger(),
+				),
+				Span::styled(
+					ellipsis_trim_start(
+						&self.repo_path_text,
+						(text_area.width as usize)
+							.saturating_sub(macro_state.width()),
+					),
+					self.theme.title(false),
 				),
-				self.theme.title(false),
-			)]))
+			]))
 			.alignment(Alignment::Right),
 			text_area,
 		);
@@ -1,4 +1,4 @@
-use crate::bug_report;
+use crate::{bug_report, frame_scheduler::DEFAULT_MAX_FPS};
 use anyhow::{anyhow, Result};
 use asyncgit::sync::RepoPath;
 use clap::{
@@ -16,6 +16,8 @@ pub struct CliArgs {
 	pub theme: PathBuf,
 	pub repo_path: RepoPath,
 	pub notify_watcher: bool,
+	pub max_fps: u32,
+	pub commit_cache_size: Option<usize>,
 }
 
 pub fn process_cmdline() -> Result<CliArgs> {
@@ -55,10 +57,20 @@ pub fn process_cmdline() -> Result<CliArgs> {
 	let notify_watcher: bool =
 		*arg_matches.get_one("watcher").unwrap_or(&false);
 
+	let max_fps = arg_matches
+		.get_one::<u32>("max_fps")
+		.copied()
+		.unwrap_or(DEFAULT_MAX_FPS);
+
+	let commit_cache_size =
+		arg_matches.get_one::<usize>("commit_cache_size").copied();
+
 	Ok(CliArgs {
 		theme,
 		repo_path,
 		notify_watcher,
+		max_fps,
+		commit_cache_size,
 	})
 }
 
@@ -105,6 +117,22 @@ fn app() -> ClapApp {
 				.long("watcher")
 				.action(clap::ArgAction::SetTrue),
 		)
+		.arg(
+			Arg::new("max_fps")
+				.help("Limit how often the screen is redrawn per second")
+				.long("max-fps")
+				.value_name("FPS")
+				.value_parser(clap::value_parser!(u32).range(1..))
+				.num_args(1),
+		)
+		.arg(
+			Arg::new("commit_cache_size")
+				.help("Amount of commits to keep parsed in memory per repository")
+				.long("commit-cache-size")
+				.value_name("COMMITS")
+				.value_parser(clap::value_parser!(usize))
+				.num_args(1),
+		)
 		.arg(
 			Arg::new("bugreport")
 				.help("Generate a bug report")
@@ -149,7 +177,7 @@ fn setup_logging(path_override: Option<PathBuf>) -> Result<()> {
 	Ok(())
 }
 
-fn get_app_cache_path() -> Result<PathBuf> {
+pub fn get_app_cache_path() -> Result<PathBuf> {
 	let mut path = dirs::cache_dir()
 		.ok_or_else(|| anyhow!("failed to find os cache dir."))?;
 
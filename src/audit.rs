@@ -0,0 +1,191 @@
+//! opt-in audit trail of the ref changing operations gitui did.
+//!
+//! enabled per repository by pointing the git config key
+//! `gitui.auditLog` to a file (relative paths are taken relative to the
+//! git dir). every operation reported by asyncgit appends one json line
+//! with the time, operation, refs and old/new commit ids to it, see
+//! [`asyncgit::sync::AuditEvent`].
+
+use crate::storage;
+use anyhow::Result;
+use asyncgit::sync::{
+	get_config_string, repo_dir, set_audit_observer, AuditEvent,
+	RepoPath,
+};
+use chrono::{DateTime, Local};
+use serde::Serialize;
+use std::{
+	fs,
+	io::ErrorKind,
+	path::{Path, PathBuf},
+	sync::{Mutex, PoisonError},
+};
+
+/// git config key holding the path of the audit log
+pub const AUDIT_LOG_CONFIG: &str = "gitui.auditLog";
+
+/// amount of lines shown of the audit log
+pub const RECENT_LINES: usize = 200;
+
+/// appending reads and rewrites the whole file, operations finishing
+/// on different threads must not lose each others lines
+static WRITE: Mutex<()> = Mutex::new(());
+
+#[derive(Serialize)]
+struct AuditLine<'a> {
+	time: String,
+	operation: &'static str,
+	refs: &'a [String],
+	old: Option<String>,
+	new: Option<String>,
+}
+
+/// installs the observer writing the audit log of every repository
+/// that has it enabled
+pub fn init() {
+	set_audit_observer(Some(Box::new(|repo_path, event| {
+		if let Err(e) = append(repo_path, event) {
+			log::error!("writing audit log failed: {e}");
+		}
+	})));
+}
+
+/// path of the audit log of `repo_path`, `None` if not enabled
+pub fn log_path(repo_path: &RepoPath) -> Result<Option<PathBuf>> {
+	let Some(path) = get_config_string(repo_path, AUDIT_LOG_CONFIG)?
+	else {
+		return Ok(None);
+	};
+
+	if path.trim().is_empty() {
+		return Ok(None);
+	}
+
+	Ok(Some(repo_dir(repo_path)?.join(path)))
+}
+
+/// the last [`RECENT_LINES`] lines of the audit log, newest first.
+/// `None` if it is not enabled
+pub fn recent(repo_path: &RepoPath) -> Result<Option<String>> {
+	let Some(path) = log_path(repo_path)? else {
+		return Ok(None);
+	};
+
+	let content = match fs::read_to_string(&path) {
+		Ok(content) => content,
+		Err(e) if e.kind() == ErrorKind::NotFound => String::new(),
+		Err(e) => return Err(e.into()),
+	};
+
+	let recent = last_lines(&content, RECENT_LINES)
+		.lines()
+		.rev()
+		.collect::<Vec<_>>()
+		.join("\n");
+
+	Ok(Some(recent))
+}
+
+fn append(repo_path: &RepoPath, event: &AuditEvent) -> Result<()> {
+	if let Some(path) = log_path(repo_path)? {
+		append_line(&path, &format_line(event, Local::now())?)?;
+	}
+
+	Ok(())
+}
+
+fn format_line(
+	event: &AuditEvent,
+	time: DateTime<Local>,
+) -> Result<String> {
+	Ok(serde_json::to_string(&AuditLine {
+		time: time.to_rfc3339(),
+		operation: event.operation.as_str(),
+		refs: &event.refs,
+		old: event.old.map(|id| id.to_string()),
+		new: event.new.map(|id| id.to_string()),
+	})?)
+}
+
+fn append_line(path: &Path, line: &str) -> Result<()> {
+	let _guard = WRITE.lock().unwrap_or_else(PoisonError::into_inner);
+
+	let mut content = match fs::read_to_string(path) {
+		Ok(content) => content,
+		Err(e) if e.kind() == ErrorKind::NotFound => String::new(),
+		Err(e) => return Err(e.into()),
+	};
+
+	if !content.is_empty() && !content.ends_with('\n') {
+		content.push('\n');
+	}
+	content.push_str(line);
+	content.push('\n');
+
+	storage::write_atomic(path, content.as_bytes())
+}
+
+fn last_lines(content: &str, count: usize) -> &str {
+	let content = content.trim_end_matches('\n');
+
+	content
+		.rmatch_indices('\n')
+		.nth(count.saturating_sub(1))
+		.map_or(content, |(idx, _)| &content[idx + 1..])
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use asyncgit::sync::{AuditOperation, CommitId};
+	use chrono::TimeZone;
+	use pretty_assertions::assert_eq;
+	use tempfile::tempdir;
+
+	#[test]
+	fn test_format_line() {
+		let event = AuditEvent {
+			operation: AuditOperation::BranchDelete,
+			refs: vec![String::from("refs/heads/a\"b")],
+			old: Some(
+				CommitId::from_str_unchecked(
+					"0123456789abcdef000000000000000000000000",
+				)
+				.unwrap(),
+			),
+			new: None,
+		};
+		let time = Local.timestamp_opt(0, 0).unwrap();
+
+		let line = format_line(&event, time).unwrap();
+
+		assert_eq!(
+			line,
+			format!(
+				r#"{{"time":"{}","operation":"branch-delete","refs":["refs/heads/a\"b"],"old":"0123456789abcdef000000000000000000000000","new":null}}"#,
+				time.to_rfc3339()
+			)
+		);
+	}
+
+	#[test]
+	fn test_append_line() {
+		let dir = tempdir().unwrap();
+		let path = dir.path().join("audit.log");
+
+		append_line(&path, "first").unwrap();
+		append_line(&path, "second").unwrap();
+
+		assert_eq!(
+			fs::read_to_string(&path).unwrap(),
+			"first\nsecond\n"
+		);
+	}
+
+	#[test]
+	fn test_last_lines() {
+		assert_eq!(last_lines("a\nb\nc\n", 2), "b\nc");
+		assert_eq!(last_lines("a\nb\n", 5), "a\nb");
+		assert_eq!(last_lines("", 5), "");
+	}
+}
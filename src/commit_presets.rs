@@ -0,0 +1,363 @@
+//! user defined commit message presets, loaded from
+//! `commit_presets.ron` in the gitui config folder:
+//!
+//! ```ron
+//! (
+//!     ticket_regex: Some("[A-Z]+-[0-9]+"),
+//!     presets: [
+//!         (name: "deps", subject: "chore(deps): bump "),
+//!         (name: "ticket", subject: "{ticket}: "),
+//!         (name: "co-author", snippet: "Co-authored-by: "),
+//!     ],
+//!     // pre-fills new commit messages on branches containing a ticket
+//!     ticket_prefix: Some("{ticket}: "),
+//! )
+//! ```
+
+use crate::args::get_app_config_path;
+use anyhow::Result;
+use regex::Regex;
+use serde::Deserialize;
+use std::{fs::File, path::PathBuf};
+
+const COMMIT_PRESETS_FILENAME: &str = "commit_presets.ron";
+
+/// matches jira style ticket keys like `ABC-123`
+const DEFAULT_TICKET_REGEX: &str = "[A-Z][A-Z0-9]+-[0-9]+";
+
+#[derive(Debug, Default, Deserialize, PartialEq, Eq)]
+pub struct CommitPreset {
+	pub name: String,
+	/// replaces the first line of the message (if not empty)
+	#[serde(default)]
+	pub subject: String,
+	/// inserted at the cursor (if not empty)
+	#[serde(default)]
+	pub snippet: String,
+}
+
+#[derive(Default, Deserialize)]
+struct CommitPresetsFile {
+	#[serde(default)]
+	ticket_regex: Option<String>,
+	#[serde(default)]
+	presets: Vec<CommitPreset>,
+	#[serde(default)]
+	ticket_prefix: Option<String>,
+}
+
+pub struct CommitPresets {
+	presets: Vec<CommitPreset>,
+	ticket_regex: Regex,
+	ticket_prefix: Option<String>,
+}
+
+impl Default for CommitPresets {
+	fn default() -> Self {
+		Self {
+			presets: Vec::new(),
+			ticket_regex: default_ticket_regex(),
+			ticket_prefix: None,
+		}
+	}
+}
+
+impl CommitPresets {
+	/// loads the presets from the config folder,
+	/// a missing or broken file results in no presets
+	pub fn init() -> Self {
+		let file = match Self::get_config_file() {
+			Ok(file) => file,
+			Err(e) => {
+				log::error!("commit presets config path: {e}");
+				return Self::default();
+			}
+		};
+
+		let Ok(f) = File::open(file) else {
+			return Self::default();
+		};
+
+		match ron::de::from_reader(f) {
+			Ok(data) => Self::from_data(data),
+			Err(e) => {
+				log::error!("commit presets parse error: {e}");
+				Self::default()
+			}
+		}
+	}
+
+	fn get_config_file() -> Result<PathBuf> {
+		Ok(get_app_config_path()?.join(COMMIT_PRESETS_FILENAME))
+	}
+
+	fn from_data(data: CommitPresetsFile) -> Self {
+		let ticket_regex = data
+			.ticket_regex
+			.and_then(|regex| {
+				Regex::new(&regex)
+					.map_err(|e| {
+						log::error!("invalid ticket_regex: {e}");
+					})
+					.ok()
+			})
+			.unwrap_or_else(default_ticket_regex);
+
+		Self {
+			presets: data.presets,
+			ticket_regex,
+			ticket_prefix: data.ticket_prefix,
+		}
+	}
+
+	pub fn presets(&self) -> &[CommitPreset] {
+		&self.presets
+	}
+
+	/// expands the placeholders in `text` (see [`expand_placeholders`])
+	/// for the current branch `branch`
+	pub fn expand(&self, text: &str, branch: Option<&str>) -> String {
+		let ticket = branch.and_then(|branch| {
+			extract_ticket(&self.ticket_regex, branch)
+		});
+
+		expand_placeholders(text, branch, ticket)
+	}
+
+	/// the configured `ticket_prefix` expanded for `branch`,
+	/// `None` if not configured or `branch` contains no ticket
+	pub fn ticket_prefix(&self, branch: &str) -> Option<String> {
+		let template = self.ticket_prefix.as_ref()?;
+		let ticket = extract_ticket(&self.ticket_regex, branch)?;
+
+		Some(expand_placeholders(
+			template,
+			Some(branch),
+			Some(ticket),
+		))
+	}
+}
+
+/// `msg` starting with `prefix`, unless it already does
+/// (ignoring trailing whitespace of `prefix`)
+pub fn prefix_msg(msg: &str, prefix: &str) -> String {
+	if msg.starts_with(prefix.trim_end()) {
+		msg.to_string()
+	} else {
+		format!("{prefix}{msg}")
+	}
+}
+
+fn default_ticket_regex() -> Regex {
+	Regex::new(DEFAULT_TICKET_REGEX).expect("valid default regex")
+}
+
+/// ticket id found in `branch` by `regex`: the first capture group
+/// if the regex has one, otherwise the whole match
+pub fn extract_ticket<'a>(
+	regex: &Regex,
+	branch: &'a str,
+) -> Option<&'a str> {
+	let captures = regex.captures(branch)?;
+
+	captures
+		.get(1)
+		.or_else(|| captures.get(0))
+		.map(|m| m.as_str())
+}
+
+/// replaces `{branch}` and `{ticket}` in `text`, placeholders without
+/// a value are replaced by nothing, unknown ones are left untouched
+pub fn expand_placeholders(
+	text: &str,
+	branch: Option<&str>,
+	ticket: Option<&str>,
+) -> String {
+	let mut result = String::with_capacity(text.len());
+	let mut rest = text;
+
+	while let Some(start) = rest.find('{') {
+		result.push_str(&rest[..start]);
+		rest = &rest[start..];
+
+		let value = rest.find('}').and_then(|end| {
+			let value = match &rest[1..end] {
+				"branch" => branch.unwrap_or_default(),
+				"ticket" => ticket.unwrap_or_default(),
+				_ => return None,
+			};
+			Some((value, end))
+		});
+
+		if let Some((value, end)) = value {
+			result.push_str(value);
+			rest = &rest[end + 1..];
+		} else {
+			result.push('{');
+			rest = &rest[1..];
+		}
+	}
+
+	result.push_str(rest);
+	result
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use pretty_assertions::assert_eq;
+
+	#[test]
+	fn test_parse() {
+		let data: CommitPresetsFile = ron::de::from_str(
+			r#"(
+				ticket_regex: Some("^feature/([a-z]+-[0-9]+)"),
+				presets: [
+					(name: "deps", subject: "chore(deps): bump "),
+					(name: "co", snippet: "Co-authored-by: "),
+				],
+			)"#,
+		)
+		.unwrap();
+
+		let presets = CommitPresets::from_data(data);
+
+		assert_eq!(
+			presets.presets(),
+			&[
+				CommitPreset {
+					name: String::from("deps"),
+					subject: String::from("chore(deps): bump "),
+					snippet: String::new(),
+				},
+				CommitPreset {
+					name: String::from("co"),
+					subject: String::new(),
+					snippet: String::from("Co-authored-by: "),
+				},
+			]
+		);
+
+		assert_eq!(
+			presets.expand("{ticket}: ", Some("feature/abc-12-foo")),
+			"abc-12: "
+		);
+	}
+
+	#[test]
+	fn test_invalid_regex_falls_back_to_default() {
+		let presets = CommitPresets::from_data(CommitPresetsFile {
+			ticket_regex: Some(String::from("(")),
+			..CommitPresetsFile::default()
+		});
+
+		assert_eq!(
+			presets.expand("{ticket}", Some("fix/ABC-123-foo")),
+			"ABC-123"
+		);
+	}
+
+	#[test]
+	fn test_ticket_prefix() {
+		let presets = CommitPresets::from_data(CommitPresetsFile {
+			ticket_regex: Some(String::from(
+				"^(?:feature|fix)/([A-Z]+-[0-9]+)",
+			)),
+			ticket_prefix: Some(String::from("{ticket}: ")),
+			..CommitPresetsFile::default()
+		});
+
+		assert_eq!(
+			presets.ticket_prefix("feature/ABC-123-foo").as_deref(),
+			Some("ABC-123: ")
+		);
+		assert_eq!(presets.ticket_prefix("chore/ABC-123-foo"), None);
+		assert_eq!(presets.ticket_prefix("feature/foo"), None);
+
+		let presets = CommitPresets::from_data(CommitPresetsFile {
+			ticket_prefix: Some(String::from("[{ticket}] ")),
+			..CommitPresetsFile::default()
+		});
+
+		assert_eq!(
+			presets.ticket_prefix("XY-7_bar").as_deref(),
+			Some("[XY-7] ")
+		);
+
+		// not configured
+		assert_eq!(
+			CommitPresets::default().ticket_prefix("feature/ABC-123"),
+			None
+		);
+	}
+
+	#[test]
+	fn test_prefix_msg_no_duplication() {
+		assert_eq!(prefix_msg("", "ABC-1: "), "ABC-1: ");
+		assert_eq!(
+			prefix_msg("fix foo", "ABC-1: "),
+			"ABC-1: fix foo"
+		);
+		assert_eq!(
+			prefix_msg("ABC-1: fix foo", "ABC-1: "),
+			"ABC-1: fix foo"
+		);
+		assert_eq!(prefix_msg("ABC-1:", "ABC-1: "), "ABC-1:");
+		assert_eq!(
+			prefix_msg("ABC-12: fix", "ABC-1: "),
+			"ABC-1: ABC-12: fix"
+		);
+	}
+
+	#[test]
+	fn test_extract_ticket() {
+		let regex = default_ticket_regex();
+
+		assert_eq!(
+			extract_ticket(&regex, "feature/ABC-123-foo"),
+			Some("ABC-123")
+		);
+		assert_eq!(extract_ticket(&regex, "feature/foo"), None);
+
+		let regex = Regex::new("^[a-z]+/([0-9]+)").unwrap();
+
+		assert_eq!(extract_ticket(&regex, "fix/42-bar"), Some("42"));
+	}
+
+	#[test]
+	fn test_expand_placeholders() {
+		assert_eq!(
+			expand_placeholders(
+				"{ticket}: on {branch}",
+				Some("feature/ABC-1"),
+				Some("ABC-1")
+			),
+			"ABC-1: on feature/ABC-1"
+		);
+
+		assert_eq!(
+			expand_placeholders("{ticket}: ", Some("main"), None),
+			": "
+		);
+
+		assert_eq!(
+			expand_placeholders("{unknown} {branch", Some("x"), None),
+			"{unknown} {branch"
+		);
+
+		// values are not expanded again
+		assert_eq!(
+			expand_placeholders(
+				"{branch}",
+				Some("{ticket}"),
+				Some("T")
+			),
+			"{ticket}"
+		);
+
+		assert_eq!(
+			expand_placeholders("{{branch}}", Some("main"), None),
+			"{main}"
+		);
+	}
+}
@@ -60,6 +60,11 @@ impl ChangesComponent {
 		self.files.selection()
 	}
 
+	/// selects the file at `path`, returns false if it is not listed
+	pub fn select_path(&mut self, path: &str) -> bool {
+		self.files.select_path(path)
+	}
+
 	///
 	pub fn focus_select(&mut self, focus: bool) {
 		self.files.focus(focus);
@@ -14,7 +14,7 @@ use crate::{
 };
 use anyhow::Result;
 use asyncgit::{
-	sync::{commit_files::OldNew, CommitTags},
+	sync::{commit_files::OldNew, CommitId, CommitTags},
 	AsyncCommitFiles, CommitFilesParams,
 };
 use compare_details::CompareDetailsComponent;
@@ -101,6 +101,13 @@ impl CommitDetailsComponent {
 				}
 			}
 
+			if let Some(res) = self.git_commit_files.cached(id)? {
+				self.file_tree.update(res.as_slice())?;
+				self.file_tree.set_title(self.get_files_title());
+
+				return Ok(());
+			}
+
 			self.file_tree.clear()?;
 			self.git_commit_files.fetch(id)?;
 		}
@@ -110,6 +117,14 @@ impl CommitDetailsComponent {
 		Ok(())
 	}
 
+	/// loads the file lists of `ids` in the background so that
+	/// selecting them later shows their files right away
+	pub fn prefetch(&self, ids: &[CommitId]) -> Result<()> {
+		self.git_commit_files.prefetch(ids)?;
+
+		Ok(())
+	}
+
 	///
 	pub fn any_work_pending(&self) -> bool {
 		self.git_commit_files.is_pending()
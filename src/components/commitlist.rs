@@ -6,6 +6,7 @@ use crate::{
 		Component, DrawableComponent, EventState, ScrollType,
 	},
 	keys::{key_match, SharedKeyConfig},
+	popups::block_pending_operation,
 	queue::{InternalEvent, Queue},
 	strings::{self, symbol},
 	try_or_popup,
@@ -109,6 +110,26 @@ impl CommitList {
 		)
 	}
 
+	/// ids of the loaded entries up to `distance` rows below and above
+	/// the selection, closest first (without the selection itself)
+	pub fn ids_around_selection(
+		&self,
+		distance: usize,
+	) -> Vec<CommitId> {
+		let items = self.items.iter().as_slice();
+		let selected =
+			self.selection.saturating_sub(self.items.index_offset());
+
+		(1..=distance)
+			.flat_map(|d| {
+				[selected.checked_add(d), selected.checked_sub(d)]
+			})
+			.flatten()
+			.filter_map(|idx| items.get(idx))
+			.map(|entry| entry.id)
+			.collect()
+	}
+
 	///
 	pub fn marked_count(&self) -> usize {
 		self.marked.len()
@@ -176,11 +197,26 @@ impl CommitList {
 			try_or_popup!(
 				self,
 				"failed to checkout commit:",
-				checkout_commit(&self.repo.borrow(), commit_hash)
+				self.checkout_inner(commit_hash)
 			);
 		}
 	}
 
+	fn checkout_inner(&self, commit_hash: CommitId) -> Result<()> {
+		if block_pending_operation(&self.repo.borrow(), &self.queue) {
+			return Ok(());
+		}
+
+		let prev_head = sync::get_head(&self.repo.borrow())?;
+		checkout_commit(&self.repo.borrow(), commit_hash)?;
+		self.queue.push(InternalEvent::PostCheckout {
+			prev_head,
+			branch_checkout: true,
+		});
+
+		Ok(())
+	}
+
 	///
 	pub fn set_local_branches(
 		&mut self,
@@ -955,13 +991,30 @@ mod tests {
 		);
 	}
 
+	#[test]
+	fn test_ids_around_selection() {
+		let cl = build_commit_list_with_some_commits();
+
+		let expected: Vec<CommitId> = [3, 1, 4, 0, 5]
+			.iter()
+			.map(|idx| {
+				CommitId::from_str_unchecked(&format!("{idx:040}"))
+					.unwrap()
+			})
+			.collect();
+
+		// the selection is the entry with id 2 (offset 2), there is
+		// nothing loaded above id 0
+		assert_eq!(cl.ids_around_selection(3), expected);
+	}
+
 	/// Build a commit list with a few commits loaded
 	fn build_commit_list_with_some_commits() -> CommitList {
 		let mut items = ItemBatch::default();
 		let basic_commit_info = CommitInfo {
 			message: String::default(),
 			time: 0,
-			author: String::default(),
+			author: "".into(),
 			id: CommitId::default(),
 		};
 		// This just creates a sequence of fake ordered ids
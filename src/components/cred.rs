@@ -134,6 +134,10 @@ impl Component for CredComponent {
 						self.hide();
 					}
 				}
+			} else if let Event::Paste(_) = ev {
+				if !self.input_username.event(ev)?.is_consumed() {
+					self.input_password.event(ev)?;
+				}
 			}
 			return Ok(EventState::Consumed);
 		}
@@ -1,7 +1,10 @@
 use super::{
 	utils::scroll_horizontal::HorizontalScroll,
-	utils::scroll_vertical::VerticalScroll, CommandBlocking,
-	Direction, DrawableComponent, HorizontalScrollType, ScrollType,
+	utils::scroll_vertical::VerticalScroll,
+	utils::search::{contains_ignore_case, SearchState},
+	utils::word_diff::word_diff,
+	CommandBlocking, Direction, DrawableComponent,
+	HorizontalScrollType, ScrollType,
 };
 use crate::{
 	app::Environment,
@@ -29,7 +32,7 @@ use ratatui::{
 	widgets::{Block, Borders, Paragraph},
 	Frame,
 };
-use std::{borrow::Cow, cell::Cell, cmp, path::Path};
+use std::{borrow::Cow, cell::Cell, cmp, ops::Range, path::Path};
 
 #[derive(Default)]
 struct Current {
@@ -105,6 +108,12 @@ impl Selection {
 pub struct DiffComponent {
 	repo: RepoPathRef,
 	diff: Option<FileDiff>,
+	/// tab expanded content of every diff line, only recomputed when
+	/// the diff hash changes instead of on every frame
+	expanded_lines: Vec<String>,
+	/// changed ranges of every expanded line, see [`word_diff`]
+	word_diff: Vec<Vec<Range<usize>>>,
+	search: SearchState,
 	longest_line: usize,
 	pending: bool,
 	selection: Selection,
@@ -131,6 +140,9 @@ impl DiffComponent {
 			pending: false,
 			selected_hunk: None,
 			diff: None,
+			expanded_lines: Vec::new(),
+			word_diff: Vec::new(),
+			search: SearchState::None,
 			longest_line: 0,
 			current_size: Cell::new((0, 0)),
 			selection: Selection::Single(0),
@@ -155,6 +167,8 @@ impl DiffComponent {
 	pub fn clear(&mut self, pending: bool) {
 		self.current = Current::default();
 		self.diff = None;
+		self.expanded_lines.clear();
+		self.word_diff.clear();
 		self.longest_line = 0;
 		self.vertical_scroll.reset();
 		self.horizontal_scroll.reset();
@@ -182,20 +196,33 @@ impl DiffComponent {
 				hash,
 			};
 
-			self.diff = Some(diff);
-
-			self.longest_line = self
-				.diff
+			self.expanded_lines = diff
+				.hunks
 				.iter()
-				.flat_map(|diff| diff.hunks.iter())
 				.flat_map(|hunk| hunk.lines.iter())
 				.map(|line| {
-					let converted_content = tabs_to_spaces(
-						line.content.as_ref().to_string(),
-					);
-
-					converted_content.len()
+					tabs_to_spaces(line.content.as_ref().to_string())
 				})
+				.collect();
+
+			self.word_diff = word_diff(
+				&diff
+					.hunks
+					.iter()
+					.flat_map(|hunk| hunk.lines.iter())
+					.zip(&self.expanded_lines)
+					.map(|(line, expanded)| {
+						(line.line_type, expanded.as_str())
+					})
+					.collect::<Vec<_>>(),
+			);
+
+			self.diff = Some(diff);
+
+			self.longest_line = self
+				.expanded_lines
+				.iter()
+				.map(String::len)
 				.max()
 				.map_or(0, |len| {
 					// Each hunk uses a 1-character wide vertical bar to its left to indicate
@@ -258,6 +285,43 @@ impl DiffComponent {
 		}
 	}
 
+	/// `true` while a search query is typed
+	pub const fn is_searching(&self) -> bool {
+		self.search.is_input()
+	}
+
+	/// lines containing `query` (case insensitive)
+	fn search_matches<'a>(
+		&'a self,
+		query: &'a str,
+	) -> impl Iterator<Item = usize> + 'a {
+		self.expanded_lines
+			.iter()
+			.enumerate()
+			.filter(|(_, line)| contains_ignore_case(line, query))
+			.map(|(idx, _)| idx)
+	}
+
+	/// selects the first match below the selection,
+	/// wraps around to the first line
+	fn search_next(&mut self) {
+		let Some(query) = self.search.query() else {
+			return;
+		};
+
+		let current = self.selection.get_bottom();
+		let matches = self.search_matches(query).collect::<Vec<_>>();
+		let next = matches
+			.iter()
+			.find(|line| **line > current)
+			.or_else(|| matches.first())
+			.copied();
+
+		if let Some(line) = next {
+			self.update_selection(line);
+		}
+	}
+
 	fn lines_count(&self) -> usize {
 		self.diff.as_ref().map_or(0, |diff| diff.lines)
 	}
@@ -333,6 +397,8 @@ impl DiffComponent {
 
 				let min = self.vertical_scroll.get_top();
 				let max = min + height as usize;
+				let word_diff =
+					self.options.borrow().diff_word_diff();
 
 				let mut line_cursor = 0_usize;
 				let mut lines_added = 0_usize;
@@ -360,6 +426,19 @@ impl DiffComponent {
 								res.push(Self::get_line_to_add(
 									width,
 									line,
+									self.expanded_lines
+										.get(line_cursor)
+										.map_or("", String::as_str),
+									if word_diff {
+										self.word_diff
+											.get(line_cursor)
+											.map_or(
+												&[],
+												Vec::as_slice,
+											)
+									} else {
+										&[]
+									},
 									self.focused()
 										&& self
 											.selection
@@ -419,9 +498,12 @@ impl DiffComponent {
 		])]
 	}
 
+	#[allow(clippy::too_many_arguments)]
 	fn get_line_to_add<'a>(
 		width: u16,
 		line: &'a DiffLine,
+		expanded_content: &str,
+		changed: &[Range<usize>],
 		selected: bool,
 		selected_hunk: bool,
 		end_of_hunk: bool,
@@ -448,29 +530,63 @@ impl DiffComponent {
 			}
 		};
 
-		let content =
-			if !is_content_line && line.content.as_ref().is_empty() {
-				theme.line_break()
-			} else {
-				tabs_to_spaces(line.content.as_ref().to_string())
-			};
-		let content = trim_offset(&content, scrolled_right);
+		let line_break =
+			!is_content_line && line.content.as_ref().is_empty();
+		let content = if line_break {
+			Cow::from(theme.line_break())
+		} else {
+			Cow::from(expanded_content)
+		};
+		let trimmed = trim_offset(&content, scrolled_right);
+		let scrolled_bytes = content.len() - trimmed.len();
+
+		let style = theme.diff_line(line.line_type, selected);
+		let mut spans = vec![left_side_of_line];
+
+		// the changed words scrolled into view
+		let mut unchanged_start = 0;
+		for range in changed.iter().filter(|_| !line_break) {
+			let start = range
+				.start
+				.saturating_sub(scrolled_bytes)
+				.min(trimmed.len());
+			let end = range
+				.end
+				.saturating_sub(scrolled_bytes)
+				.min(trimmed.len());
+			if start >= end {
+				continue;
+			}
+
+			if unchanged_start < start {
+				spans.push(Span::styled(
+					Cow::from(
+						trimmed[unchanged_start..start].to_string(),
+					),
+					style,
+				));
+			}
+			spans.push(Span::styled(
+				Cow::from(trimmed[start..end].to_string()),
+				theme.diff_word(line.line_type, selected),
+			));
+			unchanged_start = end;
+		}
 
+		let rest = &trimmed[unchanged_start..];
 		let filled = if selected {
 			// selected line
-			format!("{content:w$}\n", w = width as usize)
+			let width = usize::from(width).saturating_sub(
+				trimmed[..unchanged_start].chars().count(),
+			);
+			format!("{rest:width$}\n")
 		} else {
 			// weird eof missing eol line
-			format!("{content}\n")
+			format!("{rest}\n")
 		};
+		spans.push(Span::styled(Cow::from(filled), style));
 
-		Line::from(vec![
-			left_side_of_line,
-			Span::styled(
-				Cow::from(filled),
-				theme.diff_line(line.line_type, selected),
-			),
-		])
+		Line::from(spans)
 	}
 
 	const fn hunk_visible(
@@ -727,6 +843,18 @@ impl DrawableComponent for DiffComponent {
 			r,
 		);
 
+		self.search.draw_prompt(
+			f,
+			Rect::new(
+				r.x + 1,
+				r.bottom().saturating_sub(1),
+				r.width.saturating_sub(2),
+				1,
+			),
+			&self.theme,
+			|query| self.search_matches(query).count(),
+		);
+
 		if self.focused() {
 			self.vertical_scroll.draw(f, r, &self.theme);
 
@@ -809,6 +937,25 @@ impl Component for DiffComponent {
 			));
 		}
 
+		out.push(CommandInfo::new(
+			strings::commands::diff_search(&self.key_config),
+			self.diff.is_some(),
+			self.focused(),
+		));
+		out.push(CommandInfo::new(
+			strings::commands::diff_search_next(&self.key_config),
+			self.search.query().is_some(),
+			self.focused() && self.search.query().is_some(),
+		));
+		out.push(CommandInfo::new(
+			strings::commands::diff_toggle_word_diff(
+				&self.key_config,
+				self.options.borrow().diff_word_diff(),
+			),
+			true,
+			self.focused(),
+		));
+
 		out.push(CommandInfo::new(
 			strings::commands::copy(&self.key_config),
 			true,
@@ -821,6 +968,13 @@ impl Component for DiffComponent {
 	#[allow(clippy::cognitive_complexity, clippy::too_many_lines)]
 	fn event(&mut self, ev: &Event) -> Result<EventState> {
 		if self.focused() {
+			if self.is_searching() {
+				if self.search.input(ev) {
+					self.search_next();
+				}
+				return Ok(EventState::Consumed);
+			}
+
 			if let Event::Key(e) = ev {
 				return if key_match(e, self.key_config.keys.move_down)
 				{
@@ -922,6 +1076,24 @@ impl Component for DiffComponent {
 						}
 					}
 					Ok(EventState::Consumed)
+				} else if key_match(
+					e,
+					self.key_config.keys.diff_search,
+				) {
+					self.search = SearchState::Input(String::new());
+					Ok(EventState::Consumed)
+				} else if key_match(
+					e,
+					self.key_config.keys.diff_search_next,
+				) {
+					self.search_next();
+					Ok(EventState::Consumed)
+				} else if key_match(
+					e,
+					self.key_config.keys.diff_toggle_word_diff,
+				) {
+					self.options.borrow_mut().diff_toggle_word_diff();
+					Ok(EventState::Consumed)
 				} else if key_match(e, self.key_config.keys.copy) {
 					self.copy_selection();
 					Ok(EventState::Consumed)
@@ -965,6 +1137,8 @@ mod tests {
 				DiffComponent::get_line_to_add(
 					4,
 					&diff_line,
+					"",
+					&[],
 					false,
 					false,
 					false,
@@ -1000,7 +1174,15 @@ mod tests {
 
 			assert_eq!(
 				DiffComponent::get_line_to_add(
-					4, &diff_line, false, false, false, &theme, 0
+					4,
+					&diff_line,
+					"",
+					&[],
+					false,
+					false,
+					false,
+					&theme,
+					0
 				)
 				.spans
 				.last()
@@ -7,6 +7,7 @@ mod diff;
 mod revision_files;
 mod status_tree;
 mod syntax_text;
+mod text_viewer;
 mod textinput;
 mod utils;
 
@@ -19,7 +20,8 @@ pub use cred::CredComponent;
 pub use diff::DiffComponent;
 pub use revision_files::RevisionFilesComponent;
 pub use syntax_text::SyntaxTextComponent;
-pub use textinput::{InputType, TextInputComponent};
+pub use text_viewer::TextViewerComponent;
+pub use textinput::{InputType, SharedKillRing, TextInputComponent};
 pub use utils::{
 	filetree::FileTreeItemKind, logitems::ItemBatch,
 	scroll_vertical::VerticalScroll, string_width_align,
@@ -7,7 +7,7 @@ use crate::{
 	app::Environment,
 	keys::{key_match, SharedKeyConfig},
 	popups::{BlameFileOpen, FileRevOpen},
-	queue::{InternalEvent, Queue, StackablePopupOpen},
+	queue::{Action, InternalEvent, Queue, StackablePopupOpen},
 	strings::{self, order, symbol},
 	try_or_popup,
 	ui::{self, common_nav, style::SharedTheme},
@@ -17,7 +17,8 @@ use anyhow::Result;
 use asyncgit::{
 	asyncjob::AsyncSingleJob,
 	sync::{
-		get_commit_info, CommitId, CommitInfo, RepoPathRef, TreeFile,
+		get_commit_info, tree_file_at, CommitId, CommitInfo,
+		RepoPathRef, TreeFile,
 	},
 	AsyncGitNotification, AsyncTreeFilesJob,
 };
@@ -30,7 +31,10 @@ use ratatui::{
 	Frame,
 };
 use std::{borrow::Cow, fmt::Write};
-use std::{collections::BTreeSet, path::Path};
+use std::{
+	collections::BTreeSet,
+	path::{Path, PathBuf},
+};
 use unicode_truncate::UnicodeTruncateStr;
 use unicode_width::UnicodeWidthStr;
 
@@ -123,9 +127,11 @@ impl RevisionFilesComponent {
 					.is_some_and(|commit| commit.id == result.commit)
 				{
 					if let Ok(last) = result.result {
-						let filenames: Vec<&Path> = last
+						let paths: Vec<PathBuf> =
+							last.iter().map(TreeFile::path).collect();
+						let filenames: Vec<&Path> = paths
 							.iter()
-							.map(|f| f.path.as_path())
+							.map(PathBuf::as_path)
 							.collect();
 						self.tree = FileTree::new(
 							&filenames,
@@ -212,13 +218,27 @@ impl RevisionFilesComponent {
 		})
 	}
 
+	fn checkout_file(&self) -> bool {
+		let Some(revision) = &self.revision else {
+			return false;
+		};
+
+		self.selected_file_path().is_some_and(|path| {
+			self.queue.push(InternalEvent::ConfirmAction(
+				Action::CheckoutFile(revision.id, path),
+			));
+
+			true
+		})
+	}
+
 	fn open_finder(&self) {
 		if let Some(files) = self.files.clone() {
 			self.queue.push(InternalEvent::OpenFuzzyFinder(
 				files
 					.iter()
 					.map(|a| {
-						a.path
+						a.path()
 							.to_str()
 							.unwrap_or_default()
 							.to_string()
@@ -252,22 +272,17 @@ impl RevisionFilesComponent {
 	}
 
 	fn selection_changed(&mut self) {
-		//TODO: retrieve TreeFile from tree datastructure
-		if let Some(file) = self.selected_file_path_with_prefix() {
-			if let Some(files) = &self.files {
-				let path = Path::new(&file);
-				if let Some(item) =
-					files.iter().find(|f| f.path == path)
-				{
-					if let Ok(path) = path.strip_prefix("./") {
-						return self.current_file.load_file(
-							path.to_string_lossy().to_string(),
-							item,
-						);
-					}
-				}
-				self.current_file.clear();
+		if let (Some(path), Some(revision)) =
+			(self.selected_file_path(), &self.revision)
+		{
+			if let Ok(item) = tree_file_at(
+				&self.repo.borrow(),
+				revision.id,
+				Path::new(&path),
+			) {
+				return self.current_file.load_file(path, &item);
 			}
+			self.current_file.clear();
 		}
 	}
 
@@ -438,6 +453,16 @@ impl Component for RevisionFilesComponent {
 				)
 				.order(order::RARE_ACTION),
 			);
+			out.push(
+				CommandInfo::new(
+					strings::commands::checkout_file_version(
+						&self.key_config,
+					),
+					self.tree.selected_file().is_some(),
+					true,
+				)
+				.order(order::RARE_ACTION),
+			);
 			out.push(
 				CommandInfo::new(
 					strings::commands::copy_path(&self.key_config),
@@ -482,6 +507,13 @@ impl Component for RevisionFilesComponent {
 					self.hide();
 					return Ok(EventState::Consumed);
 				}
+			} else if key_match(
+				key,
+				self.key_config.keys.log_checkout_commit,
+			) {
+				if is_tree_focused && self.checkout_file() {
+					return Ok(EventState::Consumed);
+				}
 			} else if key_match(key, self.key_config.keys.move_right)
 			{
 				if is_tree_focused {
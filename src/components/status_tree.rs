@@ -91,6 +91,11 @@ impl StatusTreeComponent {
 		})
 	}
 
+	/// see [`StatusTree::select_path`]
+	pub fn select_path(&mut self, path: &str) -> bool {
+		self.tree.select_path(path)
+	}
+
 	///
 	pub fn show_selection(&mut self, show: bool) {
 		self.show_selection = show;
@@ -33,6 +33,8 @@ use std::{cell::Cell, path::Path};
 pub struct SyntaxTextComponent {
 	repo: RepoPathRef,
 	current_file: Option<(String, Either<ui::SyntaxText, String>)>,
+	/// the same path can be a different blob in another revision
+	current_item: Option<TreeFile>,
 	async_highlighting: AsyncSingleJob<AsyncSyntaxJob>,
 	syntax_progress: Option<ProgressPercent>,
 	key_config: SharedKeyConfig,
@@ -50,6 +52,7 @@ impl SyntaxTextComponent {
 			),
 			syntax_progress: None,
 			current_file: None,
+			current_item: None,
 			paragraph_state: Cell::new(ParagraphState::default()),
 			focused: false,
 			key_config: env.key_config.clone(),
@@ -97,16 +100,19 @@ impl SyntaxTextComponent {
 	///
 	pub fn clear(&mut self) {
 		self.current_file = None;
+		self.current_item = None;
 	}
 
 	///
 	pub fn load_file(&mut self, path: String, item: &TreeFile) {
-		let already_loaded = self
-			.current_file
-			.as_ref()
-			.is_some_and(|(current_file, _)| current_file == &path);
+		let already_loaded =
+			self.current_file.as_ref().is_some_and(
+				|(current_file, _)| current_file == &path,
+			) && self.current_item.as_ref() == Some(item);
 
 		if !already_loaded {
+			self.current_item = Some(item.clone());
+
 			//TODO: fetch file content async as well
 			match sync::tree_file_content(&self.repo.borrow(), item) {
 				Ok(content) => {
@@ -0,0 +1,456 @@
+use super::{
+	utils::search::{contains_ignore_case, SearchState},
+	CommandBlocking, CommandInfo, Component, DrawableComponent,
+	EventState, ScrollType, VerticalScroll,
+};
+use crate::{
+	app::Environment,
+	keys::{key_match, SharedKeyConfig},
+	strings::{self, order},
+	ui::style::SharedTheme,
+};
+use anyhow::Result;
+use crossterm::event::Event;
+use ratatui::{
+	layout::{Margin, Rect},
+	text::{Line, Span},
+	widgets::Paragraph,
+	Frame,
+};
+use std::cell::Cell;
+use unicode_width::UnicodeWidthChar;
+
+/// read-only viewer for long texts (hook output, error messages..)
+/// supporting scrolling, search, copying and toggling line wrapping.
+///
+/// draws into the inner area of a bordered `Rect` provided by its owner,
+/// the search prompt and scroll percentage are put into the bottom border.
+///
+/// used by `MsgPopup` (hook output, push/fetch errors and every other
+/// message) and the commit-msg hook preview of `CommitPopup`. fetch
+/// sideband messages are only logged and there is no patch preview, so
+/// neither has a viewer to route through it.
+pub struct TextViewerComponent {
+	text: String,
+	wrap: bool,
+	search: SearchState,
+	scroll: VerticalScroll,
+	/// inner width of the last draw, needed to jump to matches
+	current_width: Cell<u16>,
+	theme: SharedTheme,
+	key_config: SharedKeyConfig,
+}
+
+impl TextViewerComponent {
+	///
+	pub fn new(env: &Environment) -> Self {
+		Self {
+			text: String::new(),
+			wrap: true,
+			search: SearchState::None,
+			scroll: VerticalScroll::new(),
+			current_width: Cell::new(0),
+			theme: env.theme.clone(),
+			key_config: env.key_config.clone(),
+		}
+	}
+
+	///
+	pub fn set_text(&mut self, text: &str) {
+		self.text = text.to_string();
+		self.search = SearchState::None;
+		self.scroll.reset();
+	}
+
+	///
+	pub fn text(&self) -> &str {
+		&self.text
+	}
+
+	/// number of lines needed to show the whole text in `width`
+	pub fn line_count(&self, width: u16) -> usize {
+		layout_lines(&self.text, width.into(), self.wrap).len()
+	}
+
+	/// `true` while a search query is typed
+	pub const fn is_searching(&self) -> bool {
+		self.search.is_input()
+	}
+
+	fn query(&self) -> Option<&str> {
+		self.search.query()
+	}
+
+	fn lines(&self) -> Vec<String> {
+		layout_lines(
+			&self.text,
+			self.current_width.get().into(),
+			self.wrap,
+		)
+	}
+
+	/// scrolls to the first match after the current top line,
+	/// wraps around to the start of the text
+	fn next_match(&self) {
+		let Some(query) = self.query() else {
+			return;
+		};
+
+		let matches = find_matches(&self.lines(), query);
+		let top = self.scroll.get_top();
+
+		let next = matches
+			.iter()
+			.find(|line| **line > top)
+			.or_else(|| matches.first());
+
+		if let Some(line) = next {
+			self.scroll.move_to(*line);
+		}
+	}
+
+	fn copy(&self) {
+		if let Err(e) = crate::clipboard::copy_string(&self.text) {
+			log::error!("copy text error: {e}");
+		}
+	}
+
+	fn draw_status(&self, f: &mut Frame, r: Rect, line_count: usize) {
+		let height = usize::from(r.height.saturating_sub(2));
+		let max_top = line_count.saturating_sub(height);
+
+		let bottom = Rect::new(
+			r.x + 1,
+			r.bottom().saturating_sub(1),
+			r.width.saturating_sub(2),
+			1,
+		);
+
+		if max_top > 0 {
+			let percentage = format!(
+				"{}%",
+				scroll_percentage(self.scroll.get_top(), max_top)
+			);
+			let width = u16::try_from(percentage.len())
+				.unwrap_or_default()
+				.min(bottom.width);
+
+			f.render_widget(
+				Paragraph::new(percentage)
+					.style(self.theme.text(true, false)),
+				Rect::new(
+					bottom.right().saturating_sub(width + 1),
+					bottom.y,
+					width,
+					1,
+				),
+			);
+		}
+
+		self.search.draw_prompt(
+			f,
+			Rect::new(
+				bottom.x,
+				bottom.y,
+				bottom.width.saturating_sub(6),
+				1,
+			),
+			&self.theme,
+			|query| find_matches(&self.lines(), query).len(),
+		);
+	}
+}
+
+impl DrawableComponent for TextViewerComponent {
+	fn draw(&self, f: &mut Frame, rect: Rect) -> Result<()> {
+		let inner = rect.inner(Margin {
+			horizontal: 1,
+			vertical: 1,
+		});
+
+		self.current_width.set(inner.width);
+
+		let lines = self.lines();
+		let top = self
+			.scroll
+			.update_no_selection(lines.len(), inner.height.into());
+
+		let query = self.query();
+
+		let text = lines
+			.iter()
+			.skip(top)
+			.take(inner.height.into())
+			.map(|line| {
+				let selected = query.is_some_and(|query| {
+					contains_ignore_case(line, query)
+				});
+
+				Line::from(Span::styled(
+					line.as_str(),
+					self.theme.text(true, selected),
+				))
+			})
+			.collect::<Vec<_>>();
+
+		f.render_widget(Paragraph::new(text), inner);
+
+		self.scroll.draw(f, rect, &self.theme);
+		self.draw_status(f, rect, lines.len());
+
+		Ok(())
+	}
+}
+
+impl Component for TextViewerComponent {
+	fn commands(
+		&self,
+		out: &mut Vec<CommandInfo>,
+		force_all: bool,
+	) -> CommandBlocking {
+		out.push(
+			CommandInfo::new(
+				strings::commands::navigate_commit_message(
+					&self.key_config,
+				),
+				true,
+				true,
+			)
+			.order(order::NAV),
+		);
+		out.push(CommandInfo::new(
+			strings::commands::text_viewer_search(&self.key_config),
+			true,
+			true,
+		));
+		out.push(CommandInfo::new(
+			strings::commands::text_viewer_next_match(
+				&self.key_config,
+			),
+			self.query().is_some(),
+			self.query().is_some() || force_all,
+		));
+		out.push(CommandInfo::new(
+			strings::commands::text_viewer_toggle_wrap(
+				&self.key_config,
+				self.wrap,
+			),
+			true,
+			true,
+		));
+		out.push(CommandInfo::new(
+			strings::commands::text_viewer_copy(&self.key_config),
+			true,
+			true,
+		));
+
+		CommandBlocking::PassingOn
+	}
+
+	fn event(&mut self, ev: &Event) -> Result<EventState> {
+		if self.is_searching() {
+			if self.search.input(ev) {
+				self.next_match();
+			}
+			return Ok(EventState::Consumed);
+		}
+
+		if let Event::Key(e) = ev {
+			let keys = &self.key_config.keys;
+
+			let consumed = if key_match(e, keys.popup_down) {
+				self.scroll.move_top(ScrollType::Down);
+				true
+			} else if key_match(e, keys.popup_up) {
+				self.scroll.move_top(ScrollType::Up);
+				true
+			} else if key_match(e, keys.home) {
+				self.scroll.move_top(ScrollType::Home);
+				true
+			} else if key_match(e, keys.end) {
+				self.scroll.move_top(ScrollType::End);
+				true
+			} else if key_match(e, keys.text_viewer_search) {
+				self.search = SearchState::Input(String::new());
+				true
+			} else if key_match(e, keys.text_viewer_next_match) {
+				self.next_match();
+				true
+			} else if key_match(e, keys.text_viewer_toggle_wrap) {
+				self.wrap = !self.wrap;
+				self.scroll.reset();
+				true
+			} else if key_match(e, keys.copy) {
+				self.copy();
+				true
+			} else {
+				false
+			};
+
+			if consumed {
+				return Ok(EventState::Consumed);
+			}
+		}
+
+		Ok(EventState::NotConsumed)
+	}
+}
+
+/// splits `text` into the lines shown in `width` columns,
+/// either wrapping long lines or cutting them off
+fn layout_lines(text: &str, width: usize, wrap: bool) -> Vec<String> {
+	let width = width.max(1);
+	let mut lines = Vec::new();
+
+	for line in text.lines() {
+		let line = line.replace('\t', "  ");
+		let mut current = String::new();
+		let mut current_width = 0;
+
+		for c in line.chars() {
+			let w = c.width().unwrap_or_default();
+
+			if current_width + w > width {
+				if !wrap {
+					break;
+				}
+
+				lines.push(std::mem::take(&mut current));
+				current_width = 0;
+			}
+
+			current.push(c);
+			current_width += w;
+		}
+
+		lines.push(current);
+	}
+
+	lines
+}
+
+/// indices of `lines` containing `query` (case insensitive)
+fn find_matches(lines: &[String], query: &str) -> Vec<usize> {
+	lines
+		.iter()
+		.enumerate()
+		.filter(|(_, line)| contains_ignore_case(line, query))
+		.map(|(idx, _)| idx)
+		.collect()
+}
+
+fn scroll_percentage(top: usize, max_top: usize) -> usize {
+	(top.min(max_top) * 100).checked_div(max_top).unwrap_or(100)
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crossterm::event::{KeyCode, KeyModifiers};
+	use pretty_assertions::assert_eq;
+	use unicode_width::UnicodeWidthStr;
+
+	const UNICODE_TEXT: &str = "hook 🚫 rejected: commit message must follow conventional commits\n\
+		\tfeat(ユーザー): 新しい機能を追加しました — überall\n\
+		\n\
+		❯ ошибка: строка темы слишком длинная";
+
+	#[test]
+	fn test_layout_lines_wrap_unicode() {
+		let width = 10;
+		let lines = layout_lines(UNICODE_TEXT, width, true);
+
+		assert!(lines.len() > UNICODE_TEXT.lines().count());
+		assert!(lines.iter().all(|line| line.width() <= width));
+		assert_eq!(
+			lines.concat(),
+			UNICODE_TEXT.replace('\t', "  ").replace('\n', "")
+		);
+	}
+
+	#[test]
+	fn test_layout_lines_no_wrap() {
+		let lines = layout_lines(UNICODE_TEXT, 7, false);
+
+		assert_eq!(lines, vec!["hook 🚫", "  feat(", "", "❯ ошибк"]);
+	}
+
+	#[test]
+	fn test_layout_lines_long_content() {
+		let text = "界".repeat(10_000);
+		let lines = layout_lines(&text, 80, true);
+
+		assert_eq!(lines.len(), 250);
+		assert!(lines.iter().all(|line| line.width() == 80));
+	}
+
+	#[test]
+	fn test_find_matches() {
+		let lines = layout_lines(UNICODE_TEXT, 200, true);
+
+		assert_eq!(find_matches(&lines, "ÜBERALL"), vec![1]);
+		assert_eq!(find_matches(&lines, "ОШИБКА"), vec![3]);
+		assert_eq!(find_matches(&lines, "e"), vec![0, 1]);
+		assert!(find_matches(&lines, "missing").is_empty());
+	}
+
+	#[test]
+	fn test_search_and_wrap() {
+		use crossterm::event::KeyEvent;
+
+		let key = |code| {
+			Event::Key(KeyEvent::new(code, KeyModifiers::empty()))
+		};
+
+		let test_backend =
+			ratatui::backend::TestBackend::new(100, 100);
+		let mut terminal = ratatui::Terminal::new(test_backend)
+			.expect("Unable to set up terminal");
+		let mut frame = terminal.get_frame();
+
+		let mut viewer =
+			TextViewerComponent::new(&Environment::test_env());
+
+		let text = (0..200)
+			.map(|idx| {
+				format!("{idx}: 🦀 ✓ hook output ✗ ありがとう")
+			})
+			.collect::<Vec<_>>()
+			.join("\n");
+		viewer.set_text(&text);
+
+		let area = Rect::new(0, 0, 20, 12);
+		viewer.draw(&mut frame, area).unwrap();
+
+		// narrow area wraps every line
+		assert!(viewer.line_count(18) > 200);
+		assert_eq!(viewer.scroll.get_top(), 0);
+
+		for c in "/150:".chars() {
+			viewer.event(&key(KeyCode::Char(c))).unwrap();
+		}
+		assert!(viewer.is_searching());
+		viewer.event(&key(KeyCode::Enter)).unwrap();
+		assert!(!viewer.is_searching());
+
+		let lines = viewer.lines();
+		assert!(lines[viewer.scroll.get_top()].starts_with("150:"));
+
+		viewer.event(&key(KeyCode::Char('w'))).unwrap();
+		viewer.draw(&mut frame, area).unwrap();
+
+		assert_eq!(viewer.line_count(18), 200);
+		assert_eq!(viewer.scroll.get_top(), 0);
+
+		viewer.event(&key(KeyCode::Char('n'))).unwrap();
+		assert_eq!(viewer.scroll.get_top(), 150);
+	}
+
+	#[test]
+	fn test_scroll_percentage() {
+		assert_eq!(scroll_percentage(0, 0), 100);
+		assert_eq!(scroll_percentage(0, 10), 0);
+		assert_eq!(scroll_percentage(5, 10), 50);
+		assert_eq!(scroll_percentage(12, 10), 100);
+	}
+}
@@ -0,0 +1,114 @@
+//! text removed by the kill commands of text inputs (`ctrl-k`, `ctrl-w`,
+//! ...) to be yanked back (`ctrl-y`, then `alt-y` to cycle through older
+//! kills). the ring is shared by all inputs, so text killed in the
+//! commit message can be yanked into the branch name.
+
+use std::{cell::RefCell, collections::VecDeque, rc::Rc};
+
+/// kills kept before the oldest is dropped
+const CAPACITY: usize = 16;
+
+///
+pub type SharedKillRing = Rc<RefCell<KillRing>>;
+
+/// where a kill happened relative to the one right before it
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum KillDirection {
+	/// towards the end of the text, appended to the previous kill
+	Forward,
+	/// towards the start of the text, prepended to the previous kill
+	Backward,
+}
+
+///
+#[derive(Debug, Default)]
+pub struct KillRing {
+	/// most recent first
+	entries: VecDeque<String>,
+}
+
+impl KillRing {
+	/// stores `text` as the most recent kill, consecutive kills (e.g.
+	/// repeated `ctrl-w`) are joined into one entry if `joined` is set
+	pub fn kill(
+		&mut self,
+		text: String,
+		joined: Option<KillDirection>,
+	) {
+		if text.is_empty() {
+			return;
+		}
+
+		match (joined, self.entries.front_mut()) {
+			(Some(KillDirection::Forward), Some(last)) => {
+				last.push_str(&text);
+			}
+			(Some(KillDirection::Backward), Some(last)) => {
+				last.insert_str(0, &text);
+			}
+			_ => {
+				self.entries.push_front(text);
+				self.entries.truncate(CAPACITY);
+			}
+		}
+	}
+
+	/// the `index`th most recent kill, wrapping around
+	pub fn get(&self, index: usize) -> Option<&str> {
+		if self.entries.is_empty() {
+			None
+		} else {
+			self.entries
+				.get(index % self.entries.len())
+				.map(String::as_str)
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn test_kill_and_get() {
+		let mut ring = KillRing::default();
+		assert_eq!(ring.get(0), None);
+
+		ring.kill(String::from("first"), None);
+		ring.kill(String::new(), None);
+		ring.kill(String::from("second"), None);
+
+		assert_eq!(ring.get(0), Some("second"));
+		assert_eq!(ring.get(1), Some("first"));
+		assert_eq!(ring.get(2), Some("second"));
+	}
+
+	#[test]
+	fn test_joined_kills() {
+		let mut ring = KillRing::default();
+
+		ring.kill(String::from("world"), None);
+		ring.kill(
+			String::from("hello "),
+			Some(KillDirection::Backward),
+		);
+		ring.kill(String::from("!"), Some(KillDirection::Forward));
+
+		assert_eq!(ring.get(0), Some("hello world!"));
+		assert_eq!(ring.get(1), Some("hello world!"));
+	}
+
+	#[test]
+	fn test_capacity() {
+		let mut ring = KillRing::default();
+
+		for i in 0..=CAPACITY {
+			ring.kill(i.to_string(), None);
+		}
+
+		assert_eq!(ring.get(0), Some(CAPACITY.to_string().as_str()));
+		// the oldest kill ("0") was dropped
+		assert_eq!(ring.get(CAPACITY - 1), Some("1"));
+		assert_eq!(ring.get(CAPACITY), ring.get(0));
+	}
+}
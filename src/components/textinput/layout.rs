@@ -0,0 +1,325 @@
+//! how the text of a [`super::TextInputComponent`] is laid out on screen.
+//!
+//! the text area addresses text as (line, char index) while the terminal
+//! works in cells: wide characters (CJK, most emoji) take up two cells
+//! and combining characters none. everything here works on grapheme
+//! clusters so that e.g. an emoji ZWJ sequence is drawn, wrapped, moved
+//! over and deleted as a whole and the cursor never ends up inside one.
+
+use unicode_segmentation::UnicodeSegmentation;
+use unicode_width::UnicodeWidthStr;
+
+/// what a tab is drawn as
+pub const TAB: &str = "    ";
+
+/// a grapheme cluster of a line
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Grapheme<'a> {
+	pub text: &'a str,
+	/// char index of its start in the line
+	pub col: usize,
+	/// cells it takes up
+	pub width: usize,
+}
+
+impl Grapheme<'_> {
+	/// char index right after it
+	pub fn end(&self) -> usize {
+		self.col + self.text.chars().count()
+	}
+}
+
+/// graphemes of `line` in order
+pub fn graphemes(line: &str) -> impl Iterator<Item = Grapheme<'_>> {
+	let mut col = 0;
+	line.graphemes(true).map(move |text| {
+		let grapheme = Grapheme {
+			text,
+			col,
+			width: width(text),
+		};
+		col = grapheme.end();
+		grapheme
+	})
+}
+
+/// cells `grapheme` takes up, matches what ratatui draws (which skips
+/// control characters, tabs are drawn as [`TAB`] by us)
+fn width(grapheme: &str) -> usize {
+	if grapheme == "\t" {
+		TAB.len()
+	} else if grapheme.contains(char::is_control) {
+		0
+	} else {
+		grapheme.width()
+	}
+}
+
+/// start of the grapheme before `col` (`0` at the start of `line`)
+pub fn prev_boundary(line: &str, col: usize) -> usize {
+	graphemes(line)
+		.map(|g| g.col)
+		.take_while(|start| *start < col)
+		.last()
+		.unwrap_or_default()
+}
+
+/// end of the grapheme at `col` (`col` at the end of `line`)
+pub fn next_boundary(line: &str, col: usize) -> usize {
+	graphemes(line)
+		.map(|g| g.end())
+		.find(|end| *end > col)
+		.unwrap_or(col)
+}
+
+/// cells the part of `line` before `col` takes up
+pub fn width_to(line: &str, col: usize) -> usize {
+	graphemes(line)
+		.take_while(|g| g.col < col)
+		.map(|g| g.width)
+		.sum()
+}
+
+/// the grapheme boundary in `line` at or right before `x` cells
+pub fn col_at(line: &str, x: usize) -> usize {
+	let mut width = 0;
+	for g in graphemes(line) {
+		if width + g.width > x {
+			return g.col;
+		}
+		width += g.width;
+	}
+	line.chars().count()
+}
+
+/// a screen row showing the chars `start..end` of line `line`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Row {
+	pub line: usize,
+	pub start: usize,
+	pub end: usize,
+}
+
+/// rows `lines` take up when soft wrapped at `width` cells, every line
+/// gets at least one row. a line filling its last row completely is
+/// followed by an empty row for the cursor at its end to go to.
+pub fn wrap(lines: &[String], width: usize) -> Vec<Row> {
+	let width = width.max(1);
+	let mut rows = Vec::with_capacity(lines.len());
+
+	for (index, line) in lines.iter().enumerate() {
+		let mut row = Row {
+			line: index,
+			start: 0,
+			end: 0,
+		};
+		let mut row_width = 0;
+
+		for g in graphemes(line) {
+			if row_width + g.width > width && row.end > row.start {
+				rows.push(row);
+				row.start = row.end;
+				row_width = 0;
+			}
+			row.end = g.end();
+			row_width += g.width;
+		}
+
+		let full = row_width >= width;
+		rows.push(row);
+
+		if full {
+			rows.push(Row {
+				line: index,
+				start: row.end,
+				end: row.end,
+			});
+		}
+	}
+
+	rows
+}
+
+/// index into `rows` and cell in that row of `cursor` (line, col)
+pub fn cursor_position(
+	rows: &[Row],
+	lines: &[String],
+	(line, col): (usize, usize),
+) -> (usize, usize) {
+	let row = rows
+		.iter()
+		.rposition(|row| row.line == line && row.start <= col)
+		.unwrap_or_default();
+
+	let x = lines.get(line).map_or(0, |text| {
+		width_to(text, col) - width_to(text, rows[row].start)
+	});
+
+	(row, x)
+}
+
+/// first of `len` visible rows (or cells) so that `cursor` stays
+/// visible, moving as little as possible from `prev_top`
+pub const fn scroll_to(
+	prev_top: usize,
+	cursor: usize,
+	len: usize,
+) -> usize {
+	if cursor < prev_top {
+		cursor
+	} else if prev_top + len <= cursor {
+		cursor + 1 - len
+	} else {
+		prev_top
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use pretty_assertions::assert_eq;
+
+	const CJK: &str = "日本語";
+	// 'e' followed by a combining acute accent
+	const COMBINING: &str = "e\u{301}";
+	// man, woman, girl joined by zero width joiners
+	const FAMILY: &str =
+		"\u{1F468}\u{200D}\u{1F469}\u{200D}\u{1F467}";
+	const FLAG: &str = "\u{1F1EF}\u{1F1F5}";
+
+	fn lines(text: &[&str]) -> Vec<String> {
+		text.iter().map(ToString::to_string).collect()
+	}
+
+	#[test]
+	fn test_widths() {
+		let widths = |line: &str| {
+			graphemes(line).map(|g| g.width).collect::<Vec<_>>()
+		};
+
+		assert_eq!(widths(CJK), vec![2, 2, 2]);
+		assert_eq!(widths(COMBINING), vec![1]);
+		assert_eq!(widths(FAMILY), vec![2]);
+		assert_eq!(widths(FLAG), vec![2]);
+		assert_eq!(widths("a\tb"), vec![1, TAB.len(), 1]);
+		assert_eq!(widths("한글"), vec![2, 2]);
+	}
+
+	#[test]
+	fn test_boundaries() {
+		let line = format!("a{COMBINING}{FAMILY}b");
+		// a | e + accent | 5 chars of the family | b
+		assert_eq!(prev_boundary(&line, 0), 0);
+		assert_eq!(prev_boundary(&line, 1), 0);
+		assert_eq!(prev_boundary(&line, 3), 1);
+		assert_eq!(prev_boundary(&line, 8), 3);
+		assert_eq!(prev_boundary(&line, 9), 8);
+
+		assert_eq!(next_boundary(&line, 0), 1);
+		assert_eq!(next_boundary(&line, 1), 3);
+		assert_eq!(next_boundary(&line, 3), 8);
+		assert_eq!(next_boundary(&line, 8), 9);
+		assert_eq!(next_boundary(&line, 9), 9);
+	}
+
+	#[test]
+	fn test_width_to_and_col_at() {
+		let line = format!("a{CJK}{COMBINING}");
+
+		assert_eq!(width_to(&line, 0), 0);
+		assert_eq!(width_to(&line, 1), 1);
+		assert_eq!(width_to(&line, 3), 5);
+		assert_eq!(width_to(&line, 6), 8);
+
+		assert_eq!(col_at(&line, 0), 0);
+		assert_eq!(col_at(&line, 1), 1);
+		// in the middle of the first wide char
+		assert_eq!(col_at(&line, 2), 1);
+		assert_eq!(col_at(&line, 3), 2);
+		assert_eq!(col_at(&line, 7), 4);
+		assert_eq!(col_at(&line, 8), 6);
+		assert_eq!(col_at(&line, 100), 6);
+	}
+
+	#[test]
+	fn test_wrap_wide_chars() {
+		// 3 wide chars do not fit into 5 cells, the third one must not
+		// be split
+		let rows = wrap(&lines(&[CJK]), 5);
+
+		assert_eq!(
+			rows,
+			vec![
+				Row {
+					line: 0,
+					start: 0,
+					end: 2
+				},
+				Row {
+					line: 0,
+					start: 2,
+					end: 3
+				},
+			]
+		);
+	}
+
+	#[test]
+	fn test_wrap_keeps_clusters() {
+		let line = format!("ab{FAMILY}");
+		let rows = wrap(&lines(&[&line]), 3);
+
+		assert_eq!(rows.len(), 2);
+		assert_eq!((rows[0].start, rows[0].end), (0, 2));
+		assert_eq!((rows[1].start, rows[1].end), (2, 7));
+	}
+
+	#[test]
+	fn test_wrap_full_row() {
+		let rows = wrap(&lines(&["abcd", "", "x"]), 2);
+
+		assert_eq!(
+			rows.iter()
+				.map(|row| (row.line, row.start, row.end))
+				.collect::<Vec<_>>(),
+			vec![
+				(0, 0, 2),
+				(0, 2, 4),
+				(0, 4, 4),
+				(1, 0, 0),
+				(2, 0, 1)
+			]
+		);
+	}
+
+	#[test]
+	fn test_wrap_too_narrow() {
+		// a grapheme wider than the row still gets a row of its own
+		let rows = wrap(&lines(&[CJK]), 1);
+
+		assert_eq!(rows.len(), 4);
+		assert_eq!((rows[2].start, rows[2].end), (2, 3));
+	}
+
+	#[test]
+	fn test_cursor_position() {
+		let text = lines(&[&format!("{CJK}ab"), COMBINING]);
+		let rows = wrap(&text, 4);
+
+		assert_eq!(cursor_position(&rows, &text, (0, 0)), (0, 0));
+		assert_eq!(cursor_position(&rows, &text, (0, 1)), (0, 2));
+		// at the wrap the cursor goes to the start of the next row
+		assert_eq!(cursor_position(&rows, &text, (0, 2)), (1, 0));
+		assert_eq!(cursor_position(&rows, &text, (0, 4)), (1, 3));
+		assert_eq!(cursor_position(&rows, &text, (0, 5)), (2, 0));
+		assert_eq!(cursor_position(&rows, &text, (1, 2)), (3, 1));
+	}
+
+	#[test]
+	fn test_scroll_to() {
+		assert_eq!(scroll_to(0, 0, 3), 0);
+		assert_eq!(scroll_to(0, 3, 3), 1);
+		assert_eq!(scroll_to(5, 2, 3), 2);
+		assert_eq!(scroll_to(2, 4, 3), 2);
+	}
+}
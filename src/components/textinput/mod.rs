@@ -0,0 +1,1881 @@
+mod kill_ring;
+mod layout;
+mod words;
+
+pub use kill_ring::SharedKillRing;
+
+use crate::app::Environment;
+use crate::keys::key_match;
+use crate::ui::Size;
+use crate::{
+	components::{
+		visibility_blocking, CommandBlocking, CommandInfo, Component,
+		DrawableComponent, EventState,
+	},
+	keys::{KeysList, SharedKeyConfig},
+	strings,
+	ui::{self, style::SharedTheme},
+};
+use anyhow::Result;
+use crossterm::event::{Event, KeyCode, KeyEvent, KeyModifiers};
+use kill_ring::KillDirection;
+use ratatui::widgets::{Block, Borders};
+use ratatui::{
+	layout::{Alignment, Position, Rect},
+	style::Style,
+	widgets::{Clear, Paragraph},
+	Frame,
+};
+use std::borrow::Cow;
+use std::cell::Cell;
+use std::cell::OnceCell;
+use tui_textarea::{CursorMove, Input, Key, TextArea};
+use unicode_segmentation::UnicodeSegmentation;
+
+///
+#[derive(PartialEq, Eq)]
+pub enum InputType {
+	Singleline,
+	Multiline,
+	Password,
+}
+
+#[derive(PartialEq, Eq)]
+enum SelectionState {
+	Selecting,
+	NotSelecting,
+	SelectionEndPending,
+}
+
+type TextAreaComponent = TextArea<'static>;
+
+/// start and end (exclusive) of a selection as (line, col)
+type Selection = ((usize, usize), (usize, usize));
+
+/// what a key does in a text input, see [`edit_command`]
+#[derive(Clone, Copy)]
+enum EditCommand {
+	InsertChar(char),
+	InsertTab,
+	Newline,
+	DeleteBack,
+	DeleteForward,
+	CharLeft,
+	CharRight,
+	WordLeft,
+	WordRight,
+	LineUp,
+	LineDown,
+	PageUp,
+	PageDown,
+	Move(CursorMove),
+	KillLineStart,
+	KillLineEnd,
+	KillWordLeft,
+	KillBlankWordLeft,
+	KillWordRight,
+	Yank,
+	YankPop,
+	Undo,
+	Redo,
+}
+
+/// the previous command as far as kill and yank commands care
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum LastEdit {
+	Other,
+	/// consecutive kills are joined in the kill ring
+	Kill,
+	/// a yank can be replaced by an older kill with yank-pop
+	Yank {
+		start: (usize, usize),
+		/// kill ring index of the yanked text
+		index: usize,
+		/// length of the yanked text
+		chars: usize,
+	},
+}
+
+///
+#[allow(clippy::struct_excessive_bools)]
+pub struct TextInputComponent {
+	title: String,
+	default_msg: String,
+	selected: Option<bool>,
+	msg: OnceCell<String>,
+	show_char_count: bool,
+	theme: SharedTheme,
+	key_config: SharedKeyConfig,
+	input_type: InputType,
+	current_area: Cell<Rect>,
+	embed: bool,
+	textarea: Option<TextAreaComponent>,
+	select_state: SelectionState,
+	/// first visible row and cell (single line inputs scroll
+	/// horizontally instead of wrapping)
+	scroll: Cell<(usize, usize)>,
+	kill_ring: SharedKillRing,
+	last_edit: LastEdit,
+	line_numbers: bool,
+	subject_separator: bool,
+	/// width and height in percent of the screen when not embedded
+	popup_size: Option<(u16, u16)>,
+	/// cells text got wrapped at when last drawn, moving up and down
+	/// goes by these rows
+	wrap_width: Cell<usize>,
+}
+
+impl TextInputComponent {
+	///
+	pub fn new(
+		env: &Environment,
+		title: &str,
+		default_msg: &str,
+		show_char_count: bool,
+	) -> Self {
+		Self {
+			msg: OnceCell::default(),
+			theme: env.theme.clone(),
+			key_config: env.key_config.clone(),
+			show_char_count,
+			title: title.to_string(),
+			default_msg: default_msg.to_string(),
+			selected: None,
+			input_type: InputType::Multiline,
+			current_area: Cell::new(Rect::default()),
+			embed: false,
+			textarea: None,
+			select_state: SelectionState::NotSelecting,
+			scroll: Cell::new((0, 0)),
+			kill_ring: env.kill_ring.clone(),
+			last_edit: LastEdit::Other,
+			line_numbers: false,
+			subject_separator: false,
+			popup_size: None,
+			wrap_width: Cell::new(0),
+		}
+	}
+
+	///
+	pub const fn with_input_type(
+		mut self,
+		input_type: InputType,
+	) -> Self {
+		self.input_type = input_type;
+		self
+	}
+
+	/// size of the popup in percent of the screen (instead of the
+	/// default for the input type)
+	pub const fn with_popup_size(
+		mut self,
+		width_percent: u16,
+		height_percent: u16,
+	) -> Self {
+		self.popup_size = Some((width_percent, height_percent));
+		self
+	}
+
+	/// marks the line between subject and body of a commit message,
+	/// a rule if it is empty and a warning otherwise (the latter only
+	/// with line numbers)
+	pub const fn with_subject_separator(mut self) -> Self {
+		self.subject_separator = true;
+		self
+	}
+
+	/// shows line numbers in front of multiline text
+	pub fn set_line_numbers(&mut self, line_numbers: bool) {
+		self.line_numbers = line_numbers;
+	}
+
+	///
+	pub fn set_input_type(&mut self, input_type: InputType) {
+		self.clear();
+		self.input_type = input_type;
+	}
+
+	/// Clear the `msg`.
+	pub fn clear(&mut self) {
+		self.msg.take();
+		if self.is_visible() {
+			self.show_inner_textarea();
+		}
+	}
+
+	/// Get the `msg`.
+	pub fn get_text(&self) -> &str {
+		// the fancy footwork with the OnceCell is to allow
+		// the reading of msg as a &str.
+		// tui_textarea returns its lines to the caller as &[String]
+		// gitui wants &str of \n delimited text
+		// it would be simple if this was a mut method. You could
+		// just load up msg from the lines area and return an &str pointing at it
+		// but its not a mut method. So we need to store the text in a OnceCell
+		// The methods that change msg call take() on the cell. That makes
+		// get_or_init run again
+
+		self.msg.get_or_init(|| {
+			self.textarea
+				.as_ref()
+				.map_or_else(String::new, |ta| ta.lines().join("\n"))
+		})
+	}
+
+	/// screen area (last time we got drawn)
+	pub fn get_area(&self) -> Rect {
+		self.current_area.get()
+	}
+
+	/// embed into parent draw area
+	pub fn embed(&mut self) {
+		self.embed = true;
+	}
+
+	///
+	pub fn enabled(&mut self, enable: bool) {
+		self.selected = Some(enable);
+	}
+
+	fn show_inner_textarea(&mut self) {
+		//	create the textarea and then load it with the text
+		//	from self.msg
+		let lines: Vec<String> = self
+			.msg
+			.get()
+			.unwrap_or(&String::new())
+			.split('\n')
+			.map(ToString::to_string)
+			.collect();
+
+		// the text area is only used to edit the text, it is drawn
+		// by `draw_text` which knows about wide chars and wrapping
+		self.textarea = Some(TextArea::new(lines));
+		self.scroll.set((0, 0));
+	}
+
+	/// Set the `msg`.
+	pub fn set_text(&mut self, msg: String) {
+		self.msg = msg.into();
+		if self.is_visible() {
+			self.show_inner_textarea();
+		}
+	}
+
+	/// Insert `text` at the cursor position.
+	pub fn insert_str(&mut self, text: &str) {
+		if let Some(ta) = &mut self.textarea {
+			ta.insert_str(text);
+			self.msg.take();
+		}
+	}
+
+	/// Move the cursor to the end of the first line.
+	pub fn move_cursor_to_first_line_end(&mut self) {
+		if let Some(ta) = &mut self.textarea {
+			ta.move_cursor(CursorMove::Top);
+			ta.move_cursor(CursorMove::End);
+		}
+	}
+
+	/// Set the `title`.
+	pub fn set_title(&mut self, t: String) {
+		self.title = t;
+	}
+
+	///
+	pub fn set_default_msg(&mut self, v: String) {
+		self.default_msg = v;
+		if self.is_visible() {
+			self.show_inner_textarea();
+		}
+	}
+
+	fn draw_char_count(&self, f: &mut Frame, r: Rect) {
+		let count = self.get_text().graphemes(true).count();
+		if count > 0 {
+			let w = Paragraph::new(format!("[{count} chars]"))
+				.alignment(Alignment::Right);
+
+			let mut rect = {
+				let mut rect = r;
+				rect.y += rect.height.saturating_sub(1);
+				rect
+			};
+
+			rect.x += 1;
+			rect.width = rect.width.saturating_sub(2);
+			rect.height = rect
+				.height
+				.saturating_sub(rect.height.saturating_sub(1));
+
+			f.render_widget(w, rect);
+		}
+	}
+
+	/// rows moved by page up/down
+	fn page_height(&self) -> isize {
+		let area = self.current_area.get();
+		let height = if self.embed {
+			area.height
+		} else {
+			area.height.saturating_sub(2)
+		};
+
+		isize::try_from(height.max(1)).unwrap_or(1)
+	}
+
+	/// draws the text of `ta` soft wrapped (or scrolled horizontally
+	/// for single line inputs) into `area` and places the terminal
+	/// cursor, which is also where an IME shows its composition
+	#[allow(clippy::too_many_lines)]
+	fn draw_text(
+		&self,
+		ta: &TextAreaComponent,
+		f: &mut Frame,
+		area: Rect,
+	) {
+		let enabled = self.selected.unwrap_or(true);
+		let style = self.theme.text(enabled, false);
+		f.buffer_mut().set_style(area, style);
+
+		let wrap = self.input_type == InputType::Multiline;
+		let (gutter, area) = self.split_gutter(ta, area);
+
+		if area.width == 0 || area.height == 0 {
+			return;
+		}
+
+		let (width, height) =
+			(usize::from(area.width), usize::from(area.height));
+		let cursor_at = |(row, x): (usize, usize)| {
+			Position::new(
+				area.x + u16::try_from(x).unwrap_or_default(),
+				area.y + u16::try_from(row).unwrap_or_default(),
+			)
+		};
+
+		if ta.is_empty() {
+			self.draw_gutter(
+				f,
+				gutter,
+				&[layout::Row {
+					line: 0,
+					start: 0,
+					end: 0,
+				}],
+				ta.lines(),
+			);
+			f.buffer_mut().set_stringn(
+				area.x,
+				area.y,
+				&self.default_msg,
+				width,
+				self.theme
+					.text(self.selected.unwrap_or_default(), false),
+			);
+			if enabled {
+				f.set_cursor_position(cursor_at((0, 0)));
+			}
+			return;
+		}
+
+		let (lines, cursor, selection) = self.display_text(ta);
+		let rows = layout::wrap(
+			&lines,
+			if wrap { width } else { usize::MAX },
+		);
+		if wrap {
+			self.wrap_width.set(width);
+		}
+
+		let (cursor_row, cursor_x) =
+			layout::cursor_position(&rows, &lines, cursor);
+		let (top, left) = self.scroll.get();
+		let top = layout::scroll_to(top, cursor_row, height);
+		let left = if wrap {
+			0
+		} else {
+			layout::scroll_to(left, cursor_x, width)
+		};
+		self.scroll.set((top, left));
+
+		let selected_style = self.theme.text(enabled, true);
+		let is_selected = |line: usize, col: usize| {
+			selection.is_some_and(|(start, end)| {
+				start <= (line, col) && (line, col) < end
+			})
+		};
+
+		let visible_rows = &rows[top..rows.len().min(top + height)];
+		self.draw_gutter(f, gutter, visible_rows, &lines);
+
+		for (y, row) in visible_rows.iter().enumerate() {
+			if self.is_separator_rule(&lines, row.line) {
+				let Position { x, y } = cursor_at((y, 0));
+				f.buffer_mut().set_stringn(
+					x,
+					y,
+					"\u{2500}".repeat(width),
+					width,
+					self.theme.text(false, false),
+				);
+				continue;
+			}
+
+			let mut x = 0;
+
+			for g in layout::graphemes(&lines[row.line])
+				.skip_while(|g| g.col < row.start)
+				.take_while(|g| g.col < row.end)
+			{
+				let start = x;
+				x += g.width;
+
+				// wide chars only partially scrolled into view are left out
+				if g.width == 0 || start < left || x > left + width {
+					continue;
+				}
+
+				let Position {
+					x: cell_x,
+					y: cell_y,
+				} = cursor_at((y, start - left));
+				f.buffer_mut().set_stringn(
+					cell_x,
+					cell_y,
+					if g.text == "\t" { layout::TAB } else { g.text },
+					g.width,
+					if is_selected(row.line, g.col) {
+						selected_style
+					} else {
+						style
+					},
+				);
+			}
+		}
+
+		if enabled {
+			f.set_cursor_position(cursor_at((
+				cursor_row - top,
+				cursor_x.saturating_sub(left).min(width - 1),
+			)));
+		}
+	}
+
+	/// splits the line numbers (if shown) off the left of `area`
+	fn split_gutter(
+		&self,
+		ta: &TextAreaComponent,
+		area: Rect,
+	) -> (Option<Rect>, Rect) {
+		if !self.line_numbers
+			|| self.input_type != InputType::Multiline
+		{
+			return (None, area);
+		}
+
+		// digits of the highest line number and a space
+		let width = u16::try_from(ta.lines().len().to_string().len())
+			.unwrap_or_default()
+			+ 1;
+		if area.width <= width {
+			return (None, area);
+		}
+
+		let gutter = Rect { width, ..area };
+		let text = Rect {
+			x: area.x + width,
+			width: area.width - width,
+			..area
+		};
+
+		(Some(gutter), text)
+	}
+
+	/// numbers the first of the `rows` of every line in `gutter`
+	fn draw_gutter(
+		&self,
+		f: &mut Frame,
+		gutter: Option<Rect>,
+		rows: &[layout::Row],
+		lines: &[String],
+	) {
+		let Some(gutter) = gutter else {
+			return;
+		};
+
+		let digits = usize::from(gutter.width - 1);
+		for (y, row) in rows.iter().enumerate() {
+			if row.start > 0 {
+				continue;
+			}
+
+			// the body should be separated from the subject by an
+			// empty line
+			let style = if self.subject_separator
+				&& row.line == 1
+				&& !lines[1].is_empty()
+			{
+				self.theme.text_danger()
+			} else {
+				self.theme.text(false, false)
+			};
+
+			f.buffer_mut().set_stringn(
+				gutter.x,
+				gutter.y + u16::try_from(y).unwrap_or_default(),
+				format!("{:>digits$}", row.line + 1),
+				digits,
+				style,
+			);
+		}
+	}
+
+	/// whether line `line` is the empty line separating subject and
+	/// body of a commit message and drawn as a rule
+	fn is_separator_rule(
+		&self,
+		lines: &[String],
+		line: usize,
+	) -> bool {
+		self.subject_separator
+			&& self.input_type == InputType::Multiline
+			&& line == 1
+			&& lines[1].is_empty()
+	}
+
+	/// lines of `ta` as shown along with the cursor and selection in
+	/// them, passwords are shown as one `*` per grapheme
+	fn display_text<'a>(
+		&self,
+		ta: &'a TextAreaComponent,
+	) -> (Cow<'a, [String]>, (usize, usize), Option<Selection>) {
+		let cursor = ta.cursor();
+		let selection = ta.selection_range();
+
+		if self.input_type != InputType::Password {
+			return (Cow::Borrowed(ta.lines()), cursor, selection);
+		}
+
+		let masked_col = |(row, col): (usize, usize)| {
+			(
+				row,
+				layout::graphemes(&ta.lines()[row])
+					.take_while(|g| g.col < col)
+					.count(),
+			)
+		};
+
+		let lines = ta
+			.lines()
+			.iter()
+			.map(|line| "*".repeat(line.graphemes(true).count()))
+			.collect();
+
+		(
+			Cow::Owned(lines),
+			masked_col(cursor),
+			selection.map(|(start, end)| {
+				(masked_col(start), masked_col(end))
+			}),
+		)
+	}
+
+	fn should_select(&mut self, input: &Input) {
+		if input.key == Key::Null {
+			return;
+		}
+		// Should we start selecting text, stop the current selection, or do nothing?
+		// the end is handled after the ending keystroke
+
+		match (&self.select_state, input.shift) {
+			(SelectionState::Selecting, true)
+			| (SelectionState::NotSelecting, false) => {
+				// continue selecting or not selecting
+			}
+			(SelectionState::Selecting, false) => {
+				// end select
+				self.select_state =
+					SelectionState::SelectionEndPending;
+			}
+			(SelectionState::NotSelecting, true) => {
+				// start select
+				// this should always work since we are only called
+				// if we have a textarea to get input
+				if let Some(ta) = &mut self.textarea {
+					ta.start_selection();
+					self.select_state = SelectionState::Selecting;
+				}
+			}
+			(SelectionState::SelectionEndPending, _) => {
+				// this really should not happen because the end pending state
+				// should have been picked up in the same pass as it was set
+				// so lets clear it
+				self.select_state = SelectionState::NotSelecting;
+			}
+		}
+	}
+
+	/// runs `command` on the text area
+	#[allow(clippy::too_many_lines)]
+	fn run(&mut self, command: EditCommand) {
+		let page = self.page_height();
+		// single line inputs do not wrap, neither do inputs that were
+		// not drawn yet
+		let wrap_width = (self.input_type == InputType::Multiline)
+			.then(|| self.wrap_width.get())
+			.filter(|width| *width > 0);
+		let last_edit =
+			std::mem::replace(&mut self.last_edit, LastEdit::Other);
+		let Some(ta) = &mut self.textarea else {
+			return;
+		};
+
+		let (row, col) = ta.cursor();
+		let line = ta.lines()[row].clone();
+		let line_len = line.chars().count();
+		let prev_line_end = row
+			.checked_sub(1)
+			.map(|prev| (prev, ta.lines()[prev].chars().count()));
+		let next_line_start =
+			(row + 1 < ta.lines().len()).then_some((row + 1, 0));
+
+		// where the text to kill ends (or starts)
+		let kill_target = match command {
+			EditCommand::KillLineStart => Some(if col > 0 {
+				Some((row, 0))
+			} else {
+				prev_line_end
+			}),
+			EditCommand::KillLineEnd => Some(if col < line_len {
+				Some((row, line_len))
+			} else {
+				next_line_start
+			}),
+			EditCommand::KillWordLeft => Some(if col > 0 {
+				Some((row, words::prev_word_start(&line, col)))
+			} else {
+				prev_line_end
+			}),
+			EditCommand::KillBlankWordLeft => Some(if col > 0 {
+				Some((row, words::prev_blank_word_start(&line, col)))
+			} else {
+				prev_line_end
+			}),
+			EditCommand::KillWordRight => Some(if col < line_len {
+				Some((row, words::next_word_end(&line, col)))
+			} else {
+				next_line_start
+			}),
+			_ => None,
+		};
+
+		if let Some(target) = kill_target {
+			let killed = if ta.selection_range().is_some() {
+				ta.cut().then(|| ta.yank_text())
+			} else {
+				target.and_then(|target| delete_to(ta, target))
+			};
+
+			let direction = if target < Some((row, col)) {
+				KillDirection::Backward
+			} else {
+				KillDirection::Forward
+			};
+
+			if let Some(killed) = killed {
+				self.kill_ring.borrow_mut().kill(
+					killed,
+					(last_edit == LastEdit::Kill)
+						.then_some(direction),
+				);
+			}
+			self.last_edit = LastEdit::Kill;
+
+			return;
+		}
+
+		match command {
+			EditCommand::InsertChar(c) => ta.insert_char(c),
+			EditCommand::InsertTab => {
+				ta.insert_tab();
+			}
+			EditCommand::Newline => ta.insert_newline(),
+			EditCommand::DeleteBack => {
+				delete_back(ta);
+			}
+			EditCommand::DeleteForward => {
+				delete_forward(ta);
+			}
+			EditCommand::CharLeft => move_back(ta),
+			EditCommand::CharRight => move_forward(ta),
+			EditCommand::WordLeft if col > 0 => {
+				jump(ta, row, words::prev_word_start(&line, col));
+			}
+			EditCommand::WordRight if col < line_len => {
+				jump(ta, row, words::next_word_end(&line, col));
+			}
+			EditCommand::WordLeft => ta.move_cursor(CursorMove::Back),
+			EditCommand::WordRight => {
+				ta.move_cursor(CursorMove::Forward);
+			}
+			EditCommand::LineUp => move_rows(ta, -1, wrap_width),
+			EditCommand::LineDown => move_rows(ta, 1, wrap_width),
+			EditCommand::PageUp => move_rows(ta, -page, wrap_width),
+			EditCommand::PageDown => move_rows(ta, page, wrap_width),
+			EditCommand::Move(cursor_move) => {
+				ta.move_cursor(cursor_move);
+			}
+			EditCommand::Yank => {
+				if let Some(text) = self.kill_ring.borrow().get(0) {
+					let text = paste_text(text, &self.input_type);
+					ta.insert_str(&text);
+					self.last_edit = LastEdit::Yank {
+						start: (row, col),
+						index: 0,
+						chars: text.chars().count(),
+					};
+				}
+			}
+			EditCommand::YankPop => {
+				// replaces what was just yanked with the kill before it
+				if let LastEdit::Yank {
+					start,
+					index,
+					chars,
+				} = last_edit
+				{
+					if let Some(text) =
+						self.kill_ring.borrow().get(index + 1)
+					{
+						let text = paste_text(text, &self.input_type);
+						jump(ta, start.0, start.1);
+						ta.delete_str(chars);
+						ta.insert_str(&text);
+						self.last_edit = LastEdit::Yank {
+							start,
+							index: index + 1,
+							chars: text.chars().count(),
+						};
+					}
+				}
+			}
+			EditCommand::Undo => {
+				ta.undo();
+			}
+			EditCommand::Redo => {
+				ta.redo();
+			}
+			EditCommand::KillLineStart
+			| EditCommand::KillLineEnd
+			| EditCommand::KillWordLeft
+			| EditCommand::KillBlankWordLeft
+			| EditCommand::KillWordRight => (),
+		}
+	}
+}
+
+impl DrawableComponent for TextInputComponent {
+	fn draw(&self, f: &mut Frame, rect: Rect) -> Result<()> {
+		// this should always be true since draw should only be being called
+		// is control is visible
+		if let Some(ta) = &self.textarea {
+			let area = if self.embed {
+				rect
+			} else if self.input_type == InputType::Multiline {
+				let (width, height) =
+					self.popup_size.unwrap_or((60, 20));
+				let area = ui::centered_rect(width, height, f.area());
+				ui::rect_inside(
+					Size::new(10, 3),
+					f.area().into(),
+					area,
+				)
+			} else {
+				let area = ui::centered_rect(60, 1, f.area());
+
+				ui::rect_inside(
+					Size::new(10, 3),
+					f.area().into(),
+					area,
+				)
+			};
+
+			f.render_widget(Clear, area);
+
+			let text_area = if self.embed {
+				area
+			} else {
+				let block =
+					Block::default()
+						.borders(Borders::ALL)
+						.border_style(Style::default().add_modifier(
+							ratatui::style::Modifier::BOLD,
+						))
+						.title(self.title.clone());
+				let inner = block.inner(area);
+				f.render_widget(block, area);
+				inner
+			};
+
+			self.draw_text(ta, f, text_area);
+
+			if self.show_char_count {
+				self.draw_char_count(f, area);
+			}
+
+			self.current_area.set(area);
+		}
+		Ok(())
+	}
+}
+
+impl Component for TextInputComponent {
+	fn commands(
+		&self,
+		out: &mut Vec<CommandInfo>,
+		_force_all: bool,
+	) -> CommandBlocking {
+		out.push(
+			CommandInfo::new(
+				strings::commands::close_popup(&self.key_config),
+				true,
+				self.is_visible(),
+			)
+			.order(1),
+		);
+
+		//TODO: we might want to show the textarea specific commands here
+
+		visibility_blocking(self)
+	}
+
+	fn event(&mut self, ev: &Event) -> Result<EventState> {
+		let input = Input::from(ev.clone());
+		self.should_select(&input);
+		if self.is_visible() {
+			let modified = if let Event::Key(e) = ev {
+				if key_match(e, self.key_config.keys.exit_popup) {
+					self.hide();
+					return Ok(EventState::Consumed);
+				}
+
+				let command =
+					if key_match(e, self.key_config.keys.newline)
+						&& self.input_type == InputType::Multiline
+					{
+						Some(EditCommand::Newline)
+					} else {
+						edit_command(&self.key_config.keys, e)
+							.or_else(|| fixed_command(&input))
+					};
+
+				command.is_some_and(|command| {
+					self.run(command);
+					true
+				})
+			} else if let Event::Paste(text) = ev {
+				self.last_edit = LastEdit::Other;
+				self.textarea.as_mut().is_some_and(|ta| {
+					ta.insert_str(paste_text(text, &self.input_type))
+				})
+			} else {
+				false
+			};
+
+			let Some(ta) = &mut self.textarea else {
+				return Ok(EventState::NotConsumed);
+			};
+
+			if self.select_state
+				== SelectionState::SelectionEndPending
+			{
+				ta.cancel_selection();
+				self.select_state = SelectionState::NotSelecting;
+			}
+
+			if modified {
+				self.msg.take();
+				return Ok(EventState::Consumed);
+			}
+		}
+
+		Ok(EventState::NotConsumed)
+	}
+
+	/*
+	  visible maps to textarea Option
+	  None = > not visible
+	  Some => visible
+	*/
+	fn is_visible(&self) -> bool {
+		self.textarea.is_some()
+	}
+
+	fn hide(&mut self) {
+		self.textarea = None;
+	}
+
+	fn show(&mut self) -> Result<()> {
+		self.show_inner_textarea();
+		Ok(())
+	}
+}
+
+fn jump(ta: &mut TextArea<'_>, row: usize, col: usize) {
+	ta.move_cursor(CursorMove::Jump(
+		u16::try_from(row).unwrap_or(u16::MAX),
+		u16::try_from(col).unwrap_or(u16::MAX),
+	));
+}
+
+/// deletes the text between the cursor and `to` and returns it
+fn delete_to(
+	ta: &mut TextArea<'_>,
+	to: (usize, usize),
+) -> Option<String> {
+	let from = ta.cursor();
+	let (start, end) =
+		if to < from { (to, from) } else { (from, to) };
+
+	// newlines count as one char
+	let chars = if start.0 == end.0 {
+		end.1 - start.1
+	} else {
+		ta.lines()[start.0..end.0]
+			.iter()
+			.map(|line| line.chars().count() + 1)
+			.sum::<usize>()
+			- start.1 + end.1
+	};
+
+	if chars == 0 {
+		return None;
+	}
+
+	jump(ta, start.0, start.1);
+	ta.delete_str(chars).then(|| ta.yank_text())
+}
+
+/// command bound to `ev` in the key config (readline like by default)
+fn edit_command(
+	keys: &KeysList,
+	ev: &KeyEvent,
+) -> Option<EditCommand> {
+	let ev = &normalized(ev);
+
+	[
+		(keys.text_char_left, EditCommand::CharLeft),
+		(keys.text_char_right, EditCommand::CharRight),
+		(keys.text_line_up, EditCommand::LineUp),
+		(keys.text_line_down, EditCommand::LineDown),
+		(keys.text_line_start, EditCommand::Move(CursorMove::Head)),
+		(keys.text_line_end, EditCommand::Move(CursorMove::End)),
+		(keys.text_word_left, EditCommand::WordLeft),
+		(keys.text_word_right, EditCommand::WordRight),
+		(keys.text_kill_line_start, EditCommand::KillLineStart),
+		(keys.text_kill_line_end, EditCommand::KillLineEnd),
+		(keys.text_kill_word_left, EditCommand::KillBlankWordLeft),
+		(keys.text_kill_word_right, EditCommand::KillWordRight),
+		(keys.text_yank, EditCommand::Yank),
+		(keys.text_yank_pop, EditCommand::YankPop),
+		(keys.text_undo, EditCommand::Undo),
+		(keys.text_redo, EditCommand::Redo),
+	]
+	.into_iter()
+	.find_map(|(key, command)| key_match(ev, key).then_some(command))
+}
+
+/// terminals send `ctrl-_` as `ctrl-7`, others report the shift needed
+/// to type `_`
+fn normalized(ev: &KeyEvent) -> KeyEvent {
+	match (ev.code, ev.modifiers) {
+		(KeyCode::Char('7'), KeyModifiers::CONTROL) => {
+			KeyEvent::new(KeyCode::Char('_'), KeyModifiers::CONTROL)
+		}
+		(KeyCode::Char('_'), modifiers)
+			if modifiers
+				== KeyModifiers::CONTROL | KeyModifiers::SHIFT =>
+		{
+			KeyEvent::new(KeyCode::Char('_'), KeyModifiers::CONTROL)
+		}
+		_ => *ev,
+	}
+}
+
+/// keys every text input understands regardless of the key config
+#[allow(clippy::too_many_lines, clippy::unnested_or_patterns)]
+const fn fixed_command(input: &Input) -> Option<EditCommand> {
+	let command = match input {
+		Input {
+			key: Key::Char(c),
+			ctrl: false,
+			alt: false,
+			..
+		} => EditCommand::InsertChar(*c),
+		Input {
+			key: Key::Tab,
+			ctrl: false,
+			alt: false,
+			..
+		} => EditCommand::InsertTab,
+		Input {
+			key: Key::Char('h'),
+			ctrl: true,
+			alt: false,
+			..
+		}
+		| Input {
+			key: Key::Backspace,
+			ctrl: false,
+			alt: false,
+			..
+		} => EditCommand::DeleteBack,
+		Input {
+			key: Key::Char('d'),
+			ctrl: true,
+			alt: false,
+			..
+		}
+		| Input {
+			key: Key::Delete,
+			ctrl: false,
+			alt: false,
+			..
+		} => EditCommand::DeleteForward,
+		Input {
+			key: Key::Char('j'),
+			ctrl: true,
+			alt: false,
+			..
+		} => EditCommand::KillLineStart,
+		Input {
+			key: Key::Char('h') | Key::Backspace,
+			ctrl: false,
+			alt: true,
+			..
+		} => EditCommand::KillWordLeft,
+		Input {
+			key: Key::Delete,
+			ctrl: false,
+			alt: true,
+			..
+		} => EditCommand::KillWordRight,
+		Input {
+			key: Key::Down,
+			ctrl: false,
+			alt: false,
+			..
+		} => EditCommand::LineDown,
+		Input {
+			key: Key::Up,
+			ctrl: false,
+			alt: false,
+			..
+		} => EditCommand::LineUp,
+		Input {
+			key: Key::Right,
+			ctrl: false,
+			alt: false,
+			..
+		} => EditCommand::CharRight,
+		Input {
+			key: Key::Left,
+			ctrl: false,
+			alt: false,
+			..
+		} => EditCommand::CharLeft,
+		Input { key: Key::Home, .. }
+		| Input {
+			key: Key::Left | Key::Char('b'),
+			ctrl: true,
+			alt: true,
+			..
+		} => EditCommand::Move(CursorMove::Head),
+		Input { key: Key::End, .. }
+		| Input {
+			key: Key::Right | Key::Char('f'),
+			ctrl: true,
+			alt: true,
+			..
+		} => EditCommand::Move(CursorMove::End),
+		Input {
+			key: Key::Char('<'),
+			ctrl: false,
+			alt: true,
+			..
+		}
+		| Input {
+			key: Key::Up | Key::Char('p'),
+			ctrl: true,
+			alt: true,
+			..
+		} => EditCommand::Move(CursorMove::Top),
+		Input {
+			key: Key::Char('>'),
+			ctrl: false,
+			alt: true,
+			..
+		}
+		| Input {
+			key: Key::Down | Key::Char('n'),
+			ctrl: true,
+			alt: true,
+			..
+		} => EditCommand::Move(CursorMove::Bottom),
+		Input {
+			key: Key::Right,
+			ctrl: true,
+			alt: false,
+			..
+		}
+		| Input {
+			key: Key::Right,
+			ctrl: false,
+			alt: true,
+			..
+		} => EditCommand::WordRight,
+		Input {
+			key: Key::Left,
+			ctrl: true,
+			alt: false,
+			..
+		}
+		| Input {
+			key: Key::Left,
+			ctrl: false,
+			alt: true,
+			..
+		} => EditCommand::WordLeft,
+		Input {
+			key: Key::Char(']' | 'n'),
+			ctrl: false,
+			alt: true,
+			..
+		}
+		| Input {
+			key: Key::Down,
+			ctrl: true,
+			alt: false,
+			..
+		} => EditCommand::Move(CursorMove::ParagraphForward),
+		Input {
+			key: Key::Char('[' | 'p'),
+			ctrl: false,
+			alt: true,
+			..
+		}
+		| Input {
+			key: Key::Up,
+			ctrl: true,
+			alt: false,
+			..
+		} => EditCommand::Move(CursorMove::ParagraphBack),
+		Input {
+			key: Key::Char('v'),
+			ctrl: true,
+			alt: false,
+			..
+		}
+		| Input {
+			key: Key::PageDown, ..
+		} => EditCommand::PageDown,
+		Input {
+			key: Key::Char('v'),
+			ctrl: false,
+			alt: true,
+			..
+		}
+		| Input {
+			key: Key::PageUp, ..
+		} => EditCommand::PageUp,
+		_ => return None,
+	};
+
+	Some(command)
+}
+
+fn move_back(ta: &mut TextArea<'_>) {
+	let (row, col) = ta.cursor();
+	if col == 0 {
+		ta.move_cursor(CursorMove::Back);
+	} else {
+		jump(ta, row, layout::prev_boundary(&ta.lines()[row], col));
+	}
+}
+
+fn move_forward(ta: &mut TextArea<'_>) {
+	let (row, col) = ta.cursor();
+	let next = layout::next_boundary(&ta.lines()[row], col);
+	if next == col {
+		ta.move_cursor(CursorMove::Forward);
+	} else {
+		jump(ta, row, next);
+	}
+}
+
+/// moves the cursor `lines` down (up if negative) keeping the screen
+/// column it is in
+/// moves the cursor `rows` screen rows up (negative) or down keeping
+/// its column on screen, rows are lines if the text is not wrapped
+fn move_rows(
+	ta: &mut TextArea<'_>,
+	rows: isize,
+	wrap_width: Option<usize>,
+) {
+	let Some(width) = wrap_width else {
+		move_lines(ta, rows);
+		return;
+	};
+
+	let wrapped = layout::wrap(ta.lines(), width);
+	let (row, x) =
+		layout::cursor_position(&wrapped, ta.lines(), ta.cursor());
+	let target = row
+		.saturating_add_signed(rows)
+		.min(wrapped.len().saturating_sub(1));
+
+	if target == row {
+		return;
+	}
+
+	let layout::Row { line, start, end } = wrapped[target];
+	let text = &ta.lines()[line];
+	let mut col =
+		layout::col_at(text, layout::width_to(text, start) + x);
+
+	// the end of a row that is continued is the start of the next one
+	if col >= end
+		&& wrapped
+			.get(target + 1)
+			.is_some_and(|next| next.line == line)
+	{
+		col = layout::prev_boundary(text, end);
+	}
+
+	jump(ta, line, col.min(end));
+}
+
+fn move_lines(ta: &mut TextArea<'_>, lines: isize) {
+	let (row, col) = ta.cursor();
+	let target = row
+		.saturating_add_signed(lines)
+		.min(ta.lines().len().saturating_sub(1));
+
+	if target != row {
+		let x = layout::width_to(&ta.lines()[row], col);
+		let col = layout::col_at(&ta.lines()[target], x);
+		jump(ta, target, col);
+	}
+}
+
+/// deletes the grapheme (or selection) before the cursor
+fn delete_back(ta: &mut TextArea<'_>) -> bool {
+	let (row, col) = ta.cursor();
+	if col == 0 || ta.selection_range().is_some() {
+		return ta.delete_char();
+	}
+
+	let start = layout::prev_boundary(&ta.lines()[row], col);
+	jump(ta, row, start);
+	ta.delete_str(col - start)
+}
+
+/// deletes the grapheme (or selection) after the cursor
+fn delete_forward(ta: &mut TextArea<'_>) -> bool {
+	let (row, col) = ta.cursor();
+	let end = layout::next_boundary(&ta.lines()[row], col);
+	if end == col || ta.selection_range().is_some() {
+		return ta.delete_next_char();
+	}
+
+	ta.delete_str(end - col)
+}
+
+/// `text` pasted into an input of `input_type`: line endings are
+/// normalized to `\n`, inputs without lines get spaces instead (a pasted
+/// newline must never act like `enter` on a branch name)
+fn paste_text(text: &str, input_type: &InputType) -> String {
+	let text = text.replace("\r\n", "\n").replace('\r', "\n");
+
+	if *input_type == InputType::Multiline {
+		text
+	} else {
+		text.trim_end_matches('\n').replace('\n', " ")
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+	use ratatui::{backend::TestBackend, Terminal};
+	use unicode_width::UnicodeWidthStr;
+
+	// man, woman, girl joined by zero width joiners
+	const FAMILY: &str =
+		"\u{1F468}\u{200D}\u{1F469}\u{200D}\u{1F467}";
+
+	fn input(
+		text: &str,
+		input_type: InputType,
+	) -> TextInputComponent {
+		let env = Environment::test_env();
+		let mut comp = TextInputComponent::new(&env, "", "", false)
+			.with_input_type(input_type);
+		comp.embed();
+		comp.show_inner_textarea();
+		comp.set_text(String::from(text));
+		comp
+	}
+
+	fn press(comp: &mut TextInputComponent, code: KeyCode) {
+		comp.event(&Event::Key(KeyEvent::new(
+			code,
+			KeyModifiers::NONE,
+		)))
+		.unwrap();
+	}
+
+	fn press_with(
+		comp: &mut TextInputComponent,
+		code: KeyCode,
+		modifiers: KeyModifiers,
+	) {
+		comp.event(&Event::Key(KeyEvent::new(code, modifiers)))
+			.unwrap();
+	}
+
+	fn ctrl(comp: &mut TextInputComponent, c: char) {
+		press_with(comp, KeyCode::Char(c), KeyModifiers::CONTROL);
+	}
+
+	fn alt(comp: &mut TextInputComponent, c: char) {
+		press_with(comp, KeyCode::Char(c), KeyModifiers::ALT);
+	}
+
+	/// rows of the rendered buffer and the cursor position
+	fn render(
+		comp: &TextInputComponent,
+		width: u16,
+		height: u16,
+	) -> (Vec<String>, Position) {
+		let mut terminal =
+			Terminal::new(TestBackend::new(width, height)).unwrap();
+		terminal
+			.draw(|f| {
+				comp.draw(f, f.area()).unwrap();
+			})
+			.unwrap();
+
+		let buffer = terminal.backend().buffer();
+		let rows = (0..height)
+			.map(|y| {
+				let mut row = String::new();
+				let mut x = 0;
+				while x < width {
+					let symbol = buffer[(x, y)].symbol();
+					row.push_str(symbol);
+					// skip the cell hidden by a wide char
+					x +=
+						u16::try_from(symbol.width().max(1)).unwrap();
+				}
+				row
+			})
+			.collect();
+
+		(rows, terminal.get_cursor_position().unwrap())
+	}
+
+	#[test]
+	fn test_wide_chars_cursor() {
+		let mut comp = input("日本語", InputType::Singleline);
+		press(&mut comp, KeyCode::End);
+
+		let (rows, cursor) = render(&comp, 10, 1);
+		assert_eq!(rows, vec!["日本語    "]);
+		assert_eq!(cursor, Position::new(6, 0));
+
+		press(&mut comp, KeyCode::Left);
+		let (_, cursor) = render(&comp, 10, 1);
+		assert_eq!(cursor, Position::new(4, 0));
+		assert_eq!(comp.textarea.as_ref().unwrap().cursor(), (0, 2));
+	}
+
+	#[test]
+	fn test_clusters_as_one() {
+		let mut comp = input(
+			&format!("ae\u{301}{FAMILY}b"),
+			InputType::Singleline,
+		);
+		press(&mut comp, KeyCode::End);
+
+		press(&mut comp, KeyCode::Left);
+		press(&mut comp, KeyCode::Left);
+		// in front of the family
+		assert_eq!(comp.textarea.as_ref().unwrap().cursor(), (0, 3));
+		let (_, cursor) = render(&comp, 10, 1);
+		assert_eq!(cursor, Position::new(2, 0));
+
+		press(&mut comp, KeyCode::Delete);
+		assert_eq!(comp.get_text(), "ae\u{301}b");
+
+		press(&mut comp, KeyCode::Backspace);
+		assert_eq!(comp.get_text(), "ab");
+
+		press(&mut comp, KeyCode::Right);
+		press(&mut comp, KeyCode::Char('\u{301}'));
+		assert_eq!(comp.get_text(), "ab\u{301}");
+		let (rows, cursor) = render(&comp, 4, 1);
+		assert_eq!(rows, vec!["ab\u{301}  "]);
+		assert_eq!(cursor, Position::new(2, 0));
+	}
+
+	#[test]
+	fn test_vertical_move_keeps_column() {
+		let mut comp = input("日本語\nabcdef", InputType::Multiline);
+		press(&mut comp, KeyCode::Right);
+		press(&mut comp, KeyCode::Right);
+
+		press(&mut comp, KeyCode::Down);
+		assert_eq!(comp.textarea.as_ref().unwrap().cursor(), (1, 4));
+
+		// back up from the middle of the second wide char
+		press(&mut comp, KeyCode::Left);
+		press(&mut comp, KeyCode::Up);
+		assert_eq!(comp.textarea.as_ref().unwrap().cursor(), (0, 1));
+	}
+
+	#[test]
+	fn test_wrap_wide_chars() {
+		let mut comp =
+			input("日本語ab\nc\u{301}", InputType::Multiline);
+		press(&mut comp, KeyCode::Down);
+		press(&mut comp, KeyCode::End);
+
+		let (rows, cursor) = render(&comp, 5, 4);
+		assert_eq!(
+			rows,
+			vec!["日本 ", "語ab ", "c\u{301}    ", "     "]
+		);
+		assert_eq!(cursor, Position::new(1, 2));
+	}
+
+	#[test]
+	fn test_singleline_scrolls_horizontally() {
+		let mut comp = input("abc日本語", InputType::Singleline);
+		press(&mut comp, KeyCode::End);
+
+		// the first row only shows the end and the cursor
+		let (rows, cursor) = render(&comp, 5, 1);
+		assert_eq!(rows, vec!["本語 "]);
+		assert_eq!(cursor, Position::new(4, 0));
+	}
+
+	#[test]
+	fn test_password_masks_clusters() {
+		let mut comp = input(
+			&format!("p{FAMILY}\u{301}語"),
+			InputType::Password,
+		);
+		press(&mut comp, KeyCode::End);
+
+		let (rows, cursor) = render(&comp, 5, 1);
+		assert_eq!(rows, vec!["***  "]);
+		assert_eq!(cursor, Position::new(3, 0));
+	}
+
+	#[test]
+	fn test_char_count_graphemes() {
+		let comp =
+			input(&format!("日本{FAMILY}"), InputType::Multiline);
+		assert_eq!(comp.get_text().graphemes(true).count(), 3);
+	}
+
+	#[test]
+	fn test_scrolls_to_cursor() {
+		let text = (1..=40)
+			.map(|i| format!("line {i}"))
+			.collect::<Vec<_>>()
+			.join("\n");
+		let mut comp = input(&text, InputType::Multiline);
+
+		let (rows, _) = render(&comp, 8, 5);
+		assert_eq!(rows[0], "line 1  ");
+
+		alt(&mut comp, '>');
+		let (rows, cursor) = render(&comp, 8, 5);
+		assert_eq!(rows[0], "line 36 ");
+		assert_eq!(rows[4], "line 40 ");
+		assert_eq!(cursor, Position::new(0, 4));
+
+		press(&mut comp, KeyCode::PageUp);
+		let (rows, cursor) = render(&comp, 8, 5);
+		assert_eq!(rows[0], "line 35 ");
+		assert_eq!(cursor, Position::new(0, 0));
+	}
+
+	#[test]
+	fn test_vertical_move_by_wrapped_rows() {
+		let mut comp = input("abcdefg\nxyz", InputType::Multiline);
+		// wrapping is only known once drawn
+		render(&comp, 4, 4);
+
+		press(&mut comp, KeyCode::Right);
+		press(&mut comp, KeyCode::Down);
+		assert_eq!(comp.textarea.as_ref().unwrap().cursor(), (0, 5));
+		let (_, cursor) = render(&comp, 4, 4);
+		assert_eq!(cursor, Position::new(1, 1));
+
+		press(&mut comp, KeyCode::Down);
+		assert_eq!(comp.textarea.as_ref().unwrap().cursor(), (1, 1));
+
+		press(&mut comp, KeyCode::End);
+		press(&mut comp, KeyCode::Up);
+		assert_eq!(comp.textarea.as_ref().unwrap().cursor(), (0, 7));
+		press(&mut comp, KeyCode::Up);
+		assert_eq!(comp.textarea.as_ref().unwrap().cursor(), (0, 3));
+	}
+
+	#[test]
+	fn test_vertical_move_stays_on_wrapped_row() {
+		let mut comp = input("abcd\n日本語", InputType::Multiline);
+		render(&comp, 5, 4);
+
+		press(&mut comp, KeyCode::End);
+		press(&mut comp, KeyCode::Down);
+
+		// the end of the first row of "日本語" would be drawn on the
+		// second one
+		assert_eq!(comp.textarea.as_ref().unwrap().cursor(), (1, 1));
+		let (rows, cursor) = render(&comp, 5, 4);
+		assert_eq!(rows, vec!["abcd ", "日本 ", "語   ", "     "]);
+		assert_eq!(cursor, Position::new(2, 1));
+	}
+
+	#[test]
+	fn test_line_numbers() {
+		let mut comp = input("abcdefgh\nxy", InputType::Multiline);
+		comp.set_line_numbers(true);
+
+		let (rows, cursor) = render(&comp, 6, 5);
+		assert_eq!(
+			rows,
+			vec!["1 abcd", "  efgh", "      ", "2 xy  ", "      "]
+		);
+		assert_eq!(cursor, Position::new(2, 0));
+	}
+
+	#[test]
+	fn test_subject_separator() {
+		let comp = input("fix\n\nbody", InputType::Multiline)
+			.with_subject_separator();
+
+		let (rows, _) = render(&comp, 6, 3);
+		assert_eq!(
+			rows,
+			vec!["fix   ", &"\u{2500}".repeat(6), "body  "]
+		);
+
+		// a missing separator line is not drawn as a rule
+		let comp = input("fix\nbody", InputType::Multiline)
+			.with_subject_separator();
+		let (rows, _) = render(&comp, 6, 3);
+		assert_eq!(rows, vec!["fix   ", "body  ", "      "]);
+	}
+
+	#[test]
+	fn test_kill_and_yank() {
+		let mut comp = input("hello world", InputType::Singleline);
+		alt(&mut comp, 'f');
+		ctrl(&mut comp, 'k');
+		assert_eq!(comp.get_text(), "hello");
+
+		ctrl(&mut comp, 'a');
+		ctrl(&mut comp, 'y');
+		assert_eq!(comp.get_text(), " worldhello");
+		assert_eq!(comp.textarea.as_ref().unwrap().cursor(), (0, 6));
+	}
+
+	#[test]
+	fn test_consecutive_kills_are_joined() {
+		let mut comp = input("one two three", InputType::Singleline);
+		press(&mut comp, KeyCode::End);
+		ctrl(&mut comp, 'w');
+		ctrl(&mut comp, 'w');
+		assert_eq!(comp.get_text(), "one ");
+
+		ctrl(&mut comp, 'y');
+		assert_eq!(comp.get_text(), "one two three");
+	}
+
+	#[test]
+	fn test_yank_pop() {
+		let mut comp = input("foo bar", InputType::Singleline);
+		press(&mut comp, KeyCode::End);
+		ctrl(&mut comp, 'w');
+		// not joined with the kill before, the cursor moved in between
+		ctrl(&mut comp, 'a');
+		ctrl(&mut comp, 'k');
+		assert_eq!(comp.get_text(), "");
+
+		ctrl(&mut comp, 'y');
+		assert_eq!(comp.get_text(), "foo ");
+		alt(&mut comp, 'y');
+		assert_eq!(comp.get_text(), "bar");
+		alt(&mut comp, 'y');
+		assert_eq!(comp.get_text(), "foo ");
+	}
+
+	#[test]
+	fn test_kill_ring_is_shared() {
+		let env = Environment::test_env();
+		let mut first = TextInputComponent::new(&env, "", "", false);
+		let mut second = TextInputComponent::new(&env, "", "", false);
+		first.show_inner_textarea();
+		second.show_inner_textarea();
+		first.set_text(String::from("feature"));
+
+		ctrl(&mut first, 'k');
+		ctrl(&mut second, 'y');
+		assert_eq!(second.get_text(), "feature");
+	}
+
+	#[test]
+	fn test_kill_joins_lines() {
+		let mut comp = input("a\nb", InputType::Multiline);
+		press(&mut comp, KeyCode::Down);
+		ctrl(&mut comp, 'u');
+		assert_eq!(comp.get_text(), "ab");
+		assert_eq!(comp.textarea.as_ref().unwrap().cursor(), (0, 1));
+	}
+
+	#[test]
+	fn test_unicode_word_moves() {
+		let mut comp =
+			input("cafe\u{301} au-lait", InputType::Singleline);
+		alt(&mut comp, 'f');
+		assert_eq!(comp.textarea.as_ref().unwrap().cursor(), (0, 5));
+		alt(&mut comp, 'f');
+		alt(&mut comp, 'f');
+		assert_eq!(comp.textarea.as_ref().unwrap().cursor(), (0, 13));
+		alt(&mut comp, 'b');
+		assert_eq!(comp.textarea.as_ref().unwrap().cursor(), (0, 9));
+		alt(&mut comp, 'd');
+		assert_eq!(comp.get_text(), "cafe\u{301} au-");
+	}
+
+	#[test]
+	fn test_undo_redo() {
+		let mut comp = input("", InputType::Singleline);
+		press(&mut comp, KeyCode::Char('x'));
+		// sent by most terminals for `ctrl-_`
+		ctrl(&mut comp, '7');
+		assert_eq!(comp.get_text(), "");
+		ctrl(&mut comp, 'r');
+		assert_eq!(comp.get_text(), "x");
+	}
+
+	#[test]
+	fn test_smoke() {
+		let env = Environment::test_env();
+		let mut comp = TextInputComponent::new(&env, "", "", false);
+		comp.show_inner_textarea();
+		comp.set_text(String::from("a\nb"));
+		assert!(comp.is_visible());
+		if let Some(ta) = &mut comp.textarea {
+			assert_eq!(ta.cursor(), (0, 0));
+
+			ta.move_cursor(CursorMove::Forward);
+			assert_eq!(ta.cursor(), (0, 1));
+
+			ta.move_cursor(CursorMove::Back);
+			assert_eq!(ta.cursor(), (0, 0));
+		}
+	}
+
+	#[test]
+	fn text_cursor_initial_position() {
+		let env = Environment::test_env();
+		let mut comp = TextInputComponent::new(&env, "", "", false);
+		comp.show_inner_textarea();
+		comp.set_text(String::from("a"));
+		assert!(comp.is_visible());
+		if let Some(ta) = &mut comp.textarea {
+			let txt = ta.lines();
+			assert_eq!(txt[0].len(), 1);
+			assert_eq!(txt[0].as_bytes()[0], b'a');
+		}
+	}
+
+	#[test]
+	fn test_multiline() {
+		let env = Environment::test_env();
+		let mut comp = TextInputComponent::new(&env, "", "", false);
+		comp.show_inner_textarea();
+		comp.set_text(String::from("a\nb\nc"));
+		assert!(comp.is_visible());
+		if let Some(ta) = &mut comp.textarea {
+			let txt = ta.lines();
+			assert_eq!(txt[0], "a");
+			assert_eq!(txt[1], "b");
+			assert_eq!(txt[2], "c");
+		}
+	}
+
+	#[test]
+	fn test_next_word_position() {
+		let env = Environment::test_env();
+		let mut comp = TextInputComponent::new(&env, "", "", false);
+		comp.show_inner_textarea();
+		comp.set_text(String::from("aa b;c"));
+		assert!(comp.is_visible());
+		if let Some(ta) = &mut comp.textarea {
+			// from word start
+			ta.move_cursor(CursorMove::Head);
+			ta.move_cursor(CursorMove::WordForward);
+			assert_eq!(ta.cursor(), (0, 3));
+			// from inside start
+			ta.move_cursor(CursorMove::Forward);
+			ta.move_cursor(CursorMove::WordForward);
+			assert_eq!(ta.cursor(), (0, 5));
+			// to string end
+			ta.move_cursor(CursorMove::Forward);
+			ta.move_cursor(CursorMove::WordForward);
+			assert_eq!(ta.cursor(), (0, 6));
+
+			// from string end
+			ta.move_cursor(CursorMove::Forward);
+			let save_cursor = ta.cursor();
+			ta.move_cursor(CursorMove::WordForward);
+			assert_eq!(ta.cursor(), save_cursor);
+		}
+	}
+
+	#[test]
+	fn test_previous_word_position() {
+		let env = Environment::test_env();
+		let mut comp = TextInputComponent::new(&env, "", "", false);
+		comp.show_inner_textarea();
+		comp.set_text(String::from(" a bb;c"));
+		assert!(comp.is_visible());
+
+		if let Some(ta) = &mut comp.textarea {
+			// from string end
+			ta.move_cursor(CursorMove::End);
+			ta.move_cursor(CursorMove::WordBack);
+			assert_eq!(ta.cursor(), (0, 6));
+			// from inside word
+			ta.move_cursor(CursorMove::Back);
+			ta.move_cursor(CursorMove::WordBack);
+			assert_eq!(ta.cursor(), (0, 3));
+			// from word start
+			ta.move_cursor(CursorMove::WordBack);
+			assert_eq!(ta.cursor(), (0, 1));
+			// to string start
+			ta.move_cursor(CursorMove::WordBack);
+			assert_eq!(ta.cursor(), (0, 0));
+			// from string start
+			let save_cursor = ta.cursor();
+			ta.move_cursor(CursorMove::WordBack);
+
+			assert_eq!(ta.cursor(), save_cursor);
+		}
+	}
+
+	#[test]
+	fn test_next_word_multibyte() {
+		let env = Environment::test_env();
+		let mut comp = TextInputComponent::new(&env, "", "", false);
+		// should emojis be word boundaries or not?
+		// various editors (vs code, vim) do not agree with the
+		// behavhior of the original textinput here.
+		//
+		// tui-textarea agrees with them.
+		// So these tests are changed to match that behavior
+		// FYI: this line is "a à ❤ab🤯 a"
+
+		//              "01245       89A        EFG"
+		let text = dbg!("a à \u{2764}ab\u{1F92F} a");
+		comp.show_inner_textarea();
+		comp.set_text(String::from(text));
+		assert!(comp.is_visible());
+
+		if let Some(ta) = &mut comp.textarea {
+			ta.move_cursor(CursorMove::Head);
+			ta.move_cursor(CursorMove::WordForward);
+			assert_eq!(ta.cursor(), (0, 2));
+			ta.move_cursor(CursorMove::WordForward);
+			assert_eq!(ta.cursor(), (0, 4));
+			ta.move_cursor(CursorMove::WordForward);
+			assert_eq!(ta.cursor(), (0, 9));
+			ta.move_cursor(CursorMove::WordForward);
+			assert_eq!(ta.cursor(), (0, 10));
+			let save_cursor = ta.cursor();
+			ta.move_cursor(CursorMove::WordForward);
+			assert_eq!(ta.cursor(), save_cursor);
+
+			ta.move_cursor(CursorMove::End);
+			ta.move_cursor(CursorMove::WordBack);
+			assert_eq!(ta.cursor(), (0, 9));
+			ta.move_cursor(CursorMove::WordBack);
+			assert_eq!(ta.cursor(), (0, 4));
+			ta.move_cursor(CursorMove::WordBack);
+			assert_eq!(ta.cursor(), (0, 2));
+			ta.move_cursor(CursorMove::WordBack);
+			assert_eq!(ta.cursor(), (0, 0));
+			let save_cursor = ta.cursor();
+			ta.move_cursor(CursorMove::WordBack);
+			assert_eq!(ta.cursor(), save_cursor);
+		}
+	}
+
+	#[test]
+	fn test_paste_multiline() {
+		let env = Environment::test_env();
+		let mut comp = TextInputComponent::new(&env, "", "", false);
+		comp.show_inner_textarea();
+		comp.set_text(String::from("subject: "));
+		if let Some(ta) = &mut comp.textarea {
+			ta.move_cursor(CursorMove::End);
+		}
+
+		let res = comp
+			.event(&Event::Paste(String::from(
+				"fix\r\n\r\nbody\rmore\n",
+			)))
+			.unwrap();
+
+		assert!(res.is_consumed());
+		assert_eq!(comp.get_text(), "subject: fix\n\nbody\nmore\n");
+	}
+
+	#[test]
+	fn test_paste_singleline() {
+		let env = Environment::test_env();
+		let mut comp = TextInputComponent::new(&env, "", "", false)
+			.with_input_type(InputType::Singleline);
+		comp.show_inner_textarea();
+
+		let res = comp
+			.event(&Event::Paste(String::from(
+				"feature/\r\nsome name\n",
+			)))
+			.unwrap();
+
+		assert!(res.is_consumed());
+		assert_eq!(comp.get_text(), "feature/ some name");
+		assert!(comp.is_visible());
+	}
+
+	#[test]
+	fn test_paste_text() {
+		assert_eq!(
+			paste_text("a\r\nb", &InputType::Multiline),
+			"a\nb"
+		);
+		assert_eq!(paste_text("a\rb", &InputType::Password), "a b");
+		assert_eq!(paste_text("\n\n", &InputType::Singleline), "");
+	}
+}
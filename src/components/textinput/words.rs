@@ -0,0 +1,108 @@
+//! word boundaries for moving and deleting by word in text inputs.
+//!
+//! words are found by the unicode word boundary rules (UAX #29) instead
+//! of classifying single chars, so accented letters, CJK or emoji
+//! sequences are never split. a word is a segment containing a letter or
+//! a digit, everything else (whitespace, punctuation) is skipped over.
+
+use unicode_segmentation::UnicodeSegmentation;
+
+/// words of `line` as char ranges
+fn words(line: &str) -> impl Iterator<Item = (usize, usize)> + '_ {
+	let mut col = 0;
+	line.split_word_bounds().filter_map(move |segment| {
+		let start = col;
+		col += segment.chars().count();
+		segment
+			.chars()
+			.any(char::is_alphanumeric)
+			.then_some((start, col))
+	})
+}
+
+/// end of the word `col` is in or of the next one (end of `line` if
+/// there is none)
+pub fn next_word_end(line: &str, col: usize) -> usize {
+	words(line)
+		.map(|(_, end)| end)
+		.find(|end| *end > col)
+		.unwrap_or_else(|| line.chars().count().max(col))
+}
+
+/// start of the word `col` is in or of the previous one (`0` if there
+/// is none)
+pub fn prev_word_start(line: &str, col: usize) -> usize {
+	words(line)
+		.map(|(start, _)| start)
+		.take_while(|start| *start < col)
+		.last()
+		.unwrap_or_default()
+}
+
+/// start of the whitespace delimited word before `col`, like the
+/// `unix-word-rubout` of readline (`ctrl-w`)
+pub fn prev_blank_word_start(line: &str, col: usize) -> usize {
+	let chars = line.chars().take(col).collect::<Vec<_>>();
+
+	let word_end = chars
+		.iter()
+		.rposition(|c| !c.is_whitespace())
+		.map_or(0, |i| i + 1);
+
+	chars[..word_end]
+		.iter()
+		.rposition(|c| c.is_whitespace())
+		.map_or(0, |i| i + 1)
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use pretty_assertions::assert_eq;
+
+	#[test]
+	fn test_next_word_end() {
+		let line = "fix: café, 日本 ok";
+
+		assert_eq!(next_word_end(line, 0), 3);
+		// from the colon over the accented word
+		assert_eq!(next_word_end(line, 3), 9);
+		// ideographs are words of their own
+		assert_eq!(next_word_end(line, 9), 12);
+		assert_eq!(next_word_end(line, 12), 13);
+		assert_eq!(next_word_end(line, 13), 16);
+		assert_eq!(next_word_end(line, 16), 16);
+	}
+
+	#[test]
+	fn test_prev_word_start() {
+		let line = "fix: cafe\u{301}, 日本 ok";
+
+		assert_eq!(prev_word_start(line, 0), 0);
+		assert_eq!(prev_word_start(line, 3), 0);
+		// the combining accent stays with its letter
+		assert_eq!(prev_word_start(line, 10), 5);
+		assert_eq!(prev_word_start(line, 11), 5);
+		assert_eq!(prev_word_start(line, 17), 15);
+		assert_eq!(prev_word_start(line, 15), 13);
+	}
+
+	#[test]
+	fn test_emoji_are_not_words() {
+		let line = "a \u{1F468}\u{200D}\u{1F469} b";
+
+		assert_eq!(next_word_end(line, 1), 7);
+		assert_eq!(prev_word_start(line, 6), 0);
+	}
+
+	#[test]
+	fn test_prev_blank_word_start() {
+		let line = "git push origin/main  ";
+
+		assert_eq!(prev_blank_word_start(line, 22), 9);
+		assert_eq!(prev_blank_word_start(line, 20), 9);
+		assert_eq!(prev_blank_word_start(line, 9), 4);
+		assert_eq!(prev_blank_word_start(line, 3), 0);
+		assert_eq!(prev_blank_word_start(line, 0), 0);
+	}
+}
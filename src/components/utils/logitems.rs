@@ -1,7 +1,7 @@
 use asyncgit::sync::{CommitId, CommitInfo};
 use chrono::{DateTime, Duration, Local, Utc};
 use indexmap::IndexSet;
-use std::{rc::Rc, slice::Iter};
+use std::{rc::Rc, slice::Iter, sync::Arc};
 
 #[cfg(feature = "ghemoji")]
 use super::emoji::emojifi_string;
@@ -13,8 +13,8 @@ type BoxStr = Box<str>;
 pub struct LogEntry {
 	//TODO: cache string representation
 	pub time: DateTime<Local>,
-	//TODO: use tinyvec here
-	pub author: BoxStr,
+	/// shared with all other entries of the same author
+	pub author: Arc<str>,
 	pub msg: BoxStr,
 	//TODO: use tinyvec here
 	pub hash_short: BoxStr,
@@ -48,7 +48,7 @@ impl From<CommitInfo> for LogEntry {
 		let msg = emojifi_string(msg);
 
 		Self {
-			author: author.into(),
+			author,
 			msg: msg.into(),
 			time,
 			hash_short,
@@ -7,7 +7,9 @@ pub mod filetree;
 pub mod logitems;
 pub mod scroll_horizontal;
 pub mod scroll_vertical;
+pub mod search;
 pub mod statustree;
+pub mod word_diff;
 
 /// macro to simplify running code that might return Err.
 /// It will show a popup in that case
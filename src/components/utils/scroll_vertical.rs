@@ -49,6 +49,11 @@ impl VerticalScroll {
 		true
 	}
 
+	/// scroll so that `top` is the first visible line (if possible)
+	pub fn move_to(&self, top: usize) {
+		self.top.set(top.min(self.max_top.get()));
+	}
+
 	pub fn move_area_to_visible(
 		&self,
 		height: usize,
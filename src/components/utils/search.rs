@@ -0,0 +1,88 @@
+//! search prompt of the scrollable text components, typed after `/` and
+//! shown in their bottom border
+
+use crate::ui::style::SharedTheme;
+use crossterm::event::{Event, KeyCode, KeyModifiers};
+use ratatui::{layout::Rect, widgets::Paragraph, Frame};
+
+#[derive(Default)]
+pub enum SearchState {
+	#[default]
+	None,
+	/// query is being typed
+	Input(String),
+	/// query was submitted
+	Active(String),
+}
+
+impl SearchState {
+	/// `true` while a query is typed
+	pub const fn is_input(&self) -> bool {
+		matches!(self, Self::Input(_))
+	}
+
+	/// the submitted query, `None` if it is empty
+	pub fn query(&self) -> Option<&str> {
+		match self {
+			Self::Active(query) if !query.is_empty() => Some(query),
+			_ => None,
+		}
+	}
+
+	/// handles a key while the query is typed,
+	/// returns `true` once it was submitted
+	pub fn input(&mut self, ev: &Event) -> bool {
+		let Self::Input(query) = self else {
+			return false;
+		};
+
+		if let Event::Key(e) = ev {
+			match e.code {
+				KeyCode::Esc => *self = Self::None,
+				KeyCode::Enter => {
+					*self = Self::Active(std::mem::take(query));
+					return true;
+				}
+				KeyCode::Backspace => {
+					query.pop();
+				}
+				KeyCode::Char(c)
+					if e.modifiers.is_empty()
+						|| e.modifiers == KeyModifiers::SHIFT =>
+				{
+					query.push(c);
+				}
+				_ => (),
+			}
+		}
+
+		false
+	}
+
+	/// draws the prompt into the single line `r`, `matches` counts the
+	/// matches of a submitted query
+	pub fn draw_prompt(
+		&self,
+		f: &mut Frame,
+		r: Rect,
+		theme: &SharedTheme,
+		matches: impl FnOnce(&str) -> usize,
+	) {
+		let prompt = match self {
+			Self::None => return,
+			Self::Input(query) => format!("/{query}"),
+			Self::Active(query) => {
+				format!("/{query} [{}]", matches(query))
+			}
+		};
+
+		f.render_widget(
+			Paragraph::new(prompt).style(theme.text(true, true)),
+			r,
+		);
+	}
+}
+
+pub fn contains_ignore_case(line: &str, query: &str) -> bool {
+	line.to_lowercase().contains(&query.to_lowercase())
+}
@@ -3,7 +3,11 @@ use super::filetree::{
 };
 use anyhow::Result;
 use asyncgit::StatusItem;
-use std::{cell::Cell, cmp, collections::BTreeSet};
+use std::{
+	cell::Cell,
+	cmp,
+	collections::{BTreeSet, HashMap},
+};
 
 //TODO: use new `filetreelist` crate
 
@@ -46,24 +50,30 @@ impl SelectionChange {
 
 impl StatusTree {
 	/// update tree with a new list, try to retain selection and collapse states
+	///
+	/// the selection follows the selected path, if it disappeared the
+	/// closest following item that still exists gets selected.
 	pub fn update(&mut self, list: &[StatusItem]) -> Result<()> {
 		let last_collapsed = self.all_collapsed();
 
-		let last_selection =
-			self.selected_item().map(|e| e.info.full_path);
 		let last_selection_index = self.selection.unwrap_or(0);
+		let last_selection: Vec<String> = self
+			.selection
+			.map(|idx| {
+				self.tree.items()[idx..]
+					.iter()
+					.map(|item| item.info.full_path.clone())
+					.collect()
+			})
+			.unwrap_or_default();
 
 		self.tree = FileTreeItems::new(list, &last_collapsed)?;
-		self.selection = last_selection.as_ref().map_or_else(
-			|| self.tree.items().first().map(|_| 0),
-			|last_selection| {
-				self.find_last_selection(
-					last_selection,
-					last_selection_index,
-				)
-				.or_else(|| self.tree.items().first().map(|_| 0))
-			},
-		);
+		self.selection = self
+			.find_last_selection(
+				&last_selection,
+				last_selection_index,
+			)
+			.or_else(|| self.tree.items().first().map(|_| 0));
 
 		self.update_visibility(None, 0, true);
 		self.available_selections = self.setup_available_selections();
@@ -177,6 +187,41 @@ impl StatusTree {
 		self.tree.items().is_empty()
 	}
 
+	/// selects the file at `path`, expanding the folders it is in.
+	/// returns false if there is no such file
+	pub fn select_path(&mut self, path: &str) -> bool {
+		let Some(idx) = self.tree.items().iter().position(|item| {
+			matches!(item.kind, FileTreeItemKind::File(_))
+				&& item.info.full_path == path
+		}) else {
+			return false;
+		};
+
+		// outer folders come first so inner ones are expanded last
+		let collapsed: Vec<(usize, String)> = self.tree.items()
+			[..idx]
+			.iter()
+			.enumerate()
+			.filter(|(_, item)| {
+				matches!(
+					item.kind,
+					FileTreeItemKind::Path(PathCollapsed(true))
+				) && path
+					.starts_with(&format!("{}/", item.info.full_path))
+			})
+			.map(|(i, item)| (i, item.info.full_path.clone()))
+			.collect();
+
+		for (i, folder) in collapsed {
+			self.expand(&folder, i);
+		}
+
+		self.available_selections = self.setup_available_selections();
+		self.selection = Some(idx);
+
+		true
+	}
+
 	fn all_collapsed(&self) -> BTreeSet<&String> {
 		let mut res = BTreeSet::new();
 
@@ -193,19 +238,32 @@ impl StatusTree {
 		res
 	}
 
+	/// `last_selection` is the previously selected path followed by
+	/// all the paths that were listed after it
 	fn find_last_selection(
 		&self,
-		last_selection: &str,
+		last_selection: &[String],
 		last_index: usize,
 	) -> Option<usize> {
-		if self.is_empty() {
+		if self.is_empty() || last_selection.is_empty() {
 			return None;
 		}
 
-		if let Ok(i) = self.tree.items().binary_search_by(|e| {
-			e.info.full_path.as_str().cmp(last_selection)
-		}) {
-			return Some(i);
+		// tree order is not plain string order (see `get_status`),
+		// so no binary search here
+		let indices: HashMap<&str, usize> = self
+			.tree
+			.items()
+			.iter()
+			.enumerate()
+			.map(|(idx, item)| (item.info.full_path.as_str(), idx))
+			.collect();
+
+		if let Some(idx) = last_selection
+			.iter()
+			.find_map(|path| indices.get(path.as_str()))
+		{
+			return Some(*idx);
 		}
 
 		Some(cmp::min(last_index, self.tree.len() - 1))
@@ -514,6 +572,48 @@ mod tests {
 		assert_eq!(res.selection, Some(1));
 	}
 
+	#[test]
+	fn test_keep_selected_item_path_order() {
+		// path order differs from plain string order here
+		let mut res = StatusTree::default();
+		res.update(&string_vec_to_status(&["a/b", "a-b/x"]))
+			.unwrap();
+		res.selection = Some(3);
+		assert_eq!(
+			res.selected_item().unwrap().info.full_path,
+			"a-b/x"
+		);
+
+		res.update(&string_vec_to_status(&["0", "a/b", "a-b/x"]))
+			.unwrap();
+		assert_eq!(
+			res.selected_item().unwrap().info.full_path,
+			"a-b/x"
+		);
+
+		res.update(&string_vec_to_status(&["a-b/x"])).unwrap();
+		assert_eq!(
+			res.selected_item().unwrap().info.full_path,
+			"a-b/x"
+		);
+	}
+
+	#[test]
+	fn test_select_next_neighbor_when_removed() {
+		let mut res = StatusTree::default();
+		res.update(&string_vec_to_status(&["a", "b", "c", "d"]))
+			.unwrap();
+		res.selection = Some(1);
+
+		res.update(&string_vec_to_status(&["c", "d"])).unwrap();
+		assert_eq!(res.selected_item().unwrap().info.full_path, "c");
+
+		// inserting before the selection keeps it
+		res.update(&string_vec_to_status(&["a", "b", "c", "d"]))
+			.unwrap();
+		assert_eq!(res.selection, Some(2));
+	}
+
 	#[test]
 	fn test_keep_selected_index() {
 		let mut res = StatusTree::default();
@@ -930,4 +1030,42 @@ mod tests {
 		assert!(res.move_selection(MoveSelection::Left)); // jump to 0
 		assert_eq!(res.selection, Some(0));
 	}
+
+	#[test]
+	fn test_select_path() {
+		let items = string_vec_to_status(&[
+			"a/b/c", //
+			"a/d",   //
+			"e",     //
+		]);
+
+		//0 a/
+		//1   b/
+		//2     c
+		//3   d
+		//4 e
+
+		let mut res = StatusTree::default();
+		res.update(&items).unwrap();
+
+		res.selection = Some(1);
+		assert!(res.move_selection(MoveSelection::Left)); // fold 1
+		res.selection = Some(0);
+		assert!(res.move_selection(MoveSelection::Left)); // fold 0
+		assert_eq!(
+			get_visible(&res),
+			vec![true, false, false, false, true]
+		);
+
+		assert!(!res.select_path("a/b"));
+		assert!(!res.select_path("x"));
+		assert_eq!(res.selection, Some(0));
+
+		assert!(res.select_path("a/b/c"));
+		assert_eq!(res.selection, Some(2));
+		assert_eq!(get_visible(&res), vec![true; 5]);
+
+		assert!(res.move_selection(MoveSelection::Down));
+		assert_eq!(res.selection, Some(3));
+	}
 }
@@ -0,0 +1,228 @@
+//! highlighting of the words that changed within modified lines
+
+use asyncgit::DiffLineType;
+use std::ops::Range;
+
+/// lines with more tokens are highlighted as a whole, the table to
+/// compare them would get too large
+const MAX_TOKENS: usize = 500;
+
+#[derive(PartialEq, Eq, Clone, Copy)]
+enum Class {
+	Word,
+	Whitespace,
+	Other,
+}
+
+impl Class {
+	fn of(c: char) -> Self {
+		if c.is_alphanumeric() || c == '_' {
+			Self::Word
+		} else if c.is_whitespace() {
+			Self::Whitespace
+		} else {
+			Self::Other
+		}
+	}
+}
+
+/// byte ranges of the words, runs of whitespace and single other
+/// characters `line` consists of
+fn tokens(line: &str) -> Vec<Range<usize>> {
+	let mut res = Vec::new();
+	let mut chars = line.char_indices().peekable();
+
+	while let Some((start, c)) = chars.next() {
+		let class = Class::of(c);
+		let mut end = start + c.len_utf8();
+
+		if class != Class::Other {
+			while let Some((idx, next)) =
+				chars.next_if(|(_, next)| Class::of(*next) == class)
+			{
+				end = idx + next.len_utf8();
+			}
+		}
+
+		res.push(start..end);
+	}
+
+	res
+}
+
+/// appends `range` to `ranges`, merging it with the last one if they touch
+fn push_merged(ranges: &mut Vec<Range<usize>>, range: Range<usize>) {
+	match ranges.last_mut() {
+		Some(last) if last.end == range.start => last.end = range.end,
+		_ => ranges.push(range),
+	}
+}
+
+/// byte ranges of `old` and `new` that are not part of the longest
+/// common sequence of their tokens
+pub fn changed_ranges(
+	old: &str,
+	new: &str,
+) -> (Vec<Range<usize>>, Vec<Range<usize>>) {
+	let old_tokens = tokens(old);
+	let new_tokens = tokens(new);
+
+	if old_tokens.len() > MAX_TOKENS || new_tokens.len() > MAX_TOKENS
+	{
+		let whole =
+			|line: &str| std::iter::once(0..line.len()).collect();
+		return (whole(old), whole(new));
+	}
+
+	let same = |i: usize, j: usize| {
+		old[old_tokens[i].clone()] == new[new_tokens[j].clone()]
+	};
+
+	// `common[i][j]`: length of the common sequence of the tokens
+	// starting at `i` and `j`
+	let width = new_tokens.len() + 1;
+	let mut common = vec![0_u16; (old_tokens.len() + 1) * width];
+	for i in (0..old_tokens.len()).rev() {
+		for j in (0..new_tokens.len()).rev() {
+			common[i * width + j] = if same(i, j) {
+				common[(i + 1) * width + j + 1] + 1
+			} else {
+				common[(i + 1) * width + j]
+					.max(common[i * width + j + 1])
+			};
+		}
+	}
+
+	let mut old_changed = Vec::new();
+	let mut new_changed = Vec::new();
+	let (mut i, mut j) = (0, 0);
+
+	while i < old_tokens.len() && j < new_tokens.len() {
+		if same(i, j) {
+			i += 1;
+			j += 1;
+		} else if common[(i + 1) * width + j]
+			>= common[i * width + j + 1]
+		{
+			push_merged(&mut old_changed, old_tokens[i].clone());
+			i += 1;
+		} else {
+			push_merged(&mut new_changed, new_tokens[j].clone());
+			j += 1;
+		}
+	}
+	for token in &old_tokens[i..] {
+		push_merged(&mut old_changed, token.clone());
+	}
+	for token in &new_tokens[j..] {
+		push_merged(&mut new_changed, token.clone());
+	}
+
+	(old_changed, new_changed)
+}
+
+/// changed ranges of each of `lines`. a run of deleted lines directly
+/// followed by a run of added ones is compared line by line, lines
+/// without a counterpart get no ranges as they changed as a whole.
+pub fn word_diff(
+	lines: &[(DiffLineType, &str)],
+) -> Vec<Vec<Range<usize>>> {
+	let mut res = vec![Vec::new(); lines.len()];
+
+	let run_end = |start: usize, line_type: DiffLineType| {
+		lines[start..]
+			.iter()
+			.position(|(typ, _)| *typ != line_type)
+			.map_or(lines.len(), |len| start + len)
+	};
+
+	let mut idx = 0;
+	while idx < lines.len() {
+		if lines[idx].0 != DiffLineType::Delete {
+			idx += 1;
+			continue;
+		}
+
+		let deleted = idx..run_end(idx, DiffLineType::Delete);
+		let added =
+			deleted.end..run_end(deleted.end, DiffLineType::Add);
+
+		for (old, new) in deleted.clone().zip(added.clone()) {
+			(res[old], res[new]) =
+				changed_ranges(lines[old].1, lines[new].1);
+		}
+
+		idx = added.end.max(deleted.end);
+	}
+
+	res
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use pretty_assertions::assert_eq;
+
+	fn changed<'a>(
+		line: &'a str,
+		ranges: &[Range<usize>],
+	) -> Vec<&'a str> {
+		ranges.iter().map(|range| &line[range.clone()]).collect()
+	}
+
+	#[test]
+	fn test_tokens() {
+		let line = "let föö_1 = x.len();";
+
+		assert_eq!(
+			changed(line, &tokens(line)),
+			vec![
+				"let", " ", "föö_1", " ", "=", " ", "x", ".", "len",
+				"(", ")", ";"
+			]
+		);
+	}
+
+	#[test]
+	fn test_changed_ranges() {
+		let old = "let value = compute(a, b);";
+		let new = "let result = compute(a, c);";
+
+		let (old_changed, new_changed) = changed_ranges(old, new);
+
+		assert_eq!(changed(old, &old_changed), vec!["value", "b"]);
+		assert_eq!(changed(new, &new_changed), vec!["result", "c"]);
+	}
+
+	#[test]
+	fn test_changed_ranges_merged() {
+		let old = "a b";
+		let new = "a x y b";
+
+		let (old_changed, new_changed) = changed_ranges(old, new);
+
+		assert!(old_changed.is_empty());
+		assert_eq!(changed(new, &new_changed), vec!["x y "]);
+	}
+
+	#[test]
+	fn test_word_diff_pairs_runs() {
+		let lines = [
+			(DiffLineType::Header, "@@ -1,3 +1,2 @@"),
+			(DiffLineType::Delete, "one two"),
+			(DiffLineType::Delete, "three"),
+			(DiffLineType::Add, "one 2"),
+			(DiffLineType::None, "context"),
+			(DiffLineType::Add, "added"),
+		];
+
+		let ranges = word_diff(&lines);
+
+		assert_eq!(changed(lines[1].1, &ranges[1]), vec!["two"]);
+		assert_eq!(changed(lines[3].1, &ranges[3]), vec!["2"]);
+		// no counterparts
+		assert!(ranges[2].is_empty());
+		assert!(ranges[5].is_empty());
+		assert!(ranges[0].is_empty() && ranges[4].is_empty());
+	}
+}
@@ -0,0 +1,217 @@
+//! what happens when gitui panics, see [`report_panic`].
+//!
+//! the panic hook runs on whichever thread panicked, so the terminal is
+//! restored even if that was a worker thread (gitui exits afterwards, a
+//! half dead ui in a restored terminal helps nobody).
+
+use crate::storage;
+use anyhow::Result;
+use crossterm::{
+	cursor::Show,
+	event::{DisableBracketedPaste, DisableMouseCapture},
+	terminal::LeaveAlternateScreen,
+	QueueableCommand,
+};
+use std::{
+	fmt::Write as _,
+	io::Write,
+	path::{Path, PathBuf},
+	sync::{Mutex, PoisonError},
+	time::{SystemTime, UNIX_EPOCH},
+};
+
+static COMMIT_DRAFT: Mutex<Option<String>> = Mutex::new(None);
+
+/// remembers the commit message currently being written so that it
+/// survives a panic, an empty message clears it
+pub fn set_commit_draft(msg: &str) {
+	*COMMIT_DRAFT.lock().unwrap_or_else(PoisonError::into_inner) =
+		(!msg.trim().is_empty()).then(|| msg.to_string());
+}
+
+fn commit_draft() -> Option<String> {
+	COMMIT_DRAFT
+		.lock()
+		.unwrap_or_else(PoisonError::into_inner)
+		.clone()
+}
+
+/// undoes everything gitui sets up on `terminal` (apart from raw mode
+/// which is not controlled via escape sequences)
+pub fn restore_terminal<W: Write>(terminal: &mut W) {
+	let restored = terminal
+		.queue(LeaveAlternateScreen)
+		.and_then(|t| t.queue(DisableMouseCapture))
+		.and_then(|t| t.queue(DisableBracketedPaste))
+		.and_then(|t| t.queue(Show))
+		.and_then(Write::flush);
+
+	if let Err(e) = restored {
+		log::error!("restoring terminal failed: {e}");
+	}
+}
+
+/// restores `terminal`, writes `message` and `backtrace` to a crash file
+/// in `crash_dir` (along with the commit draft, if any) and returns what
+/// to tell the user
+pub fn report_panic<W: Write>(
+	terminal: &mut W,
+	crash_dir: Option<&Path>,
+	message: &str,
+	backtrace: &str,
+) -> String {
+	restore_terminal(terminal);
+
+	let written = crash_dir.map(|dir| {
+		write_crash_files(dir, message, backtrace, commit_draft())
+	});
+
+	let mut report = format!(
+		"\nGitUI was closed due to an unexpected panic:\n{message}\n"
+	);
+
+	match written {
+		Some(Ok((crash_file, draft_file))) => {
+			let _ = writeln!(
+				report,
+				"\nPlease file an issue on https://github.com/gitui-org/gitui/issues and attach: {}",
+				crash_file.display()
+			);
+			if let Some(draft_file) = draft_file {
+				let _ = writeln!(
+					report,
+					"your commit message was saved to: {}",
+					draft_file.display()
+				);
+			}
+		}
+		failed => {
+			if let Some(Err(e)) = failed {
+				log::error!("writing crash file failed: {e}");
+			}
+
+			let _ = writeln!(
+				report,
+				"\nPlease file an issue on https://github.com/gitui-org/gitui/issues with the following info:\n\ntrace:\n{backtrace}"
+			);
+			if let Some(draft) = commit_draft() {
+				let _ = writeln!(
+					report,
+					"\nyour commit message was:\n{draft}"
+				);
+			}
+		}
+	}
+
+	report
+}
+
+fn write_crash_files(
+	dir: &Path,
+	message: &str,
+	backtrace: &str,
+	draft: Option<String>,
+) -> Result<(PathBuf, Option<PathBuf>)> {
+	std::fs::create_dir_all(dir)?;
+
+	let stamp = SystemTime::now()
+		.duration_since(UNIX_EPOCH)
+		.unwrap_or_default()
+		.as_secs();
+
+	let crash_file = dir.join(format!("crash-{stamp}.txt"));
+	storage::write_atomic(
+		&crash_file,
+		format!(
+			"gitui {}\n\n{message}\n\ntrace:\n{backtrace}\n",
+			env!("CARGO_PKG_VERSION")
+		)
+		.as_bytes(),
+	)?;
+
+	let draft_file = draft
+		.map(|draft| -> Result<PathBuf> {
+			let path =
+				dir.join(format!("crash-{stamp}-commit-msg.txt"));
+			storage::write_atomic(&path, draft.as_bytes())?;
+			Ok(path)
+		})
+		.transpose()?;
+
+	Ok((crash_file, draft_file))
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::components::DrawableComponent;
+	use ratatui::{
+		backend::TestBackend, layout::Rect, Frame, Terminal,
+	};
+	use std::panic::{self, AssertUnwindSafe};
+
+	struct PanickingComponent;
+
+	impl DrawableComponent for PanickingComponent {
+		fn draw(&self, _f: &mut Frame, _rect: Rect) -> Result<()> {
+			panic!("controlled panic");
+		}
+	}
+
+	#[test]
+	fn test_panic_in_component() {
+		let dir = tempfile::tempdir().unwrap();
+
+		let mut terminal =
+			Terminal::new(TestBackend::new(10, 10)).unwrap();
+		let payload = panic::catch_unwind(AssertUnwindSafe(|| {
+			terminal
+				.draw(|f| {
+					PanickingComponent.draw(f, f.area()).unwrap();
+				})
+				.map(|_| ())
+		}))
+		.unwrap_err();
+		let message = payload.downcast_ref::<&str>().unwrap();
+
+		set_commit_draft("half written message");
+
+		// what would have been written to the real terminal
+		let mut output = Vec::<u8>::new();
+		let report = report_panic(
+			&mut output,
+			Some(dir.path()),
+			message,
+			"some trace",
+		);
+		set_commit_draft("");
+
+		let written = String::from_utf8(output).unwrap();
+		// leave alternate screen, mouse capture and bracketed paste,
+		// show the cursor again
+		for sequence in
+			["\x1b[?1049l", "\x1b[?1000l", "\x1b[?2004l", "\x1b[?25h"]
+		{
+			assert!(
+				written.contains(sequence),
+				"{sequence:?} in {written:?}"
+			);
+		}
+
+		let mut files = std::fs::read_dir(dir.path())
+			.unwrap()
+			.map(|entry| entry.unwrap().path())
+			.collect::<Vec<_>>();
+		files.sort();
+		assert_eq!(files.len(), 2);
+
+		let draft = std::fs::read_to_string(&files[0]).unwrap();
+		let crash = std::fs::read_to_string(&files[1]).unwrap();
+		assert_eq!(draft, "half written message");
+		assert!(crash.contains("controlled panic"));
+		assert!(crash.contains("some trace"));
+
+		assert!(report.contains(&files[1].display().to_string()));
+		assert!(report.contains(&files[0].display().to_string()));
+	}
+}
@@ -0,0 +1,155 @@
+//! coalesces redraws: every processed event only marks the ui dirty,
+//! frames are rendered at most `max_fps` times per second. the last
+//! state is always drawn because a pending frame keeps a wake up timer
+//! armed (see [`FrameScheduler::next_frame_in`]).
+
+use std::{
+	sync::atomic::{AtomicU64, Ordering},
+	time::{Duration, Instant},
+};
+
+pub const DEFAULT_MAX_FPS: u32 = 60;
+
+static NOTIFICATIONS: AtomicU64 = AtomicU64::new(0);
+static FRAMES: AtomicU64 = AtomicU64::new(0);
+
+/// counters shown in the performance popup
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct RenderStats {
+	/// async/watcher notifications received
+	pub notifications: u64,
+	/// frames actually rendered
+	pub frames: u64,
+}
+
+impl RenderStats {
+	/// notifications that did not result in a frame of their own
+	pub const fn coalesced(&self) -> u64 {
+		self.notifications.saturating_sub(self.frames)
+	}
+}
+
+///
+pub fn render_stats() -> RenderStats {
+	RenderStats {
+		notifications: NOTIFICATIONS.load(Ordering::Relaxed),
+		frames: FRAMES.load(Ordering::Relaxed),
+	}
+}
+
+///
+pub fn count_notification() {
+	NOTIFICATIONS.fetch_add(1, Ordering::Relaxed);
+}
+
+///
+pub struct FrameScheduler {
+	min_interval: Duration,
+	last_frame: Option<Instant>,
+	dirty: bool,
+}
+
+impl FrameScheduler {
+	/// `max_fps` of `0` is treated as `1`
+	pub fn new(max_fps: u32) -> Self {
+		Self {
+			min_interval: Duration::from_secs(1) / max_fps.max(1),
+			last_frame: None,
+			dirty: false,
+		}
+	}
+
+	///
+	pub fn mark_dirty(&mut self) {
+		self.dirty = true;
+	}
+
+	/// time until the next frame is due, `None` if nothing changed
+	/// since the last one
+	pub fn next_frame_in(&self, now: Instant) -> Option<Duration> {
+		if !self.dirty {
+			return None;
+		}
+
+		Some(self.last_frame.map_or(Duration::ZERO, |last| {
+			self.min_interval
+				.saturating_sub(now.saturating_duration_since(last))
+		}))
+	}
+
+	///
+	pub fn frame_due(&self, now: Instant) -> bool {
+		self.next_frame_in(now) == Some(Duration::ZERO)
+	}
+
+	///
+	pub fn frame_drawn(&mut self, now: Instant) {
+		self.dirty = false;
+		self.last_frame = Some(now);
+		FRAMES.fetch_add(1, Ordering::Relaxed);
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn test_nothing_to_draw() {
+		let scheduler = FrameScheduler::new(10);
+
+		assert_eq!(scheduler.next_frame_in(Instant::now()), None);
+		assert!(!scheduler.frame_due(Instant::now()));
+	}
+
+	#[test]
+	fn test_first_frame_immediately() {
+		let mut scheduler = FrameScheduler::new(10);
+		scheduler.mark_dirty();
+
+		assert!(scheduler.frame_due(Instant::now()));
+	}
+
+	#[test]
+	fn test_limits_frame_rate() {
+		let mut scheduler = FrameScheduler::new(10);
+		let start = Instant::now();
+
+		scheduler.mark_dirty();
+		scheduler.frame_drawn(start);
+
+		// a burst of notifications right after a frame
+		for _ in 0..100 {
+			scheduler.mark_dirty();
+		}
+
+		let now = start + Duration::from_millis(30);
+		assert!(!scheduler.frame_due(now));
+		// the final state is still drawn once the interval passed
+		assert_eq!(
+			scheduler.next_frame_in(now),
+			Some(Duration::from_millis(70))
+		);
+
+		let now = start + Duration::from_millis(100);
+		assert!(scheduler.frame_due(now));
+
+		scheduler.frame_drawn(now);
+		assert_eq!(scheduler.next_frame_in(now), None);
+	}
+
+	#[test]
+	fn test_zero_fps() {
+		let mut scheduler = FrameScheduler::new(0);
+		let start = Instant::now();
+
+		scheduler.mark_dirty();
+		scheduler.frame_drawn(start);
+		scheduler.mark_dirty();
+
+		assert_eq!(
+			scheduler.next_frame_in(start),
+			Some(Duration::from_secs(1))
+		);
+	}
+}
@@ -52,6 +52,8 @@ pub struct KeysList {
 	pub open_commit_editor: GituiKeyEvent,
 	pub open_help: GituiKeyEvent,
 	pub open_options: GituiKeyEvent,
+	pub open_performance: GituiKeyEvent,
+	pub open_audit_log: GituiKeyEvent,
 	pub move_left: GituiKeyEvent,
 	pub move_right: GituiKeyEvent,
 	pub move_up: GituiKeyEvent,
@@ -73,6 +75,8 @@ pub struct KeysList {
 	pub status_stage_all: GituiKeyEvent,
 	pub status_reset_item: GituiKeyEvent,
 	pub status_ignore_file: GituiKeyEvent,
+	pub status_show_ignored: GituiKeyEvent,
+	pub check_ignore: GituiKeyEvent,
 	pub diff_stage_lines: GituiKeyEvent,
 	pub diff_reset_lines: GituiKeyEvent,
 	pub stashing_save: GituiKeyEvent,
@@ -87,6 +91,8 @@ pub struct KeysList {
 	pub log_checkout_commit: GituiKeyEvent,
 	pub log_reset_commit: GituiKeyEvent,
 	pub log_reword_commit: GituiKeyEvent,
+	pub log_squash_commits: GituiKeyEvent,
+	pub log_drop_commit: GituiKeyEvent,
 	pub log_find: GituiKeyEvent,
 	pub find_commit_sha: GituiKeyEvent,
 	pub commit_amend: GituiKeyEvent,
@@ -109,12 +115,18 @@ pub struct KeysList {
 	pub file_find: GituiKeyEvent,
 	pub branch_find: GituiKeyEvent,
 	pub force_push: GituiKeyEvent,
+	pub push_all_remotes: GituiKeyEvent,
 	pub fetch: GituiKeyEvent,
+	pub fetch_ref: GituiKeyEvent,
 	pub pull: GituiKeyEvent,
 	pub abort_merge: GituiKeyEvent,
+	pub open_shell: GituiKeyEvent,
 	pub undo_commit: GituiKeyEvent,
 	pub diff_hunk_next: GituiKeyEvent,
 	pub diff_hunk_prev: GituiKeyEvent,
+	pub diff_search: GituiKeyEvent,
+	pub diff_search_next: GituiKeyEvent,
+	pub diff_toggle_word_diff: GituiKeyEvent,
 	pub stage_unstage_item: GituiKeyEvent,
 	pub tag_annotate: GituiKeyEvent,
 	pub view_submodules: GituiKeyEvent,
@@ -123,15 +135,52 @@ pub struct KeysList {
 	pub update_remote_url: GituiKeyEvent,
 	pub add_remote: GituiKeyEvent,
 	pub delete_remote: GituiKeyEvent,
+	pub repair_remote_branch: GituiKeyEvent,
+	pub fetch_tags_mode: GituiKeyEvent,
+	pub fetch_tags: GituiKeyEvent,
 	pub view_submodule_parent: GituiKeyEvent,
 	pub update_submodule: GituiKeyEvent,
 	pub commit_history_next: GituiKeyEvent,
 	pub commit: GituiKeyEvent,
 	pub newline: GituiKeyEvent,
+	pub text_char_left: GituiKeyEvent,
+	pub text_char_right: GituiKeyEvent,
+	pub text_line_up: GituiKeyEvent,
+	pub text_line_down: GituiKeyEvent,
+	pub text_line_start: GituiKeyEvent,
+	pub text_line_end: GituiKeyEvent,
+	pub text_word_left: GituiKeyEvent,
+	pub text_word_right: GituiKeyEvent,
+	pub text_kill_line_start: GituiKeyEvent,
+	pub text_kill_line_end: GituiKeyEvent,
+	/// kills up to the previous whitespace like readline's `ctrl-w`
+	pub text_kill_word_left: GituiKeyEvent,
+	pub text_kill_word_right: GituiKeyEvent,
+	pub text_yank: GituiKeyEvent,
+	pub text_yank_pop: GituiKeyEvent,
+	pub text_undo: GituiKeyEvent,
+	pub text_redo: GituiKeyEvent,
+	pub text_viewer_search: GituiKeyEvent,
+	pub text_viewer_next_match: GituiKeyEvent,
+	pub text_viewer_toggle_wrap: GituiKeyEvent,
+	pub select_compare_base: GituiKeyEvent,
+	pub commit_presets: GituiKeyEvent,
+	pub edit_branch_description: GituiKeyEvent,
+	pub checkout_previous_branch: GituiKeyEvent,
+	pub recent_branches: GituiKeyEvent,
+	pub rename_current_branch: GituiKeyEvent,
+	pub move_changes_to_branch: GituiKeyEvent,
+	pub move_changes_carry_commits: GituiKeyEvent,
+	pub diff_orig_head: GituiKeyEvent,
+	pub reset_to_orig_head: GituiKeyEvent,
+	pub macro_record: GituiKeyEvent,
+	pub macro_replay: GituiKeyEvent,
+	pub macro_abort: GituiKeyEvent,
 }
 
 #[rustfmt::skip]
 impl Default for KeysList {
+	#[allow(clippy::too_many_lines)]
 	fn default() -> Self {
 		Self {
 			tab_status: GituiKeyEvent::new(KeyCode::Char('1'), KeyModifiers::empty()),
@@ -149,6 +198,8 @@ impl Default for KeysList {
 			open_commit_editor: GituiKeyEvent::new(KeyCode::Char('e'), KeyModifiers::CONTROL),
 			open_help: GituiKeyEvent::new(KeyCode::Char('h'),  KeyModifiers::empty()),
 			open_options: GituiKeyEvent::new(KeyCode::Char('o'),  KeyModifiers::empty()),
+			open_performance: GituiKeyEvent::new(KeyCode::F(12),  KeyModifiers::empty()),
+			open_audit_log: GituiKeyEvent::new(KeyCode::F(11),  KeyModifiers::empty()),
 			move_left: GituiKeyEvent::new(KeyCode::Left,  KeyModifiers::empty()),
 			move_right: GituiKeyEvent::new(KeyCode::Right,  KeyModifiers::empty()),
 			tree_collapse_recursive: GituiKeyEvent::new(KeyCode::Left,  KeyModifiers::SHIFT),
@@ -171,6 +222,8 @@ impl Default for KeysList {
 			status_reset_item: GituiKeyEvent::new(KeyCode::Char('D'),  KeyModifiers::SHIFT),
 			diff_reset_lines: GituiKeyEvent::new(KeyCode::Char('d'),  KeyModifiers::empty()),
 			status_ignore_file: GituiKeyEvent::new(KeyCode::Char('i'),  KeyModifiers::empty()),
+			status_show_ignored: GituiKeyEvent::new(KeyCode::Char('I'),  KeyModifiers::SHIFT),
+			check_ignore: GituiKeyEvent::new(KeyCode::Char('i'),  KeyModifiers::ALT),
 			diff_stage_lines: GituiKeyEvent::new(KeyCode::Char('s'),  KeyModifiers::empty()),
 			stashing_save: GituiKeyEvent::new(KeyCode::Char('s'),  KeyModifiers::empty()),
 			stashing_toggle_untracked: GituiKeyEvent::new(KeyCode::Char('u'),  KeyModifiers::empty()),
@@ -184,6 +237,8 @@ impl Default for KeysList {
 			log_checkout_commit: GituiKeyEvent { code: KeyCode::Char('S'), modifiers: KeyModifiers::SHIFT },
 			log_reset_commit: GituiKeyEvent { code: KeyCode::Char('R'), modifiers: KeyModifiers::SHIFT },
 			log_reword_commit: GituiKeyEvent { code: KeyCode::Char('r'), modifiers: KeyModifiers::empty() },
+			log_squash_commits: GituiKeyEvent { code: KeyCode::Char('s'), modifiers: KeyModifiers::empty() },
+			log_drop_commit: GituiKeyEvent { code: KeyCode::Char('d'), modifiers: KeyModifiers::empty() },
 			log_find: GituiKeyEvent { code: KeyCode::Char('f'), modifiers: KeyModifiers::empty() },
 			find_commit_sha: GituiKeyEvent::new(KeyCode::Char('j'), KeyModifiers::CONTROL),
 			commit_amend: GituiKeyEvent::new(KeyCode::Char('a'),  KeyModifiers::CONTROL),
@@ -203,15 +258,21 @@ impl Default for KeysList {
 			select_tag: GituiKeyEvent::new(KeyCode::Enter,  KeyModifiers::empty()),
 			push: GituiKeyEvent::new(KeyCode::Char('p'),  KeyModifiers::empty()),
 			force_push: GituiKeyEvent::new(KeyCode::Char('P'),  KeyModifiers::SHIFT),
+			push_all_remotes: GituiKeyEvent::new(KeyCode::Char('p'),  KeyModifiers::ALT),
 			undo_commit: GituiKeyEvent::new(KeyCode::Char('U'),  KeyModifiers::SHIFT),
 			fetch: GituiKeyEvent::new(KeyCode::Char('F'),  KeyModifiers::SHIFT),
+			fetch_ref: GituiKeyEvent::new(KeyCode::Char('f'),  KeyModifiers::ALT),
 			pull: GituiKeyEvent::new(KeyCode::Char('f'),  KeyModifiers::empty()),
 			abort_merge: GituiKeyEvent::new(KeyCode::Char('A'),  KeyModifiers::SHIFT),
+			open_shell: GituiKeyEvent::new(KeyCode::Char('!'),  KeyModifiers::SHIFT),
 			open_file_tree: GituiKeyEvent::new(KeyCode::Char('F'),  KeyModifiers::SHIFT),
 			file_find: GituiKeyEvent::new(KeyCode::Char('f'),  KeyModifiers::empty()),
 			branch_find: GituiKeyEvent::new(KeyCode::Char('f'),  KeyModifiers::empty()),
 			diff_hunk_next: GituiKeyEvent::new(KeyCode::Char('n'),  KeyModifiers::empty()),
 			diff_hunk_prev: GituiKeyEvent::new(KeyCode::Char('p'),  KeyModifiers::empty()),
+			diff_search: GituiKeyEvent::new(KeyCode::Char('/'),  KeyModifiers::empty()),
+			diff_search_next: GituiKeyEvent::new(KeyCode::Char('n'),  KeyModifiers::CONTROL),
+			diff_toggle_word_diff: GituiKeyEvent::new(KeyCode::Char('w'),  KeyModifiers::empty()),
 			stage_unstage_item: GituiKeyEvent::new(KeyCode::Enter,  KeyModifiers::empty()),
 			tag_annotate: GituiKeyEvent::new(KeyCode::Char('a'),  KeyModifiers::CONTROL),
 			view_submodules: GituiKeyEvent::new(KeyCode::Char('S'),  KeyModifiers::SHIFT),
@@ -220,11 +281,46 @@ impl Default for KeysList {
 			update_remote_url: GituiKeyEvent::new(KeyCode::Char('u'),KeyModifiers::NONE),
 			add_remote: GituiKeyEvent::new(KeyCode::Char('a'), KeyModifiers::NONE),
 			delete_remote: GituiKeyEvent::new(KeyCode::Char('r'), KeyModifiers::NONE),
+			repair_remote_branch: GituiKeyEvent::new(KeyCode::Char('x'), KeyModifiers::NONE),
+			fetch_tags_mode: GituiKeyEvent::new(KeyCode::Char('t'), KeyModifiers::NONE),
+			fetch_tags: GituiKeyEvent::new(KeyCode::Char('F'), KeyModifiers::SHIFT),
 			view_submodule_parent: GituiKeyEvent::new(KeyCode::Char('p'),  KeyModifiers::empty()),
 			update_submodule: GituiKeyEvent::new(KeyCode::Char('u'),  KeyModifiers::empty()),
 			commit_history_next: GituiKeyEvent::new(KeyCode::Char('n'),  KeyModifiers::CONTROL),
 			commit: GituiKeyEvent::new(KeyCode::Char('d'),  KeyModifiers::CONTROL),
 			newline: GituiKeyEvent::new(KeyCode::Enter,  KeyModifiers::empty()),
+			text_char_left: GituiKeyEvent::new(KeyCode::Char('b'),  KeyModifiers::CONTROL),
+			text_char_right: GituiKeyEvent::new(KeyCode::Char('f'),  KeyModifiers::CONTROL),
+			text_line_up: GituiKeyEvent::new(KeyCode::Char('p'),  KeyModifiers::CONTROL),
+			text_line_down: GituiKeyEvent::new(KeyCode::Char('n'),  KeyModifiers::CONTROL),
+			text_line_start: GituiKeyEvent::new(KeyCode::Char('a'),  KeyModifiers::CONTROL),
+			text_line_end: GituiKeyEvent::new(KeyCode::Char('e'),  KeyModifiers::CONTROL),
+			text_word_left: GituiKeyEvent::new(KeyCode::Char('b'),  KeyModifiers::ALT),
+			text_word_right: GituiKeyEvent::new(KeyCode::Char('f'),  KeyModifiers::ALT),
+			text_kill_line_start: GituiKeyEvent::new(KeyCode::Char('u'),  KeyModifiers::CONTROL),
+			text_kill_line_end: GituiKeyEvent::new(KeyCode::Char('k'),  KeyModifiers::CONTROL),
+			text_kill_word_left: GituiKeyEvent::new(KeyCode::Char('w'),  KeyModifiers::CONTROL),
+			text_kill_word_right: GituiKeyEvent::new(KeyCode::Char('d'),  KeyModifiers::ALT),
+			text_yank: GituiKeyEvent::new(KeyCode::Char('y'),  KeyModifiers::CONTROL),
+			text_yank_pop: GituiKeyEvent::new(KeyCode::Char('y'),  KeyModifiers::ALT),
+			text_undo: GituiKeyEvent::new(KeyCode::Char('_'),  KeyModifiers::CONTROL),
+			text_redo: GituiKeyEvent::new(KeyCode::Char('r'),  KeyModifiers::CONTROL),
+			text_viewer_search: GituiKeyEvent::new(KeyCode::Char('/'),  KeyModifiers::empty()),
+			text_viewer_next_match: GituiKeyEvent::new(KeyCode::Char('n'),  KeyModifiers::empty()),
+			text_viewer_toggle_wrap: GituiKeyEvent::new(KeyCode::Char('w'),  KeyModifiers::empty()),
+			select_compare_base: GituiKeyEvent::new(KeyCode::Char('b'),  KeyModifiers::CONTROL),
+			commit_presets: GituiKeyEvent::new(KeyCode::Char('t'),  KeyModifiers::CONTROL),
+			edit_branch_description: GituiKeyEvent::new(KeyCode::Char('e'),  KeyModifiers::empty()),
+			checkout_previous_branch: GituiKeyEvent::new(KeyCode::Char('-'),  KeyModifiers::empty()),
+			recent_branches: GituiKeyEvent::new(KeyCode::Char('_'),  KeyModifiers::SHIFT),
+			rename_current_branch: GituiKeyEvent::new(KeyCode::Char('N'),  KeyModifiers::SHIFT),
+			move_changes_to_branch: GituiKeyEvent::new(KeyCode::Char('M'),  KeyModifiers::SHIFT),
+			move_changes_carry_commits: GituiKeyEvent::new(KeyCode::Char('o'),  KeyModifiers::CONTROL),
+			diff_orig_head: GituiKeyEvent::new(KeyCode::Char('O'),  KeyModifiers::SHIFT),
+			reset_to_orig_head: GituiKeyEvent::new(KeyCode::Char('o'),  KeyModifiers::CONTROL),
+			macro_record: GituiKeyEvent::new(KeyCode::Char('Q'),  KeyModifiers::SHIFT),
+			macro_replay: GituiKeyEvent::new(KeyCode::Char('@'),  KeyModifiers::SHIFT),
+			macro_abort: GituiKeyEvent::new(KeyCode::Esc,  KeyModifiers::empty()),
 		}
 	}
 }
@@ -0,0 +1,379 @@
+//! keyboard macros: keys recorded into a named register and replayed
+//! through the normal event dispatch.
+//!
+//! only keys actually pressed are recorded. a replay is fed one key at a
+//! time from the main loop (see [`Macros::next_replayed`]) so that async
+//! work triggered by a key is done before the next one is replayed.
+
+use crate::keys::{key_match, GituiKeyEvent, KeysList};
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use std::collections::VecDeque;
+
+/// replays nested deeper than this (a macro replaying a macro replaying
+/// ...) are aborted, a macro replaying itself would never end otherwise
+pub const MAX_REPLAY_DEPTH: usize = 8;
+
+/// upper bound of the count typed before the register
+pub const MAX_REPLAY_COUNT: usize = 99;
+
+/// a register key is expected next
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Pending {
+	Record,
+	/// `depth` of the replay key, 0 if it was pressed by the user
+	Replay {
+		count: Option<usize>,
+		depth: usize,
+	},
+}
+
+/// what the app has to do after [`Macros::key`]
+#[derive(Debug, PartialEq)]
+pub enum MacroAction {
+	/// not a macro key, dispatch it as usual
+	Dispatch,
+	/// handled by the macros
+	Consumed,
+	/// recording into `register` stopped, the keys are to be stored
+	Recorded {
+		register: char,
+		keys: Vec<GituiKeyEvent>,
+	},
+	/// the macro in `register` is to be replayed `count` times, its keys
+	/// are nested `depth` deep
+	Replay {
+		register: char,
+		count: usize,
+		depth: usize,
+	},
+}
+
+#[derive(Default)]
+pub struct Macros {
+	pending: Option<Pending>,
+	recording: Option<(char, Vec<GituiKeyEvent>)>,
+	/// keys still to be replayed along with their nesting depth
+	replay: VecDeque<(KeyEvent, usize)>,
+	/// register of the outermost replay
+	replaying: Option<char>,
+}
+
+impl Macros {
+	/// register currently recorded into
+	pub fn recording(&self) -> Option<char> {
+		self.recording.as_ref().map(|(register, _)| *register)
+	}
+
+	/// register of the macro currently replayed
+	pub const fn replaying(&self) -> Option<char> {
+		self.replaying
+	}
+
+	/// handles `ev` before it is dispatched, `depth` is `None` for keys
+	/// pressed by the user and the nesting depth for replayed ones.
+	/// only pressed keys are recorded.
+	///
+	/// the record and replay keys are only matched if `macro_keys` is
+	/// set, so they can still be typed into text inputs.
+	pub fn key(
+		&mut self,
+		ev: &KeyEvent,
+		keys: &KeysList,
+		depth: Option<usize>,
+		macro_keys: bool,
+	) -> MacroAction {
+		if macro_keys
+			&& depth.is_none()
+			&& key_match(ev, keys.macro_record)
+		{
+			return self.toggle_recording();
+		}
+
+		if depth.is_none() {
+			if let Some((_, recorded)) = &mut self.recording {
+				recorded
+					.push(GituiKeyEvent::new(ev.code, ev.modifiers));
+			}
+		}
+
+		match self.pending.take() {
+			Some(Pending::Record) => {
+				if let Some(register) = register_of(ev) {
+					self.recording = Some((register, Vec::new()));
+				}
+				MacroAction::Consumed
+			}
+			Some(Pending::Replay { count, depth }) => {
+				if let Some(digit) = digit_of(ev) {
+					let count = count
+						.unwrap_or_default()
+						.saturating_mul(10)
+						.saturating_add(digit)
+						.min(MAX_REPLAY_COUNT);
+					self.pending = Some(Pending::Replay {
+						count: Some(count),
+						depth,
+					});
+					MacroAction::Consumed
+				} else if let Some(register) = register_of(ev) {
+					MacroAction::Replay {
+						register,
+						count: count.unwrap_or(1).max(1),
+						depth: depth + 1,
+					}
+				} else {
+					MacroAction::Consumed
+				}
+			}
+			None if macro_keys
+				&& key_match(ev, keys.macro_replay) =>
+			{
+				self.pending = Some(Pending::Replay {
+					count: None,
+					depth: depth.unwrap_or_default(),
+				});
+				MacroAction::Consumed
+			}
+			None => MacroAction::Dispatch,
+		}
+	}
+
+	fn toggle_recording(&mut self) -> MacroAction {
+		self.pending = None;
+
+		if let Some((register, keys)) = self.recording.take() {
+			MacroAction::Recorded { register, keys }
+		} else {
+			self.pending = Some(Pending::Record);
+			MacroAction::Consumed
+		}
+	}
+
+	/// queues `keys` `count` times in front of the keys still to be
+	/// replayed, as keys nested `depth` deep
+	pub fn replay(
+		&mut self,
+		register: char,
+		keys: &[GituiKeyEvent],
+		count: usize,
+		depth: usize,
+	) {
+		if self.replaying.is_none() {
+			self.replaying = Some(register);
+		}
+
+		for _ in 0..count {
+			for key in keys.iter().rev() {
+				self.replay.push_front((key.into(), depth));
+			}
+		}
+	}
+
+	/// next key to replay along with its nesting depth
+	pub fn next_replayed(&mut self) -> Option<(KeyEvent, usize)> {
+		let next = self.replay.pop_front();
+		if self.replay.is_empty() {
+			self.replaying = None;
+		}
+		next
+	}
+
+	pub fn is_replaying(&self) -> bool {
+		!self.replay.is_empty()
+	}
+
+	/// drops the rest of the replay, e.g. once a replayed key was not
+	/// handled by anything
+	pub fn stop_replay(&mut self) {
+		self.replay.clear();
+		self.replaying = None;
+		// a replay key or count without its register
+		self.pending = None;
+	}
+}
+
+fn register_of(ev: &KeyEvent) -> Option<char> {
+	match ev.code {
+		KeyCode::Char(c)
+			if c.is_ascii_alphabetic()
+				&& (ev.modifiers.is_empty()
+					|| ev.modifiers == KeyModifiers::SHIFT) =>
+		{
+			Some(c)
+		}
+		_ => None,
+	}
+}
+
+fn digit_of(ev: &KeyEvent) -> Option<usize> {
+	match ev.code {
+		KeyCode::Char(c) if ev.modifiers.is_empty() => c
+			.to_digit(10)
+			.and_then(|digit| usize::try_from(digit).ok()),
+		_ => None,
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use pretty_assertions::assert_eq;
+
+	fn key(c: char) -> KeyEvent {
+		let modifiers = if c.is_ascii_uppercase() || c == '@' {
+			KeyModifiers::SHIFT
+		} else {
+			KeyModifiers::empty()
+		};
+		KeyEvent::new(KeyCode::Char(c), modifiers)
+	}
+
+	fn press(macros: &mut Macros, keys: &str) -> Vec<MacroAction> {
+		let config = KeysList::default();
+		keys.chars()
+			.map(|c| macros.key(&key(c), &config, None, true))
+			.collect()
+	}
+
+	#[test]
+	fn test_record() {
+		let mut macros = Macros::default();
+
+		press(&mut macros, "Qa");
+		assert_eq!(macros.recording(), Some('a'));
+
+		let actions = press(&mut macros, "jsQ");
+
+		assert_eq!(
+			actions,
+			vec![
+				MacroAction::Dispatch,
+				MacroAction::Dispatch,
+				MacroAction::Recorded {
+					register: 'a',
+					keys: vec![
+						GituiKeyEvent::new(
+							KeyCode::Char('j'),
+							KeyModifiers::empty()
+						),
+						GituiKeyEvent::new(
+							KeyCode::Char('s'),
+							KeyModifiers::empty()
+						),
+					],
+				},
+			]
+		);
+		assert_eq!(macros.recording(), None);
+	}
+
+	#[test]
+	fn test_replay_count() {
+		let mut macros = Macros::default();
+
+		let actions = press(&mut macros, "@12b");
+
+		assert_eq!(
+			actions.last(),
+			Some(&MacroAction::Replay {
+				register: 'b',
+				count: 12,
+				depth: 1,
+			})
+		);
+
+		let actions = press(&mut macros, "@999b");
+		assert_eq!(
+			actions.last(),
+			Some(&MacroAction::Replay {
+				register: 'b',
+				count: MAX_REPLAY_COUNT,
+				depth: 1,
+			})
+		);
+	}
+
+	#[test]
+	fn test_replay_queue() {
+		let mut macros = Macros::default();
+		let keys = [
+			GituiKeyEvent::new(
+				KeyCode::Char('j'),
+				KeyModifiers::empty(),
+			),
+			GituiKeyEvent::new(
+				KeyCode::Char('s'),
+				KeyModifiers::empty(),
+			),
+		];
+
+		macros.replay('a', &keys, 2, 1);
+		assert_eq!(macros.replaying(), Some('a'));
+
+		let mut replayed = Vec::new();
+		while let Some((ev, depth)) = macros.next_replayed() {
+			assert_eq!(depth, 1);
+			replayed.push(ev.code);
+		}
+
+		assert_eq!(
+			replayed,
+			vec![
+				KeyCode::Char('j'),
+				KeyCode::Char('s'),
+				KeyCode::Char('j'),
+				KeyCode::Char('s')
+			]
+		);
+		assert!(!macros.is_replaying());
+		assert_eq!(macros.replaying(), None);
+	}
+
+	#[test]
+	fn test_macro_keys_disabled() {
+		let mut macros = Macros::default();
+		let config = KeysList::default();
+
+		// e.g. typed into the commit message
+		assert_eq!(
+			macros.key(&key('@'), &config, None, false),
+			MacroAction::Dispatch
+		);
+		assert_eq!(
+			macros.key(&key('Q'), &config, None, false),
+			MacroAction::Dispatch
+		);
+		assert_eq!(macros.recording(), None);
+	}
+
+	#[test]
+	fn test_nested_replay_depth() {
+		let mut macros = Macros::default();
+		let config = KeysList::default();
+
+		// a replayed replay key nests one level deeper
+		assert_eq!(
+			macros.key(&key('@'), &config, Some(3), true),
+			MacroAction::Consumed
+		);
+		assert_eq!(
+			macros.key(&key('a'), &config, Some(3), true),
+			MacroAction::Replay {
+				register: 'a',
+				count: 1,
+				depth: 4,
+			}
+		);
+
+		// replayed keys are never recorded
+		press(&mut macros, "Qa");
+		macros.key(&key('j'), &config, Some(1), true);
+		assert_eq!(
+			press(&mut macros, "Q"),
+			vec![MacroAction::Recorded {
+				register: 'a',
+				keys: Vec::new()
+			}]
+		);
+	}
+}
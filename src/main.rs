@@ -28,37 +28,51 @@
 
 mod app;
 mod args;
+mod audit;
 mod bug_report;
 mod clipboard;
 mod cmdbar;
+mod commit_presets;
 mod components;
+mod crash;
+mod frame_scheduler;
 mod input;
 mod keys;
+mod macros;
 mod notify_mutex;
 mod options;
 mod popup_stack;
 mod popups;
 mod queue;
 mod spinner;
+mod storage;
 mod string_utils;
 mod strings;
 mod tabs;
 mod ui;
 mod watcher;
 
-use crate::{app::App, args::process_cmdline};
+use crate::{
+	app::App,
+	args::{get_app_cache_path, process_cmdline},
+	frame_scheduler::FrameScheduler,
+};
 use anyhow::{anyhow, bail, Result};
 use app::QuitState;
 use asyncgit::{
-	sync::{utils::repo_work_dir, RepoPath},
+	sync::{
+		set_commit_cache_capacity, utils::repo_work_dir, RepoPath,
+	},
 	AsyncGitNotification,
 };
 use backtrace::Backtrace;
-use crossbeam_channel::{never, tick, unbounded, Receiver, Select};
+use crossbeam_channel::{
+	after, never, tick, unbounded, Receiver, Select,
+};
 use crossterm::{
+	event::EnableBracketedPaste,
 	terminal::{
 		disable_raw_mode, enable_raw_mode, EnterAlternateScreen,
-		LeaveAlternateScreen,
 	},
 	ExecutableCommand,
 };
@@ -73,6 +87,7 @@ use std::{
 	io::{self, Stdout},
 	panic,
 	path::Path,
+	sync::atomic::{AtomicBool, Ordering},
 	time::{Duration, Instant},
 };
 use ui::style::Theme;
@@ -86,11 +101,15 @@ static SPINNER_INTERVAL: Duration = Duration::from_millis(80);
 ///
 #[derive(Clone)]
 pub enum QueueEvent {
+	/// work the app scheduled is due, see `App::next_tick_in`
 	Tick,
 	Notify,
 	SpinnerUpdate,
+	Redraw,
 	AsyncEvent(AsyncNotification),
 	InputEvent(InputEvent),
+	/// the next key of a macro replay is due
+	MacroReplay,
 }
 
 #[derive(Clone, Copy, Debug, PartialEq, Eq)]
@@ -135,6 +154,10 @@ fn main() -> Result<()> {
 	asyncgit::register_tracing_logging();
 	ensure_valid_path(&cliargs.repo_path)?;
 
+	if let Some(capacity) = cliargs.commit_cache_size {
+		set_commit_cache_capacity(capacity);
+	}
+
 	let key_config = KeyConfig::init()
 		.map_err(|e| log_eprintln!("KeyConfig loading error: {e}"))
 		.unwrap_or_default();
@@ -146,6 +169,7 @@ fn main() -> Result<()> {
 	}
 
 	set_panic_handler()?;
+	audit::init();
 
 	let mut repo_path = cliargs.repo_path;
 	let mut terminal = start_terminal(io::stdout(), &repo_path)?;
@@ -165,6 +189,7 @@ fn main() -> Result<()> {
 			key_config.clone(),
 			&input,
 			updater,
+			cliargs.max_fps,
 			&mut terminal,
 		)?;
 
@@ -179,6 +204,7 @@ fn main() -> Result<()> {
 	Ok(())
 }
 
+#[allow(clippy::too_many_arguments)]
 fn run_app(
 	app_start: Instant,
 	repo: RepoPath,
@@ -186,6 +212,7 @@ fn run_app(
 	key_config: KeyConfig,
 	input: &Input,
 	updater: Updater,
+	max_fps: u32,
 	terminal: &mut Terminal,
 ) -> Result<QuitState, anyhow::Error> {
 	let (tx_git, rx_git) = unbounded();
@@ -215,6 +242,7 @@ fn run_app(
 	)?;
 
 	let mut spinner = Spinner::default();
+	let mut scheduler = FrameScheduler::new(max_fps);
 	let mut first_update = true;
 
 	log::trace!("app start: {} ms", app_start.elapsed().as_millis());
@@ -223,7 +251,12 @@ fn run_app(
 		let event = if first_update {
 			first_update = false;
 			QueueEvent::Notify
+		} else if let Some(event) = next_macro_event(&app, &rx_input)
+		{
+			event
 		} else {
+			let now = Instant::now();
+
 			select_event(
 				&rx_input,
 				&rx_git,
@@ -231,6 +264,8 @@ fn run_app(
 				&rx_ticker,
 				&rx_watcher,
 				&spinner_ticker,
+				scheduler.next_frame_in(now),
+				app.next_tick_in(now),
 			)?
 		};
 
@@ -253,11 +288,19 @@ fn run_app(
 						terminal.hide_cursor()?;
 					}
 					app.event(ev)?;
+					scheduler.mark_dirty();
 				}
-				QueueEvent::Tick | QueueEvent::Notify => {
+				QueueEvent::Notify => {
+					frame_scheduler::count_notification();
 					app.update()?;
+					scheduler.mark_dirty();
+				}
+				QueueEvent::Tick => {
+					app.tick();
+					scheduler.mark_dirty();
 				}
 				QueueEvent::AsyncEvent(ev) => {
+					frame_scheduler::count_notification();
 					if !matches!(
 						ev,
 						AsyncNotification::Git(
@@ -265,12 +308,21 @@ fn run_app(
 						)
 					) {
 						app.update_async(ev)?;
+						scheduler.mark_dirty();
 					}
 				}
+				QueueEvent::MacroReplay => {
+					app.replay_macro_step()?;
+					scheduler.mark_dirty();
+				}
+				QueueEvent::Redraw => (),
 				QueueEvent::SpinnerUpdate => unreachable!(),
 			}
 
-			draw(terminal, &app)?;
+			if scheduler.frame_due(Instant::now()) {
+				draw(terminal, &app)?;
+				scheduler.frame_drawn(Instant::now());
+			}
 
 			spinner.set_state(app.any_work_pending());
 			spinner.draw(terminal)?;
@@ -287,16 +339,17 @@ fn run_app(
 fn setup_terminal() -> Result<()> {
 	enable_raw_mode()?;
 	io::stdout().execute(EnterAlternateScreen)?;
+
+	// pastes arrive as one event instead of lots of key presses
+	if let Err(e) = io::stdout().execute(EnableBracketedPaste) {
+		log::warn!("bracketed paste not supported: {e}");
+	}
+
 	Ok(())
 }
 
 fn shutdown_terminal() {
-	let leave_screen =
-		io::stdout().execute(LeaveAlternateScreen).map(|_f| ());
-
-	if let Err(e) = leave_screen {
-		log::error!("leave_screen failed:\n{e}");
-	}
+	crash::restore_terminal(&mut io::stdout());
 
 	let leave_raw_mode = disable_raw_mode();
 
@@ -329,6 +382,25 @@ fn ensure_valid_path(repo_path: &RepoPath) -> Result<()> {
 	}
 }
 
+/// while a macro is replayed its keys are fed one by one, each once the
+/// async work triggered by the previous one is done. keys pressed in the
+/// meantime go first so the replay can be aborted.
+fn next_macro_event(
+	app: &App,
+	rx_input: &Receiver<InputEvent>,
+) -> Option<QueueEvent> {
+	if !app.is_replaying_macro() || app.any_work_pending() {
+		return None;
+	}
+
+	Some(
+		rx_input
+			.try_recv()
+			.map_or(QueueEvent::MacroReplay, QueueEvent::InputEvent),
+	)
+}
+
+#[allow(clippy::too_many_arguments)]
 fn select_event(
 	rx_input: &Receiver<InputEvent>,
 	rx_git: &Receiver<AsyncGitNotification>,
@@ -336,7 +408,12 @@ fn select_event(
 	rx_ticker: &Receiver<Instant>,
 	rx_notify: &Receiver<()>,
 	rx_spinner: &Receiver<Instant>,
+	frame_in: Option<Duration>,
+	tick_in: Option<Duration>,
 ) -> Result<QueueEvent> {
+	let rx_frame = frame_in.map_or_else(never, after);
+	let rx_tick = tick_in.map_or_else(never, after);
+
 	let mut sel = Select::new();
 
 	sel.recv(rx_input);
@@ -345,6 +422,8 @@ fn select_event(
 	sel.recv(rx_ticker);
 	sel.recv(rx_notify);
 	sel.recv(rx_spinner);
+	sel.recv(&rx_frame);
+	sel.recv(&rx_tick);
 
 	let oper = sel.select();
 	let index = oper.index();
@@ -360,6 +439,8 @@ fn select_event(
 		3 => oper.recv(rx_ticker).map(|_| QueueEvent::Notify),
 		4 => oper.recv(rx_notify).map(|()| QueueEvent::Notify),
 		5 => oper.recv(rx_spinner).map(|_| QueueEvent::SpinnerUpdate),
+		6 => oper.recv(&rx_frame).map(|_| QueueEvent::Redraw),
+		7 => oper.recv(&rx_tick).map(|_| QueueEvent::Tick),
 		_ => bail!("unknown select source"),
 	}?;
 
@@ -396,9 +477,26 @@ fn start_terminal(
 
 fn set_panic_handler() -> Result<()> {
 	panic::set_hook(Box::new(|e| {
+		static PANICKED: AtomicBool = AtomicBool::new(false);
+
+		// e.g. another thread panicking while the first one is reported
+		if PANICKED.swap(true, Ordering::SeqCst) {
+			return;
+		}
+
 		let backtrace = Backtrace::new();
+		let report = crash::report_panic(
+			&mut io::stdout(),
+			get_app_cache_path().ok().as_deref(),
+			&e.to_string(),
+			&format!("{backtrace:?}"),
+		);
 		shutdown_terminal();
-		log_eprintln!("\nGitUI was closed due to an unexpected panic.\nPlease file an issue on https://github.com/gitui-org/gitui/issues with the following info:\n\n{e}\n\ntrace:\n{backtrace:?}");
+		log_eprintln!("{report}");
+
+		// panics of worker threads would leave the app running in the
+		// restored terminal
+		std::process::exit(101);
 	}));
 
 	// global threadpool
@@ -0,0 +1,318 @@
+mod versioned;
+
+use crate::{
+	keys::GituiKeyEvent,
+	queue::{InternalEvent, Queue},
+	storage::{self, Stored},
+	strings,
+};
+use anyhow::Result;
+use asyncgit::sync::{
+	diff::DiffOptions, repo_dir, FetchTags, RepoPathRef,
+	ShowUntrackedFilesConfig,
+};
+use serde::{Deserialize, Serialize};
+use std::{
+	cell::RefCell, collections::BTreeMap, path::PathBuf, rc::Rc,
+	time::Duration,
+};
+use versioned::{Fields, CURRENT_VERSION};
+
+/// missing fields are defaulted (see [`versioned::split_known`])
+#[derive(Default, Clone, Serialize, Deserialize)]
+#[serde(default)]
+struct OptionsData {
+	pub tab: usize,
+	pub diff: DiffOptions,
+	pub diff_word_diff: bool,
+	pub status_show_untracked: Option<ShowUntrackedFilesConfig>,
+	pub commit_msgs: Vec<String>,
+	pub hook_timeout: Option<Duration>,
+	pub hook_commit_msg_preview: bool,
+	pub compare_base: Option<String>,
+	pub default_branch: Option<String>,
+	pub commit_line_numbers: bool,
+	pub macros: BTreeMap<char, Vec<GituiKeyEvent>>,
+	pub fetch_tags: BTreeMap<String, FetchTags>,
+}
+
+const COMMIT_MSG_HISTORY_LENGTH: usize = 20;
+
+#[derive(Clone)]
+pub struct Options {
+	repo: RepoPathRef,
+	data: OptionsData,
+	/// fields written by a newer version of gitui, kept as they are
+	unknown: Fields,
+	version: u32,
+}
+
+#[cfg(test)]
+impl Options {
+	pub fn test_env() -> Self {
+		use asyncgit::sync::RepoPath;
+		Self {
+			repo: RefCell::new(RepoPath::Path(Default::default())),
+			data: Default::default(),
+			unknown: Fields::new(),
+			version: CURRENT_VERSION,
+		}
+	}
+}
+
+pub type SharedOptions = Rc<RefCell<Options>>;
+
+impl Options {
+	/// a corrupt options file is reported via `queue`
+	pub fn new(repo: RepoPathRef, queue: &Queue) -> SharedOptions {
+		let mut options = Self {
+			repo,
+			data: OptionsData::default(),
+			unknown: Fields::new(),
+			version: CURRENT_VERSION,
+		};
+
+		match options.read() {
+			Ok(Some(moved_to)) => {
+				queue.push(InternalEvent::ShowInfoMsg(
+					strings::options_corrupt(&moved_to),
+				));
+			}
+			Ok(None) => (),
+			Err(e) => log::error!("options read error: {}", e),
+		}
+
+		Rc::new(RefCell::new(options))
+	}
+
+	pub fn set_current_tab(&mut self, tab: usize) {
+		self.data.tab = tab;
+		self.save();
+	}
+
+	pub const fn current_tab(&self) -> usize {
+		self.data.tab
+	}
+
+	pub const fn diff_options(&self) -> DiffOptions {
+		self.data.diff
+	}
+
+	/// whether diffs highlight the changed words of modified lines
+	pub const fn diff_word_diff(&self) -> bool {
+		self.data.diff_word_diff
+	}
+
+	#[allow(unused)]
+	pub const fn hook_timeout(&self) -> Option<Duration> {
+		self.data.hook_timeout
+	}
+
+	pub const fn hook_commit_msg_preview(&self) -> bool {
+		self.data.hook_commit_msg_preview
+	}
+
+	/// whether the commit message editor shows line numbers
+	pub const fn commit_line_numbers(&self) -> bool {
+		self.data.commit_line_numbers
+	}
+
+	/// branch to compare the current branch to,
+	/// `None` uses [`Self::default_branch`]
+	pub fn compare_base(&self) -> Option<String> {
+		self.data.compare_base.clone()
+	}
+
+	/// manual override of the default branch of this repo,
+	/// `None` auto-detects it (see `sync::default_branch`)
+	pub fn default_branch(&self) -> Option<String> {
+		self.data.default_branch.clone()
+	}
+
+	pub fn set_compare_base(&mut self, base: Option<String>) {
+		self.data.compare_base = base;
+		self.save();
+	}
+
+	pub const fn status_show_untracked(
+		&self,
+	) -> Option<ShowUntrackedFilesConfig> {
+		self.data.status_show_untracked
+	}
+
+	pub fn set_status_show_untracked(
+		&mut self,
+		value: Option<ShowUntrackedFilesConfig>,
+	) {
+		self.data.status_show_untracked = value;
+		self.save();
+	}
+
+	pub fn diff_context_change(&mut self, increase: bool) {
+		self.data.diff.context = if increase {
+			self.data.diff.context.saturating_add(1)
+		} else {
+			self.data.diff.context.saturating_sub(1)
+		};
+
+		self.save();
+	}
+
+	pub fn diff_hunk_lines_change(&mut self, increase: bool) {
+		self.data.diff.interhunk_lines = if increase {
+			self.data.diff.interhunk_lines.saturating_add(1)
+		} else {
+			self.data.diff.interhunk_lines.saturating_sub(1)
+		};
+
+		self.save();
+	}
+
+	pub fn diff_toggle_whitespace(&mut self) {
+		self.data.diff.ignore_whitespace =
+			!self.data.diff.ignore_whitespace;
+
+		self.save();
+	}
+
+	pub fn diff_toggle_word_diff(&mut self) {
+		self.data.diff_word_diff = !self.data.diff_word_diff;
+
+		self.save();
+	}
+
+	pub fn add_commit_msg(&mut self, msg: &str) {
+		self.data.commit_msgs.push(msg.to_owned());
+		while self.data.commit_msgs.len() > COMMIT_MSG_HISTORY_LENGTH
+		{
+			self.data.commit_msgs.remove(0);
+		}
+		self.save();
+	}
+
+	pub fn has_commit_msg_history(&self) -> bool {
+		!self.data.commit_msgs.is_empty()
+	}
+
+	pub fn commit_msg(&self, idx: usize) -> Option<String> {
+		if self.data.commit_msgs.is_empty() {
+			None
+		} else {
+			let entries = self.data.commit_msgs.len();
+			let mut index = idx;
+
+			while index >= entries {
+				index -= entries;
+			}
+
+			index = entries.saturating_sub(1) - index;
+
+			Some(self.data.commit_msgs[index].clone())
+		}
+	}
+
+	#[allow(unused)]
+	pub fn set_hook_timeout(&mut self, timeout: Option<Duration>) {
+		self.data.hook_timeout = timeout;
+		self.save();
+	}
+
+	pub fn toggle_hook_commit_msg_preview(&mut self) {
+		self.data.hook_commit_msg_preview =
+			!self.data.hook_commit_msg_preview;
+		self.save();
+	}
+
+	pub fn toggle_commit_line_numbers(&mut self) {
+		self.data.commit_line_numbers =
+			!self.data.commit_line_numbers;
+		self.save();
+	}
+
+	/// keys of the macro recorded in `register`
+	pub fn macro_keys(
+		&self,
+		register: char,
+	) -> Option<&[GituiKeyEvent]> {
+		self.data.macros.get(&register).map(Vec::as_slice)
+	}
+
+	/// an empty recording removes the macro
+	pub fn set_macro(
+		&mut self,
+		register: char,
+		keys: Vec<GituiKeyEvent>,
+	) {
+		if keys.is_empty() {
+			self.data.macros.remove(&register);
+		} else {
+			self.data.macros.insert(register, keys);
+		}
+		self.save();
+	}
+
+	/// the tags fetched from `remote` if overridden here, otherwise
+	/// `remote.<name>.tagOpt` applies
+	pub fn fetch_tags(&self, remote: &str) -> Option<FetchTags> {
+		self.data.fetch_tags.get(remote).copied()
+	}
+
+	/// every override of [`Self::fetch_tags`] by remote name
+	pub fn fetch_tags_overrides(
+		&self,
+	) -> BTreeMap<String, FetchTags> {
+		self.data.fetch_tags.clone()
+	}
+
+	pub fn set_fetch_tags(&mut self, remote: &str, tags: FetchTags) {
+		self.data.fetch_tags.insert(remote.to_string(), tags);
+		self.save();
+	}
+
+	fn save(&self) {
+		if let Err(e) = self.save_failable() {
+			log::error!("options save error: {}", e);
+		}
+	}
+
+	/// returns where the file was moved to if it was corrupt
+	fn read(&mut self) -> Result<Option<PathBuf>> {
+		let path = Self::options_file(&self.repo)?;
+
+		let file = match versioned::read(&path)? {
+			Stored::Missing => return Ok(None),
+			Stored::Corrupt { moved_to } => {
+				return Ok(Some(moved_to))
+			}
+			Stored::Loaded(file) => file,
+		};
+
+		let (data, unknown) = versioned::split_known(file.fields)?;
+
+		self.data = data;
+		self.unknown = unknown;
+		self.version = file.version;
+
+		Ok(None)
+	}
+
+	fn save_failable(&self) -> Result<()> {
+		let path = Self::options_file(&self.repo)?;
+
+		let mut fields = self.unknown.clone();
+		fields.extend(versioned::fields_of(&self.data)?);
+
+		storage::write_atomic(
+			&path,
+			versioned::write(self.version, &fields)?.as_bytes(),
+		)?;
+
+		Ok(())
+	}
+
+	fn options_file(repo: &RepoPathRef) -> Result<PathBuf> {
+		let dir = repo_dir(&repo.borrow())?;
+		let dir = dir.join("gitui");
+		Ok(dir)
+	}
+}
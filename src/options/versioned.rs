@@ -0,0 +1,395 @@
+//! on disk format of the options file.
+//!
+//! the file is a RON struct with a `version` field, files written before
+//! it was introduced are version 0. older files are upgraded one version
+//! at a time by [`MIGRATIONS`] (after a backup of the file was made).
+//! fields this version of gitui does not know (written by a newer one)
+//! are kept as raw RON and written back unchanged.
+
+use crate::storage::{self, Stored};
+use anyhow::{anyhow, Result};
+use asyncgit::sync::diff::DiffOptions;
+use ron::{
+	ser::{to_string_pretty, PrettyConfig},
+	value::RawValue,
+};
+use serde::{
+	de::{DeserializeOwned, MapAccess, Visitor},
+	Deserialize, Deserializer, Serialize,
+};
+use std::{
+	collections::BTreeMap,
+	fmt::{self, Write},
+	fs, iter,
+	path::Path,
+};
+
+/// version of the files written by this version of gitui
+pub const CURRENT_VERSION: u32 = 1;
+
+const VERSION_FIELD: &str = "version";
+const INDENT: &str = "    ";
+
+/// top level fields of the file by name, sorted so that writing is
+/// deterministic
+pub type Fields = BTreeMap<String, Box<RawValue>>;
+
+struct Migration {
+	/// upgrades a file of this version to the next one
+	from: u32,
+	migrate: fn(&mut Fields) -> Result<()>,
+}
+
+const MIGRATIONS: &[Migration] = &[Migration {
+	from: 0,
+	migrate: migrate_v0,
+}];
+
+/// options file after all migrations were applied
+pub struct OptionsFile {
+	/// never lower than [`CURRENT_VERSION`]
+	pub version: u32,
+	pub fields: Fields,
+	/// version the file had on disk if it had to be migrated
+	pub migrated_from: Option<u32>,
+}
+
+/// reads and migrates the file at `path` (see [`storage::load`]). a
+/// migrated file is written back right away, the original is kept as
+/// `<path>.v<version>.bak`
+pub fn read(path: &Path) -> Result<Stored<OptionsFile>> {
+	let stored = storage::load(path, parse)?;
+
+	if let Stored::Loaded(file) = &stored {
+		if let Some(version) = file.migrated_from {
+			fs::copy(
+				path,
+				storage::sibling(path, &format!(".v{version}.bak")),
+			)?;
+			storage::write_atomic(
+				path,
+				write(file.version, &file.fields)?.as_bytes(),
+			)?;
+		}
+	}
+
+	Ok(stored)
+}
+
+/// parses `data` and applies all migrations needed
+pub fn parse(data: &[u8]) -> Result<OptionsFile> {
+	let RawFields(mut fields) = ron::de::from_bytes(data)?;
+
+	let version = fields
+		.remove(VERSION_FIELD)
+		.map(|version| version.into_rust::<u32>())
+		.transpose()?
+		.unwrap_or_default();
+
+	let mut current = version;
+	while current < CURRENT_VERSION {
+		let migration = MIGRATIONS
+			.iter()
+			.find(|migration| migration.from == current)
+			.ok_or_else(|| {
+				anyhow!("no migration of options version {current}")
+			})?;
+
+		(migration.migrate)(&mut fields)?;
+		current += 1;
+	}
+
+	Ok(OptionsFile {
+		version: current,
+		fields,
+		migrated_from: (version < current).then_some(version),
+	})
+}
+
+/// file content with `version` first followed by `fields`
+pub fn write(version: u32, fields: &Fields) -> Result<String> {
+	let version = RawValue::from_rust(&version)?;
+
+	Ok(to_ron(iter::once((VERSION_FIELD, &*version)).chain(
+		fields.iter().map(|(name, value)| (name.as_str(), &**value)),
+	)))
+}
+
+/// top level fields of `value` as they are written to the file
+pub fn fields_of<T: Serialize>(value: &T) -> Result<Fields> {
+	let RawFields(fields) = ron::from_str(&to_string_pretty(
+		value,
+		PrettyConfig::default(),
+	)?)?;
+	Ok(fields)
+}
+
+/// splits `fields` into a `T` and the fields `T` does not know.
+///
+/// fields that do not parse (anymore) are dropped one by one instead of
+/// resetting everything, `T` has to be `#[serde(default)]`.
+pub fn split_known<T>(fields: Fields) -> Result<(T, Fields)>
+where
+	T: Serialize + DeserializeOwned + Default,
+{
+	let known = fields_of(&T::default())?;
+
+	let (known, unknown): (Fields, Fields) = fields
+		.into_iter()
+		.partition(|(name, _)| known.contains_key(name));
+
+	let valid: Fields = known
+		.into_iter()
+		.filter(|(name, value)| {
+			let valid = from_fields::<T>(iter::once((
+				name.as_str(),
+				&**value,
+			)))
+			.is_ok();
+
+			if !valid {
+				log::warn!(
+					"options: dropping invalid field '{name}'"
+				);
+			}
+
+			valid
+		})
+		.collect();
+
+	let value = from_fields(
+		valid.iter().map(|(name, value)| (name.as_str(), &**value)),
+	)?;
+
+	Ok((value, unknown))
+}
+
+fn from_fields<'a, T: DeserializeOwned>(
+	fields: impl Iterator<Item = (&'a str, &'a RawValue)>,
+) -> Result<T> {
+	Ok(ron::from_str(&to_ron(fields))?)
+}
+
+fn to_ron<'a>(
+	fields: impl Iterator<Item = (&'a str, &'a RawValue)>,
+) -> String {
+	let mut ron = String::from("(\n");
+
+	for (name, value) in fields {
+		// nested values keep the indentation they were read with
+		let _ = writeln!(
+			ron,
+			"{INDENT}{name}: {},",
+			value.trim().get_ron()
+		);
+	}
+
+	ron.push_str(")\n");
+	ron
+}
+
+/// version 0 files were written before the file got a version,
+/// old versions of gitui saved `diff` without `interhunk_lines`
+fn migrate_v0(fields: &mut Fields) -> Result<()> {
+	#[derive(Deserialize)]
+	struct DiffV0 {
+		ignore_whitespace: bool,
+		context: u32,
+		#[serde(default)]
+		interhunk_lines: u32,
+	}
+
+	if let Some(diff) = fields.get_mut("diff") {
+		if let Ok(old) = diff.into_rust::<DiffV0>() {
+			*diff = RawValue::from_rust(&DiffOptions {
+				ignore_whitespace: old.ignore_whitespace,
+				context: old.context,
+				interhunk_lines: old.interhunk_lines,
+			})?;
+		}
+	}
+
+	Ok(())
+}
+
+/// a RON struct read field by field without knowing its fields
+struct RawFields(Fields);
+
+impl<'de> Deserialize<'de> for RawFields {
+	fn deserialize<D: Deserializer<'de>>(
+		deserializer: D,
+	) -> Result<Self, D::Error> {
+		struct FieldsVisitor;
+
+		impl<'de> Visitor<'de> for FieldsVisitor {
+			type Value = RawFields;
+
+			fn expecting(
+				&self,
+				formatter: &mut fmt::Formatter,
+			) -> fmt::Result {
+				formatter.write_str("options struct")
+			}
+
+			fn visit_map<A: MapAccess<'de>>(
+				self,
+				mut map: A,
+			) -> Result<Self::Value, A::Error> {
+				let mut fields = Fields::new();
+				while let Some((name, value)) = map.next_entry()? {
+					fields.insert(name, value);
+				}
+				Ok(RawFields(fields))
+			}
+		}
+
+		deserializer.deserialize_struct("", &[], FieldsVisitor)
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::options::OptionsData;
+	use asyncgit::sync::ShowUntrackedFilesConfig;
+	use pretty_assertions::assert_eq;
+	use std::time::Duration;
+
+	fn load(data: &str) -> (OptionsData, Fields, OptionsFile) {
+		let file = parse(data.as_bytes()).unwrap();
+		let (options, unknown) =
+			split_known(file.fields.clone()).unwrap();
+		(options, unknown, file)
+	}
+
+	#[test]
+	fn test_v0_without_interhunk_lines() {
+		let (options, unknown, file) =
+			load(include_str!("fixtures/v0_diff.ron"));
+
+		assert_eq!(file.migrated_from, Some(0));
+		assert_eq!(file.version, CURRENT_VERSION);
+		assert!(unknown.is_empty());
+		assert_eq!(options.tab, 2);
+		assert!(options.diff.ignore_whitespace);
+		assert_eq!(options.diff.context, 5);
+		assert_eq!(options.diff.interhunk_lines, 0);
+		assert!(options.commit_msgs.is_empty());
+	}
+
+	#[test]
+	fn test_v0_commit_msgs() {
+		let (options, unknown, _) =
+			load(include_str!("fixtures/v0_commit_msgs.ron"));
+
+		assert!(unknown.is_empty());
+		assert_eq!(options.diff.interhunk_lines, 1);
+		assert_eq!(options.commit_msgs, vec!["first", "second"]);
+		assert!(options.status_show_untracked.is_none());
+	}
+
+	#[test]
+	fn test_v0_hook_timeout() {
+		let (options, unknown, _) =
+			load(include_str!("fixtures/v0_hook_timeout.ron"));
+
+		assert!(unknown.is_empty());
+		assert!(matches!(
+			options.status_show_untracked,
+			Some(ShowUntrackedFilesConfig::All)
+		));
+		assert_eq!(
+			options.hook_timeout,
+			Some(Duration::from_secs(5))
+		);
+		assert!(!options.hook_commit_msg_preview);
+	}
+
+	#[test]
+	fn test_v0_compare_base() {
+		let (options, unknown, _) =
+			load(include_str!("fixtures/v0_compare_base.ron"));
+
+		assert!(unknown.is_empty());
+		assert!(options.hook_commit_msg_preview);
+		assert_eq!(options.compare_base.as_deref(), Some("main"));
+		assert_eq!(options.default_branch.as_deref(), Some("trunk"));
+	}
+
+	#[test]
+	fn test_v1_round_trips_unknown_fields() {
+		let data = include_str!("fixtures/v1.ron");
+		let (options, unknown, file) = load(data);
+
+		assert_eq!(file.migrated_from, None);
+		assert_eq!(options.tab, 1);
+		assert_eq!(
+			unknown.keys().collect::<Vec<_>>(),
+			vec!["from_the_future", "nested_future"]
+		);
+
+		let mut fields = unknown;
+		fields.extend(fields_of(&options).unwrap());
+		let written = write(file.version, &fields).unwrap();
+
+		assert_eq!(written, data);
+	}
+
+	#[test]
+	fn test_newer_version_is_kept() {
+		let file = parse(b"(version: 7, tab: 1)").unwrap();
+
+		assert_eq!(file.version, 7);
+		assert_eq!(file.migrated_from, None);
+	}
+
+	#[test]
+	fn test_invalid_field_is_dropped() {
+		let (options, unknown, _) =
+			load("(version: 1, tab: \"two\", commit_msgs: [\"a\"])");
+
+		assert!(unknown.is_empty());
+		assert_eq!(options.tab, 0);
+		assert_eq!(options.commit_msgs, vec!["a"]);
+	}
+
+	#[test]
+	fn test_migration_backup() {
+		let dir = tempfile::tempdir().unwrap();
+		let path = dir.path().join("gitui");
+		let data = include_str!("fixtures/v0_diff.ron");
+		fs::write(&path, data).unwrap();
+
+		let Stored::Loaded(file) = read(&path).unwrap() else {
+			panic!("fixture did not load");
+		};
+
+		assert_eq!(file.migrated_from, Some(0));
+		assert_eq!(
+			fs::read_to_string(dir.path().join("gitui.v0.bak"))
+				.unwrap(),
+			data
+		);
+		let migrated = parse(&fs::read(&path).unwrap()).unwrap();
+		assert_eq!(migrated.migrated_from, None);
+		assert_eq!(
+			write(migrated.version, &migrated.fields).unwrap(),
+			write(file.version, &file.fields).unwrap()
+		);
+	}
+
+	#[test]
+	fn test_truncated_file_is_moved_aside() {
+		let dir = tempfile::tempdir().unwrap();
+		let path = dir.path().join("gitui");
+		let data = include_str!("fixtures/v1.ron");
+		fs::write(&path, &data[..data.len() / 2]).unwrap();
+
+		assert!(matches!(
+			read(&path).unwrap(),
+			Stored::Corrupt { .. }
+		));
+		assert!(!path.exists());
+		assert!(dir.path().join("gitui.corrupt").exists());
+	}
+}
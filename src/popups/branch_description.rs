@@ -0,0 +1,147 @@
+use crate::components::{
+	visibility_blocking, CommandBlocking, CommandInfo, Component,
+	DrawableComponent, EventState, InputType, TextInputComponent,
+};
+use crate::{
+	app::Environment,
+	keys::{key_match, SharedKeyConfig},
+	queue::{InternalEvent, NeedsUpdate, Queue},
+	strings, try_or_popup,
+};
+use anyhow::Result;
+use asyncgit::sync::{self, RepoPathRef};
+use crossterm::event::Event;
+use ratatui::{layout::Rect, Frame};
+
+/// edits the description of a local branch
+/// (`branch.<name>.description`)
+pub struct BranchDescriptionPopup {
+	repo: RepoPathRef,
+	input: TextInputComponent,
+	branch: Option<String>,
+	queue: Queue,
+	key_config: SharedKeyConfig,
+}
+
+impl DrawableComponent for BranchDescriptionPopup {
+	fn draw(&self, f: &mut Frame, rect: Rect) -> Result<()> {
+		if self.is_visible() {
+			self.input.draw(f, rect)?;
+		}
+		Ok(())
+	}
+}
+
+impl Component for BranchDescriptionPopup {
+	fn commands(
+		&self,
+		out: &mut Vec<CommandInfo>,
+		force_all: bool,
+	) -> CommandBlocking {
+		if self.is_visible() || force_all {
+			self.input.commands(out, force_all);
+
+			out.push(CommandInfo::new(
+				strings::commands::branch_description_confirm(
+					&self.key_config,
+				),
+				true,
+				true,
+			));
+
+			out.push(CommandInfo::new(
+				strings::commands::newline(&self.key_config),
+				true,
+				true,
+			));
+		}
+
+		visibility_blocking(self)
+	}
+
+	fn event(&mut self, ev: &Event) -> Result<EventState> {
+		if self.is_visible() {
+			if let Event::Key(e) = ev {
+				if key_match(e, self.key_config.keys.commit) {
+					try_or_popup!(
+						self,
+						"branch description error:",
+						self.save()
+					);
+					return Ok(EventState::Consumed);
+				}
+			}
+
+			self.input.event(ev)?;
+			return Ok(EventState::Consumed);
+		}
+		Ok(EventState::NotConsumed)
+	}
+
+	fn is_visible(&self) -> bool {
+		self.input.is_visible()
+	}
+
+	fn hide(&mut self) {
+		self.input.hide();
+	}
+
+	fn show(&mut self) -> Result<()> {
+		self.input.show()?;
+
+		Ok(())
+	}
+}
+
+impl BranchDescriptionPopup {
+	///
+	pub fn new(env: &Environment) -> Self {
+		Self {
+			repo: env.repo.clone(),
+			queue: env.queue.clone(),
+			input: TextInputComponent::new(
+				env,
+				&strings::branch_description_popup_title(
+					&env.key_config,
+				),
+				&strings::branch_description_popup_msg(
+					&env.key_config,
+				),
+				true,
+			)
+			.with_input_type(InputType::Multiline),
+			branch: None,
+			key_config: env.key_config.clone(),
+		}
+	}
+
+	///
+	pub fn open(&mut self, branch: String) -> Result<()> {
+		let description = sync::get_branch_description(
+			&self.repo.borrow(),
+			&branch,
+		)?;
+
+		self.input.set_text(description.unwrap_or_default());
+		self.branch = Some(branch);
+		self.show()?;
+
+		Ok(())
+	}
+
+	fn save(&mut self) -> Result<()> {
+		if let Some(branch) = self.branch.take() {
+			sync::set_branch_description(
+				&self.repo.borrow(),
+				&branch,
+				self.input.get_text(),
+			)?;
+		}
+
+		self.hide();
+		self.queue.push(InternalEvent::Update(NeedsUpdate::ALL));
+		self.queue.push(InternalEvent::SelectBranch);
+
+		Ok(())
+	}
+}
@@ -6,6 +6,7 @@ use crate::{
 	app::Environment,
 	components::ScrollType,
 	keys::{key_match, SharedKeyConfig},
+	popups::block_pending_operation,
 	queue::{
 		Action, InternalEvent, NeedsUpdate, Queue, StackablePopupOpen,
 	},
@@ -31,7 +32,9 @@ use ratatui::{
 		Alignment, Constraint, Direction, Layout, Margin, Rect,
 	},
 	text::{Line, Span, Text},
-	widgets::{Block, BorderType, Borders, Clear, Paragraph, Tabs},
+	widgets::{
+		Block, BorderType, Borders, Clear, Paragraph, Tabs, Wrap,
+	},
 	Frame,
 };
 use std::cell::Cell;
@@ -40,6 +43,18 @@ use unicode_truncate::UnicodeTruncateStr;
 
 use super::InspectCommitOpen;
 
+/// height of the description area below the local branches
+/// (including its border)
+const DESCRIPTION_HEIGHT: u16 = 4;
+
+/// a remote branch pointing at a commit missing locally
+const fn is_broken(branch: &BranchInfo) -> bool {
+	matches!(
+		branch.details,
+		BranchDetails::Remote(RemoteBranch { broken: true, .. })
+	)
+}
+
 ///
 pub struct BranchListPopup {
 	repo: RepoPathRef,
@@ -50,6 +65,8 @@ pub struct BranchListPopup {
 	selection: u16,
 	scroll: VerticalScroll,
 	current_height: Cell<u16>,
+	/// description of the selected local branch
+	description: Option<String>,
 	queue: Queue,
 	theme: SharedTheme,
 	key_config: SharedKeyConfig,
@@ -85,16 +102,26 @@ impl DrawableComponent for BranchListPopup {
 				horizontal: 1,
 			});
 
+			let description_height =
+				if self.local { DESCRIPTION_HEIGHT } else { 0 };
+
 			let chunks = Layout::default()
 				.direction(Direction::Vertical)
 				.constraints(
-					[Constraint::Length(2), Constraint::Min(1)]
-						.as_ref(),
+					[
+						Constraint::Length(2),
+						Constraint::Min(1),
+						Constraint::Length(description_height),
+					]
+					.as_ref(),
 				)
 				.split(area);
 
 			self.draw_tabs(f, chunks[0]);
 			self.draw_list(f, chunks[1])?;
+			if self.local {
+				self.draw_description(f, chunks[2]);
+			}
 		}
 
 		Ok(())
@@ -118,7 +145,7 @@ impl Component for BranchListPopup {
 	}
 
 	//TODO: cleanup
-	#[allow(clippy::cognitive_complexity)]
+	#[allow(clippy::cognitive_complexity, clippy::too_many_lines)]
 	fn event(&mut self, ev: &Event) -> Result<EventState> {
 		if !self.visible {
 			return Ok(EventState::NotConsumed);
@@ -186,6 +213,29 @@ impl Component for BranchListPopup {
 						),
 					));
 				}
+			} else if key_match(
+				e,
+				self.key_config.keys.edit_branch_description,
+			) && self.local
+				&& self.valid_selection()
+			{
+				self.queue.push(
+					InternalEvent::EditBranchDescription(
+						self.branches[self.selection as usize]
+							.name
+							.clone(),
+					),
+				);
+			} else if key_match(
+				e,
+				self.key_config.keys.repair_remote_branch,
+			) && self.selection_is_broken()
+			{
+				try_or_popup!(
+					self,
+					"repair error:",
+					self.repair_selected_branch()
+				);
 			} else if key_match(e, self.key_config.keys.fetch)
 				&& self.has_remotes
 			{
@@ -250,6 +300,7 @@ impl BranchListPopup {
 			theme: env.theme.clone(),
 			key_config: env.key_config.clone(),
 			current_height: Cell::new(0),
+			description: None,
 			repo: env.repo.clone(),
 		}
 	}
@@ -345,7 +396,35 @@ impl BranchListPopup {
 		!self.branches.is_empty()
 	}
 
+	fn selection_is_broken(&self) -> bool {
+		self.branches
+			.get(self.selection as usize)
+			.is_some_and(is_broken)
+	}
+
+	/// deletes the broken remote branch and fetches it again
+	fn repair_selected_branch(&mut self) -> Result<()> {
+		let reference =
+			self.branches[self.selection as usize].reference.clone();
+
+		if let Some(target) =
+			sync::repair_remote_ref(&self.repo.borrow(), &reference)?
+		{
+			self.queue.push(InternalEvent::FetchTarget(target));
+		}
+
+		self.update_branches()?;
+		self.queue
+			.push(InternalEvent::Update(NeedsUpdate::BRANCHES));
+
+		Ok(())
+	}
+
 	fn merge_branch(&mut self) -> Result<()> {
+		if block_pending_operation(&self.repo.borrow(), &self.queue) {
+			return Ok(());
+		}
+
 		if let Some(branch) =
 			self.branches.get(usize::from(self.selection))
 		{
@@ -362,6 +441,10 @@ impl BranchListPopup {
 	}
 
 	fn rebase_branch(&mut self) -> Result<()> {
+		if block_pending_operation(&self.repo.borrow(), &self.queue) {
+			return Ok(());
+		}
+
 		if let Some(branch) =
 			self.branches.get(usize::from(self.selection))
 		{
@@ -463,10 +546,31 @@ impl BranchListPopup {
 		};
 
 		self.selection = selection;
+		self.update_description();
 
 		Ok(())
 	}
 
+	fn update_description(&mut self) {
+		self.description = if self.local {
+			self.branches.get(usize::from(self.selection)).and_then(
+				|branch| {
+					sync::get_branch_description(
+						&self.repo.borrow(),
+						&branch.name,
+					)
+					.map_err(|e| {
+						log::error!("branch description error: {e}");
+					})
+					.ok()
+					.flatten()
+				},
+			)
+		} else {
+			None
+		};
+	}
+
 	/// Get branches to display
 	fn get_text(
 		&self,
@@ -556,10 +660,17 @@ impl BranchListPopup {
 				),
 				theme.commit_hash(selected),
 			);
-			let span_msg = Span::styled(
-				commit_message.to_string(),
-				theme.text(true, selected),
-			);
+			let span_msg = if is_broken(displaybranch) {
+				Span::styled(
+					strings::branch_broken(),
+					theme.text_danger(),
+				)
+			} else {
+				Span::styled(
+					commit_message.clone(),
+					theme.text(true, selected),
+				)
+			};
 			let span_name = Span::styled(
 				format!("{branch_name:branch_name_length$} "),
 				theme.branch(selected, is_head),
@@ -582,6 +693,12 @@ impl BranchListPopup {
 			anyhow::bail!("no valid branch selected");
 		}
 
+		if block_pending_operation(&self.repo.borrow(), &self.queue) {
+			return Ok(());
+		}
+
+		let prev_head = sync::get_head(&self.repo.borrow()).ok();
+
 		if self.local {
 			checkout_branch(
 				&self.repo.borrow(),
@@ -598,6 +715,13 @@ impl BranchListPopup {
 		}
 
 		self.queue.push(InternalEvent::Update(NeedsUpdate::ALL));
+		// nothing was checked out before on an unborn branch
+		if let Some(prev_head) = prev_head {
+			self.queue.push(InternalEvent::PostCheckout {
+				prev_head,
+				branch_checkout: true,
+			});
+		}
 
 		Ok(())
 	}
@@ -655,6 +779,35 @@ impl BranchListPopup {
 		Ok(())
 	}
 
+	fn draw_description(&self, f: &mut Frame, r: Rect) {
+		let text = self.description.as_ref().map_or_else(
+			|| {
+				Text::from(Span::styled(
+					strings::branch_description_empty(
+						&self.key_config,
+					),
+					self.theme.text(false, false),
+				))
+			},
+			|description| Text::from(description.as_str()),
+		);
+
+		f.render_widget(
+			Paragraph::new(text)
+				.block(
+					Block::default()
+						.borders(Borders::TOP)
+						.border_style(self.theme.block(false))
+						.title(Span::styled(
+							strings::branch_description_title(),
+							self.theme.title(false),
+						)),
+				)
+				.wrap(Wrap { trim: false }),
+			r,
+		);
+	}
+
 	fn rename_branch(&self) {
 		let cur_branch = &self.branches[self.selection as usize];
 		self.queue.push(InternalEvent::RenameBranch(
@@ -750,6 +903,20 @@ impl BranchListPopup {
 			self.local,
 		));
 
+		out.push(CommandInfo::new(
+			strings::commands::edit_branch_description(
+				&self.key_config,
+			),
+			self.valid_selection(),
+			self.local,
+		));
+
+		out.push(CommandInfo::new(
+			strings::commands::repair_remote_branch(&self.key_config),
+			self.selection_is_broken(),
+			!self.local,
+		));
+
 		out.push(CommandInfo::new(
 			strings::commands::fetch_remotes(&self.key_config),
 			self.has_remotes,
@@ -0,0 +1,164 @@
+use crate::components::{
+	visibility_blocking, CommandBlocking, CommandInfo, Component,
+	DrawableComponent, EventState, InputType, TextInputComponent,
+};
+use crate::{
+	app::Environment,
+	keys::{key_match, SharedKeyConfig},
+	strings,
+	ui::style::SharedTheme,
+};
+use anyhow::Result;
+use asyncgit::sync::{self, RepoPathRef};
+use crossterm::event::Event;
+use ratatui::{layout::Rect, widgets::Paragraph, Frame};
+
+/// asks for a path and tells whether it is ignored and by which rule,
+/// like `git check-ignore -v` (see `sync::check_ignore`)
+pub struct CheckIgnorePopup {
+	repo: RepoPathRef,
+	input: TextInputComponent,
+	/// the answer for the path as typed and whether it is ignored,
+	/// cleared once the input changes
+	result: Option<(String, bool)>,
+	key_config: SharedKeyConfig,
+	theme: SharedTheme,
+}
+
+impl DrawableComponent for CheckIgnorePopup {
+	fn draw(&self, f: &mut Frame, rect: Rect) -> Result<()> {
+		if self.is_visible() {
+			self.input.draw(f, rect)?;
+			self.draw_result(f);
+		}
+
+		Ok(())
+	}
+}
+
+impl Component for CheckIgnorePopup {
+	fn commands(
+		&self,
+		out: &mut Vec<CommandInfo>,
+		force_all: bool,
+	) -> CommandBlocking {
+		if self.is_visible() || force_all {
+			self.input.commands(out, force_all);
+
+			out.push(CommandInfo::new(
+				strings::commands::check_ignore_confirm(
+					&self.key_config,
+				),
+				!self.input.get_text().trim().is_empty(),
+				true,
+			));
+		}
+
+		visibility_blocking(self)
+	}
+
+	fn event(&mut self, ev: &Event) -> Result<EventState> {
+		if self.is_visible() {
+			if self.input.event(ev)?.is_consumed() {
+				self.result = None;
+				return Ok(EventState::Consumed);
+			}
+
+			if let Event::Key(e) = ev {
+				if key_match(e, self.key_config.keys.enter) {
+					self.check();
+				}
+
+				return Ok(EventState::Consumed);
+			}
+		}
+		Ok(EventState::NotConsumed)
+	}
+
+	fn is_visible(&self) -> bool {
+		self.input.is_visible()
+	}
+
+	fn hide(&mut self) {
+		self.input.hide();
+	}
+
+	fn show(&mut self) -> Result<()> {
+		self.input.show()?;
+
+		Ok(())
+	}
+}
+
+impl CheckIgnorePopup {
+	///
+	pub fn new(env: &Environment) -> Self {
+		Self {
+			input: TextInputComponent::new(
+				env,
+				&strings::check_ignore_popup_title(&env.key_config),
+				&strings::check_ignore_popup_msg(&env.key_config),
+				false,
+			)
+			.with_input_type(InputType::Singleline),
+			result: None,
+			theme: env.theme.clone(),
+			key_config: env.key_config.clone(),
+			repo: env.repo.clone(),
+		}
+	}
+
+	/// `path` is checked right away
+	pub fn open(&mut self, path: Option<String>) -> Result<()> {
+		self.input.clear();
+		self.result = None;
+
+		if let Some(path) = path {
+			self.input.set_text(path);
+			self.check();
+		}
+
+		self.show()
+	}
+
+	fn check(&mut self) {
+		self.result = Some(
+			match sync::check_ignore(
+				&self.repo.borrow(),
+				self.input.get_text(),
+			) {
+				Ok(check) => (
+					strings::check_ignore_result(&check),
+					check.is_ignored(),
+				),
+				Err(e) => (format!("error: {e}"), false),
+			},
+		);
+	}
+
+	/// in the bottom line of the input
+	fn draw_result(&self, f: &mut Frame) {
+		if let Some((msg, ignored)) = &self.result {
+			let style = if *ignored {
+				self.theme.text_danger()
+			} else {
+				self.theme.text(true, false)
+			};
+
+			let rect = {
+				let mut rect = self.input.get_area();
+				rect.y += rect.height.saturating_sub(1);
+				rect.height = 1;
+				rect.x += 1;
+				rect.width = rect.width.saturating_sub(2);
+
+				rect
+			};
+
+			f.render_widget(
+				Paragraph::new(msg.as_str()).style(style),
+				rect,
+			);
+		}
+	}
+}
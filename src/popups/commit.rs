@@ -1,47 +1,61 @@
 use crate::components::{
 	visibility_blocking, CommandBlocking, CommandInfo, Component,
 	DrawableComponent, EventState, TextInputComponent,
+	TextViewerComponent,
 };
 use crate::{
 	app::Environment,
+	commit_presets::{prefix_msg, CommitPresets},
+	crash,
 	keys::{key_match, SharedKeyConfig},
 	options::SharedOptions,
 	queue::{InternalEvent, NeedsUpdate, Queue},
 	strings, try_or_popup,
 	ui::style::SharedTheme,
 };
-use anyhow::{bail, Ok, Result};
+use anyhow::{bail, Result};
 use asyncgit::sync::commit::commit_message_prettify;
 use asyncgit::{
-	cached,
+	asyncjob::AsyncSingleJob,
+	cached, hash,
 	sync::{
-		self, get_config_string, CommitId, HookResult,
-		PrepareCommitMsgSource, RepoPathRef, RepoState,
+		self, get_config_string, CommitId, CommitMsgFor,
+		HookBehavior, HookResult, PostRewriteSource, RepoPathRef,
+		RepoState, TimeoutSource,
 	},
+	AsyncCommitMsgPreviewJob, AsyncGitNotification, AsyncHooksJob,
 	StatusItem, StatusItemType,
 };
 use crossterm::event::Event;
 use easy_cast::Cast;
 use ratatui::{
 	layout::{Alignment, Rect},
-	widgets::Paragraph,
+	text::Span,
+	widgets::{Block, Borders, Clear, Paragraph},
 	Frame,
 };
 
-use std::time::Duration;
+use std::time::{Duration, Instant};
 use std::{
 	fmt::Write as _,
 	fs::{read_to_string, File},
 	io::{Read, Write},
-	path::PathBuf,
+	path::{Path, PathBuf},
 	str::FromStr,
 };
 
 use super::ExternalEditorPopup;
 
-enum CommitResult {
-	CommitDone,
-	Aborted,
+/// where [`CommitPopup`] continues once a popup shown before
+/// committing is confirmed
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CommitStep {
+	/// look for staged conflict markers and large files
+	StagedChecks,
+	/// summarize the staged changes
+	Summary,
+	/// make the commit
+	Commit,
 }
 
 enum Mode {
@@ -50,6 +64,7 @@ enum Mode {
 	Merge(Vec<CommitId>),
 	Revert,
 	Reword(CommitId),
+	Squash { base: CommitId, tip: CommitId },
 }
 
 pub struct CommitPopup {
@@ -59,14 +74,36 @@ pub struct CommitPopup {
 	queue: Queue,
 	key_config: SharedKeyConfig,
 	git_branch_name: cached::BranchName,
-	commit_template: Option<String>,
+	/// message the popup got pre-filled with
+	/// (`commit.template` and ticket prefix)
+	prefilled_msg: Option<String>,
 	theme: SharedTheme,
 	commit_msg_history_idx: usize,
 	options: SharedOptions,
 	verify: bool,
+	msg_preview: AsyncSingleJob<AsyncCommitMsgPreviewJob>,
+	msg_preview_hash: Option<u64>,
+	msg_preview_result:
+		Option<std::result::Result<HookResult, String>>,
+	/// what the commit-msg hook printed when it rejected the message
+	msg_preview_output: TextViewerComponent,
+	/// when the preview of the edited message is to be run, see
+	/// [`Self::tick`]
+	msg_preview_due: Option<Instant>,
+	hooks: AsyncSingleJob<AsyncHooksJob>,
+	/// the commit hooks currently running (before committing),
+	/// kept to cancel them
+	running_hooks: Option<AsyncHooksJob>,
+	presets: CommitPresets,
 }
 
 const FIRST_LINE_LIMIT: usize = 50;
+/// idle time after the last edit before the commit-msg hook preview runs
+const MSG_PREVIEW_DELAY: Duration = Duration::from_millis(500);
+/// upper bound for a single commit-msg hook preview run
+const MSG_PREVIEW_TIMEOUT: Duration = Duration::from_secs(2);
+/// height of the commit-msg hook preview area (including borders)
+const MSG_PREVIEW_HEIGHT: u16 = 6;
 
 impl CommitPopup {
 	///
@@ -79,18 +116,63 @@ impl CommitPopup {
 				"",
 				&strings::commit_msg(&env.key_config),
 				true,
-			),
+			)
+			.with_popup_size(80, 60)
+			.with_subject_separator(),
 			key_config: env.key_config.clone(),
 			git_branch_name: cached::BranchName::new(
 				env.repo.clone(),
 			),
-			commit_template: None,
+			prefilled_msg: None,
 			theme: env.theme.clone(),
 			repo: env.repo.clone(),
 			commit_msg_history_idx: 0,
 			options: env.options.clone(),
 			verify: true,
+			msg_preview: AsyncSingleJob::new(env.sender_git.clone()),
+			msg_preview_hash: None,
+			msg_preview_result: None,
+			msg_preview_output: TextViewerComponent::new(env),
+			msg_preview_due: None,
+			hooks: AsyncSingleJob::new(env.sender_git.clone()),
+			running_hooks: None,
+			presets: CommitPresets::init(),
+		}
+	}
+
+	/// applies preset `idx` (see `commit_presets`) to the message:
+	/// its subject replaces the first line, its snippet is inserted
+	/// at the cursor
+	pub fn apply_preset(&mut self, idx: usize) {
+		let Some(preset) = self.presets.presets().get(idx) else {
+			return;
+		};
+
+		let branch = self.git_branch_name.last();
+
+		if !preset.subject.is_empty() {
+			let subject = self
+				.presets
+				.expand(&preset.subject, branch.as_deref());
+
+			let msg = match self.input.get_text().split_once('\n') {
+				Some((_, body)) => format!("{subject}\n{body}"),
+				None => subject,
+			};
+
+			self.input.set_text(msg);
+			self.input.move_cursor_to_first_line_end();
 		}
+
+		if !preset.snippet.is_empty() {
+			let snippet = self
+				.presets
+				.expand(&preset.snippet, branch.as_deref());
+
+			self.input.insert_str(&snippet);
+		}
+
+		self.update_msg_preview();
 	}
 
 	///
@@ -98,6 +180,183 @@ impl CommitPopup {
 		self.git_branch_name.lookup().ok();
 	}
 
+	///
+	pub fn update_git(&mut self, ev: AsyncGitNotification) {
+		match ev {
+			AsyncGitNotification::CommitMsgPreview => {
+				self.update_msg_preview_result();
+			}
+			AsyncGitNotification::Hooks => {
+				try_or_popup!(
+					self,
+					"commit error:",
+					self.finish_commit()
+				);
+			}
+			_ => (),
+		}
+	}
+
+	///
+	pub fn any_work_pending(&self) -> bool {
+		self.hooks.is_pending()
+	}
+
+	fn update_msg_preview_result(&mut self) {
+		if let Some(result) =
+			self.msg_preview.take_last().and_then(|job| job.result())
+		{
+			// ignore results for an outdated version of the message
+			if Some(result.msg_hash) == self.msg_preview_hash {
+				let result = result.result.map_err(|e| {
+					log::error!("commit-msg hook preview error: {e}");
+					format!("commit-msg hook error:\n{e}")
+				});
+
+				let output = match &result {
+					Ok(HookResult::NotOk(failure)) => failure.msg(),
+					Ok(HookResult::TimedOut {
+						stdout,
+						stderr,
+						..
+					}) => format!("{stdout}{stderr}"),
+					Err(e) => e.clone(),
+					Ok(_) => String::new(),
+				};
+				self.msg_preview_output.set_text(&output);
+				self.msg_preview_result = Some(result);
+			}
+		}
+	}
+
+	fn msg_preview_enabled(&self) -> bool {
+		self.verify && self.options.borrow().hook_commit_msg_preview()
+	}
+
+	/// schedules running the commit-msg hook against the current
+	/// message once editing paused for `MSG_PREVIEW_DELAY` (see
+	/// [`Self::tick`]) if it changed since the last run
+	fn update_msg_preview(&mut self) {
+		if !self.msg_preview_enabled() {
+			return;
+		}
+
+		let msg_hash = hash(self.input.get_text());
+
+		if self.msg_preview_hash == Some(msg_hash) {
+			return;
+		}
+
+		self.msg_preview_hash = Some(msg_hash);
+		self.msg_preview_result = None;
+		self.msg_preview_output.set_text("");
+		self.msg_preview_due =
+			Some(Instant::now() + MSG_PREVIEW_DELAY);
+	}
+
+	/// time until [`Self::tick`] has something to do
+	pub fn next_tick_in(&self, now: Instant) -> Option<Duration> {
+		self.msg_preview_due
+			.map(|due| due.saturating_duration_since(now))
+	}
+
+	/// runs the commit-msg hook preview in the background once it is
+	/// due, i.e. there was no edit for `MSG_PREVIEW_DELAY`
+	pub fn tick(&mut self, now: Instant) {
+		if !self.msg_preview_due.is_some_and(|due| due <= now) {
+			return;
+		}
+
+		self.msg_preview_due = None;
+
+		if !self.is_visible() || !self.msg_preview_enabled() {
+			return;
+		}
+
+		let timeout = self
+			.get_hook_timeout()
+			.map_or(MSG_PREVIEW_TIMEOUT, |timeout| {
+				timeout.min(MSG_PREVIEW_TIMEOUT)
+			});
+
+		self.msg_preview.spawn(AsyncCommitMsgPreviewJob::new(
+			self.repo.borrow().clone(),
+			self.input.get_text().to_string(),
+			Some(timeout),
+		));
+	}
+
+	fn draw_msg_preview(&self, f: &mut Frame) -> Result<()> {
+		if !self.msg_preview_enabled() {
+			return Ok(());
+		}
+
+		let (title, passed) = match &self.msg_preview_result {
+			None | Some(Ok(HookResult::Cancelled { .. })) => {
+				(strings::commit_msg_preview_running(), true)
+			}
+			Some(Ok(HookResult::Ok)) => {
+				(strings::commit_msg_preview_passed(), true)
+			}
+			Some(Ok(HookResult::NotOk(_)) | Err(_)) => {
+				(strings::commit_msg_preview_rejected(), false)
+			}
+			Some(Ok(HookResult::TimedOut { .. })) => {
+				(strings::commit_msg_preview_timed_out(), false)
+			}
+		};
+
+		let rect = {
+			let input = self.input.get_area();
+			let bottom = f.area().bottom();
+			let y = input.bottom().min(bottom);
+
+			Rect::new(
+				input.x,
+				y,
+				input.width,
+				MSG_PREVIEW_HEIGHT.min(bottom - y),
+			)
+		};
+
+		if rect.height < 2 {
+			return Ok(());
+		}
+
+		f.render_widget(Clear, rect);
+		f.render_widget(
+			Block::default()
+				.borders(Borders::ALL)
+				.title(Span::styled(title, self.theme.option(passed)))
+				.border_style(self.theme.block(false)),
+			rect,
+		);
+
+		self.msg_preview_output.draw(f, rect)
+	}
+
+	fn draw_hooks_running(&self, f: &mut Frame) {
+		if self.running_hooks.is_none() {
+			return;
+		}
+
+		let w = Paragraph::new(strings::commit_hooks_running(
+			&self.key_config,
+		))
+		.style(self.theme.text(false, false));
+
+		let rect = {
+			let mut rect = self.input.get_area();
+			rect.y += rect.height.saturating_sub(1);
+			rect.height = 1;
+			rect.x += 1;
+			rect.width = rect.width.saturating_sub(2);
+			rect
+		};
+
+		f.render_widget(w, rect);
+	}
+
 	fn draw_branch_name(&self, f: &mut Frame) {
 		if let Some(name) = self.git_branch_name.last() {
 			let w = Paragraph::new(format!("{{{name}}}"))
@@ -207,139 +466,201 @@ impl CommitPopup {
 		Ok(())
 	}
 
-	fn commit(&mut self) -> Result<()> {
-		let msg = self.input.get_text().to_string();
-
-		if matches!(
-			self.commit_with_msg(msg)?,
-			CommitResult::CommitDone
-		) {
-			self.options
-				.borrow_mut()
-				.add_commit_msg(self.input.get_text());
-			self.commit_msg_history_idx = 0;
-
-			self.hide();
-			self.queue.push(InternalEvent::Update(NeedsUpdate::ALL));
-			self.queue.push(InternalEvent::StatusLastFileMoved);
-			self.input.clear();
+	/// goes through the checks before committing starting at `step`.
+	/// staged conflict markers and large files are shown first, then the
+	/// `CommitSummaryPopup` unless disabled by
+	/// `gitui.commitSummary.enabled`. each popup continues with the
+	/// next step once confirmed. amending, rewording and squashing
+	/// commit more than what is staged and are not summarized
+	fn commit_from(&mut self, step: CommitStep) -> Result<()> {
+		let repo = self.repo.borrow().clone();
+
+		if step == CommitStep::StagedChecks
+			&& !matches!(
+				self.mode,
+				Mode::Reword(_) | Mode::Squash { .. }
+			) {
+			let checks = sync::staged_checks(&repo)?;
+			if !checks.is_empty() {
+				self.queue
+					.push(InternalEvent::ShowStagedChecks(checks));
+				return Ok(());
+			}
 		}
 
-		Ok(())
+		if step != CommitStep::Commit
+			&& matches!(
+				self.mode,
+				Mode::Normal | Mode::Merge(_) | Mode::Revert
+			) && sync::staged_summary_config(&repo)?.enabled
+		{
+			self.queue.push(InternalEvent::ShowCommitSummary(
+				sync::staged_summary(&repo)?,
+			));
+			return Ok(());
+		}
+
+		self.commit()
 	}
 
-	fn commit_with_msg(
-		&mut self,
-		msg: String,
-	) -> Result<CommitResult> {
-		// on exit verify should always be on
-		let verify = self.verify;
+	/// continues committing after one of the popups shown by
+	/// [`Self::commit_from`] was confirmed
+	pub fn continue_commit(&mut self, step: CommitStep) {
+		if self.is_visible() {
+			try_or_popup!(
+				self,
+				"commit error:",
+				self.commit_from(step)
+			);
+		}
+	}
+
+	/// runs the `pre-commit` and `commit-msg` hooks in the background,
+	/// [`Self::finish_commit`] commits once they passed
+	fn commit(&mut self) -> Result<()> {
+		if self.running_hooks.is_some() {
+			return Ok(());
+		}
+
+		// on exit verify should always be on. squashing has nothing
+		// staged to check and runs the commit-msg hook itself
+		let behavior = HookBehavior::from_verify(
+			self.verify && !matches!(self.mode, Mode::Squash { .. }),
+		);
 		self.verify = true;
 
-		if verify {
-			// run pre commit hook - can reject commit
-			match sync::hooks_pre_commit_with_timeout(
-				&self.repo.borrow(),
-				self.get_hook_timeout(),
-			)? {
-				HookResult::NotOk(e) => {
-					log::error!("pre-commit hook error: {}", e);
-					self.queue.push(InternalEvent::ShowErrorMsg(
-						format!("pre-commit hook error:\n{e}"),
-					));
-					return Ok(CommitResult::Aborted);
-				}
-				HookResult::TimedOut { stdout, stderr } => {
-					log::error!("pre-commit hook timed out");
-					self.queue.push(InternalEvent::ShowErrorMsg(
-						format!(
-						"pre-commit hook timed out after {} seconds, see output below.\n{}\n{}",
-						self.get_hook_timeout()
-							.unwrap_or(Duration::ZERO)
-							.as_secs(),
-						stdout,
-						stderr
-					),
-					));
-					return Ok(CommitResult::Aborted);
-				}
-				HookResult::Ok => {}
-			}
+		let msg = commit_message_prettify(
+			&self.repo.borrow(),
+			self.input.get_text().to_string(),
+		)?;
+
+		let job = AsyncHooksJob::commit(
+			self.repo.borrow().clone(),
+			msg,
+			behavior,
+			self.get_hook_timeout(),
+		);
+		self.hooks.spawn(job.clone());
+		self.running_hooks = Some(job);
+
+		Ok(())
+	}
+
+	/// commits with the message left by the hooks [`Self::commit`] ran,
+	/// unless one of them rejected it, timed out or was cancelled
+	fn finish_commit(&mut self) -> Result<()> {
+		if self.running_hooks.is_none() {
+			return Ok(());
 		}
 
-		let mut msg =
-			commit_message_prettify(&self.repo.borrow(), msg)?;
+		let Some(res) =
+			self.hooks.take_last().and_then(|job| job.result())
+		else {
+			return Ok(());
+		};
+		self.running_hooks = None;
 
-		if verify {
-			// run commit message check hook - can reject commit
-			match sync::hooks_commit_msg_with_timeout(
-				&self.repo.borrow(),
-				&mut msg,
-				self.get_hook_timeout(),
-			)? {
-				HookResult::NotOk(e) => {
-					log::error!("commit-msg hook error: {}", e);
-					self.queue.push(InternalEvent::ShowErrorMsg(
-						format!("commit-msg hook error:\n{e}"),
-					));
-					return Ok(CommitResult::Aborted);
-				}
-				HookResult::TimedOut { stdout, stderr } => {
-					log::error!("commit-msg hook timed out");
-					self.queue.push(InternalEvent::ShowErrorMsg(
-						format!(
-						"commit-msg hook timed out after {} seconds, see output below.\n{}\n{}",
-						self.get_hook_timeout()
-							.unwrap_or(Duration::ZERO)
-							.as_secs(),
-						stdout,
-						stderr
-					),
-					));
-					return Ok(CommitResult::Aborted);
-				}
-				HookResult::Ok => {}
+		let name = res.hook.filename();
+		match res.result? {
+			HookResult::NotOk(failure) => {
+				let e = failure.msg();
+				log::error!("{name} hook error: {}", e);
+				self.queue.push(InternalEvent::ShowErrorMsg(
+					format!("{name} hook error:\n{e}"),
+				));
+				return Ok(());
+			}
+			HookResult::TimedOut {
+				hook,
+				stdout,
+				stderr,
+				timeout,
+				source,
+			} => {
+				self.show_hook_timed_out(
+					name, timeout, source, &hook, &stdout, &stderr,
+				);
+				return Ok(());
 			}
+			HookResult::Cancelled { .. } => {
+				log::info!("commit cancelled while running {name}");
+				self.queue.push(InternalEvent::ShowInfoMsg(
+					strings::commit_cancelled(name),
+				));
+				return Ok(());
+			}
+			HookResult::Ok => {}
 		}
-		self.do_commit(&msg)?;
+
+		self.do_commit(&res.msg.unwrap_or_default())?;
 
 		match sync::hooks_post_commit_with_timeout(
 			&self.repo.borrow(),
 			self.get_hook_timeout(),
 		)? {
-			HookResult::NotOk(e) => {
+			HookResult::NotOk(failure) => {
+				let e = failure.msg();
 				log::error!("post-commit hook error: {}", e);
 				self.queue.push(InternalEvent::ShowErrorMsg(
 					format!("post-commit hook error:\n{e}"),
 				));
 			}
-			HookResult::TimedOut { stdout, stderr } => {
-				log::error!("post-commit hook timed out");
-				self.queue.push(InternalEvent::ShowErrorMsg(
-					format!(
-						"post-commit hook timed out after {} seconds, see output below.\n{}\n{}",
-						self.get_hook_timeout()
-							.unwrap_or(Duration::ZERO)
-							.as_secs(),
-						stdout,
-						stderr
-					),
-				));
+			HookResult::TimedOut {
+				hook,
+				stdout,
+				stderr,
+				timeout,
+				source,
+			} => {
+				self.show_hook_timed_out(
+					"post-commit",
+					timeout,
+					source,
+					&hook,
+					&stdout,
+					&stderr,
+				);
 			}
-			HookResult::Ok => {}
+			HookResult::Ok | HookResult::Cancelled { .. } => {}
 		}
 
-		Ok(CommitResult::CommitDone)
+		self.options
+			.borrow_mut()
+			.add_commit_msg(self.input.get_text());
+		self.commit_msg_history_idx = 0;
+
+		self.hide();
+		self.queue.push(InternalEvent::Update(NeedsUpdate::ALL));
+		self.queue.push(InternalEvent::StatusLastFileMoved);
+		self.input.clear();
+
+		Ok(())
 	}
 
 	fn do_commit(&self, msg: &str) -> Result<()> {
 		match &self.mode {
 			Mode::Normal => sync::commit(&self.repo.borrow(), msg)?,
 			Mode::Amend(amend) => {
-				sync::amend(&self.repo.borrow(), *amend, msg)?
+				let commit =
+					sync::amend(&self.repo.borrow(), *amend, msg)?;
+				self.queue.push(InternalEvent::PostRewrite {
+					source: PostRewriteSource::Amend,
+					rewritten: vec![(*amend, commit)],
+				});
+
+				commit
 			}
 			Mode::Merge(ids) => {
-				sync::merge_commit(&self.repo.borrow(), msg, ids)?
+				let commit = sync::merge_commit(
+					&self.repo.borrow(),
+					msg,
+					ids,
+				)?;
+				self.queue.push(InternalEvent::PostMerge {
+					is_squash: false,
+				});
+
+				commit
 			}
 			Mode::Revert => {
 				sync::commit_revert(&self.repo.borrow(), msg)?
@@ -351,6 +672,12 @@ impl CommitPopup {
 
 				commit
 			}
+			Mode::Squash { base, tip } => sync::squash_range(
+				&self.repo.borrow(),
+				*base,
+				*tip,
+				msg,
+			)?,
 		};
 		Ok(())
 	}
@@ -365,13 +692,62 @@ impl CommitPopup {
 			&& (self.is_empty() || !self.is_changed())
 	}
 
+	/// content of the file `commit.template` points to
+	fn load_commit_template(&self) -> Option<String> {
+		get_config_string(
+			&self.repo.borrow(),
+			"commit.template",
+		)
+		.map_err(|e| {
+			log::error!("load git-config failed: {}", e);
+			e
+		})
+		.ok()
+		.flatten()
+		.and_then(|path| {
+			shellexpand::full(path.as_str())
+				.ok()
+				.and_then(|path| {
+					PathBuf::from_str(path.as_ref()).ok()
+				})
+		})
+		.and_then(|path| {
+			read_to_string(&path)
+				.map_err(|e| {
+					log::error!("read commit.template failed: {e} (path: '{:?}')",path);
+					e
+				})
+				.ok()
+		})
+	}
+
+	/// pre-fill pipeline for new messages:
+	/// ticket prefix of the branch (if any) in front of `commit.template`
+	fn prefill_msg(
+		&mut self,
+		template: Option<String>,
+	) -> Option<String> {
+		let prefix =
+			self.git_branch_name.lookup().ok().and_then(|branch| {
+				self.presets.ticket_prefix(&branch)
+			});
+
+		match prefix {
+			None => template,
+			Some(prefix) => Some(prefix_msg(
+				&template.unwrap_or_default(),
+				&prefix,
+			)),
+		}
+	}
+
 	fn is_empty(&self) -> bool {
 		self.input.get_text().is_empty()
 	}
 
 	fn is_changed(&self) -> bool {
 		Some(self.input.get_text().trim())
-			!= self.commit_template.as_ref().map(|s| s.trim())
+			!= self.prefilled_msg.as_ref().map(|s| s.trim())
 	}
 
 	fn amend(&mut self) -> Result<()> {
@@ -387,6 +763,8 @@ impl CommitPopup {
 			if let Some(msg) = details.message {
 				self.input.set_text(msg.combine());
 			}
+
+			self.prepare_commit_msg(CommitMsgFor::Commit(id))?;
 		}
 
 		Ok(())
@@ -400,6 +778,7 @@ impl CommitPopup {
 	}
 	fn toggle_verify(&mut self) {
 		self.verify = !self.verify;
+		self.msg_preview_hash = None;
 	}
 
 	pub fn open(&mut self, reword: Option<CommitId>) -> Result<()> {
@@ -409,10 +788,13 @@ impl CommitPopup {
 		}
 
 		self.mode = Mode::Normal;
+		self.input.set_line_numbers(
+			self.options.borrow().commit_line_numbers(),
+		);
 
 		let repo_state = sync::repo_state(&self.repo.borrow())?;
 
-		let (mode, msg_source) = if repo_state != RepoState::Clean
+		let (mode, msg_for) = if repo_state != RepoState::Clean
 			&& reword.is_some()
 		{
 			bail!("cannot reword while repo is not in a clean state");
@@ -427,7 +809,7 @@ impl CommitPopup {
 				.combine(),
 			);
 			self.input.set_title(strings::commit_reword_title());
-			(Mode::Reword(reword_id), PrepareCommitMsgSource::Message)
+			(Mode::Reword(reword_id), CommitMsgFor::Commit(reword_id))
 		} else {
 			match repo_state {
 				RepoState::Merge => {
@@ -438,7 +820,10 @@ impl CommitPopup {
 					self.input.set_text(sync::merge_msg(
 						&self.repo.borrow(),
 					)?);
-					(Mode::Merge(ids), PrepareCommitMsgSource::Merge)
+					(
+						Mode::Merge(ids),
+						CommitMsgFor::New { template: false },
+					)
 				}
 				RepoState::Revert => {
 					self.input
@@ -446,70 +831,131 @@ impl CommitPopup {
 					self.input.set_text(sync::merge_msg(
 						&self.repo.borrow(),
 					)?);
-					(Mode::Revert, PrepareCommitMsgSource::Message)
+					(
+						Mode::Revert,
+						CommitMsgFor::New { template: false },
+					)
 				}
 
 				_ => {
-					self.commit_template = get_config_string(
-						&self.repo.borrow(),
-						"commit.template",
-					)
-					.map_err(|e| {
-						log::error!("load git-config failed: {}", e);
-						e
-					})
-					.ok()
-					.flatten()
-					.and_then(|path| {
-						shellexpand::full(path.as_str())
-							.ok()
-							.and_then(|path| {
-								PathBuf::from_str(path.as_ref()).ok()
-							})
-					})
-					.and_then(|path| {
-						read_to_string(&path)
-							.map_err(|e| {
-								log::error!("read commit.template failed: {e} (path: '{:?}')",path);
-								e
-							})
-							.ok()
-					});
-
-					let msg_source = if self.is_empty() {
-						if let Some(s) = &self.commit_template {
-							self.input.set_text(s.clone());
-							PrepareCommitMsgSource::Template
-						} else {
-							PrepareCommitMsgSource::Message
-						}
+					let squash_msg =
+						sync::squash_msg(&self.repo.borrow())?;
+					let commit_template = if squash_msg.is_some() {
+						squash_msg
 					} else {
-						PrepareCommitMsgSource::Message
+						self.load_commit_template()
 					};
+
+					let template =
+						self.is_empty() && commit_template.is_some();
+
+					self.prefilled_msg =
+						self.prefill_msg(commit_template);
+
+					if self.is_empty() {
+						if let Some(msg) = &self.prefilled_msg {
+							self.input.set_text(msg.clone());
+							self.input
+								.move_cursor_to_first_line_end();
+						}
+					}
 					self.input.set_title(strings::commit_title());
 
-					(Mode::Normal, msg_source)
+					(Mode::Normal, CommitMsgFor::New { template })
 				}
 			}
 		};
 
 		self.mode = mode;
+		self.prepare_commit_msg(msg_for)?;
 
-		let mut msg = self.input.get_text().to_string();
-		if let HookResult::NotOk(e) =
-			sync::hooks_prepare_commit_msg_with_timeout(
-				&self.repo.borrow(),
-				msg_source,
-				&mut msg,
-				self.get_hook_timeout(),
-			)? {
-			log::error!("prepare-commit-msg hook rejection: {e}",);
-		}
+		self.commit_msg_history_idx = 0;
+		self.input.show()?;
+
+		self.msg_preview_hash = None;
+		self.update_msg_preview();
+
+		Ok(())
+	}
+
+	/// opens the editor to squash the commits `base..=tip` into one,
+	/// pre-filled with their messages
+	pub fn open_squash(
+		&mut self,
+		base: CommitId,
+		tip: CommitId,
+	) -> Result<()> {
+		let msg =
+			sync::squash_message(&self.repo.borrow(), base, tip)?;
+
+		self.input.clear();
+		self.mode = Mode::Squash { base, tip };
+		self.prefilled_msg = None;
+		self.input.set_line_numbers(
+			self.options.borrow().commit_line_numbers(),
+		);
+		self.input.set_title(strings::commit_title_squash());
 		self.input.set_text(msg);
+		self.prepare_commit_msg(CommitMsgFor::New {
+			template: false,
+		})?;
 
 		self.commit_msg_history_idx = 0;
 		self.input.show()?;
 
+		self.msg_preview_hash = None;
+		self.update_msg_preview();
+
+		Ok(())
+	}
+
+	/// runs the prepare-commit-msg hook on the message
+	fn prepare_commit_msg(
+		&mut self,
+		msg_for: CommitMsgFor,
+	) -> Result<()> {
+		let source = sync::prepare_commit_msg_source(
+			&self.repo.borrow(),
+			msg_for,
+		)?;
+
+		let mut msg = self.input.get_text().to_string();
+		match sync::hooks_prepare_commit_msg_with_timeout(
+			&self.repo.borrow(),
+			source,
+			&mut msg,
+			self.get_hook_timeout(),
+		)? {
+			HookResult::NotOk(failure) => {
+				let e = failure.msg();
+				log::error!("prepare-commit-msg hook rejection: {e}");
+				self.queue.push(InternalEvent::ShowErrorMsg(
+					format!("prepare-commit-msg hook error:\n{e}"),
+				));
+			}
+			HookResult::TimedOut {
+				hook,
+				stdout,
+				stderr,
+				timeout,
+				source,
+			} => {
+				self.show_hook_timed_out(
+					"prepare-commit-msg",
+					timeout,
+					source,
+					&hook,
+					&stdout,
+					&stderr,
+				);
+			}
+			HookResult::Ok | HookResult::Cancelled { .. } => {}
+		}
+
+		// what a failing hook left behind is kept as well, it is what
+		// the user has to fix before committing
+		self.input.set_text(msg);
+
 		Ok(())
 	}
 
@@ -538,6 +984,23 @@ impl CommitPopup {
 	fn get_hook_timeout(&self) -> Option<Duration> {
 		self.options.borrow().hook_timeout()
 	}
+
+	fn show_hook_timed_out(
+		&self,
+		name: &str,
+		timeout: Duration,
+		source: TimeoutSource,
+		hook: &Path,
+		stdout: &str,
+		stderr: &str,
+	) {
+		log::error!("{name} hook timed out");
+		self.queue.push(InternalEvent::ShowErrorMsg(
+			strings::hook_timed_out(
+				name, timeout, source, hook, stdout, stderr,
+			),
+		));
+	}
 }
 
 impl DrawableComponent for CommitPopup {
@@ -546,6 +1009,8 @@ impl DrawableComponent for CommitPopup {
 			self.input.draw(f, rect)?;
 			self.draw_branch_name(f);
 			self.draw_warnings(f);
+			self.draw_msg_preview(f)?;
+			self.draw_hooks_running(f);
 		}
 
 		Ok(())
@@ -558,6 +1023,18 @@ impl Component for CommitPopup {
 		out: &mut Vec<CommandInfo>,
 		force_all: bool,
 	) -> CommandBlocking {
+		if self.running_hooks.is_some() {
+			out.push(CommandInfo::new(
+				strings::commands::commit_cancel_hooks(
+					&self.key_config,
+				),
+				true,
+				true,
+			));
+
+			return visibility_blocking(self);
+		}
+
 		self.input.commands(out, force_all);
 
 		if self.is_visible() || force_all {
@@ -604,6 +1081,12 @@ impl Component for CommitPopup {
 				true,
 			));
 
+			out.push(CommandInfo::new(
+				strings::commands::commit_presets(&self.key_config),
+				true,
+				true,
+			));
+
 			out.push(CommandInfo::new(
 				strings::commands::newline(&self.key_config),
 				true,
@@ -616,6 +1099,17 @@ impl Component for CommitPopup {
 
 	fn event(&mut self, ev: &Event) -> Result<EventState> {
 		if self.is_visible() {
+			if let Some(job) = &self.running_hooks {
+				// the message is not editable while it is checked
+				if let Event::Key(e) = ev {
+					if key_match(e, self.key_config.keys.exit_popup) {
+						job.cancel();
+					}
+				}
+
+				return Ok(EventState::Consumed);
+			}
+
 			if let Event::Key(e) = ev {
 				let input_consumed =
 					if key_match(e, self.key_config.keys.commit)
@@ -624,7 +1118,9 @@ impl Component for CommitPopup {
 						try_or_popup!(
 							self,
 							"commit error:",
-							self.commit()
+							self.commit_from(
+								CommitStep::StagedChecks
+							)
 						);
 						true
 					} else if key_match(
@@ -669,6 +1165,20 @@ impl Component for CommitPopup {
 					) {
 						self.signoff_commit();
 						true
+					} else if key_match(
+						e,
+						self.key_config.keys.commit_presets,
+					) {
+						self.queue.push(
+							InternalEvent::OpenCommitPresetsPopup(
+								self.presets
+									.presets()
+									.iter()
+									.map(|preset| preset.name.clone())
+									.collect(),
+							),
+						);
+						true
 					} else {
 						false
 					};
@@ -677,9 +1187,20 @@ impl Component for CommitPopup {
 					self.input.event(ev)?;
 				}
 
+				self.update_msg_preview();
+				crash::set_commit_draft(self.input.get_text());
+
 				// stop key event propagation
 				return Ok(EventState::Consumed);
 			}
+
+			if let Event::Paste(_) = ev {
+				self.input.event(ev)?;
+				self.update_msg_preview();
+				crash::set_commit_draft(self.input.get_text());
+
+				return Ok(EventState::Consumed);
+			}
 		}
 
 		Ok(EventState::NotConsumed)
@@ -698,3 +1219,32 @@ impl Component for CommitPopup {
 		Ok(())
 	}
 }
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn test_msg_preview_tick() {
+		let mut popup = CommitPopup::new(&Environment::test_env());
+		let now = Instant::now();
+
+		assert_eq!(popup.next_tick_in(now), None);
+
+		popup.msg_preview_due = Some(now + MSG_PREVIEW_DELAY);
+		assert_eq!(popup.next_tick_in(now), Some(MSG_PREVIEW_DELAY));
+		assert_eq!(
+			popup.next_tick_in(now + MSG_PREVIEW_DELAY * 2),
+			Some(Duration::ZERO)
+		);
+
+		// not due yet
+		popup.tick(now);
+		assert!(popup.msg_preview_due.is_some());
+
+		// due, but nothing is run for a hidden popup
+		popup.tick(now + MSG_PREVIEW_DELAY);
+		assert_eq!(popup.next_tick_in(now), None);
+		assert!(!popup.msg_preview.is_pending());
+	}
+}
@@ -0,0 +1,199 @@
+use crate::{
+	app::Environment,
+	components::{
+		visibility_blocking, CommandBlocking, CommandInfo, Component,
+		DrawableComponent, EventState,
+	},
+	keys::{key_match, SharedKeyConfig},
+	queue::{InternalEvent, Queue},
+	strings,
+	ui::{self, style::SharedTheme},
+};
+use anyhow::Result;
+use crossterm::event::Event;
+use ratatui::{
+	layout::Rect,
+	text::{Line, Span},
+	widgets::{Block, Borders, Clear, Paragraph},
+	Frame,
+};
+
+/// lets the user pick one of the commit message presets
+/// (see `commit_presets`) to apply to the commit message
+pub struct CommitPresetsPopup {
+	names: Vec<String>,
+	selection: usize,
+	visible: bool,
+	queue: Queue,
+	key_config: SharedKeyConfig,
+	theme: SharedTheme,
+}
+
+impl CommitPresetsPopup {
+	///
+	pub fn new(env: &Environment) -> Self {
+		Self {
+			names: Vec::new(),
+			selection: 0,
+			visible: false,
+			queue: env.queue.clone(),
+			key_config: env.key_config.clone(),
+			theme: env.theme.clone(),
+		}
+	}
+
+	///
+	pub fn open(&mut self, names: Vec<String>) -> Result<()> {
+		self.names = names;
+		self.selection = 0;
+		self.show()
+	}
+
+	fn move_selection(&mut self, up: bool) {
+		self.selection = if up {
+			self.selection.saturating_sub(1)
+		} else {
+			self.selection
+				.saturating_add(1)
+				.min(self.names.len().saturating_sub(1))
+		};
+	}
+
+	fn confirm(&mut self) {
+		if self.selection < self.names.len() {
+			self.queue.push(InternalEvent::ApplyCommitPreset(
+				self.selection,
+			));
+		}
+		self.hide();
+	}
+
+	fn get_text(&self) -> Vec<Line<'_>> {
+		if self.names.is_empty() {
+			return vec![Line::from(Span::styled(
+				strings::commit_presets_empty(&self.key_config),
+				self.theme.text(false, false),
+			))];
+		}
+
+		self.names
+			.iter()
+			.enumerate()
+			.map(|(idx, name)| {
+				Line::from(Span::styled(
+					name.as_str(),
+					self.theme.text(true, idx == self.selection),
+				))
+			})
+			.collect()
+	}
+}
+
+impl DrawableComponent for CommitPresetsPopup {
+	fn draw(&self, f: &mut Frame, area: Rect) -> Result<()> {
+		if self.is_visible() {
+			const WIDTH: u16 = 40;
+			const MAX_HEIGHT: u16 = 12;
+
+			let height = u16::try_from(self.names.len().max(1))
+				.unwrap_or(MAX_HEIGHT)
+				.saturating_add(2)
+				.min(MAX_HEIGHT);
+
+			let area =
+				ui::centered_rect_absolute(WIDTH, height, area);
+
+			let scroll =
+				u16::try_from(self.selection.saturating_sub(
+					usize::from(height.saturating_sub(3)),
+				))
+				.unwrap_or_default();
+
+			f.render_widget(Clear, area);
+			f.render_widget(
+				Paragraph::new(self.get_text())
+					.scroll((scroll, 0))
+					.block(
+						Block::default()
+							.borders(Borders::ALL)
+							.title(Span::styled(
+								strings::commit_presets_title(
+									&self.key_config,
+								),
+								self.theme.title(true),
+							))
+							.border_style(self.theme.block(true)),
+					),
+				area,
+			);
+		}
+
+		Ok(())
+	}
+}
+
+impl Component for CommitPresetsPopup {
+	fn commands(
+		&self,
+		out: &mut Vec<CommandInfo>,
+		force_all: bool,
+	) -> CommandBlocking {
+		if self.is_visible() || force_all {
+			out.push(
+				CommandInfo::new(
+					strings::commands::close_popup(&self.key_config),
+					true,
+					true,
+				)
+				.order(1),
+			);
+			out.push(CommandInfo::new(
+				strings::commands::commit_preset_apply(
+					&self.key_config,
+				),
+				!self.names.is_empty(),
+				true,
+			));
+		}
+
+		visibility_blocking(self)
+	}
+
+	fn event(&mut self, event: &Event) -> Result<EventState> {
+		if self.is_visible() {
+			if let Event::Key(key) = event {
+				if key_match(key, self.key_config.keys.exit_popup) {
+					self.hide();
+				} else if key_match(key, self.key_config.keys.enter) {
+					self.confirm();
+				} else if key_match(key, self.key_config.keys.move_up)
+				{
+					self.move_selection(true);
+				} else if key_match(
+					key,
+					self.key_config.keys.move_down,
+				) {
+					self.move_selection(false);
+				}
+			}
+
+			return Ok(EventState::Consumed);
+		}
+
+		Ok(EventState::NotConsumed)
+	}
+
+	fn is_visible(&self) -> bool {
+		self.visible
+	}
+
+	fn hide(&mut self) {
+		self.visible = false;
+	}
+
+	fn show(&mut self) -> Result<()> {
+		self.visible = true;
+
+		Ok(())
+	}
+}
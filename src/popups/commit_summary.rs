@@ -0,0 +1,226 @@
+use crate::{
+	app::Environment,
+	components::{
+		visibility_blocking, CommandBlocking, CommandInfo, Component,
+		DrawableComponent, EventState,
+	},
+	keys::{key_match, SharedKeyConfig},
+	popups::CommitStep,
+	queue::{InternalEvent, Queue},
+	strings,
+	ui::{self, style::SharedTheme},
+};
+use anyhow::Result;
+use asyncgit::sync::{StagedSummary, StagedWarning};
+use bytesize::ByteSize;
+use crossterm::event::Event;
+use easy_cast::Cast;
+use ratatui::{
+	layout::Rect,
+	text::{Line, Span},
+	widgets::{Block, Borders, Clear, Paragraph},
+	Frame,
+};
+
+/// warnings listed, the rest is only counted
+const MAX_WARNINGS: usize = 10;
+
+/// shows what the commit about to be made contains and what looks
+/// off about it, the commit is only made once confirmed
+pub struct CommitSummaryPopup {
+	summary: Option<StagedSummary>,
+	queue: Queue,
+	key_config: SharedKeyConfig,
+	theme: SharedTheme,
+}
+
+impl CommitSummaryPopup {
+	///
+	pub fn new(env: &Environment) -> Self {
+		Self {
+			summary: None,
+			queue: env.queue.clone(),
+			key_config: env.key_config.clone(),
+			theme: env.theme.clone(),
+		}
+	}
+
+	///
+	pub fn open(&mut self, summary: StagedSummary) {
+		self.summary = Some(summary);
+	}
+
+	fn get_text(&self, summary: &StagedSummary) -> Vec<Line<'_>> {
+		let mut txt = vec![
+			Line::from(vec![
+				Span::styled(
+					"branch:  ",
+					self.theme.text(true, false),
+				),
+				Span::styled(
+					summary.branch.as_deref().map_or_else(
+						strings::commit_summary_detached,
+						ToString::to_string,
+					),
+					self.theme.text(true, true),
+				),
+			]),
+			Line::from(vec![
+				Span::styled(
+					"changes: ",
+					self.theme.text(true, false),
+				),
+				Span::styled(
+					strings::commit_summary_changes(
+						summary.files,
+						summary.insertions,
+						summary.deletions,
+					),
+					self.theme.text(true, true),
+				),
+			]),
+			Line::default(),
+		];
+
+		if summary.warnings.is_empty() {
+			txt.push(Line::from(Span::styled(
+				strings::commit_summary_no_warnings(),
+				self.theme.text(true, false),
+			)));
+		}
+
+		txt.extend(summary.warnings.iter().take(MAX_WARNINGS).map(
+			|warning| {
+				Line::from(Span::styled(
+					Self::warning_text(warning),
+					self.theme.text_danger(),
+				))
+			},
+		));
+
+		if summary.warnings.len() > MAX_WARNINGS {
+			txt.push(Line::from(Span::styled(
+				strings::commit_summary_more_warnings(
+					summary.warnings.len() - MAX_WARNINGS,
+				),
+				self.theme.text_danger(),
+			)));
+		}
+
+		if summary.truncated {
+			txt.push(Line::from(Span::styled(
+				strings::commit_summary_truncated(),
+				self.theme.text(true, false),
+			)));
+		}
+
+		txt
+	}
+
+	fn warning_text(warning: &StagedWarning) -> String {
+		match warning {
+			StagedWarning::LargeFile { path, size } => {
+				format!("large file: {path} ({})", ByteSize::b(*size))
+			}
+			StagedWarning::Secret { path } => {
+				format!("possible secret: {path}")
+			}
+			StagedWarning::ConflictMarker { path, line } => {
+				format!("conflict marker: {path}:{line}")
+			}
+		}
+	}
+}
+
+impl DrawableComponent for CommitSummaryPopup {
+	fn draw(&self, f: &mut Frame, area: Rect) -> Result<()> {
+		if let Some(summary) = &self.summary {
+			const WIDTH: u16 = 60;
+
+			let txt = self.get_text(summary);
+			let height: u16 = (txt.len() + 2).cast();
+			let area =
+				ui::centered_rect_absolute(WIDTH, height, area);
+
+			f.render_widget(Clear, area);
+			f.render_widget(
+				Paragraph::new(txt).block(
+					Block::default()
+						.borders(Borders::ALL)
+						.title(Span::styled(
+							strings::commit_summary_title(
+								&self.key_config,
+							),
+							self.theme.title(true),
+						))
+						.border_style(self.theme.block(true)),
+				),
+				area,
+			);
+		}
+
+		Ok(())
+	}
+}
+
+impl Component for CommitSummaryPopup {
+	fn commands(
+		&self,
+		out: &mut Vec<CommandInfo>,
+		force_all: bool,
+	) -> CommandBlocking {
+		if self.is_visible() || force_all {
+			out.push(
+				CommandInfo::new(
+					strings::commands::confirm_commit_summary(
+						&self.key_config,
+					),
+					true,
+					true,
+				)
+				.order(1),
+			);
+			out.push(
+				CommandInfo::new(
+					strings::commands::close_popup(&self.key_config),
+					true,
+					true,
+				)
+				.order(1),
+			);
+		}
+
+		visibility_blocking(self)
+	}
+
+	fn event(&mut self, event: &Event) -> Result<EventState> {
+		if self.is_visible() {
+			if let Event::Key(key) = event {
+				if key_match(key, self.key_config.keys.exit_popup) {
+					self.hide();
+				} else if key_match(key, self.key_config.keys.enter) {
+					self.hide();
+					self.queue.push(InternalEvent::ContinueCommit(
+						CommitStep::Commit,
+					));
+				}
+			}
+
+			return Ok(EventState::Consumed);
+		}
+
+		Ok(EventState::NotConsumed)
+	}
+
+	fn is_visible(&self) -> bool {
+		self.summary.is_some()
+	}
+
+	fn hide(&mut self) {
+		self.summary = None;
+	}
+
+	fn show(&mut self) -> Result<()> {
+		Ok(())
+	}
+}
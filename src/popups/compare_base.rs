@@ -0,0 +1,123 @@
+use crate::components::{
+	visibility_blocking, CommandBlocking, CommandInfo, Component,
+	DrawableComponent, EventState, InputType, TextInputComponent,
+};
+use crate::{
+	app::Environment,
+	keys::{key_match, SharedKeyConfig},
+	options::SharedOptions,
+	queue::{InternalEvent, NeedsUpdate, Queue},
+	strings,
+};
+use anyhow::Result;
+use crossterm::event::Event;
+use ratatui::{layout::Rect, Frame};
+
+/// lets the user pick the branch the current branch is compared
+/// to in the status tab (stored per repo in the options)
+pub struct CompareBasePopup {
+	input: TextInputComponent,
+	queue: Queue,
+	options: SharedOptions,
+	key_config: SharedKeyConfig,
+}
+
+impl DrawableComponent for CompareBasePopup {
+	fn draw(&self, f: &mut Frame, rect: Rect) -> Result<()> {
+		if self.is_visible() {
+			self.input.draw(f, rect)?;
+		}
+		Ok(())
+	}
+}
+
+impl Component for CompareBasePopup {
+	fn commands(
+		&self,
+		out: &mut Vec<CommandInfo>,
+		force_all: bool,
+	) -> CommandBlocking {
+		if self.is_visible() || force_all {
+			self.input.commands(out, force_all);
+
+			out.push(CommandInfo::new(
+				strings::commands::compare_base_confirm(
+					&self.key_config,
+				),
+				true,
+				true,
+			));
+		}
+
+		visibility_blocking(self)
+	}
+
+	fn event(&mut self, ev: &Event) -> Result<EventState> {
+		if self.is_visible() {
+			if self.input.event(ev)?.is_consumed() {
+				return Ok(EventState::Consumed);
+			}
+
+			if let Event::Key(e) = ev {
+				if key_match(e, self.key_config.keys.enter) {
+					self.set_base();
+				}
+
+				return Ok(EventState::Consumed);
+			}
+		}
+		Ok(EventState::NotConsumed)
+	}
+
+	fn is_visible(&self) -> bool {
+		self.input.is_visible()
+	}
+
+	fn hide(&mut self) {
+		self.input.hide();
+	}
+
+	fn show(&mut self) -> Result<()> {
+		self.input.show()?;
+
+		Ok(())
+	}
+}
+
+impl CompareBasePopup {
+	///
+	pub fn new(env: &Environment) -> Self {
+		Self {
+			queue: env.queue.clone(),
+			options: env.options.clone(),
+			input: TextInputComponent::new(
+				env,
+				&strings::compare_base_popup_title(&env.key_config),
+				&strings::compare_base_popup_msg(&env.key_config),
+				true,
+			)
+			.with_input_type(InputType::Singleline),
+			key_config: env.key_config.clone(),
+		}
+	}
+
+	///
+	pub fn open(&mut self) -> Result<()> {
+		self.input.set_text(
+			self.options.borrow().compare_base().unwrap_or_default(),
+		);
+		self.show()?;
+
+		Ok(())
+	}
+
+	fn set_base(&mut self) {
+		let base = self.input.get_text().trim();
+		let base = (!base.is_empty()).then(|| base.to_string());
+
+		self.options.borrow_mut().set_compare_base(base);
+
+		self.hide();
+		self.queue.push(InternalEvent::Update(NeedsUpdate::ALL));
+	}
+}
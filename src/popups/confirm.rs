@@ -125,6 +125,7 @@ impl ConfirmPopup {
 		self.hide();
 	}
 
+	#[allow(clippy::too_many_lines)]
 	fn get_text(&self) -> (String, String) {
 		if let Some(ref a) = self.target {
 			return match a {
@@ -214,6 +215,18 @@ impl ConfirmPopup {
                     strings::confirm_title_undo_commit(),
                     strings::confirm_msg_undo_commit(),
                 ),
+                Action::DropCommit(id, info) => (
+                    strings::confirm_title_drop_commit(),
+                    strings::confirm_msg_drop_commit(id, info),
+                ),
+                Action::CheckoutFile(id, path) => (
+                    strings::confirm_title_checkout_file(),
+                    strings::confirm_msg_checkout_file(id, path),
+                ),
+                Action::LargePaste(text) => (
+                    strings::confirm_title_large_paste(),
+                    strings::confirm_msg_large_paste(text),
+                ),
             };
 		}
 
@@ -10,14 +10,23 @@ use crate::{
 	ui::style::SharedTheme,
 };
 use anyhow::Result;
-use asyncgit::sync::{self, RepoPathRef};
+use asyncgit::sync::{self, RepoPathRef, RepoState};
 use crossterm::event::Event;
 use easy_cast::Cast;
 use ratatui::{layout::Rect, widgets::Paragraph, Frame};
 
+/// the branch is created to move the uncommitted changes to, see
+/// [`sync::move_changes_to_branch`]
+struct MoveChanges {
+	/// commits the checked out branch has beyond its upstream
+	local_commits: usize,
+	carry_commits: bool,
+}
+
 pub struct CreateBranchPopup {
 	repo: RepoPathRef,
 	input: TextInputComponent,
+	move_changes: Option<MoveChanges>,
 	queue: Queue,
 	key_config: SharedKeyConfig,
 	theme: SharedTheme,
@@ -43,13 +52,30 @@ impl Component for CreateBranchPopup {
 		if self.is_visible() || force_all {
 			self.input.commands(out, force_all);
 
-			out.push(CommandInfo::new(
-				strings::commands::create_branch_confirm_msg(
-					&self.key_config,
-				),
-				true,
-				true,
-			));
+			if let Some(move_changes) = &self.move_changes {
+				out.push(CommandInfo::new(
+					strings::commands::move_changes_confirm_msg(
+						&self.key_config,
+					),
+					true,
+					true,
+				));
+				out.push(CommandInfo::new(
+					strings::commands::move_changes_carry_commits(
+						&self.key_config,
+					),
+					move_changes.local_commits > 0,
+					true,
+				));
+			} else {
+				out.push(CommandInfo::new(
+					strings::commands::create_branch_confirm_msg(
+						&self.key_config,
+					),
+					true,
+					true,
+				));
+			}
 		}
 
 		visibility_blocking(self)
@@ -64,6 +90,11 @@ impl Component for CreateBranchPopup {
 			if let Event::Key(e) = ev {
 				if key_match(e, self.key_config.keys.enter) {
 					self.create_branch();
+				} else if key_match(
+					e,
+					self.key_config.keys.move_changes_carry_commits,
+				) {
+					self.toggle_carry_commits();
 				}
 
 				return Ok(EventState::Consumed);
@@ -99,6 +130,7 @@ impl CreateBranchPopup {
 				true,
 			)
 			.with_input_type(InputType::Singleline),
+			move_changes: None,
 			theme: env.theme.clone(),
 			key_config: env.key_config.clone(),
 			repo: env.repo.clone(),
@@ -107,17 +139,84 @@ impl CreateBranchPopup {
 
 	///
 	pub fn open(&mut self) -> Result<()> {
+		self.move_changes = None;
+		self.input.set_title(strings::create_branch_popup_title(
+			&self.key_config,
+		));
 		self.show()?;
 
 		Ok(())
 	}
 
+	/// opens the popup to move the uncommitted changes to the new
+	/// branch, refused while a merge, rebase or revert is pending
+	pub fn open_move_changes(&mut self) -> Result<()> {
+		let repo = self.repo.borrow().clone();
+
+		let state = sync::repo_state(&repo)?;
+		if state != RepoState::Clean {
+			self.queue.push(InternalEvent::ShowErrorMsg(
+				strings::move_changes_pending(
+					&format!("{state:?}").to_lowercase(),
+				),
+			));
+			return Ok(());
+		}
+
+		let on_branch = sync::get_branches_info(&repo, true)?
+			.iter()
+			.any(|branch| {
+				branch.local_details().is_some_and(|b| b.is_head)
+			});
+		if !on_branch {
+			self.queue.push(InternalEvent::ShowErrorMsg(
+				strings::move_changes_detached(),
+			));
+			return Ok(());
+		}
+
+		self.move_changes = Some(MoveChanges {
+			local_commits: sync::local_commits(&repo)?,
+			carry_commits: false,
+		});
+		self.update_title();
+		self.show()?;
+
+		Ok(())
+	}
+
+	fn toggle_carry_commits(&mut self) {
+		if let Some(move_changes) = &mut self.move_changes {
+			if move_changes.local_commits > 0 {
+				move_changes.carry_commits =
+					!move_changes.carry_commits;
+				self.update_title();
+			}
+		}
+	}
+
+	fn update_title(&mut self) {
+		if let Some(move_changes) = &self.move_changes {
+			self.input.set_title(strings::move_changes_popup_title(
+				move_changes.local_commits,
+				move_changes.carry_commits,
+			));
+		}
+	}
+
 	///
 	pub fn create_branch(&mut self) {
-		let res = sync::create_branch(
-			&self.repo.borrow(),
-			self.input.get_text(),
-		);
+		let res = match &self.move_changes {
+			Some(move_changes) => sync::move_changes_to_branch(
+				&self.repo.borrow(),
+				self.input.get_text(),
+				move_changes.carry_commits,
+			),
+			None => sync::create_branch(
+				&self.repo.borrow(),
+				self.input.get_text(),
+			),
+		};
 
 		self.input.clear();
 		self.hide();
@@ -13,8 +13,11 @@ use asyncgit::sync::{
 	get_config_string, utils::repo_work_dir, RepoPath,
 };
 use crossterm::{
-	event::Event,
-	terminal::{EnterAlternateScreen, LeaveAlternateScreen},
+	event::{DisableBracketedPaste, EnableBracketedPaste, Event},
+	terminal::{
+		disable_raw_mode, enable_raw_mode, EnterAlternateScreen,
+		LeaveAlternateScreen,
+	},
 	ExecutableCommand,
 };
 use ratatui::{
@@ -62,8 +65,11 @@ impl ExternalEditorPopup {
 		}
 
 		io::stdout().execute(LeaveAlternateScreen)?;
+		// editors handle pastes on their own
+		let _ = io::stdout().execute(DisableBracketedPaste);
 		defer! {
 			io::stdout().execute(EnterAlternateScreen).expect("reset terminal");
+			let _ = io::stdout().execute(EnableBracketedPaste);
 		}
 
 		let environment_options = ["GIT_EDITOR", "VISUAL", "EDITOR"];
@@ -117,6 +123,36 @@ impl ExternalEditorPopup {
 
 		Ok(())
 	}
+
+	/// runs an interactive shell in the working directory of `repo`,
+	/// e.g. to deal with an operation started by another tool
+	pub fn open_shell(repo: &RepoPath) -> Result<()> {
+		let work_dir = repo_work_dir(repo)?;
+
+		io::stdout().execute(LeaveAlternateScreen)?;
+		let _ = io::stdout().execute(DisableBracketedPaste);
+		// unlike editors shells expect a terminal in cooked mode
+		disable_raw_mode()?;
+		defer! {
+			enable_raw_mode().expect("reset terminal");
+			io::stdout().execute(EnterAlternateScreen).expect("reset terminal");
+			let _ = io::stdout().execute(EnableBracketedPaste);
+		}
+
+		let shell = if cfg!(windows) {
+			env::var("COMSPEC")
+				.unwrap_or_else(|_| String::from("cmd"))
+		} else {
+			env::var("SHELL").unwrap_or_else(|_| String::from("sh"))
+		};
+
+		Command::new(&shell)
+			.current_dir(work_dir)
+			.status()
+			.map_err(|e| anyhow!("\"{}\": {}", shell, e))?;
+
+		Ok(())
+	}
 }
 
 impl DrawableComponent for ExternalEditorPopup {
@@ -5,6 +5,7 @@ use crate::{
 		CredComponent, DrawableComponent, EventState,
 	},
 	keys::SharedKeyConfig,
+	options::SharedOptions,
 	queue::{InternalEvent, NeedsUpdate, Queue},
 	strings,
 	ui::{self, style::SharedTheme},
@@ -17,7 +18,7 @@ use asyncgit::{
 			extract_username_password, need_username_password,
 			BasicAuthCredential,
 		},
-		RepoPathRef,
+		FetchTarget, RepoPathRef,
 	},
 	AsyncFetchJob, AsyncGitNotification, ProgressPercent,
 };
@@ -36,7 +37,12 @@ pub struct FetchPopup {
 	async_fetch: AsyncSingleJob<AsyncFetchJob>,
 	progress: Option<ProgressPercent>,
 	pending: bool,
+	/// fetching only this instead of everything
+	target: Option<FetchTarget>,
+	/// fetching only the tags, see [`Self::fetch_tags`]
+	only_tags: bool,
 	queue: Queue,
+	options: SharedOptions,
 	theme: SharedTheme,
 	key_config: SharedKeyConfig,
 	input_cred: CredComponent,
@@ -48,18 +54,49 @@ impl FetchPopup {
 		Self {
 			queue: env.queue.clone(),
 			pending: false,
+			target: None,
+			only_tags: false,
 			visible: false,
 			async_fetch: AsyncSingleJob::new(env.sender_git.clone()),
 			progress: None,
 			input_cred: CredComponent::new(env),
 			theme: env.theme.clone(),
 			key_config: env.key_config.clone(),
+			options: env.options.clone(),
 			repo: env.repo.clone(),
 		}
 	}
 
 	///
 	pub fn fetch(&mut self) -> Result<()> {
+		self.target = None;
+		self.only_tags = false;
+		self.start()
+	}
+
+	/// fetches every tag of all remotes but no branches, see
+	/// [`sync::fetch_tags`]
+	///
+	/// [`sync::fetch_tags`]: asyncgit::sync::fetch_tags
+	pub fn fetch_tags(&mut self) -> Result<()> {
+		self.target = None;
+		self.only_tags = true;
+		self.start()
+	}
+
+	/// fetches only `target`, see [`sync::fetch_target`]
+	///
+	/// [`sync::fetch_target`]: asyncgit::sync::fetch_target
+	pub fn fetch_target(
+		&mut self,
+		target: FetchTarget,
+	) -> Result<()> {
+		self.target = Some(target);
+		self.only_tags = false;
+		self.start()
+	}
+
+	fn start(&mut self) -> Result<()> {
 		self.show()?;
 		if need_username_password(&self.repo.borrow())? {
 			let cred = extract_username_password(&self.repo.borrow())
@@ -83,10 +120,15 @@ impl FetchPopup {
 		self.pending = true;
 		self.progress = None;
 		self.progress = Some(ProgressPercent::empty());
-		self.async_fetch.spawn(AsyncFetchJob::new(
-			self.repo.borrow().clone(),
-			cred,
-		));
+		let job =
+			AsyncFetchJob::new(self.repo.borrow().clone(), cred);
+		self.async_fetch.spawn(match self.target.clone() {
+			Some(target) => job.with_target(target),
+			None if self.only_tags => job.only_tags(),
+			None => job.with_tags(
+				self.options.borrow().fetch_tags_overrides(),
+			),
+		});
 	}
 
 	///
@@ -110,6 +152,32 @@ impl FetchPopup {
 			self.hide();
 			self.queue
 				.push(InternalEvent::Update(NeedsUpdate::BRANCHES));
+
+			if let Some(result) = self
+				.async_fetch
+				.take_last()
+				.and_then(|job| job.result())
+			{
+				self.report(result);
+			}
+		}
+	}
+
+	/// errors and the amount of tags created, which is always
+	/// reported when fetching only the tags
+	fn report(&self, result: asyncgit::Result<usize>) {
+		match result {
+			Err(e) => {
+				self.queue.push(InternalEvent::ShowErrorMsg(
+					format!("fetch failed:\n{e}"),
+				));
+			}
+			Ok(created) if created > 0 || self.only_tags => {
+				self.queue.push(InternalEvent::ShowInfoMsg(
+					strings::fetch_tags_created(created),
+				));
+			}
+			Ok(_) => (),
 		}
 	}
 }
@@ -0,0 +1,219 @@
+use crate::components::{
+	visibility_blocking, CommandBlocking, CommandInfo, Component,
+	DrawableComponent, EventState, InputType, TextInputComponent,
+};
+use crate::{
+	app::Environment,
+	keys::{key_match, SharedKeyConfig},
+	queue::{InternalEvent, Queue},
+	strings,
+	ui::style::SharedTheme,
+};
+use anyhow::Result;
+use asyncgit::sync::{self, FetchTarget, RepoPathRef};
+use crossterm::event::Event;
+use easy_cast::Cast;
+use ratatui::{layout::Rect, widgets::Paragraph, Frame};
+
+/// asks for the single branch or refspec to fetch, see
+/// [`FetchTarget::parse`]
+pub struct FetchRefPopup {
+	repo: RepoPathRef,
+	input: TextInputComponent,
+	/// the remote branches known locally, offered for completion
+	remote_branches: Vec<String>,
+	/// what was typed before completing and the next match to use
+	completion: Option<(String, usize)>,
+	queue: Queue,
+	key_config: SharedKeyConfig,
+	theme: SharedTheme,
+}
+
+impl DrawableComponent for FetchRefPopup {
+	fn draw(&self, f: &mut Frame, rect: Rect) -> Result<()> {
+		if self.is_visible() {
+			self.input.draw(f, rect)?;
+			self.draw_warnings(f);
+		}
+
+		Ok(())
+	}
+}
+
+impl Component for FetchRefPopup {
+	fn commands(
+		&self,
+		out: &mut Vec<CommandInfo>,
+		force_all: bool,
+	) -> CommandBlocking {
+		if self.is_visible() || force_all {
+			self.input.commands(out, force_all);
+
+			out.push(CommandInfo::new(
+				strings::commands::fetch_ref_complete(
+					&self.key_config,
+				),
+				!self.remote_branches.is_empty(),
+				true,
+			));
+			out.push(CommandInfo::new(
+				strings::commands::fetch_ref_confirm(
+					&self.key_config,
+				),
+				true,
+				true,
+			));
+		}
+
+		visibility_blocking(self)
+	}
+
+	fn event(&mut self, ev: &Event) -> Result<EventState> {
+		if self.is_visible() {
+			if let Event::Key(e) = ev {
+				if key_match(e, self.key_config.keys.tab_toggle) {
+					self.complete();
+					return Ok(EventState::Consumed);
+				}
+			}
+
+			if self.input.event(ev)?.is_consumed() {
+				self.completion = None;
+				return Ok(EventState::Consumed);
+			}
+
+			if let Event::Key(e) = ev {
+				if key_match(e, self.key_config.keys.enter) {
+					self.confirm();
+				}
+
+				return Ok(EventState::Consumed);
+			}
+		}
+		Ok(EventState::NotConsumed)
+	}
+
+	fn is_visible(&self) -> bool {
+		self.input.is_visible()
+	}
+
+	fn hide(&mut self) {
+		self.input.hide();
+	}
+
+	fn show(&mut self) -> Result<()> {
+		self.input.show()?;
+
+		Ok(())
+	}
+}
+
+impl FetchRefPopup {
+	///
+	pub fn new(env: &Environment) -> Self {
+		Self {
+			queue: env.queue.clone(),
+			input: TextInputComponent::new(
+				env,
+				&strings::fetch_ref_popup_title(&env.key_config),
+				&strings::fetch_ref_popup_msg(&env.key_config),
+				true,
+			)
+			.with_input_type(InputType::Singleline),
+			remote_branches: Vec::new(),
+			completion: None,
+			theme: env.theme.clone(),
+			key_config: env.key_config.clone(),
+			repo: env.repo.clone(),
+		}
+	}
+
+	///
+	pub fn open(&mut self) -> Result<()> {
+		self.remote_branches =
+			sync::get_branches_info(&self.repo.borrow(), false)?
+				.into_iter()
+				.map(|branch| branch.name)
+				.filter(|name| !name.ends_with("/HEAD"))
+				.collect();
+		self.completion = None;
+		self.input.clear();
+		self.show()?;
+
+		Ok(())
+	}
+
+	/// replaces the input by the next remote branch starting with what
+	/// was typed, going round once all were offered
+	fn complete(&mut self) {
+		let (typed, idx) =
+			self.completion.take().unwrap_or_else(|| {
+				(self.input.get_text().to_string(), 0)
+			});
+
+		let matches = self
+			.remote_branches
+			.iter()
+			.filter(|branch| branch.starts_with(typed.as_str()))
+			.collect::<Vec<_>>();
+
+		if let Some(branch) = matches.get(idx % matches.len().max(1))
+		{
+			self.input.set_text((*branch).clone());
+			self.completion = Some((typed, idx + 1));
+		}
+	}
+
+	fn confirm(&mut self) {
+		let target = FetchTarget::parse(
+			&self.repo.borrow(),
+			self.input.get_text(),
+		);
+
+		match target {
+			Ok(target) => {
+				self.hide();
+				self.queue.push(InternalEvent::FetchTarget(target));
+			}
+			Err(e) => {
+				// kept open to fix the typo
+				self.queue.push(InternalEvent::ShowErrorMsg(
+					format!("fetch error:\n{e}"),
+				));
+			}
+		}
+	}
+
+	/// the refspec is the last word, an optional remote comes first
+	fn draw_warnings(&self, f: &mut Frame) {
+		let refspec = self
+			.input
+			.get_text()
+			.split_whitespace()
+			.next_back()
+			.unwrap_or_default();
+
+		if !refspec.is_empty()
+			&& sync::validate_refspec(refspec).is_err()
+		{
+			let msg = strings::fetch_ref_invalid();
+			let msg_length: u16 = msg.len().cast();
+			let w =
+				Paragraph::new(msg).style(self.theme.text_danger());
+
+			let rect = {
+				let mut rect = self.input.get_area();
+				rect.y += rect.height.saturating_sub(1);
+				rect.height = 1;
+				let offset =
+					rect.width.saturating_sub(msg_length + 1);
+				rect.width = rect.width.saturating_sub(offset + 1);
+				rect.x += offset;
+
+				rect
+			};
+
+			f.render_widget(w, rect);
+		}
+	}
+}
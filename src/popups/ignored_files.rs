@@ -0,0 +1,205 @@
+use crate::{
+	app::Environment,
+	components::{
+		visibility_blocking, CommandBlocking, CommandInfo, Component,
+		DrawableComponent, EventState,
+	},
+	keys::{key_match, SharedKeyConfig},
+	queue::{InternalEvent, Queue},
+	strings,
+	ui::{self, style::SharedTheme},
+};
+use anyhow::Result;
+use asyncgit::sync::{self, IgnoredFile, RepoPathRef};
+use crossterm::event::Event;
+use ratatui::{
+	layout::Rect,
+	text::{Line, Span},
+	widgets::{Block, Borders, Clear, Paragraph},
+	Frame,
+};
+
+/// lists the ignored files of the work dir along with the rule ignoring
+/// them (see `sync::get_ignored_files`). the selected one can be
+/// handed to the [`CheckIgnorePopup`](super::CheckIgnorePopup)
+pub struct IgnoredFilesPopup {
+	repo: RepoPathRef,
+	files: Vec<IgnoredFile>,
+	selection: usize,
+	visible: bool,
+	queue: Queue,
+	key_config: SharedKeyConfig,
+	theme: SharedTheme,
+}
+
+impl IgnoredFilesPopup {
+	///
+	pub fn new(env: &Environment) -> Self {
+		Self {
+			repo: env.repo.clone(),
+			files: Vec::new(),
+			selection: 0,
+			visible: false,
+			queue: env.queue.clone(),
+			key_config: env.key_config.clone(),
+			theme: env.theme.clone(),
+		}
+	}
+
+	///
+	pub fn open(&mut self) -> Result<()> {
+		self.files = sync::get_ignored_files(&self.repo.borrow())?;
+		self.selection = 0;
+		self.show()
+	}
+
+	fn move_selection(&mut self, up: bool) {
+		self.selection = if up {
+			self.selection.saturating_sub(1)
+		} else {
+			self.selection
+				.saturating_add(1)
+				.min(self.files.len().saturating_sub(1))
+		};
+	}
+
+	fn get_text(&self, height: usize) -> Vec<Line<'_>> {
+		if self.files.is_empty() {
+			return vec![Line::from(Span::styled(
+				strings::ignored_files_empty(&self.key_config),
+				self.theme.text(false, false),
+			))];
+		}
+
+		let skip =
+			self.selection.saturating_sub(height.saturating_sub(1));
+
+		self.files
+			.iter()
+			.enumerate()
+			.skip(skip)
+			.take(height)
+			.map(|(idx, file)| {
+				let selected = idx == self.selection;
+				let rule = file.rule.as_ref().map_or_else(
+					|| strings::ignored_without_rule().to_string(),
+					strings::ignore_rule,
+				);
+
+				Line::from(vec![
+					Span::styled(
+						file.path.as_str(),
+						self.theme.text(true, selected),
+					),
+					Span::styled(
+						format!("  {rule}"),
+						self.theme.text(false, selected),
+					),
+				])
+			})
+			.collect()
+	}
+}
+
+impl DrawableComponent for IgnoredFilesPopup {
+	fn draw(&self, f: &mut Frame, area: Rect) -> Result<()> {
+		if self.is_visible() {
+			let area = ui::centered_rect(70, 60, area);
+			let height = usize::from(area.height.saturating_sub(2));
+
+			f.render_widget(Clear, area);
+			f.render_widget(
+				Paragraph::new(self.get_text(height)).block(
+					Block::default()
+						.borders(Borders::ALL)
+						.title(Span::styled(
+							strings::ignored_files_title(
+								&self.key_config,
+							),
+							self.theme.title(true),
+						))
+						.border_style(self.theme.block(true)),
+				),
+				area,
+			);
+		}
+
+		Ok(())
+	}
+}
+
+impl Component for IgnoredFilesPopup {
+	fn commands(
+		&self,
+		out: &mut Vec<CommandInfo>,
+		force_all: bool,
+	) -> CommandBlocking {
+		if self.is_visible() || force_all {
+			out.push(CommandInfo::new(
+				strings::commands::ignored_files_check(
+					&self.key_config,
+				),
+				!self.files.is_empty(),
+				true,
+			));
+			out.push(
+				CommandInfo::new(
+					strings::commands::close_popup(&self.key_config),
+					true,
+					true,
+				)
+				.order(1),
+			);
+		}
+
+		visibility_blocking(self)
+	}
+
+	fn event(&mut self, event: &Event) -> Result<EventState> {
+		if self.is_visible() {
+			if let Event::Key(key) = event {
+				if key_match(key, self.key_config.keys.exit_popup)
+					|| key_match(
+						key,
+						self.key_config.keys.status_show_ignored,
+					) {
+					self.hide();
+				} else if key_match(key, self.key_config.keys.move_up)
+				{
+					self.move_selection(true);
+				} else if key_match(
+					key,
+					self.key_config.keys.move_down,
+				) {
+					self.move_selection(false);
+				} else if key_match(key, self.key_config.keys.enter) {
+					if let Some(file) = self.files.get(self.selection)
+					{
+						self.queue.push(InternalEvent::CheckIgnore(
+							Some(file.path.clone()),
+						));
+						self.hide();
+					}
+				}
+			}
+
+			return Ok(EventState::Consumed);
+		}
+
+		Ok(EventState::NotConsumed)
+	}
+
+	fn is_visible(&self) -> bool {
+		self.visible
+	}
+
+	fn hide(&mut self) {
+		self.visible = false;
+	}
+
+	fn show(&mut self) -> Result<()> {
+		self.visible = true;
+
+		Ok(())
+	}
+}
@@ -494,6 +494,10 @@ impl LogSearchPopupPopup {
 			} else if !self.option_selected() {
 				self.find_text.event(event)?;
 			}
+		} else if let Event::Paste(_) = &event {
+			if !self.option_selected() {
+				self.find_text.event(event)?;
+			}
 		}
 
 		Ok(EventState::Consumed)
@@ -504,19 +508,29 @@ impl LogSearchPopupPopup {
 		&mut self,
 		event: &crossterm::event::Event,
 	) -> Result<EventState> {
-		if let Event::Key(key) = &event {
+		let consumed = if let Event::Key(key) = &event {
 			if key_match(key, self.key_config.keys.exit_popup) {
 				self.set_mode(&PopupMode::Search);
+				false
 			} else if key_match(key, self.key_config.keys.enter)
 				&& self.is_valid()
 			{
 				self.execute_confirm();
-			} else if self.find_text.event(event)?.is_consumed() {
-				self.validate_commit_sha();
-				self.find_text.enabled(
-					!self.find_text.get_text().trim().is_empty(),
-				);
+				false
+			} else {
+				self.find_text.event(event)?.is_consumed()
 			}
+		} else if let Event::Paste(_) = &event {
+			self.find_text.event(event)?.is_consumed()
+		} else {
+			false
+		};
+
+		if consumed {
+			self.validate_commit_sha();
+			self.find_text.enabled(
+				!self.find_text.get_text().trim().is_empty(),
+			);
 		}
 
 		Ok(EventState::Consumed)
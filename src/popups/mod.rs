@@ -1,27 +1,38 @@
 mod blame_file;
+mod branch_description;
 mod branchlist;
+mod check_ignore;
 mod commit;
+mod commit_presets;
+mod commit_summary;
+mod compare_base;
 mod compare_commits;
 mod confirm;
 mod create_branch;
 mod create_remote;
 mod externaleditor;
 mod fetch;
+mod fetch_ref;
 mod file_revlog;
 mod fuzzy_find;
 mod help;
+mod ignored_files;
 mod inspect_commit;
 mod log_search;
 mod msg;
 mod options;
+mod pending_operation;
+mod performance;
 mod pull;
 mod push;
 mod push_tags;
+mod recent_branches;
 mod remotelist;
 mod rename_branch;
 mod rename_remote;
 mod reset;
 mod revision_files;
+mod staged_checks;
 mod stashmsg;
 mod submodules;
 mod tag_commit;
@@ -29,29 +40,42 @@ mod taglist;
 mod update_remote_url;
 
 pub use blame_file::{BlameFileOpen, BlameFilePopup};
+pub use branch_description::BranchDescriptionPopup;
 pub use branchlist::BranchListPopup;
-pub use commit::CommitPopup;
+pub use check_ignore::CheckIgnorePopup;
+pub use commit::{CommitPopup, CommitStep};
+pub use commit_presets::CommitPresetsPopup;
+pub use commit_summary::CommitSummaryPopup;
+pub use compare_base::CompareBasePopup;
 pub use compare_commits::CompareCommitsPopup;
 pub use confirm::ConfirmPopup;
 pub use create_branch::CreateBranchPopup;
 pub use create_remote::CreateRemotePopup;
 pub use externaleditor::ExternalEditorPopup;
 pub use fetch::FetchPopup;
+pub use fetch_ref::FetchRefPopup;
 pub use file_revlog::{FileRevOpen, FileRevlogPopup};
 pub use fuzzy_find::FuzzyFindPopup;
 pub use help::HelpPopup;
+pub use ignored_files::IgnoredFilesPopup;
 pub use inspect_commit::{InspectCommitOpen, InspectCommitPopup};
 pub use log_search::LogSearchPopupPopup;
 pub use msg::MsgPopup;
 pub use options::{AppOption, OptionsPopup};
+pub use pending_operation::{
+	block_pending_operation, PendingOperationPopup,
+};
+pub use performance::PerformancePopup;
 pub use pull::PullPopup;
 pub use push::PushPopup;
 pub use push_tags::PushTagsPopup;
+pub use recent_branches::RecentBranchesPopup;
 pub use remotelist::RemoteListPopup;
 pub use rename_branch::RenameBranchPopup;
 pub use rename_remote::RenameRemotePopup;
 pub use reset::ResetPopup;
 pub use revision_files::{FileTreeOpen, RevisionFilesPopup};
+pub use staged_checks::StagedChecksPopup;
 pub use stashmsg::StashMsgPopup;
 pub use submodules::SubmodulesListPopup;
 pub use tag_commit::TagCommitPopup;
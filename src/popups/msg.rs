@@ -1,8 +1,7 @@
 use crate::components::{
 	visibility_blocking, CommandBlocking, CommandInfo, Component,
-	DrawableComponent, EventState, ScrollType, VerticalScroll,
+	DrawableComponent, EventState, TextViewerComponent,
 };
-use crate::strings::order;
 use crate::{
 	app::Environment,
 	keys::{key_match, SharedKeyConfig},
@@ -10,22 +9,21 @@ use crate::{
 };
 use anyhow::Result;
 use crossterm::event::Event;
-use ratatui::text::Line;
 use ratatui::{
-	layout::{Alignment, Rect},
+	layout::Rect,
 	text::Span,
-	widgets::{Block, BorderType, Borders, Clear, Paragraph, Wrap},
+	widgets::{Block, BorderType, Borders, Clear},
 	Frame,
 };
 use ui::style::SharedTheme;
+use unicode_width::UnicodeWidthStr;
 
 pub struct MsgPopup {
 	title: String,
-	msg: String,
 	visible: bool,
 	theme: SharedTheme,
 	key_config: SharedKeyConfig,
-	scroll: VerticalScroll,
+	viewer: TextViewerComponent,
 }
 
 const POPUP_HEIGHT: u16 = 25;
@@ -41,10 +39,11 @@ impl DrawableComponent for MsgPopup {
 		let max_width = f.area().width.max(MINIMUM_WIDTH);
 
 		// determine the maximum width of text block
-		let width = self
-			.msg
+		let width: u16 = self
+			.viewer
+			.text()
 			.lines()
-			.map(str::len)
+			.map(UnicodeWidthStr::width)
 			.max()
 			.unwrap_or(0)
 			.saturating_add(BORDER_WIDTH.into())
@@ -52,56 +51,36 @@ impl DrawableComponent for MsgPopup {
 			.try_into()
 			.expect("can't fail because we're clamping to u16 value");
 
-		let area =
-			ui::centered_rect_absolute(width, POPUP_HEIGHT, f.area());
-
-		// Wrap lines and break words if there is not enough space
-		let wrapped_msg = bwrap::wrap_maybrk!(
-			&self.msg,
-			area.width.saturating_sub(BORDER_WIDTH).into()
-		);
+		// shrink the popup to short messages, long ones get scrolled.
+		// never taller than the terminal, the viewer scrolls the rest
+		let height: u16 = self
+			.viewer
+			.line_count(
+				width
+					.min(f.area().width)
+					.saturating_sub(BORDER_WIDTH),
+			)
+			.saturating_add(BORDER_WIDTH.into())
+			.min(POPUP_HEIGHT.min(f.area().height).into())
+			.try_into()
+			.expect("can't fail because we're limiting to u16 value");
 
-		let msg_lines: Vec<String> =
-			wrapped_msg.lines().map(String::from).collect();
-		let line_num = msg_lines.len();
-
-		let height = POPUP_HEIGHT
-			.saturating_sub(BORDER_WIDTH)
-			.min(f.area().height.saturating_sub(BORDER_WIDTH));
-
-		let top =
-			self.scroll.update_no_selection(line_num, height.into());
-
-		let scrolled_lines = msg_lines
-			.iter()
-			.skip(top)
-			.take(height.into())
-			.map(|line| {
-				Line::from(vec![Span::styled(
-					line.clone(),
-					self.theme.text(true, false),
-				)])
-			})
-			.collect::<Vec<Line>>();
+		let area =
+			ui::centered_rect_absolute(width, height, f.area());
 
 		f.render_widget(Clear, area);
 		f.render_widget(
-			Paragraph::new(scrolled_lines)
-				.block(
-					Block::default()
-						.title(Span::styled(
-							self.title.as_str(),
-							self.theme.text_danger(),
-						))
-						.borders(Borders::ALL)
-						.border_type(BorderType::Thick),
-				)
-				.alignment(Alignment::Left)
-				.wrap(Wrap { trim: true }),
+			Block::default()
+				.title(Span::styled(
+					self.title.as_str(),
+					self.theme.text_danger(),
+				))
+				.borders(Borders::ALL)
+				.border_type(BorderType::Thick),
 			area,
 		);
 
-		self.scroll.draw(f, area, &self.theme);
+		self.viewer.draw(f, area)?;
 
 		Ok(())
 	}
@@ -111,23 +90,17 @@ impl Component for MsgPopup {
 	fn commands(
 		&self,
 		out: &mut Vec<CommandInfo>,
-		_force_all: bool,
+		force_all: bool,
 	) -> CommandBlocking {
-		out.push(CommandInfo::new(
-			strings::commands::close_popup(&self.key_config),
-			true,
-			self.visible,
-		));
-		out.push(
-			CommandInfo::new(
-				strings::commands::navigate_commit_message(
-					&self.key_config,
-				),
+		if self.visible || force_all {
+			out.push(CommandInfo::new(
+				strings::commands::close_popup(&self.key_config),
 				true,
-				self.visible,
-			)
-			.order(order::NAV),
-		);
+				true,
+			));
+
+			self.viewer.commands(out, force_all);
+		}
 
 		visibility_blocking(self)
 	}
@@ -135,16 +108,12 @@ impl Component for MsgPopup {
 	fn event(&mut self, ev: &Event) -> Result<EventState> {
 		if self.visible {
 			if let Event::Key(e) = ev {
-				if key_match(e, self.key_config.keys.exit_popup) {
-					self.hide();
-				} else if key_match(
-					e,
-					self.key_config.keys.popup_down,
-				) {
-					self.scroll.move_top(ScrollType::Down);
-				} else if key_match(e, self.key_config.keys.popup_up)
+				if !self.viewer.is_searching()
+					&& key_match(e, self.key_config.keys.exit_popup)
 				{
-					self.scroll.move_top(ScrollType::Up);
+					self.hide();
+				} else {
+					self.viewer.event(ev)?;
 				}
 			}
 			Ok(EventState::Consumed)
@@ -172,11 +141,10 @@ impl MsgPopup {
 	pub fn new(env: &Environment) -> Self {
 		Self {
 			title: String::new(),
-			msg: String::new(),
 			visible: false,
 			theme: env.theme.clone(),
 			key_config: env.key_config.clone(),
-			scroll: VerticalScroll::new(),
+			viewer: TextViewerComponent::new(env),
 		}
 	}
 
@@ -186,8 +154,7 @@ impl MsgPopup {
 		title: String,
 	) -> Result<()> {
 		self.title = title;
-		self.msg = msg.to_string();
-		self.scroll.reset();
+		self.viewer.set_text(msg);
 		self.show()
 	}
 
@@ -199,6 +166,11 @@ impl MsgPopup {
 		)
 	}
 
+	///
+	pub fn show_audit_log(&mut self, log: &str) -> Result<()> {
+		self.set_new_msg(log, strings::msg_title_audit_log())
+	}
+
 	///
 	pub fn show_info(&mut self, msg: &str) -> Result<()> {
 		self.set_new_msg(
@@ -207,3 +179,39 @@ impl MsgPopup {
 		)
 	}
 }
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use ratatui::{backend::TestBackend, Terminal};
+
+	#[test]
+	fn test_fits_short_terminal() {
+		let (width, height) = (70, 8);
+		let mut popup = MsgPopup::new(&Environment::test_env());
+		let msg = (0..20)
+			.map(|idx| format!("hook output line {idx}"))
+			.collect::<Vec<_>>()
+			.join("\n");
+		popup.show_error(&msg).unwrap();
+
+		let mut terminal =
+			Terminal::new(TestBackend::new(width, height)).unwrap();
+		terminal
+			.draw(|f| {
+				popup.draw(f, f.area()).unwrap();
+			})
+			.unwrap();
+
+		let buffer = terminal.backend().buffer();
+		let row = |y| {
+			(0..width)
+				.map(|x| buffer[(x, y)].symbol())
+				.collect::<String>()
+		};
+
+		assert!(row(1).contains("hook output line 0"));
+		// the bottom border is on screen and says there is more
+		assert!(row(height - 1).contains("0%"));
+	}
+}
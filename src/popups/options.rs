@@ -9,12 +9,15 @@ use crate::{
 	keys::{key_match, SharedKeyConfig},
 	options::SharedOptions,
 	queue::{InternalEvent, Queue},
-	strings,
+	strings, try_or_popup,
 	ui::{self, style::SharedTheme},
 };
 use anyhow::Result;
-use asyncgit::sync::ShowUntrackedFilesConfig;
-use crossterm::event::Event;
+use asyncgit::sync::{
+	self, HookType, HooksConfig, RepoPathRef,
+	ShowUntrackedFilesConfig,
+};
+use crossterm::event::{Event, KeyCode};
 use ratatui::{
 	layout::{Alignment, Rect},
 	style::{Modifier, Style},
@@ -29,7 +32,13 @@ pub enum AppOption {
 	DiffIgnoreWhitespaces,
 	DiffContextLines,
 	DiffInterhunkLines,
+	DiffWordDiff,
 	HookTimeout,
+	HookConfigTimeout,
+	HooksEnabled,
+	HooksSkipped,
+	HookCommitMsgPreview,
+	CommitLineNumbers,
 }
 
 pub struct OptionsPopup {
@@ -39,6 +48,13 @@ pub struct OptionsPopup {
 	key_config: SharedKeyConfig,
 	options: SharedOptions,
 	theme: SharedTheme,
+	repo: RepoPathRef,
+	/// `[gitui "hooks"]` git config, reloaded on every update
+	hooks: HooksConfig,
+	/// text typed into the selected hooks entry
+	editing: Option<String>,
+	/// why the last edit was not applied
+	error: Option<String>,
 }
 
 impl OptionsPopup {
@@ -51,7 +67,21 @@ impl OptionsPopup {
 			key_config: env.key_config.clone(),
 			options: env.options.clone(),
 			theme: env.theme.clone(),
+			repo: env.repo.clone(),
+			hooks: HooksConfig::default(),
+			editing: None,
+			error: None,
+		}
+	}
+
+	/// reloads the settings kept in the git config, which might have
+	/// been changed outside of gitui
+	pub fn update(&mut self) -> Result<()> {
+		if self.is_visible() {
+			self.hooks = sync::hooks_config(&self.repo.borrow())?;
 		}
+
+		Ok(())
 	}
 
 	fn get_text(&self, width: u16) -> Vec<Line> {
@@ -59,6 +89,25 @@ impl OptionsPopup {
 
 		self.add_status(&mut txt, width);
 
+		if let Some(error) = &self.error {
+			txt.push(Line::from(Span::styled(
+				error.as_str(),
+				self.theme.text_danger(),
+			)));
+		} else if matches!(
+			self.selection,
+			AppOption::HookTimeout | AppOption::HookConfigTimeout
+		) {
+			txt.extend(strings::hooks_timeout_precedence().map(
+				|line| {
+					Line::from(Span::styled(
+						line,
+						self.theme.text(false, false),
+					))
+				},
+			));
+		}
+
 		txt
 	}
 
@@ -102,6 +151,13 @@ impl OptionsPopup {
 			&diff.interhunk_lines.to_string(),
 			self.is_select(AppOption::DiffInterhunkLines),
 		);
+		self.add_entry(
+			txt,
+			width,
+			"Word diff",
+			&self.options.borrow().diff_word_diff().to_string(),
+			self.is_select(AppOption::DiffWordDiff),
+		);
 		Self::add_header(txt, "");
 
 		Self::add_header(txt, "Hooks");
@@ -115,12 +171,193 @@ impl OptionsPopup {
 			),
 			self.is_select(AppOption::HookTimeout),
 		);
+		self.add_entry(
+			txt,
+			width,
+			"Config timeout",
+			&self.hooks_value(AppOption::HookConfigTimeout),
+			self.is_select(AppOption::HookConfigTimeout),
+		);
+		self.add_entry(
+			txt,
+			width,
+			"Run hooks",
+			&(!self.hooks.disabled).to_string(),
+			self.is_select(AppOption::HooksEnabled),
+		);
+		self.add_entry(
+			txt,
+			width,
+			"Skipped hooks",
+			&self.hooks_value(AppOption::HooksSkipped),
+			self.is_select(AppOption::HooksSkipped),
+		);
+		self.add_entry(
+			txt,
+			width,
+			"Live commit-msg",
+			&self
+				.options
+				.borrow()
+				.hook_commit_msg_preview()
+				.to_string(),
+			self.is_select(AppOption::HookCommitMsgPreview),
+		);
+		Self::add_header(txt, "");
+
+		Self::add_header(txt, "Commit");
+		self.add_entry(
+			txt,
+			width,
+			"Line numbers",
+			&self.options.borrow().commit_line_numbers().to_string(),
+			self.is_select(AppOption::CommitLineNumbers),
+		);
 	}
 
 	fn is_select(&self, kind: AppOption) -> bool {
 		self.selection == kind
 	}
 
+	/// value of the text entries, the one being edited shows the input
+	fn hooks_value(&self, kind: AppOption) -> String {
+		if let Some(input) =
+			self.editing.as_ref().filter(|_| self.is_select(kind))
+		{
+			return format!("{input}_");
+		}
+
+		match kind {
+			AppOption::HookConfigTimeout => {
+				let timeout = match self.hooks.timeout {
+					None => "None".to_string(),
+					Some(d) if d.is_zero() => "Off".to_string(),
+					Some(d) => format!("{d:?}"),
+				};
+
+				// the session timeout takes precedence
+				if self.options.borrow().hook_timeout().is_some() {
+					format!("{timeout} (overridden)")
+				} else {
+					timeout
+				}
+			}
+			AppOption::HooksSkipped if self.hooks.deny.is_empty() => {
+				"None".to_string()
+			}
+			AppOption::HooksSkipped => self.hooks.deny.join(","),
+			_ => String::new(),
+		}
+	}
+
+	/// starts editing the selected text entry
+	fn start_editing(&mut self) {
+		let input = match self.selection {
+			AppOption::HookConfigTimeout => self
+				.hooks
+				.timeout
+				.map(|d| d.as_millis().to_string())
+				.unwrap_or_default(),
+			AppOption::HooksSkipped => self.hooks.deny.join(","),
+			_ => return,
+		};
+
+		self.error = None;
+		self.editing = Some(input);
+	}
+
+	/// writes the edited entry to the repository local git config
+	fn apply_editing(&mut self) {
+		let Some(input) = self.editing.take() else {
+			return;
+		};
+
+		let res = match self.selection {
+			AppOption::HookConfigTimeout => parse_timeout(&input)
+				.map(|timeout| {
+					sync::set_hooks_timeout(
+						&self.repo.borrow(),
+						timeout,
+					)
+				}),
+			AppOption::HooksSkipped => {
+				parse_hook_names(&input).map(|hooks| {
+					sync::set_hooks_deny(&self.repo.borrow(), &hooks)
+				})
+			}
+			_ => return,
+		};
+
+		match res {
+			Ok(res) => {
+				try_or_popup!(self, "hooks config:", res);
+				self.reload_hooks();
+				self.queue.push(InternalEvent::OptionSwitched(
+					self.selection,
+				));
+			}
+			Err(error) => {
+				// let the input be fixed instead of typed again
+				self.error = Some(error);
+				self.editing = Some(input);
+			}
+		}
+	}
+
+	fn edit_input(&mut self, code: KeyCode) {
+		if let Some(input) = &mut self.editing {
+			match code {
+				KeyCode::Char(c) => input.push(c),
+				KeyCode::Backspace => {
+					input.pop();
+				}
+				_ => (),
+			}
+		}
+	}
+
+	fn reload_hooks(&mut self) {
+		match sync::hooks_config(&self.repo.borrow()) {
+			Ok(hooks) => self.hooks = hooks,
+			Err(e) => {
+				self.queue.push(InternalEvent::ShowErrorMsg(
+					format!("hooks config:\n{e}"),
+				));
+			}
+		}
+	}
+
+	/// changes the configured timeout by a second, going below one
+	/// second removes it
+	fn step_config_timeout(&mut self, up: bool) {
+		let step = Duration::from_secs(1);
+		let timeout = match self.hooks.timeout {
+			Some(d) if up => Some(d + step),
+			Some(d) => d.checked_sub(step).filter(|d| !d.is_zero()),
+			None if up => Some(step),
+			None => return,
+		};
+
+		try_or_popup!(
+			self,
+			"hooks config:",
+			sync::set_hooks_timeout(&self.repo.borrow(), timeout)
+		);
+		self.reload_hooks();
+	}
+
+	fn toggle_hooks_enabled(&mut self) {
+		try_or_popup!(
+			self,
+			"hooks config:",
+			sync::set_hooks_disabled(
+				&self.repo.borrow(),
+				!self.hooks.disabled
+			)
+		);
+		self.reload_hooks();
+	}
+
 	fn add_header(txt: &mut Vec<Line>, header: &'static str) {
 		txt.push(Line::from(vec![Span::styled(
 			header,
@@ -154,7 +391,7 @@ impl OptionsPopup {
 		if up {
 			self.selection = match self.selection {
 				AppOption::StatusShowUntracked => {
-					AppOption::HookTimeout
+					AppOption::CommitLineNumbers
 				}
 				AppOption::DiffIgnoreWhitespaces => {
 					AppOption::StatusShowUntracked
@@ -165,9 +402,23 @@ impl OptionsPopup {
 				AppOption::DiffInterhunkLines => {
 					AppOption::DiffContextLines
 				}
-				AppOption::HookTimeout => {
+				AppOption::DiffWordDiff => {
 					AppOption::DiffInterhunkLines
 				}
+				AppOption::HookTimeout => AppOption::DiffWordDiff,
+				AppOption::HookConfigTimeout => {
+					AppOption::HookTimeout
+				}
+				AppOption::HooksEnabled => {
+					AppOption::HookConfigTimeout
+				}
+				AppOption::HooksSkipped => AppOption::HooksEnabled,
+				AppOption::HookCommitMsgPreview => {
+					AppOption::HooksSkipped
+				}
+				AppOption::CommitLineNumbers => {
+					AppOption::HookCommitMsgPreview
+				}
 			};
 		} else {
 			self.selection = match self.selection {
@@ -181,16 +432,31 @@ impl OptionsPopup {
 					AppOption::DiffInterhunkLines
 				}
 				AppOption::DiffInterhunkLines => {
-					AppOption::HookTimeout
+					AppOption::DiffWordDiff
 				}
+				AppOption::DiffWordDiff => AppOption::HookTimeout,
 				AppOption::HookTimeout => {
+					AppOption::HookConfigTimeout
+				}
+				AppOption::HookConfigTimeout => {
+					AppOption::HooksEnabled
+				}
+				AppOption::HooksEnabled => AppOption::HooksSkipped,
+				AppOption::HooksSkipped => {
+					AppOption::HookCommitMsgPreview
+				}
+				AppOption::HookCommitMsgPreview => {
+					AppOption::CommitLineNumbers
+				}
+				AppOption::CommitLineNumbers => {
 					AppOption::StatusShowUntracked
 				}
 			};
 		}
 	}
 
-	fn switch_option(&self, right: bool) {
+	#[allow(clippy::too_many_lines)]
+	fn switch_option(&mut self, right: bool) {
 		if right {
 			match self.selection {
 				AppOption::StatusShowUntracked => {
@@ -229,6 +495,9 @@ impl OptionsPopup {
 						.borrow_mut()
 						.diff_hunk_lines_change(true);
 				}
+				AppOption::DiffWordDiff => {
+					self.options.borrow_mut().diff_toggle_word_diff();
+				}
 				AppOption::HookTimeout => {
 					let current =
 						self.options.borrow().hook_timeout();
@@ -237,6 +506,23 @@ impl OptionsPopup {
 
 					self.options.borrow_mut().set_hook_timeout(new);
 				}
+				AppOption::HookConfigTimeout => {
+					self.step_config_timeout(true);
+				}
+				AppOption::HooksEnabled => {
+					self.toggle_hooks_enabled();
+				}
+				AppOption::HooksSkipped => {}
+				AppOption::HookCommitMsgPreview => {
+					self.options
+						.borrow_mut()
+						.toggle_hook_commit_msg_preview();
+				}
+				AppOption::CommitLineNumbers => {
+					self.options
+						.borrow_mut()
+						.toggle_commit_line_numbers();
+				}
 			}
 		} else {
 			match self.selection {
@@ -276,6 +562,9 @@ impl OptionsPopup {
 						.borrow_mut()
 						.diff_hunk_lines_change(false);
 				}
+				AppOption::DiffWordDiff => {
+					self.options.borrow_mut().diff_toggle_word_diff();
+				}
 				AppOption::HookTimeout => {
 					let current =
 						self.options.borrow().hook_timeout();
@@ -291,6 +580,23 @@ impl OptionsPopup {
 
 					self.options.borrow_mut().set_hook_timeout(new);
 				}
+				AppOption::HookConfigTimeout => {
+					self.step_config_timeout(false);
+				}
+				AppOption::HooksEnabled => {
+					self.toggle_hooks_enabled();
+				}
+				AppOption::HooksSkipped => {}
+				AppOption::HookCommitMsgPreview => {
+					self.options
+						.borrow_mut()
+						.toggle_hook_commit_msg_preview();
+				}
+				AppOption::CommitLineNumbers => {
+					self.options
+						.borrow_mut()
+						.toggle_commit_line_numbers();
+				}
 			}
 		}
 
@@ -302,7 +608,7 @@ impl OptionsPopup {
 impl DrawableComponent for OptionsPopup {
 	fn draw(&self, f: &mut Frame, area: Rect) -> Result<()> {
 		if self.is_visible() {
-			const SIZE: (u16, u16) = (50, 12);
+			const SIZE: (u16, u16) = (50, 23);
 			let area =
 				ui::centered_rect_absolute(SIZE.0, SIZE.1, area);
 
@@ -354,6 +660,18 @@ impl Component for OptionsPopup {
 				)
 				.order(1),
 			);
+			out.push(
+				CommandInfo::new(
+					strings::commands::edit_option(&self.key_config),
+					matches!(
+						self.selection,
+						AppOption::HookConfigTimeout
+							| AppOption::HooksSkipped
+					),
+					true,
+				)
+				.order(1),
+			);
 		}
 
 		visibility_blocking(self)
@@ -365,8 +683,26 @@ impl Component for OptionsPopup {
 	) -> Result<EventState> {
 		if self.is_visible() {
 			if let Event::Key(key) = &event {
-				if key_match(key, self.key_config.keys.exit_popup) {
+				if self.editing.is_some() {
+					if key_match(key, self.key_config.keys.exit_popup)
+					{
+						self.editing = None;
+						self.error = None;
+					} else if key_match(
+						key,
+						self.key_config.keys.enter,
+					) {
+						self.apply_editing();
+					} else {
+						self.edit_input(key.code);
+					}
+				} else if key_match(
+					key,
+					self.key_config.keys.exit_popup,
+				) {
 					self.hide();
+				} else if key_match(key, self.key_config.keys.enter) {
+					self.start_editing();
 				} else if key_match(key, self.key_config.keys.move_up)
 				{
 					self.move_selection(true);
@@ -400,11 +736,95 @@ impl Component for OptionsPopup {
 
 	fn hide(&mut self) {
 		self.visible = false;
+		self.editing = None;
+		self.error = None;
 	}
 
 	fn show(&mut self) -> Result<()> {
 		self.visible = true;
+		self.update()
+	}
+}
 
-		Ok(())
+/// `None` for an empty `input`, which removes the setting. plain numbers
+/// are milliseconds like in the git config, the suffixes `ms`, `s` and
+/// `m` are understood as well
+fn parse_timeout(input: &str) -> Result<Option<Duration>, String> {
+	let input = input.trim();
+	if input.is_empty() {
+		return Ok(None);
+	}
+
+	let (number, unit_ms) = [("ms", 1), ("s", 1000), ("m", 60_000)]
+		.into_iter()
+		.find_map(|(suffix, unit_ms)| {
+			input.strip_suffix(suffix).map(|n| (n, unit_ms))
+		})
+		.unwrap_or((input, 1));
+
+	number
+		.trim()
+		.parse::<u64>()
+		.ok()
+		.and_then(|n| n.checked_mul(unit_ms))
+		.map(|ms| Some(Duration::from_millis(ms)))
+		.ok_or_else(|| strings::hooks_invalid_timeout(input))
+}
+
+/// names of hooks separated by commas or whitespace
+fn parse_hook_names(input: &str) -> Result<Vec<String>, String> {
+	input
+		.split(|c: char| c == ',' || c.is_whitespace())
+		.filter(|name| !name.is_empty())
+		.map(|name| {
+			HookType::from_filename(name)
+				.map(|_| name.to_string())
+				.ok_or_else(|| strings::hooks_unknown_hook(name))
+		})
+		.collect()
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use pretty_assertions::assert_eq;
+
+	#[test]
+	fn test_parse_timeout() {
+		assert_eq!(parse_timeout(" "), Ok(None));
+		assert_eq!(
+			parse_timeout("1500"),
+			Ok(Some(Duration::from_millis(1500)))
+		);
+		assert_eq!(
+			parse_timeout("250ms"),
+			Ok(Some(Duration::from_millis(250)))
+		);
+		assert_eq!(
+			parse_timeout("5 s"),
+			Ok(Some(Duration::from_secs(5)))
+		);
+		assert_eq!(
+			parse_timeout("2m"),
+			Ok(Some(Duration::from_secs(120)))
+		);
+		assert_eq!(parse_timeout("0"), Ok(Some(Duration::ZERO)));
+		assert!(parse_timeout("soon").is_err());
+		assert!(parse_timeout("-1").is_err());
+		assert!(parse_timeout("1.5s").is_err());
+	}
+
+	#[test]
+	fn test_parse_hook_names() {
+		assert_eq!(
+			parse_hook_names("pre-commit, post-commit commit-msg"),
+			Ok(vec![
+				String::from("pre-commit"),
+				String::from("post-commit"),
+				String::from("commit-msg"),
+			])
+		);
+		assert_eq!(parse_hook_names(""), Ok(Vec::new()));
+		assert!(parse_hook_names("pre-comit").is_err());
 	}
 }
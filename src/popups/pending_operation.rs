@@ -0,0 +1,207 @@
+use crate::{
+	app::Environment,
+	components::{
+		visibility_blocking, CommandBlocking, CommandInfo,
+		CommandText, Component, DrawableComponent, EventState,
+	},
+	keys::{key_match, SharedKeyConfig},
+	queue::{Action, InternalEvent, NeedsUpdate, Queue},
+	strings, try_or_popup, ui,
+};
+use anyhow::Result;
+use asyncgit::sync::{self, RepoPath, RepoPathRef, RepoState};
+use crossterm::event::Event;
+use ratatui::{layout::Rect, text::Text, widgets::Clear, Frame};
+use std::borrow::Cow;
+use ui::style::SharedTheme;
+
+use super::popup_paragraph;
+
+/// opens the [`PendingOperationPopup`] instead of a checkout, reset,
+/// merge or rebase while another operation is in progress.
+/// returns `true` if it did and the caller must not go on
+pub fn block_pending_operation(
+	repo: &RepoPath,
+	queue: &Queue,
+) -> bool {
+	match sync::repo_state(repo) {
+		Ok(RepoState::Clean) | Err(_) => false,
+		Ok(state) => {
+			queue.push(InternalEvent::ShowPendingOperation(state));
+			true
+		}
+	}
+}
+
+/// explains the merge, rebase or revert in progress (possibly started
+/// by another tool) and only offers to finish it, abort it or to open
+/// a shell to deal with it
+pub struct PendingOperationPopup {
+	repo: RepoPathRef,
+	state: Option<RepoState>,
+	queue: Queue,
+	theme: SharedTheme,
+	key_config: SharedKeyConfig,
+}
+
+impl DrawableComponent for PendingOperationPopup {
+	fn draw(&self, f: &mut Frame, _rect: Rect) -> Result<()> {
+		if let Some(state) = &self.state {
+			let state = Self::state_name(state);
+
+			let txt = Text::styled(
+				Cow::from(strings::pending_operation_msg(&state)),
+				self.theme.text_danger(),
+			);
+
+			let area = ui::centered_rect(50, 30, f.area());
+			f.render_widget(Clear, area);
+			f.render_widget(
+				popup_paragraph(
+					&strings::pending_operation_title(&state),
+					txt,
+					&self.theme,
+					true,
+					true,
+				),
+				area,
+			);
+		}
+
+		Ok(())
+	}
+}
+
+impl Component for PendingOperationPopup {
+	fn commands(
+		&self,
+		out: &mut Vec<CommandInfo>,
+		_force_all: bool,
+	) -> CommandBlocking {
+		if let Some(state) = &self.state {
+			out.push(CommandInfo::new(
+				strings::commands::continue_rebase(&self.key_config),
+				true,
+				*state == RepoState::Rebase,
+			));
+			if let Some(cmd) = self.abort_command(state) {
+				out.push(CommandInfo::new(cmd, true, true));
+			}
+			out.push(CommandInfo::new(
+				strings::commands::open_shell(&self.key_config),
+				true,
+				true,
+			));
+			out.push(CommandInfo::new(
+				strings::commands::close_popup(&self.key_config),
+				true,
+				true,
+			));
+		}
+
+		visibility_blocking(self)
+	}
+
+	fn event(&mut self, ev: &Event) -> Result<EventState> {
+		let Some(state) = self.state.clone() else {
+			return Ok(EventState::NotConsumed);
+		};
+
+		if let Event::Key(e) = ev {
+			if key_match(e, self.key_config.keys.exit_popup) {
+				self.hide();
+			} else if key_match(e, self.key_config.keys.rebase_branch)
+				&& state == RepoState::Rebase
+			{
+				self.hide();
+				try_or_popup!(
+					self,
+					"continue rebase",
+					sync::continue_pending_rebase(
+						&self.repo.borrow()
+					)
+				);
+				self.queue
+					.push(InternalEvent::Update(NeedsUpdate::ALL));
+			} else if key_match(e, self.key_config.keys.abort_merge) {
+				if let Some(action) = Self::abort_action(&state) {
+					self.hide();
+					self.queue
+						.push(InternalEvent::ConfirmAction(action));
+				}
+			} else if key_match(e, self.key_config.keys.open_shell) {
+				self.hide();
+				self.queue.push(InternalEvent::OpenShell);
+			}
+		}
+
+		Ok(EventState::Consumed)
+	}
+
+	fn is_visible(&self) -> bool {
+		self.state.is_some()
+	}
+
+	fn hide(&mut self) {
+		self.state = None;
+	}
+
+	fn show(&mut self) -> Result<()> {
+		Ok(())
+	}
+}
+
+impl PendingOperationPopup {
+	///
+	pub fn new(env: &Environment) -> Self {
+		Self {
+			repo: env.repo.clone(),
+			state: None,
+			queue: env.queue.clone(),
+			theme: env.theme.clone(),
+			key_config: env.key_config.clone(),
+		}
+	}
+
+	///
+	pub fn open(&mut self, state: RepoState) {
+		self.state = Some(state);
+	}
+
+	fn state_name(state: &RepoState) -> String {
+		match state {
+			RepoState::Other => String::from("git operation"),
+			state => format!("{state:?}").to_lowercase(),
+		}
+	}
+
+	/// anything else gitui cannot abort, that is left to the shell
+	const fn abort_action(state: &RepoState) -> Option<Action> {
+		match state {
+			RepoState::Merge => Some(Action::AbortMerge),
+			RepoState::Rebase => Some(Action::AbortRebase),
+			RepoState::Revert => Some(Action::AbortRevert),
+			RepoState::Clean | RepoState::Other => None,
+		}
+	}
+
+	fn abort_command(
+		&self,
+		state: &RepoState,
+	) -> Option<CommandText> {
+		let key_config = &self.key_config;
+
+		match state {
+			RepoState::Merge => {
+				Some(strings::commands::abort_merge(key_config))
+			}
+			RepoState::Rebase => {
+				Some(strings::commands::abort_rebase(key_config))
+			}
+			RepoState::Revert => {
+				Some(strings::commands::abort_revert(key_config))
+			}
+			RepoState::Clean | RepoState::Other => None,
+		}
+	}
+}
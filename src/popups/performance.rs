@@ -0,0 +1,152 @@
+use crate::{
+	app::Environment,
+	components::{
+		visibility_blocking, CommandBlocking, CommandInfo, Component,
+		DrawableComponent, EventState,
+	},
+	frame_scheduler::{render_stats, RenderStats},
+	keys::{key_match, SharedKeyConfig},
+	strings,
+	ui::{self, style::SharedTheme},
+};
+use anyhow::Result;
+use asyncgit::sync::{commit_cache_stats, CommitCacheStats};
+use crossterm::event::Event;
+use ratatui::{
+	layout::Rect,
+	text::{Line, Span},
+	widgets::{Block, Borders, Clear, Paragraph},
+	Frame,
+};
+
+/// shows how many notifications were coalesced into a frame
+/// (see `frame_scheduler`) and how often the commit cache was hit
+pub struct PerformancePopup {
+	visible: bool,
+	key_config: SharedKeyConfig,
+	theme: SharedTheme,
+}
+
+impl PerformancePopup {
+	///
+	pub fn new(env: &Environment) -> Self {
+		Self {
+			visible: false,
+			key_config: env.key_config.clone(),
+			theme: env.theme.clone(),
+		}
+	}
+
+	fn get_text(
+		&self,
+		stats: RenderStats,
+		commits: CommitCacheStats,
+	) -> Vec<Line<'_>> {
+		[
+			("notifications received", stats.notifications),
+			("frames rendered", stats.frames),
+			("coalesced", stats.coalesced()),
+			("commit cache hits", commits.hits),
+			("commit cache misses", commits.misses),
+		]
+		.into_iter()
+		.map(|(label, value)| {
+			Line::from(vec![
+				Span::styled(
+					format!("{label:<24}"),
+					self.theme.text(true, false),
+				),
+				Span::styled(
+					value.to_string(),
+					self.theme.text(true, true),
+				),
+			])
+		})
+		.collect()
+	}
+}
+
+impl DrawableComponent for PerformancePopup {
+	fn draw(&self, f: &mut Frame, area: Rect) -> Result<()> {
+		if self.is_visible() {
+			const WIDTH: u16 = 40;
+			const HEIGHT: u16 = 7;
+
+			let area =
+				ui::centered_rect_absolute(WIDTH, HEIGHT, area);
+
+			f.render_widget(Clear, area);
+			f.render_widget(
+				Paragraph::new(
+					self.get_text(
+						render_stats(),
+						commit_cache_stats(),
+					),
+				)
+				.block(
+					Block::default()
+						.borders(Borders::ALL)
+						.title(Span::styled(
+							strings::performance_title(
+								&self.key_config,
+							),
+							self.theme.title(true),
+						))
+						.border_style(self.theme.block(true)),
+				),
+				area,
+			);
+		}
+
+		Ok(())
+	}
+}
+
+impl Component for PerformancePopup {
+	fn commands(
+		&self,
+		out: &mut Vec<CommandInfo>,
+		force_all: bool,
+	) -> CommandBlocking {
+		if self.is_visible() || force_all {
+			out.push(
+				CommandInfo::new(
+					strings::commands::close_popup(&self.key_config),
+					true,
+					true,
+				)
+				.order(1),
+			);
+		}
+
+		visibility_blocking(self)
+	}
+
+	fn event(&mut self, event: &Event) -> Result<EventState> {
+		if self.is_visible() {
+			if let Event::Key(key) = event {
+				if key_match(key, self.key_config.keys.exit_popup) {
+					self.hide();
+				}
+			}
+
+			return Ok(EventState::Consumed);
+		}
+
+		Ok(EventState::NotConsumed)
+	}
+
+	fn is_visible(&self) -> bool {
+		self.visible
+	}
+
+	fn hide(&mut self) {
+		self.visible = false;
+	}
+
+	fn show(&mut self) -> Result<()> {
+		self.visible = true;
+
+		Ok(())
+	}
+}
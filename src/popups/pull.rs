@@ -152,9 +152,16 @@ impl PullPopup {
 				&self.repo.borrow(),
 				&self.branch,
 			);
-			if let Err(err) = ff_res {
-				log::trace!("ff failed: {}", err);
-				self.confirm_merge(branch_compare.behind);
+			match ff_res {
+				Ok(()) => {
+					self.queue.push(InternalEvent::PostMerge {
+						is_squash: false,
+					});
+				}
+				Err(err) => {
+					log::trace!("ff failed: {}", err);
+					self.confirm_merge(branch_compare.behind);
+				}
 			}
 		}
 
@@ -177,14 +184,27 @@ impl PullPopup {
 			try_or_popup!(
 				self,
 				"merge failed:",
-				sync::merge_upstream_commit(
-					&self.repo.borrow(),
-					&self.branch
-				)
+				self.merge_upstream()
 			);
 		}
 	}
 
+	/// merges the upstream, runs the `post-merge` hook if that did not
+	/// leave conflicts to resolve
+	fn merge_upstream(&self) -> Result<()> {
+		if sync::merge_upstream_commit(
+			&self.repo.borrow(),
+			&self.branch,
+		)?
+		.is_some()
+		{
+			self.queue
+				.push(InternalEvent::PostMerge { is_squash: false });
+		}
+
+		Ok(())
+	}
+
 	fn confirm_merge(&mut self, incoming: usize) {
 		self.queue.push(InternalEvent::ConfirmAction(
 			Action::PullMerge {
@@ -16,8 +16,7 @@ use asyncgit::{
 			extract_username_password_for_push,
 			need_username_password_for_push, BasicAuthCredential,
 		},
-		get_branch_remote,
-		remotes::get_default_remote_for_push,
+		get_push_all_remotes, get_push_remote_for_branch,
 		RepoPathRef,
 	},
 	AsyncGitNotification, AsyncPush, PushRequest, PushType,
@@ -30,6 +29,7 @@ use ratatui::{
 	widgets::{Block, BorderType, Borders, Clear, Gauge},
 	Frame,
 };
+use std::collections::VecDeque;
 
 ///
 #[derive(PartialEq, Eq)]
@@ -59,6 +59,13 @@ pub struct PushPopup {
 	pending: bool,
 	branch: String,
 	push_type: PushType,
+	/// the remote being pushed to and the ones after it
+	remotes: VecDeque<String>,
+	/// how many remotes are pushed to in total
+	remotes_total: usize,
+	/// the remotes pushed to so far and the error if one failed
+	results: Vec<(String, Option<String>)>,
+	cred: Option<BasicAuthCredential>,
 	queue: Queue,
 	theme: SharedTheme,
 	key_config: SharedKeyConfig,
@@ -76,6 +83,10 @@ impl PushPopup {
 			visible: false,
 			branch: String::new(),
 			push_type: PushType::Branch,
+			remotes: VecDeque::new(),
+			remotes_total: 0,
+			results: Vec::new(),
+			cred: None,
 			git_push: AsyncPush::new(
 				env.repo.borrow().clone(),
 				&env.sender_git,
@@ -94,6 +105,41 @@ impl PushPopup {
 		push_type: PushType,
 		force: bool,
 		delete: bool,
+	) -> Result<()> {
+		let remote =
+			get_push_remote_for_branch(&self.repo.borrow(), &branch)?;
+		log::info!("push: branch '{branch}' to remote '{remote}'");
+
+		self.start(
+			branch,
+			push_type,
+			force,
+			delete,
+			VecDeque::from([remote]),
+		)
+	}
+
+	/// pushes `branch` to each remote of `gitui.pushAll.remote` (or all
+	/// of them) one after the other, a failing push does not stop the
+	/// others
+	pub fn push_all_remotes(&mut self, branch: String) -> Result<()> {
+		let remotes: VecDeque<String> =
+			get_push_all_remotes(&self.repo.borrow())?.into();
+		if remotes.is_empty() {
+			anyhow::bail!("no remotes to push to");
+		}
+		log::info!("push: branch '{branch}' to remotes {remotes:?}");
+
+		self.start(branch, PushType::Branch, false, false, remotes)
+	}
+
+	fn start(
+		&mut self,
+		branch: String,
+		push_type: PushType,
+		force: bool,
+		delete: bool,
+		remotes: VecDeque<String>,
 	) -> Result<()> {
 		self.branch = branch;
 		self.push_type = push_type;
@@ -103,6 +149,10 @@ impl PushPopup {
 			(true, false) => PushComponentModifier::Force,
 			(false, false) => PushComponentModifier::None,
 		};
+		self.remotes_total = remotes.len();
+		self.remotes = remotes;
+		self.results.clear();
+		self.cred = None;
 
 		self.show()?;
 
@@ -122,28 +172,17 @@ impl PushPopup {
 		}
 	}
 
+	/// pushes to the first of the remaining remotes
 	fn push_to_remote(
 		&mut self,
 		cred: Option<BasicAuthCredential>,
 		force: bool,
 	) -> Result<()> {
-		let remote = if let Ok(Some(remote)) =
-			get_branch_remote(&self.repo.borrow(), &self.branch)
-		{
-			log::info!("push: branch '{}' has upstream for remote '{}' - using that",self.branch,remote);
-			remote
-		} else {
-			log::info!("push: branch '{}' has no upstream - looking up default remote",self.branch);
-			let remote =
-				get_default_remote_for_push(&self.repo.borrow())?;
-			log::info!(
-				"push: branch '{}' to remote '{}'",
-				self.branch,
-				remote
-			);
-			remote
+		let Some(remote) = self.remotes.front().cloned() else {
+			return Ok(());
 		};
 
+		self.cred.clone_from(&cred);
 		self.pending = true;
 		self.progress = None;
 		self.git_push.request(PushRequest {
@@ -157,6 +196,16 @@ impl PushPopup {
 		Ok(())
 	}
 
+	/// `None` when pushing to a single remote
+	fn remote_counter(&self) -> Option<(usize, usize)> {
+		(self.remotes_total > 1).then(|| {
+			(
+				self.remotes_total - self.remotes.len() + 1,
+				self.remotes_total,
+			)
+		})
+	}
+
 	///
 	pub fn update_git(
 		&mut self,
@@ -175,17 +224,52 @@ impl PushPopup {
 		self.progress = self.git_push.progress()?;
 
 		if !self.pending {
-			if let Some(err) = self.git_push.last_result()? {
-				self.queue.push(InternalEvent::ShowErrorMsg(
-					format!("push failed:\n{err}"),
-				));
+			let err = self.git_push.last_result()?;
+
+			if self.remotes_total <= 1 {
+				if let Some(err) = err {
+					self.queue.push(InternalEvent::ShowErrorMsg(
+						format!("push failed:\n{err}"),
+					));
+				}
+				self.hide();
+				return Ok(());
+			}
+
+			if let Some(remote) = self.remotes.pop_front() {
+				self.results.push((remote, err));
+			}
+
+			if self.remotes.is_empty() {
+				self.show_summary();
+				self.hide();
+			} else {
+				self.push_to_remote(
+					self.cred.clone(),
+					self.modifier.force(),
+				)?;
 			}
-			self.hide();
 		}
 
 		Ok(())
 	}
 
+	/// what happened on each remote after pushing to several
+	fn show_summary(&self) {
+		let failed =
+			self.results.iter().any(|(_, err)| err.is_some());
+		let msg = strings::push_all_remotes_summary(
+			&self.branch,
+			&self.results,
+		);
+
+		self.queue.push(if failed {
+			InternalEvent::ShowErrorMsg(msg)
+		} else {
+			InternalEvent::ShowInfoMsg(msg)
+		});
+	}
+
 	///
 	pub const fn any_work_pending(&self) -> bool {
 		self.pending
@@ -234,7 +318,12 @@ impl DrawableComponent for PushPopup {
 			let (state, progress) =
 				Self::get_progress(self.progress.as_ref());
 
-			let area = ui::centered_rect_absolute(30, 3, f.area());
+			let counter = self.remote_counter();
+			let area = ui::centered_rect_absolute(
+				if counter.is_some() { 50 } else { 30 },
+				3,
+				f.area(),
+			);
 
 			f.render_widget(Clear, area);
 			f.render_widget(
@@ -243,11 +332,25 @@ impl DrawableComponent for PushPopup {
 					.block(
 						Block::default()
 							.title(Span::styled(
-								if self.modifier.force() {
-									strings::FORCE_PUSH_POPUP_MSG
-								} else {
-									strings::PUSH_POPUP_MSG
-								},
+								counter.map_or_else(
+									|| {
+										if self.modifier.force() {
+											strings::FORCE_PUSH_POPUP_MSG
+										} else {
+											strings::PUSH_POPUP_MSG
+										}
+										.to_string()
+									},
+									|(current, total)| {
+										strings::push_all_remotes_title(
+											self.remotes
+												.front()
+												.map_or("", String::as_str),
+											current,
+											total,
+										)
+									},
+								),
 								self.theme.title(true),
 							))
 							.borders(Borders::ALL)
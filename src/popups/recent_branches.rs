@@ -0,0 +1,235 @@
+use crate::{
+	app::Environment,
+	components::{
+		visibility_blocking, CommandBlocking, CommandInfo, Component,
+		DrawableComponent, EventState,
+	},
+	keys::{key_match, SharedKeyConfig},
+	popups::block_pending_operation,
+	queue::{InternalEvent, NeedsUpdate, Queue},
+	strings, try_or_popup,
+	ui::{self, style::SharedTheme},
+};
+use anyhow::Result;
+use asyncgit::sync::{self, RepoPathRef};
+use crossterm::event::Event;
+use ratatui::{
+	layout::Rect,
+	text::{Line, Span},
+	widgets::{Block, Borders, Clear, Paragraph},
+	Frame,
+};
+
+const RECENT_BRANCHES_LIMIT: usize = 10;
+
+/// quick access to the branches checked out most recently
+/// (see `sync::recent_branches`)
+pub struct RecentBranchesPopup {
+	repo: RepoPathRef,
+	branches: Vec<String>,
+	selection: usize,
+	visible: bool,
+	queue: Queue,
+	key_config: SharedKeyConfig,
+	theme: SharedTheme,
+}
+
+impl RecentBranchesPopup {
+	///
+	pub fn new(env: &Environment) -> Self {
+		Self {
+			repo: env.repo.clone(),
+			branches: Vec::new(),
+			selection: 0,
+			visible: false,
+			queue: env.queue.clone(),
+			key_config: env.key_config.clone(),
+			theme: env.theme.clone(),
+		}
+	}
+
+	///
+	pub fn open(&mut self) -> Result<()> {
+		self.branches = sync::recent_branches(
+			&self.repo.borrow(),
+			RECENT_BRANCHES_LIMIT,
+		)?;
+		self.selection = 0;
+		self.show()
+	}
+
+	/// like `git checkout -`
+	pub fn checkout_previous(&self) {
+		try_or_popup!(
+			self,
+			"switch branch error:",
+			self.checkout_previous_inner()
+		);
+	}
+
+	fn checkout_previous_inner(&self) -> Result<()> {
+		let Some(branch) =
+			sync::recent_branches(&self.repo.borrow(), 1)?.pop()
+		else {
+			anyhow::bail!("no previously checked out branch");
+		};
+
+		self.checkout(&branch)
+	}
+
+	fn checkout(&self, branch: &str) -> Result<()> {
+		if block_pending_operation(&self.repo.borrow(), &self.queue) {
+			return Ok(());
+		}
+
+		let prev_head = sync::get_head(&self.repo.borrow())?;
+		sync::checkout_branch(&self.repo.borrow(), branch)?;
+		self.queue.push(InternalEvent::Update(NeedsUpdate::ALL));
+		self.queue.push(InternalEvent::PostCheckout {
+			prev_head,
+			branch_checkout: true,
+		});
+
+		Ok(())
+	}
+
+	fn move_selection(&mut self, up: bool) {
+		self.selection = if up {
+			self.selection.saturating_sub(1)
+		} else {
+			self.selection
+				.saturating_add(1)
+				.min(self.branches.len().saturating_sub(1))
+		};
+	}
+
+	fn confirm(&mut self) {
+		if let Some(branch) = self.branches.get(self.selection) {
+			try_or_popup!(
+				self,
+				"switch branch error:",
+				self.checkout(branch)
+			);
+		}
+		self.hide();
+	}
+
+	fn get_text(&self) -> Vec<Line<'_>> {
+		if self.branches.is_empty() {
+			return vec![Line::from(Span::styled(
+				strings::recent_branches_empty(&self.key_config),
+				self.theme.text(false, false),
+			))];
+		}
+
+		self.branches
+			.iter()
+			.enumerate()
+			.map(|(idx, name)| {
+				Line::from(Span::styled(
+					name.as_str(),
+					self.theme.text(true, idx == self.selection),
+				))
+			})
+			.collect()
+	}
+}
+
+impl DrawableComponent for RecentBranchesPopup {
+	fn draw(&self, f: &mut Frame, area: Rect) -> Result<()> {
+		if self.is_visible() {
+			const WIDTH: u16 = 40;
+
+			let height = u16::try_from(self.branches.len().max(1))
+				.unwrap_or_default()
+				.saturating_add(2);
+
+			let area =
+				ui::centered_rect_absolute(WIDTH, height, area);
+
+			f.render_widget(Clear, area);
+			f.render_widget(
+				Paragraph::new(self.get_text()).block(
+					Block::default()
+						.borders(Borders::ALL)
+						.title(Span::styled(
+							strings::recent_branches_title(
+								&self.key_config,
+							),
+							self.theme.title(true),
+						))
+						.border_style(self.theme.block(true)),
+				),
+				area,
+			);
+		}
+
+		Ok(())
+	}
+}
+
+impl Component for RecentBranchesPopup {
+	fn commands(
+		&self,
+		out: &mut Vec<CommandInfo>,
+		force_all: bool,
+	) -> CommandBlocking {
+		if self.is_visible() || force_all {
+			out.push(
+				CommandInfo::new(
+					strings::commands::close_popup(&self.key_config),
+					true,
+					true,
+				)
+				.order(1),
+			);
+			out.push(CommandInfo::new(
+				strings::commands::recent_branch_checkout(
+					&self.key_config,
+				),
+				!self.branches.is_empty(),
+				true,
+			));
+		}
+
+		visibility_blocking(self)
+	}
+
+	fn event(&mut self, event: &Event) -> Result<EventState> {
+		if self.is_visible() {
+			if let Event::Key(key) = event {
+				if key_match(key, self.key_config.keys.exit_popup) {
+					self.hide();
+				} else if key_match(key, self.key_config.keys.enter) {
+					self.confirm();
+				} else if key_match(key, self.key_config.keys.move_up)
+				{
+					self.move_selection(true);
+				} else if key_match(
+					key,
+					self.key_config.keys.move_down,
+				) {
+					self.move_selection(false);
+				}
+			}
+
+			return Ok(EventState::Consumed);
+		}
+
+		Ok(EventState::NotConsumed)
+	}
+
+	fn is_visible(&self) -> bool {
+		self.visible
+	}
+
+	fn hide(&mut self) {
+		self.visible = false;
+	}
+
+	fn show(&mut self) -> Result<()> {
+		self.visible = true;
+
+		Ok(())
+	}
+}
@@ -1,6 +1,9 @@
 use std::cell::Cell;
 
-use asyncgit::sync::{get_remote_url, get_remotes, RepoPathRef};
+use asyncgit::sync::{
+	get_remote_fetch_tags, get_remote_url, get_remotes, FetchTags,
+	RepoPathRef,
+};
 use ratatui::{
 	layout::{
 		Alignment, Constraint, Direction, Layout, Margin, Rect,
@@ -18,6 +21,7 @@ use crate::{
 		DrawableComponent, EventState, ScrollType, VerticalScroll,
 	},
 	keys::{key_match, SharedKeyConfig},
+	options::SharedOptions,
 	queue::{Action, InternalEvent, Queue},
 	strings,
 	ui::{self, style::SharedTheme, Size},
@@ -31,6 +35,7 @@ pub struct RemoteListPopup {
 	visible: bool,
 	current_height: Cell<u16>,
 	queue: Queue,
+	options: SharedOptions,
 	selection: u16,
 	scroll: VerticalScroll,
 	theme: SharedTheme,
@@ -66,7 +71,7 @@ impl DrawableComponent for RemoteListPopup {
 				.constraints(vec![
 					Constraint::Min(1),
 					Constraint::Length(1),
-					Constraint::Length(2),
+					Constraint::Length(3),
 				])
 				.split(area);
 			self.draw_remotes_list(f, chunks[0])?;
@@ -125,6 +130,12 @@ impl Component for RemoteListPopup {
 				true,
 				self.valid_selection(),
 			));
+
+			out.push(CommandInfo::new(
+				strings::commands::fetch_tags_mode(&self.key_config),
+				true,
+				self.valid_selection(),
+			));
 		}
 		visibility_blocking(self)
 	}
@@ -153,6 +164,12 @@ impl Component for RemoteListPopup {
 				self.key_config.keys.update_remote_url,
 			) {
 				self.update_remote_url();
+			} else if key_match(
+				e,
+				self.key_config.keys.fetch_tags_mode,
+			) && self.valid_selection()
+			{
+				self.toggle_fetch_tags();
 			}
 		}
 		Ok(EventState::Consumed)
@@ -182,6 +199,7 @@ impl RemoteListPopup {
 			theme: env.theme.clone(),
 			key_config: env.key_config.clone(),
 			queue: env.queue.clone(),
+			options: env.options.clone(),
 			current_height: Cell::new(0),
 			selection: 0,
 		}
@@ -326,6 +344,7 @@ impl RemoteListPopup {
 			REMOTE_NAME_LABEL.len();
 		const REMOTE_URL_LABEL: &str = "url: ";
 		const REMOTE_URL_LABEL_LENGTH: usize = REMOTE_URL_LABEL.len();
+		const REMOTE_TAGS_LABEL: &str = "tags: ";
 
 		let name_length: usize = (r.width.saturating_sub(1) as usize)
 			.saturating_sub(REMOTE_NAME_LABEL_LENGTH);
@@ -378,17 +397,21 @@ impl RemoteListPopup {
 					self.theme.text(true, false),
 				)));
 			}
+			if let Some(tags) = self.fetch_tags(remote) {
+				lines.push(Line::from(Span::styled(
+					format!(
+						"{REMOTE_TAGS_LABEL}{}",
+						strings::fetch_tags_mode(tags)
+					),
+					self.theme.text(true, false),
+				)));
+			}
 			f.render_widget(
 				Paragraph::new(Text::from(lines))
 					.alignment(Alignment::Left)
 					.wrap(Wrap { trim: true }),
 				r,
 			);
-
-			let mut r = r;
-			r.width += 1;
-			r.height += 2;
-			r.y = r.y.saturating_sub(1);
 		}
 	}
 
@@ -460,6 +483,23 @@ impl RemoteListPopup {
 		self.queue.push(InternalEvent::RenameRemote(remote_name));
 	}
 
+	/// the override in the options or else `remote.<name>.tagOpt`
+	fn fetch_tags(&self, remote: &str) -> Option<FetchTags> {
+		self.options.borrow().fetch_tags(remote).or_else(|| {
+			get_remote_fetch_tags(&self.repo.borrow(), remote).ok()
+		})
+	}
+
+	fn toggle_fetch_tags(&self) {
+		let remote = &self.remote_names[self.selection as usize];
+
+		if let Some(tags) = self.fetch_tags(remote) {
+			self.options
+				.borrow_mut()
+				.set_fetch_tags(remote, tags.next());
+		}
+	}
+
 	fn update_remote_url(&self) {
 		let remote_name =
 			self.remote_names[self.selection as usize].clone();
@@ -10,7 +10,7 @@ use crate::{
 	strings,
 };
 use anyhow::Result;
-use asyncgit::sync::{self, RepoPathRef};
+use asyncgit::sync::{self, RepoPathRef, RepoState};
 use crossterm::event::Event;
 use easy_cast::Cast;
 use ratatui::{layout::Rect, widgets::Paragraph, Frame};
@@ -19,6 +19,8 @@ pub struct RenameBranchPopup {
 	repo: RepoPathRef,
 	input: TextInputComponent,
 	branch_ref: Option<String>,
+	/// renames started from the branch list go back to it
+	back_to_branchlist: bool,
 	queue: Queue,
 	key_config: SharedKeyConfig,
 	theme: SharedTheme,
@@ -101,6 +103,7 @@ impl RenameBranchPopup {
 			)
 			.with_input_type(InputType::Singleline),
 			branch_ref: None,
+			back_to_branchlist: true,
 			key_config: env.key_config.clone(),
 			theme: env.theme.clone(),
 		}
@@ -114,12 +117,45 @@ impl RenameBranchPopup {
 	) -> Result<()> {
 		self.branch_ref = None;
 		self.branch_ref = Some(branch_ref);
+		self.back_to_branchlist = true;
 		self.input.set_text(cur_name);
 		self.show()?;
 
 		Ok(())
 	}
 
+	/// opens the popup for the checked out branch, refused while a
+	/// merge, rebase or revert is pending
+	pub fn open_current(&mut self) -> Result<()> {
+		let state = sync::repo_state(&self.repo.borrow())?;
+		if state != RepoState::Clean {
+			self.queue.push(InternalEvent::ShowErrorMsg(
+				strings::rename_current_branch_pending(
+					&format!("{state:?}").to_lowercase(),
+				),
+			));
+			return Ok(());
+		}
+
+		let Some(branch) =
+			sync::get_branches_info(&self.repo.borrow(), true)?
+				.into_iter()
+				.find(|branch| {
+					branch.local_details().is_some_and(|b| b.is_head)
+				})
+		else {
+			self.queue.push(InternalEvent::ShowErrorMsg(
+				strings::rename_current_branch_detached(),
+			));
+			return Ok(());
+		};
+
+		self.open(branch.reference, branch.name)?;
+		self.back_to_branchlist = false;
+
+		Ok(())
+	}
+
 	///
 	pub fn rename_branch(&mut self) {
 		if let Some(br) = &self.branch_ref {
@@ -132,10 +168,12 @@ impl RenameBranchPopup {
 			match res {
 				Ok(()) => {
 					self.queue.push(InternalEvent::Update(
-						NeedsUpdate::ALL,
+						NeedsUpdate::ALL | NeedsUpdate::BRANCHES,
 					));
 					self.hide();
-					self.queue.push(InternalEvent::SelectBranch);
+					if self.back_to_branchlist {
+						self.queue.push(InternalEvent::SelectBranch);
+					}
 				}
 				Err(e) => {
 					log::error!("create branch: {}", e,);
@@ -0,0 +1,340 @@
+use crate::{
+	app::Environment,
+	components::{
+		visibility_blocking, CommandBlocking, CommandInfo, Component,
+		DrawableComponent, EventState,
+	},
+	keys::{key_match, SharedKeyConfig},
+	popups::CommitStep,
+	queue::{InternalEvent, NeedsUpdate, Queue},
+	strings, try_or_popup,
+	ui::{self, style::SharedTheme},
+};
+use anyhow::Result;
+use asyncgit::sync::{self, RepoPathRef, StagedChecks};
+use bytesize::ByteSize;
+use crossterm::event::Event;
+use easy_cast::Cast;
+use itertools::Itertools;
+use ratatui::{
+	layout::Rect,
+	text::{Line, Span},
+	widgets::{Block, Borders, Clear, Paragraph, Wrap},
+	Frame,
+};
+
+/// lines of the list shown at once
+const MAX_ROWS: usize = 12;
+
+/// what the staged checks found when committing: files adding conflict
+/// markers (which can be jumped to) and large files (which can be
+/// unstaged right away). depending on `gitui.conflictMarkers.check`
+/// the commit can be made anyway or is refused
+pub struct StagedChecksPopup {
+	checks: Option<StagedChecks>,
+	/// conflict markers first, then the large files
+	selection: usize,
+	repo: RepoPathRef,
+	queue: Queue,
+	key_config: SharedKeyConfig,
+	theme: SharedTheme,
+}
+
+impl StagedChecksPopup {
+	///
+	pub fn new(env: &Environment) -> Self {
+		Self {
+			checks: None,
+			selection: 0,
+			repo: env.repo.clone(),
+			queue: env.queue.clone(),
+			key_config: env.key_config.clone(),
+			theme: env.theme.clone(),
+		}
+	}
+
+	///
+	pub fn open(&mut self, checks: StagedChecks) {
+		self.checks = Some(checks);
+		self.selection = 0;
+	}
+
+	fn blocks(&self) -> bool {
+		self.checks.as_ref().is_some_and(StagedChecks::blocks)
+	}
+
+	fn entries(&self) -> usize {
+		self.checks.as_ref().map_or(0, |checks| {
+			checks.conflict_markers.len() + checks.large_files.len()
+		})
+	}
+
+	/// index into the large files if one is selected
+	fn selected_large_file(&self) -> Option<usize> {
+		let markers = self.checks.as_ref()?.conflict_markers.len();
+
+		self.selection.checked_sub(markers)
+	}
+
+	fn get_text(&self, checks: &StagedChecks) -> Vec<Line<'_>> {
+		let entry = |text: String, idx: usize| {
+			Line::from(Span::styled(
+				text,
+				self.theme.text(true, idx == self.selection),
+			))
+		};
+
+		let mut rows = Vec::new();
+		let mut selected_row = 0;
+
+		if !checks.conflict_markers.is_empty() {
+			rows.push(Line::from(Span::styled(
+				strings::staged_checks_conflict_markers(),
+				self.theme.text_danger(),
+			)));
+			for (idx, markers) in
+				checks.conflict_markers.iter().enumerate()
+			{
+				if idx == self.selection {
+					selected_row = rows.len();
+				}
+				rows.push(entry(
+					format!(
+						"{}: {}",
+						markers.path,
+						markers.lines.iter().join(", ")
+					),
+					idx,
+				));
+			}
+		}
+
+		if !checks.large_files.is_empty() {
+			rows.push(Line::from(Span::styled(
+				strings::staged_checks_large_files(),
+				self.theme.text_danger(),
+			)));
+			let offset = checks.conflict_markers.len();
+			for (idx, file) in checks.large_files.iter().enumerate() {
+				if offset + idx == self.selection {
+					selected_row = rows.len();
+				}
+				rows.push(entry(
+					format!(
+						"{} ({})",
+						file.path,
+						ByteSize::b(file.size)
+					),
+					offset + idx,
+				));
+			}
+		}
+
+		let mut txt = vec![
+			Line::from(Span::styled(
+				strings::staged_checks_msg(checks.blocks()),
+				self.theme.text(true, false),
+			)),
+			Line::default(),
+		];
+
+		txt.extend(
+			rows.into_iter()
+				.skip(selected_row.saturating_sub(MAX_ROWS - 1))
+				.take(MAX_ROWS),
+		);
+
+		if checks.uses_lfs && !checks.large_files.is_empty() {
+			txt.push(Line::default());
+			txt.push(Line::from(Span::styled(
+				strings::staged_checks_lfs_hint(),
+				self.theme.text(true, false),
+			)));
+		}
+
+		txt
+	}
+
+	fn move_selection(&mut self, up: bool) {
+		self.selection = if up {
+			self.selection.saturating_sub(1)
+		} else {
+			(self.selection + 1).min(self.entries().saturating_sub(1))
+		};
+	}
+
+	/// jumps to the diff of a file with conflict markers or unstages a
+	/// large file
+	fn confirm_selection(&mut self) -> Result<()> {
+		let Some(checks) = &self.checks else {
+			return Ok(());
+		};
+
+		if let Some(idx) = self.selected_large_file() {
+			self.unstage_large_file(idx)?;
+		} else if let Some(markers) =
+			checks.conflict_markers.get(self.selection)
+		{
+			self.queue.push(InternalEvent::SelectStagedFile(
+				markers.path.clone(),
+			));
+			self.hide();
+		}
+
+		Ok(())
+	}
+
+	fn unstage_large_file(&mut self, idx: usize) -> Result<()> {
+		let Some(checks) = &mut self.checks else {
+			return Ok(());
+		};
+
+		sync::reset_stage(
+			&self.repo.borrow(),
+			&checks.large_files[idx].path,
+		)?;
+		checks.large_files.remove(idx);
+		let done = checks.is_empty();
+		self.queue.push(InternalEvent::Update(NeedsUpdate::ALL));
+
+		if done {
+			self.hide();
+		} else {
+			self.selection =
+				self.selection.min(self.entries().saturating_sub(1));
+		}
+
+		Ok(())
+	}
+}
+
+impl DrawableComponent for StagedChecksPopup {
+	fn draw(&self, f: &mut Frame, area: Rect) -> Result<()> {
+		if let Some(checks) = &self.checks {
+			const WIDTH: u16 = 70;
+
+			let txt = self.get_text(checks);
+			// the message might wrap once
+			let height: u16 = (txt.len() + 3).cast();
+			let area =
+				ui::centered_rect_absolute(WIDTH, height, area);
+
+			f.render_widget(Clear, area);
+			f.render_widget(
+				Paragraph::new(txt).wrap(Wrap { trim: false }).block(
+					Block::default()
+						.borders(Borders::ALL)
+						.title(Span::styled(
+							strings::staged_checks_title(
+								&self.key_config,
+							),
+							self.theme.title(true),
+						))
+						.border_style(self.theme.block(true)),
+				),
+				area,
+			);
+		}
+
+		Ok(())
+	}
+}
+
+impl Component for StagedChecksPopup {
+	fn commands(
+		&self,
+		out: &mut Vec<CommandInfo>,
+		force_all: bool,
+	) -> CommandBlocking {
+		if self.is_visible() || force_all {
+			let large_file = self.selected_large_file().is_some();
+			out.push(
+				CommandInfo::new(
+					strings::commands::staged_checks_diff(
+						&self.key_config,
+					),
+					true,
+					!large_file || force_all,
+				)
+				.order(1),
+			);
+			out.push(
+				CommandInfo::new(
+					strings::commands::staged_checks_unstage(
+						&self.key_config,
+					),
+					true,
+					large_file || force_all,
+				)
+				.order(1),
+			);
+			out.push(
+				CommandInfo::new(
+					strings::commands::staged_checks_commit(
+						&self.key_config,
+					),
+					!self.blocks(),
+					true,
+				)
+				.order(1),
+			);
+			out.push(
+				CommandInfo::new(
+					strings::commands::close_popup(&self.key_config),
+					true,
+					true,
+				)
+				.order(1),
+			);
+		}
+
+		visibility_blocking(self)
+	}
+
+	fn event(&mut self, event: &Event) -> Result<EventState> {
+		if self.is_visible() {
+			if let Event::Key(key) = event {
+				if key_match(key, self.key_config.keys.exit_popup) {
+					self.hide();
+				} else if key_match(key, self.key_config.keys.move_up)
+				{
+					self.move_selection(true);
+				} else if key_match(
+					key,
+					self.key_config.keys.move_down,
+				) {
+					self.move_selection(false);
+				} else if key_match(key, self.key_config.keys.enter) {
+					try_or_popup!(
+						self,
+						"unstage error:",
+						self.confirm_selection()
+					);
+				} else if key_match(key, self.key_config.keys.commit)
+					&& !self.blocks()
+				{
+					self.hide();
+					self.queue.push(InternalEvent::ContinueCommit(
+						CommitStep::Summary,
+					));
+				}
+			}
+
+			return Ok(EventState::Consumed);
+		}
+
+		Ok(EventState::NotConsumed)
+	}
+
+	fn is_visible(&self) -> bool {
+		self.checks.is_some()
+	}
+
+	fn hide(&mut self) {
+		self.checks = None;
+	}
+
+	fn show(&mut self) -> Result<()> {
+		Ok(())
+	}
+}
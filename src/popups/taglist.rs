@@ -6,7 +6,8 @@ use crate::{
 	app::Environment,
 	components::ScrollType,
 	keys::{key_match, SharedKeyConfig},
-	queue::{Action, InternalEvent, Queue},
+	popups::FileTreeOpen,
+	queue::{Action, InternalEvent, Queue, StackablePopupOpen},
 	strings,
 	ui::{self, Size},
 	AsyncNotification,
@@ -166,11 +167,23 @@ impl Component for TagListPopup {
 				self.valid_selection(),
 				true,
 			));
+			out.push(CommandInfo::new(
+				strings::commands::inspect_file_tree(
+					&self.key_config,
+				),
+				self.valid_selection(),
+				true,
+			));
 			out.push(CommandInfo::new(
 				strings::commands::push_tags(&self.key_config),
 				self.has_remotes,
 				true,
 			));
+			out.push(CommandInfo::new(
+				strings::commands::fetch_tags(&self.key_config),
+				self.has_remotes,
+				true,
+			));
 			out.push(CommandInfo::new(
 				strings::commands::show_tag_annotation(
 					&self.key_config,
@@ -182,6 +195,7 @@ impl Component for TagListPopup {
 		visibility_blocking(self)
 	}
 
+	#[allow(clippy::too_many_lines)]
 	fn event(&mut self, event: &Event) -> Result<EventState> {
 		if self.visible {
 			if let Event::Key(key) = event {
@@ -257,10 +271,35 @@ impl Component for TagListPopup {
 							Ok(EventState::Consumed)
 						},
 					);
+				} else if key_match(
+					key,
+					self.key_config.keys.open_file_tree,
+				) {
+					return self.selected_tag().map_or(
+						Ok(EventState::NotConsumed),
+						|tag| {
+							self.queue.push(
+								InternalEvent::OpenPopup(
+									StackablePopupOpen::FileTree(
+										FileTreeOpen::new(
+											tag.commit_id,
+										),
+									),
+								),
+							);
+							Ok(EventState::Consumed)
+						},
+					);
 				} else if key_match(key, self.key_config.keys.push)
 					&& self.has_remotes
 				{
 					self.queue.push(InternalEvent::PushTags);
+				} else if key_match(
+					key,
+					self.key_config.keys.fetch_tags,
+				) && self.has_remotes
+				{
+					self.queue.push(InternalEvent::FetchTags);
 				}
 			}
 
@@ -357,6 +396,16 @@ impl TagListPopup {
 			AsyncNotification::Git(AsyncGitNotification::PushTags)
 		) {
 			self.update_missing_remote_tags();
+		} else if self.visible
+			&& matches!(
+				ev,
+				AsyncNotification::Git(AsyncGitNotification::Fetch)
+			) {
+			// a fetch may have brought in tags
+			if let Err(e) = self.update_tags() {
+				log::error!("tags update error: {e}");
+			}
+			self.update_missing_remote_tags();
 		}
 	}
 
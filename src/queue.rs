@@ -1,14 +1,16 @@
 use crate::{
 	components::FuzzyFinderTarget,
 	popups::{
-		AppOption, BlameFileOpen, FileRevOpen, FileTreeOpen,
-		InspectCommitOpen,
+		AppOption, BlameFileOpen, CommitStep, FileRevOpen,
+		FileTreeOpen, InspectCommitOpen,
 	},
 	tabs::StashingOptions,
 };
 use asyncgit::{
 	sync::{
-		diff::DiffLinePosition, CommitId, LogFilterSearchOptions,
+		diff::DiffLinePosition, CommitId, DropCommitInfo,
+		FetchTarget, LogFilterSearchOptions, PostRewriteSource,
+		RepoState, StagedChecks, StagedSummary,
 	},
 	PushType,
 };
@@ -52,11 +54,19 @@ pub enum Action {
 	DeleteRemoteTag(String, String),
 	DeleteRemote(String),
 	ForcePush(String, bool),
-	PullMerge { incoming: usize, rebase: bool },
+	PullMerge {
+		incoming: usize,
+		rebase: bool,
+	},
 	AbortMerge,
 	AbortRebase,
 	AbortRevert,
 	UndoCommit,
+	DropCommit(CommitId, DropCommitInfo),
+	/// overwrite a file with its version in a commit
+	CheckoutFile(CommitId, String),
+	/// text too large to be pasted without asking
+	LargePaste(String),
 }
 
 #[derive(Debug)]
@@ -117,12 +127,46 @@ pub enum InternalEvent {
 	UpdateRemoteUrl(String, String),
 	///
 	RenameBranch(String, String),
+	/// rename the checked out branch
+	RenameCurrentBranch,
+	/// move the uncommitted changes to a new branch
+	MoveChangesToBranch,
+	/// name of the local branch to edit the description of
+	EditBranchDescription(String),
 	///
 	SelectBranch,
 	///
+	RecentBranches,
+	/// like `git checkout -`
+	CheckoutPreviousBranch,
+	///
+	ShowIgnoredFiles,
+	/// path to check right away
+	CheckIgnore(Option<String>),
+	///
+	OpenCompareBasePopup,
+	/// names of the presets to pick from
+	OpenCommitPresetsPopup(Vec<String>),
+	/// index of the preset to apply to the commit message
+	ApplyCommitPreset(usize),
+	///
 	OpenExternalEditor(Option<String>),
+	/// run an interactive shell in the working directory
+	OpenShell,
+	/// explain the operation in progress that blocks the one requested
+	ShowPendingOperation(RepoState),
+	/// what is about to be committed, to be confirmed first
+	ShowCommitSummary(StagedSummary),
+	/// a popup shown before committing was confirmed
+	ContinueCommit(CommitStep),
+	/// what the checks of the staged changes found when committing
+	ShowStagedChecks(StagedChecks),
+	/// select the staged file and show its diff
+	SelectStagedFile(String),
 	///
 	Push(String, PushType, bool, bool),
+	/// push the branch to every remote of `gitui.pushAll.remote`
+	PushAllRemotes(String),
 	///
 	Pull(String),
 	///
@@ -137,6 +181,12 @@ pub enum InternalEvent {
 	FuzzyFinderChanged(usize, String, FuzzyFinderTarget),
 	///
 	FetchRemotes,
+	/// ask for the single branch or refspec to fetch
+	OpenFetchRef,
+	///
+	FetchTarget(FetchTarget),
+	/// fetch every tag of all remotes but no branches
+	FetchTags,
 	///
 	OpenPopup(StackablePopupOpen),
 	///
@@ -155,6 +205,23 @@ pub enum InternalEvent {
 	OpenResetPopup(CommitId),
 	///
 	RewordCommit(CommitId),
+	/// squash the commits `base..=tip` into one
+	SquashCommits { base: CommitId, tip: CommitId },
+	/// a checkout away from `prev_head` is done, runs the
+	/// `post-checkout` hook
+	PostCheckout {
+		prev_head: CommitId,
+		branch_checkout: bool,
+	},
+	/// a merge (or fast-forward) is committed, runs the `post-merge`
+	/// hook
+	PostMerge { is_squash: bool },
+	/// commits were rewritten, runs the `post-rewrite` hook with the
+	/// `(old, new)` ids
+	PostRewrite {
+		source: PostRewriteSource,
+		rewritten: Vec<(CommitId, CommitId)>,
+	},
 	///
 	CommitSearch(LogFilterSearchOptions),
 }
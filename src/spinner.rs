@@ -1,3 +1,7 @@
+use crossterm::{
+	cursor::{RestorePosition, SavePosition},
+	QueueableCommand,
+};
 use ratatui::{
 	backend::{Backend, CrosstermBackend},
 	Terminal,
@@ -55,9 +59,12 @@ impl Spinner {
 				.set_char(char_to_draw)
 				.clone();
 
+			// the cursor might be visible in a text input
+			terminal.backend_mut().queue(SavePosition)?;
 			terminal
 				.backend_mut()
 				.draw(vec![(0_u16, 0_u16, &c)].into_iter())?;
+			terminal.backend_mut().queue(RestorePosition)?;
 
 			Backend::flush(terminal.backend_mut())?;
 		}
@@ -0,0 +1,163 @@
+//! reading and writing of files gitui persists state in.
+//!
+//! files are never written in place: the new content goes to a temporary
+//! file next to it which is synced and then renamed over the old one, so
+//! a crash (or power loss) leaves either the old or the new file behind.
+//! files that do not parse anyway are moved aside instead of making
+//! gitui fail to start, see [`load`].
+
+use anyhow::Result;
+use std::{
+	ffi::OsString,
+	fs::{self, File},
+	io::{ErrorKind, Write},
+	path::{Path, PathBuf},
+};
+
+/// outcome of [`load`]
+pub enum Stored<T> {
+	/// nothing was stored yet
+	Missing,
+	Loaded(T),
+	/// the file did not parse and was moved to `moved_to`
+	Corrupt {
+		moved_to: PathBuf,
+	},
+}
+
+/// replaces the content of `path` with `data` atomically
+pub fn write_atomic(path: &Path, data: &[u8]) -> Result<()> {
+	let tmp = sibling(path, &format!(".{}.tmp", std::process::id()));
+
+	let written = File::create(&tmp).and_then(|mut file| {
+		file.write_all(data)?;
+		file.sync_all()
+	});
+
+	if let Err(e) = written.and_then(|()| fs::rename(&tmp, path)) {
+		// best effort, the error of writing is more interesting
+		let _ = fs::remove_file(&tmp);
+		return Err(e.into());
+	}
+
+	sync_dir(path);
+
+	Ok(())
+}
+
+/// reads `path` and hands its content to `parse`.
+///
+/// if parsing fails the file is moved to `<path>.corrupt` (replacing an
+/// older one) and [`Stored::Corrupt`] is returned so that the caller can
+/// continue with defaults, only io errors are returned as errors.
+pub fn load<T>(
+	path: &Path,
+	parse: impl FnOnce(&[u8]) -> Result<T>,
+) -> Result<Stored<T>> {
+	let data = match fs::read(path) {
+		Ok(data) => data,
+		Err(e) if e.kind() == ErrorKind::NotFound => {
+			return Ok(Stored::Missing)
+		}
+		Err(e) => return Err(e.into()),
+	};
+
+	match parse(&data) {
+		Ok(value) => Ok(Stored::Loaded(value)),
+		Err(error) => {
+			let moved_to = sibling(path, ".corrupt");
+			fs::rename(path, &moved_to)?;
+
+			log::warn!(
+				"moved unreadable {:?} to {:?}: {error}",
+				path,
+				moved_to
+			);
+
+			Ok(Stored::Corrupt { moved_to })
+		}
+	}
+}
+
+/// `path` with `suffix` appended to its file name
+pub fn sibling(path: &Path, suffix: &str) -> PathBuf {
+	let mut name = OsString::from(path.as_os_str());
+	name.push(suffix);
+	name.into()
+}
+
+/// makes the rename itself durable
+#[cfg(unix)]
+fn sync_dir(path: &Path) {
+	if let Some(dir) = path.parent() {
+		if let Err(e) = File::open(dir).and_then(|dir| dir.sync_all())
+		{
+			log::warn!("syncing {:?} failed: {e}", dir);
+		}
+	}
+}
+
+/// directories can not be opened (and synced) on windows
+#[cfg(not(unix))]
+const fn sync_dir(_: &Path) {}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use pretty_assertions::assert_eq;
+	use tempfile::tempdir;
+
+	fn parse_ron(data: &[u8]) -> Result<Vec<String>> {
+		Ok(ron::de::from_bytes(data)?)
+	}
+
+	#[test]
+	fn test_write_atomic() {
+		let dir = tempdir().unwrap();
+		let path = dir.path().join("state");
+
+		write_atomic(&path, b"first").unwrap();
+		write_atomic(&path, b"second").unwrap();
+
+		assert_eq!(fs::read(&path).unwrap(), b"second");
+		// no temporary files are left behind
+		assert_eq!(fs::read_dir(dir.path()).unwrap().count(), 1);
+	}
+
+	#[test]
+	fn test_load_missing() {
+		let dir = tempdir().unwrap();
+
+		assert!(matches!(
+			load(&dir.path().join("state"), parse_ron).unwrap(),
+			Stored::Missing
+		));
+	}
+
+	#[test]
+	fn test_load_truncated() {
+		let dir = tempdir().unwrap();
+		let path = dir.path().join("state");
+
+		let data = b"[\"a\", \"b\"]";
+		for len in [0, 1, data.len() - 1] {
+			fs::write(&path, &data[..len]).unwrap();
+
+			let Stored::Corrupt { moved_to, .. } =
+				load(&path, parse_ron).unwrap()
+			else {
+				panic!("truncated to {len} bytes still parsed");
+			};
+
+			assert_eq!(moved_to, dir.path().join("state.corrupt"));
+			assert_eq!(fs::read(&moved_to).unwrap(), &data[..len]);
+			assert!(!path.exists());
+		}
+
+		fs::write(&path, data).unwrap();
+		assert!(matches!(
+			load(&path, parse_ron).unwrap(),
+			Stored::Loaded(values) if values == ["a", "b"]
+		));
+	}
+}
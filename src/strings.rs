@@ -1,10 +1,13 @@
-use std::borrow::Cow;
+use std::{borrow::Cow, path::Path, time::Duration};
 
-use asyncgit::sync::CommitId;
+use asyncgit::sync::{
+	CommitId, DropCommitInfo, FetchTags, IgnoreCheck, IgnoreRule,
+	TimeoutSource,
+};
 use unicode_truncate::UnicodeTruncateStr;
 use unicode_width::UnicodeWidthStr;
 
-use crate::keys::SharedKeyConfig;
+use crate::keys::{GituiKeyEvent, SharedKeyConfig};
 
 pub mod order {
 	pub const RARE_ACTION: i8 = 30;
@@ -106,12 +109,54 @@ pub fn msg_title_error(_key_config: &SharedKeyConfig) -> String {
 pub fn msg_title_info(_key_config: &SharedKeyConfig) -> String {
 	"Info".to_string()
 }
+pub fn msg_title_audit_log() -> String {
+	"Audit log".to_string()
+}
+pub fn audit_log_disabled() -> String {
+	format!(
+		"no audit log is kept for this repository.\nenable it with: git config {} <file>",
+		crate::audit::AUDIT_LOG_CONFIG
+	)
+}
+pub fn audit_log_empty() -> String {
+	"nothing recorded yet".to_string()
+}
+pub fn options_corrupt(moved_to: &Path) -> String {
+	format!(
+		"options could not be read and were reset to defaults.\nthe unreadable file was moved to: {}",
+		moved_to.display()
+	)
+}
+pub fn macro_recording(register: char) -> String {
+	format!("recording @{register}")
+}
+pub fn macro_replaying(register: char) -> String {
+	format!("replaying @{register}")
+}
+pub fn macro_not_recorded(register: char) -> String {
+	format!("no macro recorded in register '{register}'")
+}
+pub fn macro_replay_stopped(
+	key_config: &SharedKeyConfig,
+	key: GituiKeyEvent,
+) -> String {
+	format!(
+		"macro replay stopped: [{}] is not available here",
+		key_config.get_hint(key)
+	)
+}
+pub fn macro_replay_too_deep(max_depth: usize) -> String {
+	format!("macro replay stopped: macros nested more than {max_depth} deep (does a macro replay itself?)")
+}
 pub fn commit_title() -> String {
 	"Commit".to_string()
 }
 pub fn commit_reword_title() -> String {
 	"Reword Commit".to_string()
 }
+pub fn commit_title_squash() -> String {
+	"Squash Commits".to_string()
+}
 
 pub fn commit_title_merge() -> String {
 	"Commit (Merge)".to_string()
@@ -128,6 +173,52 @@ pub fn commit_msg(_key_config: &SharedKeyConfig) -> String {
 pub fn commit_first_line_warning(count: usize) -> String {
 	format!("[subject length: {count}]")
 }
+/// the order in which the hook timeouts apply, one line each
+pub const fn hooks_timeout_precedence() -> [&'static str; 3] {
+	[
+		"first one set applies: Timeout (session),",
+		"gitui.hooksTimeout.<hook>, Config timeout",
+		"(gitui.hooks.timeout), gitui.hooksTimeout",
+	]
+}
+pub fn commit_hooks_running(key_config: &SharedKeyConfig) -> String {
+	format!(
+		"running commit hooks.. [{}] to cancel",
+		key_config.get_hint(key_config.keys.exit_popup),
+	)
+}
+pub fn commit_cancelled(hook: &str) -> String {
+	format!("commit cancelled, the {hook} hook was stopped")
+}
+pub const fn commit_msg_preview_running() -> &'static str {
+	"commit-msg hook: running.."
+}
+pub const fn commit_msg_preview_passed() -> &'static str {
+	"commit-msg hook: passed"
+}
+pub const fn commit_msg_preview_rejected() -> &'static str {
+	"commit-msg hook: rejected"
+}
+pub const fn commit_msg_preview_timed_out() -> &'static str {
+	"commit-msg hook: timed out"
+}
+pub fn fetch_tags_created(count: usize) -> String {
+	match count {
+		0 => "no new tags fetched".to_string(),
+		1 => "fetched 1 new tag".to_string(),
+		_ => format!("fetched {count} new tags"),
+	}
+}
+pub const fn fetch_tags_mode(tags: FetchTags) -> &'static str {
+	match tags {
+		FetchTags::All => "all",
+		FetchTags::Auto => "auto",
+		FetchTags::None => "none",
+	}
+}
+pub const fn fetch_ref_invalid() -> &'static str {
+	"[invalid refspec]"
+}
 pub const fn branch_name_invalid() -> &'static str {
 	"[invalid name]"
 }
@@ -210,6 +301,49 @@ pub fn confirm_msg_reset_lines(lines: usize) -> String {
 pub fn confirm_msg_undo_commit() -> String {
 	"confirm undo last commit?".to_string()
 }
+pub fn confirm_title_drop_commit() -> String {
+	"Drop commit".to_string()
+}
+pub fn confirm_title_checkout_file() -> String {
+	"Checkout file".to_string()
+}
+pub fn confirm_msg_checkout_file(
+	id: &CommitId,
+	path: &str,
+) -> String {
+	format!(
+		"Overwrite '{path}' with its version at {}?\nLocal changes to it will be lost.",
+		id.get_short_string()
+	)
+}
+pub fn confirm_msg_drop_commit(
+	id: &CommitId,
+	info: &DropCommitInfo,
+) -> String {
+	let mut msg = format!(
+		"Remove commit {} from the branch?\n{} commit{} on top of it will be rewritten.",
+		id.get_short_string(),
+		info.descendants,
+		if info.descendants == 1 { "" } else { "s" }
+	);
+	if info.pushed {
+		msg.push_str("\n\nThe commit is already pushed, dropping it rewrites published history!");
+	}
+	msg
+}
+pub fn drop_commit_conflicted() -> String {
+	"Dropping the commit caused conflicts: resolve them and continue the rebase, or abort it.".to_string()
+}
+pub fn confirm_title_large_paste() -> String {
+	"Large paste".to_string()
+}
+pub fn confirm_msg_large_paste(text: &str) -> String {
+	format!(
+		"Paste {} lines ({} KiB)?",
+		text.lines().count(),
+		text.len() / 1024
+	)
+}
 pub fn confirm_msg_stashdrop(
 	_key_config: &SharedKeyConfig,
 	ids: &[CommitId],
@@ -287,6 +421,48 @@ pub fn confirm_title_delete_tag_remote() -> String {
 pub fn confirm_msg_delete_tag_remote(remote_name: &str) -> String {
 	format!("Confirm deleting tag on remote '{remote_name}'?")
 }
+pub fn hook_timed_out(
+	name: &str,
+	timeout: Duration,
+	source: TimeoutSource,
+	hook: &Path,
+	stdout: &str,
+	stderr: &str,
+) -> String {
+	let raise = source.config_key(name).map_or_else(
+		|| String::from("the session 'Timeout' in the options"),
+		|key| format!("'{key}'"),
+	);
+
+	format!(
+		"{name} hook timed out after {} ms and was killed ({}), raise {raise} to give it more time. see output below.\n{stdout}\n{stderr}",
+		timeout.as_millis(),
+		hook.display(),
+	)
+}
+pub fn push_all_remotes_title(
+	remote: &str,
+	current: usize,
+	total: usize,
+) -> String {
+	format!("Push to '{remote}' ({current}/{total})")
+}
+pub fn push_all_remotes_summary(
+	branch: &str,
+	results: &[(String, Option<String>)],
+) -> String {
+	let lines: String = results
+		.iter()
+		.map(|(remote, err)| {
+			err.as_ref().map_or_else(
+				|| format!("\n{remote}: ok"),
+				|err| format!("\n{remote}: failed: {err}"),
+			)
+		})
+		.collect();
+
+	format!("pushed '{branch}':{lines}")
+}
 pub fn confirm_title_force_push(
 	_key_config: &SharedKeyConfig,
 ) -> String {
@@ -342,6 +518,14 @@ pub fn stashing_options_title(
 pub fn loading_text(_key_config: &SharedKeyConfig) -> String {
 	"Loading ...".to_string()
 }
+pub fn fetch_ref_popup_title(
+	_key_config: &SharedKeyConfig,
+) -> String {
+	"Fetch".to_string()
+}
+pub fn fetch_ref_popup_msg(_key_config: &SharedKeyConfig) -> String {
+	"[remote] branch or refspec, e.g. origin/main".to_string()
+}
 pub fn create_branch_popup_title(
 	_key_config: &SharedKeyConfig,
 ) -> String {
@@ -408,6 +592,74 @@ pub fn password_popup_msg(_key_config: &SharedKeyConfig) -> String {
 	"type password".to_string()
 }
 
+pub fn compare_base_popup_title(
+	_key_config: &SharedKeyConfig,
+) -> String {
+	"Comparison Base".to_string()
+}
+pub fn compare_base_popup_msg(
+	_key_config: &SharedKeyConfig,
+) -> String {
+	"branch to compare to (empty: remote default branch)".to_string()
+}
+pub fn commit_presets_title(_key_config: &SharedKeyConfig) -> String {
+	"Commit Presets".to_string()
+}
+pub fn commit_presets_empty(_key_config: &SharedKeyConfig) -> String {
+	"no presets configured (see commit_presets.ron)".to_string()
+}
+pub fn performance_title(_key_config: &SharedKeyConfig) -> String {
+	"Performance".to_string()
+}
+pub fn recent_branches_title(
+	_key_config: &SharedKeyConfig,
+) -> String {
+	"Recent Branches".to_string()
+}
+pub fn recent_branches_empty(
+	_key_config: &SharedKeyConfig,
+) -> String {
+	"no previously checked out branches".to_string()
+}
+pub fn ignored_files_title(_key_config: &SharedKeyConfig) -> String {
+	"Ignored Files".to_string()
+}
+pub fn ignored_files_empty(_key_config: &SharedKeyConfig) -> String {
+	"no ignored files".to_string()
+}
+/// like `git check-ignore -v`: `<source>:<line>:<pattern>`
+pub fn ignore_rule(rule: &IgnoreRule) -> String {
+	format!("{}:{}:{}", rule.source, rule.line, rule.pattern)
+}
+pub const fn ignored_without_rule() -> &'static str {
+	"(no matching rule found)"
+}
+pub fn check_ignore_popup_title(
+	_key_config: &SharedKeyConfig,
+) -> String {
+	"Check Ignore".to_string()
+}
+pub fn check_ignore_popup_msg(
+	_key_config: &SharedKeyConfig,
+) -> String {
+	"path relative to the work dir, e.g. target/ or src/main.rs"
+		.to_string()
+}
+pub fn check_ignore_result(check: &IgnoreCheck) -> String {
+	match &check.rule {
+		_ if check.tracked => {
+			"tracked, ignore rules do not apply".to_string()
+		}
+		Some(rule) if rule.is_negated() => {
+			format!(
+				"not ignored, re-included by {}",
+				ignore_rule(rule)
+			)
+		}
+		Some(rule) => format!("ignored by {}", ignore_rule(rule)),
+		None => "not ignored, no rule matches".to_string(),
+	}
+}
 pub fn rename_branch_popup_title(
 	_key_config: &SharedKeyConfig,
 ) -> String {
@@ -418,6 +670,121 @@ pub fn rename_branch_popup_msg(
 ) -> String {
 	"new branch name".to_string()
 }
+pub const fn branch_broken() -> &'static str {
+	"[broken: commit missing]"
+}
+pub fn rename_current_branch_pending(state: &str) -> String {
+	format!(
+		"cannot rename the branch while a {state} is in progress, finish or abort it first"
+	)
+}
+pub fn rename_current_branch_detached() -> String {
+	"cannot rename the branch: HEAD is not on a branch".to_string()
+}
+pub fn move_changes_popup_title(
+	local_commits: usize,
+	carry_commits: bool,
+) -> String {
+	match (local_commits, carry_commits) {
+		(0, _) => "Move Changes to Branch".to_string(),
+		(n, true) => {
+			format!(
+				"Move Changes to Branch (carrying {n} local commits)"
+			)
+		}
+		(n, false) => {
+			format!(
+				"Move Changes to Branch (leaving {n} local commits)"
+			)
+		}
+	}
+}
+pub fn move_changes_pending(state: &str) -> String {
+	format!(
+		"cannot move changes while a {state} is in progress, finish or abort it first"
+	)
+}
+pub fn hooks_invalid_timeout(input: &str) -> String {
+	format!("invalid timeout '{input}', e.g. 1500, 500ms, 5s or 1m")
+}
+pub fn hooks_unknown_hook(name: &str) -> String {
+	format!("unknown hook '{name}'")
+}
+pub fn commit_summary_title(_key_config: &SharedKeyConfig) -> String {
+	"Commit summary".to_string()
+}
+pub fn commit_summary_detached() -> String {
+	"(detached HEAD)".to_string()
+}
+pub fn commit_summary_changes(
+	files: usize,
+	insertions: usize,
+	deletions: usize,
+) -> String {
+	format!("{files} files, +{insertions} -{deletions}")
+}
+pub fn commit_summary_no_warnings() -> String {
+	"nothing suspicious staged".to_string()
+}
+pub fn commit_summary_more_warnings(count: usize) -> String {
+	format!("... and {count} more")
+}
+pub fn commit_summary_truncated() -> String {
+	"not all files were searched for conflict markers".to_string()
+}
+pub fn staged_checks_title(_key_config: &SharedKeyConfig) -> String {
+	"Check staged changes".to_string()
+}
+pub fn staged_checks_msg(blocked: bool) -> String {
+	if blocked {
+		"committing conflict markers is blocked by gitui.conflictMarkers.check, resolve them first".to_string()
+	} else {
+		"the staged changes might not be meant to be committed"
+			.to_string()
+	}
+}
+pub fn staged_checks_conflict_markers() -> String {
+	"conflict markers:".to_string()
+}
+pub fn staged_checks_large_files() -> String {
+	"large files:".to_string()
+}
+pub fn staged_checks_lfs_hint() -> String {
+	"this repo uses git LFS, consider `git lfs track` for large files"
+		.to_string()
+}
+pub fn pending_operation_title(state: &str) -> String {
+	format!("{state} in progress")
+}
+pub fn pending_operation_msg(state: &str) -> String {
+	format!(
+		"a {state} is in progress, started by gitui or another tool.\n\ncheckouts, resets, merges and rebases would wreck it and are refused until it is finished or aborted. staging and viewing diffs to resolve conflicts still works."
+	)
+}
+pub fn move_changes_detached() -> String {
+	"cannot move changes: HEAD is not on a branch".to_string()
+}
+pub fn branch_description_popup_title(
+	_key_config: &SharedKeyConfig,
+) -> String {
+	"Branch Description".to_string()
+}
+pub fn branch_description_popup_msg(
+	_key_config: &SharedKeyConfig,
+) -> String {
+	"describe the branch (empty: remove description)".to_string()
+}
+pub fn branch_description_title() -> String {
+	"Description".to_string()
+}
+pub fn branch_description_empty(
+	key_config: &SharedKeyConfig,
+) -> String {
+	format!(
+		"no description [{}]",
+		key_config.get_hint(key_config.keys.edit_branch_description),
+	)
+}
 
 pub fn copy_success(s: &str) -> String {
 	format!("{POPUP_SUCCESS_COPY} \"{s}\"")
@@ -553,6 +920,30 @@ pub mod commands {
 			CMD_GROUP_GENERAL,
 		)
 	}
+	pub fn performance_popup(
+		key_config: &SharedKeyConfig,
+	) -> CommandText {
+		CommandText::new(
+			format!(
+				"Performance [{}]",
+				key_config.get_hint(key_config.keys.open_performance),
+			),
+			"show frames rendered vs notifications received",
+			CMD_GROUP_GENERAL,
+		)
+	}
+	pub fn audit_log_popup(
+		key_config: &SharedKeyConfig,
+	) -> CommandText {
+		CommandText::new(
+			format!(
+				"Audit log [{}]",
+				key_config.get_hint(key_config.keys.open_audit_log),
+			),
+			"show the recent ref changing operations",
+			CMD_GROUP_GENERAL,
+		)
+	}
 	pub fn help_open(key_config: &SharedKeyConfig) -> CommandText {
 		CommandText::new(
 			format!(
@@ -563,6 +954,63 @@ pub mod commands {
 			CMD_GROUP_GENERAL,
 		)
 	}
+	pub fn text_viewer_search(
+		key_config: &SharedKeyConfig,
+	) -> CommandText {
+		CommandText::new(
+			format!(
+				"Search [{}]",
+				key_config
+					.get_hint(key_config.keys.text_viewer_search),
+			),
+			"search text",
+			CMD_GROUP_GENERAL,
+		)
+	}
+	pub fn text_viewer_next_match(
+		key_config: &SharedKeyConfig,
+	) -> CommandText {
+		CommandText::new(
+			format!(
+				"Next Match [{}]",
+				key_config
+					.get_hint(key_config.keys.text_viewer_next_match),
+			),
+			"jump to next search match",
+			CMD_GROUP_GENERAL,
+		)
+	}
+	pub fn text_viewer_toggle_wrap(
+		key_config: &SharedKeyConfig,
+		wrap: bool,
+	) -> CommandText {
+		CommandText::new(
+			format!(
+				"Wrap lines [{}]",
+				key_config.get_hint(
+					key_config.keys.text_viewer_toggle_wrap
+				),
+			),
+			if wrap {
+				"stop wrapping long lines"
+			} else {
+				"wrap long lines"
+			},
+			CMD_GROUP_GENERAL,
+		)
+	}
+	pub fn text_viewer_copy(
+		key_config: &SharedKeyConfig,
+	) -> CommandText {
+		CommandText::new(
+			format!(
+				"Copy [{}]",
+				key_config.get_hint(key_config.keys.copy),
+			),
+			"copy whole text to clipboard",
+			CMD_GROUP_GENERAL,
+		)
+	}
 	pub fn navigate_commit_message(
 		key_config: &SharedKeyConfig,
 	) -> CommandText {
@@ -656,6 +1104,16 @@ pub mod commands {
 			CMD_GROUP_LOG,
 		)
 	}
+	pub fn fetch_tags(key_config: &SharedKeyConfig) -> CommandText {
+		CommandText::new(
+			format!(
+				"Fetch Tags [{}]",
+				key_config.get_hint(key_config.keys.fetch_tags),
+			),
+			"fetch all tags from all remotes",
+			CMD_GROUP_LOG,
+		)
+	}
 	pub fn toggle_option(
 		key_config: &SharedKeyConfig,
 	) -> CommandText {
@@ -704,6 +1162,46 @@ pub mod commands {
 			CMD_GROUP_DIFF,
 		)
 	}
+	pub fn diff_search(key_config: &SharedKeyConfig) -> CommandText {
+		CommandText::new(
+			format!(
+				"Search [{}]",
+				key_config.get_hint(key_config.keys.diff_search),
+			),
+			"search the lines of the diff",
+			CMD_GROUP_DIFF,
+		)
+	}
+	pub fn diff_search_next(
+		key_config: &SharedKeyConfig,
+	) -> CommandText {
+		CommandText::new(
+			format!(
+				"Next Match [{}]",
+				key_config.get_hint(key_config.keys.diff_search_next),
+			),
+			"select the next line matching the search",
+			CMD_GROUP_DIFF,
+		)
+	}
+	pub fn diff_toggle_word_diff(
+		key_config: &SharedKeyConfig,
+		word_diff: bool,
+	) -> CommandText {
+		CommandText::new(
+			format!(
+				"Word diff [{}]",
+				key_config
+					.get_hint(key_config.keys.diff_toggle_word_diff),
+			),
+			if word_diff {
+				"stop highlighting changed words"
+			} else {
+				"highlight the changed words of modified lines"
+			},
+			CMD_GROUP_DIFF,
+		)
+	}
 	pub fn diff_home_end(
 		key_config: &SharedKeyConfig,
 	) -> CommandText {
@@ -951,6 +1449,19 @@ pub mod commands {
 		)
 	}
 
+	pub fn fetch_tags_mode(
+		key_config: &SharedKeyConfig,
+	) -> CommandText {
+		CommandText::new(
+			format!(
+				"Tags [{}]",
+				key_config.get_hint(key_config.keys.fetch_tags_mode),
+			),
+			"toggle which tags are fetched from the remote: all, auto (only those pointing into fetched history) or none",
+			CMD_GROUP_GENERAL,
+		)
+	}
+
 	pub fn remote_confirm_name_msg(
 		key_config: &SharedKeyConfig,
 	) -> CommandText {
@@ -1032,6 +1543,17 @@ pub mod commands {
 		)
 	}
 
+	pub fn open_shell(key_config: &SharedKeyConfig) -> CommandText {
+		CommandText::new(
+			format!(
+				"Open shell [{}]",
+				key_config.get_hint(key_config.keys.open_shell),
+			),
+			"open a shell in the working directory",
+			CMD_GROUP_GENERAL,
+		)
+	}
+
 	pub fn abort_rebase(key_config: &SharedKeyConfig) -> CommandText {
 		CommandText::new(
 			format!(
@@ -1113,38 +1635,74 @@ pub mod commands {
 			CMD_GROUP_COMMIT_POPUP,
 		)
 	}
-	pub fn commit_submit(
+	pub fn commit_presets(
 		key_config: &SharedKeyConfig,
 	) -> CommandText {
 		CommandText::new(
 			format!(
-				"Do Commit [{}]",
-				key_config.get_hint(key_config.keys.commit),
+				"Presets [{}]",
+				key_config.get_hint(key_config.keys.commit_presets),
 			),
-			"commit (available when commit message is non-empty)",
+			"pick a commit message preset",
 			CMD_GROUP_COMMIT_POPUP,
 		)
-		.hide_help()
 	}
-	pub fn newline(key_config: &SharedKeyConfig) -> CommandText {
+	pub fn commit_preset_apply(
+		key_config: &SharedKeyConfig,
+	) -> CommandText {
 		CommandText::new(
 			format!(
-				"New line [{}]",
-				key_config.get_hint(key_config.keys.newline),
+				"Apply [{}]",
+				key_config.get_hint(key_config.keys.enter),
 			),
-			"create line break",
+			"apply selected preset to the commit message",
 			CMD_GROUP_COMMIT_POPUP,
 		)
-		.hide_help()
 	}
-	pub fn toggle_verify(
+	pub fn commit_cancel_hooks(
 		key_config: &SharedKeyConfig,
-		current_verify: bool,
 	) -> CommandText {
-		let verb = if current_verify { "disable" } else { "enable" };
 		CommandText::new(
 			format!(
-				"{} hooks [{}]",
+				"Cancel [{}]",
+				key_config.get_hint(key_config.keys.exit_popup),
+			),
+			"cancel the commit by stopping its hooks",
+			CMD_GROUP_COMMIT_POPUP,
+		)
+	}
+	pub fn commit_submit(
+		key_config: &SharedKeyConfig,
+	) -> CommandText {
+		CommandText::new(
+			format!(
+				"Do Commit [{}]",
+				key_config.get_hint(key_config.keys.commit),
+			),
+			"commit (available when commit message is non-empty)",
+			CMD_GROUP_COMMIT_POPUP,
+		)
+		.hide_help()
+	}
+	pub fn newline(key_config: &SharedKeyConfig) -> CommandText {
+		CommandText::new(
+			format!(
+				"New line [{}]",
+				key_config.get_hint(key_config.keys.newline),
+			),
+			"create line break",
+			CMD_GROUP_COMMIT_POPUP,
+		)
+		.hide_help()
+	}
+	pub fn toggle_verify(
+		key_config: &SharedKeyConfig,
+		current_verify: bool,
+	) -> CommandText {
+		let verb = if current_verify { "disable" } else { "enable" };
+		CommandText::new(
+			format!(
+				"{} hooks [{}]",
 				verb,
 				key_config.get_hint(key_config.keys.toggle_verify),
 			),
@@ -1185,6 +1743,64 @@ pub mod commands {
 			CMD_GROUP_CHANGES,
 		)
 	}
+	pub fn confirm_commit_summary(
+		key_config: &SharedKeyConfig,
+	) -> CommandText {
+		CommandText::new(
+			format!(
+				"Commit [{}]",
+				key_config.get_hint(key_config.keys.enter),
+			),
+			"make the summarized commit",
+			CMD_GROUP_COMMIT_POPUP,
+		)
+	}
+	pub fn staged_checks_diff(
+		key_config: &SharedKeyConfig,
+	) -> CommandText {
+		CommandText::new(
+			format!(
+				"Show diff [{}]",
+				key_config.get_hint(key_config.keys.enter),
+			),
+			"show the diff of the selected file",
+			CMD_GROUP_COMMIT_POPUP,
+		)
+	}
+	pub fn staged_checks_unstage(
+		key_config: &SharedKeyConfig,
+	) -> CommandText {
+		CommandText::new(
+			format!(
+				"Unstage [{}]",
+				key_config.get_hint(key_config.keys.enter),
+			),
+			"unstage the selected file",
+			CMD_GROUP_COMMIT_POPUP,
+		)
+	}
+	pub fn staged_checks_commit(
+		key_config: &SharedKeyConfig,
+	) -> CommandText {
+		CommandText::new(
+			format!(
+				"Commit anyway [{}]",
+				key_config.get_hint(key_config.keys.commit),
+			),
+			"commit the staged changes as they are",
+			CMD_GROUP_COMMIT_POPUP,
+		)
+	}
+	pub fn edit_option(key_config: &SharedKeyConfig) -> CommandText {
+		CommandText::new(
+			format!(
+				"Edit [{}]",
+				key_config.get_hint(key_config.keys.enter),
+			),
+			"edit the selected option",
+			CMD_GROUP_GENERAL,
+		)
+	}
 	pub fn stage_item(key_config: &SharedKeyConfig) -> CommandText {
 		CommandText::new(
 			format!(
@@ -1486,6 +2102,19 @@ pub mod commands {
 			CMD_GROUP_LOG,
 		)
 	}
+	pub fn checkout_file_version(
+		key_config: &SharedKeyConfig,
+	) -> CommandText {
+		CommandText::new(
+			format!(
+				"Checkout [{}]",
+				key_config
+					.get_hint(key_config.keys.log_checkout_commit),
+			),
+			"checkout this version of the file into the worktree",
+			CMD_GROUP_GENERAL,
+		)
+	}
 	pub fn revert_commit(
 		key_config: &SharedKeyConfig,
 	) -> CommandText {
@@ -1524,6 +2153,31 @@ pub mod commands {
 			CMD_GROUP_LOG,
 		)
 	}
+	pub fn log_drop_commit(
+		key_config: &SharedKeyConfig,
+	) -> CommandText {
+		CommandText::new(
+			format!(
+				"Drop [{}]",
+				key_config.get_hint(key_config.keys.log_drop_commit),
+			),
+			"remove commit from the branch",
+			CMD_GROUP_LOG,
+		)
+	}
+	pub fn log_squash_commits(
+		key_config: &SharedKeyConfig,
+	) -> CommandText {
+		CommandText::new(
+			format!(
+				"Squash [{}]",
+				key_config
+					.get_hint(key_config.keys.log_squash_commits),
+			),
+			"squash marked unpushed commits into one",
+			CMD_GROUP_LOG,
+		)
+	}
 	pub fn log_find_commit(
 		key_config: &SharedKeyConfig,
 	) -> CommandText {
@@ -1663,6 +2317,45 @@ pub mod commands {
 			CMD_GROUP_BRANCHES,
 		)
 	}
+	pub fn repair_remote_branch(
+		key_config: &SharedKeyConfig,
+	) -> CommandText {
+		CommandText::new(
+			format!(
+				"Repair [{}]",
+				key_config
+					.get_hint(key_config.keys.repair_remote_branch),
+			),
+			"delete the broken remote branch and fetch it again",
+			CMD_GROUP_BRANCHES,
+		)
+	}
+	pub fn edit_branch_description(
+		key_config: &SharedKeyConfig,
+	) -> CommandText {
+		CommandText::new(
+			format!(
+				"Description [{}]",
+				key_config.get_hint(
+					key_config.keys.edit_branch_description
+				),
+			),
+			"edit branch description",
+			CMD_GROUP_BRANCHES,
+		)
+	}
+	pub fn branch_description_confirm(
+		key_config: &SharedKeyConfig,
+	) -> CommandText {
+		CommandText::new(
+			format!(
+				"Save [{}]",
+				key_config.get_hint(key_config.keys.commit),
+			),
+			"save branch description",
+			CMD_GROUP_GENERAL,
+		)
+	}
 	pub fn delete_branch_popup(
 		key_config: &SharedKeyConfig,
 	) -> CommandText {
@@ -1753,6 +2446,80 @@ pub mod commands {
 			CMD_GROUP_BRANCHES,
 		)
 	}
+	pub fn diff_orig_head(
+		key_config: &SharedKeyConfig,
+	) -> CommandText {
+		CommandText::new(
+			format!(
+				"Diff ORIG_HEAD [{}]",
+				key_config.get_hint(key_config.keys.diff_orig_head),
+			),
+			"compare HEAD to ORIG_HEAD to see what the last reset, rebase or merge changed",
+			CMD_GROUP_GENERAL,
+		)
+	}
+	pub fn macro_record(
+		key_config: &SharedKeyConfig,
+		recording: bool,
+	) -> CommandText {
+		CommandText::new(
+			format!(
+				"{} Macro [{}]",
+				if recording { "Stop" } else { "Record" },
+				key_config.get_hint(key_config.keys.macro_record),
+			),
+			"record keys into a register (followed by its letter)",
+			CMD_GROUP_GENERAL,
+		)
+	}
+	pub fn macro_replay(key_config: &SharedKeyConfig) -> CommandText {
+		CommandText::new(
+			format!(
+				"Replay Macro [{}]",
+				key_config.get_hint(key_config.keys.macro_replay),
+			),
+			"replay a register (followed by an optional count and its letter)",
+			CMD_GROUP_GENERAL,
+		)
+	}
+	pub fn reset_to_orig_head(
+		key_config: &SharedKeyConfig,
+	) -> CommandText {
+		CommandText::new(
+			format!(
+				"Reset to ORIG_HEAD [{}]",
+				key_config
+					.get_hint(key_config.keys.reset_to_orig_head),
+			),
+			"undo the last reset, rebase or merge by resetting to ORIG_HEAD",
+			CMD_GROUP_GENERAL,
+		)
+	}
+	pub fn select_compare_base(
+		key_config: &SharedKeyConfig,
+	) -> CommandText {
+		CommandText::new(
+			format!(
+				"Compare Base [{}]",
+				key_config
+					.get_hint(key_config.keys.select_compare_base),
+			),
+			"change branch to show ahead/behind counts against",
+			CMD_GROUP_BRANCHES,
+		)
+	}
+	pub fn compare_base_confirm(
+		key_config: &SharedKeyConfig,
+	) -> CommandText {
+		CommandText::new(
+			format!(
+				"Set Base [{}]",
+				key_config.get_hint(key_config.keys.enter),
+			),
+			"set comparison base",
+			CMD_GROUP_GENERAL,
+		)
+	}
 	pub fn open_branch_select_popup(
 		key_config: &SharedKeyConfig,
 	) -> CommandText {
@@ -1765,6 +2532,145 @@ pub mod commands {
 			CMD_GROUP_BRANCHES,
 		)
 	}
+	pub fn checkout_previous_branch(
+		key_config: &SharedKeyConfig,
+	) -> CommandText {
+		CommandText::new(
+			format!(
+				"Previous Branch [{}]",
+				key_config.get_hint(
+					key_config.keys.checkout_previous_branch
+				),
+			),
+			"switch to the previously checked out branch",
+			CMD_GROUP_BRANCHES,
+		)
+	}
+	pub fn rename_current_branch(
+		key_config: &SharedKeyConfig,
+	) -> CommandText {
+		CommandText::new(
+			format!(
+				"Rename Branch [{}]",
+				key_config
+					.get_hint(key_config.keys.rename_current_branch),
+			),
+			"rename the checked out branch",
+			CMD_GROUP_BRANCHES,
+		)
+	}
+	pub fn move_changes_to_branch(
+		key_config: &SharedKeyConfig,
+	) -> CommandText {
+		CommandText::new(
+			format!(
+				"Move To Branch [{}]",
+				key_config
+					.get_hint(key_config.keys.move_changes_to_branch),
+			),
+			"move the uncommitted changes to a new branch",
+			CMD_GROUP_BRANCHES,
+		)
+	}
+	pub fn move_changes_confirm_msg(
+		key_config: &SharedKeyConfig,
+	) -> CommandText {
+		CommandText::new(
+			format!(
+				"Move Changes [{}]",
+				key_config.get_hint(key_config.keys.enter),
+			),
+			"create branch and switch to it",
+			CMD_GROUP_BRANCHES,
+		)
+		.hide_help()
+	}
+	pub fn move_changes_carry_commits(
+		key_config: &SharedKeyConfig,
+	) -> CommandText {
+		CommandText::new(
+			format!(
+				"Carry Commits [{}]",
+				key_config.get_hint(
+					key_config.keys.move_changes_carry_commits
+				),
+			),
+			"move the local commits along and reset the branch to its upstream",
+			CMD_GROUP_BRANCHES,
+		)
+	}
+	pub fn show_ignored_files(
+		key_config: &SharedKeyConfig,
+	) -> CommandText {
+		CommandText::new(
+			format!(
+				"Ignored [{}]",
+				key_config
+					.get_hint(key_config.keys.status_show_ignored),
+			),
+			"list the ignored files and the rules ignoring them",
+			CMD_GROUP_CHANGES,
+		)
+	}
+	pub fn check_ignore(key_config: &SharedKeyConfig) -> CommandText {
+		CommandText::new(
+			format!(
+				"Check Ignore [{}]",
+				key_config.get_hint(key_config.keys.check_ignore),
+			),
+			"tell whether a path is ignored and by which rule",
+			CMD_GROUP_CHANGES,
+		)
+	}
+	pub fn check_ignore_confirm(
+		key_config: &SharedKeyConfig,
+	) -> CommandText {
+		CommandText::new(
+			format!(
+				"Check [{}]",
+				key_config.get_hint(key_config.keys.enter),
+			),
+			"check the path",
+			CMD_GROUP_GENERAL,
+		)
+		.hide_help()
+	}
+	pub fn ignored_files_check(
+		key_config: &SharedKeyConfig,
+	) -> CommandText {
+		CommandText::new(
+			format!(
+				"Check [{}]",
+				key_config.get_hint(key_config.keys.enter),
+			),
+			"check the selected path against the ignore rules",
+			CMD_GROUP_GENERAL,
+		)
+	}
+	pub fn open_recent_branches_popup(
+		key_config: &SharedKeyConfig,
+	) -> CommandText {
+		CommandText::new(
+			format!(
+				"Recent Branches [{}]",
+				key_config.get_hint(key_config.keys.recent_branches),
+			),
+			"open list of recently checked out branches",
+			CMD_GROUP_BRANCHES,
+		)
+	}
+	pub fn recent_branch_checkout(
+		key_config: &SharedKeyConfig,
+	) -> CommandText {
+		CommandText::new(
+			format!(
+				"Checkout [{}]",
+				key_config.get_hint(key_config.keys.enter),
+			),
+			"checkout selected branch",
+			CMD_GROUP_BRANCHES,
+		)
+	}
 
 	pub fn open_tags_popup(
 		key_config: &SharedKeyConfig,
@@ -1811,6 +2717,18 @@ pub mod commands {
 			CMD_GROUP_GENERAL,
 		)
 	}
+	pub fn status_push_all_remotes(
+		key_config: &SharedKeyConfig,
+	) -> CommandText {
+		CommandText::new(
+			format!(
+				"Push All [{}]",
+				key_config.get_hint(key_config.keys.push_all_remotes),
+			),
+			"push to all remotes of gitui.pushAll.remote",
+			CMD_GROUP_GENERAL,
+		)
+	}
 	pub fn status_force_push(
 		key_config: &SharedKeyConfig,
 	) -> CommandText {
@@ -1834,6 +2752,44 @@ pub mod commands {
 			CMD_GROUP_GENERAL,
 		)
 	}
+	pub fn status_fetch_ref(
+		key_config: &SharedKeyConfig,
+	) -> CommandText {
+		CommandText::new(
+			format!(
+				"Fetch Ref [{}]",
+				key_config.get_hint(key_config.keys.fetch_ref),
+			),
+			"fetch a single branch or refspec",
+			CMD_GROUP_GENERAL,
+		)
+	}
+	pub fn fetch_ref_complete(
+		key_config: &SharedKeyConfig,
+	) -> CommandText {
+		CommandText::new(
+			format!(
+				"Complete [{}]",
+				key_config.get_hint(key_config.keys.tab_toggle),
+			),
+			"complete with the next known remote branch",
+			CMD_GROUP_GENERAL,
+		)
+		.hide_help()
+	}
+	pub fn fetch_ref_confirm(
+		key_config: &SharedKeyConfig,
+	) -> CommandText {
+		CommandText::new(
+			format!(
+				"Fetch [{}]",
+				key_config.get_hint(key_config.keys.enter),
+			),
+			"fetch the branch or refspec",
+			CMD_GROUP_GENERAL,
+		)
+		.hide_help()
+	}
 	pub fn status_pull(key_config: &SharedKeyConfig) -> CommandText {
 		CommandText::new(
 			format!(
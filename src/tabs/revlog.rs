@@ -7,7 +7,7 @@ use crate::{
 	},
 	keys::{key_match, SharedKeyConfig},
 	popups::{FileTreeOpen, InspectCommitOpen},
-	queue::{InternalEvent, Queue, StackablePopupOpen},
+	queue::{Action, InternalEvent, Queue, StackablePopupOpen},
 	strings::{self, order},
 	try_or_popup,
 	ui::style::{SharedTheme, Theme},
@@ -42,6 +42,9 @@ use std::{
 };
 use sync::CommitTags;
 
+/// commits above and below the selection whose files are prefetched
+const PREFETCH_DISTANCE: usize = 3;
+
 struct LogSearchResult {
 	options: LogFilterSearchOptions,
 	duration: Duration,
@@ -66,6 +69,7 @@ pub struct Revlog {
 	list: CommitList,
 	git_log: AsyncLog,
 	search: LogSearch,
+	prefetched_around: Option<CommitId>,
 	git_tags: AsyncTags,
 	git_local_branches: AsyncSingleJob<AsyncBranchesJob>,
 	git_remote_branches: AsyncSingleJob<AsyncBranchesJob>,
@@ -93,6 +97,7 @@ impl Revlog {
 				None,
 			),
 			search: LogSearch::Off,
+			prefetched_around: None,
 			git_tags: AsyncTags::new(
 				env.repo.borrow().clone(),
 				&env.sender_git,
@@ -144,6 +149,18 @@ impl Revlog {
 					commit.map(CommitFilesParams::from),
 					tags.as_ref(),
 				)?;
+
+				// once the selection settled (its files are shown)
+				if !self.commit_details.any_work_pending()
+					&& commit != self.prefetched_around
+				{
+					self.prefetched_around = commit;
+					self.commit_details.prefetch(
+						&self
+							.list
+							.ids_around_selection(PREFETCH_DISTANCE),
+					)?;
+				}
 			}
 		}
 
@@ -204,6 +221,41 @@ impl Revlog {
 		self.list.selected_entry().map(|e| e.id)
 	}
 
+	/// asks to confirm dropping `id` if it can be dropped
+	fn drop_commit(&self, id: CommitId) {
+		match sync::drop_commit_info(&self.repo.borrow(), id) {
+			Ok(info) => {
+				self.queue.push(InternalEvent::ConfirmAction(
+					Action::DropCommit(id, info),
+				));
+			}
+			Err(e) => {
+				self.queue.push(InternalEvent::ShowErrorMsg(
+					format!("cannot drop commit:\n{e}"),
+				));
+			}
+		}
+	}
+
+	/// oldest and newest of the marked commits if there are at least two
+	/// of them and they are next to each other in the log
+	fn marked_squash_range(&self) -> Option<(CommitId, CommitId)> {
+		let mut marked = self.list.marked().to_vec();
+		marked.sort_by_key(|(idx, _)| *idx);
+
+		let contiguous =
+			marked.windows(2).all(|pair| pair[0].0 + 1 == pair[1].0);
+
+		match (marked.first(), marked.last()) {
+			(Some((_, tip)), Some((_, base)))
+				if marked.len() > 1 && contiguous =>
+			{
+				Some((*base, *tip))
+			}
+			_ => None,
+		}
+	}
+
 	fn selected_commit_tags(
 		&self,
 		commit: Option<&CommitId>,
@@ -569,6 +621,35 @@ impl Component for Revlog {
 							Ok(EventState::Consumed)
 						},
 					);
+				} else if key_match(
+					k,
+					self.key_config.keys.log_drop_commit,
+				) && !self.is_search_pending()
+				{
+					return self.selected_commit().map_or(
+						Ok(EventState::NotConsumed),
+						|id| {
+							self.drop_commit(id);
+							Ok(EventState::Consumed)
+						},
+					);
+				} else if key_match(
+					k,
+					self.key_config.keys.log_squash_commits,
+				) && !self.is_search_pending()
+				{
+					return self.marked_squash_range().map_or(
+						Ok(EventState::NotConsumed),
+						|(base, tip)| {
+							self.queue.push(
+								InternalEvent::SquashCommits {
+									base,
+									tip,
+								},
+							);
+							Ok(EventState::Consumed)
+						},
+					);
 				} else if key_match(k, self.key_config.keys.log_find)
 					&& self.can_start_search()
 				{
@@ -612,6 +693,7 @@ impl Component for Revlog {
 		Ok(EventState::NotConsumed)
 	}
 
+	#[allow(clippy::too_many_lines)]
 	fn commands(
 		&self,
 		out: &mut Vec<CommandInfo>,
@@ -724,6 +806,19 @@ impl Component for Revlog {
 			self.selected_commit().is_some(),
 			(self.visible && !self.is_search_pending()) || force_all,
 		));
+		out.push(CommandInfo::new(
+			strings::commands::log_drop_commit(&self.key_config),
+			self.selected_commit().is_some(),
+			(self.visible && !self.is_search_pending()) || force_all,
+		));
+		out.push(CommandInfo::new(
+			strings::commands::log_squash_commits(&self.key_config),
+			self.marked_squash_range().is_some(),
+			(self.visible
+				&& !self.is_search_pending()
+				&& self.list.marked_count() > 1)
+				|| force_all,
+		));
 		out.push(CommandInfo::new(
 			strings::commands::log_find_commit(&self.key_config),
 			self.can_start_search(),
@@ -9,19 +9,25 @@ use crate::{
 	},
 	keys::{key_match, SharedKeyConfig},
 	options::SharedOptions,
-	queue::{Action, InternalEvent, NeedsUpdate, Queue, ResetItem},
+	popups::InspectCommitOpen,
+	queue::{
+		Action, InternalEvent, NeedsUpdate, Queue, ResetItem,
+		StackablePopupOpen,
+	},
 	strings, try_or_popup,
 	ui::style::Theme,
 };
 use anyhow::Result;
 use asyncgit::{
+	asyncjob::AsyncSingleJob,
 	cached,
 	sync::{
 		self, status::StatusType, RepoPath, RepoPathRef, RepoState,
 	},
-	sync::{BranchCompare, CommitId},
-	AsyncDiff, AsyncGitNotification, AsyncStatus, DiffParams,
-	DiffType, PushType, StatusItem, StatusParams,
+	sync::{BranchCompare, CommitId, PseudoRef},
+	AsyncBranchCompareJob, AsyncDiff, AsyncGitNotification,
+	AsyncStatus, BranchCompareResult, DiffParams, DiffType, PushType,
+	StatusItem, StatusParams,
 };
 use crossterm::event::Event;
 use itertools::Itertools;
@@ -73,10 +79,14 @@ pub struct Status {
 	remotes: RemoteStatus,
 	git_diff: AsyncDiff,
 	git_state: RepoState,
+	/// pseudo-refs left by the last history changing operations
+	pseudo_refs: Vec<(PseudoRef, CommitId)>,
 	git_status_workdir: AsyncStatus,
 	git_status_stage: AsyncStatus,
 	git_branch_state: Option<BranchCompare>,
 	git_branch_name: cached::BranchName,
+	git_base_compare: AsyncSingleJob<AsyncBranchCompareJob>,
+	git_base_state: Option<BranchCompareResult>,
 	queue: Queue,
 	git_action_executed: bool,
 	options: SharedOptions,
@@ -90,17 +100,15 @@ impl DrawableComponent for Status {
 		rect: ratatui::layout::Rect,
 	) -> Result<()> {
 		let repo_unclean = self.repo_state_unclean();
-		let rects = if repo_unclean {
-			Layout::default()
-				.direction(Direction::Vertical)
-				.constraints(
-					[Constraint::Min(1), Constraint::Length(3)]
-						.as_ref(),
-				)
-				.split(rect)
-		} else {
-			std::rc::Rc::new([rect])
-		};
+		let has_pseudo_refs = !self.pseudo_refs.is_empty();
+		let rects = Layout::default()
+			.direction(Direction::Vertical)
+			.constraints([
+				Constraint::Min(1),
+				Constraint::Length(u16::from(has_pseudo_refs)),
+				Constraint::Length(if repo_unclean { 3 } else { 0 }),
+			])
+			.split(rect);
 
 		let chunks = Layout::default()
 			.direction(Direction::Horizontal)
@@ -143,8 +151,11 @@ impl DrawableComponent for Status {
 		self.diff.draw(f, chunks[1])?;
 		self.draw_branch_state(f, &left_chunks);
 
+		if has_pseudo_refs {
+			self.draw_pseudo_refs(f, rects[1]);
+		}
 		if repo_unclean {
-			self.draw_repo_state(f, rects[1]);
+			self.draw_repo_state(f, rects[2]);
 		}
 
 		Ok(())
@@ -165,6 +176,7 @@ impl Status {
 				has_remote_for_push: false,
 			},
 			git_state: RepoState::Clean,
+			pseudo_refs: Vec::new(),
 			focus: Focus::WorkDir,
 			diff_target: DiffTarget::WorkingDir,
 			index_wd: ChangesComponent::new(
@@ -197,6 +209,10 @@ impl Status {
 			git_branch_name: cached::BranchName::new(
 				env.repo.clone(),
 			),
+			git_base_compare: AsyncSingleJob::new(
+				env.sender_git.clone(),
+			),
+			git_base_state: None,
 			key_config: env.key_config.clone(),
 			options: env.options.clone(),
 			repo: env.repo.clone(),
@@ -219,8 +235,21 @@ impl Status {
 					)
 				});
 
+			let base_ahead_behind = self
+				.git_base_state
+				.as_ref()
+				.filter(|state| state.base != branch_name)
+				.map_or_else(String::new, |state| {
+					format!(
+						"{} \u{2191}{} \u{2193}{}  ",
+						state.base,
+						state.compare.ahead,
+						state.compare.behind,
+					)
+				});
+
 			let w = Paragraph::new(format!(
-				"{ahead_behind}{{{branch_name}}}"
+				"{base_ahead_behind}{ahead_behind}{{{branch_name}}}"
 			))
 			.alignment(Alignment::Right);
 
@@ -313,6 +342,60 @@ impl Status {
 		}
 	}
 
+	fn draw_pseudo_refs(
+		&self,
+		f: &mut ratatui::Frame,
+		r: ratatui::layout::Rect,
+	) {
+		let txt = self
+			.pseudo_refs
+			.iter()
+			.map(|(pseudo_ref, id)| {
+				format!(
+					"{} {}",
+					pseudo_ref.name(),
+					id.get_short_string()
+				)
+			})
+			.join("  ");
+
+		f.render_widget(
+			Paragraph::new(txt)
+				.style(Style::default().fg(Color::DarkGray))
+				.alignment(Alignment::Right),
+			r,
+		);
+	}
+
+	fn orig_head(&self) -> Option<CommitId> {
+		self.pseudo_refs
+			.iter()
+			.find(|(pseudo_ref, _)| {
+				*pseudo_ref == PseudoRef::OrigHead
+			})
+			.map(|(_, id)| *id)
+	}
+
+	/// compares `HEAD` to `ORIG_HEAD`, i.e. shows what the last reset,
+	/// rebase or merge changed
+	fn diff_orig_head(&self) -> Result<()> {
+		if let Some(orig_head) = self.orig_head() {
+			let head = sync::get_head(&self.repo.borrow())?;
+
+			self.queue.push(InternalEvent::OpenPopup(
+				StackablePopupOpen::CompareCommits(
+					InspectCommitOpen {
+						commit_id: head,
+						compare_id: Some(orig_head),
+						tags: None,
+					},
+				),
+			));
+		}
+
+		Ok(())
+	}
+
 	fn repo_state_unclean(&self) -> bool {
 		self.git_state != RepoState::Clean
 	}
@@ -366,6 +449,17 @@ impl Status {
 		self.index.focus_select(is_stage);
 	}
 
+	/// selects the staged file at `path` and focuses its diff
+	pub fn select_staged_file(&mut self, path: &str) -> Result<()> {
+		if self.index.select_path(path) {
+			self.switch_focus(Focus::Stage)?;
+			self.update_diff()?;
+			self.switch_focus(Focus::Diff)?;
+		}
+
+		Ok(())
+	}
+
 	pub fn selected_path(&self) -> Option<(String, bool)> {
 		let (idx, is_stage) = match self.diff_target {
 			DiffTarget::Stage => (&self.index, true),
@@ -400,6 +494,8 @@ impl Status {
 
 			self.git_state = sync::repo_state(&self.repo.borrow())
 				.unwrap_or(RepoState::Clean);
+			self.pseudo_refs = sync::pseudo_refs(&self.repo.borrow())
+				.unwrap_or_default();
 
 			self.branch_compare();
 		}
@@ -412,6 +508,7 @@ impl Status {
 		self.git_diff.is_pending()
 			|| self.git_status_stage.is_pending()
 			|| self.git_status_workdir.is_pending()
+			|| self.git_base_compare.is_pending()
 	}
 
 	fn check_remotes(&mut self) {
@@ -445,6 +542,9 @@ impl Status {
 			| AsyncGitNotification::CommitFiles => {
 				self.branch_compare();
 			}
+			AsyncGitNotification::BranchCompare => {
+				self.update_base_compare();
+			}
 			_ => (),
 		}
 
@@ -482,6 +582,11 @@ impl Status {
 		Ok(())
 	}
 
+	/// `true` while a search in the diff is typed, keys are text then
+	pub const fn is_diff_searching(&self) -> bool {
+		self.diff.is_searching()
+	}
+
 	///
 	pub fn update_diff(&mut self) -> Result<()> {
 		if let Some((path, is_stage)) = self.selected_path() {
@@ -551,6 +656,14 @@ impl Status {
 
 			false
 		} else {
+			// like `git checkout -- <path>`, HEAD does not move
+			if let Ok(head) = sync::get_head(&self.repo.borrow()) {
+				self.queue.push(InternalEvent::PostCheckout {
+					prev_head: head,
+					branch_checkout: false,
+				});
+			}
+
 			true
 		}
 	}
@@ -581,12 +694,27 @@ impl Status {
 		}
 	}
 
+	fn push_all_remotes(&self) {
+		if self.remotes.has_remote_for_push {
+			if let Some(branch) = self.git_branch_name.last() {
+				self.queue
+					.push(InternalEvent::PushAllRemotes(branch));
+			}
+		}
+	}
+
 	fn fetch(&self) {
 		if self.can_fetch() {
 			self.queue.push(InternalEvent::FetchRemotes);
 		}
 	}
 
+	fn fetch_ref(&self) {
+		if self.can_fetch() {
+			self.queue.push(InternalEvent::OpenFetchRef);
+		}
+	}
+
 	fn pull(&self) {
 		if let Some(branch) = self.git_branch_name.last() {
 			self.queue.push(InternalEvent::Pull(branch));
@@ -607,6 +735,38 @@ impl Status {
 				)
 				.ok()
 			});
+
+		if let Some(branch) = self.git_branch_name.last() {
+			let base = {
+				let options = self.options.borrow();
+				options
+					.compare_base()
+					.or_else(|| options.default_branch())
+			};
+
+			self.git_base_compare.spawn(AsyncBranchCompareJob::new(
+				self.repo.borrow().clone(),
+				branch,
+				base,
+			));
+		}
+	}
+
+	fn update_base_compare(&mut self) {
+		let Some(result) = self
+			.git_base_compare
+			.take_last()
+			.and_then(|job| job.result())
+		else {
+			return;
+		};
+
+		self.git_base_state = result
+			.map_err(|e| {
+				log::error!("branch compare error: {e}");
+			})
+			.ok()
+			.flatten();
 	}
 
 	fn can_push(&self) -> bool {
@@ -713,6 +873,7 @@ impl Status {
 }
 
 impl Component for Status {
+	#[allow(clippy::too_many_lines)]
 	fn commands(
 		&self,
 		out: &mut Vec<CommandInfo>,
@@ -744,6 +905,73 @@ impl Component for Status {
 				!focus_on_diff,
 			));
 
+			out.push(CommandInfo::new(
+				strings::commands::checkout_previous_branch(
+					&self.key_config,
+				),
+				true,
+				!focus_on_diff,
+			));
+
+			out.push(CommandInfo::new(
+				strings::commands::open_recent_branches_popup(
+					&self.key_config,
+				),
+				true,
+				!focus_on_diff,
+			));
+
+			out.push(CommandInfo::new(
+				strings::commands::show_ignored_files(
+					&self.key_config,
+				),
+				true,
+				!focus_on_diff,
+			));
+
+			out.push(CommandInfo::new(
+				strings::commands::check_ignore(&self.key_config),
+				true,
+				!focus_on_diff,
+			));
+
+			out.push(CommandInfo::new(
+				strings::commands::rename_current_branch(
+					&self.key_config,
+				),
+				true,
+				!focus_on_diff,
+			));
+
+			out.push(CommandInfo::new(
+				strings::commands::move_changes_to_branch(
+					&self.key_config,
+				),
+				true,
+				!focus_on_diff,
+			));
+
+			out.push(CommandInfo::new(
+				strings::commands::select_compare_base(
+					&self.key_config,
+				),
+				true,
+				!focus_on_diff,
+			));
+
+			out.push(CommandInfo::new(
+				strings::commands::diff_orig_head(&self.key_config),
+				self.orig_head().is_some(),
+				!focus_on_diff,
+			));
+			out.push(CommandInfo::new(
+				strings::commands::reset_to_orig_head(
+					&self.key_config,
+				),
+				self.orig_head().is_some(),
+				!focus_on_diff,
+			));
+
 			out.push(CommandInfo::new(
 				strings::commands::status_push(&self.key_config),
 				self.can_push(),
@@ -757,11 +985,24 @@ impl Component for Status {
 				self.can_push() && !focus_on_diff,
 			));
 
+			out.push(CommandInfo::new(
+				strings::commands::status_push_all_remotes(
+					&self.key_config,
+				),
+				self.remotes.has_remote_for_push,
+				!focus_on_diff,
+			));
+
 			out.push(CommandInfo::new(
 				strings::commands::status_fetch(&self.key_config),
 				self.can_fetch(),
 				!focus_on_diff,
 			));
+			out.push(CommandInfo::new(
+				strings::commands::status_fetch_ref(&self.key_config),
+				self.can_fetch(),
+				!focus_on_diff,
+			));
 			out.push(CommandInfo::new(
 				strings::commands::status_pull(&self.key_config),
 				self.can_fetch(),
@@ -871,6 +1112,85 @@ impl Component for Status {
 				{
 					self.queue.push(InternalEvent::SelectBranch);
 					Ok(EventState::Consumed)
+				} else if key_match(
+					k,
+					self.key_config.keys.checkout_previous_branch,
+				) && !self.is_focus_on_diff()
+				{
+					self.queue
+						.push(InternalEvent::CheckoutPreviousBranch);
+					Ok(EventState::Consumed)
+				} else if key_match(
+					k,
+					self.key_config.keys.recent_branches,
+				) && !self.is_focus_on_diff()
+				{
+					self.queue.push(InternalEvent::RecentBranches);
+					Ok(EventState::Consumed)
+				} else if key_match(
+					k,
+					self.key_config.keys.status_show_ignored,
+				) && !self.is_focus_on_diff()
+				{
+					self.queue.push(InternalEvent::ShowIgnoredFiles);
+					Ok(EventState::Consumed)
+				} else if key_match(
+					k,
+					self.key_config.keys.check_ignore,
+				) && !self.is_focus_on_diff()
+				{
+					self.queue.push(InternalEvent::CheckIgnore(None));
+					Ok(EventState::Consumed)
+				} else if key_match(
+					k,
+					self.key_config.keys.rename_current_branch,
+				) && !self.is_focus_on_diff()
+				{
+					self.queue
+						.push(InternalEvent::RenameCurrentBranch);
+					Ok(EventState::Consumed)
+				} else if key_match(
+					k,
+					self.key_config.keys.move_changes_to_branch,
+				) && !self.is_focus_on_diff()
+				{
+					self.queue
+						.push(InternalEvent::MoveChangesToBranch);
+					Ok(EventState::Consumed)
+				} else if key_match(
+					k,
+					self.key_config.keys.select_compare_base,
+				) && !self.is_focus_on_diff()
+				{
+					self.queue
+						.push(InternalEvent::OpenCompareBasePopup);
+					Ok(EventState::Consumed)
+				} else if key_match(
+					k,
+					self.key_config.keys.diff_orig_head,
+				) && !self.is_focus_on_diff()
+					&& self.orig_head().is_some()
+				{
+					try_or_popup!(
+						self,
+						"diff ORIG_HEAD error:",
+						self.diff_orig_head()
+					);
+					Ok(EventState::Consumed)
+				} else if key_match(
+					k,
+					self.key_config.keys.reset_to_orig_head,
+				) && !self.is_focus_on_diff()
+				{
+					// the reset popup asks for confirmation
+					if let Some(orig_head) = self.orig_head() {
+						self.queue.push(
+							InternalEvent::OpenResetPopup(orig_head),
+						);
+						Ok(EventState::Consumed)
+					} else {
+						Ok(EventState::NotConsumed)
+					}
 				} else if key_match(
 					k,
 					self.key_config.keys.force_push,
@@ -884,12 +1204,25 @@ impl Component for Status {
 				{
 					self.push(false);
 					Ok(EventState::Consumed)
+				} else if key_match(
+					k,
+					self.key_config.keys.push_all_remotes,
+				) && !self.is_focus_on_diff()
+				{
+					self.push_all_remotes();
+					Ok(EventState::Consumed)
 				} else if key_match(k, self.key_config.keys.fetch)
 					&& !self.is_focus_on_diff()
 					&& self.can_fetch()
 				{
 					self.fetch();
 					Ok(EventState::Consumed)
+				} else if key_match(k, self.key_config.keys.fetch_ref)
+					&& !self.is_focus_on_diff()
+					&& self.can_fetch()
+				{
+					self.fetch_ref();
+					Ok(EventState::Consumed)
 				} else if key_match(k, self.key_config.keys.pull)
 					&& !self.is_focus_on_diff()
 					&& self.can_fetch()
@@ -1,10 +1,14 @@
-use crate::ui::syntax_text::DEFAULT_SYNTAX_THEME;
+use crate::{storage, ui::syntax_text::DEFAULT_SYNTAX_THEME};
 use anyhow::Result;
 use asyncgit::{DiffLineType, StatusItemType};
 use ratatui::style::{Color, Modifier, Style};
 use ron::ser::{to_string_pretty, PrettyConfig};
 use serde::{Deserialize, Serialize};
-use std::{fs::File, io::Write, path::PathBuf, rc::Rc};
+use std::{
+	fs::File,
+	path::{Path, PathBuf},
+	rc::Rc,
+};
 use struct_patch::Patch;
 
 pub type SharedTheme = Rc<Theme>;
@@ -203,6 +207,16 @@ impl Theme {
 		self.apply_select(style, selected)
 	}
 
+	/// the changed words of a line styled with [`Self::diff_line`]
+	pub fn diff_word(
+		&self,
+		typ: DiffLineType,
+		selected: bool,
+	) -> Style {
+		self.diff_line(typ, selected)
+			.add_modifier(Modifier::REVERSED)
+	}
+
 	pub fn text_danger(&self) -> Style {
 		Style::default().fg(self.danger_fg)
 	}
@@ -295,14 +309,11 @@ impl Theme {
 	}
 
 	// This is supposed to be called when theme.ron doesn't already exists.
-	fn save_patch(&self, theme_path: &PathBuf) -> Result<()> {
-		let mut file = File::create(theme_path)?;
+	fn save_patch(&self, theme_path: &Path) -> Result<()> {
 		let patch = self.clone().into_patch_by_diff(Self::default());
 		let data = to_string_pretty(&patch, PrettyConfig::default())?;
 
-		file.write_all(data.as_bytes())?;
-
-		Ok(())
+		storage::write_atomic(theme_path, data.as_bytes())
 	}
 
 	pub fn get_syntax(&self) -> String {
@@ -376,6 +387,7 @@ impl Default for Theme {
 mod tests {
 	use super::*;
 	use pretty_assertions::assert_eq;
+	use std::io::Write;
 	use tempfile::NamedTempFile;
 
 	#[test]